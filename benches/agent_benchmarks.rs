@@ -39,6 +39,7 @@ impl BenchProvider {
                 text: Some(text.into()),
                 tool_calls: vec![],
                 usage: None,
+                routed_to: None,
             }]),
         }
     }
@@ -54,11 +55,13 @@ impl BenchProvider {
                         arguments: "{}".into(),
                     }],
                     usage: None,
+                    routed_to: None,
                 },
                 ChatResponse {
                     text: Some("done".into()),
                     tool_calls: vec![],
                     usage: None,
+                    routed_to: None,
                 },
             ]),
         }
@@ -89,6 +92,7 @@ impl Provider for BenchProvider {
                 text: Some("done".into()),
                 tool_calls: vec![],
                 usage: None,
+                routed_to: None,
             });
         }
         Ok(guard.remove(0))
@@ -155,6 +159,7 @@ Let me know if you need more."#
         ),
         tool_calls: vec![],
         usage: None,
+        routed_to: None,
     };
 
     let multi_tool = ChatResponse {
@@ -172,6 +177,7 @@ Let me know if you need more."#
         ),
         tool_calls: vec![],
         usage: None,
+        routed_to: None,
     };
 
     c.bench_function("xml_parse_single_tool_call", |b| {
@@ -205,6 +211,7 @@ fn bench_native_parsing(c: &mut Criterion) {
             },
         ],
         usage: None,
+        routed_to: None,
     };
 
     c.bench_function("native_parse_tool_calls", |b| {
@@ -233,8 +233,10 @@ impl Agent {
     pub fn from_config(config: &Config) -> Result<Self> {
         let observer: Arc<dyn Observer> =
             Arc::from(observability::create_observer(&config.observability));
-        let runtime: Arc<dyn runtime::RuntimeAdapter> =
-            Arc::from(runtime::create_runtime(&config.runtime)?);
+        let runtime: Arc<dyn runtime::RuntimeAdapter> = Arc::from(runtime::create_runtime(
+            &config.runtime,
+            &config.security.resources,
+        )?);
         let security = Arc::new(SecurityPolicy::from_config(
             &config.autonomy,
             &config.workspace_dir,
@@ -282,13 +284,11 @@ impl Agent {
             .unwrap_or("anthropic/claude-sonnet-4-20250514")
             .to_string();
 
-        let provider: Box<dyn Provider> = providers::create_routed_provider(
+        let provider: Box<dyn Provider> = providers::create_routed_provider_from_config(
+            config,
             provider_name,
-            config.api_key.as_deref(),
-            config.api_url.as_deref(),
-            &config.reliability,
-            &config.model_routes,
             &model_name,
+            &providers::ProviderRuntimeOptions::default(),
         )?;
 
         let dispatcher_choice = config.agent.tool_dispatcher.as_str();
@@ -317,7 +317,13 @@ impl Agent {
             .model_name(model_name)
             .temperature(config.default_temperature)
             .workspace_dir(config.workspace_dir.clone())
-            .classification_config(config.query_classification.clone())
+            // With auto-routing on, the router folds classifier hints into its
+            // own scoring instead of the agent pinning a route up front.
+            .classification_config(if config.auto_routing.enabled {
+                crate::config::QueryClassificationConfig::default()
+            } else {
+                config.query_classification.clone()
+            })
             .available_hints(available_hints)
             .identity_config(config.identity.clone())
             .skills(crate::skills::load_skills_with_config(
@@ -374,6 +380,17 @@ impl Agent {
         let start = Instant::now();
 
         let result = if let Some(tool) = self.tools.iter().find(|t| t.name() == call.name) {
+            if tool.requires_approval() {
+                return ToolExecutionResult {
+                    name: call.name.clone(),
+                    output: format!(
+                        "Error: tool '{}' requires approval, which this agent cannot prompt for",
+                        call.name
+                    ),
+                    success: false,
+                    tool_call_id: call.tool_call_id.clone(),
+                };
+            }
             match tool.execute(call.arguments.clone()).await {
                 Ok(r) => {
                     self.observer.record_event(&ObserverEvent::ToolCall {
@@ -651,6 +668,7 @@ mod tests {
                     text: Some("done".into()),
                     tool_calls: vec![],
                     usage: None,
+                    routed_to: None,
                 });
             }
             Ok(guard.remove(0))
@@ -689,6 +707,7 @@ mod tests {
                 text: Some("hello".into()),
                 tool_calls: vec![],
                 usage: None,
+                routed_to: None,
             }]),
         });
 
@@ -728,11 +747,13 @@ mod tests {
                         arguments: "{}".into(),
                     }],
                     usage: None,
+                    routed_to: None,
                 },
                 crate::providers::ChatResponse {
                     text: Some("done".into()),
                     tool_calls: vec![],
                     usage: None,
+                    routed_to: None,
                 },
             ]),
         });
@@ -0,0 +1,481 @@
+//! Token-aware context window budgeting.
+//!
+//! Histories are measured in estimated tokens against the model's context
+//! window instead of message counts. [`ContextBudget::plan`] decides up front
+//! which older tool results and stale memory context to trim and which old
+//! turns to fold into a summary, so requests fit before they are sent rather
+//! than after the provider rejects them.
+
+use crate::providers::{ChatMessage, ContentPart};
+use crate::util::truncate_with_ellipsis;
+use std::ops::Range;
+
+/// Context window assumed for models missing from [`MODEL_CONTEXT_WINDOWS`].
+pub const DEFAULT_CONTEXT_WINDOW_TOKENS: usize = 32_768;
+
+/// Tokens held back for the model's reply.
+const OUTPUT_RESERVE_TOKENS: usize = 4_096;
+
+/// Per-message framing overhead (role markers, separators).
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+
+/// Rough cost of one image or document part.
+const MEDIA_PART_TOKENS: usize = 1_000;
+
+/// Older tool results are cut down to this many characters when over budget.
+const TOOL_RESULT_KEEP_CHARS: usize = 1_500;
+
+/// Room left for the summary that replaces compacted turns.
+const SUMMARY_RESERVE_TOKENS: usize = 600;
+
+const MEMORY_CONTEXT_HEADER: &str = "[Memory context]\n";
+const TOOL_RESULTS_HEADER: &str = "[Tool results]";
+
+/// Known context windows, matched as prefixes of the lowercased model name
+/// (after any provider prefix). More specific patterns come first.
+const MODEL_CONTEXT_WINDOWS: &[(&str, usize)] = &[
+    ("claude", 200_000),
+    ("gpt-4.1", 1_047_576),
+    ("gpt-5", 400_000),
+    ("gpt-4o", 128_000),
+    ("chatgpt-4o", 128_000),
+    ("gpt-4-turbo", 128_000),
+    ("gpt-4", 8_192),
+    ("gpt-3.5", 16_385),
+    ("o1", 200_000),
+    ("o3", 200_000),
+    ("o4", 200_000),
+    ("gemini-1.5-pro", 2_097_152),
+    ("gemini", 1_048_576),
+    ("deepseek", 128_000),
+    ("grok", 131_072),
+    ("mistral-large", 128_000),
+    ("mixtral", 32_768),
+    ("llama-3.1", 128_000),
+    ("llama3.1", 128_000),
+    ("llama-3.2", 128_000),
+    ("llama3.2", 128_000),
+    ("llama-3.3", 128_000),
+    ("llama3.3", 128_000),
+    ("meta-llama-3.1", 128_000),
+    ("meta-llama-3.2", 128_000),
+    ("meta-llama-3.3", 128_000),
+    ("llama3", 8_192),
+    ("llama-3", 8_192),
+    ("meta-llama-3", 8_192),
+    ("qwen", 32_768),
+    ("glm", 128_000),
+    ("kimi", 128_000),
+    ("moonshot", 128_000),
+];
+
+/// Context window of `model` in tokens.
+///
+/// Provider prefixes such as `anthropic/` are ignored.
+pub fn context_window_for_model(model: &str) -> usize {
+    let normalized = model.to_ascii_lowercase();
+    let name = normalized.rsplit('/').next().unwrap_or(&normalized);
+    MODEL_CONTEXT_WINDOWS
+        .iter()
+        .find(|(pattern, _)| name.starts_with(pattern))
+        .map_or(DEFAULT_CONTEXT_WINDOW_TOKENS, |(_, tokens)| *tokens)
+}
+
+/// Estimate the token count of `text`.
+///
+/// ASCII averages about four characters per token; other scripts are counted
+/// one token per character so CJK-heavy text is not underestimated.
+pub fn estimate_tokens(text: &str) -> usize {
+    let (ascii, other) = text.chars().fold((0usize, 0usize), |(ascii, other), c| {
+        if c.is_ascii() {
+            (ascii + 1, other)
+        } else {
+            (ascii, other + 1)
+        }
+    });
+    ascii.div_ceil(4) + other
+}
+
+/// Estimate the token count of a single message, including media parts.
+pub fn estimate_message_tokens(message: &ChatMessage) -> usize {
+    let media = message
+        .parts
+        .iter()
+        .filter(|part| {
+            matches!(
+                part,
+                ContentPart::Image { .. } | ContentPart::Document { .. }
+            )
+        })
+        .count();
+    MESSAGE_OVERHEAD_TOKENS + estimate_tokens(&message.content) + media * MEDIA_PART_TOKENS
+}
+
+/// Estimate the token count of a whole history.
+pub fn estimate_history_tokens(history: &[ChatMessage]) -> usize {
+    history.iter().map(estimate_message_tokens).sum()
+}
+
+/// Token budget for one model's requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextBudget {
+    pub window_tokens: usize,
+    pub reserve_tokens: usize,
+}
+
+/// What [`ContextBudget::plan`] decided for a history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextPlan {
+    /// Input tokens available after the output reserve.
+    pub budget_tokens: usize,
+    /// Estimated tokens of the history as given.
+    pub estimated_tokens: usize,
+    /// Estimated tokens once the plan is applied (summary excluded).
+    pub planned_tokens: usize,
+    /// Older messages whose tool output or memory context gets trimmed.
+    pub shrink: Vec<usize>,
+    /// Older messages to fold into a summary or drop. Always ends on a turn
+    /// boundary so tool results are never split from their calls.
+    pub compact: Range<usize>,
+}
+
+impl ContextPlan {
+    pub fn is_noop(&self) -> bool {
+        self.shrink.is_empty() && self.compact.is_empty()
+    }
+
+    pub fn fits(&self) -> bool {
+        self.planned_tokens <= self.budget_tokens
+    }
+}
+
+impl ContextBudget {
+    /// Budget for `model`, with an optional configured window that takes
+    /// precedence over the registry.
+    pub fn for_model(model: &str, window_override: Option<usize>) -> Self {
+        let window_tokens = window_override
+            .filter(|tokens| *tokens > 0)
+            .unwrap_or_else(|| context_window_for_model(model));
+        Self {
+            window_tokens,
+            reserve_tokens: OUTPUT_RESERVE_TOKENS.min(window_tokens / 4),
+        }
+    }
+
+    /// Input tokens available for the request.
+    pub fn input_tokens(&self) -> usize {
+        self.window_tokens.saturating_sub(self.reserve_tokens)
+    }
+
+    /// Decide what to keep, trim and compact so `history` fits.
+    ///
+    /// The system prompt and the latest user turn are always kept intact.
+    /// Older tool results and stale memory context are trimmed first; if that
+    /// is not enough, the oldest whole turns are marked for compaction.
+    pub fn plan(&self, history: &[ChatMessage]) -> ContextPlan {
+        let budget_tokens = self.input_tokens();
+        let estimated_tokens = estimate_history_tokens(history);
+        let mut plan = ContextPlan {
+            budget_tokens,
+            estimated_tokens,
+            planned_tokens: estimated_tokens,
+            shrink: Vec::new(),
+            compact: 0..0,
+        };
+        if estimated_tokens <= budget_tokens {
+            return plan;
+        }
+
+        let start = usize::from(history.first().is_some_and(|m| m.role == "system"));
+        let protected_from = last_turn_start(history, start);
+
+        for (idx, message) in history.iter().enumerate().take(protected_from).skip(start) {
+            if let Some(shrunk) = shrink_message(message) {
+                let saved = estimate_message_tokens(message)
+                    .saturating_sub(estimate_message_tokens(&shrunk));
+                plan.planned_tokens -= saved;
+                plan.shrink.push(idx);
+            }
+        }
+        if plan.planned_tokens <= budget_tokens {
+            return plan;
+        }
+
+        let target = budget_tokens.saturating_sub(SUMMARY_RESERVE_TOKENS);
+        let mut end = start;
+        let mut remaining = plan.planned_tokens;
+        while end < protected_from && remaining > target {
+            let next = next_turn_start(history, end + 1, protected_from);
+            for (idx, message) in history.iter().enumerate().take(next).skip(end) {
+                remaining -= if plan.shrink.contains(&idx) {
+                    shrink_message(message).map_or(0, |m| estimate_message_tokens(&m))
+                } else {
+                    estimate_message_tokens(message)
+                };
+            }
+            end = next;
+        }
+
+        plan.shrink.retain(|idx| *idx >= end);
+        plan.compact = start..end;
+        plan.planned_tokens = remaining;
+        plan
+    }
+
+    /// Apply a plan without summarizing: trims messages and drops the
+    /// compacted range, returning the dropped messages.
+    pub fn fit(&self, history: &mut Vec<ChatMessage>) -> Vec<ChatMessage> {
+        let plan = self.plan(history);
+        apply_shrink(history, &plan);
+        history.drain(plan.compact).collect()
+    }
+}
+
+/// Trim the messages listed in `plan.shrink`.
+pub fn apply_shrink(history: &mut [ChatMessage], plan: &ContextPlan) {
+    for idx in &plan.shrink {
+        if let Some(shrunk) = history.get(*idx).and_then(shrink_message) {
+            history[*idx] = shrunk;
+        }
+    }
+}
+
+/// Index of the user message that opened the current turn, or
+/// `history.len()` when there is none.
+///
+/// Compaction may remove messages before it, so callers that slice out the
+/// current turn should look it up again after the tool loop has run.
+pub fn current_turn_start(history: &[ChatMessage]) -> usize {
+    let start = usize::from(history.first().is_some_and(|m| m.role == "system"));
+    last_turn_start(history, start)
+}
+
+/// Index of the latest real user message (not a prompt-mode tool result).
+fn last_turn_start(history: &[ChatMessage], start: usize) -> usize {
+    history
+        .iter()
+        .enumerate()
+        .skip(start)
+        .rev()
+        .find(|(_, message)| is_turn_start(message))
+        .map_or(history.len(), |(idx, _)| idx)
+}
+
+fn next_turn_start(history: &[ChatMessage], from: usize, limit: usize) -> usize {
+    (from..limit)
+        .find(|idx| is_turn_start(&history[*idx]))
+        .unwrap_or(limit)
+}
+
+fn is_turn_start(message: &ChatMessage) -> bool {
+    message.role == "user" && !message.content.starts_with(TOOL_RESULTS_HEADER)
+}
+
+/// Smaller replacement for an older message, if it carries bulky tool
+/// output or memory context that has gone stale.
+fn shrink_message(message: &ChatMessage) -> Option<ChatMessage> {
+    match message.role.as_str() {
+        "tool" => shrink_tool_result(message),
+        "user" if message.content.starts_with(TOOL_RESULTS_HEADER) => {
+            shrink_text(&message.content).map(ChatMessage::user)
+        }
+        "user" if message.content.starts_with(MEMORY_CONTEXT_HEADER) => message
+            .content
+            .split_once("\n\n")
+            .map(|(_, rest)| ChatMessage::user(rest)),
+        _ => None,
+    }
+}
+
+fn shrink_tool_result(message: &ChatMessage) -> Option<ChatMessage> {
+    if let [ContentPart::ToolResult {
+        tool_use_id,
+        content,
+        is_error,
+    }] = message.parts.as_slice()
+    {
+        let content = shrink_text(content)?;
+        return Some(ChatMessage::with_parts(
+            "tool",
+            vec![ContentPart::ToolResult {
+                tool_use_id: tool_use_id.clone(),
+                content,
+                is_error: *is_error,
+            }],
+        ));
+    }
+
+    let mut value: serde_json::Value = serde_json::from_str(&message.content).ok()?;
+    let content = shrink_text(value.get("content")?.as_str()?)?;
+    value["content"] = serde_json::Value::String(content);
+    Some(ChatMessage::tool(value.to_string()))
+}
+
+fn shrink_text(text: &str) -> Option<String> {
+    let total = text.chars().count();
+    if total <= TOOL_RESULT_KEEP_CHARS {
+        return None;
+    }
+    Some(format!(
+        "{}\n[{} characters trimmed to fit the context window]",
+        truncate_with_ellipsis(text, TOOL_RESULT_KEEP_CHARS),
+        total - TOOL_RESULT_KEEP_CHARS
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn small_budget(window_tokens: usize) -> ContextBudget {
+        ContextBudget {
+            window_tokens,
+            reserve_tokens: 0,
+        }
+    }
+
+    #[test]
+    fn registry_matches_known_models_and_ignores_provider_prefix() {
+        assert_eq!(context_window_for_model("claude-sonnet-4-5"), 200_000);
+        assert_eq!(
+            context_window_for_model("anthropic/claude-3-5-haiku"),
+            200_000
+        );
+        assert_eq!(context_window_for_model("gpt-4o-mini"), 128_000);
+        assert_eq!(context_window_for_model("gpt-4"), 8_192);
+        assert_eq!(context_window_for_model("gemini-2.0-flash"), 1_048_576);
+        assert_eq!(context_window_for_model("o3-mini"), 200_000);
+        assert_eq!(
+            context_window_for_model("meta-llama/Meta-Llama-3.1-70B-Instruct"),
+            128_000
+        );
+        assert_eq!(context_window_for_model("llama3.1:8b"), 128_000);
+        // Only prefixes count: "o1"/"o3" inside another name is not a match.
+        assert_eq!(
+            context_window_for_model("demo1-chat"),
+            DEFAULT_CONTEXT_WINDOW_TOKENS
+        );
+        assert_eq!(
+            context_window_for_model("phi3-mini"),
+            DEFAULT_CONTEXT_WINDOW_TOKENS
+        );
+        assert_eq!(
+            context_window_for_model("some-local-model"),
+            DEFAULT_CONTEXT_WINDOW_TOKENS
+        );
+    }
+
+    #[test]
+    fn override_takes_precedence_and_reserve_scales_down() {
+        let budget = ContextBudget::for_model("claude-sonnet-4-5", Some(8_000));
+        assert_eq!(budget.window_tokens, 8_000);
+        assert_eq!(budget.reserve_tokens, 2_000);
+        assert_eq!(budget.input_tokens(), 6_000);
+
+        let budget = ContextBudget::for_model("claude-sonnet-4-5", Some(0));
+        assert_eq!(budget.window_tokens, 200_000);
+        assert_eq!(budget.reserve_tokens, OUTPUT_RESERVE_TOKENS);
+    }
+
+    #[test]
+    fn estimate_counts_non_ascii_per_character() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcdefgh"), 2);
+        assert_eq!(estimate_tokens("你好"), 2);
+    }
+
+    #[test]
+    fn plan_is_noop_within_budget() {
+        let history = vec![ChatMessage::system("sys"), ChatMessage::user("hi")];
+        let plan = small_budget(1_000).plan(&history);
+        assert!(plan.is_noop());
+        assert!(plan.fits());
+    }
+
+    #[test]
+    fn plan_trims_old_tool_results_before_compacting() {
+        let history = vec![
+            ChatMessage::system("sys"),
+            ChatMessage::user("list files"),
+            ChatMessage::tool_result("call_1", "x".repeat(8_000)),
+            ChatMessage::assistant("done"),
+            ChatMessage::user("thanks"),
+        ];
+        let budget = small_budget(1_000);
+        let plan = budget.plan(&history);
+        assert_eq!(plan.shrink, vec![2]);
+        assert!(plan.compact.is_empty());
+        assert!(plan.fits());
+
+        let mut fitted = history.clone();
+        assert!(budget.fit(&mut fitted).is_empty());
+        assert!(fitted[2].content.contains("characters trimmed"));
+        assert!(matches!(
+            fitted[2].parts.as_slice(),
+            [ContentPart::ToolResult { tool_use_id, .. }] if tool_use_id == "call_1"
+        ));
+    }
+
+    #[test]
+    fn plan_strips_stale_memory_context_but_keeps_latest() {
+        let memory = format!("{MEMORY_CONTEXT_HEADER}- fact: {}\n\n", "m".repeat(4_000));
+        let history = vec![
+            ChatMessage::system("sys"),
+            ChatMessage::user(format!("{memory}old question")),
+            ChatMessage::assistant("old answer"),
+            ChatMessage::user(format!("{memory}new question")),
+        ];
+        let plan = small_budget(1_500).plan(&history);
+        assert_eq!(plan.shrink, vec![1]);
+
+        let mut fitted = history.clone();
+        apply_shrink(&mut fitted, &plan);
+        assert_eq!(fitted[1].content, "old question");
+        assert!(fitted[3].content.starts_with(MEMORY_CONTEXT_HEADER));
+    }
+
+    #[test]
+    fn plan_compacts_oldest_turns_on_turn_boundaries() {
+        let mut history = vec![ChatMessage::system("sys")];
+        for i in 0..10 {
+            history.push(ChatMessage::user(format!(
+                "question {i} {}",
+                "q".repeat(400)
+            )));
+            history.push(ChatMessage::assistant_tool_calls(
+                None,
+                &[crate::providers::ToolCall {
+                    id: format!("call_{i}"),
+                    name: "shell".into(),
+                    arguments: "{}".into(),
+                }],
+            ));
+            history.push(ChatMessage::tool_result(format!("call_{i}"), "ok"));
+            history.push(ChatMessage::assistant(format!("answer {i}")));
+        }
+        let budget = small_budget(1_400);
+        let plan = budget.plan(&history);
+
+        assert_eq!(plan.compact.start, 1);
+        assert!(plan.fits());
+        assert_eq!(history[plan.compact.end].role, "user");
+        assert!(plan.compact.end < history.len() - 4);
+
+        let dropped = budget.fit(&mut history);
+        assert!(!dropped.is_empty());
+        assert_eq!(history[0].role, "system");
+        assert_eq!(history[1].role, "user");
+        assert!(history.last().unwrap().content.contains("answer 9"));
+    }
+
+    #[test]
+    fn plan_never_compacts_latest_turn() {
+        let history = vec![
+            ChatMessage::system("sys"),
+            ChatMessage::user("y".repeat(10_000)),
+        ];
+        let plan = small_budget(100).plan(&history);
+        assert!(plan.compact.is_empty());
+        assert!(!plan.fits());
+    }
+}
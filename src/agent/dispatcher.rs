@@ -204,21 +204,17 @@ impl ToolDispatcher for NativeToolDispatcher {
             .flat_map(|msg| match msg {
                 ConversationMessage::Chat(chat) => vec![chat.clone()],
                 ConversationMessage::AssistantToolCalls { text, tool_calls } => {
-                    let payload = serde_json::json!({
-                        "content": text,
-                        "tool_calls": tool_calls,
-                    });
-                    vec![ChatMessage::assistant(payload.to_string())]
+                    vec![ChatMessage::assistant_tool_calls(
+                        text.as_deref(),
+                        tool_calls,
+                    )]
                 }
                 ConversationMessage::ToolResults(results) => results
                     .iter()
                     .map(|result| {
-                        ChatMessage::tool(
-                            serde_json::json!({
-                                "tool_call_id": result.tool_call_id,
-                                "content": result.content,
-                            })
-                            .to_string(),
+                        ChatMessage::tool_result(
+                            result.tool_call_id.clone(),
+                            result.content.clone(),
                         )
                     })
                     .collect(),
@@ -244,6 +240,7 @@ mod tests {
             ),
             tool_calls: vec![],
             usage: None,
+            routed_to: None,
         };
         let dispatcher = XmlToolDispatcher;
         let (_, calls) = dispatcher.parse_response(&response);
@@ -261,6 +258,7 @@ mod tests {
                 arguments: "{\"path\":\"a.txt\"}".into(),
             }],
             usage: None,
+            routed_to: None,
         };
         let dispatcher = NativeToolDispatcher;
         let (_, calls) = dispatcher.parse_response(&response);
@@ -1,16 +1,20 @@
+use crate::agent::context::{self, ContextBudget};
 use crate::approval::{ApprovalManager, ApprovalRequest, ApprovalResponse};
 use crate::config::Config;
+use crate::cost::{CostAttribution, CostContext};
 use crate::memory::{self, Memory, MemoryCategory};
 use crate::multimodal;
 use crate::observability::{self, runtime_trace, Observer, ObserverEvent};
 use crate::providers::{
-    self, ChatMessage, ChatRequest, Provider, ProviderCapabilityError, ToolCall,
+    self, ChatMessage, ChatRequest, ChatResponse, ChatStreamAccumulator, Provider,
+    ProviderCapabilityError, StreamEvent, ToolCall,
 };
 use crate::runtime;
 use crate::security::SecurityPolicy;
 use crate::tools::{self, Tool};
 use crate::util::truncate_with_ellipsis;
 use anyhow::Result;
+use futures_util::StreamExt;
 use regex::{Regex, RegexSet};
 use std::collections::HashSet;
 use std::fmt::Write;
@@ -85,14 +89,6 @@ pub(crate) fn scrub_credentials(input: &str) -> String {
         .to_string()
 }
 
-/// Default trigger for auto-compaction when non-system message count exceeds this threshold.
-/// Prefer passing the config-driven value via `run_tool_call_loop`; this constant is only
-/// used when callers omit the parameter.
-const DEFAULT_MAX_HISTORY_MESSAGES: usize = 50;
-
-/// Keep this many most-recent non-system messages after compaction.
-const COMPACTION_KEEP_RECENT_MESSAGES: usize = 20;
-
 /// Safety cap for compaction source transcript passed to the summarizer.
 const COMPACTION_MAX_SOURCE_CHARS: usize = 12_000;
 
@@ -106,6 +102,14 @@ pub(crate) const PROGRESS_MIN_INTERVAL_MS: u64 = 500;
 /// Used before streaming the final answer so progress lines are replaced by the clean response.
 pub(crate) const DRAFT_CLEAR_SENTINEL: &str = "\x00CLEAR\x00";
 
+/// Prefix marking an on_delta message as a progress line rather than answer text.
+/// Draft updaters strip it; API consumers that only want the answer drop the line.
+pub(crate) const DRAFT_PROGRESS_PREFIX: &str = "\x00PROGRESS\x00";
+
+fn progress_delta(line: impl std::fmt::Display) -> String {
+    format!("{DRAFT_PROGRESS_PREFIX}{line}")
+}
+
 /// Extract a short hint from tool call arguments for progress display.
 fn truncate_tool_args_for_progress(name: &str, args: &serde_json::Value, max_len: usize) -> String {
     let hint = match name {
@@ -143,26 +147,6 @@ fn autosave_memory_key(prefix: &str) -> String {
     format!("{prefix}_{}", Uuid::new_v4())
 }
 
-/// Trim conversation history to prevent unbounded growth.
-/// Preserves the system prompt (first message if role=system) and the most recent messages.
-fn trim_history(history: &mut Vec<ChatMessage>, max_history: usize) {
-    // Nothing to trim if within limit
-    let has_system = history.first().map_or(false, |m| m.role == "system");
-    let non_system_count = if has_system {
-        history.len() - 1
-    } else {
-        history.len()
-    };
-
-    if non_system_count <= max_history {
-        return;
-    }
-
-    let start = if has_system { 1 } else { 0 };
-    let to_remove = non_system_count - max_history;
-    history.drain(start..start + to_remove);
-}
-
 fn build_compaction_transcript(messages: &[ChatMessage]) -> String {
     let mut transcript = String::new();
     for msg in messages {
@@ -187,31 +171,29 @@ fn apply_compaction_summary(
     history.splice(start..compact_end, std::iter::once(summary_msg));
 }
 
-async fn auto_compact_history(
+/// Fit `history` into the model's context budget before the next request.
+///
+/// Follows [`ContextBudget::plan`]: older tool results and stale memory
+/// context are trimmed, then the oldest turns are replaced by a summary.
+/// Returns `true` when the history changed.
+pub(crate) async fn auto_compact_history(
     history: &mut Vec<ChatMessage>,
     provider: &dyn Provider,
     model: &str,
-    max_history: usize,
+    budget: &ContextBudget,
 ) -> Result<bool> {
-    let has_system = history.first().map_or(false, |m| m.role == "system");
-    let non_system_count = if has_system {
-        history.len().saturating_sub(1)
-    } else {
-        history.len()
-    };
-
-    if non_system_count <= max_history {
+    let plan = budget.plan(history);
+    if plan.is_noop() {
         return Ok(false);
     }
 
-    let start = if has_system { 1 } else { 0 };
-    let keep_recent = COMPACTION_KEEP_RECENT_MESSAGES.min(non_system_count);
-    let compact_count = non_system_count.saturating_sub(keep_recent);
-    if compact_count == 0 {
-        return Ok(false);
+    context::apply_shrink(history, &plan);
+    if plan.compact.is_empty() {
+        return Ok(true);
     }
 
-    let compact_end = start + compact_count;
+    let start = plan.compact.start;
+    let compact_end = plan.compact.end;
     let to_compact: Vec<ChatMessage> = history[start..compact_end].to_vec();
     let transcript = build_compaction_transcript(&to_compact);
 
@@ -1767,43 +1749,6 @@ pub(crate) fn is_tool_loop_cancelled(err: &anyhow::Error) -> bool {
     err.chain().any(|source| source.is::<ToolLoopCancelled>())
 }
 
-/// Execute a single turn of the agent loop: send messages, parse tool calls,
-/// execute tools, and loop until the LLM produces a final text response.
-/// When `silent` is true, suppresses stdout (for channel use).
-#[allow(clippy::too_many_arguments)]
-pub(crate) async fn agent_turn(
-    provider: &dyn Provider,
-    history: &mut Vec<ChatMessage>,
-    tools_registry: &[Box<dyn Tool>],
-    observer: &dyn Observer,
-    provider_name: &str,
-    model: &str,
-    temperature: f64,
-    silent: bool,
-    multimodal_config: &crate::config::MultimodalConfig,
-    max_tool_iterations: usize,
-) -> Result<String> {
-    run_tool_call_loop(
-        provider,
-        history,
-        tools_registry,
-        observer,
-        provider_name,
-        model,
-        temperature,
-        silent,
-        None,
-        "channel",
-        multimodal_config,
-        max_tool_iterations,
-        None,
-        None,
-        None,
-        &[],
-    )
-    .await
-}
-
 async fn execute_one_tool(
     call_name: &str,
     call_arguments: serde_json::Value,
@@ -1958,6 +1903,69 @@ async fn execute_tools_sequential(
     Ok(outcomes)
 }
 
+/// Stream one provider turn, relaying text deltas and tool-call progress to
+/// `tx` as they arrive, and rebuild the complete response at the end.
+///
+/// Returns the response and whether any text was relayed, so the caller can
+/// skip re-chunking the final answer.
+async fn stream_chat_turn(
+    provider: &dyn Provider,
+    request: ChatRequest<'_>,
+    model: &str,
+    temperature: f64,
+    tx: &tokio::sync::mpsc::Sender<String>,
+    cancellation_token: Option<&CancellationToken>,
+) -> Result<(ChatResponse, bool)> {
+    // Opening the stream waits for the response headers, which a slow
+    // provider can hold back for as long as it is thinking.
+    let opening = provider.stream_chat(request, model, temperature);
+    let mut events = match cancellation_token {
+        Some(token) => tokio::select! {
+            () = token.cancelled() => return Err(ToolLoopCancelled.into()),
+            events = opening => events?,
+        },
+        None => opening.await?,
+    };
+    let mut accumulator = ChatStreamAccumulator::new();
+    let mut relayed_text = false;
+
+    loop {
+        let next = if let Some(token) = cancellation_token {
+            tokio::select! {
+                () = token.cancelled() => return Err(ToolLoopCancelled.into()),
+                next = events.next() => next,
+            }
+        } else {
+            events.next().await
+        };
+        let Some(event) = next else {
+            break;
+        };
+        let event = event?;
+        accumulator.push(&event);
+
+        match event {
+            StreamEvent::TextDelta(text) => {
+                if !relayed_text {
+                    // Clear accumulated progress lines before the answer text.
+                    let _ = tx.send(DRAFT_CLEAR_SENTINEL.to_string()).await;
+                    relayed_text = true;
+                }
+                let _ = tx.send(text).await;
+            }
+            StreamEvent::ToolCallStart { name, .. } if !name.is_empty() => {
+                let _ = tx
+                    .send(progress_delta(format!("\n\u{1f527} {name}\u{2026}\n")))
+                    .await;
+            }
+            StreamEvent::Done => break,
+            _ => {}
+        }
+    }
+
+    Ok((accumulator.finish(), relayed_text))
+}
+
 // ── Agent Tool-Call Loop ──────────────────────────────────────────────────
 // Core agentic iteration: send conversation to the LLM, parse any tool
 // calls from the response, execute them, append results to history, and
@@ -1990,6 +1998,8 @@ pub(crate) async fn run_tool_call_loop(
     on_delta: Option<tokio::sync::mpsc::Sender<String>>,
     hooks: Option<&crate::hooks::HookRunner>,
     excluded_tools: &[String],
+    cost: Option<&CostContext>,
+    context_budget: Option<&ContextBudget>,
 ) -> Result<String> {
     let max_iterations = if max_tool_iterations == 0 {
         DEFAULT_MAX_TOOL_ITERATIONS
@@ -2014,6 +2024,18 @@ pub(crate) async fn run_tool_call_loop(
             return Err(ToolLoopCancelled.into());
         }
 
+        // Tool output piles up across iterations, so every request is fitted
+        // to the window, not only the first one of the turn.
+        if let Some(budget) = context_budget {
+            match auto_compact_history(history, provider, model, budget).await {
+                Ok(true) => {
+                    tracing::debug!(iteration, "Compacted history to fit the context window");
+                }
+                Ok(false) => {}
+                Err(e) => tracing::warn!("History compaction failed: {e}"),
+            }
+        }
+
         let image_marker_count = multimodal::count_image_markers(history);
         if image_marker_count > 0 && !provider.supports_vision() {
             return Err(ProviderCapabilityError {
@@ -2036,7 +2058,7 @@ pub(crate) async fn run_tool_call_loop(
             } else {
                 format!("\u{1f914} Thinking (round {})...\n", iteration + 1)
             };
-            let _ = tx.send(phase).await;
+            let _ = tx.send(progress_delta(phase)).await;
         }
 
         observer.record_event(&ObserverEvent::LlmRequest {
@@ -2058,6 +2080,11 @@ pub(crate) async fn run_tool_call_loop(
             }),
         );
 
+        // Refuse the call once a denying budget for this conversation is spent.
+        if let Some(cost) = cost {
+            cost.ensure_within_budget()?;
+        }
+
         let llm_started_at = Instant::now();
 
         // Fire void hook before LLM call
@@ -2073,23 +2100,50 @@ pub(crate) async fn run_tool_call_loop(
             None
         };
 
-        let chat_future = provider.chat(
-            ChatRequest {
-                messages: &prepared_messages.messages,
-                tools: request_tools,
-            },
-            model,
-            temperature,
-        );
+        let request = ChatRequest {
+            messages: &prepared_messages.messages,
+            tools: request_tools,
+        };
 
-        let chat_result = if let Some(token) = cancellation_token.as_ref() {
-            tokio::select! {
-                () = token.cancelled() => return Err(ToolLoopCancelled.into()),
-                result = chat_future => result,
+        // With a draft sender attached, stream typed events so text and
+        // tool-call progress reach the channel while the model is generating.
+        // Prompt-guided tool calls arrive inline in the text, so only stream
+        // when tool calls travel out-of-band.
+        let stream_live = on_delta.is_some() && (use_native_tools || tool_specs.is_empty());
+        let mut streamed_text = false;
+        let chat_result = match on_delta.as_ref().filter(|_| stream_live) {
+            Some(tx) => {
+                match stream_chat_turn(
+                    provider,
+                    request,
+                    model,
+                    temperature,
+                    tx,
+                    cancellation_token.as_ref(),
+                )
+                .await
+                {
+                    Ok((resp, relayed)) => {
+                        streamed_text = relayed;
+                        Ok(resp)
+                    }
+                    Err(e) if e.is::<ToolLoopCancelled>() => return Err(e),
+                    Err(e) => Err(e),
+                }
+            }
+            None => {
+                let chat_future = provider.chat(request, model, temperature);
+                if let Some(token) = cancellation_token.as_ref() {
+                    tokio::select! {
+                        () = token.cancelled() => return Err(ToolLoopCancelled.into()),
+                        result = chat_future => result,
+                    }
+                } else {
+                    chat_future.await
+                }
             }
-        } else {
-            chat_future.await
         };
+        crate::providers::health::report_to(observer);
 
         let (response_text, parsed_text, tool_calls, assistant_history_content, native_tool_calls) =
             match chat_result {
@@ -2099,10 +2153,20 @@ pub(crate) async fn run_tool_call_loop(
                         .as_ref()
                         .map(|u| (u.input_tokens, u.output_tokens))
                         .unwrap_or((None, None));
+                    // Bill the route a router actually picked, not the requested model.
+                    let (served_provider, served_model) = resp
+                        .routed_to
+                        .as_ref()
+                        .map_or((provider_name, model), |route| {
+                            (route.provider_name.as_str(), route.model.as_str())
+                        });
+                    if let (Some(cost), Some(usage)) = (cost, resp.usage.as_ref()) {
+                        cost.record(served_provider, served_model, usage);
+                    }
 
                     observer.record_event(&ObserverEvent::LlmResponse {
-                        provider: provider_name.to_string(),
-                        model: model.to_string(),
+                        provider: served_provider.to_string(),
+                        model: served_model.to_string(),
                         duration: llm_started_at.elapsed(),
                         success: true,
                         error_message: None,
@@ -2226,10 +2290,10 @@ pub(crate) async fn run_tool_call_loop(
             let llm_secs = llm_started_at.elapsed().as_secs();
             if !tool_calls.is_empty() {
                 let _ = tx
-                    .send(format!(
+                    .send(progress_delta(format!(
                         "\u{1f4ac} Got {} tool call(s) ({llm_secs}s)\n",
                         tool_calls.len()
-                    ))
+                    )))
                     .await;
             }
         }
@@ -2251,7 +2315,7 @@ pub(crate) async fn run_tool_call_loop(
             // No tool calls — this is the final response.
             // If a streaming sender is provided, relay the text in small chunks
             // so the channel can progressively update the draft message.
-            if let Some(ref tx) = on_delta {
+            if let Some(tx) = on_delta.as_ref().filter(|_| !streamed_text) {
                 // Clear accumulated progress lines before streaming the final answer.
                 let _ = tx.send(DRAFT_CLEAR_SENTINEL.to_string()).await;
                 // Split on whitespace boundaries, accumulating chunks of at least
@@ -2344,19 +2408,46 @@ pub(crate) async fn run_tool_call_loop(
             }
 
             // ── Approval hook ────────────────────────────────
+            let always_gated =
+                find_tool(tools_registry, &tool_name).is_some_and(|tool| tool.requires_approval());
+            if always_gated && !approval.is_some_and(|mgr| mgr.can_prompt(channel_name)) {
+                let refused = format!(
+                    "Tool '{tool_name}' requires approval, but no approval prompt is available on this channel."
+                );
+                runtime_trace::record_event(
+                    "tool_call_result",
+                    Some(channel_name),
+                    Some(provider_name),
+                    Some(model),
+                    Some(&turn_id),
+                    Some(false),
+                    Some(&refused),
+                    serde_json::json!({
+                        "iteration": iteration + 1,
+                        "tool": tool_name.clone(),
+                        "arguments": scrub_credentials(&tool_args.to_string()),
+                    }),
+                );
+                ordered_results[idx] = Some((
+                    tool_name.clone(),
+                    call.tool_call_id.clone(),
+                    ToolExecutionOutcome {
+                        output: refused.clone(),
+                        success: false,
+                        error_reason: Some(refused),
+                        duration: Duration::ZERO,
+                    },
+                ));
+                continue;
+            }
             if let Some(mgr) = approval {
-                if mgr.needs_approval(&tool_name) {
+                if always_gated || mgr.needs_approval(&tool_name) {
                     let request = ApprovalRequest {
                         tool_name: tool_name.clone(),
                         arguments: tool_args.clone(),
                     };
 
-                    // Only prompt interactively on CLI; auto-approve on other channels.
-                    let decision = if channel_name == "cli" {
-                        mgr.prompt_cli(&request)
-                    } else {
-                        ApprovalResponse::Yes
-                    };
+                    let decision = mgr.request_approval(&request, channel_name).await;
 
                     mgr.record_decision(&tool_name, &tool_args, decision, channel_name);
 
@@ -2448,7 +2539,7 @@ pub(crate) async fn run_tool_call_loop(
                     format!("\u{23f3} {}: {hint}\n", tool_name)
                 };
                 tracing::debug!(tool = %tool_name, "Sending progress start to draft");
-                let _ = tx.send(progress).await;
+                let _ = tx.send(progress_delta(progress)).await;
             }
 
             executable_indices.push(idx);
@@ -2519,7 +2610,9 @@ pub(crate) async fn run_tool_call_loop(
                     "\u{274c}"
                 };
                 tracing::debug!(tool = %call.name, secs, "Sending progress complete to draft");
-                let _ = tx.send(format!("{icon} {} ({secs}s)\n", call.name)).await;
+                let _ = tx
+                    .send(progress_delta(format!("{icon} {} ({secs}s)\n", call.name)))
+                    .await;
             }
 
             ordered_results[*idx] = Some((call.name.clone(), call.tool_call_id.clone(), outcome));
@@ -2549,11 +2642,10 @@ pub(crate) async fn run_tool_call_loop(
                     .all(|(tool_call_id, _)| tool_call_id.is_some());
             if all_results_have_ids {
                 for (tool_call_id, result) in &individual_results {
-                    let tool_msg = serde_json::json!({
-                        "tool_call_id": tool_call_id,
-                        "content": result,
-                    });
-                    history.push(ChatMessage::tool(tool_msg.to_string()));
+                    history.push(ChatMessage::tool_result(
+                        tool_call_id.clone().unwrap_or_default(),
+                        result.clone(),
+                    ));
                 }
             } else {
                 history.push(ChatMessage::user(format!("[Tool results]\n{tool_results}")));
@@ -2562,11 +2654,10 @@ pub(crate) async fn run_tool_call_loop(
             for (native_call, (_, result)) in
                 native_tool_calls.iter().zip(individual_results.iter())
             {
-                let tool_msg = serde_json::json!({
-                    "tool_call_id": native_call.id,
-                    "content": result,
-                });
-                history.push(ChatMessage::tool(tool_msg.to_string()));
+                history.push(ChatMessage::tool_result(
+                    native_call.id.clone(),
+                    result.clone(),
+                ));
             }
         }
     }
@@ -2631,16 +2722,24 @@ pub async fn run(
     temperature: f64,
     peripheral_overrides: Vec<String>,
     interactive: bool,
+    session_id: Option<String>,
+    cost_attribution: Option<CostAttribution>,
 ) -> Result<String> {
     // ── Wire up agnostic subsystems ──────────────────────────────
     let base_observer = observability::create_observer(&config.observability);
     let observer: Arc<dyn Observer> = Arc::from(base_observer);
-    let runtime: Arc<dyn runtime::RuntimeAdapter> =
-        Arc::from(runtime::create_runtime(&config.runtime)?);
+    let runtime: Arc<dyn runtime::RuntimeAdapter> = Arc::from(runtime::create_runtime(
+        &config.runtime,
+        &config.security.resources,
+    )?);
     let security = Arc::new(SecurityPolicy::from_config(
         &config.autonomy,
         &config.workspace_dir,
     ));
+    let cost = CostContext::from_config(
+        &config,
+        cost_attribution.unwrap_or_else(|| CostAttribution::channel("cli", "user")),
+    );
 
     // ── Memory (the brain) ────────────────────────────────────────
     let mem: Arc<dyn Memory> = Arc::from(memory::create_memory_with_storage(
@@ -2708,12 +2807,9 @@ pub async fn run(
         reasoning_enabled: config.runtime.reasoning_enabled,
     };
 
-    let provider: Box<dyn Provider> = providers::create_routed_provider_with_options(
+    let provider: Box<dyn Provider> = providers::create_routed_provider_from_config(
+        &config,
         provider_name,
-        config.api_key.as_deref(),
-        config.api_url.as_deref(),
-        &config.reliability,
-        &config.model_routes,
         model_name,
         &provider_runtime_options,
     )?;
@@ -2877,12 +2973,37 @@ pub async fn run(
 
     // ── Approval manager (supervised mode) ───────────────────────
     let approval_manager = if interactive {
-        Some(ApprovalManager::from_config(&config.autonomy))
+        Some(
+            ApprovalManager::from_config(&config.autonomy)
+                .with_audit(crate::security::AuditLogger::try_from_config(&config)),
+        )
     } else {
         None
     };
     let channel_name = if interactive { "cli" } else { "daemon" };
 
+    // ── Session (persistent transcript) ───────────────────────────
+    let session = match session_id.as_deref() {
+        Some(id) => {
+            let store = crate::sessions::SessionStore::open(&config.workspace_dir)?;
+            let info = store.ensure(id, channel_name)?;
+            let transcript = store.load(&info.id)?;
+            tracing::info!(
+                session = %info.id,
+                messages = transcript.len(),
+                "Session loaded"
+            );
+            Some((store, info.id, transcript))
+        }
+        None => None,
+    };
+    let prior_history = session
+        .as_ref()
+        .map(|(_, _, transcript)| {
+            crate::sessions::history_from_transcript(transcript, native_tools)
+        })
+        .unwrap_or_default();
+
     // ── Execute ──────────────────────────────────────────────────
     let start = Instant::now();
 
@@ -2912,10 +3033,10 @@ pub async fn run(
             format!("{context}{msg}")
         };
 
-        let mut history = vec![
-            ChatMessage::system(&system_prompt),
-            ChatMessage::user(&enriched),
-        ];
+        let mut history = vec![ChatMessage::system(&system_prompt)];
+        history.extend(prior_history);
+        history.push(ChatMessage::user(&enriched));
+        let budget = ContextBudget::for_model(model_name, config.agent.context_window_tokens);
 
         let response = run_tool_call_loop(
             provider.as_ref(),
@@ -2934,8 +3055,14 @@ pub async fn run(
             None,
             None,
             &[],
+            cost.as_ref(),
+            Some(&budget),
         )
         .await?;
+        if let Some((store, id, _)) = &session {
+            let turn_start = context::current_turn_start(&history);
+            persist_session_turn(store, id, &history[turn_start..], &msg);
+        }
         final_output = response.clone();
         println!("{response}");
         observer.record_event(&ObserverEvent::TurnComplete);
@@ -2944,8 +3071,13 @@ pub async fn run(
         println!("Type /help for commands.\n");
         let cli = crate::channels::CliChannel::new();
 
+        if let Some((_, id, transcript)) = &session {
+            println!("📂 Session {id} ({} stored message(s))\n", transcript.len());
+        }
+
         // Persistent conversation history across turns
         let mut history = vec![ChatMessage::system(&system_prompt)];
+        history.extend(prior_history);
 
         loop {
             print!("> ");
@@ -2993,6 +3125,11 @@ pub async fn run(
 
                     history.clear();
                     history.push(ChatMessage::system(&system_prompt));
+                    if let Some((store, id, _)) = &session {
+                        if let Err(e) = store.clear(id) {
+                            tracing::warn!(session = %id, "Failed to clear session: {e}");
+                        }
+                    }
                     // Clear conversation and daily memory
                     let mut cleared = 0;
                     for category in [MemoryCategory::Conversation, MemoryCategory::Daily] {
@@ -3038,6 +3175,10 @@ pub async fn run(
 
             history.push(ChatMessage::user(&enriched));
 
+            // The tool loop fits every request into the model's context
+            // window, summarizing older turns instead of dropping them.
+            let budget = ContextBudget::for_model(model_name, config.agent.context_window_tokens);
+
             let response = match run_tool_call_loop(
                 provider.as_ref(),
                 &mut history,
@@ -3055,6 +3196,8 @@ pub async fn run(
                 None,
                 None,
                 &[],
+                cost.as_ref(),
+                Some(&budget),
             )
             .await
             {
@@ -3064,6 +3207,10 @@ pub async fn run(
                     continue;
                 }
             };
+            if let Some((store, id, _)) = &session {
+                let turn_start = context::current_turn_start(&history);
+                persist_session_turn(store, id, &history[turn_start..], &user_input);
+            }
             final_output = response.clone();
             if let Err(e) = crate::channels::Channel::send(
                 &cli,
@@ -3074,23 +3221,6 @@ pub async fn run(
                 eprintln!("\nError sending CLI response: {e}\n");
             }
             observer.record_event(&ObserverEvent::TurnComplete);
-
-            // Auto-compaction before hard trimming to preserve long-context signal.
-            if let Ok(compacted) = auto_compact_history(
-                &mut history,
-                provider.as_ref(),
-                model_name,
-                config.agent.max_history_messages,
-            )
-            .await
-            {
-                if compacted {
-                    println!("🧹 Auto-compaction complete");
-                }
-            }
-
-            // Hard cap as a safety net.
-            trim_history(&mut history, config.agent.max_history_messages);
         }
     }
 
@@ -3106,185 +3236,380 @@ pub async fn run(
     Ok(final_output)
 }
 
+/// Append one completed turn to a stored session.
+///
+/// `turn` starts with the context-enriched user message; the raw user input
+/// is stored in its place so resumed sessions do not replay stale context.
+/// Persistence failures are logged and never fail the turn.
+pub(crate) fn persist_session_turn(
+    store: &crate::sessions::SessionStore,
+    session_id: &str,
+    turn: &[ChatMessage],
+    user_input: &str,
+) {
+    let mut transcript = vec![providers::ConversationMessage::Chat(ChatMessage::user(
+        user_input,
+    ))];
+    transcript.extend(crate::sessions::transcript_from_history(
+        turn.get(1..).unwrap_or_default(),
+    ));
+    if let Err(e) = store.append(session_id, &transcript) {
+        tracing::warn!(session = session_id, "Failed to persist session turn: {e}");
+    }
+}
+
 /// Process a single message through the full agent (with tools, peripherals, memory).
 /// Used by channels (Telegram, Discord, etc.) to enable hardware and tool use.
 pub async fn process_message(config: Config, message: &str) -> Result<String> {
-    let observer: Arc<dyn Observer> =
-        Arc::from(observability::create_observer(&config.observability));
-    let runtime: Arc<dyn runtime::RuntimeAdapter> =
-        Arc::from(runtime::create_runtime(&config.runtime)?);
-    let security = Arc::new(SecurityPolicy::from_config(
-        &config.autonomy,
-        &config.workspace_dir,
-    ));
-    let mem: Arc<dyn Memory> = Arc::from(memory::create_memory_with_storage(
-        &config.memory,
-        Some(&config.storage.provider.config),
-        &config.workspace_dir,
-        config.api_key.as_deref(),
-    )?);
+    Box::pin(process_message_with_approval(config, message, None)).await
+}
 
-    let (composio_key, composio_entity_id) = if config.composio.enabled {
-        (
-            config.composio.api_key.as_deref(),
-            Some(config.composio.entity_id.as_str()),
-        )
-    } else {
-        (None, None)
-    };
-    let mut tools_registry = tools::all_tools_with_runtime(
-        Arc::new(config.clone()),
-        &security,
-        runtime,
-        mem.clone(),
-        composio_key,
-        composio_entity_id,
-        &config.browser,
-        &config.http_request,
-        &config.workspace_dir,
-        &config.agents,
-        config.api_key.as_deref(),
-        &config,
-    );
-    let peripheral_tools: Vec<Box<dyn Tool>> =
-        crate::peripherals::create_peripheral_tools(&config.peripherals).await?;
-    tools_registry.extend(peripheral_tools);
+/// Like [`process_message`], gating tool calls through `approval`.
+pub async fn process_message_with_approval(
+    config: Config,
+    message: &str,
+    approval: Option<&ApprovalManager>,
+) -> Result<String> {
+    process_conversation(
+        config,
+        &[],
+        message,
+        approval,
+        None,
+        CostAttribution::channel("gateway", "gateway"),
+        None,
+    )
+    .await
+}
 
-    let provider_name = config.default_provider.as_deref().unwrap_or("openrouter");
-    let model_name = config
-        .default_model
-        .clone()
-        .unwrap_or_else(|| "anthropic/claude-sonnet-4-20250514".into());
-    let provider_runtime_options = providers::ProviderRuntimeOptions {
-        auth_profile_override: None,
-        zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
-        secrets_encrypt: config.secrets.encrypt,
-        reasoning_enabled: config.runtime.reasoning_enabled,
-    };
-    let provider: Box<dyn Provider> = providers::create_routed_provider_with_options(
-        provider_name,
-        config.api_key.as_deref(),
-        config.api_url.as_deref(),
-        &config.reliability,
-        &config.model_routes,
-        &model_name,
-        &provider_runtime_options,
-    )?;
+/// Run the full agent on `message` after the caller-supplied
+/// `prior_history` (user/assistant turns, e.g. from an OpenAI-style
+/// request). Caller system messages are appended to the system prompt.
+/// Cancelling `cancellation_token` abandons the in-flight provider call or
+/// tool and ends the turn with an error.
+pub async fn process_conversation(
+    config: Config,
+    prior_history: &[ChatMessage],
+    message: &str,
+    approval: Option<&ApprovalManager>,
+    hooks: Option<&crate::hooks::HookRunner>,
+    cost_attribution: CostAttribution,
+    cancellation_token: Option<CancellationToken>,
+) -> Result<String> {
+    let agent = Box::pin(ConversationAgent::new(config, cost_attribution)).await?;
 
-    let hardware_rag: Option<crate::rag::HardwareRag> = config
-        .peripherals
-        .datasheet_dir
-        .as_ref()
-        .filter(|d| !d.trim().is_empty())
-        .map(|dir| crate::rag::HardwareRag::load(&config.workspace_dir, dir.trim()))
-        .and_then(Result::ok)
-        .filter(|r: &crate::rag::HardwareRag| !r.is_empty());
-    let board_names: Vec<String> = config
-        .peripherals
-        .boards
+    let mut system_prompt = agent.system_prompt().to_string();
+    let caller_instructions: Vec<&str> = prior_history
         .iter()
-        .map(|b| b.board.clone())
+        .filter(|msg| msg.role == "system" && !msg.content.trim().is_empty())
+        .map(|msg| msg.content.as_str())
         .collect();
-
-    let skills = crate::skills::load_skills_with_config(&config.workspace_dir, &config);
-    let mut tool_descs: Vec<(&str, &str)> = vec![
-        ("shell", "Execute terminal commands."),
-        ("file_read", "Read file contents."),
-        ("file_write", "Write file contents."),
-        ("memory_store", "Save to memory."),
-        ("memory_recall", "Search memory."),
-        ("memory_forget", "Delete a memory entry."),
-        (
-            "model_routing_config",
-            "Configure default model, scenario routing, and delegate agents.",
-        ),
-        ("screenshot", "Capture a screenshot."),
-        ("image_info", "Read image metadata."),
-    ];
-    if config.browser.enabled {
-        tool_descs.push(("browser_open", "Open approved URLs in browser."));
-    }
-    if config.composio.enabled {
-        tool_descs.push(("composio", "Execute actions on 1000+ apps via Composio."));
+    if !caller_instructions.is_empty() {
+        system_prompt.push_str("\n\n## Caller Instructions\n\n");
+        system_prompt.push_str(&caller_instructions.join("\n\n"));
     }
-    if config.peripherals.enabled && !config.peripherals.boards.is_empty() {
-        tool_descs.push(("gpio_read", "Read GPIO pin value on connected hardware."));
-        tool_descs.push((
-            "gpio_write",
-            "Set GPIO pin high or low on connected hardware.",
-        ));
-        tool_descs.push((
-            "arduino_upload",
-            "Upload Arduino sketch. Use for 'make a heart', custom patterns. You write full .ino code; ZeroClaw uploads it.",
-        ));
-        tool_descs.push((
-            "hardware_memory_map",
-            "Return flash and RAM address ranges. Use when user asks for memory addresses or memory map.",
-        ));
-        tool_descs.push((
-            "hardware_board_info",
-            "Return full board info (chip, architecture, memory map). Use when user asks for board info, what board, connected hardware, or chip info.",
-        ));
-        tool_descs.push((
-            "hardware_memory_read",
-            "Read actual memory/register values from Nucleo. Use when user asks to read registers, read memory, dump lower memory 0-126, or give address and value.",
-        ));
-        tool_descs.push((
-            "hardware_capabilities",
-            "Query connected hardware for reported GPIO pins and LED pin. Use when user asks what pins are available.",
-        ));
-    }
-    let bootstrap_max_chars = if config.agent.compact_context {
-        Some(6000)
-    } else {
-        None
-    };
-    let native_tools = provider.supports_native_tools();
-    let mut system_prompt = crate::channels::build_system_prompt_with_mode(
-        &config.workspace_dir,
-        &model_name,
-        &tool_descs,
-        &skills,
-        Some(&config.identity),
-        bootstrap_max_chars,
-        native_tools,
-        config.skills.prompt_injection_mode,
+
+    let mut history = Vec::with_capacity(prior_history.len() + 2);
+    history.push(ChatMessage::system(&system_prompt));
+    history.extend(
+        prior_history
+            .iter()
+            .filter(|msg| msg.role != "system")
+            .cloned(),
     );
-    if !native_tools {
-        system_prompt.push_str(&build_tool_instructions(&tools_registry));
-    }
 
-    let mem_context = build_context(mem.as_ref(), message, config.memory.min_relevance_score).await;
-    let rag_limit = if config.agent.compact_context { 2 } else { 5 };
-    let hw_context = hardware_rag
-        .as_ref()
-        .map(|r| build_hardware_context(r, message, &board_names, rag_limit))
-        .unwrap_or_default();
-    let context = format!("{mem_context}{hw_context}");
-    let enriched = if context.is_empty() {
-        message.to_string()
-    } else {
-        format!("{context}{message}")
-    };
+    agent
+        .turn(
+            &mut history,
+            message,
+            approval,
+            hooks,
+            cancellation_token,
+            None,
+        )
+        .await
+}
 
-    let mut history = vec![
-        ChatMessage::system(&system_prompt),
-        ChatMessage::user(&enriched),
-    ];
+/// The full agent (tools, peripherals, memory, provider) built once from a
+/// config and reused for every turn of a long-lived conversation, such as a
+/// gateway WebSocket connection.
+pub struct ConversationAgent {
+    config: Config,
+    provider: Box<dyn Provider>,
+    provider_name: String,
+    model_name: String,
+    tools_registry: Vec<Box<dyn Tool>>,
+    observer: Arc<dyn Observer>,
+    mem: Arc<dyn Memory>,
+    hardware_rag: Option<crate::rag::HardwareRag>,
+    board_names: Vec<String>,
+    system_prompt: String,
+    cost: Option<CostContext>,
+}
 
-    agent_turn(
-        provider.as_ref(),
-        &mut history,
-        &tools_registry,
-        observer.as_ref(),
-        provider_name,
-        &model_name,
-        config.default_temperature,
-        true,
-        &config.multimodal,
-        config.agent.max_tool_iterations,
-    )
-    .await
+impl ConversationAgent {
+    /// Build the agent, reporting to the observer configured in
+    /// `[observability]`.
+    pub async fn new(config: Config, cost_attribution: CostAttribution) -> Result<Self> {
+        let observer: Arc<dyn Observer> =
+            Arc::from(observability::create_observer(&config.observability));
+        Self::with_observer(config, cost_attribution, observer).await
+    }
+
+    /// Build the agent, reporting lifecycle events to `observer`.
+    #[allow(clippy::too_many_lines)]
+    pub async fn with_observer(
+        config: Config,
+        cost_attribution: CostAttribution,
+        observer: Arc<dyn Observer>,
+    ) -> Result<Self> {
+        let cost = CostContext::from_config(&config, cost_attribution);
+        let runtime: Arc<dyn runtime::RuntimeAdapter> = Arc::from(runtime::create_runtime(
+            &config.runtime,
+            &config.security.resources,
+        )?);
+        let security = Arc::new(SecurityPolicy::from_config(
+            &config.autonomy,
+            &config.workspace_dir,
+        ));
+        let mem: Arc<dyn Memory> = Arc::from(memory::create_memory_with_storage(
+            &config.memory,
+            Some(&config.storage.provider.config),
+            &config.workspace_dir,
+            config.api_key.as_deref(),
+        )?);
+
+        let (composio_key, composio_entity_id) = if config.composio.enabled {
+            (
+                config.composio.api_key.as_deref(),
+                Some(config.composio.entity_id.as_str()),
+            )
+        } else {
+            (None, None)
+        };
+        let mut tools_registry = tools::all_tools_with_runtime(
+            Arc::new(config.clone()),
+            &security,
+            runtime,
+            mem.clone(),
+            composio_key,
+            composio_entity_id,
+            &config.browser,
+            &config.http_request,
+            &config.workspace_dir,
+            &config.agents,
+            config.api_key.as_deref(),
+            &config,
+        );
+        let peripheral_tools: Vec<Box<dyn Tool>> =
+            crate::peripherals::create_peripheral_tools(&config.peripherals).await?;
+        tools_registry.extend(peripheral_tools);
+
+        let provider_name = config
+            .default_provider
+            .clone()
+            .unwrap_or_else(|| "openrouter".into());
+        let model_name = config
+            .default_model
+            .clone()
+            .unwrap_or_else(|| "anthropic/claude-sonnet-4-20250514".into());
+        let provider_runtime_options = providers::ProviderRuntimeOptions {
+            auth_profile_override: None,
+            zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
+            secrets_encrypt: config.secrets.encrypt,
+            reasoning_enabled: config.runtime.reasoning_enabled,
+        };
+        let provider: Box<dyn Provider> = providers::create_routed_provider_from_config(
+            &config,
+            &provider_name,
+            &model_name,
+            &provider_runtime_options,
+        )?;
+
+        let hardware_rag: Option<crate::rag::HardwareRag> = config
+            .peripherals
+            .datasheet_dir
+            .as_ref()
+            .filter(|d| !d.trim().is_empty())
+            .map(|dir| crate::rag::HardwareRag::load(&config.workspace_dir, dir.trim()))
+            .and_then(Result::ok)
+            .filter(|r: &crate::rag::HardwareRag| !r.is_empty());
+        let board_names: Vec<String> = config
+            .peripherals
+            .boards
+            .iter()
+            .map(|b| b.board.clone())
+            .collect();
+
+        let skills = crate::skills::load_skills_with_config(&config.workspace_dir, &config);
+        let mut tool_descs: Vec<(&str, &str)> = vec![
+            ("shell", "Execute terminal commands."),
+            ("file_read", "Read file contents."),
+            ("file_write", "Write file contents."),
+            ("memory_store", "Save to memory."),
+            ("memory_recall", "Search memory."),
+            ("memory_forget", "Delete a memory entry."),
+            (
+                "model_routing_config",
+                "Configure default model, scenario routing, and delegate agents.",
+            ),
+            ("screenshot", "Capture a screenshot."),
+            ("image_info", "Read image metadata."),
+        ];
+        if config.browser.enabled {
+            tool_descs.push(("browser_open", "Open approved URLs in browser."));
+        }
+        if config.composio.enabled {
+            tool_descs.push(("composio", "Execute actions on 1000+ apps via Composio."));
+        }
+        if config.peripherals.enabled && !config.peripherals.boards.is_empty() {
+            tool_descs.push(("gpio_read", "Read GPIO pin value on connected hardware."));
+            tool_descs.push((
+                "gpio_write",
+                "Set GPIO pin high or low on connected hardware.",
+            ));
+            tool_descs.push((
+                "arduino_upload",
+                "Upload Arduino sketch. Use for 'make a heart', custom patterns. You write full .ino code; ZeroClaw uploads it.",
+            ));
+            tool_descs.push((
+                "hardware_memory_map",
+                "Return flash and RAM address ranges. Use when user asks for memory addresses or memory map.",
+            ));
+            tool_descs.push((
+                "hardware_board_info",
+                "Return full board info (chip, architecture, memory map). Use when user asks for board info, what board, connected hardware, or chip info.",
+            ));
+            tool_descs.push((
+                "hardware_memory_read",
+                "Read actual memory/register values from Nucleo. Use when user asks to read registers, read memory, dump lower memory 0-126, or give address and value.",
+            ));
+            tool_descs.push((
+                "hardware_capabilities",
+                "Query connected hardware for reported GPIO pins and LED pin. Use when user asks what pins are available.",
+            ));
+        }
+        let bootstrap_max_chars = if config.agent.compact_context {
+            Some(6000)
+        } else {
+            None
+        };
+        let native_tools = provider.supports_native_tools();
+        let mut system_prompt = crate::channels::build_system_prompt_with_mode(
+            &config.workspace_dir,
+            &model_name,
+            &tool_descs,
+            &skills,
+            Some(&config.identity),
+            bootstrap_max_chars,
+            native_tools,
+            config.skills.prompt_injection_mode,
+        );
+        if !native_tools {
+            system_prompt.push_str(&build_tool_instructions(&tools_registry));
+        }
+
+        Ok(Self {
+            config,
+            provider,
+            provider_name,
+            model_name,
+            tools_registry,
+            observer,
+            mem,
+            hardware_rag,
+            board_names,
+            system_prompt,
+            cost,
+        })
+    }
+
+    /// The system prompt new conversations should start with.
+    pub fn system_prompt(&self) -> &str {
+        &self.system_prompt
+    }
+
+    /// Whether the provider takes tool specs natively (see
+    /// [`crate::sessions::history_from_transcript`]).
+    pub fn supports_native_tools(&self) -> bool {
+        self.provider.supports_native_tools()
+    }
+
+    /// Summarize older turns of `history` when it no longer fits the
+    /// model's context window. Returns whether anything was compacted.
+    pub async fn compact(&self, history: &mut Vec<ChatMessage>) -> Result<bool> {
+        let budget =
+            ContextBudget::for_model(&self.model_name, self.config.agent.context_window_tokens);
+        auto_compact_history(history, self.provider.as_ref(), &self.model_name, &budget).await
+    }
+
+    /// Answer `message` after `history`, which must start with the system
+    /// prompt. The context-enriched user message and everything the tool
+    /// loop produces are appended to `history`; older turns may be
+    /// summarized to fit the context window (see
+    /// [`context::current_turn_start`]).
+    ///
+    /// Answer text is streamed through `on_delta` when given; progress
+    /// lines carry [`DRAFT_PROGRESS_PREFIX`]. Cancelling
+    /// `cancellation_token` ends the turn with an error for which
+    /// [`is_tool_loop_cancelled`] holds.
+    pub async fn turn(
+        &self,
+        history: &mut Vec<ChatMessage>,
+        message: &str,
+        approval: Option<&ApprovalManager>,
+        hooks: Option<&crate::hooks::HookRunner>,
+        cancellation_token: Option<CancellationToken>,
+        on_delta: Option<tokio::sync::mpsc::Sender<String>>,
+    ) -> Result<String> {
+        let mem_context = build_context(
+            self.mem.as_ref(),
+            message,
+            self.config.memory.min_relevance_score,
+        )
+        .await;
+        let rag_limit = if self.config.agent.compact_context {
+            2
+        } else {
+            5
+        };
+        let hw_context = self
+            .hardware_rag
+            .as_ref()
+            .map(|r| build_hardware_context(r, message, &self.board_names, rag_limit))
+            .unwrap_or_default();
+        let context = format!("{mem_context}{hw_context}");
+        let enriched = if context.is_empty() {
+            message.to_string()
+        } else {
+            format!("{context}{message}")
+        };
+        history.push(ChatMessage::user(&enriched));
+        let budget =
+            ContextBudget::for_model(&self.model_name, self.config.agent.context_window_tokens);
+
+        run_tool_call_loop(
+            self.provider.as_ref(),
+            history,
+            &self.tools_registry,
+            self.observer.as_ref(),
+            &self.provider_name,
+            &self.model_name,
+            self.config.default_temperature,
+            true,
+            approval,
+            "channel",
+            &self.config.multimodal,
+            self.config.agent.max_tool_iterations,
+            cancellation_token,
+            on_delta,
+            hooks,
+            &[],
+            self.cost.as_ref(),
+            Some(&budget),
+        )
+        .await
+    }
 }
 
 #[cfg(test)]
@@ -3383,6 +3708,7 @@ mod tests {
                 text: Some("vision-ok".to_string()),
                 tool_calls: Vec::new(),
                 usage: None,
+                routed_to: None,
             })
         }
     }
@@ -3400,6 +3726,7 @@ mod tests {
                     text: Some(text.to_string()),
                     tool_calls: Vec::new(),
                     usage: None,
+                    routed_to: None,
                 })
                 .collect();
             Self {
@@ -3496,6 +3823,49 @@ mod tests {
         }
     }
 
+    /// [`CountingTool`] that must be approved on every call.
+    struct GatedTool(CountingTool);
+
+    #[async_trait]
+    impl Tool for GatedTool {
+        fn name(&self) -> &str {
+            self.0.name()
+        }
+
+        fn description(&self) -> &str {
+            self.0.description()
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            self.0.parameters_schema()
+        }
+
+        async fn execute(
+            &self,
+            args: serde_json::Value,
+        ) -> anyhow::Result<crate::tools::ToolResult> {
+            self.0.execute(args).await
+        }
+
+        fn requires_approval(&self) -> bool {
+            true
+        }
+    }
+
+    struct ApproveAll(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl crate::approval::ApprovalTransport for ApproveAll {
+        fn name(&self) -> &str {
+            "approve-all"
+        }
+
+        async fn request(&self, _request: &ApprovalRequest) -> anyhow::Result<ApprovalResponse> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(ApprovalResponse::Yes)
+        }
+    }
+
     struct DelayTool {
         name: String,
         delay_ms: u64,
@@ -3594,6 +3964,8 @@ mod tests {
             None,
             None,
             &[],
+            None,
+            None,
         )
         .await
         .expect_err("provider without vision support should fail");
@@ -3640,6 +4012,8 @@ mod tests {
             None,
             None,
             &[],
+            None,
+            None,
         )
         .await
         .expect_err("oversized payload must fail");
@@ -3680,6 +4054,8 @@ mod tests {
             None,
             None,
             &[],
+            None,
+            None,
         )
         .await
         .expect("valid multimodal payload should pass");
@@ -3688,6 +4064,167 @@ mod tests {
         assert_eq!(calls.load(Ordering::SeqCst), 1);
     }
 
+    #[tokio::test]
+    async fn run_tool_call_loop_refits_history_after_tool_output_grows() {
+        let tool_call = format!(
+            "<tool_call>\n{{\"name\":\"count_tool\",\"arguments\":{{\"value\":\"{}\"}}}}\n</tool_call>",
+            "v".repeat(6_000)
+        );
+        let provider = ScriptedProvider::from_text_responses(vec![&tool_call, "done"]);
+        let invocations = Arc::new(AtomicUsize::new(0));
+        let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(CountingTool::new(
+            "count_tool",
+            Arc::clone(&invocations),
+        ))];
+
+        let mut history = vec![ChatMessage::system("sys")];
+        for i in 0..6 {
+            history.push(ChatMessage::user(format!(
+                "question {i} {}",
+                "q".repeat(1_200)
+            )));
+            history.push(ChatMessage::assistant(format!("answer {i}")));
+        }
+        history.push(ChatMessage::user("latest"));
+        let budget = ContextBudget {
+            window_tokens: 4_000,
+            reserve_tokens: 0,
+        };
+        assert!(budget.plan(&history).is_noop());
+
+        let result = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &tools_registry,
+            &NoopObserver,
+            "mock-provider",
+            "mock-model",
+            0.0,
+            true,
+            None,
+            "cli",
+            &crate::config::MultimodalConfig::default(),
+            4,
+            None,
+            None,
+            None,
+            &[],
+            None,
+            Some(&budget),
+        )
+        .await
+        .expect("loop should complete");
+
+        assert_eq!(result, "done");
+        assert_eq!(invocations.load(Ordering::SeqCst), 1);
+        assert!(history[1].content.starts_with("[Compaction summary]"));
+        let turn_start = context::current_turn_start(&history);
+        assert_eq!(history[turn_start].content, "latest");
+        assert_eq!(history.last().unwrap().content, "done");
+    }
+
+    #[tokio::test]
+    async fn run_tool_call_loop_bills_the_auto_routed_model() {
+        use crate::config::schema::{AutoRoutingConfig, CostConfig, ModelPricing};
+        use crate::providers::router::{AutoRouting, Route, RouterProvider};
+
+        fn scripted_with_usage(text: &str) -> ScriptedProvider {
+            ScriptedProvider {
+                responses: Arc::new(Mutex::new(VecDeque::from([ChatResponse {
+                    text: Some(text.to_string()),
+                    tool_calls: Vec::new(),
+                    usage: Some(crate::providers::traits::TokenUsage {
+                        input_tokens: Some(1_000),
+                        output_tokens: Some(100),
+                        ..Default::default()
+                    }),
+                    routed_to: None,
+                }]))),
+                capabilities: ProviderCapabilities::default(),
+            }
+        }
+        let priced = |input: f64, output: f64| ModelPricing {
+            input,
+            output,
+            cache_read: None,
+            cache_write: None,
+        };
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let tracker = Arc::new(
+            crate::cost::CostTracker::new(
+                CostConfig {
+                    enabled: true,
+                    prices: std::collections::HashMap::from([
+                        ("premium-model".to_string(), priced(15.0, 75.0)),
+                        ("cheap-model".to_string(), priced(0.1, 0.4)),
+                    ]),
+                    ..CostConfig::default()
+                },
+                tmp.path(),
+            )
+            .unwrap(),
+        );
+        let router = RouterProvider::new(
+            vec![
+                (
+                    "premium".to_string(),
+                    Box::new(scripted_with_usage("premium answer")) as Box<dyn Provider>,
+                ),
+                (
+                    "budget".to_string(),
+                    Box::new(scripted_with_usage("cheap answer")) as Box<dyn Provider>,
+                ),
+            ],
+            vec![(
+                "fast".to_string(),
+                Route {
+                    provider_name: "budget".to_string(),
+                    model: "cheap-model".to_string(),
+                },
+            )],
+            "premium-model".to_string(),
+        )
+        .with_auto_routing(AutoRouting {
+            config: AutoRoutingConfig {
+                enabled: true,
+                ..AutoRoutingConfig::default()
+            },
+            classification: crate::config::QueryClassificationConfig::default(),
+            tracker: Some(Arc::clone(&tracker)),
+        });
+        let cost = CostContext::new(Arc::clone(&tracker), CostAttribution::default());
+
+        let mut history = vec![ChatMessage::system("sys"), ChatMessage::user("hi")];
+        let result = run_tool_call_loop(
+            &router,
+            &mut history,
+            &[],
+            &NoopObserver,
+            "premium",
+            "premium-model",
+            0.0,
+            true,
+            None,
+            "cli",
+            &crate::config::MultimodalConfig::default(),
+            4,
+            None,
+            None,
+            None,
+            &[],
+            Some(&cost),
+            None,
+        )
+        .await
+        .expect("loop should complete");
+
+        assert_eq!(result, "cheap answer");
+        let by_model = tracker.get_summary().unwrap().by_model;
+        assert!(by_model.contains_key("cheap-model"), "{by_model:?}");
+        assert!(!by_model.contains_key("premium-model"));
+    }
+
     #[test]
     fn should_execute_tools_in_parallel_returns_false_for_single_call() {
         let calls = vec![ParsedToolCall {
@@ -3806,6 +4343,8 @@ mod tests {
             None,
             None,
             &[],
+            None,
+            None,
         )
         .await
         .expect("parallel execution should complete");
@@ -3834,6 +4373,74 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn run_tool_call_loop_refuses_gated_tools_without_an_approval_prompt() {
+        let full_autonomy = crate::config::AutonomyConfig {
+            level: crate::security::AutonomyLevel::Full,
+            ..crate::config::AutonomyConfig::default()
+        };
+        let no_transport = ApprovalManager::from_config(&full_autonomy);
+        let prompts = Arc::new(AtomicUsize::new(0));
+        let with_transport = ApprovalManager::from_config(&full_autonomy)
+            .with_transport(Arc::new(ApproveAll(Arc::clone(&prompts))));
+
+        // Channels and the gateway run without a manager unless remote
+        // approvals are on; a manager without a transport approves silently.
+        for (approval, expected_runs) in [
+            (None, 0),
+            (Some(&no_transport), 0),
+            (Some(&with_transport), 1),
+        ] {
+            let provider = ScriptedProvider::from_text_responses(vec![
+                r#"<tool_call>
+{"name":"gated_tool","arguments":{"value":"A"}}
+</tool_call>"#,
+                "done",
+            ]);
+            let invocations = Arc::new(AtomicUsize::new(0));
+            let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(GatedTool(CountingTool::new(
+                "gated_tool",
+                Arc::clone(&invocations),
+            )))];
+            let mut history = vec![
+                ChatMessage::system("test-system"),
+                ChatMessage::user("run tool calls"),
+            ];
+
+            let result = run_tool_call_loop(
+                &provider,
+                &mut history,
+                &tools_registry,
+                &NoopObserver,
+                "mock-provider",
+                "mock-model",
+                0.0,
+                true,
+                approval,
+                "telegram",
+                &crate::config::MultimodalConfig::default(),
+                4,
+                None,
+                None,
+                None,
+                &[],
+                None,
+                None,
+            )
+            .await
+            .expect("loop should complete");
+
+            assert_eq!(result, "done");
+            assert_eq!(invocations.load(Ordering::SeqCst), expected_runs);
+            if expected_runs == 0 {
+                assert!(history.iter().any(|msg| msg
+                    .content
+                    .contains("requires approval, but no approval prompt is available")));
+            }
+        }
+        assert_eq!(prompts.load(Ordering::SeqCst), 1);
+    }
+
     #[tokio::test]
     async fn run_tool_call_loop_deduplicates_repeated_tool_calls() {
         let provider = ScriptedProvider::from_text_responses(vec![
@@ -3875,6 +4482,8 @@ mod tests {
             None,
             None,
             &[],
+            None,
+            None,
         )
         .await
         .expect("loop should finish after deduplicating repeated calls");
@@ -3931,6 +4540,8 @@ mod tests {
             None,
             None,
             &[],
+            None,
+            None,
         )
         .await
         .expect("native fallback id flow should complete");
@@ -4404,41 +5015,6 @@ Tail"#;
         assert!(names.contains(&"file_read"));
     }
 
-    #[test]
-    fn trim_history_preserves_system_prompt() {
-        let mut history = vec![ChatMessage::system("system prompt")];
-        for i in 0..DEFAULT_MAX_HISTORY_MESSAGES + 20 {
-            history.push(ChatMessage::user(format!("msg {i}")));
-        }
-        let original_len = history.len();
-        assert!(original_len > DEFAULT_MAX_HISTORY_MESSAGES + 1);
-
-        trim_history(&mut history, DEFAULT_MAX_HISTORY_MESSAGES);
-
-        // System prompt preserved
-        assert_eq!(history[0].role, "system");
-        assert_eq!(history[0].content, "system prompt");
-        // Trimmed to limit
-        assert_eq!(history.len(), DEFAULT_MAX_HISTORY_MESSAGES + 1); // +1 for system
-                                                                     // Most recent messages preserved
-        let last = &history[history.len() - 1];
-        assert_eq!(
-            last.content,
-            format!("msg {}", DEFAULT_MAX_HISTORY_MESSAGES + 19)
-        );
-    }
-
-    #[test]
-    fn trim_history_noop_when_within_limit() {
-        let mut history = vec![
-            ChatMessage::system("sys"),
-            ChatMessage::user("hello"),
-            ChatMessage::assistant("hi"),
-        ];
-        trim_history(&mut history, DEFAULT_MAX_HISTORY_MESSAGES);
-        assert_eq!(history.len(), 3);
-    }
-
     #[test]
     fn build_compaction_transcript_formats_roles() {
         let messages = vec![
@@ -4468,6 +5044,56 @@ Tail"#;
         assert!(history[3].content.contains("recent 2"));
     }
 
+    #[tokio::test]
+    async fn auto_compact_history_summarizes_turns_over_token_budget() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = NonVisionProvider {
+            calls: Arc::clone(&calls),
+        };
+        let mut history = vec![ChatMessage::system("sys")];
+        for i in 0..12 {
+            history.push(ChatMessage::user(format!(
+                "question {i} {}",
+                "q".repeat(800)
+            )));
+            history.push(ChatMessage::assistant(format!("answer {i}")));
+        }
+        history.push(ChatMessage::user("latest"));
+        let budget = ContextBudget {
+            window_tokens: 2_000,
+            reserve_tokens: 0,
+        };
+
+        let compacted = auto_compact_history(&mut history, &provider, "test-model", &budget)
+            .await
+            .unwrap();
+
+        assert!(compacted);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(history[0].role, "system");
+        assert!(history[1].content.starts_with("[Compaction summary]"));
+        assert_eq!(history.last().unwrap().content, "latest");
+        assert!(context::estimate_history_tokens(&history) <= budget.input_tokens());
+    }
+
+    #[tokio::test]
+    async fn auto_compact_history_noop_within_budget() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = NonVisionProvider {
+            calls: Arc::clone(&calls),
+        };
+        let mut history = vec![ChatMessage::system("sys"), ChatMessage::user("hi")];
+        let budget = ContextBudget::for_model("claude-sonnet-4-5", None);
+
+        let compacted = auto_compact_history(&mut history, &provider, "claude-sonnet-4-5", &budget)
+            .await
+            .unwrap();
+
+        assert!(!compacted);
+        assert_eq!(history.len(), 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
     #[test]
     fn autosave_memory_key_has_prefix_and_uniqueness() {
         let key1 = autosave_memory_key("user_msg");
@@ -4599,38 +5225,6 @@ Done."#;
     // Recovery Tests - History Management
     // ═══════════════════════════════════════════════════════════════════════
 
-    #[test]
-    fn trim_history_with_no_system_prompt() {
-        // Recovery: History without system prompt should trim correctly
-        let mut history = vec![];
-        for i in 0..DEFAULT_MAX_HISTORY_MESSAGES + 20 {
-            history.push(ChatMessage::user(format!("msg {i}")));
-        }
-        trim_history(&mut history, DEFAULT_MAX_HISTORY_MESSAGES);
-        assert_eq!(history.len(), DEFAULT_MAX_HISTORY_MESSAGES);
-    }
-
-    #[test]
-    fn trim_history_preserves_role_ordering() {
-        // Recovery: After trimming, role ordering should remain consistent
-        let mut history = vec![ChatMessage::system("system")];
-        for i in 0..DEFAULT_MAX_HISTORY_MESSAGES + 10 {
-            history.push(ChatMessage::user(format!("user {i}")));
-            history.push(ChatMessage::assistant(format!("assistant {i}")));
-        }
-        trim_history(&mut history, DEFAULT_MAX_HISTORY_MESSAGES);
-        assert_eq!(history[0].role, "system");
-        assert_eq!(history[history.len() - 1].role, "assistant");
-    }
-
-    #[test]
-    fn trim_history_with_only_system_prompt() {
-        // Recovery: Only system prompt should not be trimmed
-        let mut history = vec![ChatMessage::system("system prompt")];
-        trim_history(&mut history, DEFAULT_MAX_HISTORY_MESSAGES);
-        assert_eq!(history.len(), 1);
-    }
-
     // ═══════════════════════════════════════════════════════════════════════
     // Recovery Tests - Arguments Parsing
     // ═══════════════════════════════════════════════════════════════════════
@@ -4693,8 +5287,6 @@ Done."#;
     const _: () = {
         assert!(DEFAULT_MAX_TOOL_ITERATIONS > 0);
         assert!(DEFAULT_MAX_TOOL_ITERATIONS <= 100);
-        assert!(DEFAULT_MAX_HISTORY_MESSAGES > 0);
-        assert!(DEFAULT_MAX_HISTORY_MESSAGES <= 1000);
     };
 
     #[test]
@@ -5029,51 +5621,6 @@ Let me check the result."#;
         assert_eq!(result, input, "short values should not be redacted");
     }
 
-    // ─────────────────────────────────────────────────────────────────────
-    // TG4 (inline): trim_history edge cases
-    // ─────────────────────────────────────────────────────────────────────
-
-    #[test]
-    fn trim_history_empty_history() {
-        let mut history: Vec<crate::providers::ChatMessage> = vec![];
-        trim_history(&mut history, 10);
-        assert!(history.is_empty());
-    }
-
-    #[test]
-    fn trim_history_system_only() {
-        let mut history = vec![crate::providers::ChatMessage::system("system prompt")];
-        trim_history(&mut history, 10);
-        assert_eq!(history.len(), 1);
-        assert_eq!(history[0].role, "system");
-    }
-
-    #[test]
-    fn trim_history_exactly_at_limit() {
-        let mut history = vec![
-            crate::providers::ChatMessage::system("system"),
-            crate::providers::ChatMessage::user("msg 1"),
-            crate::providers::ChatMessage::assistant("reply 1"),
-        ];
-        trim_history(&mut history, 2); // 2 non-system messages = exactly at limit
-        assert_eq!(history.len(), 3, "should not trim when exactly at limit");
-    }
-
-    #[test]
-    fn trim_history_removes_oldest_non_system() {
-        let mut history = vec![
-            crate::providers::ChatMessage::system("system"),
-            crate::providers::ChatMessage::user("old msg"),
-            crate::providers::ChatMessage::assistant("old reply"),
-            crate::providers::ChatMessage::user("new msg"),
-            crate::providers::ChatMessage::assistant("new reply"),
-        ];
-        trim_history(&mut history, 2);
-        assert_eq!(history.len(), 3); // system + 2 kept
-        assert_eq!(history[0].role, "system");
-        assert_eq!(history[1].content, "new msg");
-    }
-
     /// When `build_system_prompt_with_mode` is called with `native_tools = true`,
     /// the output must contain ZERO XML protocol artifacts. In the native path
     /// `build_tool_instructions` is never called, so the system prompt alone
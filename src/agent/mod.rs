@@ -1,6 +1,7 @@
 #[allow(clippy::module_inception)]
 pub mod agent;
 pub mod classifier;
+pub mod context;
 pub mod dispatcher;
 pub mod loop_;
 pub mod memory_loader;
@@ -12,4 +13,6 @@ mod tests;
 #[allow(unused_imports)]
 pub use agent::{Agent, AgentBuilder};
 #[allow(unused_imports)]
-pub use loop_::{process_message, run};
+pub use loop_::{
+    process_conversation, process_message, process_message_with_approval, run, ConversationAgent,
+};
@@ -350,8 +350,11 @@ mod tests {
                 kind: "shell".into(),
                 command: "echo ok".into(),
                 args: std::collections::HashMap::new(),
+                parameters: Vec::new(),
+                method: None,
             }],
             prompts: vec!["Run smoke tests before deploy.".into()],
+            permissions: crate::skills::SkillPermissions::default(),
             location: None,
         }];
 
@@ -388,8 +391,11 @@ mod tests {
                 kind: "shell".into(),
                 command: "echo ok".into(),
                 args: std::collections::HashMap::new(),
+                parameters: Vec::new(),
+                method: None,
             }],
             prompts: vec!["Run smoke tests before deploy.".into()],
+            permissions: crate::skills::SkillPermissions::default(),
             location: Some(Path::new("/tmp/workspace/skills/deploy/SKILL.md").to_path_buf()),
         }];
 
@@ -448,8 +454,11 @@ mod tests {
                 kind: "shell&exec".into(),
                 command: "cargo clippy".into(),
                 args: std::collections::HashMap::new(),
+                parameters: Vec::new(),
+                method: None,
             }],
             prompts: vec!["Use <tool_call> and & keep output \"safe\"".into()],
+            permissions: crate::skills::SkillPermissions::default(),
             location: None,
         }];
         let ctx = PromptContext {
@@ -94,6 +94,7 @@ impl Provider for ScriptedProvider {
                 text: Some("done".into()),
                 tool_calls: vec![],
                 usage: None,
+                routed_to: None,
             });
         }
         Ok(guard.remove(0))
@@ -330,6 +331,7 @@ fn tool_response(calls: Vec<ToolCall>) -> ChatResponse {
         text: Some(String::new()),
         tool_calls: calls,
         usage: None,
+        routed_to: None,
     }
 }
 
@@ -339,6 +341,7 @@ fn text_response(text: &str) -> ChatResponse {
         text: Some(text.into()),
         tool_calls: vec![],
         usage: None,
+        routed_to: None,
     }
 }
 
@@ -350,6 +353,7 @@ fn xml_tool_response(name: &str, args: &str) -> ChatResponse {
         )),
         tool_calls: vec![],
         usage: None,
+        routed_to: None,
     }
 }
 
@@ -739,6 +743,7 @@ async fn turn_handles_empty_text_response() {
         text: Some(String::new()),
         tool_calls: vec![],
         usage: None,
+        routed_to: None,
     }]));
 
     let mut agent = build_agent_with(provider, vec![], Box::new(NativeToolDispatcher));
@@ -753,6 +758,7 @@ async fn turn_handles_none_text_response() {
         text: None,
         tool_calls: vec![],
         usage: None,
+        routed_to: None,
     }]));
 
     let mut agent = build_agent_with(provider, vec![], Box::new(NativeToolDispatcher));
@@ -777,6 +783,7 @@ async fn turn_preserves_text_alongside_tool_calls() {
                 arguments: r#"{"message": "hi"}"#.into(),
             }],
             usage: None,
+            routed_to: None,
         },
         text_response("Here are the results"),
     ]));
@@ -1014,6 +1021,7 @@ async fn native_dispatcher_handles_stringified_arguments() {
             arguments: r#"{"message": "hello"}"#.into(),
         }],
         usage: None,
+        routed_to: None,
     };
 
     let (_, calls) = dispatcher.parse_response(&response);
@@ -1040,6 +1048,7 @@ fn xml_dispatcher_handles_nested_json() {
         ),
         tool_calls: vec![],
         usage: None,
+        routed_to: None,
     };
 
     let dispatcher = XmlToolDispatcher;
@@ -1058,6 +1067,7 @@ fn xml_dispatcher_handles_empty_tool_call_tag() {
         text: Some("<tool_call>\n</tool_call>\nSome text".into()),
         tool_calls: vec![],
         usage: None,
+        routed_to: None,
     };
 
     let dispatcher = XmlToolDispatcher;
@@ -1072,6 +1082,7 @@ fn xml_dispatcher_handles_unclosed_tool_call() {
         text: Some("Before\n<tool_call>\n{\"name\": \"shell\"}".into()),
         tool_calls: vec![],
         usage: None,
+        routed_to: None,
     };
 
     let dispatcher = XmlToolDispatcher;
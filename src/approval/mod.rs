@@ -1,15 +1,25 @@
 //! Interactive approval workflow for supervised mode.
 //!
 //! Provides a pre-execution hook that prompts the user before tool calls,
-//! with session-scoped "Always" allowlists and audit logging.
+//! with session-scoped "Always" allowlists and audit logging. Prompts go to
+//! the terminal by default, or through an [`ApprovalTransport`] that reaches
+//! a chat channel or gateway client.
+
+mod remote;
+
+pub use remote::{BroadcastApprovalTransport, ChannelApprovalTransport, PendingApprovals};
 
 use crate::config::AutonomyConfig;
-use crate::security::AutonomyLevel;
+use crate::security::audit::ApprovalAuditLog;
+use crate::security::{AuditLogger, AutonomyLevel};
+use async_trait::async_trait;
 use chrono::Utc;
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::io::{self, BufRead, Write};
+use std::sync::Arc;
+use std::time::Duration;
 
 // ── Types ────────────────────────────────────────────────────────
 
@@ -42,6 +52,17 @@ pub struct ApprovalLogEntry {
     pub channel: String,
 }
 
+/// Delivers approval requests to a remote approver.
+#[async_trait]
+pub trait ApprovalTransport: Send + Sync {
+    /// Transport name used in logs.
+    fn name(&self) -> &str;
+
+    /// Send `request` and wait for the approver's decision. The manager
+    /// bounds the wait with its timeout.
+    async fn request(&self, request: &ApprovalRequest) -> anyhow::Result<ApprovalResponse>;
+}
+
 // ── ApprovalManager ──────────────────────────────────────────────
 
 /// Manages the interactive approval workflow.
@@ -57,9 +78,15 @@ pub struct ApprovalManager {
     /// Autonomy level from config.
     autonomy_level: AutonomyLevel,
     /// Session-scoped allowlist built from "Always" responses.
-    session_allowlist: Mutex<HashSet<String>>,
+    session_allowlist: Arc<Mutex<HashSet<String>>>,
     /// Audit trail of approval decisions.
-    audit_log: Mutex<Vec<ApprovalLogEntry>>,
+    audit_log: Arc<Mutex<Vec<ApprovalLogEntry>>>,
+    /// Remote transport; `None` prompts on the terminal.
+    transport: Option<Arc<dyn ApprovalTransport>>,
+    /// How long a remote approval may stay unanswered before it is denied.
+    timeout: Duration,
+    /// Persistent security audit log for decisions.
+    audit: Option<Arc<AuditLogger>>,
 }
 
 impl ApprovalManager {
@@ -69,11 +96,45 @@ impl ApprovalManager {
             auto_approve: config.auto_approve.iter().cloned().collect(),
             always_ask: config.always_ask.iter().cloned().collect(),
             autonomy_level: config.level,
-            session_allowlist: Mutex::new(HashSet::new()),
-            audit_log: Mutex::new(Vec::new()),
+            session_allowlist: Arc::new(Mutex::new(HashSet::new())),
+            audit_log: Arc::new(Mutex::new(Vec::new())),
+            transport: None,
+            timeout: Duration::from_secs(config.approval_timeout_secs),
+            audit: None,
         }
     }
 
+    /// Route prompts through `transport` instead of the terminal.
+    #[must_use]
+    pub fn with_transport(mut self, transport: Arc<dyn ApprovalTransport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// A manager that prompts through `transport` but shares this one's
+    /// policy, "Always" allowlist and decision log. Long-lived runtimes keep
+    /// one manager and derive a routed view per conversation.
+    #[must_use]
+    pub fn route_through(&self, transport: Arc<dyn ApprovalTransport>) -> Self {
+        Self {
+            auto_approve: self.auto_approve.clone(),
+            always_ask: self.always_ask.clone(),
+            autonomy_level: self.autonomy_level,
+            session_allowlist: Arc::clone(&self.session_allowlist),
+            audit_log: Arc::clone(&self.audit_log),
+            transport: Some(transport),
+            timeout: self.timeout,
+            audit: self.audit.clone(),
+        }
+    }
+
+    /// Also record decisions in the security audit log.
+    #[must_use]
+    pub fn with_audit(mut self, audit: Option<Arc<AuditLogger>>) -> Self {
+        self.audit = audit;
+        self
+    }
+
     /// Check whether a tool call requires interactive approval.
     ///
     /// Returns `true` if the call needs a prompt, `false` if it can proceed.
@@ -108,6 +169,12 @@ impl ApprovalManager {
         true
     }
 
+    /// Whether [`Self::request_approval`] actually asks someone on `channel`,
+    /// rather than approving by default.
+    pub fn can_prompt(&self, channel: &str) -> bool {
+        self.transport.is_some() || channel == "cli"
+    }
+
     /// Record an approval decision and update session state.
     pub fn record_decision(
         &self,
@@ -124,6 +191,21 @@ impl ApprovalManager {
 
         // Append to audit log.
         let summary = summarize_args(args);
+        if let Some(audit) = &self.audit {
+            let logged = audit.log_approval(ApprovalAuditLog {
+                channel,
+                tool_name,
+                arguments_summary: &summary,
+                decision: match decision {
+                    ApprovalResponse::Yes => "yes",
+                    ApprovalResponse::No => "no",
+                    ApprovalResponse::Always => "always",
+                },
+            });
+            if let Err(e) = logged {
+                tracing::warn!("Failed to write approval audit event: {e}");
+            }
+        }
         let entry = ApprovalLogEntry {
             timestamp: Utc::now().to_rfc3339(),
             tool_name: tool_name.to_string(),
@@ -146,12 +228,49 @@ impl ApprovalManager {
     }
 
     /// Prompt the user on the CLI and return their decision.
-    ///
-    /// For non-CLI channels, returns `Yes` automatically (interactive
-    /// approval is only supported on CLI for now).
     pub fn prompt_cli(&self, request: &ApprovalRequest) -> ApprovalResponse {
         prompt_cli_interactive(request)
     }
+
+    /// Ask for a decision on `request`.
+    ///
+    /// With a transport, waits up to the configured timeout and denies when
+    /// the approver does not answer or the transport fails. Without one,
+    /// prompts on the terminal for CLI and approves on other channels.
+    pub async fn request_approval(
+        &self,
+        request: &ApprovalRequest,
+        channel: &str,
+    ) -> ApprovalResponse {
+        let Some(transport) = self.transport.as_ref() else {
+            return if channel == "cli" {
+                self.prompt_cli(request)
+            } else {
+                ApprovalResponse::Yes
+            };
+        };
+
+        match tokio::time::timeout(self.timeout, transport.request(request)).await {
+            Ok(Ok(decision)) => decision,
+            Ok(Err(e)) => {
+                tracing::warn!(
+                    transport = transport.name(),
+                    tool = %request.tool_name,
+                    "Approval request failed; denying: {e}"
+                );
+                ApprovalResponse::No
+            }
+            Err(_) => {
+                tracing::warn!(
+                    transport = transport.name(),
+                    tool = %request.tool_name,
+                    timeout_secs = self.timeout.as_secs(),
+                    "Approval request timed out; denying"
+                );
+                ApprovalResponse::No
+            }
+        }
+    }
 }
 
 // ── CLI prompt ───────────────────────────────────────────────────
@@ -350,6 +469,30 @@ mod tests {
         assert_eq!(log[1].decision, ApprovalResponse::Yes);
     }
 
+    #[test]
+    fn decisions_are_written_to_security_audit_log() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let audit = AuditLogger::new(
+            crate::config::AuditConfig::default(),
+            tmp.path().to_path_buf(),
+        )
+        .unwrap();
+        let mgr =
+            ApprovalManager::from_config(&supervised_config()).with_audit(Some(Arc::new(audit)));
+
+        mgr.record_decision(
+            "shell",
+            &serde_json::json!({"command": "rm -rf ./build/"}),
+            ApprovalResponse::No,
+            "telegram",
+        );
+
+        let log = std::fs::read_to_string(tmp.path().join("audit.log")).unwrap();
+        assert!(log.contains("approval_decision"));
+        assert!(log.contains("telegram"));
+        assert!(log.contains("rm -rf ./build/"));
+    }
+
     #[test]
     fn audit_log_contains_timestamp_and_channel() {
         let mgr = ApprovalManager::from_config(&supervised_config());
@@ -366,6 +509,95 @@ mod tests {
         assert_eq!(log[0].channel, "telegram");
     }
 
+    // ── remote transport ─────────────────────────────────────
+
+    struct FixedTransport(Option<ApprovalResponse>);
+
+    #[async_trait]
+    impl ApprovalTransport for FixedTransport {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        async fn request(&self, _request: &ApprovalRequest) -> anyhow::Result<ApprovalResponse> {
+            match self.0 {
+                Some(decision) => Ok(decision),
+                None => anyhow::bail!("transport unavailable"),
+            }
+        }
+    }
+
+    struct SilentTransport;
+
+    #[async_trait]
+    impl ApprovalTransport for SilentTransport {
+        fn name(&self) -> &str {
+            "silent"
+        }
+
+        async fn request(&self, _request: &ApprovalRequest) -> anyhow::Result<ApprovalResponse> {
+            std::future::pending().await
+        }
+    }
+
+    fn shell_request() -> ApprovalRequest {
+        ApprovalRequest {
+            tool_name: "shell".into(),
+            arguments: serde_json::json!({"command": "ls"}),
+        }
+    }
+
+    #[tokio::test]
+    async fn transport_decision_is_returned() {
+        let mgr = ApprovalManager::from_config(&supervised_config())
+            .with_transport(Arc::new(FixedTransport(Some(ApprovalResponse::Always))));
+        let decision = mgr.request_approval(&shell_request(), "telegram").await;
+        assert_eq!(decision, ApprovalResponse::Always);
+    }
+
+    #[tokio::test]
+    async fn transport_failure_denies() {
+        let mgr = ApprovalManager::from_config(&supervised_config())
+            .with_transport(Arc::new(FixedTransport(None)));
+        let decision = mgr.request_approval(&shell_request(), "telegram").await;
+        assert_eq!(decision, ApprovalResponse::No);
+    }
+
+    #[tokio::test]
+    async fn unanswered_request_times_out_as_denied() {
+        let config = AutonomyConfig {
+            approval_timeout_secs: 0,
+            ..supervised_config()
+        };
+        let mgr = ApprovalManager::from_config(&config).with_transport(Arc::new(SilentTransport));
+        let decision = mgr.request_approval(&shell_request(), "slack").await;
+        assert_eq!(decision, ApprovalResponse::No);
+    }
+
+    #[test]
+    fn routed_managers_share_allowlist_and_log() {
+        let runtime = ApprovalManager::from_config(&supervised_config());
+        let first = runtime.route_through(Arc::new(FixedTransport(Some(ApprovalResponse::Always))));
+        first.record_decision(
+            "file_write",
+            &serde_json::json!({}),
+            ApprovalResponse::Always,
+            "telegram",
+        );
+
+        let second = runtime.route_through(Arc::new(FixedTransport(None)));
+        assert!(!second.needs_approval("file_write"));
+        assert!(second.needs_approval("shell"));
+        assert_eq!(runtime.audit_log().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn non_cli_without_transport_keeps_auto_approve() {
+        let mgr = ApprovalManager::from_config(&supervised_config());
+        let decision = mgr.request_approval(&shell_request(), "discord").await;
+        assert_eq!(decision, ApprovalResponse::Yes);
+    }
+
     // ── summarize_args ───────────────────────────────────────
 
     #[test]
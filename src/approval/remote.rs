@@ -0,0 +1,354 @@
+//! Remote approval transports.
+//!
+//! Pending requests live in a shared [`PendingApprovals`] registry keyed by a
+//! short id and a conversation scope. Transports publish a request and wait
+//! on its ticket; channel replies, gateway API calls and WebSocket frames
+//! resolve it. Dropping a ticket (e.g. on timeout) removes the entry.
+
+use super::{summarize_args, ApprovalRequest, ApprovalResponse, ApprovalTransport};
+use crate::channels::traits::{Channel, SendMessage};
+use async_trait::async_trait;
+use chrono::Utc;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{broadcast, oneshot};
+use uuid::Uuid;
+
+struct PendingEntry {
+    scope: String,
+    tool_name: String,
+    arguments_summary: String,
+    created_at: String,
+    responder: oneshot::Sender<ApprovalResponse>,
+}
+
+/// A pending approval as shown to gateway clients.
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingApprovalInfo {
+    pub id: String,
+    pub scope: String,
+    pub tool_name: String,
+    pub arguments_summary: String,
+    pub created_at: String,
+}
+
+/// Registry of approval requests waiting for a decision.
+#[derive(Default)]
+pub struct PendingApprovals {
+    entries: Mutex<HashMap<String, PendingEntry>>,
+}
+
+/// Handle for one pending request. Await [`ApprovalTicket::wait`] for the
+/// decision; dropping the ticket withdraws the request.
+pub struct ApprovalTicket {
+    id: String,
+    rx: Option<oneshot::Receiver<ApprovalResponse>>,
+    registry: Arc<PendingApprovals>,
+}
+
+impl ApprovalTicket {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub async fn wait(mut self) -> anyhow::Result<ApprovalResponse> {
+        let rx = self.rx.take().expect("approval ticket awaited once");
+        rx.await
+            .map_err(|_| anyhow::anyhow!("approval request {} was withdrawn", self.id))
+    }
+}
+
+impl Drop for ApprovalTicket {
+    fn drop(&mut self) {
+        self.registry.entries.lock().remove(&self.id);
+    }
+}
+
+impl PendingApprovals {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `request` under `scope` and return its ticket.
+    pub fn register(self: &Arc<Self>, scope: &str, request: &ApprovalRequest) -> ApprovalTicket {
+        let id = Uuid::new_v4().simple().to_string()[..8].to_string();
+        let (tx, rx) = oneshot::channel();
+        self.entries.lock().insert(
+            id.clone(),
+            PendingEntry {
+                scope: scope.to_string(),
+                tool_name: request.tool_name.clone(),
+                arguments_summary: summarize_args(&request.arguments),
+                created_at: Utc::now().to_rfc3339(),
+                responder: tx,
+            },
+        );
+        ApprovalTicket {
+            id,
+            rx: Some(rx),
+            registry: Arc::clone(self),
+        }
+    }
+
+    /// Resolve the request `id`. Returns `false` when it is not pending.
+    pub fn resolve(&self, id: &str, decision: ApprovalResponse) -> bool {
+        let Some(entry) = self.entries.lock().remove(id) else {
+            return false;
+        };
+        entry.responder.send(decision).is_ok()
+    }
+
+    /// Resolve a request from a chat reply such as `approve 1a2b3c4d`,
+    /// `deny 1a2b3c4d` or `always 1a2b3c4d`. The reply must name a request
+    /// pending in `scope`, so ordinary "yes"/"no" messages are never taken
+    /// as decisions. Returns `false` when `text` is not such a reply.
+    pub fn resolve_reply(&self, scope: &str, text: &str) -> bool {
+        let Some((decision, id)) = parse_approval_reply(text) else {
+            return false;
+        };
+        self.is_pending_in(scope, id) && self.resolve(id, decision)
+    }
+
+    /// Whether `id` is waiting for a decision and was raised under `scope`.
+    pub fn is_pending_in(&self, scope: &str, id: &str) -> bool {
+        self.entries
+            .lock()
+            .get(id)
+            .is_some_and(|entry| entry.scope == scope)
+    }
+
+    /// Snapshot of pending requests, oldest first.
+    pub fn list(&self) -> Vec<PendingApprovalInfo> {
+        let mut pending: Vec<PendingApprovalInfo> = self
+            .entries
+            .lock()
+            .iter()
+            .map(|(id, entry)| PendingApprovalInfo {
+                id: id.clone(),
+                scope: entry.scope.clone(),
+                tool_name: entry.tool_name.clone(),
+                arguments_summary: entry.arguments_summary.clone(),
+                created_at: entry.created_at.clone(),
+            })
+            .collect();
+        pending.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        pending
+    }
+}
+
+/// Parse a chat reply into a decision and the request id it answers.
+fn parse_approval_reply(text: &str) -> Option<(ApprovalResponse, &str)> {
+    let mut words = text.split_whitespace();
+    let verb = words.next()?.trim_start_matches('/').to_ascii_lowercase();
+    let id = words.next()?;
+    if words.next().is_some() {
+        return None;
+    }
+
+    let decision = match verb.as_str() {
+        "approve" | "yes" | "y" | "✅" => ApprovalResponse::Yes,
+        "deny" | "no" | "n" | "❌" => ApprovalResponse::No,
+        "always" => ApprovalResponse::Always,
+        _ => return None,
+    };
+    Some((decision, id))
+}
+
+fn approval_card(id: &str, request: &ApprovalRequest) -> String {
+    format!(
+        "🔧 Approval needed [{id}]\nTool: {}\nArguments: {}\n\nReply `approve {id}`, `deny {id}` or `always {id}`.",
+        request.tool_name,
+        summarize_args(&request.arguments),
+    )
+}
+
+/// Sends approval cards to the chat the request originated from and waits
+/// for a reply in the same conversation.
+pub struct ChannelApprovalTransport {
+    channel: Arc<dyn Channel>,
+    recipient: String,
+    thread_ts: Option<String>,
+    scope: String,
+    pending: Arc<PendingApprovals>,
+}
+
+impl ChannelApprovalTransport {
+    pub fn new(
+        channel: Arc<dyn Channel>,
+        recipient: impl Into<String>,
+        thread_ts: Option<String>,
+        scope: impl Into<String>,
+        pending: Arc<PendingApprovals>,
+    ) -> Self {
+        Self {
+            channel,
+            recipient: recipient.into(),
+            thread_ts,
+            scope: scope.into(),
+            pending,
+        }
+    }
+}
+
+#[async_trait]
+impl ApprovalTransport for ChannelApprovalTransport {
+    fn name(&self) -> &str {
+        self.channel.name()
+    }
+
+    async fn request(&self, request: &ApprovalRequest) -> anyhow::Result<ApprovalResponse> {
+        let ticket = self.pending.register(&self.scope, request);
+        self.channel
+            .send(
+                &SendMessage::new(approval_card(ticket.id(), request), &self.recipient)
+                    .in_thread(self.thread_ts.clone()),
+            )
+            .await?;
+        ticket.wait().await
+    }
+}
+
+/// Publishes approval requests as gateway events (SSE and WebSocket) and
+/// waits for a client to answer through the approvals API.
+pub struct BroadcastApprovalTransport {
+    events: broadcast::Sender<serde_json::Value>,
+    scope: String,
+    pending: Arc<PendingApprovals>,
+}
+
+impl BroadcastApprovalTransport {
+    pub fn new(
+        events: broadcast::Sender<serde_json::Value>,
+        scope: impl Into<String>,
+        pending: Arc<PendingApprovals>,
+    ) -> Self {
+        Self {
+            events,
+            scope: scope.into(),
+            pending,
+        }
+    }
+}
+
+#[async_trait]
+impl ApprovalTransport for BroadcastApprovalTransport {
+    fn name(&self) -> &str {
+        "gateway"
+    }
+
+    async fn request(&self, request: &ApprovalRequest) -> anyhow::Result<ApprovalResponse> {
+        let ticket = self.pending.register(&self.scope, request);
+        self.events
+            .send(serde_json::json!({
+                "type": "approval_request",
+                "id": ticket.id(),
+                "scope": self.scope,
+                "tool": request.tool_name,
+                "arguments_summary": summarize_args(&request.arguments),
+                "timestamp": Utc::now().to_rfc3339(),
+            }))
+            .map_err(|_| anyhow::anyhow!("no gateway clients are listening for approvals"))?;
+        ticket.wait().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shell_request() -> ApprovalRequest {
+        ApprovalRequest {
+            tool_name: "shell".into(),
+            arguments: serde_json::json!({"command": "ls"}),
+        }
+    }
+
+    #[test]
+    fn parse_reply_requires_verb_and_id() {
+        assert_eq!(
+            parse_approval_reply("approve 1a2b3c4d"),
+            Some((ApprovalResponse::Yes, "1a2b3c4d"))
+        );
+        assert_eq!(
+            parse_approval_reply("/deny 1a2b3c4d"),
+            Some((ApprovalResponse::No, "1a2b3c4d"))
+        );
+        assert_eq!(
+            parse_approval_reply("ALWAYS 1a2b3c4d"),
+            Some((ApprovalResponse::Always, "1a2b3c4d"))
+        );
+        assert_eq!(parse_approval_reply("approve"), None);
+        assert_eq!(parse_approval_reply("yes"), None);
+        assert_eq!(parse_approval_reply("yes please run it"), None);
+        assert_eq!(parse_approval_reply("hello"), None);
+        assert_eq!(parse_approval_reply(""), None);
+    }
+
+    #[tokio::test]
+    async fn resolve_delivers_decision_to_ticket() {
+        let pending = Arc::new(PendingApprovals::new());
+        let ticket = pending.register("telegram_chat_alice", &shell_request());
+        let id = ticket.id().to_string();
+
+        assert_eq!(pending.list().len(), 1);
+        assert!(pending.resolve(&id, ApprovalResponse::Always));
+        assert_eq!(ticket.wait().await.unwrap(), ApprovalResponse::Always);
+        assert!(pending.list().is_empty());
+        assert!(!pending.resolve(&id, ApprovalResponse::Yes));
+    }
+
+    #[tokio::test]
+    async fn resolve_reply_is_scoped_to_conversation() {
+        let pending = Arc::new(PendingApprovals::new());
+        let ticket = pending.register("telegram_chat_alice", &shell_request());
+
+        let id = ticket.id().to_string();
+
+        assert!(!pending.resolve_reply("telegram_chat_mallory", &format!("approve {id}")));
+        assert!(!pending.resolve_reply("telegram_chat_alice", "what is this?"));
+        assert!(!pending.resolve_reply("telegram_chat_alice", "no"));
+        assert!(!pending.resolve_reply("telegram_chat_alice", "deny 00000000"));
+        assert_eq!(pending.list().len(), 1);
+        assert!(pending.resolve_reply("telegram_chat_alice", &format!("deny {id}")));
+        assert_eq!(ticket.wait().await.unwrap(), ApprovalResponse::No);
+    }
+
+    #[test]
+    fn dropping_ticket_withdraws_request() {
+        let pending = Arc::new(PendingApprovals::new());
+        let ticket = pending.register("gateway", &shell_request());
+        drop(ticket);
+        assert!(pending.list().is_empty());
+    }
+
+    #[tokio::test]
+    async fn broadcast_transport_publishes_event_and_waits() {
+        let pending = Arc::new(PendingApprovals::new());
+        let (tx, mut rx) = broadcast::channel(4);
+        let transport = BroadcastApprovalTransport::new(tx, "gateway", Arc::clone(&pending));
+
+        let resolver = tokio::spawn(async move {
+            let event = rx.recv().await.unwrap();
+            assert_eq!(event["type"], "approval_request");
+            assert_eq!(event["tool"], "shell");
+            let id = event["id"].as_str().unwrap().to_string();
+            assert!(pending.resolve(&id, ApprovalResponse::Yes));
+        });
+
+        let decision = transport.request(&shell_request()).await.unwrap();
+        assert_eq!(decision, ApprovalResponse::Yes);
+        resolver.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn broadcast_transport_fails_without_listeners() {
+        let pending = Arc::new(PendingApprovals::new());
+        let (tx, _) = broadcast::channel(4);
+        let transport = BroadcastApprovalTransport::new(tx, "gateway", Arc::clone(&pending));
+
+        assert!(transport.request(&shell_request()).await.is_err());
+        assert!(pending.list().is_empty());
+    }
+}
@@ -32,9 +32,11 @@ pub mod nostr;
 pub mod qq;
 pub mod signal;
 pub mod slack;
+pub mod teams;
 pub mod telegram;
 pub mod traits;
 pub mod transcription;
+pub mod webchat;
 pub mod whatsapp;
 #[cfg(feature = "whatsapp-web")]
 pub mod whatsapp_storage;
@@ -59,14 +61,21 @@ pub use nostr::NostrChannel;
 pub use qq::QQChannel;
 pub use signal::SignalChannel;
 pub use slack::SlackChannel;
+pub use teams::TeamsChannel;
 pub use telegram::TelegramChannel;
 pub use traits::{Channel, SendMessage};
+pub use webchat::WebChatChannel;
 pub use whatsapp::WhatsAppChannel;
 #[cfg(feature = "whatsapp-web")]
 pub use whatsapp_web::WhatsAppWebChannel;
 
-use crate::agent::loop_::{build_tool_instructions, run_tool_call_loop, scrub_credentials};
+use crate::agent::context::{self, ContextBudget};
+use crate::agent::loop_::{
+    auto_compact_history, build_tool_instructions, run_tool_call_loop, scrub_credentials,
+};
+use crate::approval::{ApprovalManager, ChannelApprovalTransport, PendingApprovals};
 use crate::config::Config;
+use crate::cost::{CostAttribution, CostContext};
 use crate::identity;
 use crate::memory::{self, Memory};
 use crate::observability::{self, runtime_trace, Observer};
@@ -221,6 +230,19 @@ struct ChannelRuntimeContext {
     multimodal: crate::config::MultimodalConfig,
     hooks: Option<Arc<crate::hooks::HookRunner>>,
     non_cli_excluded_tools: Arc<Vec<String>>,
+    context_window_tokens: Option<usize>,
+    approvals: Option<Arc<ChannelApprovals>>,
+    session_store: Option<Arc<crate::sessions::SessionStore>>,
+    cost_tracker: Option<Arc<crate::cost::CostTracker>>,
+}
+
+/// Remote approval state shared by channel workers when
+/// `[autonomy] remote_approvals` is enabled in supervised mode. Each message
+/// routes `manager` to its own chat, so "Always" answers and the decision
+/// log outlive a single message.
+struct ChannelApprovals {
+    manager: ApprovalManager,
+    pending: Arc<PendingApprovals>,
 }
 
 #[derive(Clone)]
@@ -709,11 +731,132 @@ fn set_route_selection(ctx: &ChannelRuntimeContext, sender_key: &str, next: Chan
     }
 }
 
-fn clear_sender_history(ctx: &ChannelRuntimeContext, sender_key: &str) {
+/// Run a session-store operation for a sender off the async runtime.
+///
+/// Failures are logged and never fail the message being processed.
+async fn with_sender_session<F>(ctx: &ChannelRuntimeContext, sender_key: &str, action: &str, op: F)
+where
+    F: FnOnce(&crate::sessions::SessionStore) -> anyhow::Result<()> + Send + 'static,
+{
+    let Some(store) = ctx.session_store.clone() else {
+        return;
+    };
+    match tokio::task::spawn_blocking(move || op(&store)).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => tracing::warn!(session = sender_key, "Failed to {action}: {e}"),
+        Err(e) => tracing::warn!(session = sender_key, "Session store task failed: {e}"),
+    }
+}
+
+async fn clear_sender_history(ctx: &ChannelRuntimeContext, sender_key: &str) {
     ctx.conversation_histories
         .lock()
         .unwrap_or_else(|e| e.into_inner())
         .remove(sender_key);
+    let key = sender_key.to_string();
+    with_sender_session(ctx, sender_key, "clear stored session", move |store| {
+        store.clear(&key)
+    })
+    .await;
+}
+
+/// Fold a stored transcript back into the user/assistant turns cached per
+/// sender. Tool calls collapse into the same `[Used tools: ...]` prefix the
+/// live path adds to assistant replies.
+fn channel_turns_from_transcript(
+    transcript: Vec<providers::ConversationMessage>,
+    summarize_tools: bool,
+) -> Vec<ChatMessage> {
+    let mut turns = Vec::new();
+    let mut tool_names: Vec<String> = Vec::new();
+    for message in transcript {
+        match message {
+            providers::ConversationMessage::Chat(mut chat) => {
+                if chat.role == "assistant" && summarize_tools && !tool_names.is_empty() {
+                    chat.content =
+                        format!("[Used tools: {}]\n{}", tool_names.join(", "), chat.content);
+                }
+                if chat.role == "user" || chat.role == "assistant" {
+                    tool_names.clear();
+                    turns.push(chat);
+                }
+            }
+            providers::ConversationMessage::AssistantToolCalls { tool_calls, .. } => {
+                for call in tool_calls {
+                    if !tool_names.contains(&call.name) {
+                        tool_names.push(call.name);
+                    }
+                }
+            }
+            providers::ConversationMessage::ToolResults(_) => {}
+        }
+    }
+    turns
+}
+
+/// Seed a sender's in-memory history from the session store after a restart.
+async fn hydrate_sender_history(ctx: &ChannelRuntimeContext, sender_key: &str, channel: &str) {
+    let Some(store) = ctx.session_store.clone() else {
+        return;
+    };
+    if ctx
+        .conversation_histories
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .contains_key(sender_key)
+    {
+        return;
+    }
+
+    let key = sender_key.to_string();
+    let loaded =
+        tokio::task::spawn_blocking(move || store.load_recent(&key, MAX_CHANNEL_HISTORY)).await;
+    let transcript = match loaded {
+        Ok(Ok(transcript)) => transcript,
+        Ok(Err(e)) => {
+            tracing::warn!(session = sender_key, "Failed to load stored session: {e}");
+            return;
+        }
+        Err(e) => {
+            tracing::warn!(session = sender_key, "Session store task failed: {e}");
+            return;
+        }
+    };
+    let turns = channel_turns_from_transcript(transcript, channel != "telegram");
+    if turns.is_empty() {
+        return;
+    }
+
+    ctx.conversation_histories
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(sender_key.to_string())
+        .or_insert(turns);
+}
+
+/// Rewrite a sender's stored transcript from the in-memory cache so that
+/// compaction survives restarts.
+async fn mirror_sender_history(ctx: &ChannelRuntimeContext, sender_key: &str, channel: &str) {
+    let turns: Vec<providers::ConversationMessage> = ctx
+        .conversation_histories
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(sender_key)
+        .map(|turns| {
+            turns
+                .iter()
+                .cloned()
+                .map(providers::ConversationMessage::Chat)
+                .collect()
+        })
+        .unwrap_or_default();
+    let key = sender_key.to_string();
+    let source = channel.to_string();
+    with_sender_session(ctx, sender_key, "store compacted session", move |store| {
+        store.ensure(&key, &source)?;
+        store.replace(&key, &turns)
+    })
+    .await;
 }
 
 fn compact_sender_history(ctx: &ChannelRuntimeContext, sender_key: &str) -> bool {
@@ -751,42 +894,93 @@ fn compact_sender_history(ctx: &ChannelRuntimeContext, sender_key: &str) -> bool
     true
 }
 
-fn append_sender_turn(ctx: &ChannelRuntimeContext, sender_key: &str, turn: ChatMessage) {
+/// Replace the cached turns that precede the in-flight user turn, e.g. with
+/// a compacted version of them.
+fn replace_sender_history(ctx: &ChannelRuntimeContext, sender_key: &str, earlier: &[ChatMessage]) {
     let mut histories = ctx
         .conversation_histories
         .lock()
         .unwrap_or_else(|e| e.into_inner());
-    let turns = histories.entry(sender_key.to_string()).or_default();
-    turns.push(turn);
-    while turns.len() > MAX_CHANNEL_HISTORY {
-        turns.remove(0);
+    if let Some(turns) = histories.get_mut(sender_key) {
+        let current = turns.pop();
+        *turns = earlier.to_vec();
+        turns.extend(current);
+    }
+}
+
+/// Cache one user/assistant turn for a sender and persist `transcript` (the
+/// turn itself plus any tool calls that produced it) to the session store.
+async fn append_sender_turn(
+    ctx: &ChannelRuntimeContext,
+    sender_key: &str,
+    channel: &str,
+    turn: ChatMessage,
+    transcript: Vec<providers::ConversationMessage>,
+) {
+    {
+        let mut histories = ctx
+            .conversation_histories
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let turns = histories.entry(sender_key.to_string()).or_default();
+        turns.push(turn);
+        while turns.len() > MAX_CHANNEL_HISTORY {
+            turns.remove(0);
+        }
     }
+
+    let key = sender_key.to_string();
+    let source = channel.to_string();
+    with_sender_session(ctx, sender_key, "persist channel turn", move |store| {
+        store.ensure(&key, &source)?;
+        store.append(&key, &transcript)
+    })
+    .await;
 }
 
-fn rollback_orphan_user_turn(
+/// Like [`append_sender_turn`] for a turn with no tool activity.
+async fn append_sender_chat_turn(
+    ctx: &ChannelRuntimeContext,
+    sender_key: &str,
+    channel: &str,
+    turn: ChatMessage,
+) {
+    let transcript = vec![providers::ConversationMessage::Chat(turn.clone())];
+    append_sender_turn(ctx, sender_key, channel, turn, transcript).await;
+}
+
+async fn rollback_orphan_user_turn(
     ctx: &ChannelRuntimeContext,
     sender_key: &str,
     expected_content: &str,
 ) -> bool {
-    let mut histories = ctx
-        .conversation_histories
-        .lock()
-        .unwrap_or_else(|e| e.into_inner());
-    let Some(turns) = histories.get_mut(sender_key) else {
-        return false;
-    };
+    {
+        let mut histories = ctx
+            .conversation_histories
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let Some(turns) = histories.get_mut(sender_key) else {
+            return false;
+        };
 
-    let should_pop = turns
-        .last()
-        .is_some_and(|turn| turn.role == "user" && turn.content == expected_content);
-    if !should_pop {
-        return false;
-    }
+        let should_pop = turns
+            .last()
+            .is_some_and(|turn| turn.role == "user" && turn.content == expected_content);
+        if !should_pop {
+            return false;
+        }
 
-    turns.pop();
-    if turns.is_empty() {
-        histories.remove(sender_key);
+        turns.pop();
+        if turns.is_empty() {
+            histories.remove(sender_key);
+        }
     }
+
+    let key = sender_key.to_string();
+    with_sender_session(ctx, sender_key, "roll back stored turn", move |store| {
+        store.pop_last(&key).map(|_| ())
+    })
+    .await;
     true
 }
 
@@ -988,7 +1182,7 @@ async fn handle_runtime_command_if_needed(
                         if provider_name != current.provider {
                             current.provider = provider_name.clone();
                             set_route_selection(ctx, &sender_key, current.clone());
-                            clear_sender_history(ctx, &sender_key);
+                            clear_sender_history(ctx, &sender_key).await;
                         }
 
                         format!(
@@ -1018,7 +1212,7 @@ async fn handle_runtime_command_if_needed(
             } else {
                 current.model = model.clone();
                 set_route_selection(ctx, &sender_key, current.clone());
-                clear_sender_history(ctx, &sender_key);
+                clear_sender_history(ctx, &sender_key).await;
 
                 format!(
                     "Model switched to `{model}` for provider `{}` in this sender session.",
@@ -1562,6 +1756,8 @@ async fn process_channel_message(
     println!("  ⏳ Processing message...");
     let started_at = Instant::now();
 
+    hydrate_sender_history(ctx.as_ref(), &history_key, &msg.channel).await;
+
     let had_prior_history = ctx
         .conversation_histories
         .lock()
@@ -1570,7 +1766,13 @@ async fn process_channel_message(
         .is_some_and(|turns| !turns.is_empty());
 
     // Preserve user turn before the LLM call so interrupted requests keep context.
-    append_sender_turn(ctx.as_ref(), &history_key, ChatMessage::user(&msg.content));
+    append_sender_chat_turn(
+        ctx.as_ref(),
+        &history_key,
+        &msg.channel,
+        ChatMessage::user(&msg.content),
+    )
+    .await;
 
     // Build history from per-sender conversation cache.
     let prior_turns_raw = ctx
@@ -1597,6 +1799,21 @@ async fn process_channel_message(
     let system_prompt = build_channel_system_prompt(ctx.system_prompt.as_str(), &msg.channel);
     let mut history = vec![ChatMessage::system(system_prompt)];
     history.extend(prior_turns);
+
+    // Fit the request into the model's context window up front. Compacted
+    // turns are written back so the summary is reused on later messages.
+    let budget = ContextBudget::for_model(&route.model, ctx.context_window_tokens);
+    if let Ok(true) = auto_compact_history(
+        &mut history,
+        active_provider.as_ref(),
+        &route.model,
+        &budget,
+    )
+    .await
+    {
+        replace_sender_history(ctx.as_ref(), &history_key, &history[1..history.len() - 1]);
+        mirror_sender_history(ctx.as_ref(), &history_key, &msg.channel).await;
+    }
     let use_streaming = target_channel
         .as_ref()
         .is_some_and(|ch| ch.supports_draft_updates());
@@ -1652,7 +1869,11 @@ async fn process_channel_message(
                     accumulated.clear();
                     continue;
                 }
-                accumulated.push_str(&delta);
+                accumulated.push_str(
+                    delta
+                        .strip_prefix(crate::agent::loop_::DRAFT_PROGRESS_PREFIX)
+                        .unwrap_or(&delta),
+                );
                 if let Err(e) = channel
                     .update_draft(&reply_target, &draft_id, &accumulated)
                     .await
@@ -1685,14 +1906,33 @@ async fn process_channel_message(
         _ => None,
     };
 
-    // Record history length before tool loop so we can extract tool context after.
-    let history_len_before_tools = history.len();
-
     enum LlmExecutionResult {
         Completed(Result<Result<String, anyhow::Error>, tokio::time::error::Elapsed>),
         Cancelled,
     }
 
+    let approval_manager =
+        ctx.approvals
+            .as_ref()
+            .zip(target_channel.as_ref())
+            .map(|(approvals, channel)| {
+                approvals
+                    .manager
+                    .route_through(Arc::new(ChannelApprovalTransport::new(
+                        Arc::clone(channel),
+                        msg.reply_target.clone(),
+                        msg.thread_ts.clone(),
+                        interruption_scope_key(&msg),
+                        Arc::clone(&approvals.pending),
+                    )))
+            });
+    let cost = ctx.cost_tracker.as_ref().map(|tracker| {
+        CostContext::new(
+            Arc::clone(tracker),
+            CostAttribution::channel(&msg.channel, &msg.sender),
+        )
+    });
+
     let timeout_budget_secs =
         channel_message_timeout_budget_secs(ctx.message_timeout_secs, ctx.max_tool_iterations);
     let llm_result = tokio::select! {
@@ -1708,7 +1948,7 @@ async fn process_channel_message(
                 route.model.as_str(),
                 runtime_defaults.temperature,
                 true,
-                None,
+                approval_manager.as_ref(),
                 msg.channel.as_str(),
                 &ctx.multimodal,
                 ctx.max_tool_iterations,
@@ -1720,6 +1960,8 @@ async fn process_channel_message(
                 } else {
                     ctx.non_cli_excluded_tools.as_ref()
                 },
+                cost.as_ref(),
+                Some(&budget),
             ),
         ) => LlmExecutionResult::Completed(result),
     };
@@ -1853,7 +2095,9 @@ async fn process_channel_message(
 
             // Extract condensed tool-use context from the history messages
             // added during run_tool_call_loop, so the LLM retains awareness
-            // of what it did on subsequent turns.
+            // of what it did on subsequent turns. Compaction inside the loop
+            // can shift indices, so the turn is located again here.
+            let history_len_before_tools = context::current_turn_start(&history) + 1;
             let tool_summary = extract_tool_context_summary(&history, history_len_before_tools);
             let history_response = if tool_summary.is_empty() || msg.channel == "telegram" {
                 delivered_response.clone()
@@ -1861,11 +2105,28 @@ async fn process_channel_message(
                 format!("{tool_summary}\n{delivered_response}")
             };
 
+            // The store keeps the tool calls and results themselves; the
+            // cache only needs the condensed reply.
+            let mut transcript = crate::sessions::transcript_from_history(
+                history.get(history_len_before_tools..).unwrap_or_default(),
+            );
+            if matches!(
+                transcript.last(),
+                Some(providers::ConversationMessage::Chat(chat)) if chat.role == "assistant"
+            ) {
+                transcript.pop();
+            }
+            transcript.push(providers::ConversationMessage::Chat(
+                ChatMessage::assistant(&delivered_response),
+            ));
             append_sender_turn(
                 ctx.as_ref(),
                 &history_key,
+                &msg.channel,
                 ChatMessage::assistant(&history_response),
-            );
+                transcript,
+            )
+            .await;
             println!(
                 "  🤖 Reply ({}ms): {}",
                 started_at.elapsed().as_millis(),
@@ -1926,6 +2187,9 @@ async fn process_channel_message(
                 }
             } else if is_context_window_overflow_error(&e) {
                 let compacted = compact_sender_history(ctx.as_ref(), &history_key);
+                if compacted {
+                    mirror_sender_history(ctx.as_ref(), &history_key, &msg.channel).await;
+                }
                 let error_text = if compacted {
                     "⚠️ Context window exceeded for this conversation. I compacted recent history and kept the latest context. Please resend your last message."
                 } else {
@@ -1987,16 +2251,18 @@ async fn process_channel_message(
                     .downcast_ref::<providers::ProviderCapabilityError>()
                     .is_some_and(|capability| capability.capability.eq_ignore_ascii_case("vision"));
                 let rolled_back = should_rollback_user_turn
-                    && rollback_orphan_user_turn(ctx.as_ref(), &history_key, &msg.content);
+                    && rollback_orphan_user_turn(ctx.as_ref(), &history_key, &msg.content).await;
 
                 if !rolled_back {
                     // Close the orphan user turn so subsequent messages don't
                     // inherit this failed request as unfinished context.
-                    append_sender_turn(
+                    append_sender_chat_turn(
                         ctx.as_ref(),
                         &history_key,
+                        &msg.channel,
                         ChatMessage::assistant("[Task failed — not continuing this request]"),
-                    );
+                    )
+                    .await;
                 }
                 if let Some(channel) = target_channel.as_ref() {
                     if let Some(ref draft_id) = draft_message_id {
@@ -2039,11 +2305,13 @@ async fn process_channel_message(
             );
             // Close the orphan user turn so subsequent messages don't
             // inherit this timed-out request as unfinished context.
-            append_sender_turn(
+            append_sender_chat_turn(
                 ctx.as_ref(),
                 &history_key,
+                &msg.channel,
                 ChatMessage::assistant("[Task timed out — not continuing this request]"),
-            );
+            )
+            .await;
             if let Some(channel) = target_channel.as_ref() {
                 let error_text =
                     "⚠️ Request timed out while waiting for the model. Please try again.";
@@ -2088,6 +2356,25 @@ async fn run_message_dispatch_loop(
     let task_sequence = Arc::new(AtomicU64::new(1));
 
     while let Some(msg) = rx.recv().await {
+        // Approval replies answer a waiting tool call instead of starting a
+        // new turn (and must not interrupt the turn that is waiting).
+        if let Some(approvals) = ctx.approvals.as_ref() {
+            if approvals
+                .pending
+                .resolve_reply(&interruption_scope_key(&msg), &msg.content)
+            {
+                if let Some(channel) = ctx.channels_by_name.get(&msg.channel) {
+                    if let Err(e) = channel
+                        .add_reaction(&msg.reply_target, &msg.id, "\u{2705}")
+                        .await
+                    {
+                        tracing::debug!("Failed to acknowledge approval reply: {e}");
+                    }
+                }
+                continue;
+            }
+        }
+
         let permit = match Arc::clone(&semaphore).acquire_owned().await {
             Ok(permit) => permit,
             Err(_) => break,
@@ -2824,6 +3111,23 @@ fn collect_configured_channels(
         });
     }
 
+    if let Some(ref teams) = config.channels_config.teams {
+        channels.push(ConfiguredChannel {
+            display_name: "Teams",
+            channel: Arc::new(TeamsChannel::new(teams.clone())),
+        });
+    }
+
+    if let Some(ref webchat) = config.channels_config.webchat {
+        channels.push(ConfiguredChannel {
+            display_name: "WebChat",
+            channel: Arc::new(
+                WebChatChannel::new(webchat.clone())
+                    .with_workspace_dir(config.workspace_dir.clone()),
+            ),
+        });
+    }
+
     if let Some(ref email_cfg) = config.channels_config.email {
         channels.push(ConfiguredChannel {
             display_name: "Email",
@@ -2997,8 +3301,10 @@ pub async fn start_channels(config: Config) -> Result<()> {
 
     let observer: Arc<dyn Observer> =
         Arc::from(observability::create_observer(&config.observability));
-    let runtime: Arc<dyn runtime::RuntimeAdapter> =
-        Arc::from(runtime::create_runtime(&config.runtime)?);
+    let runtime: Arc<dyn runtime::RuntimeAdapter> = Arc::from(runtime::create_runtime(
+        &config.runtime,
+        &config.security.resources,
+    )?);
     let security = Arc::new(SecurityPolicy::from_config(
         &config.autonomy,
         &config.workspace_dir,
@@ -3251,6 +3557,36 @@ pub async fn start_channels(config: Config) -> Result<()> {
             None
         },
         non_cli_excluded_tools: Arc::new(config.autonomy.non_cli_excluded_tools.clone()),
+        context_window_tokens: config.agent.context_window_tokens,
+        approvals: (config.autonomy.remote_approvals
+            && config.autonomy.level == crate::security::AutonomyLevel::Supervised)
+            .then(|| {
+                Arc::new(ChannelApprovals {
+                    manager: ApprovalManager::from_config(&config.autonomy)
+                        .with_audit(crate::security::AuditLogger::try_from_config(&config)),
+                    pending: Arc::new(PendingApprovals::new()),
+                })
+            }),
+        session_store: match crate::sessions::SessionStore::open(&config.workspace_dir) {
+            Ok(store) => Some(Arc::new(store)),
+            Err(e) => {
+                tracing::warn!("Session store unavailable; channel history will not persist: {e}");
+                None
+            }
+        },
+        cost_tracker: if config.cost.enabled {
+            match crate::cost::shared_tracker(&config.cost, &config.workspace_dir) {
+                Ok(tracker) => Some(tracker),
+                Err(e) => {
+                    tracing::warn!(
+                        "Cost tracker unavailable; channel spend will not be tracked: {e}"
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        },
     });
 
     run_message_dispatch_loop(rx, runtime_ctx, max_in_flight_messages).await;
@@ -3464,6 +3800,10 @@ mod tests {
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: None,
+            session_store: None,
+            cost_tracker: None,
         };
 
         assert!(compact_sender_history(&ctx, &sender));
@@ -3485,7 +3825,72 @@ mod tests {
     }
 
     #[test]
-    fn append_sender_turn_stores_single_turn_per_call() {
+    fn replace_sender_history_keeps_in_flight_turn() {
+        let mut histories = HashMap::new();
+        let sender = "telegram_u1".to_string();
+        histories.insert(
+            sender.clone(),
+            vec![
+                ChatMessage::user("old question"),
+                ChatMessage::assistant("old answer"),
+                ChatMessage::user("current question"),
+            ],
+        );
+
+        let ctx = ChannelRuntimeContext {
+            channels_by_name: Arc::new(HashMap::new()),
+            provider: Arc::new(DummyProvider),
+            default_provider: Arc::new("test-provider".to_string()),
+            memory: Arc::new(NoopMemory),
+            tools_registry: Arc::new(vec![]),
+            observer: Arc::new(NoopObserver),
+            system_prompt: Arc::new("system".to_string()),
+            model: Arc::new("test-model".to_string()),
+            temperature: 0.0,
+            auto_save_memory: false,
+            max_tool_iterations: 5,
+            min_relevance_score: 0.0,
+            conversation_histories: Arc::new(Mutex::new(histories)),
+            provider_cache: Arc::new(Mutex::new(HashMap::new())),
+            route_overrides: Arc::new(Mutex::new(HashMap::new())),
+            api_key: None,
+            api_url: None,
+            reliability: Arc::new(crate::config::ReliabilityConfig::default()),
+            interrupt_on_new_message: false,
+            multimodal: crate::config::MultimodalConfig::default(),
+            hooks: None,
+            provider_runtime_options: providers::ProviderRuntimeOptions::default(),
+            workspace_dir: Arc::new(std::env::temp_dir()),
+            message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
+            non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: None,
+            session_store: None,
+            cost_tracker: None,
+        };
+
+        replace_sender_history(
+            &ctx,
+            &sender,
+            &[ChatMessage::assistant(
+                "[Compaction summary]\n- asked earlier",
+            )],
+        );
+
+        let histories = ctx
+            .conversation_histories
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let turns = histories
+            .get(&sender)
+            .expect("sender history should remain");
+        assert_eq!(turns.len(), 2);
+        assert!(turns[0].content.starts_with("[Compaction summary]"));
+        assert_eq!(turns[1].content, "current question");
+    }
+
+    #[tokio::test]
+    async fn append_sender_turn_stores_single_turn_per_call() {
         let sender = "telegram_u2".to_string();
         let ctx = ChannelRuntimeContext {
             channels_by_name: Arc::new(HashMap::new()),
@@ -3513,9 +3918,13 @@ mod tests {
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: None,
+            session_store: None,
+            cost_tracker: None,
         };
 
-        append_sender_turn(&ctx, &sender, ChatMessage::user("hello"));
+        append_sender_chat_turn(&ctx, &sender, "telegram", ChatMessage::user("hello")).await;
 
         let histories = ctx
             .conversation_histories
@@ -3527,8 +3936,104 @@ mod tests {
         assert_eq!(turns[0].content, "hello");
     }
 
-    #[test]
-    fn rollback_orphan_user_turn_removes_only_latest_matching_user_turn() {
+    #[tokio::test]
+    async fn sender_history_survives_restart_via_session_store() {
+        let sender = "telegram_u4".to_string();
+        let tmp = tempfile::TempDir::new().unwrap();
+        let make_ctx = || ChannelRuntimeContext {
+            channels_by_name: Arc::new(HashMap::new()),
+            provider: Arc::new(DummyProvider),
+            default_provider: Arc::new("test-provider".to_string()),
+            memory: Arc::new(NoopMemory),
+            tools_registry: Arc::new(vec![]),
+            observer: Arc::new(NoopObserver),
+            system_prompt: Arc::new("system".to_string()),
+            model: Arc::new("test-model".to_string()),
+            temperature: 0.0,
+            auto_save_memory: false,
+            max_tool_iterations: 5,
+            min_relevance_score: 0.0,
+            conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            provider_cache: Arc::new(Mutex::new(HashMap::new())),
+            route_overrides: Arc::new(Mutex::new(HashMap::new())),
+            api_key: None,
+            api_url: None,
+            reliability: Arc::new(crate::config::ReliabilityConfig::default()),
+            interrupt_on_new_message: false,
+            multimodal: crate::config::MultimodalConfig::default(),
+            hooks: None,
+            provider_runtime_options: providers::ProviderRuntimeOptions::default(),
+            workspace_dir: Arc::new(tmp.path().to_path_buf()),
+            message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
+            non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: None,
+            session_store: Some(Arc::new(
+                crate::sessions::SessionStore::open(tmp.path()).unwrap(),
+            )),
+            cost_tracker: None,
+        };
+
+        let before = make_ctx();
+        append_sender_chat_turn(
+            &before,
+            &sender,
+            "discord",
+            ChatMessage::user("remember 42"),
+        )
+        .await;
+        append_sender_turn(
+            &before,
+            &sender,
+            "discord",
+            ChatMessage::assistant("[Used tools: memory_store]\nnoted"),
+            vec![
+                providers::ConversationMessage::AssistantToolCalls {
+                    text: None,
+                    tool_calls: vec![providers::ToolCall {
+                        id: "call_1".into(),
+                        name: "memory_store".into(),
+                        arguments: "{}".into(),
+                    }],
+                },
+                providers::ConversationMessage::ToolResults(vec![providers::ToolResultMessage {
+                    tool_call_id: "call_1".into(),
+                    content: "stored".into(),
+                }]),
+                providers::ConversationMessage::Chat(ChatMessage::assistant("noted")),
+            ],
+        )
+        .await;
+        let store = before.session_store.clone().unwrap();
+        assert_eq!(store.load(&sender).unwrap().len(), 4);
+        assert_eq!(store.get(&sender).unwrap().unwrap().source, "discord");
+        drop(before);
+
+        let after = make_ctx();
+        hydrate_sender_history(&after, &sender, "discord").await;
+        {
+            let histories = after
+                .conversation_histories
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            let turns = histories.get(&sender).expect("history restored from store");
+            assert_eq!(turns.len(), 2);
+            assert_eq!(turns[0].content, "remember 42");
+            assert_eq!(turns[1].role, "assistant");
+            assert_eq!(turns[1].content, "[Used tools: memory_store]\nnoted");
+        }
+
+        compact_sender_history(&after, &sender);
+        mirror_sender_history(&after, &sender, "discord").await;
+        let stored = after.session_store.as_ref().unwrap().load(&sender).unwrap();
+        assert_eq!(stored.len(), 2);
+        assert!(stored
+            .iter()
+            .all(|message| matches!(message, providers::ConversationMessage::Chat(_))));
+    }
+
+    #[tokio::test]
+    async fn rollback_orphan_user_turn_removes_only_latest_matching_user_turn() {
         let sender = "telegram_u3".to_string();
         let mut histories = HashMap::new();
         histories.insert(
@@ -3565,9 +4070,13 @@ mod tests {
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: None,
+            session_store: None,
+            cost_tracker: None,
         };
 
-        assert!(rollback_orphan_user_turn(&ctx, &sender, "pending"));
+        assert!(rollback_orphan_user_turn(&ctx, &sender, "pending").await);
 
         let histories = ctx
             .conversation_histories
@@ -4038,6 +4547,10 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: None,
+            session_store: None,
+            cost_tracker: None,
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
         });
@@ -4097,6 +4610,10 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: None,
+            session_store: None,
+            cost_tracker: None,
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
         });
@@ -4172,6 +4689,10 @@ BTC is currently around $65,000 based on latest tool output."#
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: None,
+            session_store: None,
+            cost_tracker: None,
         });
 
         process_channel_message(
@@ -4231,6 +4752,10 @@ BTC is currently around $65,000 based on latest tool output."#
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: None,
+            session_store: None,
+            cost_tracker: None,
         });
 
         process_channel_message(
@@ -4299,6 +4824,10 @@ BTC is currently around $65,000 based on latest tool output."#
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: None,
+            session_store: None,
+            cost_tracker: None,
         });
 
         process_channel_message(
@@ -4388,6 +4917,10 @@ BTC is currently around $65,000 based on latest tool output."#
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: None,
+            session_store: None,
+            cost_tracker: None,
         });
 
         process_channel_message(
@@ -4459,6 +4992,10 @@ BTC is currently around $65,000 based on latest tool output."#
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: None,
+            session_store: None,
+            cost_tracker: None,
         });
 
         process_channel_message(
@@ -4545,6 +5082,10 @@ BTC is currently around $65,000 based on latest tool output."#
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: None,
+            session_store: None,
+            cost_tracker: None,
         });
 
         process_channel_message(
@@ -4616,6 +5157,10 @@ BTC is currently around $65,000 based on latest tool output."#
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: None,
+            session_store: None,
+            cost_tracker: None,
         });
 
         process_channel_message(
@@ -4676,6 +5221,10 @@ BTC is currently around $65,000 based on latest tool output."#
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: None,
+            session_store: None,
+            cost_tracker: None,
         });
 
         process_channel_message(
@@ -4847,6 +5396,10 @@ BTC is currently around $65,000 based on latest tool output."#
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: None,
+            session_store: None,
+            cost_tracker: None,
         });
 
         let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(4);
@@ -4888,6 +5441,89 @@ BTC is currently around $65,000 based on latest tool output."#
         assert_eq!(sent_messages.len(), 2);
     }
 
+    #[tokio::test]
+    async fn message_dispatch_routes_approval_reply_to_pending_request() {
+        let channel_impl = Arc::new(RecordingChannel::default());
+        let channel: Arc<dyn Channel> = channel_impl.clone();
+
+        let mut channels_by_name = HashMap::new();
+        channels_by_name.insert(channel.name().to_string(), channel);
+
+        let pending = Arc::new(PendingApprovals::new());
+        let ticket = pending.register(
+            "test-channel_alice_alice",
+            &crate::approval::ApprovalRequest {
+                tool_name: "shell".into(),
+                arguments: serde_json::json!({"command": "ls"}),
+            },
+        );
+
+        let runtime_ctx = Arc::new(ChannelRuntimeContext {
+            channels_by_name: Arc::new(channels_by_name),
+            provider: Arc::new(SlowProvider {
+                delay: Duration::from_millis(10),
+            }),
+            default_provider: Arc::new("test-provider".to_string()),
+            memory: Arc::new(NoopMemory),
+            tools_registry: Arc::new(vec![]),
+            observer: Arc::new(NoopObserver),
+            system_prompt: Arc::new("test-system-prompt".to_string()),
+            model: Arc::new("test-model".to_string()),
+            temperature: 0.0,
+            auto_save_memory: false,
+            max_tool_iterations: 10,
+            min_relevance_score: 0.0,
+            conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            provider_cache: Arc::new(Mutex::new(HashMap::new())),
+            route_overrides: Arc::new(Mutex::new(HashMap::new())),
+            api_key: None,
+            api_url: None,
+            reliability: Arc::new(crate::config::ReliabilityConfig::default()),
+            provider_runtime_options: providers::ProviderRuntimeOptions::default(),
+            workspace_dir: Arc::new(std::env::temp_dir()),
+            message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
+            interrupt_on_new_message: false,
+            multimodal: crate::config::MultimodalConfig::default(),
+            hooks: None,
+            non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: Some(Arc::new(ChannelApprovals {
+                manager: ApprovalManager::from_config(&crate::config::AutonomyConfig::default()),
+                pending: Arc::clone(&pending),
+            })),
+            session_store: None,
+            cost_tracker: None,
+        });
+
+        let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(4);
+        // A bare "yes" is an ordinary message, not an answer to the card.
+        for (id, content) in [
+            ("1", "yes".to_string()),
+            ("2", format!("approve {}", ticket.id())),
+        ] {
+            tx.send(traits::ChannelMessage {
+                id: id.to_string(),
+                sender: "alice".to_string(),
+                reply_target: "alice".to_string(),
+                content,
+                channel: "test-channel".to_string(),
+                timestamp: 1,
+                thread_ts: None,
+            })
+            .await
+            .unwrap();
+        }
+        drop(tx);
+
+        run_message_dispatch_loop(rx, runtime_ctx, 2).await;
+
+        assert_eq!(
+            ticket.wait().await.unwrap(),
+            crate::approval::ApprovalResponse::Yes
+        );
+        assert_eq!(channel_impl.sent_messages.lock().await.len(), 1);
+    }
+
     #[tokio::test]
     async fn message_dispatch_interrupts_in_flight_telegram_request_and_preserves_context() {
         let channel_impl = Arc::new(TelegramRecordingChannel::default());
@@ -4927,6 +5563,10 @@ BTC is currently around $65,000 based on latest tool output."#
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: None,
+            session_store: None,
+            cost_tracker: None,
         });
 
         let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(8);
@@ -5019,6 +5659,10 @@ BTC is currently around $65,000 based on latest tool output."#
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: None,
+            session_store: None,
+            cost_tracker: None,
         });
 
         let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(8);
@@ -5093,6 +5737,10 @@ BTC is currently around $65,000 based on latest tool output."#
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: None,
+            session_store: None,
+            cost_tracker: None,
         });
 
         process_channel_message(
@@ -5152,6 +5800,10 @@ BTC is currently around $65,000 based on latest tool output."#
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: None,
+            session_store: None,
+            cost_tracker: None,
         });
 
         process_channel_message(
@@ -5359,8 +6011,11 @@ BTC is currently around $65,000 based on latest tool output."#
                 kind: "shell".into(),
                 command: "cargo clippy".into(),
                 args: HashMap::new(),
+                parameters: Vec::new(),
+                method: None,
             }],
             prompts: vec!["Always run cargo test before final response.".into()],
+            permissions: crate::skills::SkillPermissions::default(),
             location: None,
         }];
 
@@ -5394,8 +6049,11 @@ BTC is currently around $65,000 based on latest tool output."#
                 kind: "shell".into(),
                 command: "cargo clippy".into(),
                 args: HashMap::new(),
+                parameters: Vec::new(),
+                method: None,
             }],
             prompts: vec!["Always run cargo test before final response.".into()],
+            permissions: crate::skills::SkillPermissions::default(),
             location: None,
         }];
 
@@ -5435,8 +6093,11 @@ BTC is currently around $65,000 based on latest tool output."#
                 kind: "shell&exec".into(),
                 command: "cargo clippy".into(),
                 args: HashMap::new(),
+                parameters: Vec::new(),
+                method: None,
             }],
             prompts: vec!["Use <tool_call> and & keep output \"safe\"".into()],
+            permissions: crate::skills::SkillPermissions::default(),
             location: None,
         }];
 
@@ -5668,6 +6329,10 @@ BTC is currently around $65,000 based on latest tool output."#
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: None,
+            session_store: None,
+            cost_tracker: None,
         });
 
         process_channel_message(
@@ -5753,6 +6418,10 @@ BTC is currently around $65,000 based on latest tool output."#
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: None,
+            session_store: None,
+            cost_tracker: None,
         });
 
         process_channel_message(
@@ -5838,6 +6507,10 @@ BTC is currently around $65,000 based on latest tool output."#
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: None,
+            session_store: None,
+            cost_tracker: None,
         });
 
         process_channel_message(
@@ -6387,6 +7060,10 @@ This is an example JSON object for profile settings."#;
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: None,
+            session_store: None,
+            cost_tracker: None,
         });
 
         // Simulate a photo attachment message with [IMAGE:] marker.
@@ -6453,6 +7130,10 @@ This is an example JSON object for profile settings."#;
             multimodal: crate::config::MultimodalConfig::default(),
             hooks: None,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            context_window_tokens: None,
+            approvals: None,
+            session_store: None,
+            cost_tracker: None,
         });
 
         process_channel_message(
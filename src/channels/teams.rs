@@ -0,0 +1,955 @@
+use super::traits::{Channel, ChannelMessage, SendMessage};
+use crate::config::schema::TeamsConfig;
+use anyhow::Context;
+use async_trait::async_trait;
+use base64::Engine;
+use parking_lot::Mutex;
+use ring::signature::{RsaPublicKeyComponents, RSA_PKCS1_2048_8192_SHA256};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+/// Issuer of Bot Framework channel tokens when the metadata names none.
+const BOT_FRAMEWORK_ISSUER: &str = "https://api.botframework.com";
+/// Scope requested for connector API access tokens.
+const CONNECTOR_SCOPE: &str = "https://api.botframework.com/.default";
+/// Signing keys are re-fetched at least this often, and on unknown `kid`s.
+const SIGNING_KEYS_TTL: Duration = Duration::from_secs(24 * 3600);
+/// Minimum spacing between key fetches forced by an unknown `kid`, so
+/// unauthenticated callers cannot turn random `kid`s into upstream requests.
+const FORCED_KEY_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+/// Tolerated clock skew for `exp` / `nbf`.
+const CLOCK_SKEW_SECS: i64 = 300;
+
+/// Bot Framework hands each activity a `serviceUrl`, and replies for that
+/// conversation must go back to it. Shared by the gateway (which learns
+/// them) and the channel runtime (which sends replies).
+fn service_urls() -> &'static Mutex<HashMap<String, String>> {
+    static SERVICE_URLS: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+    SERVICE_URLS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Channel runtime listener that gateway-received messages are handed to.
+fn inbound_listener() -> &'static Mutex<Option<mpsc::Sender<ChannelMessage>>> {
+    static INBOUND: OnceLock<Mutex<Option<mpsc::Sender<ChannelMessage>>>> = OnceLock::new();
+    INBOUND.get_or_init(|| Mutex::new(None))
+}
+
+/// Hand a message received by the gateway to the channel runtime, so it gets
+/// per-sender history, typing indicators and threaded replies like every
+/// other channel. Returns the message when no runtime is listening, so the
+/// caller can answer it directly.
+pub fn dispatch_inbound(msg: ChannelMessage) -> Result<(), Box<ChannelMessage>> {
+    let mut listener = inbound_listener().lock();
+    let Some(tx) = listener.as_ref() else {
+        return Err(Box::new(msg));
+    };
+    match tx.try_send(msg) {
+        Ok(()) => Ok(()),
+        Err(mpsc::error::TrySendError::Full(msg)) => Err(Box::new(msg)),
+        Err(mpsc::error::TrySendError::Closed(msg)) => {
+            *listener = None;
+            Err(Box::new(msg))
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenIdMetadata {
+    #[serde(default)]
+    issuer: Option<String>,
+    jwks_uri: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Jwk {
+    #[serde(default)]
+    kid: Option<String>,
+    kty: String,
+    #[serde(default)]
+    n: Option<String>,
+    #[serde(default)]
+    e: Option<String>,
+    /// Channels (e.g. `msteams`) the key may sign tokens for.
+    #[serde(default)]
+    endorsements: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JwkSet {
+    keys: Vec<Jwk>,
+}
+
+struct SigningKeys {
+    fetched_at: Instant,
+    issuer: String,
+    keys: Vec<Jwk>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JwtHeader {
+    alg: String,
+    #[serde(default)]
+    kid: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JwtClaims {
+    iss: String,
+    aud: serde_json::Value,
+    exp: i64,
+    #[serde(default)]
+    nbf: Option<i64>,
+    #[serde(default, rename = "serviceurl", alias = "serviceUrl")]
+    service_url: Option<String>,
+}
+
+struct AccessToken {
+    token: String,
+    expires_at: Instant,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    expires_in: Option<u64>,
+}
+
+/// Microsoft Teams channel via the Bot Framework, in webhook mode.
+///
+/// Activities arrive at the gateway endpoint `/teams`, authenticated by a
+/// JWT signed with keys from `openid_metadata_url`. Replies go out through
+/// the connector API of the activity's `serviceUrl`.
+pub struct TeamsChannel {
+    config: TeamsConfig,
+    signing_keys: Mutex<Option<Arc<SigningKeys>>>,
+    /// Serializes key fetches and remembers when the last one started.
+    key_refresh: tokio::sync::Mutex<Option<Instant>>,
+    access_token: tokio::sync::Mutex<Option<AccessToken>>,
+}
+
+impl TeamsChannel {
+    pub fn new(config: TeamsConfig) -> Self {
+        Self {
+            config,
+            signing_keys: Mutex::new(None),
+            key_refresh: tokio::sync::Mutex::new(None),
+            access_token: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    fn http_client(&self) -> reqwest::Client {
+        crate::config::build_runtime_proxy_client("channel.teams")
+    }
+
+    /// Check an Entra object ID or Teams user ID against the allowlist.
+    /// Empty list means deny everyone; `"*"` allows everyone.
+    fn is_user_allowed(&self, user_id: &str) -> bool {
+        self.config
+            .allowed_users
+            .iter()
+            .any(|u| u == "*" || u == user_id)
+    }
+
+    fn token_url(&self) -> String {
+        self.config.token_url.clone().unwrap_or_else(|| {
+            let tenant = self
+                .config
+                .tenant_id
+                .as_deref()
+                .map(str::trim)
+                .filter(|tenant| !tenant.is_empty())
+                .unwrap_or("botframework.com");
+            format!("https://login.microsoftonline.com/{tenant}/oauth2/v2.0/token")
+        })
+    }
+
+    async fn fetch_signing_keys(&self) -> anyhow::Result<SigningKeys> {
+        let client = self.http_client();
+        let metadata: OpenIdMetadata = client
+            .get(&self.config.openid_metadata_url)
+            .send()
+            .await?
+            .error_for_status()
+            .context("Teams: OpenID metadata request failed")?
+            .json()
+            .await?;
+        let jwks: JwkSet = client
+            .get(&metadata.jwks_uri)
+            .send()
+            .await?
+            .error_for_status()
+            .context("Teams: signing key request failed")?
+            .json()
+            .await?;
+        Ok(SigningKeys {
+            fetched_at: Instant::now(),
+            issuer: metadata
+                .issuer
+                .unwrap_or_else(|| BOT_FRAMEWORK_ISSUER.to_string()),
+            keys: jwks.keys,
+        })
+    }
+
+    /// Signing key for `kid` plus the expected issuer, refreshing the cache
+    /// when it is stale or does not know the key (keys rotate). Refreshes for
+    /// unknown keys happen at most once per [`FORCED_KEY_REFRESH_INTERVAL`],
+    /// and lookups in the cache never wait for a fetch in progress.
+    async fn signing_key(&self, kid: Option<&str>) -> anyhow::Result<(Jwk, String)> {
+        let find = |keys: &SigningKeys| {
+            keys.keys
+                .iter()
+                .find(|key| key.kty == "RSA" && (kid.is_none() || key.kid.as_deref() == kid))
+                .cloned()
+                .map(|key| (key, keys.issuer.clone()))
+        };
+        let cached = || {
+            self.signing_keys
+                .lock()
+                .clone()
+                .filter(|keys| keys.fetched_at.elapsed() < SIGNING_KEYS_TTL)
+        };
+        let no_match = || anyhow::anyhow!("Teams: no signing key matches the token");
+
+        if let Some(found) = cached().as_deref().and_then(find) {
+            return Ok(found);
+        }
+
+        let mut last_fetch = self.key_refresh.lock().await;
+        // Another request may have refreshed the keys while this one waited.
+        let current = cached();
+        if let Some(found) = current.as_deref().and_then(find) {
+            return Ok(found);
+        }
+        if current.is_some()
+            && last_fetch.is_some_and(|at| at.elapsed() < FORCED_KEY_REFRESH_INTERVAL)
+        {
+            return Err(no_match());
+        }
+
+        *last_fetch = Some(Instant::now());
+        let fresh = Arc::new(self.fetch_signing_keys().await?);
+        *self.signing_keys.lock() = Some(Arc::clone(&fresh));
+        find(&fresh).ok_or_else(no_match)
+    }
+
+    /// Validate the `Authorization` header of an inbound activity.
+    ///
+    /// Checks the RS256 signature against the OpenID metadata keys, the
+    /// issuer, the audience (our app ID), the validity window, the
+    /// `serviceurl` claim and the key's channel endorsements.
+    pub async fn validate_token(
+        &self,
+        authorization: &str,
+        service_url: &str,
+        channel_id: &str,
+    ) -> anyhow::Result<()> {
+        let token = authorization
+            .strip_prefix("Bearer ")
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("missing bearer token"))?;
+        let mut parts = token.split('.');
+        let (Some(header_b64), Some(claims_b64), Some(signature_b64), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            anyhow::bail!("malformed token");
+        };
+
+        let b64 = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        let header: JwtHeader = serde_json::from_slice(&b64.decode(header_b64)?)?;
+        if header.alg != "RS256" {
+            anyhow::bail!("unsupported token algorithm {}", header.alg);
+        }
+
+        let (key, issuer) = self.signing_key(header.kid.as_deref()).await?;
+        let (Some(n), Some(e)) = (key.n.as_deref(), key.e.as_deref()) else {
+            anyhow::bail!("signing key has no RSA components");
+        };
+        let public_key = RsaPublicKeyComponents {
+            n: b64.decode(n)?,
+            e: b64.decode(e)?,
+        };
+        let signed = format!("{header_b64}.{claims_b64}");
+        public_key
+            .verify(
+                &RSA_PKCS1_2048_8192_SHA256,
+                signed.as_bytes(),
+                &b64.decode(signature_b64)?,
+            )
+            .map_err(|_| anyhow::anyhow!("invalid token signature"))?;
+
+        if !key.endorsements.is_empty() && !key.endorsements.iter().any(|e| e == channel_id) {
+            anyhow::bail!("signing key is not endorsed for channel {channel_id}");
+        }
+
+        let claims: JwtClaims = serde_json::from_slice(&b64.decode(claims_b64)?)?;
+        if claims.iss != issuer {
+            anyhow::bail!("unexpected token issuer {}", claims.iss);
+        }
+        let audience_ok = match &claims.aud {
+            serde_json::Value::String(aud) => *aud == self.config.app_id,
+            serde_json::Value::Array(auds) => auds
+                .iter()
+                .any(|aud| aud.as_str() == Some(self.config.app_id.as_str())),
+            _ => false,
+        };
+        if !audience_ok {
+            anyhow::bail!("token audience does not match app_id");
+        }
+        let now = chrono::Utc::now().timestamp();
+        if claims.exp + CLOCK_SKEW_SECS < now {
+            anyhow::bail!("token expired");
+        }
+        if claims.nbf.is_some_and(|nbf| nbf - CLOCK_SKEW_SECS > now) {
+            anyhow::bail!("token not yet valid");
+        }
+        // Replies (carrying our connector token) go to the activity's
+        // serviceUrl, so the signed claim must pin it.
+        let Some(claimed) = claims.service_url.as_deref() else {
+            anyhow::bail!("token has no serviceurl claim");
+        };
+        if claimed.trim_end_matches('/') != service_url.trim_end_matches('/') {
+            anyhow::bail!("token serviceurl does not match the activity");
+        }
+        Ok(())
+    }
+
+    /// Remove `<at>…</at>` mentions (of the bot or anyone else) from text.
+    fn strip_mentions(text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find("<at>") {
+            out.push_str(&rest[..start]);
+            match rest[start..].find("</at>") {
+                Some(end) => rest = &rest[start + end + "</at>".len()..],
+                None => {
+                    rest = "";
+                }
+            }
+        }
+        out.push_str(rest);
+        out.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    fn parse_timestamp_secs(value: Option<&serde_json::Value>) -> u64 {
+        value
+            .and_then(serde_json::Value::as_str)
+            .and_then(|raw| chrono::DateTime::parse_from_rfc3339(raw).ok())
+            .and_then(|ts| u64::try_from(ts.timestamp()).ok())
+            .unwrap_or_else(|| {
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs()
+            })
+    }
+
+    /// Parse a Bot Framework activity into channel messages.
+    ///
+    /// Only call this for activities whose token passed [`validate_token`]:
+    /// it records the activity's `serviceUrl` as the reply endpoint for the
+    /// conversation.
+    ///
+    /// Relevant fields:
+    /// - `type` (expects `message`)
+    /// - `from.id`, `from.aadObjectId` (sender, checked against `allowed_users`)
+    /// - `conversation.id` (reply target; channel threads carry `;messageid=`)
+    /// - `id` (replies are posted in reply to it, keeping them in the thread)
+    /// - `serviceUrl`, `text`, `timestamp`
+    ///
+    /// [`validate_token`]: TeamsChannel::validate_token
+    pub fn parse_activity(&self, activity: &serde_json::Value) -> Vec<ChannelMessage> {
+        let mut messages = Vec::new();
+        let field = |pointer: &str| {
+            activity
+                .pointer(pointer)
+                .and_then(serde_json::Value::as_str)
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        };
+
+        let activity_type = field("/type").unwrap_or("");
+        if !activity_type.eq_ignore_ascii_case("message") {
+            tracing::debug!("Teams: skipping non-message activity: {activity_type}");
+            return messages;
+        }
+
+        let (Some(conversation_id), Some(service_url)) =
+            (field("/conversation/id"), field("/serviceUrl"))
+        else {
+            tracing::warn!("Teams: activity without conversation.id or serviceUrl");
+            return messages;
+        };
+
+        let Some(sender) = field("/from/aadObjectId").or_else(|| field("/from/id")) else {
+            tracing::warn!("Teams: activity without a sender");
+            return messages;
+        };
+        let allowed = self.is_user_allowed(sender)
+            || field("/from/id").is_some_and(|id| self.is_user_allowed(id));
+        if !allowed {
+            tracing::warn!(
+                "Teams: ignoring message from unauthorized user: {sender}. \
+                Add to channels_config.teams.allowed_users in config.toml."
+            );
+            return messages;
+        }
+
+        let content = Self::strip_mentions(field("/text").unwrap_or(""));
+        if content.is_empty() {
+            return messages;
+        }
+
+        service_urls()
+            .lock()
+            .insert(conversation_id.to_string(), service_url.to_string());
+
+        let activity_id = field("/id").map(ToString::to_string);
+        messages.push(ChannelMessage {
+            id: activity_id
+                .clone()
+                .unwrap_or_else(|| Uuid::new_v4().to_string()),
+            sender: sender.to_string(),
+            reply_target: conversation_id.to_string(),
+            content,
+            channel: "teams".to_string(),
+            timestamp: Self::parse_timestamp_secs(activity.get("timestamp")),
+            thread_ts: activity_id,
+        });
+        messages
+    }
+
+    /// Connector API access token, cached until shortly before expiry.
+    async fn connector_token(&self) -> anyhow::Result<String> {
+        let mut cached = self.access_token.lock().await;
+        if let Some(token) = cached
+            .as_ref()
+            .filter(|token| token.expires_at > Instant::now())
+        {
+            return Ok(token.token.clone());
+        }
+
+        let response: TokenResponse = self
+            .http_client()
+            .post(self.token_url())
+            .form(&[
+                ("grant_type", "client_credentials"),
+                ("client_id", self.config.app_id.as_str()),
+                ("client_secret", self.config.app_password.as_str()),
+                ("scope", CONNECTOR_SCOPE),
+            ])
+            .send()
+            .await?
+            .error_for_status()
+            .context("Teams: connector token request failed")?
+            .json()
+            .await?;
+
+        // Refresh a minute early so in-flight sends never carry an expired token.
+        let lifetime = response.expires_in.unwrap_or(3600).saturating_sub(60);
+        *cached = Some(AccessToken {
+            token: response.access_token.clone(),
+            expires_at: Instant::now() + Duration::from_secs(lifetime),
+        });
+        Ok(response.access_token)
+    }
+
+    /// Outgoing message activity. Content that already is an Adaptive Card
+    /// (JSON with `"type": "AdaptiveCard"`) is sent as a card; otherwise the
+    /// Markdown text is wrapped in one when `adaptive_cards` is on.
+    fn message_activity(&self, content: &str) -> serde_json::Value {
+        let card = serde_json::from_str::<serde_json::Value>(content.trim())
+            .ok()
+            .filter(|value| value.get("type").and_then(|t| t.as_str()) == Some("AdaptiveCard"))
+            .or_else(|| {
+                self.config.adaptive_cards.then(|| {
+                    serde_json::json!({
+                        "type": "AdaptiveCard",
+                        "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+                        "version": "1.5",
+                        "body": [{"type": "TextBlock", "text": content, "wrap": true}],
+                    })
+                })
+            });
+
+        match card {
+            Some(card) => serde_json::json!({
+                "type": "message",
+                "attachments": [{
+                    "contentType": "application/vnd.microsoft.card.adaptive",
+                    "content": card,
+                }],
+            }),
+            None => serde_json::json!({
+                "type": "message",
+                "text": content,
+                "textFormat": "markdown",
+            }),
+        }
+    }
+
+    async fn post_activity(
+        &self,
+        conversation_id: &str,
+        reply_to_activity: Option<&str>,
+        activity: &serde_json::Value,
+    ) -> anyhow::Result<()> {
+        let service_url = service_urls()
+            .lock()
+            .get(conversation_id)
+            .cloned()
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Teams: no serviceUrl known for conversation {conversation_id}; \
+                    the bot can only reply after receiving a message there"
+                )
+            })?;
+        let base = format!(
+            "{}/v3/conversations/{}/activities",
+            service_url.trim_end_matches('/'),
+            urlencoding::encode(conversation_id)
+        );
+        let url = match reply_to_activity {
+            Some(activity_id) => format!("{base}/{}", urlencoding::encode(activity_id)),
+            None => base,
+        };
+
+        let token = self.connector_token().await?;
+        let response = self
+            .http_client()
+            .post(&url)
+            .bearer_auth(token)
+            .json(activity)
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            return Ok(());
+        }
+
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        tracing::error!("Teams send failed: {status} — {body}");
+        anyhow::bail!("Teams connector API error: {status}");
+    }
+}
+
+#[async_trait]
+impl Channel for TeamsChannel {
+    fn name(&self) -> &str {
+        "teams"
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        let activity = self.message_activity(&message.content);
+        self.post_activity(&message.recipient, message.thread_ts.as_deref(), &activity)
+            .await
+    }
+
+    async fn listen(&self, tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        tracing::info!(
+            "Teams channel active (webhook mode). \
+            Point the Azure Bot messaging endpoint at your gateway's /teams endpoint."
+        );
+
+        // Incoming activities are received by the gateway and handed over here.
+        *inbound_listener().lock() = Some(tx.clone());
+        tx.closed().await;
+        Ok(())
+    }
+
+    async fn health_check(&self) -> bool {
+        self.http_client()
+            .get(&self.config.openid_metadata_url)
+            .send()
+            .await
+            .map(|r| r.status().is_success())
+            .unwrap_or(false)
+    }
+
+    async fn start_typing(&self, recipient: &str) -> anyhow::Result<()> {
+        // Teams clears the indicator by itself after a few seconds; the
+        // channel runtime re-sends it while a reply is being produced.
+        self.post_activity(recipient, None, &serde_json::json!({"type": "typing"}))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{RsaKeyPair, RSA_PKCS1_SHA256};
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// 2048-bit RSA test key (PKCS#8 DER, base64). Test use only.
+    const TEST_SIGNING_KEY: &str = concat!(
+        "MIIEvAIBADANBgkqhkiG9w0BAQEFAASCBKYwggSiAgEAAoIBAQDYr9w7teA986NWSm5SvgKF03WUd6avTj9P8cwh36XZGM+5",
+        "p7bg8Z1o4RYqPx7jjxRmSIbdlJmEqAXwW0WklmaHIw8+1DJTksNOBWNWl4nJIaZ4KGz40VRoSyI4VpFd1t7Mj0M0xjC7foWj",
+        "nPl2XK3O9GdjqRqOMZQ/VUmT8VteV0m42VASVUu0xHisWdgsp888US91wA251kwEUQ50K3xQ9ygfyRxCCzQ5krAsP+V/JH41",
+        "CsuJwzuQodUwQ0Q1wBllhnHPKB+nyveVmPXd3JLkahZRPBz45yy1hOddauo9HDU686iJZbfWrPCw38OdNyFQnZCQl1wPUhKh",
+        "D8fFcv3lAgMBAAECggEAKrRd/xaVHyCJLFR28a+o558JKT17iEkviR3qUTK2iNau+bPoAuUbVLQnniPafpaQOqhIjBrhNpe2",
+        "s+wJCarqge+S7Zr8YCQogZPmRAaRjSYonL263D7FF9jW4auN8qSKPF5vrFLy/8CrrdceAwm453Sbe7S0GxoCUYkJezKyAyFr",
+        "5E7NfFNZzKDXpXKLLKTxW1Urb7zMYpR29ZgVRVEDyaATwlvOjiMQvOG2gQxjI9ak8+yzgCi3x3sLgD7jVIbx9+N5NNdVC0C+",
+        "ZmCoBZVdluhLTORueobUf45xQxQx5vXKEhBE+FNkwN9bMmfbhABCjfAWUXQ24JvTqAnctvDscQKBgQD2UebrcVDJkTDA+lcj",
+        "whOFTpEp3jZx3WPBpWuiZFhw1A1rH4lGOwOu8yhFxiH4TFZOQQPwLpdUPSZ5w6tSnXztytEmPQJSmCtNB04QqOEoyuS0VEYm",
+        "akC0r8sBENZ79LgfnzqP+RE78QXwC6ZksmcHiBVtgb2pkHZI1LAu5tRyWwKBgQDhM9YN1T9fVbW5UJwF2sKg+W8FQcASoxws",
+        "JLj3umSVdvg4J/peCTeWZqJ61bAeMKEjxbGPtFvewn/RK9o7QkawQ/IVGSdFHwxUE7326++yenHqP+E0LfrsR/J9icGaEBtx",
+        "Lm0+IaVUaNxv16p3p+db3EzAAT44OiWsYzdOpWzEvwKBgG2FtTtyww0c9aSYqy5HGQ7pBDqth1/RpI1x1A38uCZS4zSCbTNB",
+        "Aj5D9+AuXORebPGWjv24eakHF5iEnCS7ydShnaV28xVue7JgUSGvNY5ByLc10Ys7K1Ir6AOtTPEIuuW0kwF5KWSRoqY9R9r2",
+        "SEFdDMoIKiC/DxkLGNuB32yJAoGAZP3oCoANUWWQfzCFJ6L5EVOK1CKuTi1TnwBA6udt4BFgM17KSo17njkcuFVIC+HnAbHQ",
+        "dQ9++6DhzR4LWfPY24c88vU6xSatUq8fy7H25mU+3LwU9F+CXZ/OQTob1klO5Sza1Lgl+nqkEIv46Hwlpu5ehOSJ0JFS/8Hm",
+        "taA97OUCgYBQf004GHiKmv3lZxKUSahOtWbae4BviY8/eYsZlgiZmxGT5IoV9EaUXk+FPqoHS14eqKPfjywMe8rxnJDm3tkl",
+        "dORTBrUBC5qwJunFvtculTXyYvJrdRcgdYXCsqZ+B6rb310ITCPqpFuUFf/UG5s/HGmC6Wx+gTq6zYeZmmKU6g==",
+    );
+
+    const APP_ID: &str = "test-app-id";
+    const ISSUER: &str = "https://api.botframework.com";
+    const SERVICE_URL: &str = "https://smba.example.com/teams/";
+
+    fn key_pair() -> RsaKeyPair {
+        let der = base64::engine::general_purpose::STANDARD
+            .decode(TEST_SIGNING_KEY)
+            .unwrap();
+        RsaKeyPair::from_pkcs8(&der).unwrap()
+    }
+
+    fn sign_token(claims: &serde_json::Value) -> String {
+        sign_token_with_kid(claims, "test-key")
+    }
+
+    fn sign_token_with_kid(claims: &serde_json::Value, kid: &str) -> String {
+        let b64 = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        let header =
+            b64.encode(serde_json::json!({"alg": "RS256", "kid": kid, "typ": "JWT"}).to_string());
+        let claims = b64.encode(claims.to_string());
+        let signed = format!("{header}.{claims}");
+        let key_pair = key_pair();
+        let mut signature = vec![0; key_pair.public().modulus_len()];
+        key_pair
+            .sign(
+                &RSA_PKCS1_SHA256,
+                &SystemRandom::new(),
+                signed.as_bytes(),
+                &mut signature,
+            )
+            .unwrap();
+        format!("Bearer {signed}.{}", b64.encode(signature))
+    }
+
+    fn valid_claims() -> serde_json::Value {
+        let now = chrono::Utc::now().timestamp();
+        serde_json::json!({
+            "iss": ISSUER,
+            "aud": APP_ID,
+            "nbf": now - 10,
+            "exp": now + 3600,
+            "serviceurl": SERVICE_URL,
+        })
+    }
+
+    /// Local stand-in for the Bot Framework OpenID metadata and key set.
+    async fn openid_server() -> MockServer {
+        let server = MockServer::start().await;
+        let public: RsaPublicKeyComponents<Vec<u8>> = key_pair().public().into();
+        let b64 = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        Mock::given(method("GET"))
+            .and(path("/openid"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "issuer": ISSUER,
+                "jwks_uri": format!("{}/keys", server.uri()),
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/keys"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "keys": [{
+                    "kty": "RSA",
+                    "kid": "test-key",
+                    "n": b64.encode(&public.n),
+                    "e": b64.encode(&public.e),
+                    "endorsements": ["msteams"],
+                }]
+            })))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    fn make_channel(openid_metadata_url: String, allowed_users: Vec<String>) -> TeamsChannel {
+        TeamsChannel::new(TeamsConfig {
+            app_id: APP_ID.into(),
+            app_password: "secret".into(),
+            tenant_id: None,
+            openid_metadata_url,
+            token_url: None,
+            allowed_users,
+            adaptive_cards: false,
+        })
+    }
+
+    fn message_activity(conversation_id: &str, from: &str, text: &str) -> serde_json::Value {
+        serde_json::json!({
+            "type": "message",
+            "id": "1700000000000",
+            "timestamp": "2025-01-01T03:00:00.000Z",
+            "serviceUrl": SERVICE_URL,
+            "channelId": "msteams",
+            "from": {"id": "29:teams-user", "aadObjectId": from, "name": "User"},
+            "conversation": {"id": conversation_id, "conversationType": "channel"},
+            "recipient": {"id": "28:bot", "name": "ZeroClaw"},
+            "text": text,
+        })
+    }
+
+    #[test]
+    fn teams_channel_name() {
+        let channel = make_channel("http://localhost/openid".into(), vec![]);
+        assert_eq!(channel.name(), "teams");
+    }
+
+    #[tokio::test]
+    async fn teams_accepts_token_signed_by_metadata_key() {
+        let server = openid_server().await;
+        let channel = make_channel(format!("{}/openid", server.uri()), vec![]);
+
+        let token = sign_token(&valid_claims());
+        channel
+            .validate_token(&token, SERVICE_URL, "msteams")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn teams_rejects_bad_tokens() {
+        let server = openid_server().await;
+        let channel = make_channel(format!("{}/openid", server.uri()), vec![]);
+        let token = sign_token(&valid_claims());
+
+        assert!(channel
+            .validate_token("", SERVICE_URL, "msteams")
+            .await
+            .is_err());
+        // Key endorsed for msteams only.
+        assert!(channel
+            .validate_token(&token, SERVICE_URL, "webchat")
+            .await
+            .is_err());
+        // serviceurl claim pins the activity's service URL.
+        assert!(channel
+            .validate_token(&token, "https://attacker.example.com", "msteams")
+            .await
+            .is_err());
+
+        let mut wrong_audience = valid_claims();
+        wrong_audience["aud"] = "someone-else".into();
+        assert!(channel
+            .validate_token(&sign_token(&wrong_audience), SERVICE_URL, "msteams")
+            .await
+            .is_err());
+
+        let mut expired = valid_claims();
+        expired["exp"] = (chrono::Utc::now().timestamp() - 3600).into();
+        assert!(channel
+            .validate_token(&sign_token(&expired), SERVICE_URL, "msteams")
+            .await
+            .is_err());
+
+        // A token that does not pin the serviceurl could redirect replies.
+        let mut unpinned = valid_claims();
+        unpinned.as_object_mut().unwrap().remove("serviceurl");
+        assert!(channel
+            .validate_token(&sign_token(&unpinned), SERVICE_URL, "msteams")
+            .await
+            .is_err());
+
+        // Tampered claims no longer match the signature.
+        let (head, _) = token.rsplit_once('.').unwrap();
+        let forged = format!("{head}.{}", "A".repeat(342));
+        assert!(channel
+            .validate_token(&forged, SERVICE_URL, "msteams")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn teams_unknown_kids_do_not_force_repeated_key_fetches() {
+        let server = openid_server().await;
+        let channel = make_channel(format!("{}/openid", server.uri()), vec![]);
+        channel
+            .validate_token(&sign_token(&valid_claims()), SERVICE_URL, "msteams")
+            .await
+            .unwrap();
+
+        for kid in ["random-1", "random-2", "random-3"] {
+            let err = channel
+                .validate_token(
+                    &sign_token_with_kid(&valid_claims(), kid),
+                    SERVICE_URL,
+                    "msteams",
+                )
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains("no signing key"));
+        }
+        let key_fetches = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .filter(|request| request.url.path() == "/keys")
+            .count();
+        assert_eq!(key_fetches, 1);
+
+        // Once the interval has passed, an unknown key triggers a refresh.
+        *channel.key_refresh.lock().await = Instant::now().checked_sub(FORCED_KEY_REFRESH_INTERVAL);
+        assert!(channel
+            .validate_token(
+                &sign_token_with_kid(&valid_claims(), "rotated"),
+                SERVICE_URL,
+                "msteams",
+            )
+            .await
+            .is_err());
+        let key_fetches = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .filter(|request| request.url.path() == "/keys")
+            .count();
+        assert_eq!(key_fetches, 2);
+    }
+
+    #[test]
+    fn teams_parse_message_activity() {
+        let channel = make_channel("http://localhost/openid".into(), vec!["aad-user".into()]);
+        let conversation = "19:general@thread.tacv2;messageid=1699999999999";
+        let activity =
+            message_activity(conversation, "aad-user", "<at>ZeroClaw</at> hello   there");
+
+        let messages = channel.parse_activity(&activity);
+        assert_eq!(messages.len(), 1);
+        let msg = &messages[0];
+        assert_eq!(msg.channel, "teams");
+        assert_eq!(msg.sender, "aad-user");
+        assert_eq!(msg.reply_target, conversation);
+        assert_eq!(msg.content, "hello there");
+        assert_eq!(msg.thread_ts.as_deref(), Some("1700000000000"));
+        assert_eq!(msg.timestamp, 1_735_700_400);
+        assert_eq!(
+            service_urls().lock().get(conversation).map(String::as_str),
+            Some(SERVICE_URL)
+        );
+    }
+
+    #[test]
+    fn teams_parse_skips_unauthorized_and_non_message_activities() {
+        let channel = make_channel("http://localhost/openid".into(), vec!["aad-user".into()]);
+        assert!(channel
+            .parse_activity(&message_activity("19:a", "aad-other", "hi"))
+            .is_empty());
+
+        let mut typing = message_activity("19:a", "aad-user", "hi");
+        typing["type"] = "typing".into();
+        assert!(channel.parse_activity(&typing).is_empty());
+
+        let mention_only = message_activity("19:a", "aad-user", "<at>ZeroClaw</at>");
+        assert!(channel.parse_activity(&mention_only).is_empty());
+    }
+
+    #[test]
+    fn teams_message_activity_formats() {
+        let channel = make_channel("http://localhost/openid".into(), vec![]);
+        let plain = channel.message_activity("**hi**");
+        assert_eq!(plain["text"], "**hi**");
+        assert_eq!(plain["textFormat"], "markdown");
+
+        let card = channel.message_activity(r#"{"type":"AdaptiveCard","version":"1.5","body":[]}"#);
+        assert_eq!(
+            card["attachments"][0]["contentType"],
+            "application/vnd.microsoft.card.adaptive"
+        );
+
+        let mut config = channel.config.clone();
+        config.adaptive_cards = true;
+        let wrapped = TeamsChannel::new(config).message_activity("hello");
+        assert_eq!(
+            wrapped["attachments"][0]["content"]["body"][0]["text"],
+            "hello"
+        );
+    }
+
+    #[tokio::test]
+    async fn teams_send_replies_in_thread_via_connector_api() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "connector-token",
+                "expires_in": 3600,
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path(
+                "/v3/conversations/19%3Aroom%40thread.v2/activities/42",
+            ))
+            .and(header("authorization", "Bearer connector-token"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({"id": "43"})))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let mut channel = make_channel("http://localhost/openid".into(), vec![]);
+        channel.config.token_url = Some(format!("{}/token", server.uri()));
+        service_urls()
+            .lock()
+            .insert("19:room@thread.v2".into(), server.uri());
+
+        for _ in 0..2 {
+            channel
+                .send(&SendMessage::new("done", "19:room@thread.v2").in_thread(Some("42".into())))
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn teams_send_requires_known_conversation() {
+        let channel = make_channel("http://localhost/openid".into(), vec![]);
+        let err = channel
+            .send(&SendMessage::new("hi", "19:never-seen"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("no serviceUrl known"));
+    }
+
+    #[tokio::test]
+    async fn teams_dispatch_inbound_reaches_listener() {
+        let (tx, mut rx) = mpsc::channel(4);
+        *inbound_listener().lock() = Some(tx);
+        let channel = make_channel("http://localhost/openid".into(), vec!["*".into()]);
+        let msg = channel
+            .parse_activity(&message_activity("19:dispatch", "aad-user", "hi"))
+            .remove(0);
+
+        dispatch_inbound(msg).unwrap();
+        assert_eq!(rx.recv().await.unwrap().reply_target, "19:dispatch");
+
+        drop(rx);
+        let msg = channel
+            .parse_activity(&message_activity("19:dispatch", "aad-user", "again"))
+            .remove(0);
+        assert!(dispatch_inbound(msg).is_err());
+        assert!(inbound_listener().lock().is_none());
+    }
+}
@@ -31,10 +31,13 @@ const SUPPORTED_PROXY_SERVICE_KEYS: &[&str] = &[
     "channel.qq",
     "channel.signal",
     "channel.slack",
+    "channel.teams",
     "channel.telegram",
     "channel.whatsapp",
     "tool.browser",
     "tool.composio",
+    "tool.github",
+    "tool.home_assistant",
     "tool.http_request",
     "tool.pushover",
     "memory.embeddings",
@@ -93,6 +96,10 @@ pub struct Config {
     #[serde(default)]
     pub runtime: RuntimeConfig,
 
+    /// Sandboxing, per-command resource limits and audit logging (`[security]`).
+    #[serde(default)]
+    pub security: SecurityConfig,
+
     /// Reliability settings: retries, fallback providers, backoff (`[reliability]`).
     #[serde(default)]
     pub reliability: ReliabilityConfig,
@@ -121,6 +128,10 @@ pub struct Config {
     #[serde(default)]
     pub query_classification: QueryClassificationConfig,
 
+    /// Cost- and latency-aware automatic routing across `[[model_routes]]` (`[auto_routing]`).
+    #[serde(default)]
+    pub auto_routing: AutoRoutingConfig,
+
     /// Heartbeat configuration for periodic health pings (`[heartbeat]`).
     #[serde(default)]
     pub heartbeat: HeartbeatConfig,
@@ -173,6 +184,14 @@ pub struct Config {
     #[serde(default)]
     pub web_search: WebSearchConfig,
 
+    /// GitHub issues, pull requests and reviews tools (`[github]`).
+    #[serde(default)]
+    pub github: GitHubConfig,
+
+    /// Home Assistant tool and state-change triggers (`[home_assistant]`).
+    #[serde(default)]
+    pub home_assistant: HomeAssistantConfig,
+
     /// Proxy configuration for outbound HTTP/HTTPS/SOCKS5 traffic (`[proxy]`).
     #[serde(default)]
     pub proxy: ProxyConfig,
@@ -410,6 +429,10 @@ pub struct AgentConfig {
     /// Maximum conversation history messages retained per session. Default: `50`.
     #[serde(default = "default_agent_max_history_messages")]
     pub max_history_messages: usize,
+    /// Context window in tokens used to budget history. Default: unset, which
+    /// looks the window up from the model name.
+    #[serde(default)]
+    pub context_window_tokens: Option<usize>,
     /// Enable parallel tool execution within a single iteration. Default: `false`.
     #[serde(default)]
     pub parallel_tools: bool,
@@ -436,6 +459,7 @@ impl Default for AgentConfig {
             compact_context: false,
             max_tool_iterations: default_agent_max_tool_iterations(),
             max_history_messages: default_agent_max_history_messages(),
+            context_window_tokens: None,
             parallel_tools: false,
             tool_dispatcher: default_agent_tool_dispatcher(),
         }
@@ -564,7 +588,7 @@ impl Default for IdentityConfig {
 // ── Cost tracking and budget enforcement ───────────────────────────
 
 /// Cost tracking and budget enforcement configuration (`[cost]` section).
-#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct CostConfig {
     /// Enable cost tracking (default: false)
     #[serde(default)]
@@ -589,10 +613,65 @@ pub struct CostConfig {
     /// Per-model pricing (USD per 1M tokens)
     #[serde(default)]
     pub prices: std::collections::HashMap<String, ModelPricing>,
+
+    /// Per-channel, per-user, per-job and per-agent limits (`[cost.budgets]`)
+    #[serde(default)]
+    pub budgets: ScopedBudgetsConfig,
+}
+
+/// Scoped budgets checked alongside the global daily/monthly limits.
+///
+/// Each table is keyed by the scope value: channel name, `<channel>:<sender>`
+/// for users, cron job id, or delegate agent name. A `"*"` entry applies to
+/// every key without its own entry, with spend tracked per key.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ScopedBudgetsConfig {
+    /// Limits per channel (e.g. `telegram`, `gateway`, `cron`)
+    #[serde(default)]
+    pub channel: std::collections::HashMap<String, BudgetLimitConfig>,
+
+    /// Limits per sender, keyed as `<channel>:<sender>`
+    #[serde(default)]
+    pub user: std::collections::HashMap<String, BudgetLimitConfig>,
+
+    /// Limits per cron job id
+    #[serde(default)]
+    pub job: std::collections::HashMap<String, BudgetLimitConfig>,
+
+    /// Limits per delegate agent name
+    #[serde(default)]
+    pub agent: std::collections::HashMap<String, BudgetLimitConfig>,
+}
+
+/// Daily/monthly limit for one budget scope.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BudgetLimitConfig {
+    /// Daily spending limit in USD (unset = no daily limit)
+    #[serde(default)]
+    pub daily_limit_usd: Option<f64>,
+
+    /// Monthly spending limit in USD (unset = no monthly limit)
+    #[serde(default)]
+    pub monthly_limit_usd: Option<f64>,
+
+    /// What to do when the limit is reached (default: deny)
+    #[serde(default)]
+    pub action: BudgetAction,
+}
+
+/// Action taken when a scoped budget is exceeded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetAction {
+    /// Log a warning and let the request proceed
+    Warn,
+    /// Block the request
+    #[default]
+    Deny,
 }
 
 /// Per-model pricing entry (USD per 1M tokens).
-#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ModelPricing {
     /// Input price per 1M tokens
     #[serde(default)]
@@ -601,6 +680,26 @@ pub struct ModelPricing {
     /// Output price per 1M tokens
     #[serde(default)]
     pub output: f64,
+
+    /// Prompt-cache read price per 1M tokens (default: input price)
+    #[serde(default)]
+    pub cache_read: Option<f64>,
+
+    /// Prompt-cache write price per 1M tokens (default: input price)
+    #[serde(default)]
+    pub cache_write: Option<f64>,
+}
+
+impl ModelPricing {
+    /// Effective price per 1M tokens read from the prompt cache.
+    pub fn cache_read_price(&self) -> f64 {
+        self.cache_read.unwrap_or(self.input)
+    }
+
+    /// Effective price per 1M tokens written to the prompt cache.
+    pub fn cache_write_price(&self) -> f64 {
+        self.cache_write.unwrap_or(self.input)
+    }
 }
 
 fn default_daily_limit() -> f64 {
@@ -624,6 +723,7 @@ impl Default for CostConfig {
             warn_at_percent: default_warn_percent(),
             allow_override: false,
             prices: get_default_pricing(),
+            budgets: ScopedBudgetsConfig::default(),
         }
     }
 }
@@ -638,6 +738,8 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 3.0,
             output: 15.0,
+            cache_read: Some(0.3),
+            cache_write: Some(3.75),
         },
     );
     prices.insert(
@@ -645,6 +747,8 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 15.0,
             output: 75.0,
+            cache_read: Some(1.5),
+            cache_write: Some(18.75),
         },
     );
     prices.insert(
@@ -652,6 +756,8 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 3.0,
             output: 15.0,
+            cache_read: Some(0.3),
+            cache_write: Some(3.75),
         },
     );
     prices.insert(
@@ -659,6 +765,8 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 0.25,
             output: 1.25,
+            cache_read: Some(0.025),
+            cache_write: Some(0.3125),
         },
     );
 
@@ -668,6 +776,8 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 5.0,
             output: 15.0,
+            cache_read: Some(2.5),
+            cache_write: None,
         },
     );
     prices.insert(
@@ -675,6 +785,8 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 0.15,
             output: 0.60,
+            cache_read: Some(0.075),
+            cache_write: None,
         },
     );
     prices.insert(
@@ -682,6 +794,8 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 15.0,
             output: 60.0,
+            cache_read: Some(7.5),
+            cache_write: None,
         },
     );
 
@@ -691,6 +805,8 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 0.10,
             output: 0.40,
+            cache_read: Some(0.025),
+            cache_write: None,
         },
     );
     prices.insert(
@@ -698,6 +814,8 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 1.25,
             output: 5.0,
+            cache_read: Some(0.3125),
+            cache_write: None,
         },
     );
 
@@ -1097,6 +1215,126 @@ impl Default for WebSearchConfig {
     }
 }
 
+// ── GitHub ──────────────────────────────────────────────────────
+
+/// GitHub integration (`[github]` section).
+///
+/// Enables the read-only `github` tool and the approval-gated `github_write`
+/// tool. Point `api_url` at a GitHub Enterprise `/api/v3` endpoint to use
+/// an on-prem instance.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitHubConfig {
+    /// Enable the GitHub tools. Default: `false`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// REST API base URL. Default: `https://api.github.com`.
+    #[serde(default = "default_github_api_url")]
+    pub api_url: String,
+    /// Personal access token (stored encrypted when secrets.encrypt = true).
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Default repository as `owner/name`. When unset, the workspace's
+    /// `origin` remote is used.
+    #[serde(default)]
+    pub repo: Option<String>,
+    /// Request timeout in seconds. Default: `30`.
+    #[serde(default = "default_github_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_github_api_url() -> String {
+    "https://api.github.com".into()
+}
+
+fn default_github_timeout_secs() -> u64 {
+    30
+}
+
+impl Default for GitHubConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            api_url: default_github_api_url(),
+            token: None,
+            repo: None,
+            timeout_secs: default_github_timeout_secs(),
+        }
+    }
+}
+
+// ── Home Assistant ───────────────────────────────────────────────
+
+/// Home Assistant integration (`[home_assistant]` section).
+///
+/// Enables the `home_assistant` tool and, under the daemon, state-change
+/// triggers fed by the Home Assistant WebSocket API.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HomeAssistantConfig {
+    /// Enable the `home_assistant` tool. Default: `false`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Base URL of the Home Assistant instance.
+    #[serde(default = "default_home_assistant_url")]
+    pub url: String,
+    /// Long-lived access token (stored encrypted when secrets.encrypt = true).
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Request timeout in seconds. Default: `15`.
+    #[serde(default = "default_home_assistant_timeout_secs")]
+    pub timeout_secs: u64,
+    /// State-change triggers that run an agent prompt or a cron job.
+    #[serde(default)]
+    pub triggers: Vec<HomeAssistantTrigger>,
+}
+
+fn default_home_assistant_url() -> String {
+    "http://homeassistant.local:8123".into()
+}
+
+fn default_home_assistant_timeout_secs() -> u64 {
+    15
+}
+
+impl Default for HomeAssistantConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: default_home_assistant_url(),
+            token: None,
+            timeout_secs: default_home_assistant_timeout_secs(),
+            triggers: Vec::new(),
+        }
+    }
+}
+
+/// A Home Assistant state change that starts work (`[[home_assistant.triggers]]`).
+///
+/// Exactly one of `prompt` or `cron_job` should be set.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HomeAssistantTrigger {
+    /// Entity to watch. `*` matches any run of characters (e.g. `binary_sensor.*_door`).
+    pub entity_id: String,
+    /// Only fire when the previous state equals this value.
+    #[serde(default)]
+    pub from: Option<String>,
+    /// Only fire when the new state equals this value.
+    #[serde(default)]
+    pub to: Option<String>,
+    /// Agent task to run, like a heartbeat task. The state change is appended.
+    #[serde(default)]
+    pub prompt: Option<String>,
+    /// ID of a cron job to run immediately.
+    #[serde(default)]
+    pub cron_job: Option<String>,
+    /// Minimum seconds between two firings of this trigger. Default: `60`.
+    #[serde(default = "default_home_assistant_trigger_cooldown_secs")]
+    pub cooldown_secs: u64,
+}
+
+fn default_home_assistant_trigger_cooldown_secs() -> u64 {
+    60
+}
+
 // ── Proxy ───────────────────────────────────────────────────────
 
 /// Proxy application scope — determines which outbound traffic uses the proxy.
@@ -1636,9 +1874,10 @@ impl Default for StorageProviderConfig {
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[allow(clippy::struct_excessive_bools)]
 pub struct MemoryConfig {
-    /// "sqlite" | "lucid" | "postgres" | "markdown" | "none" (`none` = explicit no-op memory)
+    /// "sqlite" | "lucid" | "postgres" | "markdown" | "obsidian" | "none" (`none` = explicit no-op memory)
     ///
     /// `postgres` requires `[storage.provider.config]` with `db_url` (`dbURL` alias supported).
+    /// `obsidian` requires `[memory.obsidian]` with `vault_path`.
     pub backend: String,
     /// Auto-save user-stated conversation input to memory (assistant output is excluded)
     pub auto_save: bool,
@@ -1654,10 +1893,11 @@ pub struct MemoryConfig {
     /// For sqlite backend: prune conversation rows older than this many days
     #[serde(default = "default_conversation_retention_days")]
     pub conversation_retention_days: u32,
-    /// Embedding provider: "none" | "openai" | "custom:URL"
+    /// Embedding provider: "none" | "openai" | "custom:URL" | "local"
     #[serde(default = "default_embedding_provider")]
     pub embedding_provider: String,
-    /// Embedding model name (e.g. "text-embedding-3-small")
+    /// Embedding model name (e.g. "text-embedding-3-small"); for "local",
+    /// the model directory (relative paths resolve against the workspace)
     #[serde(default = "default_embedding_model")]
     pub embedding_model: String,
     /// Embedding vector dimensions
@@ -1708,6 +1948,60 @@ pub struct MemoryConfig {
     /// None = wait indefinitely (default). Recommended max: 300.
     #[serde(default)]
     pub sqlite_open_timeout_secs: Option<u64>,
+
+    // ── Obsidian backend options ───────────────────────────────
+    /// Vault settings for `backend = "obsidian"` (`[memory.obsidian]`).
+    #[serde(default)]
+    pub obsidian: ObsidianMemoryConfig,
+}
+
+/// Obsidian vault memory backend (`[memory.obsidian]` section).
+///
+/// Indexes an existing vault (frontmatter, tags, `[[wikilinks]]`) into a
+/// search index under the workspace. The vault stays the source of truth.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ObsidianMemoryConfig {
+    /// Path to the vault root (`~` is expanded).
+    #[serde(default)]
+    pub vault_path: Option<String>,
+    /// Vault folder for notes the agent stores. Default: `ZeroClaw`.
+    #[serde(default = "default_obsidian_inbox_folder")]
+    pub inbox_folder: String,
+    /// Vault folder holding `YYYY-MM-DD.md` daily notes. Default: vault root.
+    #[serde(default)]
+    pub daily_folder: String,
+    /// Seconds between scans for external edits (0 = rescan on every recall).
+    /// Default: `10`.
+    #[serde(default = "default_obsidian_watch_interval_secs")]
+    pub watch_interval_secs: u64,
+    /// How many `[[wikilink]]` hops recall follows from matching notes.
+    /// Default: `1` (0 disables expansion).
+    #[serde(default = "default_obsidian_link_depth")]
+    pub link_expansion_depth: usize,
+}
+
+fn default_obsidian_inbox_folder() -> String {
+    "ZeroClaw".into()
+}
+
+fn default_obsidian_watch_interval_secs() -> u64 {
+    10
+}
+
+fn default_obsidian_link_depth() -> usize {
+    1
+}
+
+impl Default for ObsidianMemoryConfig {
+    fn default() -> Self {
+        Self {
+            vault_path: None,
+            inbox_folder: default_obsidian_inbox_folder(),
+            daily_folder: String::new(),
+            watch_interval_secs: default_obsidian_watch_interval_secs(),
+            link_expansion_depth: default_obsidian_link_depth(),
+        }
+    }
 }
 
 fn default_embedding_provider() -> String {
@@ -1777,6 +2071,7 @@ impl Default for MemoryConfig {
             snapshot_on_hygiene: false,
             auto_hydrate: true,
             sqlite_open_timeout_secs: None,
+            obsidian: ObsidianMemoryConfig::default(),
         }
     }
 }
@@ -1879,6 +2174,7 @@ impl Default for BuiltinHooksConfig {
 /// Controls what the agent is allowed to do: shell commands, filesystem access,
 /// risk approval gates, and per-policy budgets.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct AutonomyConfig {
     /// Autonomy level: `read_only`, `supervised` (default), or `full`.
     pub level: AutonomyLevel,
@@ -1929,6 +2225,19 @@ pub struct AutonomyConfig {
     /// model in tool specs.
     #[serde(default)]
     pub non_cli_excluded_tools: Vec<String>,
+
+    /// Route approval prompts from channels and the gateway back to the
+    /// originating conversation instead of auto-approving. Default: `false`.
+    #[serde(default)]
+    pub remote_approvals: bool,
+
+    /// Seconds to wait for a remote approval before denying the call. Default: `300`.
+    #[serde(default = "default_approval_timeout_secs")]
+    pub approval_timeout_secs: u64,
+}
+
+fn default_approval_timeout_secs() -> u64 {
+    300
 }
 
 fn default_auto_approve() -> Vec<String> {
@@ -1936,7 +2245,7 @@ fn default_auto_approve() -> Vec<String> {
 }
 
 fn default_always_ask() -> Vec<String> {
-    vec![]
+    vec!["github_write".into()]
 }
 
 fn is_valid_env_var_name(name: &str) -> bool {
@@ -1997,6 +2306,8 @@ impl Default for AutonomyConfig {
             always_ask: default_always_ask(),
             allowed_roots: Vec::new(),
             non_cli_excluded_tools: Vec::new(),
+            remote_approvals: false,
+            approval_timeout_secs: default_approval_timeout_secs(),
         }
     }
 }
@@ -2014,6 +2325,10 @@ pub struct RuntimeConfig {
     #[serde(default)]
     pub docker: DockerRuntimeConfig,
 
+    /// WASM sandbox settings for tool plugins (`[runtime.wasm]`).
+    #[serde(default)]
+    pub wasm: WasmRuntimeConfig,
+
     /// Global reasoning override for providers that expose explicit controls.
     /// - `None`: provider default behavior
     /// - `Some(true)`: request reasoning/thinking when supported
@@ -2054,6 +2369,62 @@ pub struct DockerRuntimeConfig {
     pub allowed_workspace_roots: Vec<String>,
 }
 
+/// WASM sandbox configuration (`[runtime.wasm]` section).
+///
+/// Applies to WASM tool plugins installed with `zeroclaw plugins install`.
+/// Requires a build with `--features runtime-wasm`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WasmRuntimeConfig {
+    /// Directory holding plugin modules and manifests, relative to the workspace.
+    #[serde(default = "default_wasm_tools_dir")]
+    pub tools_dir: String,
+
+    /// Default fuel budget per invocation (roughly one unit per instruction).
+    #[serde(default = "default_wasm_fuel_limit")]
+    pub fuel_limit: u64,
+
+    /// Default linear memory ceiling per invocation in MB.
+    #[serde(default = "default_wasm_memory_limit_mb")]
+    pub memory_limit_mb: u64,
+
+    /// Grant workspace read access to modules without a manifest.
+    #[serde(default)]
+    pub allow_workspace_read: bool,
+
+    /// Grant workspace write access to modules without a manifest.
+    #[serde(default)]
+    pub allow_workspace_write: bool,
+
+    /// HTTP hosts modules may reach (reserved; no network host functions yet).
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+}
+
+fn default_wasm_tools_dir() -> String {
+    "tools/wasm".into()
+}
+
+fn default_wasm_fuel_limit() -> u64 {
+    1_000_000
+}
+
+fn default_wasm_memory_limit_mb() -> u64 {
+    64
+}
+
+impl Default for WasmRuntimeConfig {
+    fn default() -> Self {
+        Self {
+            tools_dir: default_wasm_tools_dir(),
+            fuel_limit: default_wasm_fuel_limit(),
+            memory_limit_mb: default_wasm_memory_limit_mb(),
+            allow_workspace_read: false,
+            allow_workspace_write: false,
+            allowed_hosts: Vec::new(),
+        }
+    }
+}
+
 fn default_runtime_kind() -> String {
     "native".into()
 }
@@ -2093,6 +2464,7 @@ impl Default for RuntimeConfig {
         Self {
             kind: default_runtime_kind(),
             docker: DockerRuntimeConfig::default(),
+            wasm: WasmRuntimeConfig::default(),
             reasoning_enabled: None,
         }
     }
@@ -2134,6 +2506,66 @@ pub struct ReliabilityConfig {
     /// Max retries for cron job execution attempts.
     #[serde(default = "default_scheduler_retries")]
     pub scheduler_retries: u32,
+    /// Per-provider and per-model circuit breakers used to skip failing
+    /// candidates and route to the healthiest one.
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerConfig,
+}
+
+/// Circuit breaker settings for provider failover (`[reliability.circuit_breaker]`).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CircuitBreakerConfig {
+    /// Skip open circuits and order candidates by health.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Rolling window (seconds) for error-rate and latency statistics.
+    #[serde(default = "default_circuit_window_secs")]
+    pub window_secs: u64,
+    /// Calls required in the window before the error rate can open a circuit.
+    #[serde(default = "default_circuit_min_requests")]
+    pub min_requests: u32,
+    /// Error rate (0.0–1.0) at which a circuit opens.
+    #[serde(default = "default_circuit_failure_rate_threshold")]
+    pub failure_rate_threshold: f64,
+    /// Seconds an open circuit waits before letting a half-open probe through.
+    #[serde(default = "default_circuit_open_secs")]
+    pub open_secs: u64,
+    /// Calls slower than this (ms, p95 over the window) rank a candidate lower.
+    #[serde(default = "default_circuit_slow_call_ms")]
+    pub slow_call_ms: u64,
+}
+
+fn default_circuit_window_secs() -> u64 {
+    300
+}
+
+fn default_circuit_min_requests() -> u32 {
+    5
+}
+
+fn default_circuit_failure_rate_threshold() -> f64 {
+    0.5
+}
+
+fn default_circuit_open_secs() -> u64 {
+    60
+}
+
+fn default_circuit_slow_call_ms() -> u64 {
+    30_000
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window_secs: default_circuit_window_secs(),
+            min_requests: default_circuit_min_requests(),
+            failure_rate_threshold: default_circuit_failure_rate_threshold(),
+            open_secs: default_circuit_open_secs(),
+            slow_call_ms: default_circuit_slow_call_ms(),
+        }
+    }
 }
 
 fn default_provider_retries() -> u32 {
@@ -2172,6 +2604,7 @@ impl Default for ReliabilityConfig {
             channel_max_backoff_secs: default_channel_backoff_max_secs(),
             scheduler_poll_secs: default_scheduler_poll_secs(),
             scheduler_retries: default_scheduler_retries(),
+            circuit_breaker: CircuitBreakerConfig::default(),
         }
     }
 }
@@ -2262,7 +2695,7 @@ pub struct ModelRouteConfig {
 pub struct EmbeddingRouteConfig {
     /// Route hint name (e.g. "semantic", "archive", "faq")
     pub hint: String,
-    /// Embedding provider (`none`, `openai`, or `custom:<url>`)
+    /// Embedding provider (`none`, `openai`, `custom:<url>`, or `local`)
     pub provider: String,
     /// Embedding model to use with that provider
     pub model: String,
@@ -2310,6 +2743,63 @@ pub struct ClassificationRule {
     pub priority: i32,
 }
 
+/// Automatic routing (`[auto_routing]` section).
+///
+/// When enabled, requests for the default model are sent to the cheapest
+/// candidate route expected to handle them, judged by request complexity,
+/// `[cost.prices]` and the latency and success rate observed this session.
+/// A failed call or an unparseable tool call escalates to the next pricier
+/// route. Explicit `hint:` requests keep using the route table. Disabled by
+/// default.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AutoRoutingConfig {
+    /// Enable automatic routing. Default: `false`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Route hints to choose from. Empty means every `[[model_routes]]`
+    /// entry. The default model is always a candidate.
+    #[serde(default)]
+    pub hints: Vec<String>,
+    /// Pricier routes to try after a failed or unparseable response. Default: `2`.
+    #[serde(default = "default_auto_routing_max_escalations")]
+    pub max_escalations: u32,
+    /// Skip routes whose observed success rate is below this (0.0–1.0). Default: `0.8`.
+    #[serde(default = "default_auto_routing_min_success_rate")]
+    pub min_success_rate: f64,
+    /// Calls to observe before a route's success rate is trusted. Default: `5`.
+    #[serde(default = "default_auto_routing_min_samples")]
+    pub min_samples: u32,
+    /// Skip routes whose average observed latency exceeds this many
+    /// milliseconds. `0` disables the check. Default: `0`.
+    #[serde(default)]
+    pub max_latency_ms: u64,
+}
+
+fn default_auto_routing_max_escalations() -> u32 {
+    2
+}
+
+fn default_auto_routing_min_success_rate() -> f64 {
+    0.8
+}
+
+fn default_auto_routing_min_samples() -> u32 {
+    5
+}
+
+impl Default for AutoRoutingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            hints: Vec::new(),
+            max_escalations: default_auto_routing_max_escalations(),
+            min_success_rate: default_auto_routing_min_success_rate(),
+            min_samples: default_auto_routing_min_samples(),
+            max_latency_ms: 0,
+        }
+    }
+}
+
 // ── Heartbeat ────────────────────────────────────────────────────
 
 /// Heartbeat configuration for periodic health pings (`[heartbeat]` section).
@@ -2478,6 +2968,10 @@ pub struct ChannelsConfig {
     pub linq: Option<LinqConfig>,
     /// Nextcloud Talk bot channel configuration.
     pub nextcloud_talk: Option<NextcloudTalkConfig>,
+    /// Microsoft Teams bot channel configuration (Bot Framework).
+    pub teams: Option<TeamsConfig>,
+    /// Built-in browser chat served by the gateway.
+    pub webchat: Option<WebChatConfig>,
     /// Email channel configuration.
     pub email: Option<crate::channels::email_channel::EmailConfig>,
     /// IRC channel configuration.
@@ -2545,6 +3039,14 @@ impl ChannelsConfig {
                 Box::new(ConfigWrapper::new(&self.nextcloud_talk)),
                 self.nextcloud_talk.is_some(),
             ),
+            (
+                Box::new(ConfigWrapper::new(&self.teams)),
+                self.teams.is_some(),
+            ),
+            (
+                Box::new(ConfigWrapper::new(&self.webchat)),
+                self.webchat.is_some(),
+            ),
             (
                 Box::new(ConfigWrapper::new(&self.email)),
                 self.email.is_some(),
@@ -2605,6 +3107,8 @@ impl Default for ChannelsConfig {
             whatsapp: None,
             linq: None,
             nextcloud_talk: None,
+            teams: None,
+            webchat: None,
             email: None,
             irc: None,
             lark: None,
@@ -2938,6 +3442,104 @@ impl ChannelConfig for NextcloudTalkConfig {
     }
 }
 
+/// Microsoft Teams bot configuration (Bot Framework webhook receive +
+/// connector API send).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TeamsConfig {
+    /// Microsoft App ID of the Azure Bot registration. Inbound tokens must
+    /// carry it as their audience.
+    pub app_id: String,
+    /// Client secret of the bot registration, used for connector API calls.
+    pub app_password: String,
+    /// Entra tenant for single-tenant bots. Default: multi-tenant
+    /// (`botframework.com`).
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+    /// OpenID metadata document listing the keys that sign inbound tokens.
+    #[serde(default = "default_teams_openid_metadata_url")]
+    pub openid_metadata_url: String,
+    /// OAuth token endpoint override. Default is derived from `tenant_id`.
+    #[serde(default)]
+    pub token_url: Option<String>,
+    /// Allowed Entra object IDs or Teams user IDs (`[]` = deny all, `"*"` = allow all).
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+    /// Reply with Adaptive Cards instead of plain Markdown messages. Default: `false`.
+    #[serde(default)]
+    pub adaptive_cards: bool,
+}
+
+fn default_teams_openid_metadata_url() -> String {
+    "https://login.botframework.com/v1/.well-known/openidconfiguration".into()
+}
+
+impl ChannelConfig for TeamsConfig {
+    fn name() -> &'static str {
+        "Teams"
+    }
+    fn desc() -> &'static str {
+        "Microsoft Teams via Bot Framework"
+    }
+}
+
+/// Built-in WebChat channel: browser visitors chat over the gateway
+/// (`/webchat/ws`), usually through the embeddable `/webchat/widget.js`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WebChatConfig {
+    /// Accept visitors without a signed visitor token; each browser gets a
+    /// random visitor ID. Requires `allowed_origins`. Default: `false`.
+    #[serde(default)]
+    pub allow_anonymous: bool,
+    /// HMAC-SHA256 secret for visitor tokens issued by the embedding site
+    /// (`<visitor_id>.<expires_unix>.<hex signature>`). Unset = token-scoped
+    /// sessions are disabled.
+    #[serde(default)]
+    pub visitor_secret: Option<String>,
+    /// Origins allowed to open chat sessions, e.g. `https://example.com`
+    /// (`[]` = any origin; not accepted with `allow_anonymous`).
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+    /// Maximum size of one uploaded file in MiB. Default: `10`.
+    #[serde(default = "default_webchat_max_upload_mb")]
+    pub max_upload_mb: usize,
+    /// Stream replies into the chat while they are generated. Default: `true`.
+    #[serde(default = "default_true")]
+    pub stream_drafts: bool,
+    /// Title shown in the widget header. Default: `"Chat"`.
+    #[serde(default = "default_webchat_title")]
+    pub title: String,
+}
+
+fn default_webchat_max_upload_mb() -> usize {
+    10
+}
+
+fn default_webchat_title() -> String {
+    "Chat".into()
+}
+
+impl Default for WebChatConfig {
+    fn default() -> Self {
+        Self {
+            allow_anonymous: false,
+            visitor_secret: None,
+            allowed_origins: Vec::new(),
+            max_upload_mb: default_webchat_max_upload_mb(),
+            stream_drafts: true,
+            title: default_webchat_title(),
+        }
+    }
+}
+
+impl ChannelConfig for WebChatConfig {
+    fn name() -> &'static str {
+        "WebChat"
+    }
+    fn desc() -> &'static str {
+        "Browser chat served by the gateway"
+    }
+}
+
 impl WhatsAppConfig {
     /// Detect which backend to use based on config fields.
     /// Returns "cloud" if phone_number_id is set, "web" if session_path is set.
@@ -3093,6 +3695,10 @@ pub struct SandboxConfig {
     /// Custom Firejail arguments (when backend = firejail)
     #[serde(default)]
     pub firejail_args: Vec<String>,
+
+    /// Seccomp syscall filter layered on top of the selected backend
+    #[serde(default)]
+    pub seccomp: SeccompConfig,
 }
 
 impl Default for SandboxConfig {
@@ -3101,6 +3707,95 @@ impl Default for SandboxConfig {
             enabled: None, // Auto-detect
             backend: SandboxBackend::Auto,
             firejail_args: Vec::new(),
+            seccomp: SeccompConfig::default(),
+        }
+    }
+}
+
+impl SandboxConfig {
+    /// Whether shell commands should get a seccomp filter, either as the
+    /// selected backend or stacked on top of it.
+    pub fn seccomp_enabled(&self) -> bool {
+        self.enabled != Some(false)
+            && !matches!(self.backend, SandboxBackend::None)
+            && (self.seccomp.enabled || matches!(self.backend, SandboxBackend::Seccomp))
+    }
+}
+
+/// Seccomp-BPF syscall filtering for shell commands (Linux only).
+///
+/// The profile for each command is chosen from `tool_profiles` first, then
+/// from the command's risk level as classified by the security policy.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SeccompConfig {
+    /// Install a seccomp filter on spawned commands. Stacks with Landlock.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Profile for low-risk commands
+    #[serde(default = "default_seccomp_low_risk_profile")]
+    pub low_risk_profile: SeccompProfile,
+
+    /// Profile for medium-risk commands
+    #[serde(default = "default_seccomp_medium_risk_profile")]
+    pub medium_risk_profile: SeccompProfile,
+
+    /// Profile for high-risk commands
+    #[serde(default = "default_seccomp_high_risk_profile")]
+    pub high_risk_profile: SeccompProfile,
+
+    /// Per-tool profile overrides keyed by tool name (e.g. `shell`)
+    #[serde(default)]
+    pub tool_profiles: HashMap<String, SeccompProfile>,
+}
+
+fn default_seccomp_low_risk_profile() -> SeccompProfile {
+    SeccompProfile::Build
+}
+
+fn default_seccomp_medium_risk_profile() -> SeccompProfile {
+    SeccompProfile::Build
+}
+
+fn default_seccomp_high_risk_profile() -> SeccompProfile {
+    SeccompProfile::NetworkDenied
+}
+
+impl Default for SeccompConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            low_risk_profile: default_seccomp_low_risk_profile(),
+            medium_risk_profile: default_seccomp_medium_risk_profile(),
+            high_risk_profile: default_seccomp_high_risk_profile(),
+            tool_profiles: HashMap::new(),
+        }
+    }
+}
+
+/// Syscall filter profile applied by the seccomp sandbox.
+///
+/// Every profile denies privileged kernel interfaces (mount, ptrace, module
+/// loading, bpf, namespaces, io_uring). Profiles are ordered from least to
+/// most restrictive: `build` < `network_denied` < `read_only`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SeccompProfile {
+    /// Compilers and package managers: file writes and network allowed
+    #[default]
+    Build,
+    /// Like `build`, but only Unix domain sockets may be created
+    NetworkDenied,
+    /// Inspection only: no network and no filesystem mutation
+    ReadOnly,
+}
+
+impl SeccompProfile {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Build => "build",
+            Self::NetworkDenied => "network_denied",
+            Self::ReadOnly => "read_only",
         }
     }
 }
@@ -3120,6 +3815,8 @@ pub enum SandboxBackend {
     Bubblewrap,
     /// Docker container isolation
     Docker,
+    /// Seccomp-BPF syscall filter only (Linux)
+    Seccomp,
     /// No sandboxing (application-layer only)
     None,
 }
@@ -3127,33 +3824,44 @@ pub enum SandboxBackend {
 /// Resource limits for command execution
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ResourceLimitsConfig {
-    /// Maximum memory in MB per command
+    /// Apply the limits below to spawned commands. The defaults are sized to
+    /// leave room for builds and test suites; set `false` to run uncapped.
+    #[serde(default = "default_resource_limits_enabled")]
+    pub enabled: bool,
+
+    /// Maximum memory in MB per command (cgroup `memory.max`, or a
+    /// per-process rlimit without a cgroup). `0` disables the cap.
     #[serde(default = "default_max_memory_mb")]
     pub max_memory_mb: u32,
 
-    /// Maximum CPU time in seconds per command
+    /// Maximum CPU time in seconds per command (`RLIMIT_CPU`). `0` disables the cap.
     #[serde(default = "default_max_cpu_time_seconds")]
     pub max_cpu_time_seconds: u64,
 
-    /// Maximum number of subprocesses
+    /// Maximum processes per command tree (cgroup `pids.max`; only enforced
+    /// when a delegated cgroup v2 subtree is available). `0` disables the cap.
     #[serde(default = "default_max_subprocesses")]
     pub max_subprocesses: u32,
 
-    /// Enable memory monitoring
+    /// Enforce `max_memory_mb`. When `false` only the CPU and process caps apply.
     #[serde(default = "default_memory_monitoring_enabled")]
     pub memory_monitoring: bool,
 }
 
+fn default_resource_limits_enabled() -> bool {
+    true
+}
+
 fn default_max_memory_mb() -> u32 {
-    512
+    2048
 }
 
 fn default_max_cpu_time_seconds() -> u64 {
-    60
+    600
 }
 
 fn default_max_subprocesses() -> u32 {
-    10
+    256
 }
 
 fn default_memory_monitoring_enabled() -> bool {
@@ -3163,6 +3871,7 @@ fn default_memory_monitoring_enabled() -> bool {
 impl Default for ResourceLimitsConfig {
     fn default() -> Self {
         Self {
+            enabled: default_resource_limits_enabled(),
             max_memory_mb: default_max_memory_mb(),
             max_cpu_time_seconds: default_max_cpu_time_seconds(),
             max_subprocesses: default_max_subprocesses(),
@@ -3325,14 +4034,18 @@ impl Default for Config {
             http_request: HttpRequestConfig::default(),
             multimodal: MultimodalConfig::default(),
             web_search: WebSearchConfig::default(),
+            github: GitHubConfig::default(),
+            home_assistant: HomeAssistantConfig::default(),
             proxy: ProxyConfig::default(),
             identity: IdentityConfig::default(),
+            security: SecurityConfig::default(),
             cost: CostConfig::default(),
             peripherals: PeripheralsConfig::default(),
             agents: HashMap::new(),
             hooks: HooksConfig::default(),
             hardware: HardwareConfig::default(),
             query_classification: QueryClassificationConfig::default(),
+            auto_routing: AutoRoutingConfig::default(),
             transcription: TranscriptionConfig::default(),
             providers: HashMap::new(),
         }
@@ -3728,6 +4441,14 @@ impl Config {
                 "config.web_search.brave_api_key",
             )?;
 
+            decrypt_optional_secret(&store, &mut config.github.token, "config.github.token")?;
+
+            decrypt_optional_secret(
+                &store,
+                &mut config.home_assistant.token,
+                "config.home_assistant.token",
+            )?;
+
             decrypt_optional_secret(
                 &store,
                 &mut config.storage.provider.config.db_url,
@@ -3846,6 +4567,53 @@ impl Config {
             }
         }
 
+        // Obsidian memory backend
+        if self.memory.backend.trim().eq_ignore_ascii_case("obsidian")
+            && self
+                .memory
+                .obsidian
+                .vault_path
+                .as_deref()
+                .is_none_or(|path| path.trim().is_empty())
+        {
+            anyhow::bail!(
+                "memory.obsidian.vault_path must be set when memory.backend = \"obsidian\""
+            );
+        }
+
+        // GitHub default repository
+        if let Some(repo) = &self.github.repo {
+            let mut parts = repo.trim().split('/');
+            let valid = matches!(
+                (parts.next(), parts.next(), parts.next()),
+                (Some(owner), Some(name), None) if !owner.is_empty() && !name.is_empty()
+            );
+            if !valid {
+                anyhow::bail!("github.repo must be in owner/name form");
+            }
+        }
+
+        // WebChat: anonymous visitors only from known embedding sites
+        if let Some(webchat) = &self.channels_config.webchat {
+            if webchat.allow_anonymous && webchat.allowed_origins.is_empty() {
+                anyhow::bail!(
+                    "channels_config.webchat.allow_anonymous requires non-empty allowed_origins"
+                );
+            }
+        }
+
+        // Home Assistant triggers
+        for (i, trigger) in self.home_assistant.triggers.iter().enumerate() {
+            if trigger.entity_id.trim().is_empty() {
+                anyhow::bail!("home_assistant.triggers[{i}].entity_id must not be empty");
+            }
+            if trigger.prompt.is_some() == trigger.cron_job.is_some() {
+                anyhow::bail!(
+                    "home_assistant.triggers[{i}] must set exactly one of prompt or cron_job"
+                );
+            }
+        }
+
         // Ollama cloud-routing safety checks
         if self
             .default_provider
@@ -4054,6 +4822,26 @@ impl Config {
             }
         }
 
+        // GitHub token: ZEROCLAW_GITHUB_TOKEN or GITHUB_TOKEN
+        if let Ok(token) =
+            std::env::var("ZEROCLAW_GITHUB_TOKEN").or_else(|_| std::env::var("GITHUB_TOKEN"))
+        {
+            let token = token.trim();
+            if !token.is_empty() {
+                self.github.token = Some(token.to_string());
+            }
+        }
+
+        // Home Assistant token: ZEROCLAW_HOME_ASSISTANT_TOKEN or HASS_TOKEN
+        if let Ok(token) =
+            std::env::var("ZEROCLAW_HOME_ASSISTANT_TOKEN").or_else(|_| std::env::var("HASS_TOKEN"))
+        {
+            let token = token.trim();
+            if !token.is_empty() {
+                self.home_assistant.token = Some(token.to_string());
+            }
+        }
+
         // Web search max results: ZEROCLAW_WEB_SEARCH_MAX_RESULTS or WEB_SEARCH_MAX_RESULTS
         if let Ok(max_results) = std::env::var("ZEROCLAW_WEB_SEARCH_MAX_RESULTS")
             .or_else(|_| std::env::var("WEB_SEARCH_MAX_RESULTS"))
@@ -4198,6 +4986,18 @@ impl Config {
             "config.web_search.brave_api_key",
         )?;
 
+        encrypt_optional_secret(
+            &store,
+            &mut config_to_save.github.token,
+            "config.github.token",
+        )?;
+
+        encrypt_optional_secret(
+            &store,
+            &mut config_to_save.home_assistant.token,
+            "config.home_assistant.token",
+        )?;
+
         encrypt_optional_secret(
             &store,
             &mut config_to_save.storage.provider.config.db_url,
@@ -4529,6 +5329,8 @@ default_temperature = 0.7
                 always_ask: vec![],
                 allowed_roots: vec![],
                 non_cli_excluded_tools: vec![],
+                remote_approvals: false,
+                approval_timeout_secs: 300,
             },
             runtime: RuntimeConfig {
                 kind: "docker".into(),
@@ -4540,6 +5342,7 @@ default_temperature = 0.7
             model_routes: Vec::new(),
             embedding_routes: Vec::new(),
             query_classification: QueryClassificationConfig::default(),
+            auto_routing: AutoRoutingConfig::default(),
             heartbeat: HeartbeatConfig {
                 enabled: true,
                 interval_minutes: 15,
@@ -4565,6 +5368,8 @@ default_temperature = 0.7
                 whatsapp: None,
                 linq: None,
                 nextcloud_talk: None,
+                teams: None,
+                webchat: None,
                 email: None,
                 irc: None,
                 lark: None,
@@ -4584,9 +5389,12 @@ default_temperature = 0.7
             http_request: HttpRequestConfig::default(),
             multimodal: MultimodalConfig::default(),
             web_search: WebSearchConfig::default(),
+            github: GitHubConfig::default(),
+            home_assistant: HomeAssistantConfig::default(),
             proxy: ProxyConfig::default(),
             agent: AgentConfig::default(),
             identity: IdentityConfig::default(),
+            security: SecurityConfig::default(),
             cost: CostConfig::default(),
             peripherals: PeripheralsConfig::default(),
             agents: HashMap::new(),
@@ -4685,6 +5493,7 @@ reasoning_enabled = false
         assert!(!cfg.compact_context);
         assert_eq!(cfg.max_tool_iterations, 10);
         assert_eq!(cfg.max_history_messages, 50);
+        assert_eq!(cfg.context_window_tokens, None);
         assert!(!cfg.parallel_tools);
         assert_eq!(cfg.tool_dispatcher, "auto");
     }
@@ -4697,6 +5506,7 @@ default_temperature = 0.7
 compact_context = true
 max_tool_iterations = 20
 max_history_messages = 80
+context_window_tokens = 16384
 parallel_tools = true
 tool_dispatcher = "xml"
 "#;
@@ -4704,6 +5514,7 @@ tool_dispatcher = "xml"
         assert!(parsed.agent.compact_context);
         assert_eq!(parsed.agent.max_tool_iterations, 20);
         assert_eq!(parsed.agent.max_history_messages, 80);
+        assert_eq!(parsed.agent.context_window_tokens, Some(16384));
         assert!(parsed.agent.parallel_tools);
         assert_eq!(parsed.agent.tool_dispatcher, "xml");
     }
@@ -4745,6 +5556,7 @@ tool_dispatcher = "xml"
             model_routes: Vec::new(),
             embedding_routes: Vec::new(),
             query_classification: QueryClassificationConfig::default(),
+            auto_routing: AutoRoutingConfig::default(),
             heartbeat: HeartbeatConfig::default(),
             cron: CronConfig::default(),
             channels_config: ChannelsConfig::default(),
@@ -4758,9 +5570,12 @@ tool_dispatcher = "xml"
             http_request: HttpRequestConfig::default(),
             multimodal: MultimodalConfig::default(),
             web_search: WebSearchConfig::default(),
+            github: GitHubConfig::default(),
+            home_assistant: HomeAssistantConfig::default(),
             proxy: ProxyConfig::default(),
             agent: AgentConfig::default(),
             identity: IdentityConfig::default(),
+            security: SecurityConfig::default(),
             cost: CostConfig::default(),
             peripherals: PeripheralsConfig::default(),
             agents: HashMap::new(),
@@ -5167,6 +5982,8 @@ allowed_users = ["@ops:matrix.org"]
             whatsapp: None,
             linq: None,
             nextcloud_talk: None,
+            teams: None,
+            webchat: None,
             email: None,
             irc: None,
             lark: None,
@@ -5379,6 +6196,8 @@ channel_id = "C123"
             }),
             linq: None,
             nextcloud_talk: None,
+            teams: None,
+            webchat: None,
             email: None,
             irc: None,
             lark: None,
@@ -5926,6 +6745,71 @@ default_temperature = 0.7
         ));
     }
 
+    #[test]
+    async fn github_writes_always_ask_by_default() {
+        let config = Config::default();
+        assert!(config
+            .autonomy
+            .always_ask
+            .contains(&"github_write".to_string()));
+        assert_eq!(config.github.api_url, "https://api.github.com");
+
+        let mut config = Config::default();
+        config.github.repo = Some("acme/widgets".into());
+        assert!(config.validate().is_ok());
+        config.github.repo = Some("widgets".into());
+        let error = config.validate().expect_err("expected validation to fail");
+        assert!(error
+            .to_string()
+            .contains("github.repo must be in owner/name form"));
+    }
+
+    #[test]
+    async fn validate_webchat_anonymous_needs_allowed_origins() {
+        let mut config = Config::default();
+        config.channels_config.webchat = Some(WebChatConfig::default());
+        assert!(config.validate().is_ok());
+
+        config.channels_config.webchat = Some(WebChatConfig {
+            allow_anonymous: true,
+            ..WebChatConfig::default()
+        });
+        let error = config.validate().expect_err("expected validation to fail");
+        assert!(error
+            .to_string()
+            .contains("allow_anonymous requires non-empty allowed_origins"));
+
+        config.channels_config.webchat = Some(WebChatConfig {
+            allow_anonymous: true,
+            allowed_origins: vec!["https://example.com".into()],
+            ..WebChatConfig::default()
+        });
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    async fn validate_home_assistant_trigger_needs_one_action() {
+        let trigger: HomeAssistantTrigger = toml::from_str(
+            r#"
+entity_id = "binary_sensor.front_door"
+to = "on"
+prompt = "Someone opened the front door"
+"#,
+        )
+        .unwrap();
+        assert_eq!(trigger.cooldown_secs, 60);
+
+        let mut config = Config::default();
+        config.home_assistant.triggers.push(trigger.clone());
+        assert!(config.validate().is_ok());
+
+        config.home_assistant.triggers[0].cron_job = Some("job-1".into());
+        let error = config.validate().expect_err("expected validation to fail");
+        assert!(error
+            .to_string()
+            .contains("must set exactly one of prompt or cron_job"));
+    }
+
     #[test]
     async fn validate_ollama_cloud_model_accepts_remote_endpoint_and_env_key() {
         let _env_guard = env_override_lock().await;
@@ -6744,6 +7628,31 @@ default_model = "legacy-model"
         assert_eq!(parsed.allowed_users, vec!["user_a", "*"]);
     }
 
+    #[test]
+    async fn teams_config_defaults_optional_fields() {
+        let json = r#"{"app_id":"app-id","app_password":"secret"}"#;
+        let parsed: TeamsConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            parsed.openid_metadata_url,
+            "https://login.botframework.com/v1/.well-known/openidconfiguration"
+        );
+        assert!(parsed.tenant_id.is_none());
+        assert!(parsed.token_url.is_none());
+        assert!(parsed.allowed_users.is_empty());
+        assert!(!parsed.adaptive_cards);
+    }
+
+    #[test]
+    async fn webchat_config_defaults() {
+        let parsed: WebChatConfig = toml::from_str("").unwrap();
+        assert!(!parsed.allow_anonymous);
+        assert!(parsed.visitor_secret.is_none());
+        assert!(parsed.allowed_origins.is_empty());
+        assert_eq!(parsed.max_upload_mb, 10);
+        assert!(parsed.stream_drafts);
+        assert_eq!(parsed.title, "Chat");
+    }
+
     #[test]
     async fn nextcloud_talk_config_defaults_optional_fields() {
         let json = r#"{"base_url":"https://cloud.example.com","app_token":"app-token"}"#;
@@ -0,0 +1,161 @@
+//! Cost attribution and budget enforcement for a single conversation.
+
+use super::tracker::{shared_tracker, CostTracker};
+use super::types::{BudgetCheck, CostAttribution, UsagePeriod};
+use crate::config::Config;
+use crate::providers::traits::TokenUsage as ProviderTokenUsage;
+use std::sync::Arc;
+
+/// A tracker plus the attribution that spend from one conversation is
+/// charged to. Passed into the tool-call loop, which checks budgets before
+/// each model call and records usage after it.
+#[derive(Clone)]
+pub struct CostContext {
+    tracker: Arc<CostTracker>,
+    attribution: CostAttribution,
+}
+
+impl CostContext {
+    pub fn new(tracker: Arc<CostTracker>, attribution: CostAttribution) -> Self {
+        Self {
+            tracker,
+            attribution,
+        }
+    }
+
+    /// Context for `config`, or `None` when cost tracking is disabled or the
+    /// tracker cannot be opened.
+    pub fn from_config(config: &Config, attribution: CostAttribution) -> Option<Self> {
+        if !config.cost.enabled {
+            return None;
+        }
+        match shared_tracker(&config.cost, &config.workspace_dir) {
+            Ok(tracker) => Some(Self::new(tracker, attribution)),
+            Err(e) => {
+                tracing::warn!("Cost tracking unavailable: {e}");
+                None
+            }
+        }
+    }
+
+    pub fn attribution(&self) -> &CostAttribution {
+        &self.attribution
+    }
+
+    /// The same tracker, charged to the delegate agent `agent`.
+    pub fn for_agent(&self, agent: &str) -> Self {
+        Self::new(
+            Arc::clone(&self.tracker),
+            self.attribution.with_agent(agent),
+        )
+    }
+
+    /// Fail when a denying budget is exhausted. Warnings are logged.
+    pub fn ensure_within_budget(&self) -> anyhow::Result<()> {
+        match self.tracker.check_budget_for(0.0, &self.attribution)? {
+            BudgetCheck::Allowed => Ok(()),
+            BudgetCheck::Warning {
+                current_usd,
+                limit_usd,
+                period,
+                scope,
+            } => {
+                tracing::warn!(
+                    "{} is at ${current_usd:.4} of its ${limit_usd:.2} {} limit",
+                    scope_label(scope.as_deref()),
+                    period_label(period)
+                );
+                Ok(())
+            }
+            BudgetCheck::Exceeded {
+                current_usd,
+                limit_usd,
+                period,
+                scope,
+            } => anyhow::bail!(
+                "{} exceeded: ${current_usd:.4} spent of the ${limit_usd:.2} {} limit",
+                scope_label(scope.as_deref()),
+                period_label(period)
+            ),
+        }
+    }
+
+    /// Price and record provider-reported usage. Failures are logged, never
+    /// surfaced to the conversation.
+    pub fn record(&self, provider: &str, model: &str, usage: &ProviderTokenUsage) {
+        if let Err(e) =
+            self.tracker
+                .record_provider_usage_for(provider, model, usage, &self.attribution)
+        {
+            tracing::warn!("Failed to record cost for {model}: {e}");
+        }
+    }
+}
+
+fn scope_label(scope: Option<&str>) -> String {
+    match scope {
+        Some(scope) => format!("Budget for {scope}"),
+        None => "Global budget".to_string(),
+    }
+}
+
+fn period_label(period: UsagePeriod) -> &'static str {
+    match period {
+        UsagePeriod::Session => "session",
+        UsagePeriod::Day => "daily",
+        UsagePeriod::Month => "monthly",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::schema::BudgetLimitConfig;
+    use tempfile::TempDir;
+
+    fn test_config(tmp: &TempDir) -> Config {
+        let mut config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        };
+        config.cost.enabled = true;
+        config
+    }
+
+    #[test]
+    fn from_config_is_none_when_cost_tracking_disabled() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = test_config(&tmp);
+        config.cost.enabled = false;
+        assert!(CostContext::from_config(&config, CostAttribution::default()).is_none());
+    }
+
+    #[test]
+    fn exhausted_scoped_budget_fails_with_scope_in_message() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = test_config(&tmp);
+        config.cost.budgets.channel.insert(
+            "telegram".into(),
+            BudgetLimitConfig {
+                daily_limit_usd: Some(0.001),
+                ..Default::default()
+            },
+        );
+        let tracker = Arc::new(CostTracker::new(config.cost.clone(), tmp.path()).unwrap());
+        let ctx = CostContext::new(tracker, CostAttribution::channel("telegram", "alice"));
+        assert!(ctx.ensure_within_budget().is_ok());
+
+        ctx.record(
+            "anthropic",
+            "claude-sonnet-4-20250514",
+            &ProviderTokenUsage {
+                input_tokens: Some(1_000),
+                ..ProviderTokenUsage::default()
+            },
+        );
+
+        let err = ctx.ensure_within_budget().unwrap_err();
+        assert!(err.to_string().contains("channel:telegram"));
+        assert!(err.to_string().contains("daily"));
+    }
+}
@@ -1,8 +1,53 @@
+pub mod context;
 pub mod tracker;
 pub mod types;
 
 // Re-exported for potential external use (public API)
 #[allow(unused_imports)]
-pub use tracker::CostTracker;
+pub use context::CostContext;
 #[allow(unused_imports)]
-pub use types::{BudgetCheck, CostRecord, CostSummary, ModelStats, TokenUsage, UsagePeriod};
+pub use tracker::{lookup_pricing, shared_tracker, CostTracker};
+#[allow(unused_imports)]
+pub use types::{
+    BudgetCheck, CostAttribution, CostBreakdownEntry, CostDimension, CostRecord, CostSummary,
+    ModelPerformance, ModelStats, TokenUsage, UsagePeriod,
+};
+
+use crate::config::Config;
+use anyhow::Result;
+
+/// Handle `zeroclaw cost` subcommands.
+pub fn handle_command(command: crate::CostCommands, config: &Config) -> Result<()> {
+    match command {
+        crate::CostCommands::Report { by, period } => {
+            let dimension: CostDimension = by.parse().map_err(anyhow::Error::msg)?;
+            let period: UsagePeriod = period.parse().map_err(anyhow::Error::msg)?;
+            let tracker = CostTracker::new(config.cost.clone(), &config.workspace_dir)?;
+            let entries = tracker.breakdown(dimension, period)?;
+
+            if entries.is_empty() {
+                println!("No spend recorded for this period.");
+                if !config.cost.enabled {
+                    println!(
+                        "Cost tracking is disabled; set [cost] enabled = true in config.toml."
+                    );
+                }
+                return Ok(());
+            }
+
+            let total: f64 = entries.iter().map(|entry| entry.cost_usd).sum();
+            println!(
+                "💰 Spend by {} ({:?}): ${total:.4}",
+                dimension.as_str(),
+                period
+            );
+            for entry in entries {
+                println!(
+                    "- {} | ${:.4} | {} token(s) | {} request(s)",
+                    entry.key, entry.cost_usd, entry.total_tokens, entry.request_count
+                );
+            }
+            Ok(())
+        }
+    }
+}
@@ -1,5 +1,9 @@
-use super::types::{BudgetCheck, CostRecord, CostSummary, ModelStats, TokenUsage, UsagePeriod};
-use crate::config::schema::CostConfig;
+use super::types::{
+    BudgetCheck, CostAttribution, CostBreakdownEntry, CostDimension, CostRecord, CostSummary,
+    ModelPerformance, ModelStats, TokenUsage, UsagePeriod,
+};
+use crate::config::schema::{BudgetAction, BudgetLimitConfig, CostConfig, ModelPricing};
+use crate::providers::traits::TokenUsage as ProviderTokenUsage;
 use anyhow::{anyhow, Context, Result};
 use chrono::{Datelike, NaiveDate, Utc};
 use parking_lot::{Mutex, MutexGuard};
@@ -7,7 +11,27 @@ use std::collections::HashMap;
 use std::fs::{self, File, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+/// Find the `[cost.prices]` entry for `model` served by `provider`.
+///
+/// Entries may be keyed by the model name as sent to the provider or as
+/// `{provider}/{model}` (the form the default table uses for direct
+/// providers); the exact name wins.
+pub fn lookup_pricing<'a>(
+    prices: &'a HashMap<String, ModelPricing>,
+    provider: &str,
+    model: &str,
+) -> Option<&'a ModelPricing> {
+    prices.get(model).or_else(|| {
+        if provider.is_empty() {
+            None
+        } else {
+            prices.get(&format!("{provider}/{model}"))
+        }
+    })
+}
 
 /// Cost tracker for API usage monitoring and budget enforcement.
 pub struct CostTracker {
@@ -15,6 +39,8 @@ pub struct CostTracker {
     storage: Arc<Mutex<CostStorage>>,
     session_id: String,
     session_costs: Arc<Mutex<Vec<CostRecord>>>,
+    /// Per-model call outcomes for this session, used by automatic routing.
+    performance: Arc<Mutex<HashMap<String, ModelPerformance>>>,
 }
 
 impl CostTracker {
@@ -31,9 +57,21 @@ impl CostTracker {
             storage: Arc::new(Mutex::new(storage)),
             session_id: uuid::Uuid::new_v4().to_string(),
             session_costs: Arc::new(Mutex::new(Vec::new())),
+            performance: Arc::new(Mutex::new(HashMap::new())),
         })
     }
 
+    /// The same storage and session under a different configuration.
+    fn with_config(&self, config: CostConfig) -> Self {
+        Self {
+            config,
+            storage: Arc::clone(&self.storage),
+            session_id: self.session_id.clone(),
+            session_costs: Arc::clone(&self.session_costs),
+            performance: Arc::clone(&self.performance),
+        }
+    }
+
     /// Get the session ID.
     pub fn session_id(&self) -> &str {
         &self.session_id
@@ -47,8 +85,21 @@ impl CostTracker {
         self.session_costs.lock()
     }
 
-    /// Check if a request is within budget.
+    /// Check if a request is within the global budget.
     pub fn check_budget(&self, estimated_cost_usd: f64) -> Result<BudgetCheck> {
+        self.check_budget_for(estimated_cost_usd, &CostAttribution::default())
+    }
+
+    /// Check if a request is within the global budget and every scoped
+    /// budget that applies to `attribution`.
+    ///
+    /// A denying limit that would be exceeded wins over any warning; among
+    /// warnings the first one found (global before scoped) is returned.
+    pub fn check_budget_for(
+        &self,
+        estimated_cost_usd: f64,
+        attribution: &CostAttribution,
+    ) -> Result<BudgetCheck> {
         if !self.config.enabled {
             return Ok(BudgetCheck::Allowed);
         }
@@ -62,52 +113,80 @@ impl CostTracker {
         let mut storage = self.lock_storage();
         let (daily_cost, monthly_cost) = storage.get_aggregated_costs()?;
 
-        // Check daily limit
-        let projected_daily = daily_cost + estimated_cost_usd;
-        if projected_daily > self.config.daily_limit_usd {
-            return Ok(BudgetCheck::Exceeded {
-                current_usd: daily_cost,
-                limit_usd: self.config.daily_limit_usd,
-                period: UsagePeriod::Day,
-            });
-        }
-
-        // Check monthly limit
-        let projected_monthly = monthly_cost + estimated_cost_usd;
-        if projected_monthly > self.config.monthly_limit_usd {
-            return Ok(BudgetCheck::Exceeded {
-                current_usd: monthly_cost,
-                limit_usd: self.config.monthly_limit_usd,
-                period: UsagePeriod::Month,
-            });
+        let global = BudgetLimitConfig {
+            daily_limit_usd: Some(self.config.daily_limit_usd),
+            monthly_limit_usd: Some(self.config.monthly_limit_usd),
+            action: BudgetAction::Deny,
+        };
+        let mut scopes = vec![(None, &global, daily_cost, monthly_cost)];
+        for dimension in SCOPED_DIMENSIONS {
+            let Some(key) = attribution.key(dimension) else {
+                continue;
+            };
+            if let Some(limit) = self.scoped_limit(dimension, &key) {
+                let (daily, monthly) = storage.scoped_costs(dimension, &key)?;
+                scopes.push((
+                    Some(format!("{}:{key}", dimension.as_str())),
+                    limit,
+                    daily,
+                    monthly,
+                ));
+            }
         }
 
-        // Check warning thresholds
         let warn_threshold = f64::from(self.config.warn_at_percent.min(100)) / 100.0;
-        let daily_warn_threshold = self.config.daily_limit_usd * warn_threshold;
-        let monthly_warn_threshold = self.config.monthly_limit_usd * warn_threshold;
-
-        if projected_daily >= daily_warn_threshold {
-            return Ok(BudgetCheck::Warning {
-                current_usd: daily_cost,
-                limit_usd: self.config.daily_limit_usd,
-                period: UsagePeriod::Day,
-            });
+        let mut warning = None;
+        for (scope, limit, daily, monthly) in scopes {
+            for (current_usd, limit_usd, period) in [
+                (daily, limit.daily_limit_usd, UsagePeriod::Day),
+                (monthly, limit.monthly_limit_usd, UsagePeriod::Month),
+            ] {
+                let Some(limit_usd) = limit_usd else {
+                    continue;
+                };
+                let projected = current_usd + estimated_cost_usd;
+                if projected > limit_usd && limit.action == BudgetAction::Deny {
+                    return Ok(BudgetCheck::Exceeded {
+                        current_usd,
+                        limit_usd,
+                        period,
+                        scope,
+                    });
+                }
+                if warning.is_none() && projected >= limit_usd * warn_threshold {
+                    warning = Some(BudgetCheck::Warning {
+                        current_usd,
+                        limit_usd,
+                        period,
+                        scope: scope.clone(),
+                    });
+                }
+            }
         }
 
-        if projected_monthly >= monthly_warn_threshold {
-            return Ok(BudgetCheck::Warning {
-                current_usd: monthly_cost,
-                limit_usd: self.config.monthly_limit_usd,
-                period: UsagePeriod::Month,
-            });
-        }
+        Ok(warning.unwrap_or(BudgetCheck::Allowed))
+    }
 
-        Ok(BudgetCheck::Allowed)
+    /// Configured limit for `key`, falling back to the dimension's `"*"` entry.
+    fn scoped_limit(&self, dimension: CostDimension, key: &str) -> Option<&BudgetLimitConfig> {
+        let budgets = &self.config.budgets;
+        let table = match dimension {
+            CostDimension::Channel => &budgets.channel,
+            CostDimension::User => &budgets.user,
+            CostDimension::Job => &budgets.job,
+            CostDimension::Agent => &budgets.agent,
+            CostDimension::Model => return None,
+        };
+        table.get(key).or_else(|| table.get("*"))
     }
 
     /// Record a usage event.
     pub fn record_usage(&self, usage: TokenUsage) -> Result<()> {
+        self.record_usage_for(usage, &CostAttribution::default())
+    }
+
+    /// Record a usage event attributed to a channel, sender, job or agent.
+    pub fn record_usage_for(&self, usage: TokenUsage, attribution: &CostAttribution) -> Result<()> {
         if !self.config.enabled {
             return Ok(());
         }
@@ -118,7 +197,7 @@ impl CostTracker {
             ));
         }
 
-        let record = CostRecord::new(&self.session_id, usage);
+        let record = CostRecord::new(&self.session_id, usage).with_attribution(attribution.clone());
 
         // Persist first for durability guarantees.
         {
@@ -133,6 +212,65 @@ impl CostTracker {
         Ok(())
     }
 
+    /// Price provider-reported usage with the `[cost.prices]` entry for
+    /// `model` served by `provider` (see [`lookup_pricing`]) and record it.
+    /// Models without a price entry cost nothing.
+    pub fn record_provider_usage(
+        &self,
+        provider: &str,
+        model: &str,
+        usage: &ProviderTokenUsage,
+    ) -> Result<()> {
+        self.record_provider_usage_for(provider, model, usage, &CostAttribution::default())
+    }
+
+    /// Like [`Self::record_provider_usage`], attributing the spend.
+    pub fn record_provider_usage_for(
+        &self,
+        provider: &str,
+        model: &str,
+        usage: &ProviderTokenUsage,
+        attribution: &CostAttribution,
+    ) -> Result<()> {
+        let pricing = self
+            .pricing(provider, model)
+            .cloned()
+            .unwrap_or(ModelPricing {
+                input: 0.0,
+                output: 0.0,
+                cache_read: None,
+                cache_write: None,
+            });
+        self.record_usage_for(
+            TokenUsage::from_provider_usage(model, usage, &pricing),
+            attribution,
+        )
+    }
+
+    /// Configured `[cost.prices]` entry for `model` served by `provider`.
+    pub fn pricing(&self, provider: &str, model: &str) -> Option<&ModelPricing> {
+        lookup_pricing(&self.config.prices, provider, model)
+    }
+
+    /// Record the latency and outcome of one call to `model`. Kept in memory
+    /// for the session whether or not cost tracking is enabled.
+    pub fn record_call_outcome(&self, model: &str, latency: Duration, success: bool) {
+        let mut performance = self.performance.lock();
+        let entry = performance.entry(model.to_string()).or_default();
+        entry.requests += 1;
+        if success {
+            entry.successes += 1;
+        }
+        entry.total_latency_ms = entry
+            .total_latency_ms
+            .saturating_add(u64::try_from(latency.as_millis()).unwrap_or(u64::MAX));
+    }
+
+    /// Latency and success observed for `model` this session.
+    pub fn model_performance(&self, model: &str) -> Option<ModelPerformance> {
+        self.performance.lock().get(model).copied()
+    }
+
     /// Get the current cost summary.
     pub fn get_summary(&self) -> Result<CostSummary> {
         let (daily_cost, monthly_cost) = {
@@ -173,6 +311,85 @@ impl CostTracker {
         let storage = self.lock_storage();
         storage.get_cost_for_month(year, month)
     }
+
+    /// Break spend in `period` down by `dimension`, most expensive first.
+    /// Records without a key for the dimension are grouped as `unattributed`.
+    pub fn breakdown(
+        &self,
+        dimension: CostDimension,
+        period: UsagePeriod,
+    ) -> Result<Vec<CostBreakdownEntry>> {
+        let now = Utc::now();
+        let mut by_key: HashMap<String, CostBreakdownEntry> = HashMap::new();
+
+        let storage = self.lock_storage();
+        storage.for_each_record(|record| {
+            let timestamp = record.usage.timestamp;
+            let in_period = match period {
+                UsagePeriod::Session => record.session_id == self.session_id,
+                UsagePeriod::Day => timestamp.date_naive() == now.date_naive(),
+                UsagePeriod::Month => {
+                    timestamp.year() == now.year() && timestamp.month() == now.month()
+                }
+            };
+            if !in_period {
+                return;
+            }
+
+            let key = record
+                .key(dimension)
+                .unwrap_or_else(|| UNATTRIBUTED_KEY.to_string());
+            let entry = by_key
+                .entry(key.clone())
+                .or_insert_with(|| CostBreakdownEntry {
+                    key,
+                    cost_usd: 0.0,
+                    total_tokens: 0,
+                    request_count: 0,
+                });
+            entry.cost_usd += record.usage.cost_usd;
+            entry.total_tokens += record.usage.total_tokens;
+            entry.request_count += 1;
+        })?;
+
+        let mut entries: Vec<CostBreakdownEntry> = by_key.into_values().collect();
+        entries.sort_by(|a, b| {
+            b.cost_usd
+                .total_cmp(&a.cost_usd)
+                .then_with(|| a.key.cmp(&b.key))
+        });
+        Ok(entries)
+    }
+}
+
+/// Dimensions that can carry scoped budgets.
+const SCOPED_DIMENSIONS: [CostDimension; 4] = [
+    CostDimension::Channel,
+    CostDimension::User,
+    CostDimension::Job,
+    CostDimension::Agent,
+];
+
+const UNATTRIBUTED_KEY: &str = "unattributed";
+
+/// A tracker shared by every component of the process that writes to the same
+/// workspace, so cached daily/monthly totals see each other's spend.
+///
+/// When `config` differs from the one the shared tracker was built with (for
+/// example after a config reload), a tracker with the new limits and prices
+/// replaces it; storage and session totals carry over.
+pub fn shared_tracker(config: &CostConfig, workspace_dir: &Path) -> Result<Arc<CostTracker>> {
+    static TRACKERS: OnceLock<Mutex<HashMap<PathBuf, Arc<CostTracker>>>> = OnceLock::new();
+
+    let storage_path = resolve_storage_path(workspace_dir)?;
+    let mut trackers = TRACKERS.get_or_init(|| Mutex::new(HashMap::new())).lock();
+    let tracker = match trackers.get(&storage_path) {
+        Some(tracker) if tracker.config == *config => return Ok(Arc::clone(tracker)),
+        Some(tracker) => Arc::new(tracker.with_config(config.clone())),
+        None => Arc::new(CostTracker::new(config.clone(), workspace_dir)?),
+    };
+    trackers.insert(storage_path, Arc::clone(&tracker));
+    Ok(tracker)
 }
 
 fn resolve_storage_path(workspace_dir: &Path) -> Result<PathBuf> {
@@ -225,11 +442,33 @@ fn build_session_model_stats(session_costs: &[CostRecord]) -> HashMap<String, Mo
     by_model
 }
 
+fn add_scoped_cost(
+    scoped_costs: &mut HashMap<(CostDimension, String), (f64, f64)>,
+    record: &CostRecord,
+    in_day: bool,
+    in_month: bool,
+) {
+    if !in_month {
+        return;
+    }
+    for dimension in SCOPED_DIMENSIONS {
+        if let Some(key) = record.attribution.key(dimension) {
+            let totals = scoped_costs.entry((dimension, key)).or_insert((0.0, 0.0));
+            if in_day {
+                totals.0 += record.usage.cost_usd;
+            }
+            totals.1 += record.usage.cost_usd;
+        }
+    }
+}
+
 /// Persistent storage for cost records.
 struct CostStorage {
     path: PathBuf,
     daily_cost_usd: f64,
     monthly_cost_usd: f64,
+    /// Daily and monthly totals per scoped-budget key.
+    scoped_costs: HashMap<(CostDimension, String), (f64, f64)>,
     cached_day: NaiveDate,
     cached_year: i32,
     cached_month: u32,
@@ -248,6 +487,7 @@ impl CostStorage {
             path: path.to_path_buf(),
             daily_cost_usd: 0.0,
             monthly_cost_usd: 0.0,
+            scoped_costs: HashMap::new(),
             cached_day: now.date_naive(),
             cached_year: now.year(),
             cached_month: now.month(),
@@ -306,21 +546,25 @@ impl CostStorage {
     fn rebuild_aggregates(&mut self, day: NaiveDate, year: i32, month: u32) -> Result<()> {
         let mut daily_cost = 0.0;
         let mut monthly_cost = 0.0;
+        let mut scoped_costs = HashMap::new();
 
         self.for_each_record(|record| {
             let timestamp = record.usage.timestamp.naive_utc();
+            let in_day = timestamp.date() == day;
+            let in_month = timestamp.year() == year && timestamp.month() == month;
 
-            if timestamp.date() == day {
+            if in_day {
                 daily_cost += record.usage.cost_usd;
             }
-
-            if timestamp.year() == year && timestamp.month() == month {
+            if in_month {
                 monthly_cost += record.usage.cost_usd;
             }
+            add_scoped_cost(&mut scoped_costs, &record, in_day, in_month);
         })?;
 
         self.daily_cost_usd = daily_cost;
         self.monthly_cost_usd = monthly_cost;
+        self.scoped_costs = scoped_costs;
         self.cached_day = day;
         self.cached_year = year;
         self.cached_month = month;
@@ -357,12 +601,16 @@ impl CostStorage {
         self.ensure_period_cache_current()?;
 
         let timestamp = record.usage.timestamp.naive_utc();
-        if timestamp.date() == self.cached_day {
+        let in_day = timestamp.date() == self.cached_day;
+        let in_month =
+            timestamp.year() == self.cached_year && timestamp.month() == self.cached_month;
+        if in_day {
             self.daily_cost_usd += record.usage.cost_usd;
         }
-        if timestamp.year() == self.cached_year && timestamp.month() == self.cached_month {
+        if in_month {
             self.monthly_cost_usd += record.usage.cost_usd;
         }
+        add_scoped_cost(&mut self.scoped_costs, &record, in_day, in_month);
 
         Ok(())
     }
@@ -373,6 +621,16 @@ impl CostStorage {
         Ok((self.daily_cost_usd, self.monthly_cost_usd))
     }
 
+    /// Daily and monthly totals for one scoped-budget key.
+    fn scoped_costs(&mut self, dimension: CostDimension, key: &str) -> Result<(f64, f64)> {
+        self.ensure_period_cache_current()?;
+        Ok(self
+            .scoped_costs
+            .get(&(dimension, key.to_string()))
+            .copied()
+            .unwrap_or((0.0, 0.0)))
+    }
+
     /// Get cost for a specific date.
     fn get_cost_for_date(&self, date: NaiveDate) -> Result<f64> {
         let mut cost = 0.0;
@@ -420,6 +678,21 @@ mod tests {
         assert!(!tracker.session_id().is_empty());
     }
 
+    #[test]
+    fn call_outcomes_are_tracked_per_model() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = CostTracker::new(CostConfig::default(), tmp.path()).unwrap();
+        assert!(tracker.model_performance("fast").is_none());
+
+        tracker.record_call_outcome("fast", Duration::from_millis(100), true);
+        tracker.record_call_outcome("fast", Duration::from_millis(300), false);
+
+        let stats = tracker.model_performance("fast").unwrap();
+        assert_eq!(stats.requests, 2);
+        assert!((stats.success_rate() - 0.5).abs() < f64::EPSILON);
+        assert_eq!(stats.avg_latency_ms(), Some(200));
+    }
+
     #[test]
     fn budget_check_when_disabled() {
         let tmp = TempDir::new().unwrap();
@@ -447,6 +720,47 @@ mod tests {
         assert_eq!(summary.by_model.len(), 1);
     }
 
+    #[test]
+    fn record_provider_usage_uses_configured_cache_prices() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = CostTracker::new(enabled_config(), tmp.path()).unwrap();
+
+        let usage = ProviderTokenUsage {
+            input_tokens: Some(0),
+            output_tokens: Some(0),
+            cache_read_tokens: Some(1_000_000),
+            cache_write_tokens: None,
+        };
+        tracker
+            .record_provider_usage("openrouter", "anthropic/claude-sonnet-4-20250514", &usage)
+            .unwrap();
+
+        let summary = tracker.get_summary().unwrap();
+        assert!((summary.session_cost_usd - 0.3).abs() < 0.0001);
+        assert_eq!(summary.total_tokens, 1_000_000);
+    }
+
+    #[test]
+    fn direct_provider_usage_is_priced_by_provider_prefixed_entry() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = CostTracker::new(enabled_config(), tmp.path()).unwrap();
+
+        let usage = ProviderTokenUsage {
+            input_tokens: Some(1_000_000),
+            output_tokens: Some(0),
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+        };
+        tracker
+            .record_provider_usage("anthropic", "claude-sonnet-4-20250514", &usage)
+            .unwrap();
+
+        let summary = tracker.get_summary().unwrap();
+        assert!((summary.session_cost_usd - 3.0).abs() < 0.0001);
+        assert!(summary.by_model.contains_key("claude-sonnet-4-20250514"));
+        assert!(tracker.pricing("anthropic", "unknown-model").is_none());
+    }
+
     #[test]
     fn budget_exceeded_daily_limit() {
         let tmp = TempDir::new().unwrap();
@@ -533,4 +847,228 @@ mod tests {
             .to_string()
             .contains("Estimated cost must be a finite, non-negative value"));
     }
+
+    fn scoped_config(dimension: &str, key: &str, limit: BudgetLimitConfig) -> CostConfig {
+        let mut config = enabled_config();
+        let table = match dimension {
+            "channel" => &mut config.budgets.channel,
+            "user" => &mut config.budgets.user,
+            "job" => &mut config.budgets.job,
+            _ => &mut config.budgets.agent,
+        };
+        table.insert(key.to_string(), limit);
+        config
+    }
+
+    #[test]
+    fn scoped_channel_budget_denies_only_that_channel() {
+        let tmp = TempDir::new().unwrap();
+        let config = scoped_config(
+            "channel",
+            "telegram",
+            BudgetLimitConfig {
+                daily_limit_usd: Some(0.01),
+                ..Default::default()
+            },
+        );
+        let tracker = CostTracker::new(config, tmp.path()).unwrap();
+        let telegram = CostAttribution::channel("telegram", "alice");
+        tracker
+            .record_usage_for(
+                TokenUsage::new("test/model", 10000, 5000, 1.0, 2.0),
+                &telegram,
+            )
+            .unwrap();
+
+        match tracker.check_budget_for(0.0, &telegram).unwrap() {
+            BudgetCheck::Exceeded { scope, period, .. } => {
+                assert_eq!(scope.as_deref(), Some("channel:telegram"));
+                assert_eq!(period, UsagePeriod::Day);
+            }
+            other => panic!("expected exceeded, got {other:?}"),
+        }
+
+        let discord = CostAttribution::channel("discord", "bob");
+        assert!(matches!(
+            tracker.check_budget_for(0.0, &discord).unwrap(),
+            BudgetCheck::Allowed
+        ));
+    }
+
+    #[test]
+    fn scoped_warn_action_never_blocks() {
+        let tmp = TempDir::new().unwrap();
+        let config = scoped_config(
+            "job",
+            "nightly",
+            BudgetLimitConfig {
+                monthly_limit_usd: Some(0.01),
+                action: BudgetAction::Warn,
+                ..Default::default()
+            },
+        );
+        let tracker = CostTracker::new(config, tmp.path()).unwrap();
+        let job = CostAttribution::job("nightly");
+        tracker
+            .record_usage_for(TokenUsage::new("test/model", 10000, 5000, 1.0, 2.0), &job)
+            .unwrap();
+
+        match tracker.check_budget_for(0.0, &job).unwrap() {
+            BudgetCheck::Warning { scope, .. } => {
+                assert_eq!(scope.as_deref(), Some("job:nightly"));
+            }
+            other => panic!("expected warning, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn wildcard_user_budget_tracks_each_user_separately() {
+        let tmp = TempDir::new().unwrap();
+        let config = scoped_config(
+            "user",
+            "*",
+            BudgetLimitConfig {
+                daily_limit_usd: Some(0.01),
+                ..Default::default()
+            },
+        );
+        let tracker = CostTracker::new(config, tmp.path()).unwrap();
+        let alice = CostAttribution::channel("slack", "alice");
+        tracker
+            .record_usage_for(TokenUsage::new("test/model", 10000, 5000, 1.0, 2.0), &alice)
+            .unwrap();
+
+        assert!(matches!(
+            tracker.check_budget_for(0.0, &alice).unwrap(),
+            BudgetCheck::Exceeded { .. }
+        ));
+        assert!(matches!(
+            tracker
+                .check_budget_for(0.0, &CostAttribution::channel("slack", "bob"))
+                .unwrap(),
+            BudgetCheck::Allowed
+        ));
+    }
+
+    #[test]
+    fn scoped_totals_survive_reload() {
+        let tmp = TempDir::new().unwrap();
+        let limit = BudgetLimitConfig {
+            daily_limit_usd: Some(0.01),
+            ..Default::default()
+        };
+        let agent = CostAttribution::channel("cli", "user").with_agent("researcher");
+        {
+            let tracker = CostTracker::new(
+                scoped_config("agent", "researcher", limit.clone()),
+                tmp.path(),
+            )
+            .unwrap();
+            tracker
+                .record_usage_for(TokenUsage::new("test/model", 10000, 5000, 1.0, 2.0), &agent)
+                .unwrap();
+        }
+
+        let tracker =
+            CostTracker::new(scoped_config("agent", "researcher", limit), tmp.path()).unwrap();
+        assert!(matches!(
+            tracker.check_budget_for(0.0, &agent).unwrap(),
+            BudgetCheck::Exceeded { .. }
+        ));
+    }
+
+    #[test]
+    fn breakdown_groups_spend_by_dimension() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = CostTracker::new(enabled_config(), tmp.path()).unwrap();
+        let telegram = CostAttribution::channel("telegram", "alice");
+        tracker
+            .record_usage_for(TokenUsage::new("model/a", 2000, 0, 1.0, 1.0), &telegram)
+            .unwrap();
+        tracker
+            .record_usage_for(TokenUsage::new("model/b", 1000, 0, 1.0, 1.0), &telegram)
+            .unwrap();
+        tracker
+            .record_usage(TokenUsage::new("model/a", 500, 0, 1.0, 1.0))
+            .unwrap();
+
+        let by_channel = tracker
+            .breakdown(CostDimension::Channel, UsagePeriod::Month)
+            .unwrap();
+        assert_eq!(by_channel.len(), 2);
+        assert_eq!(by_channel[0].key, "telegram");
+        assert_eq!(by_channel[0].request_count, 2);
+        assert_eq!(by_channel[1].key, UNATTRIBUTED_KEY);
+
+        let by_model = tracker
+            .breakdown(CostDimension::Model, UsagePeriod::Day)
+            .unwrap();
+        assert_eq!(by_model[0].key, "model/a");
+        assert_eq!(by_model[0].total_tokens, 2500);
+
+        let by_user = tracker
+            .breakdown(CostDimension::User, UsagePeriod::Session)
+            .unwrap();
+        assert_eq!(by_user[0].key, "telegram:alice");
+    }
+
+    #[test]
+    fn shared_tracker_is_reused_per_workspace() {
+        let tmp = TempDir::new().unwrap();
+        let first = shared_tracker(&enabled_config(), tmp.path()).unwrap();
+        let second = shared_tracker(&enabled_config(), tmp.path()).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn shared_tracker_follows_config_changes() {
+        let tmp = TempDir::new().unwrap();
+        let first = shared_tracker(&enabled_config(), tmp.path()).unwrap();
+        first
+            .record_usage(TokenUsage::new("test/model", 1000, 1000, 1.0, 1.0))
+            .unwrap();
+
+        let tightened = CostConfig {
+            daily_limit_usd: 0.001,
+            ..enabled_config()
+        };
+        let second = shared_tracker(&tightened, tmp.path()).unwrap();
+        assert!(!Arc::ptr_eq(&first, &second));
+        assert_eq!(second.session_id(), first.session_id());
+        assert!(matches!(
+            second.check_budget(0.0).unwrap(),
+            BudgetCheck::Exceeded { .. }
+        ));
+        assert!(Arc::ptr_eq(
+            &second,
+            &shared_tracker(&tightened, tmp.path()).unwrap()
+        ));
+    }
+
+    #[test]
+    fn budget_check_fails_closed_when_storage_is_unreadable() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = CostTracker::new(
+            scoped_config(
+                "channel",
+                "telegram",
+                BudgetLimitConfig {
+                    daily_limit_usd: Some(1.0),
+                    monthly_limit_usd: None,
+                    action: BudgetAction::Deny,
+                },
+            ),
+            tmp.path(),
+        )
+        .unwrap();
+        // Force a period rebuild against a storage path that cannot be read.
+        {
+            let mut storage = tracker.lock_storage();
+            storage.cached_day = NaiveDate::MIN;
+            storage.path = tmp.path().to_path_buf();
+        }
+
+        let attribution = CostAttribution::channel("telegram", "alice");
+        assert!(tracker.check_budget_for(0.0, &attribution).is_err());
+    }
 }
@@ -1,3 +1,5 @@
+use crate::config::schema::ModelPricing;
+use crate::providers::traits::TokenUsage as ProviderTokenUsage;
 use serde::{Deserialize, Serialize};
 
 /// Token usage information from a single API call.
@@ -9,6 +11,12 @@ pub struct TokenUsage {
     pub input_tokens: u64,
     /// Output/completion tokens
     pub output_tokens: u64,
+    /// Prompt tokens read from the provider's prompt cache
+    #[serde(default)]
+    pub cache_read_tokens: u64,
+    /// Prompt tokens written to the provider's prompt cache
+    #[serde(default)]
+    pub cache_write_tokens: u64,
     /// Total tokens
     pub total_tokens: u64,
     /// Calculated cost in USD
@@ -48,12 +56,48 @@ impl TokenUsage {
             model,
             input_tokens,
             output_tokens,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
             total_tokens,
             cost_usd,
             timestamp: chrono::Utc::now(),
         }
     }
 
+    /// Create a usage record from provider-reported counts, pricing cache
+    /// reads and writes at their own rates.
+    ///
+    /// Missing counts are treated as zero.
+    pub fn from_provider_usage(
+        model: impl Into<String>,
+        usage: &ProviderTokenUsage,
+        pricing: &ModelPricing,
+    ) -> Self {
+        let cache_read_tokens = usage.cache_read_tokens.unwrap_or(0);
+        let cache_write_tokens = usage.cache_write_tokens.unwrap_or(0);
+        let mut record = Self::new(
+            model,
+            usage.input_tokens.unwrap_or(0),
+            usage.output_tokens.unwrap_or(0),
+            pricing.input,
+            pricing.output,
+        );
+
+        let cache_read_cost = (cache_read_tokens as f64 / 1_000_000.0)
+            * Self::sanitize_price(pricing.cache_read_price());
+        let cache_write_cost = (cache_write_tokens as f64 / 1_000_000.0)
+            * Self::sanitize_price(pricing.cache_write_price());
+
+        record.cache_read_tokens = cache_read_tokens;
+        record.cache_write_tokens = cache_write_tokens;
+        record.total_tokens = record
+            .total_tokens
+            .saturating_add(cache_read_tokens)
+            .saturating_add(cache_write_tokens);
+        record.cost_usd += cache_read_cost + cache_write_cost;
+        record
+    }
+
     /// Get the total cost.
     pub fn cost(&self) -> f64 {
         self.cost_usd
@@ -68,6 +112,130 @@ pub enum UsagePeriod {
     Month,
 }
 
+impl std::str::FromStr for UsagePeriod {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "session" => Ok(Self::Session),
+            "day" | "daily" | "today" => Ok(Self::Day),
+            "month" | "monthly" => Ok(Self::Month),
+            _ => Err(format!(
+                "Invalid period '{value}'. Expected one of: session, day, month"
+            )),
+        }
+    }
+}
+
+/// Dimension that spend can be broken down and budgeted by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CostDimension {
+    Channel,
+    User,
+    Model,
+    Job,
+    Agent,
+}
+
+impl CostDimension {
+    pub const ALL: [Self; 5] = [
+        Self::Channel,
+        Self::User,
+        Self::Model,
+        Self::Job,
+        Self::Agent,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Channel => "channel",
+            Self::User => "user",
+            Self::Model => "model",
+            Self::Job => "job",
+            Self::Agent => "agent",
+        }
+    }
+}
+
+impl std::str::FromStr for CostDimension {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "channel" => Ok(Self::Channel),
+            "user" | "sender" => Ok(Self::User),
+            "model" => Ok(Self::Model),
+            "job" => Ok(Self::Job),
+            "agent" => Ok(Self::Agent),
+            _ => Err(format!(
+                "Invalid cost dimension '{value}'. Expected one of: channel, user, model, job, agent"
+            )),
+        }
+    }
+}
+
+/// Who or what a request's spend is attributed to.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CostAttribution {
+    /// Channel the request came from (e.g. "telegram", "gateway", "cli")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    /// Sender id on that channel
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sender: Option<String>,
+    /// Cron job id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job: Option<String>,
+    /// Delegate agent name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+}
+
+impl CostAttribution {
+    /// Spend from a message on `channel` sent by `sender`.
+    pub fn channel(channel: impl Into<String>, sender: impl Into<String>) -> Self {
+        Self {
+            channel: Some(channel.into()),
+            sender: Some(sender.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Spend from the cron job `job_id`.
+    pub fn job(job_id: impl Into<String>) -> Self {
+        Self {
+            channel: Some("cron".into()),
+            job: Some(job_id.into()),
+            ..Self::default()
+        }
+    }
+
+    /// The same attribution, run through the delegate agent `agent`.
+    pub fn with_agent(&self, agent: impl Into<String>) -> Self {
+        Self {
+            agent: Some(agent.into()),
+            ..self.clone()
+        }
+    }
+
+    /// Budget/report key for `dimension`. Users are keyed as
+    /// `<channel>:<sender>` because sender ids are only unique per channel.
+    /// Model keys come from the usage record, not the attribution.
+    pub fn key(&self, dimension: CostDimension) -> Option<String> {
+        match dimension {
+            CostDimension::Channel => self.channel.clone(),
+            CostDimension::User => self.sender.as_ref().map(|sender| match &self.channel {
+                Some(channel) => format!("{channel}:{sender}"),
+                None => sender.clone(),
+            }),
+            CostDimension::Model => None,
+            CostDimension::Job => self.job.clone(),
+            CostDimension::Agent => self.agent.clone(),
+        }
+    }
+}
+
 /// A single cost record for persistent storage.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CostRecord {
@@ -77,6 +245,9 @@ pub struct CostRecord {
     pub usage: TokenUsage,
     /// Session identifier (for grouping)
     pub session_id: String,
+    /// Channel, sender, job and agent the spend belongs to
+    #[serde(default)]
+    pub attribution: CostAttribution,
 }
 
 impl CostRecord {
@@ -86,11 +257,29 @@ impl CostRecord {
             id: uuid::Uuid::new_v4().to_string(),
             usage,
             session_id: session_id.into(),
+            attribution: CostAttribution::default(),
+        }
+    }
+
+    /// Attach an attribution to the record.
+    pub fn with_attribution(mut self, attribution: CostAttribution) -> Self {
+        self.attribution = attribution;
+        self
+    }
+
+    /// Breakdown key for `dimension`.
+    pub fn key(&self, dimension: CostDimension) -> Option<String> {
+        match dimension {
+            CostDimension::Model => Some(self.usage.model.clone()),
+            other => self.attribution.key(other),
         }
     }
 }
 
 /// Budget enforcement result.
+///
+/// `scope` names the scoped budget that triggered the result (e.g.
+/// `channel:telegram`); `None` means the global `[cost]` limits.
 #[derive(Debug, Clone)]
 pub enum BudgetCheck {
     /// Within budget, request can proceed
@@ -100,15 +289,27 @@ pub enum BudgetCheck {
         current_usd: f64,
         limit_usd: f64,
         period: UsagePeriod,
+        scope: Option<String>,
     },
     /// Budget exceeded, request blocked
     Exceeded {
         current_usd: f64,
         limit_usd: f64,
         period: UsagePeriod,
+        scope: Option<String>,
     },
 }
 
+/// Spend for one key of a [`CostDimension`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostBreakdownEntry {
+    /// Channel, user, model, job or agent name
+    pub key: String,
+    pub cost_usd: f64,
+    pub total_tokens: u64,
+    pub request_count: usize,
+}
+
 /// Cost summary for reporting.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CostSummary {
@@ -139,6 +340,34 @@ pub struct ModelStats {
     pub request_count: usize,
 }
 
+/// Latency and success observed for a model during this session.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelPerformance {
+    /// Calls observed
+    pub requests: u64,
+    /// Calls that returned a usable response
+    pub successes: u64,
+    /// Summed wall-clock latency of every call, in milliseconds
+    pub total_latency_ms: u64,
+}
+
+impl ModelPerformance {
+    /// Share of calls that succeeded (1.0 when nothing was observed).
+    #[allow(clippy::cast_precision_loss)]
+    pub fn success_rate(&self) -> f64 {
+        if self.requests == 0 {
+            1.0
+        } else {
+            self.successes as f64 / self.requests as f64
+        }
+    }
+
+    /// Mean latency per call, if any call was observed.
+    pub fn avg_latency_ms(&self) -> Option<u64> {
+        self.total_latency_ms.checked_div(self.requests)
+    }
+}
+
 impl Default for CostSummary {
     fn default() -> Self {
         Self {
@@ -181,6 +410,95 @@ mod tests {
         assert_eq!(usage.total_tokens, 2000);
     }
 
+    #[test]
+    fn provider_usage_prices_cache_reads_and_writes_separately() {
+        let pricing = ModelPricing {
+            input: 3.0,
+            output: 15.0,
+            cache_read: Some(0.3),
+            cache_write: Some(3.75),
+        };
+        let usage = ProviderTokenUsage {
+            input_tokens: Some(1_000),
+            output_tokens: Some(500),
+            cache_read_tokens: Some(100_000),
+            cache_write_tokens: Some(10_000),
+        };
+        let record = TokenUsage::from_provider_usage("anthropic/claude", &usage, &pricing);
+
+        // 0.003 input + 0.0075 output + 0.03 cache read + 0.0375 cache write
+        assert!((record.cost_usd - 0.078).abs() < 0.0001);
+        assert_eq!(record.cache_read_tokens, 100_000);
+        assert_eq!(record.cache_write_tokens, 10_000);
+        assert_eq!(record.total_tokens, 111_500);
+    }
+
+    #[test]
+    fn provider_usage_cache_prices_default_to_input_price() {
+        let pricing = ModelPricing {
+            input: 2.0,
+            output: 0.0,
+            cache_read: None,
+            cache_write: None,
+        };
+        let usage = ProviderTokenUsage {
+            cache_read_tokens: Some(1_000_000),
+            ..ProviderTokenUsage::default()
+        };
+        let record = TokenUsage::from_provider_usage("test/model", &usage, &pricing);
+        assert!((record.cost_usd - 2.0).abs() < 0.0001);
+        assert_eq!(record.input_tokens, 0);
+    }
+
+    #[test]
+    fn legacy_records_without_cache_fields_deserialize() {
+        let json = r#"{"model":"m","input_tokens":1,"output_tokens":2,"total_tokens":3,"cost_usd":0.0,"timestamp":"2025-01-01T00:00:00Z"}"#;
+        let usage: TokenUsage = serde_json::from_str(json).unwrap();
+        assert_eq!(usage.cache_read_tokens, 0);
+        assert_eq!(usage.cache_write_tokens, 0);
+    }
+
+    #[test]
+    fn attribution_keys_users_by_channel() {
+        let attribution = CostAttribution::channel("telegram", "alice").with_agent("researcher");
+        assert_eq!(
+            attribution.key(CostDimension::User).as_deref(),
+            Some("telegram:alice")
+        );
+        assert_eq!(
+            attribution.key(CostDimension::Agent).as_deref(),
+            Some("researcher")
+        );
+        assert_eq!(attribution.key(CostDimension::Job), None);
+
+        let job = CostAttribution::job("job-1");
+        assert_eq!(job.key(CostDimension::Channel).as_deref(), Some("cron"));
+        assert_eq!(job.key(CostDimension::Job).as_deref(), Some("job-1"));
+    }
+
+    #[test]
+    fn legacy_records_without_attribution_deserialize() {
+        let json = r#"{"id":"r1","session_id":"s1","usage":{"model":"m","input_tokens":1,"output_tokens":2,"total_tokens":3,"cost_usd":0.0,"timestamp":"2025-01-01T00:00:00Z"}}"#;
+        let record: CostRecord = serde_json::from_str(json).unwrap();
+        assert_eq!(record.attribution, CostAttribution::default());
+        assert_eq!(record.key(CostDimension::Model).as_deref(), Some("m"));
+        assert_eq!(record.key(CostDimension::Channel), None);
+    }
+
+    #[test]
+    fn cost_dimension_parses_aliases() {
+        assert_eq!("Channel".parse(), Ok(CostDimension::Channel));
+        assert_eq!("sender".parse(), Ok(CostDimension::User));
+        assert!("team".parse::<CostDimension>().is_err());
+    }
+
+    #[test]
+    fn usage_period_parses_aliases() {
+        assert_eq!("daily".parse(), Ok(UsagePeriod::Day));
+        assert_eq!("Month".parse(), Ok(UsagePeriod::Month));
+        assert!("year".parse::<UsagePeriod>().is_err());
+    }
+
     #[test]
     fn cost_record_creation() {
         let usage = TokenUsage::new("test/model", 100, 50, 1.0, 2.0);
@@ -14,10 +14,15 @@ pub use schedule::{
 };
 #[allow(unused_imports)]
 pub use store::{
-    add_agent_job, add_job, add_shell_job, due_jobs, get_job, list_jobs, list_runs,
-    record_last_run, record_run, remove_job, reschedule_after_run, update_job,
+    add_agent_job, add_job, add_shell_job, defer_job, due_jobs, get_job, list_jobs, list_runs,
+    record_last_run, record_run, remove_job, reschedule_after_run, reschedule_with_status,
+    update_job,
 };
-pub use types::{CronJob, CronJobPatch, CronRun, DeliveryConfig, JobType, Schedule, SessionTarget};
+pub use types::{
+    CronJob, CronJobPatch, CronRun, DeliveryConfig, JobType, RetryPolicy, Schedule, SessionTarget,
+};
+
+const RUN_OUTPUT_PREVIEW_CHARS: usize = 200;
 
 #[allow(clippy::needless_pass_by_value)]
 pub fn handle_command(command: crate::CronCommands, config: &Config) -> Result<()> {
@@ -51,6 +56,25 @@ pub fn handle_command(command: crate::CronCommands, config: &Config) -> Result<(
                 if let Some(prompt) = &job.prompt {
                     println!("    prompt: {prompt}");
                 }
+                if !job.depends_on.is_empty() {
+                    println!("    depends on: {}", job.depends_on.join(", "));
+                }
+                if let Some(retry) = &job.retry {
+                    println!(
+                        "    retry: {} attempts, backoff {}ms (max {}ms)",
+                        retry.max_attempts, retry.backoff_ms, retry.max_backoff_ms
+                    );
+                }
+                if let Some(secs) = job.timeout_secs {
+                    println!("    timeout: {secs}s");
+                }
+                if let Some(alert) = &job.on_failure {
+                    println!(
+                        "    on failure: {} -> {}",
+                        alert.channel.as_deref().unwrap_or("?"),
+                        alert.to.as_deref().unwrap_or("?")
+                    );
+                }
             }
             Ok(())
         }
@@ -58,16 +82,40 @@ pub fn handle_command(command: crate::CronCommands, config: &Config) -> Result<(
             expression,
             tz,
             command,
+            depends_on,
+            max_attempts,
+            timeout_secs,
+            on_failure_channel,
+            on_failure_to,
         } => {
+            for dep in &depends_on {
+                get_job(config, dep)?;
+            }
             let schedule = Schedule::Cron {
                 expr: expression,
                 tz,
             };
-            let job = add_shell_job(config, None, schedule, &command)?;
+            let mut job = add_shell_job(config, None, schedule, &command)?;
+            let patch = CronJobPatch {
+                depends_on: (!depends_on.is_empty()).then_some(depends_on),
+                retry: max_attempts.map(|max_attempts| RetryPolicy {
+                    max_attempts,
+                    ..RetryPolicy::default()
+                }),
+                timeout_secs,
+                on_failure: failure_alert(on_failure_channel, on_failure_to),
+                ..CronJobPatch::default()
+            };
+            if !patch.is_empty() {
+                job = update_job(config, &job.id, patch)?;
+            }
             println!("✅ Added cron job {}", job.id);
             println!("  Expr: {}", job.expression);
             println!("  Next: {}", job.next_run.to_rfc3339());
             println!("  Cmd : {}", job.command);
+            if !job.depends_on.is_empty() {
+                println!("  Deps: {}", job.depends_on.join(", "));
+            }
             Ok(())
         }
         crate::CronCommands::AddAt { at, command } => {
@@ -103,9 +151,25 @@ pub fn handle_command(command: crate::CronCommands, config: &Config) -> Result<(
             tz,
             command,
             name,
+            depends_on,
+            max_attempts,
+            timeout_secs,
+            on_failure_channel,
+            on_failure_to,
         } => {
-            if expression.is_none() && tz.is_none() && command.is_none() && name.is_none() {
-                bail!("At least one of --expression, --tz, --command, or --name must be provided");
+            if expression.is_none()
+                && tz.is_none()
+                && command.is_none()
+                && name.is_none()
+                && depends_on.is_none()
+                && max_attempts.is_none()
+                && timeout_secs.is_none()
+                && on_failure_channel.is_none()
+            {
+                bail!(
+                    "At least one of --expression, --tz, --command, --name, --depends-on, \
+                     --max-attempts, --timeout-secs, or --on-failure-channel must be provided"
+                );
             }
 
             // Merge expression/tz with the existing schedule so that
@@ -135,10 +199,23 @@ pub fn handle_command(command: crate::CronCommands, config: &Config) -> Result<(
                 }
             }
 
+            // Keep an existing backoff when only the attempt count changes.
+            let retry = match max_attempts {
+                Some(max_attempts) => Some(RetryPolicy {
+                    max_attempts,
+                    ..get_job(config, &id)?.retry.unwrap_or_default()
+                }),
+                None => None,
+            };
+
             let patch = CronJobPatch {
                 schedule,
                 command,
                 name,
+                depends_on,
+                retry,
+                timeout_secs,
+                on_failure: failure_alert(on_failure_channel, on_failure_to),
                 ..CronJobPatch::default()
             };
 
@@ -149,6 +226,39 @@ pub fn handle_command(command: crate::CronCommands, config: &Config) -> Result<(
             println!("  Cmd : {}", job.command);
             Ok(())
         }
+        crate::CronCommands::Runs { id, limit } => {
+            let job = get_job(config, &id)?;
+            let runs = list_runs(config, &job.id, limit)?;
+            if runs.is_empty() {
+                println!("No runs recorded for cron job {id}.");
+                return Ok(());
+            }
+
+            let max_attempts = job.retry.map(|retry| retry.max_attempts);
+            println!("🕒 Recent runs for {id} ({}):", runs.len());
+            for run in runs {
+                let attempt = match max_attempts {
+                    Some(max) => format!("{}/{max}", run.attempt),
+                    None => run.attempt.to_string(),
+                };
+                println!(
+                    "- {} | {} | attempt {} | {}ms",
+                    run.started_at.to_rfc3339(),
+                    run.status,
+                    attempt,
+                    run.duration_ms.unwrap_or_default(),
+                );
+                if let Some(output) = run.output.as_deref().map(str::trim) {
+                    if !output.is_empty() {
+                        println!(
+                            "    {}",
+                            crate::util::truncate_with_ellipsis(output, RUN_OUTPUT_PREVIEW_CHARS)
+                        );
+                    }
+                }
+            }
+            Ok(())
+        }
         crate::CronCommands::Remove { id } => remove_job(config, &id),
         crate::CronCommands::Pause { id } => {
             pause_job(config, &id)?;
@@ -163,6 +273,16 @@ pub fn handle_command(command: crate::CronCommands, config: &Config) -> Result<(
     }
 }
 
+/// Announce target for `--on-failure-channel` / `--on-failure-to`.
+fn failure_alert(channel: Option<String>, to: Option<String>) -> Option<DeliveryConfig> {
+    channel.map(|channel| DeliveryConfig {
+        mode: "announce".into(),
+        channel: Some(channel),
+        to,
+        best_effort: true,
+    })
+}
+
 pub fn add_once(config: &Config, delay: &str, command: &str) -> Result<CronJob> {
     let duration = parse_delay(delay)?;
     let at = chrono::Utc::now() + duration;
@@ -264,6 +384,11 @@ mod tests {
                 tz: tz.map(Into::into),
                 command: command.map(Into::into),
                 name: name.map(Into::into),
+                depends_on: None,
+                max_attempts: None,
+                timeout_secs: None,
+                on_failure_channel: None,
+                on_failure_to: None,
             },
             config,
         )
@@ -413,4 +538,60 @@ mod tests {
         let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
         assert!(security.is_command_allowed("echo safe"));
     }
+
+    #[test]
+    fn add_with_options_sets_dependencies_and_retry_policy() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let upstream = make_job(&config, "*/5 * * * *", None, "echo upstream");
+
+        handle_command(
+            crate::CronCommands::Add {
+                expression: "*/10 * * * *".into(),
+                tz: None,
+                command: "echo downstream".into(),
+                depends_on: vec![upstream.id.clone()],
+                max_attempts: Some(4),
+                timeout_secs: Some(60),
+                on_failure_channel: Some("slack".into()),
+                on_failure_to: Some("C123".into()),
+            },
+            &config,
+        )
+        .unwrap();
+
+        let job = list_jobs(&config)
+            .unwrap()
+            .into_iter()
+            .find(|job| job.command == "echo downstream")
+            .unwrap();
+        assert_eq!(job.depends_on, vec![upstream.id]);
+        assert_eq!(job.retry.unwrap().max_attempts, 4);
+        assert_eq!(job.timeout_secs, Some(60));
+        let alert = job.on_failure.unwrap();
+        assert_eq!(alert.channel.as_deref(), Some("slack"));
+        assert_eq!(alert.to.as_deref(), Some("C123"));
+    }
+
+    #[test]
+    fn add_with_unknown_dependency_creates_nothing() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+
+        let result = handle_command(
+            crate::CronCommands::Add {
+                expression: "*/10 * * * *".into(),
+                tz: None,
+                command: "echo downstream".into(),
+                depends_on: vec!["missing".into()],
+                max_attempts: None,
+                timeout_secs: None,
+                on_failure_channel: None,
+                on_failure_to: None,
+            },
+            &config,
+        );
+        assert!(result.is_err());
+        assert!(list_jobs(&config).unwrap().is_empty());
+    }
 }
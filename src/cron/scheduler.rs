@@ -3,20 +3,24 @@ use crate::channels::{
 };
 use crate::config::Config;
 use crate::cron::{
-    due_jobs, next_run_for_schedule, record_last_run, record_run, remove_job, reschedule_after_run,
-    update_job, CronJob, CronJobPatch, DeliveryConfig, JobType, Schedule, SessionTarget,
+    defer_job, due_jobs, get_job, next_run_for_schedule, record_last_run, record_run, remove_job,
+    reschedule_after_run, reschedule_with_status, update_job, CronJob, CronJobPatch,
+    DeliveryConfig, JobType, RetryPolicy, Schedule, SessionTarget,
 };
-use crate::security::SecurityPolicy;
+use crate::runtime::{LimitBreach, LimitedOutput, ResourceLimits};
+use crate::security::{AuditLogger, ResourceLimitLog, SecurityPolicy};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use futures_util::{stream, StreamExt};
 use std::process::Stdio;
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::process::Command;
 use tokio::time::{self, Duration};
 
 const MIN_POLL_SECONDS: u64 = 5;
 const SHELL_JOB_TIMEOUT_SECS: u64 = 120;
+const FAILURE_ALERT_OUTPUT_CHARS: usize = 1_000;
 const SCHEDULER_COMPONENT: &str = "scheduler";
 
 pub async fn run(config: Config) -> Result<()> {
@@ -48,44 +52,130 @@ pub async fn run(config: Config) -> Result<()> {
     }
 }
 
-pub async fn execute_job_now(config: &Config, job: &CronJob) -> (bool, String) {
+/// Outcome of running a job through its retry policy.
+#[derive(Debug, Clone)]
+pub struct JobOutcome {
+    pub success: bool,
+    pub output: String,
+    /// 1-based number of the attempt that produced `output`.
+    pub attempt: u32,
+    /// Start of that attempt. Earlier failed attempts are already recorded.
+    pub started_at: DateTime<Utc>,
+}
+
+pub async fn execute_job_now(config: &Config, job: &CronJob) -> JobOutcome {
     let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
     execute_job_with_retry(config, &security, job).await
 }
 
+/// The job's own retry policy, or one derived from `[reliability]`.
+fn effective_retry_policy(config: &Config, job: &CronJob) -> RetryPolicy {
+    job.retry.clone().unwrap_or_else(|| RetryPolicy {
+        max_attempts: config.reliability.scheduler_retries.saturating_add(1),
+        backoff_ms: config.reliability.provider_backoff_ms.max(200),
+        ..RetryPolicy::default()
+    })
+}
+
 async fn execute_job_with_retry(
     config: &Config,
     security: &SecurityPolicy,
     job: &CronJob,
-) -> (bool, String) {
-    let mut last_output = String::new();
-    let retries = config.reliability.scheduler_retries;
-    let mut backoff_ms = config.reliability.provider_backoff_ms.max(200);
-
-    for attempt in 0..=retries {
-        let (success, output) = match job.job_type {
-            JobType::Shell => run_job_command(config, security, job).await,
-            JobType::Agent => run_agent_job(config, security, job).await,
-        };
-        last_output = output;
+) -> JobOutcome {
+    let policy = effective_retry_policy(config, job);
+    let max_attempts = policy.max_attempts.max(1);
+    let mut backoff_ms = policy.backoff_ms.min(policy.max_backoff_ms);
+    let mut attempt = 1;
 
-        if success {
-            return (true, last_output);
+    loop {
+        let started_at = Utc::now();
+        let (success, output) = run_job_attempt(config, security, job).await;
+
+        // Deterministic policy violations are not retryable.
+        if success || attempt >= max_attempts || output.starts_with("blocked by security policy:") {
+            return JobOutcome {
+                success,
+                output,
+                attempt,
+                started_at,
+            };
         }
 
-        if last_output.starts_with("blocked by security policy:") {
-            // Deterministic policy violations are not retryable.
-            return (false, last_output);
-        }
+        let finished_at = Utc::now();
+        let _ = record_run(
+            config,
+            &job.id,
+            started_at,
+            finished_at,
+            "error",
+            Some(&output),
+            (finished_at - started_at).num_milliseconds(),
+            attempt,
+        );
+        tracing::warn!(
+            "Cron job '{}' attempt {attempt}/{max_attempts} failed; retrying",
+            job.id
+        );
 
-        if attempt < retries {
-            let jitter_ms = u64::from(Utc::now().timestamp_subsec_millis() % 250);
-            time::sleep(Duration::from_millis(backoff_ms + jitter_ms)).await;
-            backoff_ms = (backoff_ms.saturating_mul(2)).min(30_000);
-        }
+        let jitter_ms = u64::from(Utc::now().timestamp_subsec_millis() % 250);
+        time::sleep(Duration::from_millis(backoff_ms + jitter_ms)).await;
+        backoff_ms = backoff_ms.saturating_mul(2).min(policy.max_backoff_ms);
+        attempt += 1;
     }
+}
+
+async fn run_job_attempt(
+    config: &Config,
+    security: &SecurityPolicy,
+    job: &CronJob,
+) -> (bool, String) {
+    match job.job_type {
+        JobType::Shell => run_job_command(config, security, job).await,
+        JobType::Agent => match job.timeout_secs {
+            Some(secs) => {
+                match time::timeout(
+                    Duration::from_secs(secs),
+                    run_agent_job(config, security, job),
+                )
+                .await
+                {
+                    Ok(result) => result,
+                    Err(_) => (false, format!("job timed out after {secs}s")),
+                }
+            }
+            None => run_agent_job(config, security, job).await,
+        },
+    }
+}
+
+/// Whether a job's dependencies allow it to run now.
+#[derive(Debug, PartialEq, Eq)]
+enum DependencyGate {
+    Ready,
+    /// A dependency has not finished since this job last ran.
+    Waiting(String),
+    /// A dependency failed or no longer exists; skip this occurrence.
+    Blocked(String),
+}
 
-    (false, last_output)
+fn check_dependencies(config: &Config, job: &CronJob) -> DependencyGate {
+    for dep_id in &job.depends_on {
+        let Ok(dep) = get_job(config, dep_id) else {
+            return DependencyGate::Blocked(format!("dependency '{dep_id}' no longer exists"));
+        };
+        let finished_since = match (dep.last_run, job.last_run) {
+            (Some(dep_run), Some(own_run)) => dep_run > own_run,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        if !finished_since {
+            return DependencyGate::Waiting(format!("waiting for dependency '{dep_id}'"));
+        }
+        if dep.last_status.as_deref() != Some("ok") {
+            return DependencyGate::Blocked(format!("dependency '{dep_id}' did not succeed"));
+        }
+    }
+    DependencyGate::Ready
 }
 
 async fn process_due_jobs(
@@ -125,16 +215,56 @@ async fn execute_and_persist_job(
     component: &str,
 ) -> (String, bool) {
     crate::health::mark_component_ok(component);
+
+    match check_dependencies(config, job) {
+        DependencyGate::Ready => {}
+        DependencyGate::Waiting(reason) => {
+            tracing::debug!("Cron job '{}' deferred: {reason}", job.id);
+            let poll_secs = config.reliability.scheduler_poll_secs.max(MIN_POLL_SECONDS);
+            let retry_at = Utc::now() + chrono::Duration::seconds(poll_secs as i64);
+            if let Err(e) = defer_job(config, &job.id, retry_at) {
+                tracing::warn!("Failed to defer cron job '{}': {e}", job.id);
+            }
+            return (job.id.clone(), true);
+        }
+        DependencyGate::Blocked(reason) => {
+            skip_job(config, job, &reason);
+            return (job.id.clone(), false);
+        }
+    }
+
     warn_if_high_frequency_agent_job(job);
 
-    let started_at = Utc::now();
-    let (success, output) = execute_job_with_retry(config, security, job).await;
+    let outcome = execute_job_with_retry(config, security, job).await;
     let finished_at = Utc::now();
-    let success = persist_job_result(config, job, success, &output, started_at, finished_at).await;
+    let success = persist_job_result(config, job, &outcome, finished_at).await;
 
     (job.id.clone(), success)
 }
 
+/// Record a skipped occurrence and move the job past it.
+fn skip_job(config: &Config, job: &CronJob, reason: &str) {
+    let now = Utc::now();
+    let output = format!("skipped: {reason}");
+    let _ = record_run(config, &job.id, now, now, "skipped", Some(&output), 0, 1);
+
+    if matches!(job.schedule, Schedule::At { .. }) {
+        let _ = record_last_run(config, &job.id, now, false, &output);
+        if let Err(e) = update_job(
+            config,
+            &job.id,
+            CronJobPatch {
+                enabled: Some(false),
+                ..CronJobPatch::default()
+            },
+        ) {
+            tracing::warn!("Failed to disable skipped one-shot cron job: {e}");
+        }
+    } else if let Err(e) = reschedule_with_status(config, job, "skipped", &output) {
+        tracing::warn!("Failed to reschedule skipped cron job: {e}");
+    }
+}
+
 async fn run_agent_job(
     config: &Config,
     security: &SecurityPolicy,
@@ -175,6 +305,8 @@ async fn run_agent_job(
                 config.default_temperature,
                 vec![],
                 false,
+                None,
+                Some(crate::cost::CostAttribution::job(&job.id)),
             )
             .await
         }
@@ -196,31 +328,11 @@ async fn run_agent_job(
 async fn persist_job_result(
     config: &Config,
     job: &CronJob,
-    mut success: bool,
-    output: &str,
-    started_at: DateTime<Utc>,
+    outcome: &JobOutcome,
     finished_at: DateTime<Utc>,
 ) -> bool {
-    let duration_ms = (finished_at - started_at).num_milliseconds();
-
-    if let Err(e) = deliver_if_configured(config, job, output).await {
-        if job.delivery.best_effort {
-            tracing::warn!("Cron delivery failed (best_effort): {e}");
-        } else {
-            success = false;
-            tracing::warn!("Cron delivery failed: {e}");
-        }
-    }
-
-    let _ = record_run(
-        config,
-        &job.id,
-        started_at,
-        finished_at,
-        if success { "ok" } else { "error" },
-        Some(output),
-        duration_ms,
-    );
+    let output = outcome.output.as_str();
+    let success = deliver_and_record(config, job, outcome, finished_at).await;
 
     if is_one_shot_auto_delete(job) {
         if success {
@@ -250,6 +362,67 @@ async fn persist_job_result(
     success
 }
 
+/// Deliver a finished run's output, record the run and alert on failure.
+/// Returns whether the run counts as successful once delivery is included.
+async fn deliver_and_record(
+    config: &Config,
+    job: &CronJob,
+    outcome: &JobOutcome,
+    finished_at: DateTime<Utc>,
+) -> bool {
+    let output = outcome.output.as_str();
+    let mut success = outcome.success;
+
+    if let Err(e) = deliver_if_configured(config, job, output).await {
+        if job.delivery.best_effort {
+            tracing::warn!("Cron delivery failed (best_effort): {e}");
+        } else {
+            success = false;
+            tracing::warn!("Cron delivery failed: {e}");
+        }
+    }
+
+    let _ = record_run(
+        config,
+        &job.id,
+        outcome.started_at,
+        finished_at,
+        if success { "ok" } else { "error" },
+        Some(output),
+        (finished_at - outcome.started_at).num_milliseconds(),
+        outcome.attempt,
+    );
+
+    if !outcome.success {
+        alert_on_failure(config, job, outcome).await;
+    }
+
+    success
+}
+
+/// Run a job outside its schedule because an event fired (e.g. a Home
+/// Assistant trigger). Dependencies, retries, delivery and failure alerts
+/// apply as for scheduled runs; the job's next scheduled run is unchanged.
+pub async fn run_triggered_job(config: &Config, job: &CronJob) -> bool {
+    let reason = match check_dependencies(config, job) {
+        DependencyGate::Ready => None,
+        DependencyGate::Waiting(reason) | DependencyGate::Blocked(reason) => Some(reason),
+    };
+    if let Some(reason) = reason {
+        tracing::info!("Triggered cron job '{}' skipped: {reason}", job.id);
+        let now = Utc::now();
+        let output = format!("skipped: {reason}");
+        let _ = record_run(config, &job.id, now, now, "skipped", Some(&output), 0, 1);
+        return false;
+    }
+
+    let outcome = execute_job_now(config, job).await;
+    let finished_at = Utc::now();
+    let success = deliver_and_record(config, job, &outcome, finished_at).await;
+    let _ = record_last_run(config, &job.id, finished_at, success, &outcome.output);
+    success
+}
+
 fn is_one_shot_auto_delete(job: &CronJob) -> bool {
     job.delete_after_run && matches!(job.schedule, Schedule::At { .. })
 }
@@ -281,12 +454,32 @@ fn warn_if_high_frequency_agent_job(job: &CronJob) {
     }
 }
 
+/// Send a failure alert to the job's `on_failure` target once its retries
+/// are exhausted.
+async fn alert_on_failure(config: &Config, job: &CronJob, outcome: &JobOutcome) {
+    let Some(target) = &job.on_failure else {
+        return;
+    };
+    let label = job.name.as_deref().unwrap_or(&job.id);
+    let message = format!(
+        "❌ Cron job '{label}' ({}) failed after {} attempt(s):\n{}",
+        job.id,
+        outcome.attempt,
+        crate::util::truncate_with_ellipsis(&outcome.output, FAILURE_ALERT_OUTPUT_CHARS),
+    );
+    if let Err(e) = deliver_to(config, target, &message).await {
+        tracing::warn!("Cron failure alert for '{}' failed: {e}", job.id);
+    }
+}
+
 async fn deliver_if_configured(config: &Config, job: &CronJob, output: &str) -> Result<()> {
-    let delivery: &DeliveryConfig = &job.delivery;
-    if !delivery.mode.eq_ignore_ascii_case("announce") {
+    if !job.delivery.mode.eq_ignore_ascii_case("announce") {
         return Ok(());
     }
+    deliver_to(config, &job.delivery, output).await
+}
 
+async fn deliver_to(config: &Config, delivery: &DeliveryConfig, output: &str) -> Result<()> {
     let channel = delivery
         .channel
         .as_deref()
@@ -423,13 +616,8 @@ async fn run_job_command(
     security: &SecurityPolicy,
     job: &CronJob,
 ) -> (bool, String) {
-    run_job_command_with_timeout(
-        config,
-        security,
-        job,
-        Duration::from_secs(SHELL_JOB_TIMEOUT_SECS),
-    )
-    .await
+    let timeout_secs = job.timeout_secs.unwrap_or(SHELL_JOB_TIMEOUT_SECS);
+    run_job_command_with_timeout(config, security, job, Duration::from_secs(timeout_secs)).await
 }
 
 async fn run_job_command_with_timeout(
@@ -476,22 +664,21 @@ async fn run_job_command_with_timeout(
         );
     }
 
-    let child = match Command::new("sh")
+    let mut command = Command::new("sh");
+    command
         .arg("-lc")
         .arg(&job.command)
         .current_dir(&config.workspace_dir)
         .stdin(Stdio::null())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
-        .kill_on_drop(true)
-        .spawn()
-    {
-        Ok(child) => child,
-        Err(e) => return (false, format!("spawn error: {e}")),
-    };
+        .kill_on_drop(true);
+    let command = ResourceLimits::from_config(&config.security.resources).apply(command);
+    let enforcement = command.enforcement();
+    let started = Instant::now();
 
-    match time::timeout(timeout, child.wait_with_output()).await {
-        Ok(Ok(output)) => {
+    match time::timeout(timeout, command.output()).await {
+        Ok(Ok(LimitedOutput { output, breach })) => {
             let stdout = String::from_utf8_lossy(&output.stdout);
             let stderr = String::from_utf8_lossy(&output.stderr);
             let combined = format!(
@@ -500,6 +687,20 @@ async fn run_job_command_with_timeout(
                 stdout.trim(),
                 stderr.trim()
             );
+            if let Some(breach) = breach {
+                audit_limit_breach(
+                    config,
+                    job,
+                    &breach,
+                    enforcement,
+                    output.status.code(),
+                    started.elapsed(),
+                );
+                return (
+                    false,
+                    format!("resource limit exceeded: {breach}\n{combined}"),
+                );
+            }
             (output.status.success(), combined)
         }
         Ok(Err(e)) => (false, format!("spawn error: {e}")),
@@ -510,6 +711,37 @@ async fn run_job_command_with_timeout(
     }
 }
 
+fn audit_limit_breach(
+    config: &Config,
+    job: &CronJob,
+    breach: &LimitBreach,
+    enforcement: &str,
+    exit_code: Option<i32>,
+    elapsed: Duration,
+) {
+    tracing::warn!(
+        job_id = %job.id,
+        limit = breach.kind(),
+        enforcement,
+        "Cron shell job stopped by resource limit: {breach}"
+    );
+    let Some(audit) = AuditLogger::try_from_config(config) else {
+        return;
+    };
+    let detail = breach.to_string();
+    let logged = audit.log_resource_limit(ResourceLimitLog {
+        channel: "cron",
+        command: &job.command,
+        detail: &detail,
+        enforcement,
+        exit_code,
+        duration_ms: u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+    });
+    if let Err(e) = logged {
+        tracing::warn!("Failed to write resource limit audit event: {e}");
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -548,6 +780,10 @@ mod tests {
             enabled: true,
             delivery: DeliveryConfig::default(),
             delete_after_run: false,
+            retry: None,
+            timeout_secs: None,
+            depends_on: Vec::new(),
+            on_failure: None,
             created_at: Utc::now(),
             next_run: Utc::now(),
             last_run: None,
@@ -556,6 +792,15 @@ mod tests {
         }
     }
 
+    fn test_outcome(success: bool, output: &str) -> JobOutcome {
+        JobOutcome {
+            success,
+            output: output.into(),
+            attempt: 1,
+            started_at: Utc::now(),
+        }
+    }
+
     fn unique_component(prefix: &str) -> String {
         format!("{prefix}-{}", uuid::Uuid::new_v4())
     }
@@ -674,9 +919,10 @@ mod tests {
         .unwrap();
         let job = test_job("sh ./retry-once.sh");
 
-        let (success, output) = execute_job_with_retry(&config, &security, &job).await;
-        assert!(success);
-        assert!(output.contains("recovered"));
+        let outcome = execute_job_with_retry(&config, &security, &job).await;
+        assert!(outcome.success);
+        assert_eq!(outcome.attempt, 2);
+        assert!(outcome.output.contains("recovered"));
     }
 
     #[tokio::test]
@@ -689,9 +935,10 @@ mod tests {
 
         let job = test_job("ls always_missing_for_retry_test");
 
-        let (success, output) = execute_job_with_retry(&config, &security, &job).await;
-        assert!(!success);
-        assert!(output.contains("always_missing_for_retry_test"));
+        let outcome = execute_job_with_retry(&config, &security, &job).await;
+        assert!(!outcome.success);
+        assert_eq!(outcome.attempt, 2);
+        assert!(outcome.output.contains("always_missing_for_retry_test"));
     }
 
     #[tokio::test]
@@ -809,10 +1056,9 @@ mod tests {
         let tmp = TempDir::new().unwrap();
         let config = test_config(&tmp).await;
         let job = cron::add_job(&config, "*/5 * * * *", "echo ok").unwrap();
-        let started = Utc::now();
-        let finished = started + ChronoDuration::milliseconds(10);
+        let finished = Utc::now() + ChronoDuration::milliseconds(10);
 
-        let success = persist_job_result(&config, &job, true, "ok", started, finished).await;
+        let success = persist_job_result(&config, &job, &test_outcome(true, "ok"), finished).await;
         assert!(success);
 
         let runs = cron::list_runs(&config, &job.id, 10).unwrap();
@@ -837,10 +1083,9 @@ mod tests {
             true,
         )
         .unwrap();
-        let started = Utc::now();
-        let finished = started + ChronoDuration::milliseconds(10);
+        let finished = Utc::now() + ChronoDuration::milliseconds(10);
 
-        let success = persist_job_result(&config, &job, true, "ok", started, finished).await;
+        let success = persist_job_result(&config, &job, &test_outcome(true, "ok"), finished).await;
         assert!(success);
         let lookup = cron::get_job(&config, &job.id);
         assert!(lookup.is_err());
@@ -862,10 +1107,10 @@ mod tests {
             true,
         )
         .unwrap();
-        let started = Utc::now();
-        let finished = started + ChronoDuration::milliseconds(10);
+        let finished = Utc::now() + ChronoDuration::milliseconds(10);
 
-        let success = persist_job_result(&config, &job, false, "boom", started, finished).await;
+        let success =
+            persist_job_result(&config, &job, &test_outcome(false, "boom"), finished).await;
         assert!(!success);
         let updated = cron::get_job(&config, &job.id).unwrap();
         assert!(!updated.enabled);
@@ -889,4 +1134,192 @@ mod tests {
         let err = deliver_if_configured(&config, &job, "x").await.unwrap_err();
         assert!(err.to_string().contains("unsupported delivery channel"));
     }
+
+    #[tokio::test]
+    async fn execute_job_with_retry_uses_job_policy_and_records_attempts() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = test_config(&tmp).await;
+        config.reliability.scheduler_retries = 0;
+        let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
+        let job = cron::add_job(&config, "*/5 * * * *", "ls missing_for_job_policy_test").unwrap();
+        let job = cron::update_job(
+            &config,
+            &job.id,
+            CronJobPatch {
+                retry: Some(RetryPolicy {
+                    max_attempts: 3,
+                    backoff_ms: 1,
+                    max_backoff_ms: 2,
+                }),
+                ..CronJobPatch::default()
+            },
+        )
+        .unwrap();
+
+        let outcome = execute_job_with_retry(&config, &security, &job).await;
+        assert!(!outcome.success);
+        assert_eq!(outcome.attempt, 3);
+
+        let runs = cron::list_runs(&config, &job.id, 10).unwrap();
+        let attempts: Vec<u32> = runs.iter().map(|run| run.attempt).collect();
+        assert_eq!(attempts, vec![2, 1]);
+    }
+
+    #[tokio::test]
+    async fn run_job_command_honors_job_timeout() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = test_config(&tmp).await;
+        config.autonomy.allowed_commands = vec!["sleep".into()];
+        let mut job = test_job("sleep 3");
+        job.timeout_secs = Some(1);
+        let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
+
+        let (success, output) = run_job_command(&config, &security, &job).await;
+        assert!(!success);
+        assert!(output.contains("job timed out after 1s"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn run_job_command_reports_resource_limit_breach() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = test_config(&tmp).await;
+        config.autonomy.allowed_commands = vec!["sh".into()];
+        config.security.resources.max_cpu_time_seconds = 1;
+        std::fs::write(
+            config.workspace_dir.join("burn.sh"),
+            "while :; do :; done\n",
+        )
+        .unwrap();
+        let job = test_job("sh ./burn.sh");
+        let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
+
+        let (success, output) = run_job_command(&config, &security, &job).await;
+        assert!(!success);
+        assert!(output.starts_with("resource limit exceeded: CPU time limit of 1s exceeded"));
+        let audit = std::fs::read_to_string(tmp.path().join("audit.log")).unwrap();
+        assert!(audit.contains("resource_limit_exceeded"));
+    }
+
+    #[tokio::test]
+    async fn dependencies_gate_job_until_upstream_succeeds() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp).await;
+        let upstream = cron::add_job(&config, "*/5 * * * *", "echo upstream").unwrap();
+        let downstream = cron::add_job(&config, "*/5 * * * *", "echo downstream").unwrap();
+        let downstream = cron::update_job(
+            &config,
+            &downstream.id,
+            CronJobPatch {
+                depends_on: Some(vec![upstream.id.clone()]),
+                ..CronJobPatch::default()
+            },
+        )
+        .unwrap();
+
+        assert!(matches!(
+            check_dependencies(&config, &downstream),
+            DependencyGate::Waiting(_)
+        ));
+
+        cron::reschedule_after_run(&config, &upstream, false, "boom").unwrap();
+        assert!(matches!(
+            check_dependencies(&config, &downstream),
+            DependencyGate::Blocked(_)
+        ));
+
+        cron::reschedule_after_run(&config, &upstream, true, "ok").unwrap();
+        assert_eq!(
+            check_dependencies(&config, &downstream),
+            DependencyGate::Ready
+        );
+    }
+
+    #[tokio::test]
+    async fn blocked_dependency_records_skipped_run() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp).await;
+        let upstream = cron::add_job(&config, "*/5 * * * *", "echo upstream").unwrap();
+        let downstream = cron::add_job(&config, "*/5 * * * *", "echo downstream").unwrap();
+        let downstream = cron::update_job(
+            &config,
+            &downstream.id,
+            CronJobPatch {
+                depends_on: Some(vec![upstream.id.clone()]),
+                ..CronJobPatch::default()
+            },
+        )
+        .unwrap();
+        cron::reschedule_after_run(&config, &upstream, false, "boom").unwrap();
+        let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
+
+        let (_, success) =
+            execute_and_persist_job(&config, &security, &downstream, "scheduler-test").await;
+        assert!(!success);
+
+        let runs = cron::list_runs(&config, &downstream.id, 10).unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].status, "skipped");
+        let stored = cron::get_job(&config, &downstream.id).unwrap();
+        assert_eq!(stored.last_status.as_deref(), Some("skipped"));
+    }
+
+    #[tokio::test]
+    async fn failure_alert_with_invalid_channel_does_not_change_outcome() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp).await;
+        let job = cron::add_job(&config, "*/5 * * * *", "echo alert").unwrap();
+        let job = cron::update_job(
+            &config,
+            &job.id,
+            CronJobPatch {
+                on_failure: Some(DeliveryConfig {
+                    mode: "announce".into(),
+                    channel: Some("invalid".into()),
+                    to: Some("ops".into()),
+                    best_effort: true,
+                }),
+                ..CronJobPatch::default()
+            },
+        )
+        .unwrap();
+
+        let finished = Utc::now();
+        let success =
+            persist_job_result(&config, &job, &test_outcome(false, "boom"), finished).await;
+        assert!(!success);
+        let updated = cron::get_job(&config, &job.id).unwrap();
+        assert_eq!(updated.last_status.as_deref(), Some("error"));
+    }
+
+    #[tokio::test]
+    async fn triggered_run_respects_dependencies_and_keeps_schedule() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp).await;
+        let upstream = cron::add_job(&config, "*/5 * * * *", "echo upstream").unwrap();
+        let downstream = cron::add_job(&config, "*/5 * * * *", "echo downstream").unwrap();
+        let downstream = cron::update_job(
+            &config,
+            &downstream.id,
+            CronJobPatch {
+                depends_on: Some(vec![upstream.id.clone()]),
+                ..CronJobPatch::default()
+            },
+        )
+        .unwrap();
+
+        cron::reschedule_after_run(&config, &upstream, false, "boom").unwrap();
+        assert!(!run_triggered_job(&config, &downstream).await);
+        let runs = cron::list_runs(&config, &downstream.id, 10).unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].status, "skipped");
+
+        cron::reschedule_after_run(&config, &upstream, true, "ok").unwrap();
+        assert!(run_triggered_job(&config, &downstream).await);
+        let stored = cron::get_job(&config, &downstream.id).unwrap();
+        assert_eq!(stored.last_status.as_deref(), Some("ok"));
+        assert_eq!(stored.next_run, downstream.next_run);
+        let runs = cron::list_runs(&config, &downstream.id, 10).unwrap();
+        assert_eq!(runs.len(), 2);
+    }
 }
@@ -1,7 +1,7 @@
 use crate::config::Config;
 use crate::cron::{
     next_run_for_schedule, schedule_cron_expression, validate_schedule, CronJob, CronJobPatch,
-    CronRun, DeliveryConfig, JobType, Schedule, SessionTarget,
+    CronRun, DeliveryConfig, JobType, RetryPolicy, Schedule, SessionTarget,
 };
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
@@ -114,7 +114,8 @@ pub fn list_jobs(config: &Config) -> Result<Vec<CronJob>> {
     with_connection(config, |conn| {
         let mut stmt = conn.prepare(
             "SELECT id, expression, command, schedule, job_type, prompt, name, session_target, model,
-                    enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output
+                    enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output,
+                    retry, timeout_secs, depends_on, on_failure
              FROM cron_jobs ORDER BY next_run ASC",
         )?;
 
@@ -132,7 +133,8 @@ pub fn get_job(config: &Config, job_id: &str) -> Result<CronJob> {
     with_connection(config, |conn| {
         let mut stmt = conn.prepare(
             "SELECT id, expression, command, schedule, job_type, prompt, name, session_target, model,
-                    enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output
+                    enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output,
+                    retry, timeout_secs, depends_on, on_failure
              FROM cron_jobs WHERE id = ?1",
         )?;
 
@@ -165,7 +167,8 @@ pub fn due_jobs(config: &Config, now: DateTime<Utc>) -> Result<Vec<CronJob>> {
     with_connection(config, |conn| {
         let mut stmt = conn.prepare(
             "SELECT id, expression, command, schedule, job_type, prompt, name, session_target, model,
-                    enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output
+                    enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output,
+                    retry, timeout_secs, depends_on, on_failure
              FROM cron_jobs
              WHERE enabled = 1 AND next_run <= ?1
              ORDER BY next_run ASC
@@ -216,6 +219,19 @@ pub fn update_job(config: &Config, job_id: &str, patch: CronJobPatch) -> Result<
     if let Some(delete_after_run) = patch.delete_after_run {
         job.delete_after_run = delete_after_run;
     }
+    if let Some(retry) = patch.retry {
+        job.retry = Some(retry);
+    }
+    if let Some(timeout_secs) = patch.timeout_secs {
+        job.timeout_secs = Some(timeout_secs);
+    }
+    if let Some(depends_on) = patch.depends_on {
+        validate_dependencies(config, &job.id, &depends_on)?;
+        job.depends_on = depends_on;
+    }
+    if let Some(on_failure) = patch.on_failure {
+        job.on_failure = Some(on_failure);
+    }
 
     if schedule_changed {
         job.next_run = next_run_for_schedule(&job.schedule, Utc::now())?;
@@ -226,8 +242,8 @@ pub fn update_job(config: &Config, job_id: &str, patch: CronJobPatch) -> Result<
             "UPDATE cron_jobs
              SET expression = ?1, command = ?2, schedule = ?3, job_type = ?4, prompt = ?5, name = ?6,
                  session_target = ?7, model = ?8, enabled = ?9, delivery = ?10, delete_after_run = ?11,
-                 next_run = ?12
-             WHERE id = ?13",
+                 next_run = ?12, retry = ?13, timeout_secs = ?14, depends_on = ?15, on_failure = ?16
+             WHERE id = ?17",
             params![
                 job.expression,
                 job.command,
@@ -241,6 +257,10 @@ pub fn update_job(config: &Config, job_id: &str, patch: CronJobPatch) -> Result<
                 serde_json::to_string(&job.delivery)?,
                 if job.delete_after_run { 1 } else { 0 },
                 job.next_run.to_rfc3339(),
+                job.retry.as_ref().map(serde_json::to_string).transpose()?,
+                job.timeout_secs.map(i64::try_from).transpose()?,
+                serde_json::to_string(&job.depends_on)?,
+                job.on_failure.as_ref().map(serde_json::to_string).transpose()?,
                 job.id,
             ],
         )
@@ -251,6 +271,35 @@ pub fn update_job(config: &Config, job_id: &str, patch: CronJobPatch) -> Result<
     get_job(config, job_id)
 }
 
+/// Reject dependencies on unknown jobs, on the job itself, or ones that
+/// would close a cycle.
+fn validate_dependencies(config: &Config, job_id: &str, depends_on: &[String]) -> Result<()> {
+    let jobs = list_jobs(config)?;
+    for dep in depends_on {
+        if dep == job_id {
+            anyhow::bail!("Cron job '{job_id}' cannot depend on itself");
+        }
+        if !jobs.iter().any(|job| &job.id == dep) {
+            anyhow::bail!("Cron dependency '{dep}' not found");
+        }
+    }
+
+    let mut stack: Vec<&str> = depends_on.iter().map(String::as_str).collect();
+    let mut seen = std::collections::HashSet::new();
+    while let Some(current) = stack.pop() {
+        if current == job_id {
+            anyhow::bail!("Cron dependencies for '{job_id}' would form a cycle");
+        }
+        if !seen.insert(current) {
+            continue;
+        }
+        if let Some(job) = jobs.iter().find(|job| job.id == current) {
+            stack.extend(job.depends_on.iter().map(String::as_str));
+        }
+    }
+    Ok(())
+}
+
 pub fn record_last_run(
     config: &Config,
     job_id: &str,
@@ -277,10 +326,20 @@ pub fn reschedule_after_run(
     job: &CronJob,
     success: bool,
     output: &str,
+) -> Result<()> {
+    reschedule_with_status(config, job, if success { "ok" } else { "error" }, output)
+}
+
+/// Advance `job` to its next occurrence and record `status` as the outcome
+/// of this one (e.g. `skipped` when a dependency failed).
+pub fn reschedule_with_status(
+    config: &Config,
+    job: &CronJob,
+    status: &str,
+    output: &str,
 ) -> Result<()> {
     let now = Utc::now();
     let next_run = next_run_for_schedule(&job.schedule, now)?;
-    let status = if success { "ok" } else { "error" };
     let bounded_output = truncate_cron_output(output);
 
     with_connection(config, |conn| {
@@ -301,6 +360,20 @@ pub fn reschedule_after_run(
     })
 }
 
+/// Push a due job back to `next_run` without recording a run, e.g. while it
+/// waits for a dependency.
+pub fn defer_job(config: &Config, job_id: &str, next_run: DateTime<Utc>) -> Result<()> {
+    with_connection(config, |conn| {
+        conn.execute(
+            "UPDATE cron_jobs SET next_run = ?1 WHERE id = ?2",
+            params![next_run.to_rfc3339(), job_id],
+        )
+        .context("Failed to defer cron job")?;
+        Ok(())
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn record_run(
     config: &Config,
     job_id: &str,
@@ -309,6 +382,7 @@ pub fn record_run(
     status: &str,
     output: Option<&str>,
     duration_ms: i64,
+    attempt: u32,
 ) -> Result<()> {
     let bounded_output = output.map(truncate_cron_output);
     with_connection(config, |conn| {
@@ -318,8 +392,8 @@ pub fn record_run(
         let tx = conn.unchecked_transaction()?;
 
         tx.execute(
-            "INSERT INTO cron_runs (job_id, started_at, finished_at, status, output, duration_ms)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            "INSERT INTO cron_runs (job_id, started_at, finished_at, status, output, duration_ms, attempt)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
             params![
                 job_id,
                 started_at.to_rfc3339(),
@@ -327,6 +401,7 @@ pub fn record_run(
                 status,
                 bounded_output.as_deref(),
                 duration_ms,
+                attempt.max(1),
             ],
         )
         .context("Failed to insert cron run")?;
@@ -374,7 +449,7 @@ pub fn list_runs(config: &Config, job_id: &str, limit: usize) -> Result<Vec<Cron
     with_connection(config, |conn| {
         let lim = i64::try_from(limit.max(1)).context("Run history limit overflow")?;
         let mut stmt = conn.prepare(
-            "SELECT id, job_id, started_at, finished_at, status, output, duration_ms
+            "SELECT id, job_id, started_at, finished_at, status, output, duration_ms, attempt
              FROM cron_runs
              WHERE job_id = ?1
              ORDER BY started_at DESC, id DESC
@@ -392,6 +467,7 @@ pub fn list_runs(config: &Config, job_id: &str, limit: usize) -> Result<Vec<Cron
                 status: row.get(4)?,
                 output: row.get(5)?,
                 duration_ms: row.get(6)?,
+                attempt: row.get(7)?,
             })
         })?;
 
@@ -426,6 +502,20 @@ fn map_cron_job_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<CronJob> {
     let last_run_raw: Option<String> = row.get(14)?;
     let created_at_raw: String = row.get(12)?;
 
+    let retry_raw: Option<String> = row.get(17)?;
+    let retry = decode_optional_json::<RetryPolicy>(retry_raw.as_deref(), "retry policy")
+        .map_err(sql_conversion_error)?;
+    let timeout_secs: Option<i64> = row.get(18)?;
+    let depends_on_raw: Option<String> = row.get(19)?;
+    let depends_on =
+        decode_optional_json::<Vec<String>>(depends_on_raw.as_deref(), "dependency list")
+            .map_err(sql_conversion_error)?
+            .unwrap_or_default();
+    let on_failure_raw: Option<String> = row.get(20)?;
+    let on_failure =
+        decode_optional_json::<DeliveryConfig>(on_failure_raw.as_deref(), "on_failure delivery")
+            .map_err(sql_conversion_error)?;
+
     Ok(CronJob {
         id: row.get(0)?,
         expression,
@@ -439,6 +529,10 @@ fn map_cron_job_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<CronJob> {
         enabled: row.get::<_, i64>(9)? != 0,
         delivery,
         delete_after_run: row.get::<_, i64>(11)? != 0,
+        retry,
+        timeout_secs: timeout_secs.and_then(|secs| u64::try_from(secs).ok()),
+        depends_on,
+        on_failure,
         created_at: parse_rfc3339(&created_at_raw).map_err(sql_conversion_error)?,
         next_run: parse_rfc3339(&next_run_raw).map_err(sql_conversion_error)?,
         last_run: match last_run_raw {
@@ -480,8 +574,20 @@ fn decode_delivery(delivery_raw: Option<&str>) -> Result<DeliveryConfig> {
     Ok(DeliveryConfig::default())
 }
 
-fn add_column_if_missing(conn: &Connection, name: &str, sql_type: &str) -> Result<()> {
-    let mut stmt = conn.prepare("PRAGMA table_info(cron_jobs)")?;
+fn decode_optional_json<T: serde::de::DeserializeOwned>(
+    raw: Option<&str>,
+    what: &str,
+) -> Result<Option<T>> {
+    match raw.map(str::trim) {
+        Some(trimmed) if !trimmed.is_empty() => serde_json::from_str(trimmed)
+            .map(Some)
+            .with_context(|| format!("Failed to parse cron {what} JSON: {trimmed}")),
+        _ => Ok(None),
+    }
+}
+
+fn add_column_if_missing(conn: &Connection, table: &str, name: &str, sql_type: &str) -> Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({table})"))?;
     let mut rows = stmt.query([])?;
     while let Some(row) = rows.next()? {
         let col_name: String = row.get(1)?;
@@ -496,17 +602,17 @@ fn add_column_if_missing(conn: &Connection, name: &str, sql_type: &str) -> Resul
     // Tolerate "duplicate column name" errors to handle the race where
     // another process adds the column between our PRAGMA check and ALTER.
     match conn.execute(
-        &format!("ALTER TABLE cron_jobs ADD COLUMN {name} {sql_type}"),
+        &format!("ALTER TABLE {table} ADD COLUMN {name} {sql_type}"),
         [],
     ) {
         Ok(_) => Ok(()),
         Err(rusqlite::Error::SqliteFailure(err, Some(ref msg)))
             if msg.contains("duplicate column name") =>
         {
-            tracing::debug!("Column cron_jobs.{name} already exists (concurrent migration): {err}");
+            tracing::debug!("Column {table}.{name} already exists (concurrent migration): {err}");
             Ok(())
         }
-        Err(e) => Err(e).with_context(|| format!("Failed to add cron_jobs.{name}")),
+        Err(e) => Err(e).with_context(|| format!("Failed to add {table}.{name}")),
     }
 }
 
@@ -539,7 +645,11 @@ fn with_connection<T>(config: &Config, f: impl FnOnce(&Connection) -> Result<T>)
             next_run         TEXT NOT NULL,
             last_run         TEXT,
             last_status      TEXT,
-            last_output      TEXT
+            last_output      TEXT,
+            retry            TEXT,
+            timeout_secs     INTEGER,
+            depends_on       TEXT,
+            on_failure       TEXT
         );
         CREATE INDEX IF NOT EXISTS idx_cron_jobs_next_run ON cron_jobs(next_run);
 
@@ -551,6 +661,7 @@ fn with_connection<T>(config: &Config, f: impl FnOnce(&Connection) -> Result<T>)
             status      TEXT NOT NULL,
             output      TEXT,
             duration_ms INTEGER,
+            attempt     INTEGER NOT NULL DEFAULT 1,
             FOREIGN KEY (job_id) REFERENCES cron_jobs(id) ON DELETE CASCADE
         );
         CREATE INDEX IF NOT EXISTS idx_cron_runs_job_id ON cron_runs(job_id);
@@ -559,15 +670,35 @@ fn with_connection<T>(config: &Config, f: impl FnOnce(&Connection) -> Result<T>)
     )
     .context("Failed to initialize cron schema")?;
 
-    add_column_if_missing(&conn, "schedule", "TEXT")?;
-    add_column_if_missing(&conn, "job_type", "TEXT NOT NULL DEFAULT 'shell'")?;
-    add_column_if_missing(&conn, "prompt", "TEXT")?;
-    add_column_if_missing(&conn, "name", "TEXT")?;
-    add_column_if_missing(&conn, "session_target", "TEXT NOT NULL DEFAULT 'isolated'")?;
-    add_column_if_missing(&conn, "model", "TEXT")?;
-    add_column_if_missing(&conn, "enabled", "INTEGER NOT NULL DEFAULT 1")?;
-    add_column_if_missing(&conn, "delivery", "TEXT")?;
-    add_column_if_missing(&conn, "delete_after_run", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(&conn, "cron_jobs", "schedule", "TEXT")?;
+    add_column_if_missing(
+        &conn,
+        "cron_jobs",
+        "job_type",
+        "TEXT NOT NULL DEFAULT 'shell'",
+    )?;
+    add_column_if_missing(&conn, "cron_jobs", "prompt", "TEXT")?;
+    add_column_if_missing(&conn, "cron_jobs", "name", "TEXT")?;
+    add_column_if_missing(
+        &conn,
+        "cron_jobs",
+        "session_target",
+        "TEXT NOT NULL DEFAULT 'isolated'",
+    )?;
+    add_column_if_missing(&conn, "cron_jobs", "model", "TEXT")?;
+    add_column_if_missing(&conn, "cron_jobs", "enabled", "INTEGER NOT NULL DEFAULT 1")?;
+    add_column_if_missing(&conn, "cron_jobs", "delivery", "TEXT")?;
+    add_column_if_missing(
+        &conn,
+        "cron_jobs",
+        "delete_after_run",
+        "INTEGER NOT NULL DEFAULT 0",
+    )?;
+    add_column_if_missing(&conn, "cron_jobs", "retry", "TEXT")?;
+    add_column_if_missing(&conn, "cron_jobs", "timeout_secs", "INTEGER")?;
+    add_column_if_missing(&conn, "cron_jobs", "depends_on", "TEXT")?;
+    add_column_if_missing(&conn, "cron_jobs", "on_failure", "TEXT")?;
+    add_column_if_missing(&conn, "cron_runs", "attempt", "INTEGER NOT NULL DEFAULT 1")?;
 
     f(&conn)
 }
@@ -766,7 +897,7 @@ mod tests {
         for idx in 0..3 {
             let start = base + ChronoDuration::seconds(idx);
             let end = start + ChronoDuration::milliseconds(100);
-            record_run(&config, &job.id, start, end, "ok", Some("done"), 100).unwrap();
+            record_run(&config, &job.id, start, end, "ok", Some("done"), 100, 1).unwrap();
         }
 
         let runs = list_runs(&config, &job.id, 10).unwrap();
@@ -787,6 +918,7 @@ mod tests {
             "ok",
             Some("ok"),
             5,
+            1,
         )
         .unwrap();
 
@@ -810,6 +942,7 @@ mod tests {
             "ok",
             Some(&output),
             1,
+            1,
         )
         .unwrap();
 
@@ -833,4 +966,87 @@ mod tests {
         assert!(last_output.ends_with(TRUNCATED_OUTPUT_MARKER));
         assert!(last_output.len() <= MAX_CRON_OUTPUT_BYTES);
     }
+
+    #[test]
+    fn record_run_keeps_attempt_numbers() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let job = add_job(&config, "*/5 * * * *", "echo retry").unwrap();
+        let base = Utc::now();
+
+        for attempt in 1..=2 {
+            let start = base + ChronoDuration::seconds(i64::from(attempt));
+            let status = if attempt == 2 { "ok" } else { "error" };
+            record_run(&config, &job.id, start, start, status, None, 0, attempt).unwrap();
+        }
+
+        let runs = list_runs(&config, &job.id, 10).unwrap();
+        assert_eq!(runs[0].attempt, 2);
+        assert_eq!(runs[0].status, "ok");
+        assert_eq!(runs[1].attempt, 1);
+        assert_eq!(runs[1].status, "error");
+    }
+
+    #[test]
+    fn update_job_persists_retry_timeout_and_failure_alert() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let job = add_job(&config, "*/5 * * * *", "echo policy").unwrap();
+        assert!(job.retry.is_none());
+        assert!(job.depends_on.is_empty());
+
+        let alert = DeliveryConfig {
+            mode: "announce".into(),
+            channel: Some("telegram".into()),
+            to: Some("ops".into()),
+            best_effort: true,
+        };
+        update_job(
+            &config,
+            &job.id,
+            CronJobPatch {
+                retry: Some(RetryPolicy {
+                    max_attempts: 4,
+                    ..RetryPolicy::default()
+                }),
+                timeout_secs: Some(30),
+                on_failure: Some(alert.clone()),
+                ..CronJobPatch::default()
+            },
+        )
+        .unwrap();
+
+        let stored = get_job(&config, &job.id).unwrap();
+        assert_eq!(stored.retry.unwrap().max_attempts, 4);
+        assert_eq!(stored.timeout_secs, Some(30));
+        assert_eq!(stored.on_failure, Some(alert));
+    }
+
+    #[test]
+    fn update_job_validates_dependencies() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let first = add_job(&config, "*/5 * * * *", "echo first").unwrap();
+        let second = add_job(&config, "*/5 * * * *", "echo second").unwrap();
+
+        let depend = |id: &str, deps: Vec<String>| {
+            update_job(
+                &config,
+                id,
+                CronJobPatch {
+                    depends_on: Some(deps),
+                    ..CronJobPatch::default()
+                },
+            )
+        };
+
+        assert!(depend(&second.id, vec!["missing".into()]).is_err());
+        assert!(depend(&second.id, vec![second.id.clone()]).is_err());
+
+        let updated = depend(&second.id, vec![first.id.clone()]).unwrap();
+        assert_eq!(updated.depends_on, vec![first.id.clone()]);
+
+        let cycle = depend(&first.id, vec![second.id.clone()]).unwrap_err();
+        assert!(cycle.to_string().contains("cycle"));
+    }
 }
@@ -101,6 +101,43 @@ fn default_true() -> bool {
     true
 }
 
+/// Per-job retry policy. Jobs without one use `[reliability]`
+/// `scheduler_retries` and `provider_backoff_ms`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first run.
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubles after every failed attempt.
+    #[serde(default = "default_backoff_ms")]
+    pub backoff_ms: u64,
+    /// Upper bound for the doubled delay.
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_max_attempts(),
+            backoff_ms: default_backoff_ms(),
+            max_backoff_ms: default_max_backoff_ms(),
+        }
+    }
+}
+
+fn default_max_attempts() -> u32 {
+    3
+}
+
+fn default_backoff_ms() -> u64 {
+    1_000
+}
+
+fn default_max_backoff_ms() -> u64 {
+    30_000
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CronJob {
     pub id: String,
@@ -115,6 +152,10 @@ pub struct CronJob {
     pub enabled: bool,
     pub delivery: DeliveryConfig,
     pub delete_after_run: bool,
+    pub retry: Option<RetryPolicy>,
+    pub timeout_secs: Option<u64>,
+    pub depends_on: Vec<String>,
+    pub on_failure: Option<DeliveryConfig>,
     pub created_at: DateTime<Utc>,
     pub next_run: DateTime<Utc>,
     pub last_run: Option<DateTime<Utc>>,
@@ -131,6 +172,7 @@ pub struct CronRun {
     pub status: String,
     pub output: Option<String>,
     pub duration_ms: Option<i64>,
+    pub attempt: u32,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -144,11 +186,34 @@ pub struct CronJobPatch {
     pub model: Option<String>,
     pub session_target: Option<SessionTarget>,
     pub delete_after_run: Option<bool>,
+    pub retry: Option<RetryPolicy>,
+    pub timeout_secs: Option<u64>,
+    pub depends_on: Option<Vec<String>>,
+    pub on_failure: Option<DeliveryConfig>,
+}
+
+impl CronJobPatch {
+    /// Whether the patch changes anything.
+    pub fn is_empty(&self) -> bool {
+        self.schedule.is_none()
+            && self.command.is_none()
+            && self.prompt.is_none()
+            && self.name.is_none()
+            && self.enabled.is_none()
+            && self.delivery.is_none()
+            && self.model.is_none()
+            && self.session_target.is_none()
+            && self.delete_after_run.is_none()
+            && self.retry.is_none()
+            && self.timeout_secs.is_none()
+            && self.depends_on.is_none()
+            && self.on_failure.is_none()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::JobType;
+    use super::{JobType, RetryPolicy};
 
     #[test]
     fn job_type_try_from_accepts_known_values_case_insensitive() {
@@ -163,4 +228,12 @@ mod tests {
         assert!(JobType::try_from("").is_err());
         assert!(JobType::try_from("unknown").is_err());
     }
+
+    #[test]
+    fn retry_policy_fills_missing_fields_with_defaults() {
+        let policy: RetryPolicy = serde_json::from_str(r#"{"max_attempts":5}"#).unwrap();
+        assert_eq!(policy.max_attempts, 5);
+        assert_eq!(policy.backoff_ms, 1_000);
+        assert_eq!(policy.max_backoff_ms, 30_000);
+    }
 }
@@ -1,10 +1,11 @@
 use crate::config::Config;
 use anyhow::Result;
 use chrono::Utc;
+use std::collections::HashMap;
 use std::future::Future;
 use std::path::PathBuf;
 use tokio::task::JoinHandle;
-use tokio::time::Duration;
+use tokio::time::{Duration, Instant};
 
 const STATUS_FLUSH_SECONDS: u64 = 5;
 
@@ -87,9 +88,26 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
         tracing::info!("Cron disabled; scheduler supervisor not started");
     }
 
+    if home_assistant_triggers_enabled(&config) {
+        let home_assistant_cfg = config.clone();
+        handles.push(spawn_component_supervisor(
+            "home_assistant",
+            initial_backoff,
+            max_backoff,
+            move || {
+                let cfg = home_assistant_cfg.clone();
+                async move { run_home_assistant_worker(cfg).await }
+            },
+        ));
+    }
+
+    let mut components = vec!["gateway", "channels", "heartbeat", "scheduler"];
+    if home_assistant_triggers_enabled(&config) {
+        components.push("home_assistant triggers");
+    }
     println!("🧠 ZeroClaw daemon started");
     println!("   Gateway:  http://{host}:{port}");
-    println!("   Components: gateway, channels, heartbeat, scheduler");
+    println!("   Components: {}", components.join(", "));
     println!("   Ctrl+C to stop");
 
     tokio::signal::ctrl_c().await?;
@@ -204,6 +222,8 @@ async fn run_heartbeat_worker(config: Config) -> Result<()> {
                 temp,
                 vec![],
                 false,
+                None,
+                Some(crate::cost::CostAttribution::channel("heartbeat", "daemon")),
             )
             .await
             {
@@ -216,6 +236,103 @@ async fn run_heartbeat_worker(config: Config) -> Result<()> {
     }
 }
 
+/// Watch Home Assistant state changes and fire the configured triggers.
+async fn run_home_assistant_worker(config: Config) -> Result<()> {
+    let Some(client) =
+        crate::tools::home_assistant::HomeAssistantClient::from_config(&config.home_assistant)
+    else {
+        anyhow::bail!("home_assistant triggers configured but no access token set");
+    };
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel(64);
+    let subscription = tokio::spawn(async move { client.subscribe_state_changes(tx).await });
+    let mut last_fired: HashMap<usize, Instant> = HashMap::new();
+
+    while let Some(change) = rx.recv().await {
+        for (index, trigger) in config.home_assistant.triggers.iter().enumerate() {
+            if !crate::tools::home_assistant::trigger_matches(trigger, &change) {
+                continue;
+            }
+            let cooldown = Duration::from_secs(trigger.cooldown_secs);
+            if last_fired
+                .get(&index)
+                .is_some_and(|fired| fired.elapsed() < cooldown)
+            {
+                continue;
+            }
+            last_fired.insert(index, Instant::now());
+            tracing::info!(
+                "Home Assistant trigger fired for {} ({} -> {})",
+                change.entity_id,
+                change.old_state.as_deref().unwrap_or("none"),
+                change.new_state.as_deref().unwrap_or("none")
+            );
+            tokio::spawn(fire_home_assistant_trigger(
+                config.clone(),
+                trigger.clone(),
+                change.clone(),
+            ));
+        }
+    }
+
+    subscription.await??;
+    Ok(())
+}
+
+async fn fire_home_assistant_trigger(
+    config: Config,
+    trigger: crate::config::HomeAssistantTrigger,
+    change: crate::tools::home_assistant::StateChange,
+) {
+    if let Some(job_id) = trigger.cron_job.as_deref() {
+        let job = match crate::cron::get_job(&config, job_id) {
+            Ok(job) => job,
+            Err(e) => {
+                tracing::warn!("Home Assistant trigger references unknown cron job {job_id}: {e}");
+                return;
+            }
+        };
+        if !crate::cron::scheduler::run_triggered_job(&config, &job).await {
+            tracing::warn!("Home Assistant trigger run of cron job {job_id} failed");
+        }
+        return;
+    }
+
+    let Some(prompt) = trigger.prompt.as_deref() else {
+        return;
+    };
+    let prompt = format!(
+        "[Home Assistant Trigger] {prompt}\n\n{} changed from {} to {}",
+        change.entity_id,
+        change.old_state.as_deref().unwrap_or("unknown"),
+        change.new_state.as_deref().unwrap_or("unknown")
+    );
+    let temp = config.default_temperature;
+    if let Err(e) = crate::agent::run(
+        config,
+        Some(prompt),
+        None,
+        None,
+        temp,
+        vec![],
+        false,
+        None,
+        Some(crate::cost::CostAttribution::channel(
+            "home_assistant",
+            "daemon",
+        )),
+    )
+    .await
+    {
+        crate::health::mark_component_error("home_assistant", e.to_string());
+        tracing::warn!("Home Assistant trigger failed: {e}");
+    }
+}
+
+fn home_assistant_triggers_enabled(config: &Config) -> bool {
+    config.home_assistant.enabled && !config.home_assistant.triggers.is_empty()
+}
+
 fn has_supervised_channels(config: &Config) -> bool {
     config
         .channels_config
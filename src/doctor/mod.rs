@@ -564,9 +564,13 @@ fn embedding_provider_validation_error(name: &str) -> Option<String> {
     if normalized.eq_ignore_ascii_case("none") || normalized.eq_ignore_ascii_case("openai") {
         return None;
     }
+    if normalized.eq_ignore_ascii_case("local") {
+        return (!cfg!(feature = "embeddings-local"))
+            .then(|| "local embeddings require a build with `--features embeddings-local`".into());
+    }
 
     let Some(url) = normalized.strip_prefix("custom:") else {
-        return Some("supported values: none, openai, custom:<url>".into());
+        return Some("supported values: none, openai, local, custom:<url>".into());
     };
 
     let url = url.trim();
@@ -829,6 +833,61 @@ fn check_daemon_state(config: &Config, items: &mut Vec<DiagItem>) {
             ));
         }
     }
+
+    check_provider_circuits(&snapshot, items);
+}
+
+fn check_provider_circuits(snapshot: &serde_json::Value, items: &mut Vec<DiagItem>) {
+    let cat = "daemon";
+    let Some(circuits) = snapshot
+        .get("provider_circuits")
+        .and_then(serde_json::Value::as_array)
+    else {
+        return;
+    };
+
+    let mut closed = 0usize;
+    for circuit in circuits {
+        let field = |key: &str| circuit.get(key).and_then(serde_json::Value::as_str);
+        let provider = field("provider").unwrap_or("?");
+        let target = field("model").map_or_else(
+            || format!("provider:{provider}"),
+            |model| format!("provider:{provider}/{model}"),
+        );
+        #[allow(clippy::cast_possible_truncation)] // percentage of a 0.0–1.0 ratio
+        let error_pct = circuit
+            .get("error_rate")
+            .and_then(serde_json::Value::as_f64)
+            .map_or(0, |rate| (rate * 100.0).round() as i64);
+        let last_error = field("last_error").unwrap_or("none");
+
+        match field("state").unwrap_or("closed") {
+            "open" => {
+                let retry_in = circuit
+                    .get("retry_in_secs")
+                    .and_then(serde_json::Value::as_u64)
+                    .unwrap_or(0);
+                items.push(DiagItem::error(
+                    cat,
+                    format!(
+                        "{target} circuit open ({error_pct}% errors, probe in {retry_in}s, last error: {last_error})"
+                    ),
+                ));
+            }
+            "half_open" => items.push(DiagItem::warn(
+                cat,
+                format!("{target} circuit half-open, probing ({error_pct}% errors)"),
+            )),
+            _ => closed += 1,
+        }
+    }
+
+    if closed > 0 {
+        items.push(DiagItem::ok(
+            cat,
+            format!("{closed} provider circuits closed"),
+        ));
+    }
 }
 
 // ── Environment checks ───────────────────────────────────────────
@@ -964,6 +1023,31 @@ mod tests {
         assert!(invalid_unknown.contains("Unknown provider"));
     }
 
+    #[test]
+    fn provider_circuits_report_open_and_half_open() {
+        let snapshot = serde_json::json!({
+            "provider_circuits": [
+                {"provider": "openai", "model": null, "state": "open", "error_rate": 0.8,
+                 "retry_in_secs": 12, "last_error": "503 unavailable"},
+                {"provider": "openai", "model": "gpt-4o", "state": "half_open", "error_rate": 0.5},
+                {"provider": "anthropic", "model": null, "state": "closed", "error_rate": 0.0}
+            ]
+        });
+        let mut items = Vec::new();
+        check_provider_circuits(&snapshot, &mut items);
+
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].severity, Severity::Error);
+        assert!(items[0]
+            .message
+            .contains("provider:openai circuit open (80% errors"));
+        assert!(items[0].message.contains("503 unavailable"));
+        assert_eq!(items[1].severity, Severity::Warn);
+        assert!(items[1].message.contains("provider:openai/gpt-4o"));
+        assert_eq!(items[2].severity, Severity::Ok);
+        assert!(items[2].message.contains("1 provider circuits closed"));
+    }
+
     #[test]
     fn diag_item_icons() {
         assert_eq!(DiagItem::ok("t", "m").icon(), "✅");
@@ -3,6 +3,7 @@
 //! All `/api/*` routes require bearer token authentication (PairingGuard).
 
 use super::AppState;
+use crate::cost::{CostDimension, UsagePeriod};
 use axum::{
     extract::{Path, Query, State},
     http::{header, HeaderMap, StatusCode},
@@ -57,6 +58,11 @@ pub struct MemoryStoreBody {
     pub category: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub struct ApprovalDecisionBody {
+    pub decision: crate::approval::ApprovalResponse,
+}
+
 #[derive(Deserialize)]
 pub struct CronAddBody {
     pub name: Option<String>,
@@ -447,18 +453,71 @@ pub async fn handle_api_memory_delete(
     }
 }
 
-/// GET /api/cost — cost summary
+#[derive(Deserialize)]
+pub struct CostQuery {
+    pub by: Option<String>,
+    pub period: Option<String>,
+}
+
+/// GET /api/cost — cost summary plus a per-dimension breakdown.
+///
+/// `?by=channel|user|model|job|agent` limits the breakdown to one dimension;
+/// `?period=session|day|month` selects the window (default: month).
 pub async fn handle_api_cost(
     State(state): State<AppState>,
     headers: HeaderMap,
+    Query(params): Query<CostQuery>,
 ) -> impl IntoResponse {
     if let Err(e) = require_auth(&state, &headers) {
         return e.into_response();
     }
 
+    let dimensions = match params.by.as_deref() {
+        Some(by) => match by.parse::<CostDimension>() {
+            Ok(dimension) => vec![dimension],
+            Err(e) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({"error": e})),
+                )
+                    .into_response()
+            }
+        },
+        None => CostDimension::ALL.to_vec(),
+    };
+    let period = match params
+        .period
+        .as_deref()
+        .unwrap_or("month")
+        .parse::<UsagePeriod>()
+    {
+        Ok(period) => period,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": e})),
+            )
+                .into_response()
+        }
+    };
+
     if let Some(ref tracker) = state.cost_tracker {
-        match tracker.get_summary() {
-            Ok(summary) => Json(serde_json::json!({"cost": summary})).into_response(),
+        let result = tracker.get_summary().and_then(|summary| {
+            let mut breakdown = serde_json::Map::new();
+            for dimension in dimensions {
+                breakdown.insert(
+                    dimension.as_str().to_string(),
+                    serde_json::to_value(tracker.breakdown(dimension, period)?)?,
+                );
+            }
+            Ok((summary, breakdown))
+        });
+        match result {
+            Ok((summary, breakdown)) => Json(serde_json::json!({
+                "cost": summary,
+                "breakdown": breakdown,
+            }))
+            .into_response(),
             Err(e) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(serde_json::json!({"error": format!("Cost summary failed: {e}")})),
@@ -466,6 +525,10 @@ pub async fn handle_api_cost(
                 .into_response(),
         }
     } else {
+        let breakdown: serde_json::Map<String, serde_json::Value> = dimensions
+            .into_iter()
+            .map(|dimension| (dimension.as_str().to_string(), serde_json::json!([])))
+            .collect();
         Json(serde_json::json!({
             "cost": {
                 "session_cost_usd": 0.0,
@@ -474,7 +537,8 @@ pub async fn handle_api_cost(
                 "total_tokens": 0,
                 "request_count": 0,
                 "by_model": {},
-            }
+            },
+            "breakdown": breakdown,
         }))
         .into_response()
     }
@@ -507,8 +571,60 @@ pub async fn handle_api_health(
     Json(serde_json::json!({"health": snapshot})).into_response()
 }
 
+/// GET /api/approvals — tool calls waiting for approval
+pub async fn handle_api_approvals_list(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    Json(serde_json::json!({"approvals": state.pending_approvals.list()})).into_response()
+}
+
+/// POST /api/approvals/{id} — answer a pending approval
+pub async fn handle_api_approval_decide(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(body): Json<ApprovalDecisionBody>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    if resolve_approval(&state, &id, body.decision) {
+        Json(serde_json::json!({"status": "ok"})).into_response()
+    } else {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": format!("No pending approval with id {id}")})),
+        )
+            .into_response()
+    }
+}
+
 // ── Helpers ─────────────────────────────────────────────────────
 
+/// Resolve a pending approval and tell other clients it was answered.
+pub(super) fn resolve_approval(
+    state: &AppState,
+    id: &str,
+    decision: crate::approval::ApprovalResponse,
+) -> bool {
+    let resolved = state.pending_approvals.resolve(id, decision);
+    if resolved {
+        let _ = state.event_tx.send(serde_json::json!({
+            "type": "approval_resolved",
+            "id": id,
+            "decision": decision,
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+        }));
+    }
+    resolved
+}
+
 fn mask_sensitive_fields(toml_str: &str) -> String {
     let mut output = String::with_capacity(toml_str.len());
     for line in toml_str.lines() {
@@ -8,11 +8,17 @@
 //! - Header sanitization (handled by axum/hyper)
 
 pub mod api;
+pub mod openai;
 pub mod sse;
 pub mod static_files;
+pub mod webchat;
 pub mod ws;
 
-use crate::channels::{Channel, LinqChannel, NextcloudTalkChannel, SendMessage, WhatsAppChannel};
+use crate::approval::{ApprovalManager, BroadcastApprovalTransport, PendingApprovals};
+use crate::channels::{
+    Channel, LinqChannel, NextcloudTalkChannel, SendMessage, TeamsChannel, WebChatChannel,
+    WhatsAppChannel,
+};
 use crate::config::Config;
 use crate::cost::CostTracker;
 use crate::memory::{self, Memory, MemoryCategory};
@@ -68,6 +74,10 @@ fn nextcloud_talk_memory_key(msg: &crate::channels::traits::ChannelMessage) -> S
     format!("nextcloud_talk_{}_{}", msg.sender, msg.id)
 }
 
+fn teams_memory_key(msg: &crate::channels::traits::ChannelMessage) -> String {
+    format!("teams_{}_{}", msg.sender, msg.id)
+}
+
 fn hash_webhook_secret(value: &str) -> String {
     use sha2::{Digest, Sha256};
 
@@ -295,6 +305,8 @@ pub struct AppState {
     pub nextcloud_talk: Option<Arc<NextcloudTalkChannel>>,
     /// Nextcloud Talk webhook secret for signature verification
     pub nextcloud_talk_webhook_secret: Option<Arc<str>>,
+    pub teams: Option<Arc<TeamsChannel>>,
+    pub webchat: Option<Arc<WebChatChannel>>,
     /// Observability backend for metrics scraping
     pub observer: Arc<dyn crate::observability::Observer>,
     /// Registered tool specs (for web dashboard tools page)
@@ -303,6 +315,10 @@ pub struct AppState {
     pub cost_tracker: Option<Arc<CostTracker>>,
     /// SSE broadcast channel for real-time events
     pub event_tx: tokio::sync::broadcast::Sender<serde_json::Value>,
+    /// Tool calls waiting for a decision from a gateway client
+    pub pending_approvals: Arc<PendingApprovals>,
+    /// Lifecycle hooks for agent runs started through the gateway
+    pub hooks: Option<Arc<crate::hooks::HookRunner>>,
 }
 
 /// Run the HTTP gateway using axum with proper HTTP/1.1 compliance.
@@ -354,8 +370,10 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         &config.workspace_dir,
         config.api_key.as_deref(),
     )?);
-    let runtime: Arc<dyn runtime::RuntimeAdapter> =
-        Arc::from(runtime::create_runtime(&config.runtime)?);
+    let runtime: Arc<dyn runtime::RuntimeAdapter> = Arc::from(runtime::create_runtime(
+        &config.runtime,
+        &config.security.resources,
+    )?);
     let security = Arc::new(SecurityPolicy::from_config(
         &config.autonomy,
         &config.workspace_dir,
@@ -389,8 +407,8 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
 
     // Cost tracker (optional)
     let cost_tracker = if config.cost.enabled {
-        match CostTracker::new(config.cost.clone(), &config.workspace_dir) {
-            Ok(ct) => Some(Arc::new(ct)),
+        match crate::cost::shared_tracker(&config.cost, &config.workspace_dir) {
+            Ok(ct) => Some(ct),
             Err(e) => {
                 tracing::warn!("Failed to initialize cost tracker: {e}");
                 None
@@ -508,6 +526,22 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
             })
             .map(Arc::from);
 
+    // Microsoft Teams channel (if configured)
+    let teams_channel: Option<Arc<TeamsChannel>> = config
+        .channels_config
+        .teams
+        .as_ref()
+        .map(|teams| Arc::new(TeamsChannel::new(teams.clone())));
+
+    // Built-in WebChat (if configured)
+    let webchat_channel: Option<Arc<WebChatChannel>> =
+        config.channels_config.webchat.as_ref().map(|webchat| {
+            Arc::new(
+                WebChatChannel::new(webchat.clone())
+                    .with_workspace_dir(config.workspace_dir.clone()),
+            )
+        });
+
     // ── Pairing guard ──────────────────────────────────────
     let pairing = Arc::new(PairingGuard::new(
         config.gateway.require_pairing,
@@ -566,6 +600,12 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
     if nextcloud_talk_channel.is_some() {
         println!("  POST /nextcloud-talk — Nextcloud Talk bot webhook");
     }
+    if teams_channel.is_some() {
+        println!("  POST /teams     — Microsoft Teams (Bot Framework) activities");
+    }
+    if webchat_channel.is_some() {
+        println!("  GET  /webchat/ws — WebChat visitor sessions (widget: /webchat/widget.js)");
+    }
     println!("  GET  /api/*     — REST API (bearer token required)");
     println!("  GET  /ws/chat   — WebSocket agent chat");
     println!("  GET  /health    — health check");
@@ -616,10 +656,14 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         linq_signing_secret,
         nextcloud_talk: nextcloud_talk_channel,
         nextcloud_talk_webhook_secret,
+        teams: teams_channel,
+        webchat: webchat_channel,
         observer: broadcast_observer,
         tools_registry,
         cost_tracker,
         event_tx,
+        pending_approvals: Arc::new(PendingApprovals::new()),
+        hooks: hooks.clone(),
     };
 
     // Config PUT needs larger body limit (1MB)
@@ -627,6 +671,21 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .route("/api/config", put(api::handle_api_config_put))
         .layer(RequestBodyLimitLayer::new(1_048_576));
 
+    // OpenAI-compatible API runs the full agent: larger bodies (inline
+    // images) and a timeout sized for tool loops.
+    let openai_router = Router::new()
+        .route(
+            "/v1/chat/completions",
+            post(openai::handle_chat_completions),
+        )
+        .route("/v1/models", get(openai::handle_models))
+        .with_state(state.clone())
+        .layer(RequestBodyLimitLayer::new(openai::MAX_BODY_SIZE))
+        .layer(TimeoutLayer::with_status_code(
+            StatusCode::REQUEST_TIMEOUT,
+            Duration::from_secs(openai::REQUEST_TIMEOUT_SECS),
+        ));
+
     // Build router with middleware
     let app = Router::new()
         // ── Existing routes ──
@@ -638,6 +697,7 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .route("/whatsapp", post(handle_whatsapp_message))
         .route("/linq", post(handle_linq_webhook))
         .route("/nextcloud-talk", post(handle_nextcloud_talk_webhook))
+        .route("/teams", post(handle_teams_activity))
         // ── Web Dashboard API routes ──
         .route("/api/status", get(api::handle_api_status))
         .route("/api/config", get(api::handle_api_config_get))
@@ -651,12 +711,17 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .route("/api/memory", post(api::handle_api_memory_store))
         .route("/api/memory/{key}", delete(api::handle_api_memory_delete))
         .route("/api/cost", get(api::handle_api_cost))
+        .route("/api/approvals", get(api::handle_api_approvals_list))
+        .route("/api/approvals/{id}", post(api::handle_api_approval_decide))
         .route("/api/cli-tools", get(api::handle_api_cli_tools))
         .route("/api/health", get(api::handle_api_health))
         // ── SSE event stream ──
         .route("/api/events", get(sse::handle_sse_events))
         // ── WebSocket agent chat ──
         .route("/ws/chat", get(ws::handle_ws_chat))
+        // ── Built-in WebChat ──
+        .route("/webchat/ws", get(webchat::handle_webchat_ws))
+        .route("/webchat/widget.js", get(webchat::handle_widget_script))
         // ── Static assets (web dashboard) ──
         .route("/_app/{*path}", get(static_files::handle_static))
         // ── Config PUT with larger body limit ──
@@ -667,6 +732,8 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
             StatusCode::REQUEST_TIMEOUT,
             Duration::from_secs(REQUEST_TIMEOUT_SECS),
         ))
+        // ── OpenAI-compatible API (own limits) ──
+        .merge(openai_router)
         // ── SPA fallback: non-API GET requests serve index.html ──
         .fallback(get(static_files::handle_spa_fallback));
 
@@ -828,10 +895,41 @@ async fn run_gateway_chat_simple(state: &AppState, message: &str) -> anyhow::Res
         .await
 }
 
-/// Full-featured chat with tools for channel handlers (WhatsApp, Linq, Nextcloud Talk).
+/// Full-featured chat with tools for channel handlers (WhatsApp, Linq, Nextcloud Talk, Teams).
+///
+/// With `[autonomy] remote_approvals`, supervised tool calls wait for a
+/// decision from a gateway client (SSE/WebSocket + approvals API).
 async fn run_gateway_chat_with_tools(state: &AppState, message: &str) -> anyhow::Result<String> {
     let config = state.config.lock().clone();
-    crate::agent::process_message(config, message).await
+    let approval = gateway_approval_manager(state, &config, "gateway");
+    Box::pin(crate::agent::process_message_with_approval(
+        config,
+        message,
+        approval.as_ref(),
+    ))
+    .await
+}
+
+/// Approval manager that routes supervised tool calls to gateway clients,
+/// or `None` unless `[autonomy] remote_approvals` is enabled in supervised mode.
+/// Requests are published under `scope`, which lets a connection pick out
+/// the ones its own tool calls raised.
+fn gateway_approval_manager(
+    state: &AppState,
+    config: &Config,
+    scope: &str,
+) -> Option<ApprovalManager> {
+    (config.autonomy.remote_approvals
+        && config.autonomy.level == crate::security::AutonomyLevel::Supervised)
+        .then(|| {
+            ApprovalManager::from_config(&config.autonomy)
+                .with_transport(Arc::new(BroadcastApprovalTransport::new(
+                    state.event_tx.clone(),
+                    scope,
+                    Arc::clone(&state.pending_approvals),
+                )))
+                .with_audit(crate::security::AuditLogger::try_from_config(config))
+        })
 }
 
 /// Webhook request body
@@ -955,7 +1053,10 @@ async fn handle_webhook(
             messages_count: 1,
         });
 
-    match run_gateway_chat_simple(&state, message).await {
+    let result = run_gateway_chat_simple(&state, message).await;
+    crate::providers::health::report_to(state.observer.as_ref());
+
+    match result {
         Ok(response) => {
             let duration = started_at.elapsed();
             state
@@ -1395,6 +1496,95 @@ async fn handle_nextcloud_talk_webhook(
     (StatusCode::OK, Json(serde_json::json!({"status": "ok"})))
 }
 
+/// POST /teams — incoming Bot Framework activity (Microsoft Teams)
+async fn handle_teams_activity(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    let Some(ref teams) = state.teams else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Teams not configured"})),
+        );
+    };
+
+    let Ok(activity) = serde_json::from_slice::<serde_json::Value>(&body) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "Invalid JSON payload"})),
+        );
+    };
+
+    // ── Security: Verify the Bot Framework JWT ──
+    let authorization = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    let service_url = activity
+        .get("serviceUrl")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    let channel_id = activity
+        .get("channelId")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    if let Err(e) = teams
+        .validate_token(authorization, service_url, channel_id)
+        .await
+    {
+        tracing::warn!("Teams activity authentication failed: {e}");
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({"error": "Invalid token"})),
+        );
+    }
+
+    for msg in teams.parse_activity(&activity) {
+        tracing::info!(
+            "Teams message from {}: {}",
+            msg.sender,
+            truncate_with_ellipsis(&msg.content, 50)
+        );
+
+        // A running channel runtime answers with history, typing and threads.
+        let Err(msg) = crate::channels::teams::dispatch_inbound(msg) else {
+            continue;
+        };
+        let msg = *msg;
+
+        // Bot Framework expects an answer within seconds, so reply out of band.
+        let state = state.clone();
+        let teams = Arc::clone(teams);
+        tokio::spawn(async move {
+            if state.auto_save {
+                let key = teams_memory_key(&msg);
+                let _ = state
+                    .mem
+                    .store(&key, &msg.content, MemoryCategory::Conversation, None)
+                    .await;
+            }
+
+            let _ = teams.start_typing(&msg.reply_target).await;
+            let reply = match run_gateway_chat_with_tools(&state, &msg.content).await {
+                Ok(response) => response,
+                Err(e) => {
+                    tracing::error!("LLM error for Teams message: {e:#}");
+                    "Sorry, I couldn't process your message right now.".to_string()
+                }
+            };
+            if let Err(e) = teams
+                .send(&SendMessage::new(reply, &msg.reply_target).in_thread(msg.thread_ts.clone()))
+                .await
+            {
+                tracing::error!("Failed to send Teams reply: {e}");
+            }
+        });
+    }
+
+    (StatusCode::OK, Json(serde_json::json!({"status": "ok"})))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1472,10 +1662,14 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            teams: None,
+            webchat: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             cost_tracker: None,
             event_tx: tokio::sync::broadcast::channel(16).0,
+            pending_approvals: Arc::new(PendingApprovals::new()),
+            hooks: None,
         };
 
         let response = handle_metrics(State(state)).await.into_response();
@@ -1493,6 +1687,79 @@ mod tests {
         assert!(text.contains("Prometheus backend not enabled"));
     }
 
+    #[tokio::test]
+    async fn approvals_api_resolves_pending_request_and_broadcasts() {
+        let (event_tx, mut event_rx) = tokio::sync::broadcast::channel(16);
+        let pending = Arc::new(PendingApprovals::new());
+        let state = AppState {
+            config: Arc::new(Mutex::new(Config::default())),
+            provider: Arc::new(MockProvider::default()),
+            model: "test-model".into(),
+            temperature: 0.0,
+            mem: Arc::new(MockMemory),
+            auto_save: false,
+            webhook_secret_hash: None,
+            pairing: Arc::new(PairingGuard::new(false, &[])),
+            trust_forwarded_headers: false,
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            linq: None,
+            linq_signing_secret: None,
+            nextcloud_talk: None,
+            nextcloud_talk_webhook_secret: None,
+            teams: None,
+            webchat: None,
+            observer: Arc::new(crate::observability::NoopObserver),
+            tools_registry: Arc::new(Vec::new()),
+            cost_tracker: None,
+            event_tx,
+            pending_approvals: Arc::clone(&pending),
+            hooks: None,
+        };
+        let ticket = pending.register(
+            "gateway",
+            &crate::approval::ApprovalRequest {
+                tool_name: "shell".into(),
+                arguments: serde_json::json!({"command": "ls"}),
+            },
+        );
+        let id = ticket.id().to_string();
+
+        let response = api::handle_api_approval_decide(
+            State(state.clone()),
+            HeaderMap::new(),
+            axum::extract::Path(id.clone()),
+            Json(api::ApprovalDecisionBody {
+                decision: crate::approval::ApprovalResponse::No,
+            }),
+        )
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            ticket.wait().await.unwrap(),
+            crate::approval::ApprovalResponse::No
+        );
+
+        let event = event_rx.recv().await.unwrap();
+        assert_eq!(event["type"], "approval_resolved");
+        assert_eq!(event["id"], id.as_str());
+
+        let missing = api::handle_api_approval_decide(
+            State(state),
+            HeaderMap::new(),
+            axum::extract::Path(id),
+            Json(api::ApprovalDecisionBody {
+                decision: crate::approval::ApprovalResponse::Yes,
+            }),
+        )
+        .await
+        .into_response();
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+    }
+
     #[tokio::test]
     async fn metrics_endpoint_renders_prometheus_output() {
         let prom = Arc::new(crate::observability::PrometheusObserver::new());
@@ -1520,10 +1787,14 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            teams: None,
+            webchat: None,
             observer,
             tools_registry: Arc::new(Vec::new()),
             cost_tracker: None,
             event_tx: tokio::sync::broadcast::channel(16).0,
+            pending_approvals: Arc::new(PendingApprovals::new()),
+            hooks: None,
         };
 
         let response = handle_metrics(State(state)).await.into_response();
@@ -1857,10 +2128,45 @@ mod tests {
         }
     }
 
-    fn test_connect_info() -> ConnectInfo<SocketAddr> {
+    pub(super) fn test_connect_info() -> ConnectInfo<SocketAddr> {
         ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 30_300)))
     }
 
+    /// Minimal gateway state for handler tests in this module and its
+    /// submodules; agent runs build their provider from `config`.
+    pub(super) fn test_app_state(config: Config, pairing: PairingGuard) -> AppState {
+        AppState {
+            model: config
+                .default_model
+                .clone()
+                .unwrap_or_else(|| "test-model".into()),
+            config: Arc::new(Mutex::new(config)),
+            provider: Arc::new(MockProvider::default()),
+            temperature: 0.0,
+            mem: Arc::new(MockMemory),
+            auto_save: false,
+            webhook_secret_hash: None,
+            pairing: Arc::new(pairing),
+            trust_forwarded_headers: false,
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            linq: None,
+            linq_signing_secret: None,
+            nextcloud_talk: None,
+            nextcloud_talk_webhook_secret: None,
+            teams: None,
+            webchat: None,
+            observer: Arc::new(crate::observability::NoopObserver),
+            tools_registry: Arc::new(Vec::new()),
+            cost_tracker: None,
+            event_tx: tokio::sync::broadcast::channel(16).0,
+            pending_approvals: Arc::new(PendingApprovals::new()),
+            hooks: None,
+        }
+    }
+
     #[tokio::test]
     async fn webhook_idempotency_skips_duplicate_provider_calls() {
         let provider_impl = Arc::new(MockProvider::default());
@@ -1885,10 +2191,14 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            teams: None,
+            webchat: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             cost_tracker: None,
             event_tx: tokio::sync::broadcast::channel(16).0,
+            pending_approvals: Arc::new(PendingApprovals::new()),
+            hooks: None,
         };
 
         let mut headers = HeaderMap::new();
@@ -1948,10 +2258,14 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            teams: None,
+            webchat: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             cost_tracker: None,
             event_tx: tokio::sync::broadcast::channel(16).0,
+            pending_approvals: Arc::new(PendingApprovals::new()),
+            hooks: None,
         };
 
         let headers = HeaderMap::new();
@@ -2023,10 +2337,14 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            teams: None,
+            webchat: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             cost_tracker: None,
             event_tx: tokio::sync::broadcast::channel(16).0,
+            pending_approvals: Arc::new(PendingApprovals::new()),
+            hooks: None,
         };
 
         let response = handle_webhook(
@@ -2070,10 +2388,14 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            teams: None,
+            webchat: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             cost_tracker: None,
             event_tx: tokio::sync::broadcast::channel(16).0,
+            pending_approvals: Arc::new(PendingApprovals::new()),
+            hooks: None,
         };
 
         let mut headers = HeaderMap::new();
@@ -2122,10 +2444,14 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            teams: None,
+            webchat: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             cost_tracker: None,
             event_tx: tokio::sync::broadcast::channel(16).0,
+            pending_approvals: Arc::new(PendingApprovals::new()),
+            hooks: None,
         };
 
         let mut headers = HeaderMap::new();
@@ -2179,10 +2505,14 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            teams: None,
+            webchat: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             cost_tracker: None,
             event_tx: tokio::sync::broadcast::channel(16).0,
+            pending_approvals: Arc::new(PendingApprovals::new()),
+            hooks: None,
         };
 
         let response = handle_nextcloud_talk_webhook(
@@ -2232,10 +2562,14 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: Some(channel),
             nextcloud_talk_webhook_secret: Some(Arc::from(secret)),
+            teams: None,
+            webchat: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             cost_tracker: None,
             event_tx: tokio::sync::broadcast::channel(16).0,
+            pending_approvals: Arc::new(PendingApprovals::new()),
+            hooks: None,
         };
 
         let mut headers = HeaderMap::new();
@@ -2255,6 +2589,59 @@ mod tests {
         assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 0);
     }
 
+    #[tokio::test]
+    async fn teams_activity_rejects_missing_token() {
+        let provider_impl = Arc::new(MockProvider::default());
+        let provider: Arc<dyn Provider> = provider_impl.clone();
+        let memory: Arc<dyn Memory> = Arc::new(MockMemory);
+
+        let channel = Arc::new(TeamsChannel::new(crate::config::TeamsConfig {
+            app_id: "app-id".into(),
+            app_password: "app-password".into(),
+            tenant_id: None,
+            // Never reached: the missing bearer token is rejected first.
+            openid_metadata_url: "http://127.0.0.1:9/openid".into(),
+            token_url: None,
+            allowed_users: vec!["*".into()],
+            adaptive_cards: false,
+        }));
+
+        let state = AppState {
+            config: Arc::new(Mutex::new(Config::default())),
+            provider,
+            model: "test-model".into(),
+            temperature: 0.0,
+            mem: memory,
+            auto_save: false,
+            webhook_secret_hash: None,
+            pairing: Arc::new(PairingGuard::new(false, &[])),
+            trust_forwarded_headers: false,
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            linq: None,
+            linq_signing_secret: None,
+            nextcloud_talk: None,
+            nextcloud_talk_webhook_secret: None,
+            teams: Some(channel),
+            webchat: None,
+            observer: Arc::new(crate::observability::NoopObserver),
+            tools_registry: Arc::new(Vec::new()),
+            cost_tracker: None,
+            event_tx: tokio::sync::broadcast::channel(16).0,
+            pending_approvals: Arc::new(PendingApprovals::new()),
+            hooks: None,
+        };
+
+        let body = r#"{"type":"message","id":"1","serviceUrl":"https://smba.example.com/","channelId":"msteams","from":{"id":"29:user"},"conversation":{"id":"19:room"},"text":"hello"}"#;
+        let response = handle_teams_activity(State(state), HeaderMap::new(), Bytes::from(body))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 0);
+    }
+
     // ══════════════════════════════════════════════════════════
     // WhatsApp Signature Verification Tests (CWE-345 Prevention)
     // ══════════════════════════════════════════════════════════
@@ -0,0 +1,680 @@
+//! OpenAI-compatible chat API.
+//!
+//! `POST /v1/chat/completions` runs the full agent (tools, memory, hooks,
+//! security policy) on the request's conversation and answers in the
+//! chat-completions format, as SSE chunks when `stream` is true. Streaming is
+//! buffered: the answer is sent in one chunk once the tool loop settles on its
+//! final reply, because text from earlier iterations is superseded and cannot
+//! be retracted from an SSE body. The agent run is cancelled when the SSE
+//! client disconnects. `GET /v1/models` lists `zeroclaw` (the configured
+//! default model) plus the default model and `hint:<name>` routes, each marked
+//! `"streaming": "buffered"`. Both require a paired bearer token.
+
+use super::{client_key_from_request, gateway_approval_manager, AppState};
+use crate::cost::CostAttribution;
+use crate::providers::ChatMessage;
+use axum::{
+    extract::{ConnectInfo, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Json, Response,
+    },
+};
+use serde::Deserialize;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Request body limit — conversations may carry inline (base64) images.
+pub const MAX_BODY_SIZE: usize = 8 * 1_048_576;
+/// Request timeout — long enough for a multi-round tool loop.
+pub const REQUEST_TIMEOUT_SECS: u64 = 300;
+/// Model id that always maps to the configured default model.
+pub const AGENT_MODEL_ID: &str = "zeroclaw";
+/// How `stream: true` is served, advertised on every `/v1/models` entry.
+const STREAMING_MODE: &str = "buffered";
+
+#[derive(Debug, Deserialize)]
+pub struct ChatCompletionRequest {
+    #[serde(default)]
+    pub model: Option<String>,
+    pub messages: Vec<ChatCompletionMessage>,
+    #[serde(default)]
+    pub stream: bool,
+    #[serde(default)]
+    pub temperature: Option<f64>,
+    /// End-user id; spend is attributed to it.
+    #[serde(default)]
+    pub user: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChatCompletionMessage {
+    pub role: String,
+    #[serde(default)]
+    pub content: Option<MessageContent>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum MessageContent {
+    Text(String),
+    Parts(Vec<ContentPart>),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentPart {
+    Text {
+        text: String,
+    },
+    ImageUrl {
+        image_url: ImageUrl,
+    },
+    #[serde(other)]
+    Unsupported,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImageUrl {
+    pub url: String,
+}
+
+impl ChatCompletionMessage {
+    /// Flatten content into text; image parts become `[IMAGE:<url>]` markers
+    /// for the multimodal pipeline.
+    fn text(&self) -> String {
+        match &self.content {
+            None => String::new(),
+            Some(MessageContent::Text(text)) => text.clone(),
+            Some(MessageContent::Parts(parts)) => parts
+                .iter()
+                .filter_map(|part| match part {
+                    ContentPart::Text { text } => Some(text.clone()),
+                    ContentPart::ImageUrl { image_url } => {
+                        Some(format!("[IMAGE:{}]", image_url.url))
+                    }
+                    ContentPart::Unsupported => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
+
+/// Split a request's messages into prior history and the final user message.
+fn split_conversation(
+    messages: &[ChatCompletionMessage],
+) -> Result<(Vec<ChatMessage>, String), String> {
+    let Some((last, prior)) = messages.split_last() else {
+        return Err("`messages` must not be empty".into());
+    };
+    if last.role != "user" {
+        return Err("the last message must have role `user`".into());
+    }
+
+    let mut history = Vec::with_capacity(prior.len());
+    for msg in prior {
+        let text = msg.text();
+        match msg.role.as_str() {
+            "system" | "developer" => history.push(ChatMessage::system(text)),
+            "user" => history.push(ChatMessage::user(text)),
+            "assistant" if !text.is_empty() => history.push(ChatMessage::assistant(text)),
+            // Tool calls and results belong to the caller's own tool loop;
+            // ZeroClaw runs its own tools, so they are not replayed.
+            "assistant" | "tool" | "function" => {}
+            other => return Err(format!("unsupported message role `{other}`")),
+        }
+    }
+    Ok((history, last.text()))
+}
+
+fn openai_error(status: StatusCode, kind: &str, message: impl Into<String>) -> Response {
+    (
+        status,
+        Json(serde_json::json!({
+            "error": {
+                "message": message.into(),
+                "type": kind,
+            }
+        })),
+    )
+        .into_response()
+}
+
+fn is_authorized(state: &AppState, headers: &HeaderMap) -> bool {
+    if !state.pairing.require_pairing() {
+        return true;
+    }
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|auth| auth.strip_prefix("Bearer "))
+        .unwrap_or("");
+    state.pairing.is_authenticated(token)
+}
+
+fn unauthorized() -> Response {
+    openai_error(
+        StatusCode::UNAUTHORIZED,
+        "invalid_request_error",
+        "Unauthorized — pair first via POST /pair, then send Authorization: Bearer <token>",
+    )
+}
+
+fn completion_chunk(
+    id: &str,
+    created: i64,
+    model: &str,
+    delta: serde_json::Value,
+    finish_reason: Option<&str>,
+) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "object": "chat.completion.chunk",
+        "created": created,
+        "model": model,
+        "choices": [{
+            "index": 0,
+            "delta": delta,
+            "finish_reason": finish_reason,
+        }],
+    })
+}
+
+/// GET /v1/models
+pub async fn handle_models(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if !is_authorized(&state, &headers) {
+        return unauthorized();
+    }
+
+    let config = state.config.lock().clone();
+    let mut ids = vec![AGENT_MODEL_ID.to_string(), state.model.clone()];
+    ids.extend(
+        config
+            .model_routes
+            .iter()
+            .map(|route| format!("hint:{}", route.hint)),
+    );
+    ids.dedup();
+
+    let data: Vec<serde_json::Value> = ids
+        .into_iter()
+        .map(|id| {
+            serde_json::json!({
+                "id": id,
+                "object": "model",
+                "created": 0,
+                "owned_by": "zeroclaw",
+                "streaming": STREAMING_MODE,
+            })
+        })
+        .collect();
+    Json(serde_json::json!({"object": "list", "data": data})).into_response()
+}
+
+/// POST /v1/chat/completions
+#[allow(clippy::too_many_lines)]
+pub async fn handle_chat_completions(
+    State(state): State<AppState>,
+    ConnectInfo(peer_addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    body: Result<Json<ChatCompletionRequest>, axum::extract::rejection::JsonRejection>,
+) -> Response {
+    let rate_key =
+        client_key_from_request(Some(peer_addr), &headers, state.trust_forwarded_headers);
+    if !state.rate_limiter.allow_webhook(&rate_key) {
+        tracing::warn!("/v1/chat/completions rate limit exceeded");
+        return openai_error(
+            StatusCode::TOO_MANY_REQUESTS,
+            "rate_limit_error",
+            "Too many requests. Please retry later.",
+        );
+    }
+    if !is_authorized(&state, &headers) {
+        return unauthorized();
+    }
+
+    let Json(request) = match body {
+        Ok(body) => body,
+        Err(e) => {
+            return openai_error(
+                StatusCode::BAD_REQUEST,
+                "invalid_request_error",
+                format!("Invalid request body: {e}"),
+            );
+        }
+    };
+    let (prior_history, message) = match split_conversation(&request.messages) {
+        Ok(split) => split,
+        Err(e) => return openai_error(StatusCode::BAD_REQUEST, "invalid_request_error", e),
+    };
+
+    let mut config = state.config.lock().clone();
+    let model = match request.model.as_deref().map(str::trim) {
+        None | Some("" | AGENT_MODEL_ID) => state.model.clone(),
+        Some(model) => model.to_string(),
+    };
+    config.default_model = Some(model.clone());
+    if let Some(temperature) = request.temperature {
+        config.default_temperature = temperature;
+    }
+    let attribution = CostAttribution::channel(
+        "openai",
+        request
+            .user
+            .clone()
+            .filter(|user| !user.trim().is_empty())
+            .unwrap_or_else(|| "api".to_string()),
+    );
+
+    let id = format!("chatcmpl-{}", Uuid::new_v4().simple());
+    let created = chrono::Utc::now().timestamp();
+
+    if !request.stream {
+        let approval = gateway_approval_manager(&state, &config, "gateway");
+        let result = Box::pin(crate::agent::process_conversation(
+            config,
+            &prior_history,
+            &message,
+            approval.as_ref(),
+            state.hooks.as_deref(),
+            attribution,
+            None,
+        ))
+        .await;
+        return match result {
+            Ok(reply) => Json(serde_json::json!({
+                "id": id,
+                "object": "chat.completion",
+                "created": created,
+                "model": model,
+                "choices": [{
+                    "index": 0,
+                    "message": {"role": "assistant", "content": reply},
+                    "finish_reason": "stop",
+                }],
+            }))
+            .into_response(),
+            Err(e) => {
+                let sanitized = crate::providers::sanitize_api_error(&e.to_string());
+                tracing::error!("/v1/chat/completions agent error: {sanitized}");
+                openai_error(StatusCode::INTERNAL_SERVER_ERROR, "server_error", sanitized)
+            }
+        };
+    }
+
+    let (event_tx, event_rx) = mpsc::channel::<String>(64);
+    tokio::spawn(async move {
+        let send = |value: serde_json::Value| {
+            let event_tx = event_tx.clone();
+            async move { event_tx.send(value.to_string()).await.is_ok() }
+        };
+        let chunk = |delta: serde_json::Value, finish_reason: Option<&str>| {
+            completion_chunk(&id, created, &model, delta, finish_reason)
+        };
+
+        if !send(chunk(serde_json::json!({"role": "assistant"}), None)).await {
+            return;
+        }
+
+        // The agent's draft deltas are not relayed: a later tool-loop
+        // iteration replaces earlier text, which an SSE body cannot take
+        // back. Keep-alives hold the connection while the loop runs.
+        let approval = gateway_approval_manager(&state, &config, "gateway");
+        let cancel = CancellationToken::new();
+        let mut run = Box::pin(crate::agent::process_conversation(
+            config,
+            &prior_history,
+            &message,
+            approval.as_ref(),
+            state.hooks.as_deref(),
+            attribution,
+            Some(cancel.clone()),
+        ));
+        let result = tokio::select! {
+            result = &mut run => result,
+            () = event_tx.closed() => {
+                // The client went away: stop paying for tools and provider calls.
+                cancel.cancel();
+                let _ = run.await;
+                tracing::debug!("/v1/chat/completions client disconnected; agent run cancelled");
+                return;
+            }
+        };
+
+        match result {
+            Ok(reply) => {
+                if !reply.is_empty()
+                    && !send(chunk(serde_json::json!({"content": reply}), None)).await
+                {
+                    return;
+                }
+                let _ = send(chunk(serde_json::json!({}), Some("stop"))).await;
+            }
+            Err(e) => {
+                let sanitized = crate::providers::sanitize_api_error(&e.to_string());
+                tracing::error!("/v1/chat/completions agent error: {sanitized}");
+                let _ = send(serde_json::json!({
+                    "error": {"message": sanitized, "type": "server_error"}
+                }))
+                .await;
+            }
+        }
+        let _ = event_tx.send("[DONE]".to_string()).await;
+    });
+
+    let stream =
+        ReceiverStream::new(event_rx).map(|data| Ok::<_, Infallible>(Event::default().data(data)));
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{test_app_state, test_connect_info};
+    use super::*;
+    use crate::config::Config;
+    use crate::security::PairingGuard;
+    use axum::routing::post;
+    use http_body_util::BodyExt;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn parse(body: serde_json::Value) -> ChatCompletionRequest {
+        serde_json::from_value(body).unwrap()
+    }
+
+    #[test]
+    fn split_conversation_keeps_history_and_last_user_message() {
+        let request = parse(serde_json::json!({
+            "model": "zeroclaw",
+            "messages": [
+                {"role": "system", "content": "Answer tersely."},
+                {"role": "user", "content": "hi"},
+                {"role": "assistant", "content": "hello"},
+                {"role": "user", "content": "what's 2+2?"}
+            ]
+        }));
+        let (history, message) = split_conversation(&request.messages).unwrap();
+        assert_eq!(message, "what's 2+2?");
+        let roles: Vec<&str> = history.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, vec!["system", "user", "assistant"]);
+        assert_eq!(history[0].content, "Answer tersely.");
+    }
+
+    #[test]
+    fn split_conversation_turns_image_parts_into_markers() {
+        let request = parse(serde_json::json!({
+            "messages": [{
+                "role": "user",
+                "content": [
+                    {"type": "text", "text": "What is this?"},
+                    {"type": "image_url", "image_url": {"url": "data:image/png;base64,AAAA"}},
+                    {"type": "input_audio", "input_audio": {}}
+                ]
+            }]
+        }));
+        let (history, message) = split_conversation(&request.messages).unwrap();
+        assert!(history.is_empty());
+        assert_eq!(message, "What is this?\n[IMAGE:data:image/png;base64,AAAA]");
+    }
+
+    #[test]
+    fn split_conversation_skips_caller_tool_traffic() {
+        let request = parse(serde_json::json!({
+            "messages": [
+                {"role": "user", "content": "run it"},
+                {"role": "assistant", "content": null, "tool_calls": []},
+                {"role": "tool", "content": "ok", "tool_call_id": "call_1"},
+                {"role": "user", "content": "thanks"}
+            ]
+        }));
+        let (history, message) = split_conversation(&request.messages).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(message, "thanks");
+    }
+
+    #[test]
+    fn split_conversation_rejects_bad_shapes() {
+        assert!(split_conversation(&[]).is_err());
+
+        let trailing_assistant = parse(serde_json::json!({
+            "messages": [{"role": "assistant", "content": "hi"}]
+        }));
+        assert!(split_conversation(&trailing_assistant.messages).is_err());
+
+        let unknown_role = parse(serde_json::json!({
+            "messages": [
+                {"role": "narrator", "content": "once upon a time"},
+                {"role": "user", "content": "go on"}
+            ]
+        }));
+        assert!(split_conversation(&unknown_role.messages).is_err());
+    }
+
+    #[test]
+    fn completion_chunk_matches_openai_shape() {
+        let chunk = completion_chunk(
+            "chatcmpl-1",
+            7,
+            "zeroclaw",
+            serde_json::json!({"content": "Hi"}),
+            None,
+        );
+        assert_eq!(chunk["object"], "chat.completion.chunk");
+        assert_eq!(chunk["choices"][0]["delta"]["content"], "Hi");
+        assert!(chunk["choices"][0]["finish_reason"].is_null());
+    }
+
+    /// OpenAI-compatible upstream whose first reply narrates a tool call and
+    /// whose second reply is the final answer. The first reply is held back
+    /// for `first_reply_delay`.
+    async fn spawn_upstream(first_reply_delay: Duration) -> (String, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let app = axum::Router::new().route(
+            "/v1/chat/completions",
+            post(move || {
+                let call = counter.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if call == 0 {
+                        tokio::time::sleep(first_reply_delay).await;
+                    }
+                    let message = if call == 0 {
+                        serde_json::json!({
+                            "role": "assistant",
+                            "content": "Let me look that up.",
+                            "tool_calls": [{
+                                "id": "call_1",
+                                "type": "function",
+                                "function": {"name": "no_such_tool", "arguments": "{}"}
+                            }]
+                        })
+                    } else {
+                        serde_json::json!({"role": "assistant", "content": "Four."})
+                    };
+                    Json(serde_json::json!({
+                        "id": "upstream",
+                        "object": "chat.completion",
+                        "created": 0,
+                        "model": "upstream-model",
+                        "choices": [{"index": 0, "message": message, "finish_reason": "stop"}]
+                    }))
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("custom:http://{addr}/v1"), calls)
+    }
+
+    fn agent_config(tmp: &tempfile::TempDir, provider: String) -> Config {
+        let mut config = Config::default();
+        config.workspace_dir = tmp.path().join("workspace");
+        config.config_path = tmp.path().join("config.toml");
+        config.default_provider = Some(provider);
+        config.default_model = Some("upstream-model".into());
+        config.api_key = Some("upstream-key".into());
+        config.memory.backend = "none".into();
+        std::fs::create_dir_all(&config.workspace_dir).unwrap();
+        config
+    }
+
+    fn completion_body(stream: bool) -> Json<ChatCompletionRequest> {
+        Json(parse(serde_json::json!({
+            "model": "zeroclaw",
+            "stream": stream,
+            "messages": [{"role": "user", "content": "what's 2+2?"}]
+        })))
+    }
+
+    async fn body_text(response: Response) -> String {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn chat_completions_requires_bearer_when_pairing_is_enabled() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = test_app_state(
+            agent_config(&tmp, "custom:http://127.0.0.1:9/v1".into()),
+            PairingGuard::new(true, &["zc_test_token".into()]),
+        );
+
+        let response = Box::pin(handle_chat_completions(
+            State(state.clone()),
+            test_connect_info(),
+            HeaderMap::new(),
+            Ok(completion_body(false)),
+        ))
+        .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let body: serde_json::Value = serde_json::from_str(&body_text(response).await).unwrap();
+        assert_eq!(body["error"]["type"], "invalid_request_error");
+
+        let response = handle_models(State(state), HeaderMap::new()).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn models_advertise_buffered_streaming() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = test_app_state(
+            agent_config(&tmp, "custom:http://127.0.0.1:9/v1".into()),
+            PairingGuard::new(false, &[]),
+        );
+
+        let response = handle_models(State(state), HeaderMap::new()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = serde_json::from_str(&body_text(response).await).unwrap();
+        let models = body["data"].as_array().unwrap();
+        assert_eq!(models[0]["id"], AGENT_MODEL_ID);
+        assert!(models.iter().all(|model| model["streaming"] == "buffered"));
+    }
+
+    #[tokio::test]
+    async fn chat_completions_returns_only_the_final_reply() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (provider, calls) = spawn_upstream(Duration::ZERO).await;
+        let state = test_app_state(agent_config(&tmp, provider), PairingGuard::new(false, &[]));
+
+        let response = Box::pin(handle_chat_completions(
+            State(state),
+            test_connect_info(),
+            HeaderMap::new(),
+            Ok(completion_body(false)),
+        ))
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = serde_json::from_str(&body_text(response).await).unwrap();
+        assert_eq!(body["object"], "chat.completion");
+        assert_eq!(body["model"], "upstream-model");
+        assert_eq!(body["choices"][0]["message"]["role"], "assistant");
+        assert_eq!(body["choices"][0]["message"]["content"], "Four.");
+        assert_eq!(body["choices"][0]["finish_reason"], "stop");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn chat_completions_streams_final_reply_as_sse_frames() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (provider, _calls) = spawn_upstream(Duration::ZERO).await;
+        let state = test_app_state(agent_config(&tmp, provider), PairingGuard::new(false, &[]));
+
+        let response = Box::pin(handle_chat_completions(
+            State(state),
+            test_connect_info(),
+            HeaderMap::new(),
+            Ok(completion_body(true)),
+        ))
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response
+                .headers()
+                .get(header::CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok()),
+            Some("text/event-stream")
+        );
+
+        let text = body_text(response).await;
+        let frames: Vec<&str> = text
+            .lines()
+            .filter_map(|line| line.strip_prefix("data: "))
+            .collect();
+        assert_eq!(frames.last(), Some(&"[DONE]"));
+        let chunks: Vec<serde_json::Value> = frames[..frames.len() - 1]
+            .iter()
+            .map(|frame| serde_json::from_str(frame).unwrap())
+            .collect();
+        assert!(chunks
+            .iter()
+            .all(|chunk| chunk["object"] == "chat.completion.chunk"));
+        assert_eq!(chunks[0]["choices"][0]["delta"]["role"], "assistant");
+        let content: String = chunks
+            .iter()
+            .filter_map(|chunk| chunk["choices"][0]["delta"]["content"].as_str())
+            .collect();
+        assert_eq!(content, "Four.");
+        let last = chunks.last().unwrap();
+        assert_eq!(last["choices"][0]["finish_reason"], "stop");
+    }
+
+    #[tokio::test]
+    async fn dropped_stream_cancels_the_agent_run() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (provider, calls) = spawn_upstream(Duration::from_millis(300)).await;
+        let state = test_app_state(agent_config(&tmp, provider), PairingGuard::new(false, &[]));
+
+        let response = Box::pin(handle_chat_completions(
+            State(state),
+            test_connect_info(),
+            HeaderMap::new(),
+            Ok(completion_body(true)),
+        ))
+        .await;
+        let mut body = response.into_body();
+        let first = body.frame().await.unwrap().unwrap().into_data().unwrap();
+        assert!(String::from_utf8_lossy(&first).contains("\"role\":\"assistant\""));
+
+        // Wait for the first provider call, then hang up before it answers.
+        while calls.load(Ordering::SeqCst) == 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        drop(body);
+
+        // Uncancelled, the run would answer the tool call with a second
+        // provider call once the first reply lands.
+        tokio::time::sleep(Duration::from_millis(800)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,228 @@
+//! Built-in WebChat: visitor sockets and the embeddable widget.
+//!
+//! Protocol:
+//! ```text
+//! Server -> Client: {"type":"session","visitor":"<anonymous id or null>","title":"Chat"}
+//! Client -> Server: {"type":"message","content":"Hello","attachments":[{"name":"a.png","mime":"image/png","data":"<base64>"}]}
+//! Server -> Client: {"type":"typing","active":true}
+//! Server -> Client: {"type":"draft","id":"…","content":"Hel"}
+//! Server -> Client: {"type":"message","id":"…","content":"Hello!"}
+//! Server -> Client: {"type":"draft_cancel","id":"…"}
+//! Server -> Client: {"type":"error","message":"…"}
+//! ```
+//!
+//! A `message` frame with the id of an open draft replaces that draft.
+
+use super::{run_gateway_chat_with_tools, AppState};
+use crate::channels::traits::ChannelMessage;
+use crate::channels::webchat::{dispatch_inbound, open_session, Upload, Visitor};
+use crate::channels::{Channel, SendMessage, WebChatChannel};
+use crate::memory::MemoryCategory;
+use axum::{
+    extract::{
+        ws::{Message, WebSocket},
+        Query, State, WebSocketUpgrade,
+    },
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use std::sync::Arc;
+
+const WIDGET_SCRIPT: &str = include_str!("webchat_widget.js");
+
+#[derive(Deserialize)]
+pub struct WebChatQuery {
+    /// Visitor token signed by the embedding site.
+    pub token: Option<String>,
+    /// Anonymous visitor ID to resume.
+    pub visitor: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct VisitorFrame {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    attachments: Vec<Upload>,
+}
+
+/// GET /webchat/widget.js — embeddable chat widget
+pub async fn handle_widget_script() -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        [
+            (
+                header::CONTENT_TYPE,
+                "application/javascript; charset=utf-8",
+            ),
+            (header::CACHE_CONTROL, "no-cache"),
+        ],
+        WIDGET_SCRIPT,
+    )
+}
+
+/// GET /webchat/ws — WebSocket upgrade for a visitor session
+pub async fn handle_webchat_ws(
+    State(state): State<AppState>,
+    Query(params): Query<WebChatQuery>,
+    headers: HeaderMap,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let Some(webchat) = state.webchat.clone() else {
+        return (StatusCode::NOT_FOUND, "WebChat not configured").into_response();
+    };
+
+    let origin = headers
+        .get(header::ORIGIN)
+        .and_then(|value| value.to_str().ok());
+    if !webchat.is_origin_allowed(origin) {
+        tracing::warn!(
+            "WebChat: rejected session from origin {}",
+            origin.unwrap_or("(none)")
+        );
+        return (StatusCode::FORBIDDEN, "Origin not allowed").into_response();
+    }
+
+    let visitor = match webchat.authenticate(params.token.as_deref(), params.visitor.as_deref()) {
+        Ok(visitor) => visitor,
+        Err(e) => {
+            tracing::warn!("WebChat: rejected visitor: {e}");
+            return (StatusCode::UNAUTHORIZED, "Invalid visitor token").into_response();
+        }
+    };
+
+    ws.max_message_size(webchat.max_frame_bytes())
+        .on_upgrade(move |socket| handle_socket(socket, state, webchat, visitor))
+        .into_response()
+}
+
+async fn handle_socket(
+    socket: WebSocket,
+    state: AppState,
+    webchat: Arc<WebChatChannel>,
+    visitor: Visitor,
+) {
+    let (mut sender, mut receiver) = socket.split();
+    let (_session, mut outbox) = open_session(&visitor.key);
+
+    let hello = serde_json::json!({
+        "type": "session",
+        "visitor": visitor.anonymous_id,
+        "title": webchat.title(),
+    });
+    if sender
+        .send(Message::Text(hello.to_string().into()))
+        .await
+        .is_err()
+    {
+        return;
+    }
+
+    loop {
+        let text = tokio::select! {
+            frame = outbox.recv() => {
+                let Some(frame) = frame else { break };
+                if sender.send(Message::Text(frame.to_string().into())).await.is_err() {
+                    break;
+                }
+                continue;
+            }
+            incoming = receiver.next() => match incoming {
+                Some(Ok(Message::Text(text))) => text,
+                Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+        };
+
+        if let Err(message) = handle_visitor_frame(&state, &webchat, &visitor, &text).await {
+            let err = serde_json::json!({"type": "error", "message": message});
+            let _ = sender.send(Message::Text(err.to_string().into())).await;
+        }
+    }
+}
+
+/// Turn one visitor frame into a channel message and route it.
+async fn handle_visitor_frame(
+    state: &AppState,
+    webchat: &Arc<WebChatChannel>,
+    visitor: &Visitor,
+    text: &str,
+) -> Result<(), String> {
+    let frame: VisitorFrame =
+        serde_json::from_str(text).map_err(|_| "Invalid message frame".to_string())?;
+    if frame.kind != "message" {
+        return Ok(());
+    }
+
+    if !state.rate_limiter.allow_webhook(&visitor.key) {
+        return Err("Too many messages. Please wait a moment.".to_string());
+    }
+
+    let content = webchat
+        .compose_content(&frame.content, &frame.attachments)
+        .await
+        .map_err(|e| e.to_string())?;
+    if content.is_empty() {
+        return Ok(());
+    }
+
+    let msg = webchat.inbound_message(visitor, content);
+    tracing::info!(
+        "WebChat message from {}: {}",
+        msg.sender,
+        crate::util::truncate_with_ellipsis(&msg.content, 50)
+    );
+
+    // A running channel runtime answers with history and draft streaming.
+    if let Err(msg) = dispatch_inbound(msg) {
+        tokio::spawn(answer_directly(state.clone(), Arc::clone(webchat), *msg));
+    }
+    Ok(())
+}
+
+/// Answer a visitor without the channel runtime (gateway-only deployments).
+async fn answer_directly(state: AppState, webchat: Arc<WebChatChannel>, msg: ChannelMessage) {
+    if state.auto_save {
+        let key = format!("webchat_{}_{}", msg.sender, msg.id);
+        let _ = state
+            .mem
+            .store(&key, &msg.content, MemoryCategory::Conversation, None)
+            .await;
+    }
+
+    let _ = webchat.start_typing(&msg.reply_target).await;
+    let reply = match run_gateway_chat_with_tools(&state, &msg.content).await {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::error!("LLM error for WebChat message: {e:#}");
+            "Sorry, I couldn't process your message right now.".to_string()
+        }
+    };
+    let _ = webchat.stop_typing(&msg.reply_target).await;
+    let _ = webchat
+        .send(&SendMessage::new(reply, &msg.reply_target))
+        .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::BodyExt;
+
+    #[tokio::test]
+    async fn widget_script_is_served_as_javascript() {
+        let response = handle_widget_script().await.into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "application/javascript; charset=utf-8"
+        );
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let script = String::from_utf8(body.to_vec()).unwrap();
+        assert!(script.contains("/webchat/ws"));
+    }
+}
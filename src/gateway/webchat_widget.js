@@ -0,0 +1,215 @@
+/*
+ * ZeroClaw WebChat widget.
+ *
+ * Embed with:
+ *   <script src="https://<gateway>/webchat/widget.js" async></script>
+ *
+ * Optional attributes on the script tag:
+ *   data-token="<visitor token>"  signed visitor token (token-scoped session)
+ *   data-open="true"              open the chat panel on load
+ */
+(function () {
+  "use strict";
+
+  var script = document.currentScript;
+  if (!script || window.__zeroclawWebChat) {
+    return;
+  }
+  window.__zeroclawWebChat = true;
+
+  var base = new URL(script.src, window.location.href);
+  var token = script.getAttribute("data-token") || "";
+  var storageKey = "zeroclaw-webchat-visitor:" + base.host;
+
+  var host = document.createElement("div");
+  var root = host.attachShadow({ mode: "open" });
+  root.innerHTML =
+    "<style>" +
+    ":host{all:initial;font-family:system-ui,sans-serif;font-size:14px}" +
+    ".toggle{position:fixed;right:20px;bottom:20px;width:56px;height:56px;border:0;" +
+    "border-radius:50%;background:#111827;color:#fff;font-size:24px;cursor:pointer;" +
+    "box-shadow:0 4px 12px rgba(0,0,0,.25);z-index:2147483646}" +
+    ".panel{position:fixed;right:20px;bottom:88px;width:360px;max-width:calc(100vw - 40px);" +
+    "height:520px;max-height:calc(100vh - 120px);display:none;flex-direction:column;" +
+    "background:#fff;color:#111827;border-radius:12px;overflow:hidden;" +
+    "box-shadow:0 8px 24px rgba(0,0,0,.2);z-index:2147483647}" +
+    ".panel.open{display:flex}" +
+    ".header{padding:12px 16px;background:#111827;color:#fff;font-weight:600}" +
+    ".log{flex:1;overflow-y:auto;padding:12px;display:flex;flex-direction:column;gap:8px}" +
+    ".msg{max-width:85%;padding:8px 12px;border-radius:10px;white-space:pre-wrap;" +
+    "word-wrap:break-word}" +
+    ".msg.user{align-self:flex-end;background:#2563eb;color:#fff}" +
+    ".msg.bot{align-self:flex-start;background:#f3f4f6}" +
+    ".msg.draft{opacity:.7}" +
+    ".msg.error{align-self:center;background:#fee2e2;color:#991b1b;font-size:12px}" +
+    ".typing{padding:0 12px 6px;font-size:12px;color:#6b7280;min-height:16px}" +
+    "form{display:flex;gap:6px;padding:8px;border-top:1px solid #e5e7eb}" +
+    "input[type=text]{flex:1;padding:8px;border:1px solid #d1d5db;border-radius:8px}" +
+    "button.send,label.attach{padding:8px 10px;border:0;border-radius:8px;" +
+    "background:#111827;color:#fff;cursor:pointer}" +
+    "input[type=file]{display:none}" +
+    ".files{padding:0 12px;font-size:12px;color:#6b7280}" +
+    "</style>" +
+    '<button class="toggle" type="button" aria-label="Open chat">&#128172;</button>' +
+    '<div class="panel" role="dialog">' +
+    '<div class="header">Chat</div>' +
+    '<div class="log" aria-live="polite"></div>' +
+    '<div class="typing"></div>' +
+    '<div class="files"></div>' +
+    "<form>" +
+    '<label class="attach" title="Attach files">&#128206;<input type="file" multiple></label>' +
+    '<input type="text" placeholder="Type a message" autocomplete="off">' +
+    '<button class="send" type="submit">Send</button>' +
+    "</form>" +
+    "</div>";
+
+  var toggle = root.querySelector(".toggle");
+  var panel = root.querySelector(".panel");
+  var header = root.querySelector(".header");
+  var log = root.querySelector(".log");
+  var typing = root.querySelector(".typing");
+  var filesLabel = root.querySelector(".files");
+  var form = root.querySelector("form");
+  var input = root.querySelector("input[type=text]");
+  var fileInput = root.querySelector("input[type=file]");
+
+  var socket = null;
+  var retryDelay = 1000;
+  var bubbles = {};
+
+  toggle.addEventListener("click", function () {
+    panel.classList.toggle("open");
+    if (panel.classList.contains("open")) {
+      input.focus();
+    }
+  });
+
+  fileInput.addEventListener("change", function () {
+    var names = [];
+    for (var i = 0; i < fileInput.files.length; i++) {
+      names.push(fileInput.files[i].name);
+    }
+    filesLabel.textContent = names.join(", ");
+  });
+
+  function addBubble(kind, text, id) {
+    var bubble = id && bubbles[id];
+    if (!bubble) {
+      bubble = document.createElement("div");
+      log.appendChild(bubble);
+      if (id) {
+        bubbles[id] = bubble;
+      }
+    }
+    bubble.className = "msg " + kind;
+    bubble.textContent = text;
+    log.scrollTop = log.scrollHeight;
+    return bubble;
+  }
+
+  function connect() {
+    var url = new URL("/webchat/ws", base);
+    url.protocol = base.protocol === "https:" ? "wss:" : "ws:";
+    if (token) {
+      url.searchParams.set("token", token);
+    } else {
+      var visitor = window.localStorage.getItem(storageKey);
+      if (visitor) {
+        url.searchParams.set("visitor", visitor);
+      }
+    }
+
+    socket = new WebSocket(url.toString());
+    socket.onopen = function () {
+      retryDelay = 1000;
+    };
+    socket.onmessage = function (event) {
+      var frame;
+      try {
+        frame = JSON.parse(event.data);
+      } catch (e) {
+        return;
+      }
+      switch (frame.type) {
+        case "session":
+          if (frame.visitor) {
+            window.localStorage.setItem(storageKey, frame.visitor);
+          }
+          if (frame.title) {
+            header.textContent = frame.title;
+          }
+          break;
+        case "typing":
+          typing.textContent = frame.active ? "Typing…" : "";
+          break;
+        case "draft":
+          addBubble("bot draft", frame.content, frame.id);
+          break;
+        case "draft_cancel":
+          if (bubbles[frame.id]) {
+            bubbles[frame.id].remove();
+            delete bubbles[frame.id];
+          }
+          break;
+        case "message":
+          typing.textContent = "";
+          addBubble("bot", frame.content, frame.id);
+          delete bubbles[frame.id];
+          break;
+        case "error":
+          addBubble("error", frame.message);
+          break;
+      }
+    };
+    socket.onclose = function () {
+      socket = null;
+      setTimeout(connect, retryDelay);
+      retryDelay = Math.min(retryDelay * 2, 30000);
+    };
+  }
+
+  function readFile(file) {
+    return new Promise(function (resolve, reject) {
+      var reader = new FileReader();
+      reader.onload = function () {
+        var result = String(reader.result);
+        resolve({
+          name: file.name,
+          mime: file.type || null,
+          data: result.slice(result.indexOf(",") + 1),
+        });
+      };
+      reader.onerror = reject;
+      reader.readAsDataURL(file);
+    });
+  }
+
+  form.addEventListener("submit", function (event) {
+    event.preventDefault();
+    var text = input.value.trim();
+    var files = Array.prototype.slice.call(fileInput.files || []);
+    if (!socket || socket.readyState !== WebSocket.OPEN || (!text && !files.length)) {
+      return;
+    }
+
+    Promise.all(files.map(readFile)).then(function (attachments) {
+      var label = files.map(function (f) {
+        return "📎 " + f.name;
+      });
+      if (text) {
+        label.push(text);
+      }
+      addBubble("user", label.join("\n"));
+      socket.send(JSON.stringify({ type: "message", content: text, attachments: attachments }));
+      input.value = "";
+      fileInput.value = "";
+      filesLabel.textContent = "";
+    });
+  });
+
+  document.body.appendChild(host);
+  if (script.getAttribute("data-open") === "true") {
+    panel.classList.add("open");
+  }
+  connect();
+})();
@@ -1,15 +1,46 @@
 //! WebSocket agent chat handler.
 //!
+//! Each connection runs the full agent (tools, memory, approvals) and keeps
+//! its conversation history between turns. Connect with `?session=<id>` to
+//! resume a stored session and persist new turns to it.
+//!
 //! Protocol:
 //! ```text
+//! Server -> Client: {"type":"session","id":"work","messages":12}   (with ?session=)
 //! Client -> Server: {"type":"message","content":"Hello"}
+//! Server -> Client: {"type":"progress","content":"⏳ shell: ls\n"}
+//! Server -> Client: {"type":"tool_start","name":"shell"}
+//! Server -> Client: {"type":"tool_end","name":"shell","success":true,"duration_ms":12}
+//! Server -> Client: {"type":"cost","model":"...","input_tokens":900,"output_tokens":40,"cost_usd":0.0031}
+//! Server -> Client: {"type":"clear"}              (discard chunks streamed so far)
 //! Server -> Client: {"type":"chunk","content":"Hi! "}
-//! Server -> Client: {"type":"tool_call","name":"shell","args":{...}}
-//! Server -> Client: {"type":"tool_result","name":"shell","output":"..."}
-//! Server -> Client: {"type":"done","full_response":"..."}
+//! Server -> Client: {"type":"done","full_response":"...","usage":{...}}
+//! Server -> Client: {"type":"approval_request","id":"1a2b3c4d","tool":"shell",...}
+//! Client -> Server: {"type":"approval","id":"1a2b3c4d","decision":"yes"}
+//! Server -> Client: {"type":"approval_ack","id":"1a2b3c4d","resolved":true}
+//! Client -> Server: {"type":"cancel"}
+//! Server -> Client: {"type":"cancelled"}
 //! ```
+//!
+//! A cancelled or failed turn is dropped from the history, so the next
+//! message continues from the last completed turn.
+//!
+//! A connection only sees and answers the approval requests raised by its
+//! own tool calls. A session can be open on one connection at a time; a
+//! second socket asking for it gets an `error` frame and is closed.
 
-use super::AppState;
+use super::{gateway_approval_manager, AppState};
+use crate::agent::loop_::{
+    is_tool_loop_cancelled, persist_session_turn, DRAFT_CLEAR_SENTINEL, DRAFT_PROGRESS_PREFIX,
+};
+use crate::agent::ConversationAgent;
+use crate::approval::{ApprovalManager, ApprovalResponse};
+use crate::config::schema::ModelPricing;
+use crate::cost::{lookup_pricing, CostAttribution, TokenUsage};
+use crate::observability::traits::ObserverMetric;
+use crate::observability::{Observer, ObserverEvent};
+use crate::providers::ChatMessage;
+use crate::sessions::SessionStore;
 use axum::{
     extract::{
         ws::{Message, WebSocket},
@@ -17,12 +48,23 @@ use axum::{
     },
     response::IntoResponse,
 };
-use futures_util::{SinkExt, StreamExt};
+use futures_util::{
+    stream::{SplitSink, SplitStream},
+    SinkExt, StreamExt,
+};
+use parking_lot::Mutex;
 use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use tokio::sync::{broadcast, mpsc};
+use tokio_util::sync::CancellationToken;
 
 #[derive(Deserialize)]
 pub struct WsQuery {
     pub token: Option<String>,
+    /// Stored session to resume and append to.
+    pub session: Option<String>,
 }
 
 /// GET /ws/chat — WebSocket upgrade for agent chat
@@ -43,125 +85,900 @@ pub async fn handle_ws_chat(
         }
     }
 
-    ws.on_upgrade(move |socket| handle_socket(socket, state))
+    let session = params.session.filter(|id| !id.trim().is_empty());
+    ws.on_upgrade(move |socket| handle_socket(socket, state, session))
         .into_response()
 }
 
-async fn handle_socket(socket: WebSocket, state: AppState) {
-    let (mut sender, mut receiver) = socket.split();
+/// The socket halves plus the gateway event feed, which carries approval
+/// requests raised by this connection's tool calls.
+struct Client {
+    sender: SplitSink<WebSocket, Message>,
+    receiver: SplitStream<WebSocket>,
+    events: broadcast::Receiver<serde_json::Value>,
+    /// Approval scope of this connection's tool calls.
+    scope: String,
+    /// Approval requests relayed to this client and not yet resolved.
+    relayed: HashSet<String>,
+}
 
-    while let Some(msg) = receiver.next().await {
-        let msg = match msg {
-            Ok(Message::Text(text)) => text,
-            Ok(Message::Close(_)) => break,
-            Err(_) => break,
-            _ => continue,
+impl Client {
+    /// Relay the approval events that belong to this connection: requests
+    /// raised under its scope and the resolution of those requests.
+    async fn relay_event(&mut self, event: &serde_json::Value) {
+        let id = event["id"].as_str().unwrap_or_default();
+        let relay = match event["type"].as_str() {
+            Some("approval_request") if event["scope"] == self.scope.as_str() => {
+                self.relayed.insert(id.to_string())
+            }
+            Some("approval_resolved") => self.relayed.remove(id),
+            _ => false,
         };
+        if relay {
+            let _ = send_frame(&mut self.sender, event).await;
+        }
+    }
+}
 
-        // Parse incoming message
-        let parsed: serde_json::Value = match serde_json::from_str(&msg) {
-            Ok(v) => v,
-            Err(_) => {
-                let err = serde_json::json!({"type": "error", "message": "Invalid JSON"});
-                let _ = sender.send(Message::Text(err.to_string().into())).await;
-                continue;
+/// Marks a stored session as open on a connection until dropped.
+struct SessionClaim {
+    key: (PathBuf, String),
+}
+
+impl SessionClaim {
+    fn open_sessions() -> &'static Mutex<HashSet<(PathBuf, String)>> {
+        static OPEN: OnceLock<Mutex<HashSet<(PathBuf, String)>>> = OnceLock::new();
+        OPEN.get_or_init(|| Mutex::new(HashSet::new()))
+    }
+
+    /// Claim session `id` of `workspace_dir`, failing while another
+    /// connection holds it.
+    fn acquire(workspace_dir: &Path, id: &str) -> anyhow::Result<Self> {
+        let key = (workspace_dir.to_path_buf(), id.to_string());
+        if !Self::open_sessions().lock().insert(key.clone()) {
+            anyhow::bail!("session '{id}' is already open in another connection");
+        }
+        Ok(Self { key })
+    }
+}
+
+impl Drop for SessionClaim {
+    fn drop(&mut self) {
+        Self::open_sessions().lock().remove(&self.key);
+    }
+}
+
+/// Agent state that lives as long as the connection.
+struct Connection {
+    agent: ConversationAgent,
+    history: Vec<ChatMessage>,
+    session: Option<(SessionStore, String)>,
+    /// Held while `session` is open here; released when the socket closes.
+    _claim: Option<SessionClaim>,
+    approval: Option<ApprovalManager>,
+}
+
+impl Connection {
+    async fn open(
+        state: &AppState,
+        session_id: Option<&str>,
+        scope: &str,
+        frames: mpsc::UnboundedSender<serde_json::Value>,
+    ) -> anyhow::Result<(Self, Option<usize>)> {
+        let mut config = state.config.lock().clone();
+        config.default_model = Some(state.model.clone());
+
+        let mut claim = None;
+        let session = match session_id {
+            Some(id) => {
+                let store = SessionStore::open(&config.workspace_dir)?;
+                let info = store.ensure(id, "gateway")?;
+                claim = Some(SessionClaim::acquire(&config.workspace_dir, &info.id)?);
+                let transcript = store.load(&info.id)?;
+                Some((store, info.id, transcript))
             }
+            None => None,
         };
 
-        let msg_type = parsed["type"].as_str().unwrap_or("");
-        if msg_type != "message" {
-            continue;
-        }
-
-        let content = parsed["content"].as_str().unwrap_or("").to_string();
-        if content.is_empty() {
-            continue;
-        }
-
-        // Process message with the LLM provider
-        let provider_label = state
-            .config
-            .lock()
-            .default_provider
-            .clone()
-            .unwrap_or_else(|| "unknown".to_string());
-
-        // Broadcast agent_start event
-        let _ = state.event_tx.send(serde_json::json!({
-            "type": "agent_start",
-            "provider": provider_label,
-            "model": state.model,
-        }));
-
-        // Simple single-turn chat (no streaming for now — use provider.chat_with_system)
-        let system_prompt = {
-            let config_guard = state.config.lock();
-            crate::channels::build_system_prompt(
-                &config_guard.workspace_dir,
-                &state.model,
-                &[],
-                &[],
-                Some(&config_guard.identity),
-                None,
-            )
+        let observer: Arc<dyn Observer> = Arc::new(FrameObserver {
+            inner: Arc::clone(&state.observer),
+            frames,
+            prices: config.cost.prices.clone(),
+        });
+        let attribution = CostAttribution::channel("ws", session_id.unwrap_or("ws"));
+        let approval = gateway_approval_manager(state, &config, scope);
+        let agent = ConversationAgent::with_observer(config, attribution, observer).await?;
+
+        let mut history = vec![ChatMessage::system(agent.system_prompt())];
+        let mut resumed = None;
+        let session = session.map(|(store, id, transcript)| {
+            history.extend(crate::sessions::history_from_transcript(
+                &transcript,
+                agent.supports_native_tools(),
+            ));
+            resumed = Some(transcript.len());
+            (store, id)
+        });
+
+        Ok((
+            Self {
+                agent,
+                history,
+                session,
+                _claim: claim,
+                approval,
+            },
+            resumed,
+        ))
+    }
+}
+
+async fn handle_socket(socket: WebSocket, state: AppState, session_id: Option<String>) {
+    let (sender, receiver) = socket.split();
+    let mut client = Client {
+        sender,
+        receiver,
+        events: state.event_tx.subscribe(),
+        scope: format!("ws_{}", &uuid::Uuid::new_v4().simple().to_string()[..12]),
+        relayed: HashSet::new(),
+    };
+
+    let (frame_tx, mut frames) = mpsc::unbounded_channel();
+    let opened = Box::pin(Connection::open(
+        &state,
+        session_id.as_deref(),
+        &client.scope,
+        frame_tx,
+    ))
+    .await;
+    let mut conn = match opened {
+        Ok((conn, resumed)) => {
+            if let (Some((_, id)), Some(messages)) = (&conn.session, resumed) {
+                let frame = serde_json::json!({
+                    "type": "session",
+                    "id": id,
+                    "messages": messages,
+                });
+                let _ = send_frame(&mut client.sender, &frame).await;
+            }
+            conn
+        }
+        Err(e) => {
+            let sanitized = crate::providers::sanitize_api_error(&format!("{e:#}"));
+            tracing::warn!("WebSocket agent setup failed: {sanitized}");
+            let err = serde_json::json!({"type": "error", "message": sanitized});
+            let _ = send_frame(&mut client.sender, &err).await;
+            return;
+        }
+    };
+
+    loop {
+        let msg = tokio::select! {
+            incoming = client.receiver.next() => match incoming {
+                Some(Ok(Message::Text(text))) => text,
+                Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+            event = client.events.recv() => {
+                match event {
+                    Ok(event) => client.relay_event(&event).await,
+                    Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+                continue;
+            }
         };
 
-        let messages = vec![
-            crate::providers::ChatMessage::system(system_prompt),
-            crate::providers::ChatMessage::user(&content),
-        ];
+        let parsed = match parse_frame(&msg) {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                let _ = send_frame(&mut client.sender, &err).await;
+                continue;
+            }
+        };
 
-        let multimodal_config = state.config.lock().multimodal.clone();
-        let prepared =
-            match crate::multimodal::prepare_messages_for_provider(&messages, &multimodal_config)
-                .await
-            {
-                Ok(p) => p,
-                Err(e) => {
-                    let err = serde_json::json!({
-                        "type": "error",
-                        "message": format!("Multimodal prep failed: {e}")
-                    });
-                    let _ = sender.send(Message::Text(err.to_string().into())).await;
+        match parsed["type"].as_str().unwrap_or("") {
+            "approval" => {
+                let ack = answer_approval(&state, &client.scope, &parsed);
+                let _ = send_frame(&mut client.sender, &ack).await;
+            }
+            "message" => {
+                let content = parsed["content"].as_str().unwrap_or("");
+                if content.is_empty() {
                     continue;
                 }
-            };
+                if !run_turn(&mut client, &mut frames, &mut conn, &state, content).await {
+                    break;
+                }
+            }
+            // `cancel` with no turn in flight has nothing to do.
+            _ => {}
+        }
+    }
+}
 
-        match state
-            .provider
-            .chat_with_history(&prepared.messages, &state.model, state.temperature)
-            .await
-        {
-            Ok(response) => {
-                // Send the full response as a done message
-                let done = serde_json::json!({
-                    "type": "done",
-                    "full_response": response,
-                });
-                let _ = sender.send(Message::Text(done.to_string().into())).await;
+/// Run one agent turn, relaying its frames while still serving the client's
+/// `cancel` and `approval` frames. Returns `false` once the socket is closed.
+async fn run_turn(
+    client: &mut Client,
+    frames: &mut mpsc::UnboundedReceiver<serde_json::Value>,
+    conn: &mut Connection,
+    state: &AppState,
+    content: &str,
+) -> bool {
+    let provider_label = state
+        .config
+        .lock()
+        .default_provider
+        .clone()
+        .unwrap_or_else(|| "unknown".to_string());
+    let _ = state.event_tx.send(serde_json::json!({
+        "type": "agent_start",
+        "provider": provider_label,
+        "model": state.model,
+    }));
 
-                // Broadcast agent_end event
-                let _ = state.event_tx.send(serde_json::json!({
-                    "type": "agent_end",
-                    "provider": provider_label,
-                    "model": state.model,
-                }));
+    if let Err(e) = conn.agent.compact(&mut conn.history).await {
+        tracing::warn!("WebSocket history compaction failed: {e}");
+    }
+    let cancel = CancellationToken::new();
+    let (delta_tx, mut deltas) = mpsc::channel::<String>(64);
+    let mut usage = TurnUsage::default();
+    let mut open = true;
+
+    let result = {
+        let turn = conn.agent.turn(
+            &mut conn.history,
+            content,
+            conn.approval.as_ref(),
+            state.hooks.as_deref(),
+            Some(cancel.clone()),
+            Some(delta_tx),
+        );
+        tokio::pin!(turn);
+
+        loop {
+            tokio::select! {
+                result = &mut turn => break result,
+                Some(delta) = deltas.recv() => {
+                    let _ = send_frame(&mut client.sender, &delta_frame(&delta)).await;
+                }
+                Some(frame) = frames.recv() => {
+                    usage.add(&frame);
+                    let _ = send_frame(&mut client.sender, &frame).await;
+                }
+                incoming = client.receiver.next(), if open => match incoming {
+                    Some(Ok(Message::Text(text))) => {
+                        let reply = answer_during_turn(state, &client.scope, &text, &cancel);
+                        if let Some(reply) = reply {
+                            let _ = send_frame(&mut client.sender, &reply).await;
+                        }
+                    }
+                    Some(Ok(Message::Close(_)) | Err(_)) | None => {
+                        // Nobody is left to read the answer.
+                        open = false;
+                        cancel.cancel();
+                    }
+                    Some(Ok(_)) => {}
+                },
+                Ok(event) = client.events.recv() => client.relay_event(&event).await,
             }
-            Err(e) => {
-                let sanitized = crate::providers::sanitize_api_error(&e.to_string());
-                let err = serde_json::json!({
-                    "type": "error",
-                    "message": sanitized,
-                });
-                let _ = sender.send(Message::Text(err.to_string().into())).await;
+        }
+    };
+
+    while let Ok(delta) = deltas.try_recv() {
+        let _ = send_frame(&mut client.sender, &delta_frame(&delta)).await;
+    }
+    while let Ok(frame) = frames.try_recv() {
+        usage.add(&frame);
+        let _ = send_frame(&mut client.sender, &frame).await;
+    }
 
-                // Broadcast error event
+    // The tool loop may have summarized older turns, shifting indices.
+    let turn_start = crate::agent::context::current_turn_start(&conn.history);
+    let frame = match result {
+        Ok(response) => {
+            if let Some((store, id)) = &conn.session {
+                persist_session_turn(store, id, &conn.history[turn_start..], content);
+            }
+            let _ = state.event_tx.send(serde_json::json!({
+                "type": "agent_end",
+                "provider": provider_label,
+                "model": state.model,
+            }));
+            serde_json::json!({
+                "type": "done",
+                "full_response": response,
+                "usage": usage.to_json(),
+            })
+        }
+        Err(e) => {
+            conn.history.truncate(turn_start);
+            if is_tool_loop_cancelled(&e) {
+                serde_json::json!({"type": "cancelled", "usage": usage.to_json()})
+            } else {
+                let sanitized = crate::providers::sanitize_api_error(&e.to_string());
                 let _ = state.event_tx.send(serde_json::json!({
                     "type": "error",
                     "component": "ws_chat",
                     "message": sanitized,
                 }));
+                serde_json::json!({"type": "error", "message": sanitized})
             }
         }
+    };
+    if open {
+        let _ = send_frame(&mut client.sender, &frame).await;
+    }
+    open
+}
+
+/// Handle a client frame that arrives while a turn is running.
+fn answer_during_turn(
+    state: &AppState,
+    scope: &str,
+    text: &str,
+    cancel: &CancellationToken,
+) -> Option<serde_json::Value> {
+    let parsed = match parse_frame(text) {
+        Ok(parsed) => parsed,
+        Err(err) => return Some(err),
+    };
+    match parsed["type"].as_str().unwrap_or("") {
+        "cancel" => {
+            cancel.cancel();
+            None
+        }
+        "approval" => Some(answer_approval(state, scope, &parsed)),
+        "message" => Some(serde_json::json!({
+            "type": "error",
+            "message": "A turn is already running; wait for it or send {\"type\":\"cancel\"}",
+        })),
+        _ => None,
+    }
+}
+
+fn parse_frame(text: &str) -> Result<serde_json::Value, serde_json::Value> {
+    serde_json::from_str(text)
+        .map_err(|_| serde_json::json!({"type": "error", "message": "Invalid JSON"}))
+}
+
+async fn send_frame(
+    sender: &mut SplitSink<WebSocket, Message>,
+    frame: &serde_json::Value,
+) -> Result<(), axum::Error> {
+    sender.send(Message::Text(frame.to_string().into())).await
+}
+
+/// Map a tool-loop delta to its frame.
+fn delta_frame(delta: &str) -> serde_json::Value {
+    if delta == DRAFT_CLEAR_SENTINEL {
+        serde_json::json!({"type": "clear"})
+    } else if let Some(line) = delta.strip_prefix(DRAFT_PROGRESS_PREFIX) {
+        serde_json::json!({"type": "progress", "content": line})
+    } else {
+        serde_json::json!({"type": "chunk", "content": delta})
+    }
+}
+
+/// Apply a client's `approval` frame and build the acknowledgement. Only
+/// requests raised under the connection's `scope` can be answered.
+fn answer_approval(state: &AppState, scope: &str, frame: &serde_json::Value) -> serde_json::Value {
+    let id = frame["id"].as_str().unwrap_or_default();
+    let Ok(decision) = serde_json::from_value::<ApprovalResponse>(frame["decision"].clone()) else {
+        return serde_json::json!({
+            "type": "error",
+            "message": "approval decision must be \"yes\", \"no\" or \"always\"",
+        });
+    };
+    serde_json::json!({
+        "type": "approval_ack",
+        "id": id,
+        "resolved": state.pending_approvals.is_pending_in(scope, id)
+            && super::api::resolve_approval(state, id, decision),
+    })
+}
+
+/// Token and cost totals for one turn, summed from its `cost` frames.
+#[derive(Default)]
+struct TurnUsage {
+    input_tokens: u64,
+    output_tokens: u64,
+    cost_usd: f64,
+}
+
+impl TurnUsage {
+    fn add(&mut self, frame: &serde_json::Value) {
+        if frame["type"] != "cost" {
+            return;
+        }
+        self.input_tokens += frame["input_tokens"].as_u64().unwrap_or(0);
+        self.output_tokens += frame["output_tokens"].as_u64().unwrap_or(0);
+        self.cost_usd += frame["cost_usd"].as_f64().unwrap_or(0.0);
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "input_tokens": self.input_tokens,
+            "output_tokens": self.output_tokens,
+            "cost_usd": self.cost_usd,
+        })
+    }
+}
+
+/// Forwards to the gateway observer and mirrors one connection's tool and
+/// usage events to its socket.
+struct FrameObserver {
+    inner: Arc<dyn Observer>,
+    frames: mpsc::UnboundedSender<serde_json::Value>,
+    prices: HashMap<String, ModelPricing>,
+}
+
+impl FrameObserver {
+    fn frame_for(&self, event: &ObserverEvent) -> Option<serde_json::Value> {
+        match event {
+            ObserverEvent::ToolCallStart { tool } => Some(serde_json::json!({
+                "type": "tool_start",
+                "name": tool,
+            })),
+            ObserverEvent::ToolCall {
+                tool,
+                duration,
+                success,
+            } => Some(serde_json::json!({
+                "type": "tool_end",
+                "name": tool,
+                "success": success,
+                "duration_ms": duration.as_millis(),
+            })),
+            ObserverEvent::LlmResponse {
+                provider,
+                model,
+                input_tokens,
+                output_tokens,
+                ..
+            } if input_tokens.is_some() || output_tokens.is_some() => {
+                let input_tokens = input_tokens.unwrap_or(0);
+                let output_tokens = output_tokens.unwrap_or(0);
+                let cost_usd =
+                    lookup_pricing(&self.prices, provider, model).map_or(0.0, |pricing| {
+                        TokenUsage::new(
+                            model.as_str(),
+                            input_tokens,
+                            output_tokens,
+                            pricing.input,
+                            pricing.output,
+                        )
+                        .cost_usd
+                    });
+                Some(serde_json::json!({
+                    "type": "cost",
+                    "model": model,
+                    "input_tokens": input_tokens,
+                    "output_tokens": output_tokens,
+                    "cost_usd": cost_usd,
+                }))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Observer for FrameObserver {
+    fn record_event(&self, event: &ObserverEvent) {
+        self.inner.record_event(event);
+        if let Some(frame) = self.frame_for(event) {
+            let _ = self.frames.send(frame);
+        }
+    }
+
+    fn record_metric(&self, metric: &ObserverMetric) {
+        self.inner.record_metric(metric);
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+
+    fn name(&self) -> &str {
+        "ws"
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::test_app_state;
+    use super::*;
+    use crate::config::Config;
+    use crate::observability::NoopObserver;
+    use crate::security::{AutonomyLevel, PairingGuard};
+    use axum::routing::{get, post};
+    use axum::Json;
+    use std::time::Duration;
+    use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+    type TestSocket = tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >;
+
+    fn observer(
+        prices: HashMap<String, ModelPricing>,
+    ) -> (FrameObserver, mpsc::UnboundedReceiver<serde_json::Value>) {
+        let (frames, rx) = mpsc::unbounded_channel();
+        let observer = FrameObserver {
+            inner: Arc::new(NoopObserver),
+            frames,
+            prices,
+        };
+        (observer, rx)
+    }
+
+    #[test]
+    fn delta_frames_separate_progress_answer_and_clear() {
+        assert_eq!(
+            delta_frame("Hello "),
+            serde_json::json!({"type": "chunk", "content": "Hello "})
+        );
+        assert_eq!(delta_frame(DRAFT_CLEAR_SENTINEL)["type"], "clear");
+        let progress = format!("{DRAFT_PROGRESS_PREFIX}\u{23f3} shell: ls\n");
+        assert_eq!(
+            delta_frame(&progress),
+            serde_json::json!({"type": "progress", "content": "\u{23f3} shell: ls\n"})
+        );
+    }
+
+    #[test]
+    fn observer_emits_tool_start_and_end_frames() {
+        let (observer, mut rx) = observer(HashMap::new());
+        observer.record_event(&ObserverEvent::ToolCallStart {
+            tool: "shell".into(),
+        });
+        observer.record_event(&ObserverEvent::ToolCall {
+            tool: "shell".into(),
+            duration: Duration::from_millis(12),
+            success: false,
+        });
+        observer.record_event(&ObserverEvent::TurnComplete);
+
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            serde_json::json!({"type": "tool_start", "name": "shell"})
+        );
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            serde_json::json!({
+                "type": "tool_end",
+                "name": "shell",
+                "success": false,
+                "duration_ms": 12,
+            })
+        );
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn cost_frames_are_priced_and_summed_per_turn() {
+        let prices = HashMap::from([(
+            "test/model".to_string(),
+            ModelPricing {
+                input: 1.0,
+                output: 2.0,
+                cache_read: None,
+                cache_write: None,
+            },
+        )]);
+        let (observer, mut rx) = observer(prices);
+        let response = |model: &str, input: Option<u64>| ObserverEvent::LlmResponse {
+            provider: "test".into(),
+            model: model.into(),
+            duration: Duration::from_millis(5),
+            success: true,
+            error_message: None,
+            input_tokens: input,
+            output_tokens: Some(500_000),
+        };
+        observer.record_event(&response("test/model", Some(1_000_000)));
+        observer.record_event(&response("unpriced/model", None));
+        // Served name without the provider prefix resolves the same entry.
+        observer.record_event(&response("model", None));
+
+        let mut usage = TurnUsage::default();
+        let priced = rx.try_recv().unwrap();
+        assert_eq!(priced["type"], "cost");
+        assert!((priced["cost_usd"].as_f64().unwrap() - 2.0).abs() < 1e-9);
+        usage.add(&priced);
+        let unpriced = rx.try_recv().unwrap();
+        assert_eq!(unpriced["input_tokens"], 0);
+        assert_eq!(unpriced["cost_usd"], 0.0);
+        usage.add(&unpriced);
+        let unprefixed = rx.try_recv().unwrap();
+        assert!((unprefixed["cost_usd"].as_f64().unwrap() - 1.0).abs() < 1e-9);
+        usage.add(&unprefixed);
+        usage.add(&serde_json::json!({"type": "tool_start", "name": "shell"}));
+
+        let totals = usage.to_json();
+        assert_eq!(totals["input_tokens"], 1_000_000);
+        assert_eq!(totals["output_tokens"], 1_500_000);
+        assert!((totals["cost_usd"].as_f64().unwrap() - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn invalid_json_is_reported() {
+        let err = parse_frame("not json").unwrap_err();
+        assert_eq!(err["type"], "error");
+        assert!(parse_frame(r#"{"type":"cancel"}"#).is_ok());
+    }
+
+    /// OpenAI-compatible upstream that answers call `n` with `replies[n]`
+    /// (the last reply repeats), streamed as one SSE chunk when asked to; a
+    /// `None` reply never arrives. Also returns the request bodies it has seen.
+    async fn spawn_upstream(
+        replies: Vec<Option<serde_json::Value>>,
+    ) -> (String, Arc<Mutex<Vec<serde_json::Value>>>) {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&requests);
+        let replies = Arc::new(replies);
+        let app = axum::Router::new().route(
+            "/v1/chat/completions",
+            post(move |Json(body): Json<serde_json::Value>| {
+                let seen = Arc::clone(&seen);
+                let replies = Arc::clone(&replies);
+                async move {
+                    let stream = body["stream"] == true;
+                    let call = {
+                        let mut seen = seen.lock();
+                        seen.push(body);
+                        seen.len() - 1
+                    };
+                    let Some(mut message) = replies[call.min(replies.len() - 1)].clone() else {
+                        return std::future::pending().await;
+                    };
+                    if !stream {
+                        return Json(serde_json::json!({
+                            "id": "upstream",
+                            "object": "chat.completion",
+                            "created": 0,
+                            "model": "upstream-model",
+                            "choices": [{"index": 0, "message": message, "finish_reason": "stop"}]
+                        }))
+                        .into_response();
+                    }
+                    if let Some(calls) = message
+                        .get_mut("tool_calls")
+                        .and_then(serde_json::Value::as_array_mut)
+                    {
+                        for (index, call) in calls.iter_mut().enumerate() {
+                            call["index"] = index.into();
+                        }
+                    }
+                    let chunk = serde_json::json!({"choices": [{"index": 0, "delta": message}]});
+                    (
+                        [(axum::http::header::CONTENT_TYPE, "text/event-stream")],
+                        format!("data: {chunk}\n\ndata: [DONE]\n\n"),
+                    )
+                        .into_response()
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("custom:http://{addr}/v1"), requests)
+    }
+
+    fn answer(content: &str) -> Option<serde_json::Value> {
+        Some(serde_json::json!({"role": "assistant", "content": content}))
+    }
+
+    fn agent_config(tmp: &tempfile::TempDir, provider: String) -> Config {
+        let mut config = Config::default();
+        config.workspace_dir = tmp.path().join("workspace");
+        config.config_path = tmp.path().join("config.toml");
+        config.default_provider = Some(provider);
+        config.default_model = Some("upstream-model".into());
+        config.api_key = Some("upstream-key".into());
+        config.memory.backend = "none".into();
+        std::fs::create_dir_all(&config.workspace_dir).unwrap();
+        config
+    }
+
+    /// Serve `/ws/chat` for `state` and return its URL.
+    async fn serve(state: AppState) -> String {
+        let app = axum::Router::new()
+            .route("/ws/chat", get(handle_ws_chat))
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("ws://{addr}/ws/chat")
+    }
+
+    async fn connect(url: &str) -> TestSocket {
+        tokio_tungstenite::connect_async(url).await.unwrap().0
+    }
+
+    async fn send(socket: &mut TestSocket, frame: serde_json::Value) {
+        socket
+            .send(WsMessage::Text(frame.to_string().into()))
+            .await
+            .unwrap();
+    }
+
+    /// Next text frame, or `None` once the server closes the socket.
+    async fn next_frame(socket: &mut TestSocket) -> Option<serde_json::Value> {
+        loop {
+            let message = tokio::time::timeout(Duration::from_secs(10), socket.next())
+                .await
+                .expect("timed out waiting for a frame");
+            match message {
+                Some(Ok(WsMessage::Text(text))) => {
+                    return Some(serde_json::from_str(&text).unwrap())
+                }
+                Some(Ok(WsMessage::Close(_)) | Err(_)) | None => return None,
+                Some(Ok(_)) => {}
+            }
+        }
+    }
+
+    /// Skip ahead to the next frame of type `kind`.
+    async fn frame_of_type(socket: &mut TestSocket, kind: &str) -> serde_json::Value {
+        loop {
+            let frame = next_frame(socket).await.expect("socket closed");
+            if frame["type"] == kind {
+                return frame;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn cancel_mid_turn_drops_the_turn_from_history() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (provider, requests) = spawn_upstream(vec![None, answer("Four.")]).await;
+        let state = test_app_state(agent_config(&tmp, provider), PairingGuard::new(false, &[]));
+        let mut socket = connect(&serve(state).await).await;
+
+        send(
+            &mut socket,
+            serde_json::json!({"type": "message", "content": "first question"}),
+        )
+        .await;
+        while requests.lock().is_empty() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        send(&mut socket, serde_json::json!({"type": "cancel"})).await;
+        frame_of_type(&mut socket, "cancelled").await;
+
+        send(
+            &mut socket,
+            serde_json::json!({"type": "message", "content": "what's 2+2?"}),
+        )
+        .await;
+        let done = frame_of_type(&mut socket, "done").await;
+        assert_eq!(done["full_response"], "Four.");
+
+        let requests = requests.lock();
+        assert_eq!(requests.len(), 2);
+        let retried = requests[1].to_string();
+        assert!(retried.contains("what's 2+2?"));
+        assert!(!retried.contains("first question"));
+    }
+
+    #[tokio::test]
+    async fn sessions_resume_and_open_on_one_socket_at_a_time() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (provider, requests) = spawn_upstream(vec![answer("Four.")]).await;
+        let state = test_app_state(agent_config(&tmp, provider), PairingGuard::new(false, &[]));
+        let url = format!("{}?session=work", serve(state).await);
+
+        let mut first = connect(&url).await;
+        assert_eq!(
+            next_frame(&mut first).await.unwrap(),
+            serde_json::json!({"type": "session", "id": "work", "messages": 0})
+        );
+        send(
+            &mut first,
+            serde_json::json!({"type": "message", "content": "what's 2+2?"}),
+        )
+        .await;
+        frame_of_type(&mut first, "done").await;
+
+        let mut second = connect(&url).await;
+        let refused = next_frame(&mut second).await.unwrap();
+        assert_eq!(refused["type"], "error");
+        assert!(refused["message"]
+            .as_str()
+            .unwrap()
+            .contains("already open in another connection"));
+        assert!(next_frame(&mut second).await.is_none());
+
+        first.close(None).await.unwrap();
+        while next_frame(&mut first).await.is_some() {}
+        // The server releases the session once its handler has wound down.
+        let mut resumed = None;
+        for _ in 0..50 {
+            let mut socket = connect(&url).await;
+            let frame = next_frame(&mut socket).await.unwrap();
+            if frame["type"] == "session" {
+                resumed = Some((socket, frame));
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        let (mut socket, frame) = resumed.expect("session was never released");
+        assert_eq!(frame["messages"], 2);
+
+        send(
+            &mut socket,
+            serde_json::json!({"type": "message", "content": "and 3+3?"}),
+        )
+        .await;
+        frame_of_type(&mut socket, "done").await;
+        let resumed_request = requests.lock().last().unwrap().to_string();
+        assert!(resumed_request.contains("what's 2+2?"));
+        assert!(resumed_request.contains("and 3+3?"));
+    }
+
+    #[tokio::test]
+    async fn approval_requests_reach_only_the_socket_that_raised_them() {
+        let tmp = tempfile::tempdir().unwrap();
+        let tool_call = serde_json::json!({
+            "role": "assistant",
+            "content": "",
+            "tool_calls": [{
+                "id": "call_1",
+                "type": "function",
+                "function": {"name": "shell", "arguments": "{\"command\":\"ls\"}"}
+            }]
+        });
+        let (provider, requests) = spawn_upstream(vec![Some(tool_call), answer("Four.")]).await;
+        let mut config = agent_config(&tmp, provider);
+        config.autonomy.level = AutonomyLevel::Supervised;
+        config.autonomy.remote_approvals = true;
+        let state = test_app_state(config, PairingGuard::new(false, &[]));
+        let url = serve(state).await;
+        let mut owner = connect(&url).await;
+        let mut other = connect(&url).await;
+
+        send(
+            &mut owner,
+            serde_json::json!({"type": "message", "content": "list the files"}),
+        )
+        .await;
+        let request = frame_of_type(&mut owner, "approval_request").await;
+        assert_eq!(request["tool"], "shell");
+        let id = request["id"].as_str().unwrap();
+
+        send(
+            &mut other,
+            serde_json::json!({"type": "approval", "id": id, "decision": "yes"}),
+        )
+        .await;
+        let ack = next_frame(&mut other).await.unwrap();
+        assert_eq!(
+            ack,
+            serde_json::json!({"type": "approval_ack", "id": id, "resolved": false})
+        );
+
+        send(
+            &mut owner,
+            serde_json::json!({"type": "approval", "id": id, "decision": "no"}),
+        )
+        .await;
+        let ack = frame_of_type(&mut owner, "approval_ack").await;
+        assert_eq!(ack["resolved"], true);
+        let done = frame_of_type(&mut owner, "done").await;
+        assert_eq!(done["full_response"], "Four.");
+        assert!(requests.lock()[1].to_string().contains("Denied"));
+
+        // The other socket never saw the request or its resolution.
+        assert!(
+            tokio::time::timeout(Duration::from_millis(200), other.next())
+                .await
+                .is_err()
+        );
     }
 }
@@ -20,6 +20,8 @@ pub struct HealthSnapshot {
     pub updated_at: String,
     pub uptime_seconds: u64,
     pub components: BTreeMap<String, ComponentHealth>,
+    /// Provider circuit breakers seen by this process (provider-wide and per model).
+    pub provider_circuits: Vec<crate::providers::health::CircuitSnapshot>,
 }
 
 struct HealthRegistry {
@@ -90,6 +92,7 @@ pub fn snapshot() -> HealthSnapshot {
         updated_at: now_rfc3339(),
         uptime_seconds: registry().started_at.elapsed().as_secs(),
         components,
+        provider_circuits: crate::providers::health::snapshot(),
     }
 }
 
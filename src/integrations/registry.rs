@@ -97,7 +97,13 @@ pub fn all_integrations() -> Vec<IntegrationEntry> {
             name: "Microsoft Teams",
             description: "Enterprise chat support",
             category: IntegrationCategory::Chat,
-            status_fn: |_| IntegrationStatus::ComingSoon,
+            status_fn: |c| {
+                if c.channels_config.teams.is_some() {
+                    IntegrationStatus::Active
+                } else {
+                    IntegrationStatus::Available
+                }
+            },
         },
         IntegrationEntry {
             name: "Matrix",
@@ -121,7 +127,13 @@ pub fn all_integrations() -> Vec<IntegrationEntry> {
             name: "WebChat",
             description: "Browser-based chat UI",
             category: IntegrationCategory::Chat,
-            status_fn: |_| IntegrationStatus::ComingSoon,
+            status_fn: |c| {
+                if c.channels_config.webchat.is_some() {
+                    IntegrationStatus::Active
+                } else {
+                    IntegrationStatus::Available
+                }
+            },
         },
         IntegrationEntry {
             name: "Nextcloud Talk",
@@ -489,7 +501,13 @@ pub fn all_integrations() -> Vec<IntegrationEntry> {
             name: "GitHub",
             description: "Code, issues, PRs",
             category: IntegrationCategory::Productivity,
-            status_fn: |_| IntegrationStatus::ComingSoon,
+            status_fn: |c| {
+                if c.github.enabled {
+                    IntegrationStatus::Active
+                } else {
+                    IntegrationStatus::Available
+                }
+            },
         },
         IntegrationEntry {
             name: "Notion",
@@ -513,7 +531,13 @@ pub fn all_integrations() -> Vec<IntegrationEntry> {
             name: "Obsidian",
             description: "Knowledge graph notes",
             category: IntegrationCategory::Productivity,
-            status_fn: |_| IntegrationStatus::ComingSoon,
+            status_fn: |c| {
+                if c.memory.backend == "obsidian" {
+                    IntegrationStatus::Active
+                } else {
+                    IntegrationStatus::Available
+                }
+            },
         },
         IntegrationEntry {
             name: "Things 3",
@@ -563,7 +587,13 @@ pub fn all_integrations() -> Vec<IntegrationEntry> {
             name: "Home Assistant",
             description: "Home automation hub",
             category: IntegrationCategory::SmartHome,
-            status_fn: |_| IntegrationStatus::ComingSoon,
+            status_fn: |c| {
+                if c.home_assistant.enabled {
+                    IntegrationStatus::Active
+                } else {
+                    IntegrationStatus::Available
+                }
+            },
         },
         IntegrationEntry {
             name: "Philips Hue",
@@ -862,7 +892,7 @@ mod tests {
     fn coming_soon_integrations_stay_coming_soon() {
         let config = Config::default();
         let entries = all_integrations();
-        for name in ["Nostr", "Spotify", "Home Assistant"] {
+        for name in ["Nostr", "Spotify"] {
             let entry = entries.iter().find(|e| e.name == name).unwrap();
             assert!(
                 matches!((entry.status_fn)(&config), IntegrationStatus::ComingSoon),
@@ -871,6 +901,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn github_active_when_enabled() {
+        let mut config = Config::default();
+        let entries = all_integrations();
+        let gh = entries.iter().find(|e| e.name == "GitHub").unwrap();
+        assert!(matches!(
+            (gh.status_fn)(&config),
+            IntegrationStatus::Available
+        ));
+        config.github.enabled = true;
+        assert!(matches!((gh.status_fn)(&config), IntegrationStatus::Active));
+    }
+
+    #[test]
+    fn obsidian_active_when_memory_backend() {
+        let mut config = Config::default();
+        let entries = all_integrations();
+        let obsidian = entries.iter().find(|e| e.name == "Obsidian").unwrap();
+        assert!(matches!(
+            (obsidian.status_fn)(&config),
+            IntegrationStatus::Available
+        ));
+        config.memory.backend = "obsidian".into();
+        assert!(matches!(
+            (obsidian.status_fn)(&config),
+            IntegrationStatus::Active
+        ));
+    }
+
+    #[test]
+    fn home_assistant_active_when_enabled() {
+        let mut config = Config::default();
+        let entries = all_integrations();
+        let ha = entries.iter().find(|e| e.name == "Home Assistant").unwrap();
+        assert!(matches!(
+            (ha.status_fn)(&config),
+            IntegrationStatus::Available
+        ));
+        config.home_assistant.enabled = true;
+        assert!(matches!((ha.status_fn)(&config), IntegrationStatus::Active));
+    }
+
     #[test]
     fn whatsapp_available_when_not_configured() {
         let config = Config::default();
@@ -60,11 +60,13 @@ pub(crate) mod multimodal;
 pub mod observability;
 pub(crate) mod onboard;
 pub mod peripherals;
+pub(crate) mod plugins;
 pub mod providers;
 pub mod rag;
 pub mod runtime;
 pub(crate) mod security;
 pub(crate) mod service;
+pub mod sessions;
 pub(crate) mod skills;
 pub mod tools;
 pub(crate) mod tunnel;
@@ -155,6 +157,26 @@ pub enum SkillCommands {
     },
 }
 
+/// WASM plugin management subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PluginCommands {
+    /// List installed WASM tool plugins
+    List,
+    /// Install a plugin from a directory containing plugin.toml, or a manifest path
+    Install {
+        /// Plugin directory or manifest path
+        source: String,
+        /// Grant the requested capabilities without prompting
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Remove an installed plugin
+    Remove {
+        /// Plugin name to remove
+        name: String,
+    },
+}
+
 /// Migration subcommands
 #[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum MigrateCommands {
@@ -185,7 +207,10 @@ timezone name to override.
 
 Examples:
   zeroclaw cron add '0 9 * * 1-5' 'Good morning' --tz America/New_York
-  zeroclaw cron add '*/30 * * * *' 'Check system health'")]
+  zeroclaw cron add '*/30 * * * *' 'Check system health'
+  zeroclaw cron add '0 2 * * *' './backup.sh' --max-attempts 3 --timeout-secs 600 \
+    --on-failure-channel telegram --on-failure-to 123456789
+  zeroclaw cron add '30 2 * * *' './verify-backup.sh' --depends-on <backup-task-id>")]
     Add {
         /// Cron expression
         expression: String,
@@ -194,6 +219,21 @@ Examples:
         tz: Option<String>,
         /// Command to run
         command: String,
+        /// Run only after this job succeeds (repeatable)
+        #[arg(long = "depends-on")]
+        depends_on: Vec<String>,
+        /// Total attempts before the run counts as failed
+        #[arg(long)]
+        max_attempts: Option<u32>,
+        /// Per-attempt timeout in seconds
+        #[arg(long)]
+        timeout_secs: Option<u64>,
+        /// Channel alerted when the job exhausts its retries (telegram, discord, slack, mattermost)
+        #[arg(long, requires = "on_failure_to")]
+        on_failure_channel: Option<String>,
+        /// Recipient for failure alerts on --on-failure-channel
+        #[arg(long, requires = "on_failure_channel")]
+        on_failure_to: Option<String>,
     },
     /// Add a one-shot scheduled task at an RFC3339 timestamp
     #[command(long_about = "\
@@ -272,6 +312,29 @@ Examples:
         /// New job name
         #[arg(long)]
         name: Option<String>,
+        /// Replace the jobs this task waits on (repeatable)
+        #[arg(long = "depends-on")]
+        depends_on: Option<Vec<String>>,
+        /// Total attempts before the run counts as failed
+        #[arg(long)]
+        max_attempts: Option<u32>,
+        /// Per-attempt timeout in seconds
+        #[arg(long)]
+        timeout_secs: Option<u64>,
+        /// Channel alerted when the job exhausts its retries
+        #[arg(long, requires = "on_failure_to")]
+        on_failure_channel: Option<String>,
+        /// Recipient for failure alerts on --on-failure-channel
+        #[arg(long, requires = "on_failure_channel")]
+        on_failure_to: Option<String>,
+    },
+    /// Show recent runs of a scheduled task, one row per attempt
+    Runs {
+        /// Task ID
+        id: String,
+        /// Maximum number of runs to display
+        #[arg(long, default_value = "20")]
+        limit: usize,
     },
     /// Pause a scheduled task
     Pause {
@@ -285,6 +348,119 @@ Examples:
     },
 }
 
+/// Cost reporting subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CostCommands {
+    /// Break spend down by channel, user, model, job or agent
+    #[command(long_about = "\
+Break recorded spend down by one dimension.
+
+Dimensions: channel, user (channel:sender), model, job (cron job id) and \
+agent (delegate agent name). Periods: session, day, month.
+
+Examples:
+  zeroclaw cost report
+  zeroclaw cost report --by channel
+  zeroclaw cost report --by job --period day")]
+    Report {
+        /// Dimension to group by: channel, user, model, job or agent
+        #[arg(long, default_value = "model")]
+        by: String,
+        /// Period to report on: session, day or month
+        #[arg(long, default_value = "month")]
+        period: String,
+    },
+}
+
+/// Audit log subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AuditCommands {
+    /// Verify the signed audit log's hash chain and signatures
+    #[command(long_about = "\
+Verify the signed audit log.
+
+Checks every record in the live log and its rotated files, oldest first: \
+the record hash, the HMAC signature and the link to the previous record. \
+Reports the first modified, missing or unsigned record, or records cut from \
+the start of the chain, and exits non-zero. \
+Requires sign_events = true under [security.audit].
+
+Examples:
+  zeroclaw audit verify
+  zeroclaw audit verify --path /backup/audit.log")]
+    Verify {
+        /// Audit log to check (defaults to the configured log path)
+        #[arg(long)]
+        path: Option<std::path::PathBuf>,
+    },
+}
+
+/// Session management subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SessionCommands {
+    /// List stored sessions, most recently active first
+    List {
+        /// Maximum number of sessions to display
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
+    /// Show a session's transcript
+    Show {
+        /// Session ID
+        id: String,
+        /// Only show the last N messages
+        #[arg(long)]
+        last: Option<usize>,
+    },
+    /// Continue a session in interactive agent mode
+    #[command(long_about = "\
+Continue a stored session in interactive agent mode.
+
+The saved transcript (including tool calls and tool results) is loaded \
+as conversation history, and new turns are appended to the same session.
+
+Examples:
+  zeroclaw sessions resume <session-id>
+  zeroclaw sessions resume <session-id> -p anthropic --model claude-sonnet-4-20250514")]
+    Resume {
+        /// Session ID
+        id: String,
+        /// Provider to use
+        #[arg(short, long)]
+        provider: Option<String>,
+        /// Model to use
+        #[arg(long)]
+        model: Option<String>,
+    },
+    /// Export a session transcript as JSON or Markdown
+    #[command(long_about = "\
+Export a session transcript.
+
+Writes to stdout unless --output is given.
+
+Examples:
+  zeroclaw sessions export <session-id> > session.json
+  zeroclaw sessions export <session-id> --format markdown --output session.md")]
+    Export {
+        /// Session ID
+        id: String,
+        /// Output format: json or markdown
+        #[arg(long, default_value = "json")]
+        format: String,
+        /// Write to this file instead of stdout
+        #[arg(long)]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Delete a session and its transcript
+    Delete {
+        /// Session ID
+        id: String,
+        /// Skip confirmation prompt
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
 /// Memory management subcommands
 #[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum MemoryCommands {
@@ -322,6 +498,8 @@ pub enum MemoryCommands {
         #[arg(long)]
         yes: bool,
     },
+    /// Rebuild search indexes and embed memories stored without an embedding
+    Reindex,
 }
 
 /// Integration subcommands
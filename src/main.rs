@@ -73,10 +73,12 @@ mod multimodal;
 mod observability;
 mod onboard;
 mod peripherals;
+mod plugins;
 mod providers;
 mod runtime;
 mod security;
 mod service;
+mod sessions;
 mod skillforge;
 mod skills;
 mod tools;
@@ -87,8 +89,9 @@ use config::Config;
 
 // Re-export so binary modules can use crate::<CommandEnum> while keeping a single source of truth.
 pub use zeroclaw::{
-    ChannelCommands, CronCommands, HardwareCommands, IntegrationCommands, MigrateCommands,
-    PeripheralCommands, ServiceCommands, SkillCommands,
+    AuditCommands, ChannelCommands, CostCommands, CronCommands, HardwareCommands,
+    IntegrationCommands, MigrateCommands, PeripheralCommands, PluginCommands, ServiceCommands,
+    SessionCommands, SkillCommands,
 };
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
@@ -161,7 +164,8 @@ Examples:
   zeroclaw agent                              # interactive session
   zeroclaw agent -m \"Summarize today's logs\"  # single message
   zeroclaw agent -p anthropic --model claude-sonnet-4-20250514
-  zeroclaw agent --peripheral nucleo-f401re:/dev/ttyACM0")]
+  zeroclaw agent --peripheral nucleo-f401re:/dev/ttyACM0
+  zeroclaw agent --session work              # persist/resume session \"work\"")]
     Agent {
         /// Single message mode (don't enter interactive mode)
         #[arg(short, long)]
@@ -182,6 +186,10 @@ Examples:
         /// Attach a peripheral (board:path, e.g. nucleo-f401re:/dev/ttyACM0)
         #[arg(long)]
         peripheral: Vec<String>,
+
+        /// Persist the conversation under this session ID, resuming it if it exists
+        #[arg(long)]
+        session: Option<String>,
     },
 
     /// Start the gateway server (webhooks, websockets)
@@ -317,6 +325,25 @@ Examples:
         skill_command: SkillCommands,
     },
 
+    /// Manage WASM tool plugins
+    #[command(long_about = "\
+Manage sandboxed WASM tool plugins.
+
+A plugin is a WASM module plus a plugin.toml manifest declaring its \
+tool name, description, parameter schema and requested capabilities. \
+Installing shows the requested capabilities and asks for confirmation. \
+Running plugins requires a build with `--features runtime-wasm`.
+
+Examples:
+  zeroclaw plugins list
+  zeroclaw plugins install ./word-count
+  zeroclaw plugins install ./word-count/plugin.toml --yes
+  zeroclaw plugins remove word_count")]
+    Plugins {
+        #[command(subcommand)]
+        plugin_command: PluginCommands,
+    },
+
     /// Migrate data from other agent runtimes
     Migrate {
         #[command(subcommand)]
@@ -384,6 +411,55 @@ Examples:
         memory_command: MemoryCommands,
     },
 
+    /// Manage persistent agent sessions
+    #[command(long_about = "\
+Manage persistent agent sessions.
+
+Sessions store full conversation transcripts (including tool calls \
+and results) in the workspace so they survive restarts. Use \
+`zeroclaw agent --session <id>` to start or continue one.
+
+Examples:
+  zeroclaw sessions list
+  zeroclaw sessions show <session-id> --last 10
+  zeroclaw sessions resume <session-id>
+  zeroclaw sessions export <session-id> --format markdown
+  zeroclaw sessions delete <session-id> --yes")]
+    Sessions {
+        #[command(subcommand)]
+        session_command: SessionCommands,
+    },
+
+    /// Report API spend
+    #[command(long_about = "\
+Report API spend recorded by the cost tracker.
+
+Spend is attributed to the channel and sender, cron job and delegate \
+agent that caused it. Budgets for each are configured under \
+[cost.budgets] in config.toml.
+
+Examples:
+  zeroclaw cost report --by channel
+  zeroclaw cost report --by user --period day")]
+    Cost {
+        #[command(subcommand)]
+        cost_command: CostCommands,
+    },
+
+    /// Inspect the security audit log
+    #[command(long_about = "\
+Inspect the security audit log.
+
+With sign_events = true under [security.audit], records are hash-chained \
+and HMAC-signed, and `verify` proves the log was not edited.
+
+Examples:
+  zeroclaw audit verify")]
+    Audit {
+        #[command(subcommand)]
+        audit_command: AuditCommands,
+    },
+
     /// Manage configuration
     #[command(long_about = "\
 Manage ZeroClaw configuration.
@@ -577,6 +653,8 @@ enum MemoryCommands {
         #[arg(long)]
         yes: bool,
     },
+    /// Rebuild search indexes and embed memories stored without an embedding
+    Reindex,
 }
 
 #[tokio::main]
@@ -685,6 +763,7 @@ async fn main() -> Result<()> {
             model,
             temperature,
             peripheral,
+            session,
         } => agent::run(
             config,
             message,
@@ -693,6 +772,8 @@ async fn main() -> Result<()> {
             temperature,
             peripheral,
             true,
+            session,
+            None,
         )
         .await
         .map(|_| ()),
@@ -892,6 +973,8 @@ async fn main() -> Result<()> {
 
         Commands::Skills { skill_command } => skills::handle_command(skill_command, &config),
 
+        Commands::Plugins { plugin_command } => plugins::handle_command(plugin_command, &config),
+
         Commands::Migrate { migrate_command } => {
             migration::handle_command(migrate_command, &config).await
         }
@@ -900,6 +983,16 @@ async fn main() -> Result<()> {
             memory::cli::handle_command(memory_command, &config).await
         }
 
+        Commands::Sessions { session_command } => {
+            Box::pin(sessions::handle_command(session_command, config)).await
+        }
+
+        Commands::Cost { cost_command } => cost::handle_command(cost_command, &config),
+
+        Commands::Audit { audit_command } => {
+            security::audit::handle_command(audit_command, &config)
+        }
+
         Commands::Auth { auth_command } => handle_auth_command(auth_command, &config).await,
 
         Commands::Hardware { hardware_command } => {
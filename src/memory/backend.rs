@@ -4,6 +4,7 @@ pub enum MemoryBackendKind {
     Lucid,
     Postgres,
     Markdown,
+    Obsidian,
     None,
     Unknown,
 }
@@ -55,6 +56,15 @@ const POSTGRES_PROFILE: MemoryBackendProfile = MemoryBackendProfile {
     optional_dependency: true,
 };
 
+const OBSIDIAN_PROFILE: MemoryBackendProfile = MemoryBackendProfile {
+    key: "obsidian",
+    label: "Obsidian vault — index an existing vault with tags, links and daily notes",
+    auto_save_default: false,
+    uses_sqlite_hygiene: false,
+    sqlite_based: false,
+    optional_dependency: false,
+};
+
 const NONE_PROFILE: MemoryBackendProfile = MemoryBackendProfile {
     key: "none",
     label: "None — disable persistent memory",
@@ -94,6 +104,7 @@ pub fn classify_memory_backend(backend: &str) -> MemoryBackendKind {
         "lucid" => MemoryBackendKind::Lucid,
         "postgres" => MemoryBackendKind::Postgres,
         "markdown" => MemoryBackendKind::Markdown,
+        "obsidian" => MemoryBackendKind::Obsidian,
         "none" => MemoryBackendKind::None,
         _ => MemoryBackendKind::Unknown,
     }
@@ -105,6 +116,7 @@ pub fn memory_backend_profile(backend: &str) -> MemoryBackendProfile {
         MemoryBackendKind::Lucid => LUCID_PROFILE,
         MemoryBackendKind::Postgres => POSTGRES_PROFILE,
         MemoryBackendKind::Markdown => MARKDOWN_PROFILE,
+        MemoryBackendKind::Obsidian => OBSIDIAN_PROFILE,
         MemoryBackendKind::None => NONE_PROFILE,
         MemoryBackendKind::Unknown => CUSTOM_PROFILE,
     }
@@ -126,6 +138,10 @@ mod tests {
             classify_memory_backend("markdown"),
             MemoryBackendKind::Markdown
         );
+        assert_eq!(
+            classify_memory_backend("obsidian"),
+            MemoryBackendKind::Obsidian
+        );
         assert_eq!(classify_memory_backend("none"), MemoryBackendKind::None);
     }
 
@@ -23,6 +23,7 @@ pub async fn handle_command(command: crate::MemoryCommands, config: &Config) ->
         crate::MemoryCommands::Clear { key, category, yes } => {
             handle_clear(config, key, category, yes).await
         }
+        crate::MemoryCommands::Reindex => handle_reindex(config).await,
     }
 }
 
@@ -198,6 +199,24 @@ async fn handle_stats(config: &Config) -> Result<()> {
     Ok(())
 }
 
+/// Reindexing embeds memories, so it builds the backend with the configured
+/// embedding provider rather than the lightweight CLI one.
+async fn handle_reindex(config: &Config) -> Result<()> {
+    let mem = super::create_memory_with_storage_and_routes(
+        &config.memory,
+        &config.embedding_routes,
+        Some(&config.storage.provider.config),
+        &config.workspace_dir,
+        config.api_key.as_deref(),
+    )?;
+    let embedded = mem.reindex().await?;
+    println!(
+        "Reindexed {} memory ({embedded} entries embedded).",
+        style(mem.name()).white().bold()
+    );
+    Ok(())
+}
+
 async fn handle_clear(
     config: &Config,
     key: Option<String>,
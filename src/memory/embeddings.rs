@@ -186,10 +186,33 @@ pub fn create_embedding_provider(
             let key = api_key.unwrap_or("");
             Box::new(OpenAiEmbedding::new(base_url, key, model, dims))
         }
+        "local" => create_local_embedding(model, dims),
         _ => Box::new(NoopEmbedding),
     }
 }
 
+/// Local model from the directory `model`; falls back to keyword-only search
+/// when it cannot be loaded.
+#[cfg(feature = "embeddings-local")]
+fn create_local_embedding(model: &str, dims: usize) -> Box<dyn EmbeddingProvider> {
+    let model_dir = shellexpand::tilde(model).into_owned();
+    match super::local_embedding::LocalEmbedding::load(std::path::Path::new(&model_dir), dims) {
+        Ok(provider) => Box::new(provider),
+        Err(e) => {
+            tracing::warn!("Local embeddings unavailable; using keyword-only search: {e:#}");
+            Box::new(NoopEmbedding)
+        }
+    }
+}
+
+#[cfg(not(feature = "embeddings-local"))]
+fn create_local_embedding(_model: &str, _dims: usize) -> Box<dyn EmbeddingProvider> {
+    tracing::warn!(
+        "embedding provider 'local' requested but this build was compiled without `embeddings-local`; rebuild with `--features embeddings-local`. Using keyword-only search."
+    );
+    Box::new(NoopEmbedding)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -233,6 +256,12 @@ mod tests {
         assert_eq!(p.dimensions(), 1536);
     }
 
+    #[test]
+    fn factory_local_without_model_falls_back_to_noop() {
+        let p = create_embedding_provider("local", None, "/nonexistent/zeroclaw-model", 384);
+        assert_eq!(p.name(), "none");
+    }
+
     #[test]
     fn factory_custom_url() {
         let p = create_embedding_provider("custom:http://localhost:1234", None, "model", 768);
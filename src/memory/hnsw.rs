@@ -0,0 +1,788 @@
+//! Approximate nearest-neighbour index (HNSW) over memory embeddings.
+//!
+//! [`SqliteMemory`](super::SqliteMemory) keeps the graph in memory and
+//! persists it node by node in the `vector_index` table of `brain.db`, in the
+//! same transaction as the memory write that changed it. Opening the index
+//! drops nodes whose memory row is gone and adds embedded rows it has not
+//! seen, so rows written behind its back (hygiene, snapshot hydration) are
+//! reconciled on the next open.
+//!
+//! Triggers bump `memory_generation` whenever a memory is added, removed or
+//! re-embedded. The index records the generation it reflects, so a writer
+//! that bypassed it (another process, hygiene) shows up as a mismatch and
+//! the index is reopened before it is used again.
+
+use super::vector;
+use anyhow::Context;
+use rusqlite::{params, Connection};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+/// Links kept per node on upper layers; layer 0 keeps twice as many.
+const M: usize = 16;
+
+/// Candidate list size while inserting.
+const EF_CONSTRUCTION: usize = 100;
+
+/// Minimum candidate list size while searching.
+const EF_SEARCH: usize = 64;
+
+/// Highest layer a node can be assigned to.
+const MAX_LEVEL: usize = 16;
+
+struct Node {
+    memory_id: String,
+    /// Unit-length embedding, so dot product is cosine similarity.
+    vector: Vec<f32>,
+    /// Neighbour node numbers, one list per layer from 0 to the node's level.
+    links: Vec<Vec<u32>>,
+}
+
+/// A node and its similarity to the current query, ordered by similarity.
+#[derive(Clone, Copy, PartialEq)]
+struct Scored {
+    sim: f32,
+    node: u32,
+}
+
+impl Eq for Scored {}
+
+impl Ord for Scored {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sim
+            .total_cmp(&other.sim)
+            .then_with(|| other.node.cmp(&self.node))
+    }
+}
+
+impl PartialOrd for Scored {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Hierarchical navigable small-world graph keyed by memory id.
+pub struct HnswIndex {
+    /// Slot = node number (`vector_index.node`); `None` once removed.
+    nodes: Vec<Option<Node>>,
+    by_memory: HashMap<String, u32>,
+    entry: Option<u32>,
+    dims: usize,
+    rng: u64,
+    /// `memory_generation` the graph reflects.
+    generation: i64,
+}
+
+impl HnswIndex {
+    fn empty() -> Self {
+        Self {
+            nodes: Vec::new(),
+            by_memory: HashMap::new(),
+            entry: None,
+            dims: 0,
+            rng: 0,
+            generation: 0,
+        }
+    }
+
+    /// Number of indexed memories.
+    pub fn len(&self) -> usize {
+        self.by_memory.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_memory.is_empty()
+    }
+
+    /// Whether [`Self::search`] can answer `query`. Queries from a
+    /// different embedding model (other dimensions) need the exact scan.
+    pub fn accepts(&self, query: &[f32]) -> bool {
+        !self.is_empty() && query.len() == self.dims
+    }
+
+    /// Whether no memory was added, removed or re-embedded since the index
+    /// was opened or last marked current.
+    pub fn is_current(&self, conn: &Connection) -> anyhow::Result<bool> {
+        Ok(memory_generation(conn)? == self.generation)
+    }
+
+    /// Record that the index reflects every memory write so far; called in
+    /// the transaction of a write that went through the index.
+    pub fn mark_current(&mut self, conn: &Connection) -> anyhow::Result<()> {
+        self.generation = memory_generation(conn)?;
+        Ok(())
+    }
+
+    /// Load the index from `vector_index` and reconcile it with the
+    /// `memories` table. A graph that cannot be decoded is rebuilt.
+    pub fn open(conn: &mut Connection) -> anyhow::Result<Self> {
+        match Self::load(conn) {
+            Ok(index) => Ok(index),
+            Err(e) => {
+                tracing::warn!("Rebuilding memory vector index: {e:#}");
+                Self::rebuild(conn)
+            }
+        }
+    }
+
+    /// Build the index from scratch over every embedded memory.
+    pub fn rebuild(conn: &mut Connection) -> anyhow::Result<Self> {
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM vector_index", [])?;
+        let rows = embedded_memories(&tx, false)?;
+        let mut index = Self::empty();
+        let mut changed = Vec::new();
+        for (id, blob) in rows {
+            changed.extend(index.insert(&id, &vector::bytes_to_vec(&blob)));
+        }
+        index.persist(&tx, &changed)?;
+        index.mark_current(&tx)?;
+        tx.commit()?;
+        Ok(index)
+    }
+
+    fn load(conn: &mut Connection) -> anyhow::Result<Self> {
+        let tx = conn.transaction()?;
+        let mut index = Self::empty();
+        let mut stale = Vec::new();
+        {
+            let mut stmt = tx.prepare(
+                "SELECT v.node, v.memory_id, v.links, m.embedding
+                 FROM vector_index v LEFT JOIN memories m ON m.id = v.memory_id
+                 ORDER BY v.node",
+            )?;
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                let node: u32 = row.get(0)?;
+                let memory_id: String = row.get(1)?;
+                let links = decode_links(&row.get::<_, Vec<u8>>(2)?)
+                    .with_context(|| format!("vector index node {node}"))?;
+                let vector = row
+                    .get::<_, Option<Vec<u8>>>(3)?
+                    .and_then(|blob| normalized(&vector::bytes_to_vec(&blob)))
+                    .filter(|v| index.dims == 0 || v.len() == index.dims);
+
+                let slot = node as usize;
+                if index.nodes.len() <= slot {
+                    index.nodes.resize_with(slot + 1, || None);
+                }
+                match vector {
+                    Some(vector) => {
+                        index.dims = vector.len();
+                        index.by_memory.insert(memory_id.clone(), node);
+                        index.nodes[slot] = Some(Node {
+                            memory_id,
+                            vector,
+                            links,
+                        });
+                    }
+                    None => {
+                        // Memory deleted or re-embedded elsewhere: keep the
+                        // links long enough to repair the graph around it.
+                        stale.push(node);
+                        index.nodes[slot] = Some(Node {
+                            memory_id,
+                            vector: Vec::new(),
+                            links,
+                        });
+                    }
+                }
+            }
+        }
+
+        for node in index.nodes.iter().flatten() {
+            let dangling = node
+                .links
+                .iter()
+                .flatten()
+                .any(|&l| index.nodes.get(l as usize).is_none_or(Option::is_none));
+            anyhow::ensure!(!dangling, "vector index links to a missing node");
+        }
+
+        let mut changed = index.unlink(&stale);
+        index.entry = index.highest_node();
+        index.rng = index.nodes.len() as u64;
+        for (id, blob) in embedded_memories(&tx, true)? {
+            changed.extend(index.insert(&id, &vector::bytes_to_vec(&blob)));
+        }
+        index.persist(&tx, &changed)?;
+        index.mark_current(&tx)?;
+        tx.commit()?;
+        Ok(index)
+    }
+
+    /// Write the given nodes back to `vector_index`, deleting removed ones.
+    pub fn persist(&self, conn: &Connection, changed: &[u32]) -> anyhow::Result<()> {
+        let mut changed = changed.to_vec();
+        changed.sort_unstable();
+        changed.dedup();
+        let mut upsert = conn.prepare_cached(
+            "INSERT OR REPLACE INTO vector_index (node, memory_id, links) VALUES (?1, ?2, ?3)",
+        )?;
+        let mut delete = conn.prepare_cached("DELETE FROM vector_index WHERE node = ?1")?;
+        for n in changed {
+            match self.nodes.get(n as usize).and_then(Option::as_ref) {
+                Some(node) => {
+                    upsert.execute(params![n, node.memory_id, encode_links(&node.links)])?;
+                }
+                None => {
+                    delete.execute(params![n])?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Index `vector` under `memory_id`, replacing any earlier vector.
+    /// Returns the nodes whose persisted state changed.
+    pub fn insert(&mut self, memory_id: &str, vector: &[f32]) -> Vec<u32> {
+        let unit = normalized(vector);
+        if let (Some(unit), Some(&n)) = (&unit, self.by_memory.get(memory_id)) {
+            if *unit == self.node(n).vector {
+                return Vec::new();
+            }
+        }
+        let mut changed = self.remove(memory_id);
+        let Some(unit) = unit else {
+            return changed;
+        };
+        if self.is_empty() {
+            self.dims = unit.len();
+        } else if unit.len() != self.dims {
+            tracing::debug!(
+                memory_id,
+                "Not indexing {}-dimensional embedding in a {}-dimensional index",
+                unit.len(),
+                self.dims
+            );
+            return changed;
+        }
+
+        let level = self.random_level();
+        #[allow(clippy::cast_possible_truncation)]
+        let n = self.nodes.len() as u32;
+        self.nodes.push(Some(Node {
+            memory_id: memory_id.to_string(),
+            vector: unit.clone(),
+            links: vec![Vec::new(); level + 1],
+        }));
+        self.by_memory.insert(memory_id.to_string(), n);
+        changed.push(n);
+
+        let Some(entry) = self.entry else {
+            self.entry = Some(n);
+            return changed;
+        };
+        let top = self.level_of(entry);
+        let mut nearest = vec![self.score(&unit, entry)];
+        for layer in (level + 1..=top).rev() {
+            nearest = self.search_layer(&unit, &nearest, 1, layer);
+        }
+        for layer in (0..=level.min(top)).rev() {
+            nearest = self.search_layer(&unit, &nearest, EF_CONSTRUCTION, layer);
+            let neighbours = self.select_neighbours(&nearest, M);
+            for &neighbour in &neighbours {
+                self.link(neighbour, n, layer);
+                changed.push(neighbour);
+            }
+            self.node_mut(n).links[layer] = neighbours;
+        }
+        if level > top {
+            self.entry = Some(n);
+        }
+        changed
+    }
+
+    /// Drop `memory_id` from the index. Returns the nodes whose persisted
+    /// state changed.
+    pub fn remove(&mut self, memory_id: &str) -> Vec<u32> {
+        match self.by_memory.remove(memory_id) {
+            Some(n) => self.unlink(&[n]),
+            None => Vec::new(),
+        }
+    }
+
+    /// Up to `limit` memories most similar to `query`, best first, scored
+    /// with [`vector::cosine_similarity`] like the exact scan. Non-positive
+    /// scores are dropped.
+    pub fn search(&self, query: &[f32], limit: usize) -> Vec<(String, f32)> {
+        let (Some(entry), Some(unit)) = (self.entry, normalized(query)) else {
+            return Vec::new();
+        };
+        if limit == 0 || unit.len() != self.dims {
+            return Vec::new();
+        }
+
+        let mut nearest = vec![self.score(&unit, entry)];
+        for layer in (1..=self.level_of(entry)).rev() {
+            nearest = self.search_layer(&unit, &nearest, 1, layer);
+        }
+        self.search_layer(&unit, &nearest, EF_SEARCH.max(limit), 0)
+            .into_iter()
+            .map(|scored| {
+                let node = self.node(scored.node);
+                (
+                    node.memory_id.clone(),
+                    vector::cosine_similarity(query, &node.vector),
+                )
+            })
+            .filter(|(_, sim)| *sim > 0.0)
+            .take(limit)
+            .collect()
+    }
+
+    fn node(&self, n: u32) -> &Node {
+        self.nodes[n as usize]
+            .as_ref()
+            .expect("vector index links only to live nodes")
+    }
+
+    fn node_mut(&mut self, n: u32) -> &mut Node {
+        self.nodes[n as usize]
+            .as_mut()
+            .expect("vector index links only to live nodes")
+    }
+
+    fn level_of(&self, n: u32) -> usize {
+        self.node(n).links.len() - 1
+    }
+
+    fn score(&self, unit: &[f32], n: u32) -> Scored {
+        Scored {
+            sim: dot(unit, &self.node(n).vector),
+            node: n,
+        }
+    }
+
+    fn max_links(layer: usize) -> usize {
+        if layer == 0 {
+            2 * M
+        } else {
+            M
+        }
+    }
+
+    /// Live node on the highest layer, the entry point for searches.
+    fn highest_node(&self) -> Option<u32> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter_map(|(n, node)| Some((n, node.as_ref()?.links.len())))
+            .max_by_key(|&(n, levels)| (levels, Reverse(n)))
+            .map(|(n, _)| u32::try_from(n).unwrap_or(u32::MAX))
+    }
+
+    /// Draw a layer from the usual exponentially decaying distribution,
+    /// using a deterministic splitmix64 sequence.
+    fn random_level(&mut self) -> usize {
+        self.rng = self.rng.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.rng;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        #[allow(clippy::cast_precision_loss)]
+        let uniform = ((z >> 11) as f64 + 1.0) / (1_u64 << 53) as f64;
+        #[allow(clippy::cast_precision_loss)]
+        let level = -uniform.ln() / (M as f64).ln();
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let level = level as usize;
+        level.min(MAX_LEVEL)
+    }
+
+    /// Beam search on one layer, returning up to `ef` nodes best first.
+    fn search_layer(
+        &self,
+        unit: &[f32],
+        entry_points: &[Scored],
+        ef: usize,
+        layer: usize,
+    ) -> Vec<Scored> {
+        let mut visited: HashSet<u32> = entry_points.iter().map(|s| s.node).collect();
+        let mut candidates: BinaryHeap<Scored> = entry_points.iter().copied().collect();
+        let mut found: BinaryHeap<Reverse<Scored>> =
+            entry_points.iter().copied().map(Reverse).collect();
+        while found.len() > ef {
+            found.pop();
+        }
+
+        while let Some(current) = candidates.pop() {
+            if found.peek().is_some_and(|worst| current.sim < worst.0.sim) {
+                break;
+            }
+            let Some(links) = self.node(current.node).links.get(layer) else {
+                continue;
+            };
+            for &next in links {
+                if !visited.insert(next) {
+                    continue;
+                }
+                let scored = self.score(unit, next);
+                if found.len() < ef || found.peek().is_some_and(|worst| scored > worst.0) {
+                    candidates.push(scored);
+                    found.push(Reverse(scored));
+                    if found.len() > ef {
+                        found.pop();
+                    }
+                }
+            }
+        }
+
+        let mut found: Vec<Scored> = found.into_iter().map(|r| r.0).collect();
+        found.sort_unstable_by(|a, b| b.cmp(a));
+        found
+    }
+
+    /// Keep a candidate (sorted best first) only when it is closer to the
+    /// base node than to every neighbour kept so far, which preserves links
+    /// between clusters.
+    fn select_neighbours(&self, candidates: &[Scored], m: usize) -> Vec<u32> {
+        let mut kept: Vec<u32> = Vec::with_capacity(m);
+        for candidate in candidates {
+            if kept.len() >= m {
+                break;
+            }
+            let vector = &self.node(candidate.node).vector;
+            if kept
+                .iter()
+                .all(|&k| dot(vector, &self.node(k).vector) < candidate.sim)
+            {
+                kept.push(candidate.node);
+            }
+        }
+        kept
+    }
+
+    /// Link `from` to `to` on `layer`, pruning `from` back to capacity.
+    fn link(&mut self, from: u32, to: u32, layer: usize) {
+        let links = &mut self.node_mut(from).links[layer];
+        if links.contains(&to) {
+            return;
+        }
+        links.push(to);
+        if links.len() <= Self::max_links(layer) {
+            return;
+        }
+
+        let node = self.node(from);
+        let mut candidates: Vec<Scored> = node.links[layer]
+            .iter()
+            .map(|&c| self.score(&node.vector, c))
+            .collect();
+        candidates.sort_unstable_by(|a, b| b.cmp(a));
+        let kept = self.select_neighbours(&candidates, Self::max_links(layer));
+        self.node_mut(from).links[layer] = kept;
+    }
+
+    /// Take `dead` nodes out of the graph, reconnecting every node that
+    /// linked to one of them through the dead node's own neighbours.
+    fn unlink(&mut self, dead: &[u32]) -> Vec<u32> {
+        let removed: HashMap<u32, Node> = dead
+            .iter()
+            .filter_map(|&n| Some((n, self.nodes.get_mut(n as usize)?.take()?)))
+            .collect();
+        if removed.is_empty() {
+            return Vec::new();
+        }
+        let mut changed: Vec<u32> = removed.keys().copied().collect();
+
+        for n in 0..self.nodes.len() {
+            let Some(node) = self.nodes[n].as_ref() else {
+                continue;
+            };
+            #[allow(clippy::cast_possible_truncation)]
+            let n = n as u32;
+            let mut rewired = Vec::new();
+            for (layer, links) in node.links.iter().enumerate() {
+                if !links.iter().any(|l| removed.contains_key(l)) {
+                    continue;
+                }
+                let mut pool: Vec<u32> = links.clone();
+                for gone in links.iter().filter_map(|l| removed.get(l)) {
+                    pool.extend(gone.links.get(layer).into_iter().flatten());
+                }
+                pool.sort_unstable();
+                pool.dedup();
+                let mut candidates: Vec<Scored> = pool
+                    .into_iter()
+                    .filter(|c| *c != n && !removed.contains_key(c))
+                    .filter(|&c| self.nodes.get(c as usize).is_some_and(Option::is_some))
+                    .map(|c| self.score(&node.vector, c))
+                    .collect();
+                candidates.sort_unstable_by(|a, b| b.cmp(a));
+                rewired.push((
+                    layer,
+                    self.select_neighbours(&candidates, Self::max_links(layer)),
+                ));
+            }
+            if !rewired.is_empty() {
+                let node = self.node_mut(n);
+                for (layer, links) in rewired {
+                    node.links[layer] = links;
+                }
+                changed.push(n);
+            }
+        }
+
+        if self.entry.is_some_and(|e| removed.contains_key(&e)) {
+            self.entry = self.highest_node();
+        }
+        changed
+    }
+}
+
+/// `(id, embedding)` of embedded memories, optionally only those the
+/// index has no node for.
+fn embedded_memories(
+    conn: &Connection,
+    unindexed_only: bool,
+) -> anyhow::Result<Vec<(String, Vec<u8>)>> {
+    let sql = if unindexed_only {
+        "SELECT id, embedding FROM memories
+         WHERE embedding IS NOT NULL
+           AND id NOT IN (SELECT memory_id FROM vector_index)
+         ORDER BY rowid"
+    } else {
+        "SELECT id, embedding FROM memories WHERE embedding IS NOT NULL ORDER BY rowid"
+    };
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+fn memory_generation(conn: &Connection) -> anyhow::Result<i64> {
+    Ok(conn.query_row(
+        "SELECT value FROM memory_generation WHERE id = 0",
+        [],
+        |row| row.get(0),
+    )?)
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// `v` scaled to unit length, or `None` for zero or non-finite vectors.
+fn normalized(v: &[f32]) -> Option<Vec<f32>> {
+    let norm = v
+        .iter()
+        .map(|x| f64::from(*x) * f64::from(*x))
+        .sum::<f64>()
+        .sqrt();
+    if v.is_empty() || !norm.is_finite() || norm < f64::EPSILON {
+        return None;
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    let unit: Vec<f32> = v.iter().map(|x| (f64::from(*x) / norm) as f32).collect();
+    Some(unit)
+}
+
+/// Layer count, then per layer a link count and the linked node numbers,
+/// all little-endian `u32`.
+fn encode_links(links: &[Vec<u32>]) -> Vec<u8> {
+    let words = 1 + links.iter().map(|l| l.len() + 1).sum::<usize>();
+    let mut bytes = Vec::with_capacity(words * 4);
+    #[allow(clippy::cast_possible_truncation)]
+    bytes.extend_from_slice(&(links.len() as u32).to_le_bytes());
+    for layer in links {
+        #[allow(clippy::cast_possible_truncation)]
+        bytes.extend_from_slice(&(layer.len() as u32).to_le_bytes());
+        for n in layer {
+            bytes.extend_from_slice(&n.to_le_bytes());
+        }
+    }
+    bytes
+}
+
+fn decode_links(bytes: &[u8]) -> anyhow::Result<Vec<Vec<u32>>> {
+    anyhow::ensure!(
+        bytes.len().is_multiple_of(4),
+        "links blob is not u32-aligned"
+    );
+    let mut words = bytes
+        .chunks_exact(4)
+        .map(|w| u32::from_le_bytes([w[0], w[1], w[2], w[3]]));
+    let mut next = || words.next().context("links blob is truncated");
+    let layers = next()? as usize;
+    anyhow::ensure!(
+        (1..=MAX_LEVEL + 1).contains(&layers),
+        "links blob has {layers} layers"
+    );
+    let mut links = Vec::with_capacity(layers);
+    for _ in 0..layers {
+        let count = next()? as usize;
+        anyhow::ensure!(count <= 2 * M + 1, "links blob has {count} links");
+        links.push((0..count).map(|_| next()).collect::<anyhow::Result<_>>()?);
+    }
+    Ok(links)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random unit vectors, loosely clustered so the
+    /// graph has structure to exploit.
+    fn vectors(count: usize, dims: usize, seed: u64) -> Vec<Vec<f32>> {
+        let mut state = seed;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            #[allow(clippy::cast_precision_loss)]
+            let x = (state >> 40) as f32 / (1_u64 << 24) as f32;
+            x - 0.5
+        };
+        let centres: Vec<Vec<f32>> = (0..8)
+            .map(|_| (0..dims).map(|_| next()).collect())
+            .collect();
+        (0..count)
+            .map(|i| {
+                centres[i % centres.len()]
+                    .iter()
+                    .map(|c| c + next() * 0.6)
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn exact(data: &[Vec<f32>], query: &[f32], k: usize) -> Vec<String> {
+        let mut scored: Vec<(usize, f32)> = data
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (i, vector::cosine_similarity(query, v)))
+            .filter(|(_, sim)| *sim > 0.0)
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored
+            .into_iter()
+            .take(k)
+            .map(|(i, _)| format!("m{i}"))
+            .collect()
+    }
+
+    fn recall_at(index: &HnswIndex, data: &[Vec<f32>], queries: &[Vec<f32>], k: usize) -> f64 {
+        let mut hits = 0;
+        let mut total = 0;
+        for query in queries {
+            let expected = exact(data, query, k);
+            let found: HashSet<String> = index
+                .search(query, k)
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect();
+            hits += expected.iter().filter(|id| found.contains(*id)).count();
+            total += expected.len();
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let recall = hits as f64 / total as f64;
+        recall
+    }
+
+    fn build(data: &[Vec<f32>]) -> HnswIndex {
+        let mut index = HnswIndex::empty();
+        for (i, v) in data.iter().enumerate() {
+            index.insert(&format!("m{i}"), v);
+        }
+        index
+    }
+
+    #[test]
+    fn recall_matches_exact_scan() {
+        let mut data = vectors(1_050, 32, 7);
+        let queries = data.split_off(1_000);
+        let index = build(&data);
+
+        assert_eq!(index.len(), 1_000);
+        let recall = recall_at(&index, &data, &queries, 10);
+        assert!(recall >= 0.95, "recall@10 = {recall}");
+    }
+
+    #[test]
+    fn search_scores_equal_cosine_similarity() {
+        let data = vectors(200, 16, 3);
+        let index = build(&data);
+        let query = &data[17];
+
+        let results = index.search(query, 5);
+        assert_eq!(results[0].0, "m17");
+        for (id, sim) in &results {
+            let i: usize = id[1..].parse().unwrap();
+            let expected = vector::cosine_similarity(query, &data[i]);
+            assert!((sim - expected).abs() < 1e-5, "{id}: {sim} vs {expected}");
+        }
+    }
+
+    #[test]
+    fn recall_holds_after_removals_and_updates() {
+        let mut data = vectors(1_540, 24, 11);
+        let queries = data.split_off(1_500);
+        let mut index = build(&data);
+        for i in (0..data.len()).step_by(3) {
+            index.remove(&format!("m{i}"));
+        }
+        let replacements = vectors(100, 24, 12);
+        for (i, v) in replacements.iter().enumerate() {
+            let id = i * 3 + 1;
+            index.insert(&format!("m{id}"), v);
+            data[id].clone_from(v);
+        }
+
+        // Removed entries must never come back.
+        let live: Vec<Vec<f32>> = data
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                if i % 3 == 0 {
+                    vec![0.0; v.len()]
+                } else {
+                    v.clone()
+                }
+            })
+            .collect();
+        for query in &queries {
+            for (id, _) in index.search(query, 10) {
+                let i: usize = id[1..].parse().unwrap();
+                assert_ne!(i % 3, 0, "removed memory {id} returned");
+            }
+        }
+        let recall = recall_at(&index, &live, &queries, 10);
+        assert!(recall >= 0.9, "recall@10 after churn = {recall}");
+        assert_eq!(index.len(), 1_000);
+    }
+
+    #[test]
+    fn removing_everything_empties_the_index() {
+        let data = vectors(50, 8, 5);
+        let mut index = build(&data);
+        for i in 0..data.len() {
+            index.remove(&format!("m{i}"));
+        }
+        assert!(index.is_empty());
+        assert!(index.entry.is_none());
+        assert!(index.search(&data[0], 5).is_empty());
+
+        index.insert("again", &data[0]);
+        assert_eq!(index.search(&data[0], 5)[0].0, "again");
+    }
+
+    #[test]
+    fn zero_and_mismatched_vectors_are_not_indexed() {
+        let mut index = HnswIndex::empty();
+        index.insert("zero", &[0.0, 0.0, 0.0]);
+        assert!(index.is_empty());
+        index.insert("a", &[1.0, 0.0, 0.0]);
+        index.insert("b", &[1.0, 0.0]);
+        assert_eq!(index.len(), 1);
+        assert!(index.accepts(&[0.5, 0.5, 0.0]));
+        assert!(!index.accepts(&[0.5, 0.5]));
+    }
+
+    #[test]
+    fn links_roundtrip_and_reject_garbage() {
+        let links = vec![vec![1, 2, 3], vec![], vec![7]];
+        assert_eq!(decode_links(&encode_links(&links)).unwrap(), links);
+        assert!(decode_links(&[1, 2, 3]).is_err());
+        assert!(decode_links(&encode_links(&links)[..10]).is_err());
+        assert!(decode_links(&0_u32.to_le_bytes()).is_err());
+    }
+}
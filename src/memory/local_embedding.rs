@@ -0,0 +1,268 @@
+//! Local sentence-transformer embeddings on CPU (candle).
+//!
+//! Selected with `embedding_provider = "local"`; `embedding_model` is a
+//! directory holding `config.json`, `tokenizer.json` and `model.safetensors`
+//! for a BERT-family model (e.g. a download of
+//! `sentence-transformers/all-MiniLM-L6-v2`). Texts are embedded in batches
+//! with mean pooling and L2 normalization, and results are cached in-process
+//! by content hash so repeated texts skip inference.
+
+use super::embeddings::EmbeddingProvider;
+use anyhow::Context;
+use async_trait::async_trait;
+use candle_core::{DType, Device, Tensor};
+use candle_nn::VarBuilder;
+use candle_transformers::models::bert::{BertModel, Config as BertConfig, DTYPE};
+use parking_lot::Mutex;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::sync::Arc;
+use tokenizers::{PaddingParams, PaddingStrategy, Tokenizer, TruncationParams};
+
+/// Texts per forward pass.
+const BATCH_SIZE: usize = 32;
+/// Cached embeddings kept in memory.
+const CACHE_CAPACITY: usize = 4096;
+/// Longer inputs are truncated; sentence-transformers are trained on short passages.
+const MAX_SEQUENCE_TOKENS: usize = 256;
+
+struct LocalModel {
+    model: BertModel,
+    tokenizer: Tokenizer,
+    device: Device,
+}
+
+impl LocalModel {
+    fn load(dir: &Path) -> anyhow::Result<Self> {
+        let config_path = dir.join("config.json");
+        let config: BertConfig = serde_json::from_str(
+            &std::fs::read_to_string(&config_path)
+                .with_context(|| format!("failed to read {}", config_path.display()))?,
+        )
+        .with_context(|| format!("invalid model config {}", config_path.display()))?;
+
+        let mut tokenizer = Tokenizer::from_file(dir.join("tokenizer.json"))
+            .map_err(|e| anyhow::anyhow!("failed to load tokenizer.json: {e}"))?;
+        tokenizer.with_padding(Some(PaddingParams {
+            strategy: PaddingStrategy::BatchLongest,
+            ..PaddingParams::default()
+        }));
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: MAX_SEQUENCE_TOKENS,
+                ..TruncationParams::default()
+            }))
+            .map_err(|e| anyhow::anyhow!("failed to configure truncation: {e}"))?;
+
+        let device = Device::Cpu;
+        let weights_path = dir.join("model.safetensors");
+        let weights = std::fs::read(&weights_path)
+            .with_context(|| format!("failed to read {}", weights_path.display()))?;
+        let vb = VarBuilder::from_buffered_safetensors(weights, DTYPE, &device)?;
+        let model = BertModel::load(vb, &config)?;
+
+        Ok(Self {
+            model,
+            tokenizer,
+            device,
+        })
+    }
+
+    fn hidden_size(&self) -> anyhow::Result<usize> {
+        Ok(self.embed_batch(&["dimension probe"])?[0].len())
+    }
+
+    /// One forward pass: mean-pool token states over the attention mask,
+    /// then L2-normalize.
+    fn embed_batch(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        let encodings = self
+            .tokenizer
+            .encode_batch(texts.to_vec(), true)
+            .map_err(|e| anyhow::anyhow!("tokenization failed: {e}"))?;
+
+        let ids = encodings
+            .iter()
+            .map(|encoding| Tensor::new(encoding.get_ids(), &self.device))
+            .collect::<candle_core::Result<Vec<_>>>()?;
+        let masks = encodings
+            .iter()
+            .map(|encoding| Tensor::new(encoding.get_attention_mask(), &self.device))
+            .collect::<candle_core::Result<Vec<_>>>()?;
+        let input_ids = Tensor::stack(&ids, 0)?;
+        let attention_mask = Tensor::stack(&masks, 0)?;
+        let token_type_ids = input_ids.zeros_like()?;
+
+        let hidden = self
+            .model
+            .forward(&input_ids, &token_type_ids, Some(&attention_mask))?;
+        let mask = attention_mask.to_dtype(DType::F32)?.unsqueeze(2)?;
+        let summed = hidden.broadcast_mul(&mask)?.sum(1)?;
+        let counts = mask.sum(1)?.clamp(1e-9, f64::MAX)?;
+        let pooled = summed.broadcast_div(&counts)?;
+        let norms = pooled
+            .sqr()?
+            .sum_keepdim(1)?
+            .sqrt()?
+            .clamp(1e-12, f64::MAX)?;
+        Ok(pooled.broadcast_div(&norms)?.to_vec2::<f32>()?)
+    }
+}
+
+/// Bounded content-hash → embedding cache; evicts the oldest entries first.
+struct EmbeddingCache {
+    capacity: usize,
+    entries: HashMap<[u8; 32], Vec<f32>>,
+    order: VecDeque<[u8; 32]>,
+}
+
+impl EmbeddingCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    fn key(text: &str) -> [u8; 32] {
+        Sha256::digest(text.as_bytes()).into()
+    }
+
+    fn get(&self, key: &[u8; 32]) -> Option<Vec<f32>> {
+        self.entries.get(key).cloned()
+    }
+
+    fn insert(&mut self, key: [u8; 32], embedding: Vec<f32>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.insert(key, embedding).is_none() {
+            self.order.push_back(key);
+        }
+        while self.entries.len() > self.capacity {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+}
+
+pub struct LocalEmbedding {
+    model: Arc<LocalModel>,
+    dims: usize,
+    cache: Mutex<EmbeddingCache>,
+}
+
+impl LocalEmbedding {
+    /// Load the model in `model_dir`. The embedding width comes from the
+    /// model; a mismatching `configured_dims` is reported and ignored.
+    pub fn load(model_dir: &Path, configured_dims: usize) -> anyhow::Result<Self> {
+        let model = LocalModel::load(model_dir).with_context(|| {
+            format!(
+                "failed to load local embedding model {}",
+                model_dir.display()
+            )
+        })?;
+        let dims = model.hidden_size()?;
+        if configured_dims != 0 && configured_dims != dims {
+            tracing::warn!(
+                configured_dims,
+                model_dims = dims,
+                "embedding_dimensions does not match the local model; using the model's width"
+            );
+        }
+        Ok(Self {
+            model: Arc::new(model),
+            dims,
+            cache: Mutex::new(EmbeddingCache::new(CACHE_CAPACITY)),
+        })
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for LocalEmbedding {
+    fn name(&self) -> &str {
+        "local"
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dims
+    }
+
+    async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        let keys: Vec<[u8; 32]> = texts.iter().map(|text| EmbeddingCache::key(text)).collect();
+        let mut results: Vec<Option<Vec<f32>>> = {
+            let cache = self.cache.lock();
+            keys.iter().map(|key| cache.get(key)).collect()
+        };
+
+        let missing: Vec<usize> = (0..texts.len()).filter(|&i| results[i].is_none()).collect();
+        if !missing.is_empty() {
+            let model = Arc::clone(&self.model);
+            let pending: Vec<String> = missing.iter().map(|&i| texts[i].to_string()).collect();
+            let computed = tokio::task::spawn_blocking(move || -> anyhow::Result<Vec<Vec<f32>>> {
+                let mut out = Vec::with_capacity(pending.len());
+                for batch in pending.chunks(BATCH_SIZE) {
+                    let batch: Vec<&str> = batch.iter().map(String::as_str).collect();
+                    out.extend(model.embed_batch(&batch)?);
+                }
+                Ok(out)
+            })
+            .await??;
+
+            let mut cache = self.cache.lock();
+            for (&i, embedding) in missing.iter().zip(computed) {
+                cache.insert(keys[i], embedding.clone());
+                results[i] = Some(embedding);
+            }
+        }
+
+        Ok(results.into_iter().flatten().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_keys_by_content() {
+        assert_eq!(EmbeddingCache::key("hello"), EmbeddingCache::key("hello"));
+        assert_ne!(EmbeddingCache::key("hello"), EmbeddingCache::key("hello "));
+    }
+
+    #[test]
+    fn cache_evicts_oldest_entries_past_capacity() {
+        let mut cache = EmbeddingCache::new(2);
+        let (a, b, c) = (
+            EmbeddingCache::key("a"),
+            EmbeddingCache::key("b"),
+            EmbeddingCache::key("c"),
+        );
+        cache.insert(a, vec![1.0]);
+        cache.insert(b, vec![2.0]);
+        cache.insert(a, vec![1.5]);
+        cache.insert(c, vec![3.0]);
+
+        assert!(cache.get(&a).is_none());
+        assert_eq!(cache.get(&b), Some(vec![2.0]));
+        assert_eq!(cache.get(&c), Some(vec![3.0]));
+    }
+
+    #[test]
+    fn zero_capacity_cache_stores_nothing() {
+        let mut cache = EmbeddingCache::new(0);
+        let key = EmbeddingCache::key("a");
+        cache.insert(key, vec![1.0]);
+        assert!(cache.get(&key).is_none());
+    }
+
+    #[test]
+    fn load_reports_missing_model_files() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let err = LocalEmbedding::load(tmp.path(), 384).err().unwrap();
+        assert!(format!("{err:#}").contains("config.json"));
+    }
+}
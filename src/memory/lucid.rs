@@ -382,6 +382,10 @@ impl Memory for LucidMemory {
     async fn health_check(&self) -> bool {
         self.local.health_check().await
     }
+
+    async fn reindex(&self) -> anyhow::Result<usize> {
+        self.local.reindex().await
+    }
 }
 
 #[cfg(all(test, unix))]
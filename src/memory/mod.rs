@@ -2,10 +2,14 @@ pub mod backend;
 pub mod chunker;
 pub mod cli;
 pub mod embeddings;
+pub mod hnsw;
 pub mod hygiene;
+#[cfg(feature = "embeddings-local")]
+pub mod local_embedding;
 pub mod lucid;
 pub mod markdown;
 pub mod none;
+pub mod obsidian;
 #[cfg(feature = "memory-postgres")]
 pub mod postgres;
 pub mod response_cache;
@@ -22,6 +26,7 @@ pub use backend::{
 pub use lucid::LucidMemory;
 pub use markdown::MarkdownMemory;
 pub use none::NoneMemory;
+pub use obsidian::ObsidianMemory;
 #[cfg(feature = "memory-postgres")]
 pub use postgres::PostgresMemory;
 pub use response_cache::ResponseCache;
@@ -36,16 +41,18 @@ use anyhow::Context;
 use std::path::Path;
 use std::sync::Arc;
 
-fn create_memory_with_builders<F, G>(
+fn create_memory_with_builders<F, G, H>(
     backend_name: &str,
     workspace_dir: &Path,
     mut sqlite_builder: F,
     mut postgres_builder: G,
+    mut obsidian_builder: H,
     unknown_context: &str,
 ) -> anyhow::Result<Box<dyn Memory>>
 where
     F: FnMut() -> anyhow::Result<SqliteMemory>,
     G: FnMut() -> anyhow::Result<Box<dyn Memory>>,
+    H: FnMut() -> anyhow::Result<ObsidianMemory>,
 {
     match classify_memory_backend(backend_name) {
         MemoryBackendKind::Sqlite => Ok(Box::new(sqlite_builder()?)),
@@ -55,6 +62,7 @@ where
         }
         MemoryBackendKind::Postgres => postgres_builder(),
         MemoryBackendKind::Markdown => Ok(Box::new(MarkdownMemory::new(workspace_dir))),
+        MemoryBackendKind::Obsidian => Ok(Box::new(obsidian_builder()?)),
         MemoryBackendKind::None => Ok(Box::new(NoneMemory::new())),
         MemoryBackendKind::Unknown => {
             tracing::warn!(
@@ -237,23 +245,39 @@ pub fn create_memory_with_storage_and_routes(
         }
     }
 
+    fn build_embedder(
+        workspace_dir: &Path,
+        resolved_embedding: &ResolvedEmbeddingConfig,
+    ) -> Arc<dyn embeddings::EmbeddingProvider> {
+        // Relative local model directories live under the workspace.
+        let model = if resolved_embedding.provider == "local"
+            && Path::new(&resolved_embedding.model).is_relative()
+            && !resolved_embedding.model.starts_with('~')
+        {
+            workspace_dir
+                .join(&resolved_embedding.model)
+                .to_string_lossy()
+                .into_owned()
+        } else {
+            resolved_embedding.model.clone()
+        };
+        Arc::from(embeddings::create_embedding_provider(
+            &resolved_embedding.provider,
+            resolved_embedding.api_key.as_deref(),
+            &model,
+            resolved_embedding.dimensions,
+        ))
+    }
+
     fn build_sqlite_memory(
         config: &MemoryConfig,
         workspace_dir: &Path,
         resolved_embedding: &ResolvedEmbeddingConfig,
     ) -> anyhow::Result<SqliteMemory> {
-        let embedder: Arc<dyn embeddings::EmbeddingProvider> =
-            Arc::from(embeddings::create_embedding_provider(
-                &resolved_embedding.provider,
-                resolved_embedding.api_key.as_deref(),
-                &resolved_embedding.model,
-                resolved_embedding.dimensions,
-            ));
-
         #[allow(clippy::cast_possible_truncation)]
         let mem = SqliteMemory::with_embedder(
             workspace_dir,
-            embedder,
+            build_embedder(workspace_dir, resolved_embedding),
             config.vector_weight as f32,
             config.keyword_weight as f32,
             config.embedding_cache_size,
@@ -262,8 +286,26 @@ pub fn create_memory_with_storage_and_routes(
         Ok(mem)
     }
 
+    fn build_obsidian_memory(
+        config: &MemoryConfig,
+        workspace_dir: &Path,
+        resolved_embedding: &ResolvedEmbeddingConfig,
+    ) -> anyhow::Result<ObsidianMemory> {
+        #[allow(clippy::cast_possible_truncation)]
+        ObsidianMemory::new(
+            workspace_dir,
+            &config.obsidian,
+            build_embedder(workspace_dir, resolved_embedding),
+            config.vector_weight as f32,
+            config.keyword_weight as f32,
+        )
+    }
+
     #[cfg(feature = "memory-postgres")]
     fn build_postgres_memory(
+        config: &MemoryConfig,
+        workspace_dir: &Path,
+        resolved_embedding: &ResolvedEmbeddingConfig,
         storage_provider: Option<&StorageProviderConfig>,
     ) -> anyhow::Result<Box<dyn Memory>> {
         let storage_provider = storage_provider
@@ -277,17 +319,24 @@ pub fn create_memory_with_storage_and_routes(
                 "memory backend 'postgres' requires [storage.provider.config].db_url (or dbURL)",
             )?;
 
-        let memory = PostgresMemory::new(
+        #[allow(clippy::cast_possible_truncation)]
+        let memory = PostgresMemory::with_embedder(
             db_url,
             &storage_provider.schema,
             &storage_provider.table,
             storage_provider.connect_timeout_secs,
+            build_embedder(workspace_dir, resolved_embedding),
+            config.vector_weight as f32,
+            config.keyword_weight as f32,
         )?;
         Ok(Box::new(memory))
     }
 
     #[cfg(not(feature = "memory-postgres"))]
     fn build_postgres_memory(
+        _config: &MemoryConfig,
+        _workspace_dir: &Path,
+        _resolved_embedding: &ResolvedEmbeddingConfig,
         _storage_provider: Option<&StorageProviderConfig>,
     ) -> anyhow::Result<Box<dyn Memory>> {
         anyhow::bail!(
@@ -299,7 +348,8 @@ pub fn create_memory_with_storage_and_routes(
         &backend_name,
         workspace_dir,
         || build_sqlite_memory(config, workspace_dir, &resolved_embedding),
-        || build_postgres_memory(storage_provider),
+        || build_postgres_memory(config, workspace_dir, &resolved_embedding, storage_provider),
+        || build_obsidian_memory(config, workspace_dir, &resolved_embedding),
         "",
     )
 }
@@ -323,11 +373,21 @@ pub fn create_memory_for_migration(
         );
     }
 
+    if matches!(
+        classify_memory_backend(backend),
+        MemoryBackendKind::Obsidian
+    ) {
+        anyhow::bail!(
+            "memory migration for backend 'obsidian' is unsupported; the vault itself is the source of truth"
+        );
+    }
+
     create_memory_with_builders(
         backend,
         workspace_dir,
         || SqliteMemory::new(workspace_dir),
         || anyhow::bail!("postgres backend is not available in migration context"),
+        || anyhow::bail!("obsidian backend is not available in migration context"),
         " during migration",
     )
 }
@@ -395,6 +455,32 @@ mod tests {
         assert_eq!(mem.name(), "markdown");
     }
 
+    #[test]
+    fn factory_obsidian() {
+        let tmp = TempDir::new().unwrap();
+        let vault = TempDir::new().unwrap();
+        std::fs::write(vault.path().join("Note.md"), "Hello vault").unwrap();
+        let cfg = MemoryConfig {
+            backend: "obsidian".into(),
+            obsidian: crate::config::ObsidianMemoryConfig {
+                vault_path: Some(vault.path().display().to_string()),
+                ..crate::config::ObsidianMemoryConfig::default()
+            },
+            ..MemoryConfig::default()
+        };
+        let mem = create_memory(&cfg, tmp.path(), None).unwrap();
+        assert_eq!(mem.name(), "obsidian");
+    }
+
+    #[test]
+    fn migration_factory_rejects_obsidian() {
+        let tmp = TempDir::new().unwrap();
+        let error = create_memory_for_migration("obsidian", tmp.path())
+            .err()
+            .expect("obsidian migration should be rejected");
+        assert!(error.to_string().contains("'obsidian' is unsupported"));
+    }
+
     #[test]
     fn factory_lucid() {
         let tmp = TempDir::new().unwrap();
@@ -0,0 +1,1311 @@
+use super::embeddings::EmbeddingProvider;
+use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::vector;
+use crate::config::ObsidianMemoryConfig;
+use anyhow::Context;
+use async_trait::async_trait;
+use chrono::{Local, NaiveDate, TimeZone};
+use parking_lot::Mutex;
+use regex::Regex;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Weak};
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Score multiplier applied per `[[wikilink]]` hop during recall expansion.
+const LINK_DECAY: f32 = 0.5;
+
+/// Notes embedded per provider call while catching up on changed notes.
+const EMBED_BATCH_SIZE: usize = 16;
+
+/// Characters of a note sent to the embedding provider.
+const EMBED_MAX_CHARS: usize = 8_000;
+
+/// `[[target]]`, `[[target|alias]]`, `[[target#heading]]` and `![[embeds]]`.
+static WIKILINK_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[\[([^\[\]|#^]+)[^\[\]]*\]\]").unwrap());
+
+/// Inline `#tag` (Obsidian requires at least one non-digit character).
+static TAG_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|\s)#([A-Za-z0-9_/-]*[A-Za-z_/-][A-Za-z0-9_/-]*)").unwrap());
+
+/// A vault note reduced to what the index stores.
+#[derive(Debug, Clone, PartialEq)]
+struct ParsedNote {
+    /// Vault-relative path without the `.md` extension.
+    key: String,
+    /// Lower-cased file stem, used to resolve `[[wikilinks]]`.
+    name: String,
+    title: String,
+    category: MemoryCategory,
+    /// Memory key the note was stored under by [`ObsidianMemory::store`].
+    memory_key: Option<String>,
+    tags: Vec<String>,
+    frontmatter: serde_json::Map<String, serde_json::Value>,
+    body: String,
+    /// Normalized link targets (lower-cased, without `.md`).
+    links: Vec<String>,
+}
+
+/// Split a leading `---` frontmatter block from the note body.
+fn split_frontmatter(text: &str) -> (Option<&str>, &str) {
+    let Some(rest) = text
+        .strip_prefix("---\n")
+        .or_else(|| text.strip_prefix("---\r\n"))
+    else {
+        return (None, text);
+    };
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return (Some(&rest[..offset]), &rest[offset + line.len()..]);
+        }
+        offset += line.len();
+    }
+    (None, text)
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value)
+        .to_string()
+}
+
+/// Parse the flat YAML subset Obsidian writes: `key: value`, inline
+/// `[a, b]` lists and `- item` block lists.
+fn parse_frontmatter(yaml: &str) -> serde_json::Map<String, serde_json::Value> {
+    use serde_json::Value;
+
+    let mut map = serde_json::Map::new();
+    let mut list_key: Option<String> = None;
+    for line in yaml.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if let Some(item) = trimmed.strip_prefix("- ") {
+            if let Some(Value::Array(items)) = list_key.as_ref().and_then(|key| map.get_mut(key)) {
+                items.push(Value::String(unquote(item)));
+            }
+            continue;
+        }
+        let Some((key, value)) = trimmed.split_once(':') else {
+            continue;
+        };
+        let key = key.trim().to_string();
+        let value = value.trim();
+        if value.is_empty() {
+            map.insert(key.clone(), Value::Array(Vec::new()));
+            list_key = Some(key);
+            continue;
+        }
+        list_key = None;
+        let value = match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            Some(items) => Value::Array(
+                items
+                    .split(',')
+                    .map(unquote)
+                    .filter(|item| !item.is_empty())
+                    .map(Value::String)
+                    .collect(),
+            ),
+            None => Value::String(unquote(value)),
+        };
+        map.insert(key, value);
+    }
+    map
+}
+
+/// Body text outside fenced code blocks, where tags and links are recognised.
+fn prose_lines(body: &str) -> impl Iterator<Item = &str> {
+    let mut in_fence = false;
+    body.lines().filter(move |line| {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            return false;
+        }
+        !in_fence
+    })
+}
+
+fn normalize_link(target: &str) -> String {
+    let target = target.trim().trim_start_matches('/');
+    let target = target.strip_suffix(".md").unwrap_or(target);
+    target.to_lowercase()
+}
+
+fn category_to_str(category: &MemoryCategory) -> String {
+    category.to_string()
+}
+
+fn str_to_category(value: &str) -> MemoryCategory {
+    match value {
+        "core" => MemoryCategory::Core,
+        "daily" => MemoryCategory::Daily,
+        "conversation" => MemoryCategory::Conversation,
+        other => MemoryCategory::Custom(other.to_string()),
+    }
+}
+
+fn parse_note(rel_path: &str, text: &str) -> ParsedNote {
+    let key = rel_path.strip_suffix(".md").unwrap_or(rel_path).to_string();
+    let stem = key.rsplit('/').next().unwrap_or(&key).to_string();
+    let (frontmatter, body) = split_frontmatter(text);
+    let frontmatter = frontmatter.map(parse_frontmatter).unwrap_or_default();
+    let field = |name: &str| {
+        frontmatter
+            .get(name)
+            .and_then(serde_json::Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+
+    let mut tags: Vec<String> = Vec::new();
+    let mut add_tag = |tag: &str| {
+        let tag = tag.trim().trim_start_matches('#').to_lowercase();
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    };
+    for key in ["tags", "tag"] {
+        match frontmatter.get(key) {
+            Some(serde_json::Value::Array(items)) => items
+                .iter()
+                .filter_map(serde_json::Value::as_str)
+                .for_each(&mut add_tag),
+            Some(serde_json::Value::String(value)) => value
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .for_each(&mut add_tag),
+            _ => {}
+        }
+    }
+
+    let mut links: Vec<String> = Vec::new();
+    let mut heading = None;
+    for line in prose_lines(body) {
+        if heading.is_none() {
+            heading = line.strip_prefix("# ").map(str::trim);
+        }
+        for capture in TAG_RE.captures_iter(line) {
+            add_tag(&capture[1]);
+        }
+        for capture in WIKILINK_RE.captures_iter(line) {
+            let target = normalize_link(&capture[1]);
+            if !target.is_empty() && !links.contains(&target) {
+                links.push(target);
+            }
+        }
+    }
+
+    let category = if NaiveDate::parse_from_str(&stem, "%Y-%m-%d").is_ok() {
+        MemoryCategory::Daily
+    } else {
+        field("category").map_or(MemoryCategory::Core, str_to_category)
+    };
+
+    ParsedNote {
+        title: field("title")
+            .or(heading)
+            .filter(|title| !title.is_empty())
+            .unwrap_or(&stem)
+            .to_string(),
+        memory_key: field("key").map(ToString::to_string),
+        name: stem.to_lowercase(),
+        key,
+        category,
+        tags,
+        body: body.trim().to_string(),
+        frontmatter,
+        links,
+    }
+}
+
+fn content_hash(text: &str) -> String {
+    use sha2::{Digest, Sha256};
+    hex::encode(&Sha256::digest(text.as_bytes())[..16])
+}
+
+fn modified_millis(metadata: &std::fs::Metadata) -> i64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .and_then(|elapsed| i64::try_from(elapsed.as_millis()).ok())
+        .unwrap_or_default()
+}
+
+fn millis_to_rfc3339(millis: i64) -> String {
+    Local
+        .timestamp_millis_opt(millis)
+        .single()
+        .map(|time| time.to_rfc3339())
+        .unwrap_or_default()
+}
+
+/// Vault-relative paths of every Markdown note, skipping dot-directories
+/// such as `.obsidian` and `.trash`.
+fn walk_vault(vault_dir: &Path) -> Vec<(String, PathBuf)> {
+    let mut notes = Vec::new();
+    let mut pending = vec![vault_dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with('.') {
+                continue;
+            }
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if file_type.is_file() && name.ends_with(".md") {
+                if let Ok(rel) = path.strip_prefix(vault_dir) {
+                    let rel = rel
+                        .components()
+                        .map(|c| c.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    notes.push((rel, path));
+                }
+            }
+        }
+    }
+    notes
+}
+
+/// Sanitize a memory key into a note file name.
+fn note_file_name(key: &str) -> String {
+    let name: String = key
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '#' | '^' | '[' | ']' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let name = name.trim_start_matches('.');
+    if name.is_empty() {
+        "memory".into()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Search index over the vault, shared with the background watcher.
+struct VaultIndex {
+    vault_dir: PathBuf,
+    inbox_folder: String,
+    daily_folder: String,
+    conn: Arc<Mutex<Connection>>,
+    embedder: Arc<dyn EmbeddingProvider>,
+    vector_weight: f32,
+    keyword_weight: f32,
+    link_depth: usize,
+    /// Serializes vault scans so the watcher and recall never race.
+    sync_lock: tokio::sync::Mutex<()>,
+}
+
+impl VaultIndex {
+    fn init_schema(conn: &Connection) -> anyhow::Result<()> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS notes (
+                path         TEXT PRIMARY KEY,
+                key          TEXT NOT NULL UNIQUE,
+                name         TEXT NOT NULL,
+                memory_key   TEXT,
+                title        TEXT NOT NULL,
+                category     TEXT NOT NULL,
+                tags         TEXT NOT NULL DEFAULT '',
+                frontmatter  TEXT NOT NULL DEFAULT '{}',
+                body         TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                modified     INTEGER NOT NULL,
+                embedding    BLOB
+            );
+            CREATE INDEX IF NOT EXISTS idx_notes_name ON notes(name);
+            CREATE INDEX IF NOT EXISTS idx_notes_memory_key ON notes(memory_key);
+
+            CREATE VIRTUAL TABLE IF NOT EXISTS notes_fts USING fts5(
+                title, tags, body, content=notes, content_rowid=rowid
+            );
+            CREATE TRIGGER IF NOT EXISTS notes_ai AFTER INSERT ON notes BEGIN
+                INSERT INTO notes_fts(rowid, title, tags, body)
+                VALUES (new.rowid, new.title, new.tags, new.body);
+            END;
+            CREATE TRIGGER IF NOT EXISTS notes_ad AFTER DELETE ON notes BEGIN
+                INSERT INTO notes_fts(notes_fts, rowid, title, tags, body)
+                VALUES ('delete', old.rowid, old.title, old.tags, old.body);
+            END;
+            CREATE TRIGGER IF NOT EXISTS notes_au AFTER UPDATE ON notes BEGIN
+                INSERT INTO notes_fts(notes_fts, rowid, title, tags, body)
+                VALUES ('delete', old.rowid, old.title, old.tags, old.body);
+                INSERT INTO notes_fts(rowid, title, tags, body)
+                VALUES (new.rowid, new.title, new.tags, new.body);
+            END;
+
+            -- Wikilink graph: source note path -> normalized link target
+            CREATE TABLE IF NOT EXISTS links (
+                source TEXT NOT NULL,
+                target TEXT NOT NULL,
+                PRIMARY KEY (source, target)
+            );
+            CREATE INDEX IF NOT EXISTS idx_links_target ON links(target);",
+        )?;
+        Ok(())
+    }
+
+    /// Index one note. Unchanged content keeps its embedding; changed
+    /// content is queued for re-embedding.
+    fn index_note(
+        conn: &Connection,
+        rel_path: &str,
+        text: &str,
+        modified: i64,
+    ) -> anyhow::Result<()> {
+        let hash = content_hash(text);
+        let existing: Option<String> = conn
+            .query_row(
+                "SELECT content_hash FROM notes WHERE path = ?1",
+                params![rel_path],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if existing.as_deref() == Some(hash.as_str()) {
+            conn.execute(
+                "UPDATE notes SET modified = ?1 WHERE path = ?2",
+                params![modified, rel_path],
+            )?;
+            return Ok(());
+        }
+
+        let note = parse_note(rel_path, text);
+        let frontmatter = serde_json::Value::Object(note.frontmatter).to_string();
+        conn.execute(
+            "INSERT INTO notes (path, key, name, memory_key, title, category, tags, frontmatter,
+                                body, content_hash, modified, embedding)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, NULL)
+             ON CONFLICT(path) DO UPDATE SET
+                key = excluded.key, name = excluded.name, memory_key = excluded.memory_key,
+                title = excluded.title, category = excluded.category, tags = excluded.tags,
+                frontmatter = excluded.frontmatter, body = excluded.body,
+                content_hash = excluded.content_hash, modified = excluded.modified,
+                embedding = NULL",
+            params![
+                rel_path,
+                note.key,
+                note.name,
+                note.memory_key,
+                note.title,
+                category_to_str(&note.category),
+                note.tags.join(" "),
+                frontmatter,
+                note.body,
+                hash,
+                modified,
+            ],
+        )?;
+        conn.execute("DELETE FROM links WHERE source = ?1", params![rel_path])?;
+        for target in &note.links {
+            conn.execute(
+                "INSERT OR IGNORE INTO links (source, target) VALUES (?1, ?2)",
+                params![rel_path, target],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn remove_note(conn: &Connection, rel_path: &str) -> anyhow::Result<bool> {
+        conn.execute("DELETE FROM links WHERE source = ?1", params![rel_path])?;
+        Ok(conn.execute("DELETE FROM notes WHERE path = ?1", params![rel_path])? > 0)
+    }
+
+    /// Bring the index in line with the vault. Returns the number of notes
+    /// added, changed or removed.
+    fn scan(conn: &Connection, vault_dir: &Path) -> anyhow::Result<usize> {
+        let known: HashMap<String, i64> = {
+            let mut stmt = conn.prepare("SELECT path, modified FROM notes")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<Result<_, _>>()?
+        };
+
+        let mut seen = HashSet::new();
+        let mut changed = 0;
+        for (rel_path, path) in walk_vault(vault_dir) {
+            let Ok(metadata) = std::fs::metadata(&path) else {
+                continue;
+            };
+            let modified = modified_millis(&metadata);
+            seen.insert(rel_path.clone());
+            if known.get(&rel_path) == Some(&modified) {
+                continue;
+            }
+            let Ok(text) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            Self::index_note(conn, &rel_path, &text, modified)?;
+            changed += 1;
+        }
+
+        for rel_path in known.keys().filter(|path| !seen.contains(*path)) {
+            Self::remove_note(conn, rel_path)?;
+            changed += 1;
+        }
+        Ok(changed)
+    }
+
+    /// Re-scan the vault, then embed notes whose content changed.
+    async fn sync(&self) -> anyhow::Result<usize> {
+        let _guard = self.sync_lock.lock().await;
+        let conn = self.conn.clone();
+        let vault_dir = self.vault_dir.clone();
+        let changed =
+            tokio::task::spawn_blocking(move || Self::scan(&conn.lock(), &vault_dir)).await??;
+        if changed > 0 {
+            tracing::debug!("Obsidian vault: {changed} note(s) changed");
+        }
+        self.embed_pending().await?;
+        Ok(changed)
+    }
+
+    async fn embed_pending(&self) -> anyhow::Result<()> {
+        if self.embedder.dimensions() == 0 {
+            return Ok(());
+        }
+        loop {
+            let conn = self.conn.clone();
+            let batch: Vec<(String, String)> = tokio::task::spawn_blocking(move || {
+                let conn = conn.lock();
+                let mut stmt = conn.prepare(
+                    "SELECT path, title, body FROM notes WHERE embedding IS NULL LIMIT ?1",
+                )?;
+                #[allow(clippy::cast_possible_wrap)]
+                let rows = stmt.query_map(params![EMBED_BATCH_SIZE as i64], |row| {
+                    let title: String = row.get(1)?;
+                    let body: String = row.get(2)?;
+                    let text: String = format!("{title}\n\n{body}")
+                        .chars()
+                        .take(EMBED_MAX_CHARS)
+                        .collect();
+                    Ok((row.get(0)?, text))
+                })?;
+                rows.collect::<Result<Vec<_>, _>>()
+                    .map_err(anyhow::Error::from)
+            })
+            .await??;
+            if batch.is_empty() {
+                return Ok(());
+            }
+
+            let texts: Vec<&str> = batch.iter().map(|(_, text)| text.as_str()).collect();
+            let embeddings = self.embedder.embed(&texts).await?;
+            if embeddings.len() != batch.len() {
+                anyhow::bail!(
+                    "embedding provider returned {} vectors for {} notes",
+                    embeddings.len(),
+                    batch.len()
+                );
+            }
+
+            let conn = self.conn.clone();
+            tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+                let conn = conn.lock();
+                for ((path, _), embedding) in batch.iter().zip(embeddings) {
+                    conn.execute(
+                        "UPDATE notes SET embedding = ?1 WHERE path = ?2",
+                        params![vector::vec_to_bytes(&embedding), path],
+                    )?;
+                }
+                Ok(())
+            })
+            .await??;
+        }
+    }
+
+    fn keyword_search(
+        conn: &Connection,
+        query: &str,
+        limit: usize,
+    ) -> anyhow::Result<Vec<(String, f32)>> {
+        let fts_query = query
+            .split_whitespace()
+            .map(|word| format!("\"{}\"", word.replace('"', "")))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        if fts_query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut stmt = conn.prepare(
+            "SELECT n.path, bm25(notes_fts) AS score
+             FROM notes_fts f JOIN notes n ON n.rowid = f.rowid
+             WHERE notes_fts MATCH ?1
+             ORDER BY score
+             LIMIT ?2",
+        )?;
+        #[allow(clippy::cast_possible_wrap)]
+        let rows = stmt.query_map(params![fts_query, limit as i64], |row| {
+            let score: f64 = row.get(1)?;
+            #[allow(clippy::cast_possible_truncation)]
+            Ok((row.get(0)?, (-score) as f32))
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    fn vector_search(
+        conn: &Connection,
+        query: &[f32],
+        limit: usize,
+    ) -> anyhow::Result<Vec<(String, f32)>> {
+        let mut stmt =
+            conn.prepare("SELECT path, embedding FROM notes WHERE embedding IS NOT NULL")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?))
+        })?;
+        let mut scored = Vec::new();
+        for row in rows {
+            let (path, blob) = row?;
+            let similarity = vector::cosine_similarity(query, &vector::bytes_to_vec(&blob));
+            if similarity > 0.0 {
+                scored.push((path, similarity));
+            }
+        }
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(limit);
+        Ok(scored)
+    }
+
+    /// Notes linked from or linking to `path`.
+    fn neighbours(conn: &Connection, path: &str) -> anyhow::Result<Vec<String>> {
+        let mut stmt = conn.prepare(
+            "SELECT n.path FROM links l
+               JOIN notes n ON n.name = l.target OR lower(n.key) = l.target
+              WHERE l.source = ?1
+             UNION
+             SELECT l.source FROM links l
+               JOIN notes n ON n.name = l.target OR lower(n.key) = l.target
+              WHERE n.path = ?1",
+        )?;
+        let rows = stmt.query_map(params![path], |row| row.get(0))?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// Follow wikilinks from the matched notes, scoring each hop with
+    /// [`LINK_DECAY`]. Direct matches keep their own score.
+    fn expand_links(
+        conn: &Connection,
+        seeds: Vec<(String, f32)>,
+        depth: usize,
+    ) -> anyhow::Result<HashMap<String, f32>> {
+        let mut scores: HashMap<String, f32> = seeds.iter().cloned().collect();
+        let mut frontier = seeds;
+        for _ in 0..depth {
+            let mut next = Vec::new();
+            for (path, score) in &frontier {
+                let linked_score = score * LINK_DECAY;
+                for neighbour in Self::neighbours(conn, path)? {
+                    let best = scores.entry(neighbour.clone()).or_insert(0.0);
+                    if linked_score > *best {
+                        *best = linked_score;
+                        next.push((neighbour, linked_score));
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+        Ok(scores)
+    }
+
+    fn row_to_entry(row: &rusqlite::Row<'_>) -> rusqlite::Result<MemoryEntry> {
+        let key: String = row.get(0)?;
+        let memory_key: Option<String> = row.get(1)?;
+        let category: String = row.get(3)?;
+        Ok(MemoryEntry {
+            id: key.clone(),
+            key: memory_key.unwrap_or(key),
+            content: row.get(2)?,
+            category: str_to_category(&category),
+            timestamp: millis_to_rfc3339(row.get(4)?),
+            session_id: None,
+            score: None,
+        })
+    }
+
+    fn entry_by_path(conn: &Connection, path: &str) -> anyhow::Result<Option<MemoryEntry>> {
+        Ok(conn
+            .query_row(
+                "SELECT key, memory_key, body, category, modified FROM notes WHERE path = ?1",
+                params![path],
+                Self::row_to_entry,
+            )
+            .optional()?)
+    }
+
+    /// Note path for a memory key: the key it was stored under, its vault
+    /// path without `.md`, or its file name.
+    /// Note written for memory `key` (frontmatter `key:`), if any.
+    fn path_for_memory_key(conn: &Connection, key: &str) -> anyhow::Result<Option<String>> {
+        Ok(conn
+            .query_row(
+                "SELECT path FROM notes WHERE memory_key = ?1 ORDER BY path LIMIT 1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    fn resolve_key(conn: &Connection, key: &str) -> anyhow::Result<Option<String>> {
+        Ok(conn
+            .query_row(
+                "SELECT path FROM notes
+                 WHERE memory_key = ?1 OR key = ?1 OR name = lower(?1)
+                 ORDER BY (memory_key = ?1) DESC, (key = ?1) DESC
+                 LIMIT 1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+}
+
+/// Obsidian vault memory — an existing vault as the source of truth.
+///
+/// - **Index**: frontmatter, tags and bodies in SQLite FTS5 (plus embeddings)
+///   under `workspace/memory/obsidian_index.db`
+/// - **Graph**: `[[wikilinks]]` stored as edges; recall expands through them
+/// - **Daily notes**: `YYYY-MM-DD.md` notes map to [`MemoryCategory::Daily`]
+/// - **Watcher**: periodic rescans pick up external edits and re-embed only
+///   notes whose content changed
+pub struct ObsidianMemory {
+    index: Arc<VaultIndex>,
+    /// Without a watcher, every recall rescans the vault first.
+    rescan_on_recall: bool,
+}
+
+impl ObsidianMemory {
+    pub fn new(
+        workspace_dir: &Path,
+        config: &ObsidianMemoryConfig,
+        embedder: Arc<dyn EmbeddingProvider>,
+        vector_weight: f32,
+        keyword_weight: f32,
+    ) -> anyhow::Result<Self> {
+        let vault_path = config
+            .vault_path
+            .as_deref()
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .context("memory backend 'obsidian' requires [memory.obsidian].vault_path")?;
+        let vault_dir = PathBuf::from(shellexpand::tilde(vault_path).into_owned());
+        if !vault_dir.is_dir() {
+            anyhow::bail!("Obsidian vault not found: {}", vault_dir.display());
+        }
+
+        let db_path = workspace_dir.join("memory").join("obsidian_index.db");
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(&db_path).context("SQLite failed to open Obsidian index")?;
+        conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")?;
+        VaultIndex::init_schema(&conn)?;
+        let changed = VaultIndex::scan(&conn, &vault_dir)?;
+        tracing::info!(
+            "Obsidian vault {} indexed ({changed} note(s) updated)",
+            vault_dir.display()
+        );
+
+        let index = Arc::new(VaultIndex {
+            vault_dir,
+            inbox_folder: config.inbox_folder.trim().trim_matches('/').to_string(),
+            daily_folder: config.daily_folder.trim().trim_matches('/').to_string(),
+            conn: Arc::new(Mutex::new(conn)),
+            embedder,
+            vector_weight,
+            keyword_weight,
+            link_depth: config.link_expansion_depth,
+            sync_lock: tokio::sync::Mutex::new(()),
+        });
+
+        if config.watch_interval_secs > 0 {
+            if let Ok(handle) = tokio::runtime::Handle::try_current() {
+                handle.spawn(watch_vault(
+                    Arc::downgrade(&index),
+                    Duration::from_secs(config.watch_interval_secs),
+                ));
+            }
+        }
+
+        Ok(Self {
+            index,
+            rescan_on_recall: config.watch_interval_secs == 0,
+        })
+    }
+
+    /// Re-scan the vault now instead of waiting for the watcher.
+    pub async fn sync(&self) -> anyhow::Result<usize> {
+        self.index.sync().await
+    }
+
+    fn folder_path(&self, folder: &str, file_name: &str) -> (String, PathBuf) {
+        let rel_path = if folder.is_empty() {
+            file_name.to_string()
+        } else {
+            format!("{folder}/{file_name}")
+        };
+        let path = self.index.vault_dir.join(&rel_path);
+        (rel_path, path)
+    }
+
+    /// Path for a new note in the inbox folder that does not replace any
+    /// existing file.
+    async fn new_note_path(&self, key: &str) -> anyhow::Result<(String, PathBuf)> {
+        let name = note_file_name(key);
+        let mut file_name = format!("{name}.md");
+        let mut n = 1;
+        loop {
+            let (rel_path, path) = self.folder_path(&self.index.inbox_folder, &file_name);
+            if !tokio::fs::try_exists(&path).await? {
+                return Ok((rel_path, path));
+            }
+            n += 1;
+            file_name = format!("{name} {n}.md");
+        }
+    }
+
+    async fn reindex(&self, rel_path: String, path: PathBuf) -> anyhow::Result<()> {
+        let conn = self.index.conn.clone();
+        tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+            let text = std::fs::read_to_string(&path)?;
+            let modified = modified_millis(&std::fs::metadata(&path)?);
+            VaultIndex::index_note(&conn.lock(), &rel_path, &text, modified)
+        })
+        .await??;
+        self.index.embed_pending().await
+    }
+}
+
+/// Rescan the vault until the memory is dropped.
+async fn watch_vault(index: Weak<VaultIndex>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        let Some(index) = index.upgrade() else {
+            break;
+        };
+        if let Err(e) = index.sync().await {
+            tracing::warn!("Obsidian vault sync failed: {e}");
+        }
+    }
+}
+
+#[async_trait]
+impl Memory for ObsidianMemory {
+    fn name(&self) -> &str {
+        "obsidian"
+    }
+
+    async fn store(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        _session_id: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let (rel_path, path, text) = match category {
+            // Session logs go to today's daily note, like the markdown backend.
+            MemoryCategory::Daily | MemoryCategory::Conversation => {
+                let date = Local::now().format("%Y-%m-%d").to_string();
+                let (rel_path, path) =
+                    self.folder_path(&self.index.daily_folder, &format!("{date}.md"));
+                let existing = tokio::fs::read_to_string(&path).await.unwrap_or_default();
+                let entry = format!("- **{key}**: {content}");
+                let text = if existing.trim().is_empty() {
+                    format!("# {date}\n\n{entry}\n")
+                } else {
+                    format!("{}\n{entry}\n", existing.trim_end())
+                };
+                (rel_path, path, text)
+            }
+            MemoryCategory::Core | MemoryCategory::Custom(_) => {
+                // Only notes written for this memory key are overwritten; a
+                // user note that merely shares its name or title is kept.
+                let existing = {
+                    let conn = self.index.conn.clone();
+                    let key = key.to_string();
+                    tokio::task::spawn_blocking(move || {
+                        VaultIndex::path_for_memory_key(&conn.lock(), &key)
+                    })
+                    .await??
+                };
+                let inbox_prefix = format!("{}/", self.index.inbox_folder);
+                let (rel_path, path) = match existing {
+                    Some(rel_path)
+                        if self.index.inbox_folder.is_empty()
+                            || rel_path.starts_with(&inbox_prefix) =>
+                    {
+                        let path = self.index.vault_dir.join(&rel_path);
+                        (rel_path, path)
+                    }
+                    _ => self.new_note_path(key).await?,
+                };
+                let text = format!(
+                    "---\nkey: {}\ncategory: {}\nupdated: {}\n---\n\n{content}\n",
+                    serde_json::Value::String(key.to_string()),
+                    category_to_str(&category),
+                    Local::now().to_rfc3339()
+                );
+                (rel_path, path, text)
+            }
+        };
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&path, text).await?;
+        self.reindex(rel_path, path).await
+    }
+
+    async fn recall(
+        &self,
+        query: &str,
+        limit: usize,
+        _session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        if query.trim().is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+        if self.rescan_on_recall {
+            self.index.sync().await?;
+        }
+
+        let query_embedding = if self.index.embedder.dimensions() > 0 {
+            Some(self.index.embedder.embed_one(query).await?)
+        } else {
+            None
+        };
+
+        let conn = self.index.conn.clone();
+        let query = query.to_string();
+        let vector_weight = self.index.vector_weight;
+        let keyword_weight = self.index.keyword_weight;
+        let depth = self.index.link_depth;
+        tokio::task::spawn_blocking(move || -> anyhow::Result<Vec<MemoryEntry>> {
+            let conn = conn.lock();
+            let keyword = VaultIndex::keyword_search(&conn, &query, limit * 2)?;
+            let vector = match &query_embedding {
+                Some(embedding) => VaultIndex::vector_search(&conn, embedding, limit * 2)?,
+                None => Vec::new(),
+            };
+            let merged = if vector.is_empty() {
+                vector::hybrid_merge(&[], &keyword, 0.0, 1.0, limit)
+            } else {
+                vector::hybrid_merge(&vector, &keyword, vector_weight, keyword_weight, limit)
+            };
+            let seeds = merged
+                .into_iter()
+                .map(|scored| (scored.id, scored.final_score))
+                .collect();
+
+            let mut scored: Vec<(String, f32)> = VaultIndex::expand_links(&conn, seeds, depth)?
+                .into_iter()
+                .collect();
+            scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+            scored.truncate(limit);
+
+            let mut results = Vec::with_capacity(scored.len());
+            for (path, score) in scored {
+                if let Some(mut entry) = VaultIndex::entry_by_path(&conn, &path)? {
+                    entry.score = Some(f64::from(score));
+                    results.push(entry);
+                }
+            }
+            Ok(results)
+        })
+        .await?
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>> {
+        let conn = self.index.conn.clone();
+        let key = key.to_string();
+        tokio::task::spawn_blocking(move || -> anyhow::Result<Option<MemoryEntry>> {
+            let conn = conn.lock();
+            match VaultIndex::resolve_key(&conn, &key)? {
+                Some(path) => VaultIndex::entry_by_path(&conn, &path),
+                None => Ok(None),
+            }
+        })
+        .await?
+    }
+
+    async fn list(
+        &self,
+        category: Option<&MemoryCategory>,
+        _session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        let conn = self.index.conn.clone();
+        let category = category.map(category_to_str);
+        tokio::task::spawn_blocking(move || -> anyhow::Result<Vec<MemoryEntry>> {
+            let conn = conn.lock();
+            let mut stmt = conn.prepare(
+                "SELECT key, memory_key, body, category, modified FROM notes
+                 WHERE ?1 IS NULL OR category = ?1
+                 ORDER BY modified DESC",
+            )?;
+            let rows = stmt.query_map(params![category], VaultIndex::row_to_entry)?;
+            Ok(rows.collect::<Result<_, _>>()?)
+        })
+        .await?
+    }
+
+    /// Delete a note the agent stored. Notes outside the inbox folder are
+    /// the user's and are never deleted.
+    async fn forget(&self, key: &str) -> anyhow::Result<bool> {
+        let conn = self.index.conn.clone();
+        let lookup = key.to_string();
+        let Some(rel_path) =
+            tokio::task::spawn_blocking(move || VaultIndex::resolve_key(&conn.lock(), &lookup))
+                .await??
+        else {
+            return Ok(false);
+        };
+
+        let inbox_prefix = format!("{}/", self.index.inbox_folder);
+        if self.index.inbox_folder.is_empty() || !rel_path.starts_with(&inbox_prefix) {
+            tracing::warn!("Obsidian memory: refusing to delete user note {rel_path}");
+            return Ok(false);
+        }
+
+        let path = self.index.vault_dir.join(&rel_path);
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        let conn = self.index.conn.clone();
+        tokio::task::spawn_blocking(move || VaultIndex::remove_note(&conn.lock(), &rel_path))
+            .await?
+    }
+
+    async fn count(&self) -> anyhow::Result<usize> {
+        let conn = self.index.conn.clone();
+        tokio::task::spawn_blocking(move || -> anyhow::Result<usize> {
+            let count: i64 = conn
+                .lock()
+                .query_row("SELECT COUNT(*) FROM notes", [], |row| row.get(0))?;
+            Ok(usize::try_from(count).unwrap_or_default())
+        })
+        .await?
+    }
+
+    async fn health_check(&self) -> bool {
+        if !self.index.vault_dir.is_dir() {
+            return false;
+        }
+        let conn = self.index.conn.clone();
+        tokio::task::spawn_blocking(move || conn.lock().execute_batch("SELECT 1").is_ok())
+            .await
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::TempDir;
+
+    /// Embeds each text as keyword presence flags and counts embedded texts.
+    struct KeywordEmbedding {
+        embedded: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for KeywordEmbedding {
+        fn name(&self) -> &str {
+            "keyword"
+        }
+
+        fn dimensions(&self) -> usize {
+            3
+        }
+
+        async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+            self.embedded.fetch_add(texts.len(), Ordering::SeqCst);
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    let text = text.to_lowercase();
+                    ["rust", "garden", "coffee"]
+                        .iter()
+                        .map(|word| if text.contains(word) { 1.0 } else { 0.01 })
+                        .collect()
+                })
+                .collect())
+        }
+    }
+
+    struct Fixture {
+        _workspace: TempDir,
+        vault: TempDir,
+        memory: ObsidianMemory,
+    }
+
+    fn write_note(vault: &Path, rel_path: &str, text: &str) {
+        let path = vault.join(rel_path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, text).unwrap();
+    }
+
+    fn fixture(link_depth: usize, embedder: Arc<dyn EmbeddingProvider>) -> Fixture {
+        let workspace = TempDir::new().unwrap();
+        let vault = TempDir::new().unwrap();
+        write_note(
+            vault.path(),
+            "Projects/Compiler.md",
+            "---\ntags: [work, rust]\n---\n# Compiler\nWriting a borrow checker. See [[Ideas]].\n",
+        );
+        write_note(
+            vault.path(),
+            "Ideas.md",
+            "Use arenas for the AST. #design\n",
+        );
+        write_note(vault.path(), "Unrelated.md", "Tomatoes need sun.\n");
+        write_note(vault.path(), ".obsidian/workspace.md", "ignored borrow");
+
+        let config = ObsidianMemoryConfig {
+            vault_path: Some(vault.path().display().to_string()),
+            watch_interval_secs: 0,
+            link_expansion_depth: link_depth,
+            ..ObsidianMemoryConfig::default()
+        };
+        let memory = ObsidianMemory::new(workspace.path(), &config, embedder, 0.7, 0.3).unwrap();
+        Fixture {
+            _workspace: workspace,
+            vault,
+            memory,
+        }
+    }
+
+    fn noop() -> Arc<dyn EmbeddingProvider> {
+        Arc::new(super::super::embeddings::NoopEmbedding)
+    }
+
+    #[test]
+    fn parse_note_reads_frontmatter_tags_and_links() {
+        let note = parse_note(
+            "Projects/Compiler.md",
+            "---\ntitle: \"Rust compiler\"\ntags:\n  - work\n  - '#Rust'\ncategory: projects\n---\n\
+             Body with #inline/tag and [[Ideas|some ideas]], ![[Diagram.png]] and [[Notes/Plan#Step 2]].\n\
+             ```\n#not-a-tag [[NotALink]]\n```\nIssue #42 is not a tag.\n",
+        );
+
+        assert_eq!(note.key, "Projects/Compiler");
+        assert_eq!(note.name, "compiler");
+        assert_eq!(note.title, "Rust compiler");
+        assert_eq!(note.category, MemoryCategory::Custom("projects".into()));
+        assert_eq!(note.tags, vec!["work", "rust", "inline/tag"]);
+        assert_eq!(note.links, vec!["ideas", "diagram.png", "notes/plan"]);
+        assert!(note.body.starts_with("Body with"));
+    }
+
+    #[test]
+    fn daily_notes_map_to_daily_category() {
+        let note = parse_note("Journal/2026-03-14.md", "# Pi day\nBaked a pie.");
+        assert_eq!(note.category, MemoryCategory::Daily);
+        assert_eq!(note.title, "Pi day");
+
+        let note = parse_note("Plain.md", "No frontmatter here.");
+        assert_eq!(note.category, MemoryCategory::Core);
+        assert_eq!(note.title, "Plain");
+    }
+
+    #[tokio::test]
+    async fn indexes_vault_and_skips_dot_directories() {
+        let fx = fixture(1, noop());
+        assert_eq!(fx.memory.count().await.unwrap(), 3);
+        let entry = fx.memory.get("Projects/Compiler").await.unwrap().unwrap();
+        assert!(entry.content.contains("borrow checker"));
+        assert_eq!(fx.memory.get("ideas").await.unwrap().unwrap().key, "Ideas");
+    }
+
+    #[tokio::test]
+    async fn recall_expands_through_wikilinks() {
+        let fx = fixture(1, noop());
+        let results = fx.memory.recall("borrow checker", 5, None).await.unwrap();
+        let keys: Vec<&str> = results.iter().map(|e| e.key.as_str()).collect();
+        assert_eq!(keys, vec!["Projects/Compiler", "Ideas"]);
+        assert!(results[1].score.unwrap() < results[0].score.unwrap());
+
+        let fx = fixture(0, noop());
+        let results = fx.memory.recall("borrow checker", 5, None).await.unwrap();
+        assert_eq!(results.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn recall_follows_backlinks() {
+        let fx = fixture(1, noop());
+        let results = fx.memory.recall("arenas", 5, None).await.unwrap();
+        let keys: Vec<&str> = results.iter().map(|e| e.key.as_str()).collect();
+        assert_eq!(keys, vec!["Ideas", "Projects/Compiler"]);
+    }
+
+    #[tokio::test]
+    async fn sync_picks_up_external_edits_and_deletions() {
+        let fx = fixture(0, noop());
+        std::thread::sleep(Duration::from_millis(20));
+        write_note(fx.vault.path(), "Unrelated.md", "Tomatoes and basil.\n");
+        write_note(fx.vault.path(), "New.md", "Fresh basil pesto.\n");
+        std::fs::remove_file(fx.vault.path().join("Ideas.md")).unwrap();
+
+        assert_eq!(fx.memory.sync().await.unwrap(), 3);
+        let keys: Vec<String> = fx
+            .memory
+            .recall("basil", 5, None)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| e.key)
+            .collect();
+        assert_eq!(keys.len(), 2);
+        assert!(keys.contains(&"New".to_string()));
+        assert!(fx.memory.get("Ideas").await.unwrap().is_none());
+        assert_eq!(fx.memory.sync().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn only_changed_notes_are_re_embedded() {
+        let embedder = Arc::new(KeywordEmbedding {
+            embedded: AtomicUsize::new(0),
+        });
+        let fx = fixture(0, embedder.clone());
+        fx.memory.sync().await.unwrap();
+        assert_eq!(embedder.embedded.load(Ordering::SeqCst), 3);
+
+        std::thread::sleep(Duration::from_millis(20));
+        write_note(fx.vault.path(), "Unrelated.md", "Tomatoes in the garden.\n");
+        // Touched but unchanged content keeps its embedding.
+        write_note(
+            fx.vault.path(),
+            "Ideas.md",
+            "Use arenas for the AST. #design\n",
+        );
+        fx.memory.sync().await.unwrap();
+        assert_eq!(embedder.embedded.load(Ordering::SeqCst), 4);
+
+        let results = fx.memory.recall("garden", 1, None).await.unwrap();
+        assert_eq!(results[0].key, "Unrelated");
+    }
+
+    #[tokio::test]
+    async fn store_writes_notes_into_the_vault() {
+        let fx = fixture(1, noop());
+        fx.memory
+            .store("user_lang", "Prefers Rust", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        let stored = fx.vault.path().join("ZeroClaw/user_lang.md");
+        assert!(std::fs::read_to_string(&stored)
+            .unwrap()
+            .contains("Prefers Rust"));
+
+        let entry = fx.memory.get("user_lang").await.unwrap().unwrap();
+        assert_eq!(entry.key, "user_lang");
+        assert_eq!(entry.category, MemoryCategory::Core);
+
+        fx.memory
+            .store("user_lang", "Prefers Zig", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        assert_eq!(fx.memory.count().await.unwrap(), 4);
+        assert!(fx
+            .memory
+            .get("user_lang")
+            .await
+            .unwrap()
+            .unwrap()
+            .content
+            .contains("Zig"));
+
+        fx.memory
+            .store("standup", "Shipped the parser", MemoryCategory::Daily, None)
+            .await
+            .unwrap();
+        let daily = fx
+            .memory
+            .list(Some(&MemoryCategory::Daily), None)
+            .await
+            .unwrap();
+        assert_eq!(daily.len(), 1);
+        assert!(daily[0].content.contains("**standup**: Shipped the parser"));
+
+        assert!(fx.memory.forget("user_lang").await.unwrap());
+        assert!(!stored.exists());
+        // User notes outside the inbox folder are never deleted.
+        assert!(!fx.memory.forget("Ideas").await.unwrap());
+        assert!(fx.vault.path().join("Ideas.md").exists());
+    }
+
+    #[tokio::test]
+    async fn store_without_inbox_folder_keeps_user_notes() {
+        let workspace = TempDir::new().unwrap();
+        let vault = TempDir::new().unwrap();
+        write_note(vault.path(), "Ideas.md", "Use arenas for the AST.\n");
+        let config = ObsidianMemoryConfig {
+            vault_path: Some(vault.path().display().to_string()),
+            watch_interval_secs: 0,
+            inbox_folder: String::new(),
+            ..ObsidianMemoryConfig::default()
+        };
+        let memory = ObsidianMemory::new(workspace.path(), &config, noop(), 0.7, 0.3).unwrap();
+        memory.sync().await.unwrap();
+
+        memory
+            .store("Ideas", "Agent notes", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(vault.path().join("Ideas.md")).unwrap(),
+            "Use arenas for the AST.\n"
+        );
+        let stored = vault.path().join("Ideas 2.md");
+        assert!(std::fs::read_to_string(&stored)
+            .unwrap()
+            .contains("Agent notes"));
+
+        // Later stores of the same key overwrite the agent's own note.
+        memory
+            .store("Ideas", "Revised notes", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        assert!(std::fs::read_to_string(&stored)
+            .unwrap()
+            .contains("Revised notes"));
+        assert_eq!(memory.count().await.unwrap(), 2);
+    }
+
+    #[test]
+    fn missing_vault_is_rejected() {
+        let workspace = TempDir::new().unwrap();
+        let config = ObsidianMemoryConfig {
+            vault_path: Some(workspace.path().join("nope").display().to_string()),
+            ..ObsidianMemoryConfig::default()
+        };
+        let error = ObsidianMemory::new(workspace.path(), &config, noop(), 0.7, 0.3)
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("vault not found"));
+    }
+}
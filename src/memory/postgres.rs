@@ -1,9 +1,14 @@
+use super::embeddings::{EmbeddingProvider, NoopEmbedding};
 use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::vector;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use parking_lot::Mutex;
+use postgres::types::ToSql;
 use postgres::{Client, NoTls, Row};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use uuid::Uuid;
@@ -11,13 +16,33 @@ use uuid::Uuid;
 /// Maximum allowed connect timeout (seconds) to avoid unreasonable waits.
 const POSTGRES_CONNECT_TIMEOUT_CAP_SECS: u64 = 300;
 
+/// Full-text document searched by keyword recall. Must match the expression
+/// of the `idx_memories_fts` index for PostgreSQL to use it.
+const SEARCH_DOCUMENT: &str = "to_tsvector('simple', key || ' ' || content)";
+
+/// Memories embedded per provider call during [`PostgresMemory::reindex`].
+const REINDEX_BATCH_SIZE: usize = 32;
+
 /// PostgreSQL-backed persistent memory.
 ///
-/// This backend focuses on reliable CRUD and keyword recall using SQL, without
-/// requiring extension setup (for example pgvector).
+/// Recall ranks memories with `tsvector` full-text search. When an embedding
+/// provider is configured and the pgvector extension is available, memories
+/// also carry an `embedding vector(N)` column (HNSW-indexed) and recall blends
+/// both rankings with [`vector::hybrid_merge`]. Without pgvector the backend
+/// falls back to full-text search alone.
+///
+/// Stored embeddings are never dropped on startup: when the embedder's
+/// dimensions no longer match the column, recall stays full-text only until
+/// `zeroclaw memory reindex` replaces the column and re-embeds every memory.
 pub struct PostgresMemory {
-    client: Arc<Mutex<Client>>,
+    client: Arc<ClientHandle>,
     qualified_table: String,
+    embedder: Arc<dyn EmbeddingProvider>,
+    vector_weight: f32,
+    keyword_weight: f32,
+    /// Whether the table has an `embedding` column matching the embedder.
+    /// Set by [`Memory::reindex`] once it has replaced a mismatched column.
+    vector_enabled: AtomicBool,
 }
 
 impl PostgresMemory {
@@ -26,6 +51,31 @@ impl PostgresMemory {
         schema: &str,
         table: &str,
         connect_timeout_secs: Option<u64>,
+    ) -> Result<Self> {
+        Self::with_embedder(
+            db_url,
+            schema,
+            table,
+            connect_timeout_secs,
+            Arc::new(NoopEmbedding),
+            0.7,
+            0.3,
+        )
+    }
+
+    /// Build PostgreSQL memory with semantic recall.
+    ///
+    /// Embeddings are only stored when `embedder` produces vectors and the
+    /// pgvector extension is installed (or can be created); otherwise recall
+    /// uses full-text search and a warning is logged.
+    pub fn with_embedder(
+        db_url: &str,
+        schema: &str,
+        table: &str,
+        connect_timeout_secs: Option<u64>,
+        embedder: Arc<dyn EmbeddingProvider>,
+        vector_weight: f32,
+        keyword_weight: f32,
     ) -> Result<Self> {
         validate_identifier(schema, "storage schema")?;
         validate_identifier(table, "storage table")?;
@@ -34,16 +84,21 @@ impl PostgresMemory {
         let table_ident = quote_identifier(table);
         let qualified_table = format!("{schema_ident}.{table_ident}");
 
-        let client = Self::initialize_client(
+        let (client, vector_enabled) = Self::initialize_client(
             db_url.to_string(),
             connect_timeout_secs,
             schema_ident.clone(),
             qualified_table.clone(),
+            embedder.dimensions(),
         )?;
 
         Ok(Self {
-            client: Arc::new(Mutex::new(client)),
+            client: Arc::new(ClientHandle(Some(Mutex::new(client)))),
             qualified_table,
+            embedder,
+            vector_weight,
+            keyword_weight,
+            vector_enabled: AtomicBool::new(vector_enabled),
         })
     }
 
@@ -52,10 +107,11 @@ impl PostgresMemory {
         connect_timeout_secs: Option<u64>,
         schema_ident: String,
         qualified_table: String,
-    ) -> Result<Client> {
+        dimensions: usize,
+    ) -> Result<(Client, bool)> {
         let init_handle = std::thread::Builder::new()
             .name("postgres-memory-init".to_string())
-            .spawn(move || -> Result<Client> {
+            .spawn(move || -> Result<(Client, bool)> {
                 let mut config: postgres::Config = db_url
                     .parse()
                     .context("invalid PostgreSQL connection URL")?;
@@ -70,7 +126,22 @@ impl PostgresMemory {
                     .context("failed to connect to PostgreSQL memory backend")?;
 
                 Self::init_schema(&mut client, &schema_ident, &qualified_table)?;
-                Ok(client)
+                let vector_enabled = dimensions > 0
+                    && match Self::init_vector_column(
+                        &mut client,
+                        &qualified_table,
+                        dimensions,
+                        false,
+                    ) {
+                        Ok(matches) => matches,
+                        Err(e) => {
+                            tracing::warn!(
+                                "PostgreSQL memory recall falls back to full-text search: {e:#}"
+                            );
+                            false
+                        }
+                    };
+                Ok((client, vector_enabled))
             })
             .context("failed to spawn PostgreSQL initializer thread")?;
 
@@ -99,12 +170,182 @@ impl PostgresMemory {
             CREATE INDEX IF NOT EXISTS idx_memories_category ON {qualified_table}(category);
             CREATE INDEX IF NOT EXISTS idx_memories_session_id ON {qualified_table}(session_id);
             CREATE INDEX IF NOT EXISTS idx_memories_updated_at ON {qualified_table}(updated_at DESC);
+            CREATE INDEX IF NOT EXISTS idx_memories_fts ON {qualified_table} USING GIN ({SEARCH_DOCUMENT});
             "
         ))?;
 
         Ok(())
     }
 
+    /// Ensure pgvector is installed and the table has an `embedding` column
+    /// of the embedder's dimensions, adding the column when it is missing.
+    /// A column of other dimensions is only replaced (dropping its
+    /// embeddings) when `replace_mismatched` is set; otherwise it is left
+    /// alone and `false` is returned.
+    fn init_vector_column(
+        client: &mut Client,
+        qualified_table: &str,
+        dimensions: usize,
+        replace_mismatched: bool,
+    ) -> Result<bool> {
+        let installed: bool = client
+            .query_one(
+                "SELECT EXISTS (SELECT 1 FROM pg_extension WHERE extname = 'vector')",
+                &[],
+            )?
+            .get(0);
+        if !installed {
+            // Needs CREATE privilege on the database; an administrator may
+            // have to install the extension instead.
+            client
+                .batch_execute("CREATE EXTENSION IF NOT EXISTS vector")
+                .context("pgvector extension is not available")?;
+        }
+
+        let current: Option<String> = client
+            .query_opt(
+                "
+                SELECT format_type(atttypid, atttypmod)
+                FROM pg_attribute
+                WHERE attrelid = $1::TEXT::regclass
+                  AND attname = 'embedding'
+                  AND NOT attisdropped
+                ",
+                &[&qualified_table],
+            )?
+            .map(|row| row.get(0));
+        let wanted = format!("vector({dimensions})");
+        match current.as_deref() {
+            Some(current) if current == wanted => {}
+            Some(current) if !replace_mismatched => {
+                tracing::warn!(
+                    "PostgreSQL memory embeddings are {current} but the embedder produces \
+                     {wanted}; recall uses full-text search until `zeroclaw memory reindex` \
+                     re-embeds them"
+                );
+                return Ok(false);
+            }
+            Some(_) => client.batch_execute(&format!(
+                "
+                ALTER TABLE {qualified_table} DROP COLUMN embedding;
+                ALTER TABLE {qualified_table} ADD COLUMN embedding {wanted};
+                "
+            ))?,
+            None => client.batch_execute(&format!(
+                "ALTER TABLE {qualified_table} ADD COLUMN embedding {wanted}"
+            ))?,
+        }
+
+        // HNSW needs pgvector 0.5+ and at most 2000 dimensions; without the
+        // index similarity queries still work as an exact scan.
+        if let Err(e) = client.batch_execute(&format!(
+            "CREATE INDEX IF NOT EXISTS idx_memories_embedding \
+             ON {qualified_table} USING hnsw (embedding vector_cosine_ops)"
+        )) {
+            tracing::warn!("PostgreSQL memory embeddings are not indexed: {e}");
+        }
+
+        Ok(true)
+    }
+
+    /// Embed `text` as a pgvector literal. Failures are logged and yield
+    /// `None` so writes and recall degrade to full-text search.
+    async fn embed_text(&self, text: &str) -> Option<String> {
+        match self.embedder.embed_one(text).await {
+            Ok(embedding) => vector_literal(&embedding, self.embedder.dimensions()),
+            Err(e) => {
+                tracing::warn!("PostgreSQL memory embedding failed: {e:#}");
+                None
+            }
+        }
+    }
+
+    /// Full-text matches for any query word, ranked by `ts_rank_cd`.
+    fn keyword_search(
+        client: &mut Client,
+        qualified_table: &str,
+        query: &str,
+        session_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<(String, f32)>> {
+        // plainto_tsquery ANDs the words; OR them to match SQLite's FTS5 recall.
+        let stmt = format!(
+            "
+            SELECT id, ts_rank_cd({SEARCH_DOCUMENT}, query) AS score
+            FROM {qualified_table},
+                 to_tsquery('simple', replace(plainto_tsquery('simple', $1)::TEXT, '&', '|')) AS query
+            WHERE {SEARCH_DOCUMENT} @@ query
+              AND ($2::TEXT IS NULL OR session_id = $2)
+            ORDER BY score DESC
+            LIMIT $3
+            "
+        );
+        Ok(client
+            .query(&stmt, &[&query, &session_id, &limit])?
+            .iter()
+            .map(|row| (row.get(0), row.get(1)))
+            .collect())
+    }
+
+    /// Nearest embeddings by cosine similarity, clamped to 0–1.
+    fn vector_search(
+        client: &mut Client,
+        qualified_table: &str,
+        embedding: &str,
+        session_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<(String, f32)>> {
+        let stmt = format!(
+            "
+            SELECT id, 1 - (embedding <=> $1::TEXT::vector) AS similarity
+            FROM {qualified_table}
+            WHERE embedding IS NOT NULL
+              AND ($2::TEXT IS NULL OR session_id = $2)
+            ORDER BY embedding <=> $1::TEXT::vector
+            LIMIT $3
+            "
+        );
+        Ok(client
+            .query(&stmt, &[&embedding, &session_id, &limit])?
+            .iter()
+            .map(|row| {
+                #[allow(clippy::cast_possible_truncation)]
+                let similarity = row.get::<_, f64>(1).clamp(0.0, 1.0) as f32;
+                (row.get(0), similarity)
+            })
+            .collect())
+    }
+
+    /// Substring match on key and content; an empty query lists the most
+    /// recent memories.
+    fn substring_search(
+        client: &mut Client,
+        qualified_table: &str,
+        query: &str,
+        session_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<MemoryEntry>> {
+        let stmt = format!(
+            "
+            SELECT id, key, content, category, created_at, session_id,
+                   (
+                     CASE WHEN key ILIKE '%' || $1 || '%' THEN 2.0 ELSE 0.0 END +
+                     CASE WHEN content ILIKE '%' || $1 || '%' THEN 1.0 ELSE 0.0 END
+                   ) AS score
+            FROM {qualified_table}
+            WHERE ($2::TEXT IS NULL OR session_id = $2)
+              AND ($1 = '' OR key ILIKE '%' || $1 || '%' OR content ILIKE '%' || $1 || '%')
+            ORDER BY score DESC, updated_at DESC
+            LIMIT $3
+            "
+        );
+
+        let rows = client.query(&stmt, &[&query, &session_id, &limit])?;
+        rows.iter()
+            .map(Self::row_to_entry)
+            .collect::<Result<Vec<MemoryEntry>>>()
+    }
+
     fn category_to_str(category: &MemoryCategory) -> String {
         match category {
             MemoryCategory::Core => "core".to_string(),
@@ -138,6 +379,28 @@ impl PostgresMemory {
     }
 }
 
+/// The synchronous client, closed on a plain thread when dropped: closing
+/// blocks on the client's own runtime, which panics inside an async context.
+struct ClientHandle(Option<Mutex<Client>>);
+
+impl std::ops::Deref for ClientHandle {
+    type Target = Mutex<Client>;
+
+    fn deref(&self) -> &Mutex<Client> {
+        self.0.as_ref().expect("PostgreSQL client is open until dropped")
+    }
+}
+
+impl Drop for ClientHandle {
+    fn drop(&mut self) {
+        if let Some(client) = self.0.take() {
+            if tokio::runtime::Handle::try_current().is_ok() {
+                let _ = std::thread::spawn(move || drop(client)).join();
+            }
+        }
+    }
+}
+
 fn validate_identifier(value: &str, field_name: &str) -> Result<()> {
     if value.is_empty() {
         anyhow::bail!("{field_name} must not be empty");
@@ -165,6 +428,19 @@ fn quote_identifier(value: &str) -> String {
     format!("\"{value}\"")
 }
 
+/// Format an embedding as a pgvector literal (`[0.1,0.2,...]`). Vectors of
+/// the wrong dimensions or with non-finite values yield `None`.
+fn vector_literal(embedding: &[f32], dimensions: usize) -> Option<String> {
+    if embedding.is_empty()
+        || embedding.len() != dimensions
+        || !embedding.iter().all(|v| v.is_finite())
+    {
+        return None;
+    }
+    let values: Vec<String> = embedding.iter().map(f32::to_string).collect();
+    Some(format!("[{}]", values.join(",")))
+}
+
 #[async_trait]
 impl Memory for PostgresMemory {
     fn name(&self) -> &str {
@@ -178,6 +454,13 @@ impl Memory for PostgresMemory {
         category: MemoryCategory,
         session_id: Option<&str>,
     ) -> Result<()> {
+        let vector_enabled = self.vector_enabled.load(Ordering::Relaxed);
+        let embedding = if vector_enabled {
+            self.embed_text(content).await
+        } else {
+            None
+        };
+
         let client = self.client.clone();
         let qualified_table = self.qualified_table.clone();
         let key = key.to_string();
@@ -188,22 +471,36 @@ impl Memory for PostgresMemory {
         tokio::task::spawn_blocking(move || -> Result<()> {
             let now = Utc::now();
             let mut client = client.lock();
+            let (embedding_column, embedding_value, embedding_update) = if vector_enabled {
+                (
+                    ", embedding",
+                    ", $8::TEXT::vector",
+                    ",\n                    embedding = EXCLUDED.embedding",
+                )
+            } else {
+                ("", "", "")
+            };
             let stmt = format!(
                 "
                 INSERT INTO {qualified_table}
-                    (id, key, content, category, created_at, updated_at, session_id)
+                    (id, key, content, category, created_at, updated_at, session_id{embedding_column})
                 VALUES
-                    ($1, $2, $3, $4, $5, $6, $7)
+                    ($1, $2, $3, $4, $5, $6, $7{embedding_value})
                 ON CONFLICT (key) DO UPDATE SET
                     content = EXCLUDED.content,
                     category = EXCLUDED.category,
                     updated_at = EXCLUDED.updated_at,
-                    session_id = EXCLUDED.session_id
+                    session_id = EXCLUDED.session_id{embedding_update}
                 "
             );
 
             let id = Uuid::new_v4().to_string();
-            client.execute(&stmt, &[&id, &key, &content, &category, &now, &now, &sid])?;
+            let mut params: Vec<&(dyn ToSql + Sync)> =
+                vec![&id, &key, &content, &category, &now, &now, &sid];
+            if vector_enabled {
+                params.push(&embedding);
+            }
+            client.execute(&stmt, &params)?;
             Ok(())
         })
         .await?
@@ -215,35 +512,108 @@ impl Memory for PostgresMemory {
         limit: usize,
         session_id: Option<&str>,
     ) -> Result<Vec<MemoryEntry>> {
+        let query = query.trim().to_string();
+        let query_embedding = if self.vector_enabled.load(Ordering::Relaxed) && !query.is_empty() {
+            self.embed_text(&query).await
+        } else {
+            None
+        };
+
         let client = self.client.clone();
         let qualified_table = self.qualified_table.clone();
-        let query = query.trim().to_string();
         let sid = session_id.map(str::to_string);
+        let vector_weight = self.vector_weight;
+        let keyword_weight = self.keyword_weight;
 
         tokio::task::spawn_blocking(move || -> Result<Vec<MemoryEntry>> {
             let mut client = client.lock();
+            let session_ref = sid.as_deref();
+
+            #[allow(clippy::cast_possible_wrap)]
+            let limit_i64 = limit as i64;
+            if query.is_empty() {
+                return Self::substring_search(
+                    &mut client,
+                    &qualified_table,
+                    &query,
+                    session_ref,
+                    limit_i64,
+                );
+            }
+
+            let keyword_results = Self::keyword_search(
+                &mut client,
+                &qualified_table,
+                &query,
+                session_ref,
+                limit_i64 * 2,
+            )?;
+            let vector_results = match &query_embedding {
+                Some(embedding) => Self::vector_search(
+                    &mut client,
+                    &qualified_table,
+                    embedding,
+                    session_ref,
+                    limit_i64 * 2,
+                )?,
+                None => Vec::new(),
+            };
+
+            let merged = if vector_results.is_empty() {
+                keyword_results
+                    .into_iter()
+                    .take(limit)
+                    .map(|(id, score)| vector::ScoredResult {
+                        id,
+                        vector_score: None,
+                        keyword_score: Some(score),
+                        final_score: score,
+                    })
+                    .collect::<Vec<_>>()
+            } else {
+                vector::hybrid_merge(
+                    &vector_results,
+                    &keyword_results,
+                    vector_weight,
+                    keyword_weight,
+                    limit,
+                )
+            };
+
+            // Words too short or unusual for the full-text parser still
+            // match as substrings.
+            if merged.is_empty() {
+                return Self::substring_search(
+                    &mut client,
+                    &qualified_table,
+                    &query,
+                    session_ref,
+                    limit_i64,
+                );
+            }
+
+            let ids: Vec<&str> = merged.iter().map(|scored| scored.id.as_str()).collect();
             let stmt = format!(
                 "
-                SELECT id, key, content, category, created_at, session_id,
-                       (
-                         CASE WHEN key ILIKE '%' || $1 || '%' THEN 2.0 ELSE 0.0 END +
-                         CASE WHEN content ILIKE '%' || $1 || '%' THEN 1.0 ELSE 0.0 END
-                       ) AS score
+                SELECT id, key, content, category, created_at, session_id
                 FROM {qualified_table}
-                WHERE ($2::TEXT IS NULL OR session_id = $2)
-                  AND ($1 = '' OR key ILIKE '%' || $1 || '%' OR content ILIKE '%' || $1 || '%')
-                ORDER BY score DESC, updated_at DESC
-                LIMIT $3
+                WHERE id = ANY($1)
                 "
             );
+            let mut entries = client
+                .query(&stmt, &[&ids])?
+                .iter()
+                .map(|row| Self::row_to_entry(row).map(|entry| (entry.id.clone(), entry)))
+                .collect::<Result<HashMap<String, MemoryEntry>>>()?;
 
-            #[allow(clippy::cast_possible_wrap)]
-            let limit_i64 = limit as i64;
-
-            let rows = client.query(&stmt, &[&query, &sid, &limit_i64])?;
-            rows.iter()
-                .map(Self::row_to_entry)
-                .collect::<Result<Vec<MemoryEntry>>>()
+            Ok(merged
+                .iter()
+                .filter_map(|scored| {
+                    let mut entry = entries.remove(&scored.id)?;
+                    entry.score = Some(f64::from(scored.final_score));
+                    Some(entry)
+                })
+                .collect())
         })
         .await?
     }
@@ -337,6 +707,85 @@ impl Memory for PostgresMemory {
             .await
             .unwrap_or(false)
     }
+
+    /// Backfill embeddings for memories stored without one (for example
+    /// after pgvector was installed), then rebuild the table's indexes. An
+    /// `embedding` column of other dimensions than the embedder's is
+    /// replaced first and every memory is re-embedded.
+    async fn reindex(&self) -> Result<usize> {
+        let dimensions = self.embedder.dimensions();
+        if dimensions > 0 {
+            let client = self.client.clone();
+            let qualified_table = self.qualified_table.clone();
+            let enabled = tokio::task::spawn_blocking(move || {
+                Self::init_vector_column(&mut client.lock(), &qualified_table, dimensions, true)
+            })
+            .await?
+            .unwrap_or_else(|e| {
+                tracing::warn!("PostgreSQL memory reindex skips embeddings: {e:#}");
+                false
+            });
+            self.vector_enabled.store(enabled, Ordering::Relaxed);
+        }
+
+        let mut count = 0;
+        if self.vector_enabled.load(Ordering::Relaxed) {
+            let client = self.client.clone();
+            let qualified_table = self.qualified_table.clone();
+            let missing = tokio::task::spawn_blocking(move || -> Result<Vec<(String, String)>> {
+                let mut client = client.lock();
+                let stmt =
+                    format!("SELECT id, content FROM {qualified_table} WHERE embedding IS NULL");
+                Ok(client
+                    .query(&stmt, &[])?
+                    .iter()
+                    .map(|row| (row.get(0), row.get(1)))
+                    .collect())
+            })
+            .await??;
+
+            for batch in missing.chunks(REINDEX_BATCH_SIZE) {
+                let texts: Vec<&str> = batch.iter().map(|(_, content)| content.as_str()).collect();
+                let embeddings = self.embedder.embed(&texts).await?;
+                let updates: Vec<(String, String)> = batch
+                    .iter()
+                    .zip(&embeddings)
+                    .filter_map(|((id, _), embedding)| {
+                        vector_literal(embedding, self.embedder.dimensions())
+                            .map(|literal| (id.clone(), literal))
+                    })
+                    .collect();
+
+                let client = self.client.clone();
+                let qualified_table = self.qualified_table.clone();
+                count += tokio::task::spawn_blocking(move || -> Result<usize> {
+                    let mut client = client.lock();
+                    let mut tx = client.transaction()?;
+                    let stmt = format!(
+                        "UPDATE {qualified_table} SET embedding = $2::TEXT::vector WHERE id = $1"
+                    );
+                    for (id, literal) in &updates {
+                        tx.execute(&stmt, &[id, literal])?;
+                    }
+                    tx.commit()?;
+                    Ok(updates.len())
+                })
+                .await??;
+            }
+        }
+
+        let client = self.client.clone();
+        let qualified_table = self.qualified_table.clone();
+        tokio::task::spawn_blocking(move || -> Result<()> {
+            client
+                .lock()
+                .batch_execute(&format!("REINDEX TABLE {qualified_table}"))?;
+            Ok(())
+        })
+        .await??;
+
+        Ok(count)
+    }
 }
 
 #[cfg(test)]
@@ -373,6 +822,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn vector_literal_formats_pgvector_input() {
+        assert_eq!(
+            vector_literal(&[0.5, -1.0, 2.25], 3).as_deref(),
+            Some("[0.5,-1,2.25]")
+        );
+    }
+
+    #[test]
+    fn vector_literal_rejects_mismatched_or_non_finite_vectors() {
+        assert!(vector_literal(&[0.5, 1.0], 3).is_none());
+        assert!(vector_literal(&[], 0).is_none());
+        assert!(vector_literal(&[0.5, f32::NAN, 1.0], 3).is_none());
+        assert!(vector_literal(&[f32::INFINITY], 1).is_none());
+    }
+
     #[tokio::test(flavor = "current_thread")]
     async fn new_does_not_panic_inside_tokio_runtime() {
         let outcome = std::panic::catch_unwind(|| {
@@ -390,4 +855,215 @@ mod tests {
             "PostgresMemory::new should return a connect error for an unreachable endpoint"
         );
     }
+
+    // ── Integration tests ────────────────────────────────────────
+    // Run against a live server with
+    // `ZEROCLAW_TEST_POSTGRES_URL=postgres://... cargo test --features memory-postgres -- --ignored memory::postgres`.
+    // Each test works in its own schema and drops it afterwards.
+
+    /// Embeds text by which topics it mentions, so synonyms land together.
+    struct TopicEmbedding(usize);
+
+    const TOPICS: [[&str; 2]; 4] = [
+        ["rust", "cargo"],
+        ["garden", "tomatoes"],
+        ["coffee", "espresso"],
+        ["music", "guitar"],
+    ];
+
+    #[async_trait]
+    impl EmbeddingProvider for TopicEmbedding {
+        fn name(&self) -> &str {
+            "topic"
+        }
+
+        fn dimensions(&self) -> usize {
+            self.0
+        }
+
+        async fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    TOPICS[..self.0]
+                        .iter()
+                        .map(|words| {
+                            if words.iter().any(|word| text.contains(word)) {
+                                1.0
+                            } else {
+                                0.01
+                            }
+                        })
+                        .collect()
+                })
+                .collect())
+        }
+    }
+
+    struct TestSchema {
+        url: String,
+        name: String,
+    }
+
+    impl TestSchema {
+        fn new() -> Self {
+            Self {
+                url: std::env::var("ZEROCLAW_TEST_POSTGRES_URL")
+                    .expect("set ZEROCLAW_TEST_POSTGRES_URL to run PostgreSQL memory tests"),
+                name: format!("zc_test_{}", Uuid::new_v4().simple()),
+            }
+        }
+
+        fn open(&self, dimensions: usize) -> PostgresMemory {
+            PostgresMemory::with_embedder(
+                &self.url,
+                &self.name,
+                "memories",
+                Some(5),
+                Arc::new(TopicEmbedding(dimensions)),
+                0.7,
+                0.3,
+            )
+            .unwrap()
+        }
+
+        /// Run `sql` on a fresh connection and return its first column.
+        fn scalar<T>(&self, sql: &str) -> T
+        where
+            T: for<'a> postgres::types::FromSql<'a> + Send + 'static,
+        {
+            let (url, sql) = (self.url.clone(), sql.to_string());
+            std::thread::spawn(move || {
+                let mut client = Client::connect(&url, NoTls).unwrap();
+                client.query_one(&sql, &[]).unwrap().get(0)
+            })
+            .join()
+            .unwrap()
+        }
+
+        fn pgvector_available(&self) -> bool {
+            self.scalar(
+                "SELECT EXISTS (SELECT 1 FROM pg_available_extensions WHERE name = 'vector')",
+            )
+        }
+    }
+
+    impl Drop for TestSchema {
+        fn drop(&mut self) {
+            let (url, name) = (self.url.clone(), self.name.clone());
+            let _ = std::thread::spawn(move || {
+                if let Ok(mut client) = Client::connect(&url, NoTls) {
+                    let _ = client.batch_execute(&format!("DROP SCHEMA IF EXISTS {name} CASCADE"));
+                }
+            })
+            .join();
+        }
+    }
+
+    async fn store_topics(mem: &PostgresMemory) {
+        for (key, content) in [
+            ("lang", "writes rust daily"),
+            ("hobby", "tends the garden"),
+            ("drink", "black coffee, no sugar"),
+            ("mixed", "rust notes over coffee"),
+        ] {
+            mem.store(key, content, MemoryCategory::Core, None)
+                .await
+                .unwrap();
+        }
+    }
+
+    fn keys(entries: &[MemoryEntry]) -> Vec<&str> {
+        entries.iter().map(|entry| entry.key.as_str()).collect()
+    }
+
+    #[tokio::test]
+    #[ignore = "requires PostgreSQL with pgvector (ZEROCLAW_TEST_POSTGRES_URL)"]
+    async fn hybrid_recall_blends_vector_and_full_text_ranking() {
+        let schema = TestSchema::new();
+        if !schema.pgvector_available() {
+            eprintln!("skipped: pgvector is not installed on the test server");
+            return;
+        }
+        let mem = schema.open(3);
+        assert!(mem.vector_enabled.load(Ordering::Relaxed));
+        store_topics(&mem).await;
+
+        // Both mention coffee; the memory only about coffee is closer.
+        let results = mem.recall("coffee", 2, None).await.unwrap();
+        assert_eq!(keys(&results), ["drink", "mixed"]);
+        assert!(results[0].score.unwrap() > results[1].score.unwrap());
+
+        // No memory contains the word; only the embedding links it.
+        let results = mem.recall("espresso", 1, None).await.unwrap();
+        assert_eq!(keys(&results), ["drink"]);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires PostgreSQL without pgvector (ZEROCLAW_TEST_POSTGRES_URL)"]
+    async fn recall_falls_back_to_full_text_without_pgvector() {
+        let schema = TestSchema::new();
+        if schema.pgvector_available() {
+            eprintln!("skipped: pgvector is installed on the test server");
+            return;
+        }
+        let mem = schema.open(3);
+        assert!(!mem.vector_enabled.load(Ordering::Relaxed));
+        store_topics(&mem).await;
+
+        let results = mem.recall("coffee", 5, None).await.unwrap();
+        let mut found = keys(&results);
+        found.sort_unstable();
+        assert_eq!(found, ["drink", "mixed"]);
+        assert!(mem.recall("espresso", 5, None).await.unwrap().is_empty());
+
+        assert_eq!(mem.reindex().await.unwrap(), 0);
+        assert!(!mem.vector_enabled.load(Ordering::Relaxed));
+        assert_eq!(mem.recall("garden", 5, None).await.unwrap()[0].key, "hobby");
+    }
+
+    #[tokio::test]
+    #[ignore = "requires PostgreSQL with pgvector (ZEROCLAW_TEST_POSTGRES_URL)"]
+    async fn reindex_replaces_mismatched_embeddings_only_when_asked() {
+        let schema = TestSchema::new();
+        if !schema.pgvector_available() {
+            eprintln!("skipped: pgvector is not installed on the test server");
+            return;
+        }
+        let mem = schema.open(3);
+        store_topics(&mem).await;
+        drop(mem);
+        let embedded = format!(
+            "SELECT COUNT(*) FROM {}.memories WHERE embedding IS NOT NULL",
+            schema.name
+        );
+
+        // A new embedding model leaves the stored embeddings alone.
+        let mem = schema.open(4);
+        assert!(!mem.vector_enabled.load(Ordering::Relaxed));
+        assert_eq!(schema.scalar::<i64>(&embedded), 4);
+        let results = mem.recall("coffee", 5, None).await.unwrap();
+        assert_eq!(results.len(), 2);
+
+        // Reindex replaces the column and embeds every memory again.
+        assert_eq!(mem.reindex().await.unwrap(), 4);
+        assert!(mem.vector_enabled.load(Ordering::Relaxed));
+        assert_eq!(
+            schema.scalar::<String>(&format!(
+                "SELECT format_type(atttypid, atttypmod) FROM pg_attribute \
+                 WHERE attrelid = '{}.memories'::regclass AND attname = 'embedding'",
+                schema.name
+            )),
+            "vector(4)"
+        );
+        assert_eq!(mem.reindex().await.unwrap(), 0);
+        let results = mem.recall("espresso", 1, None).await.unwrap();
+        assert_eq!(keys(&results), ["drink"]);
+
+        mem.store("band", "plays guitar", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        let results = mem.recall("music", 1, None).await.unwrap();
+        assert_eq!(keys(&results), ["band"]);
+    }
 }
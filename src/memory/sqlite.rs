@@ -1,15 +1,17 @@
 use super::embeddings::EmbeddingProvider;
+use super::hnsw::HnswIndex;
 use super::traits::{Memory, MemoryCategory, MemoryEntry};
 use super::vector;
 use anyhow::Context;
 use async_trait::async_trait;
 use chrono::Local;
-use parking_lot::Mutex;
-use rusqlite::{params, Connection};
+use parking_lot::{Mutex, MutexGuard};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
 use std::fmt::Write as _;
 use std::path::{Path, PathBuf};
 use std::sync::mpsc;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock, Weak};
 use std::thread;
 use std::time::Duration;
 use uuid::Uuid;
@@ -20,13 +22,17 @@ const SQLITE_OPEN_TIMEOUT_CAP_SECS: u64 = 300;
 /// SQLite-backed persistent memory — the brain
 ///
 /// Full-stack search engine:
-/// - **Vector DB**: embeddings stored as BLOB, cosine similarity search
+/// - **Vector DB**: embeddings stored as BLOB, searched through a persisted
+///   HNSW index (exact cosine scan for session-scoped recall)
 /// - **Keyword Search**: FTS5 virtual table with BM25 scoring
 /// - **Hybrid Merge**: weighted fusion of vector + keyword results
 /// - **Embedding Cache**: LRU-evicted cache to avoid redundant API calls
 /// - **Safe Reindex**: temp DB → seed → sync → atomic swap → rollback
 pub struct SqliteMemory {
     conn: Arc<Mutex<Connection>>,
+    /// Shared by every instance open on `db_path` (see [`shared_index`]).
+    /// Always locked after `conn`.
+    index: Arc<Mutex<HnswIndex>>,
     db_path: PathBuf,
     embedder: Arc<dyn EmbeddingProvider>,
     vector_weight: f32,
@@ -65,7 +71,7 @@ impl SqliteMemory {
             std::fs::create_dir_all(parent)?;
         }
 
-        let conn = Self::open_connection(&db_path, open_timeout_secs)?;
+        let mut conn = Self::open_connection(&db_path, open_timeout_secs)?;
 
         // ── Production-grade PRAGMA tuning ──────────────────────
         // WAL mode: concurrent reads during writes, crash-safe
@@ -82,9 +88,11 @@ impl SqliteMemory {
         )?;
 
         Self::init_schema(&conn)?;
+        let index = shared_index(&db_path, &mut conn)?;
 
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
+            index,
             db_path,
             embedder,
             vector_weight,
@@ -168,7 +176,30 @@ impl SqliteMemory {
                 created_at   TEXT NOT NULL,
                 accessed_at  TEXT NOT NULL
             );
-            CREATE INDEX IF NOT EXISTS idx_cache_accessed ON embedding_cache(accessed_at);",
+            CREATE INDEX IF NOT EXISTS idx_cache_accessed ON embedding_cache(accessed_at);
+
+            -- HNSW graph over memory embeddings (see memory::hnsw)
+            CREATE TABLE IF NOT EXISTS vector_index (
+                node      INTEGER PRIMARY KEY,
+                memory_id TEXT NOT NULL UNIQUE,
+                links     BLOB NOT NULL
+            );
+
+            -- Bumped on every change the vector index has to follow
+            CREATE TABLE IF NOT EXISTS memory_generation (
+                id    INTEGER PRIMARY KEY CHECK (id = 0),
+                value INTEGER NOT NULL
+            );
+            INSERT OR IGNORE INTO memory_generation (id, value) VALUES (0, 0);
+            CREATE TRIGGER IF NOT EXISTS memories_gen_ai AFTER INSERT ON memories BEGIN
+                UPDATE memory_generation SET value = value + 1 WHERE id = 0;
+            END;
+            CREATE TRIGGER IF NOT EXISTS memories_gen_ad AFTER DELETE ON memories BEGIN
+                UPDATE memory_generation SET value = value + 1 WHERE id = 0;
+            END;
+            CREATE TRIGGER IF NOT EXISTS memories_gen_au AFTER UPDATE OF embedding ON memories BEGIN
+                UPDATE memory_generation SET value = value + 1 WHERE id = 0;
+            END;",
         )?;
 
         // Migration: add session_id column if not present (safe to run repeatedly)
@@ -327,7 +358,9 @@ impl SqliteMemory {
         Ok(results)
     }
 
-    /// Vector similarity search: scan embeddings and compute cosine similarity.
+    /// Exact vector similarity search: scan embeddings and compute cosine
+    /// similarity. Used where the HNSW index cannot answer (scoped queries,
+    /// embeddings of another dimension) and as its reference in tests.
     ///
     /// Optional `category` and `session_id` filters reduce full-table scans
     /// when the caller already knows the scope of relevant memories.
@@ -376,25 +409,9 @@ impl SqliteMemory {
         Ok(scored)
     }
 
-    /// Safe reindex: rebuild FTS5 + embeddings with rollback on failure
-    #[allow(dead_code)]
-    pub async fn reindex(&self) -> anyhow::Result<usize> {
-        // Step 1: Rebuild FTS5
-        {
-            let conn = self.conn.clone();
-            tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
-                let conn = conn.lock();
-                conn.execute_batch("INSERT INTO memories_fts(memories_fts) VALUES('rebuild');")?;
-                Ok(())
-            })
-            .await??;
-        }
-
-        // Step 2: Re-embed all memories that lack embeddings
-        if self.embedder.dimensions() == 0 {
-            return Ok(0);
-        }
-
+    /// Embed memories stored without an embedding. Returns how many were
+    /// embedded.
+    async fn embed_missing(&self) -> anyhow::Result<usize> {
         let conn = self.conn.clone();
         let entries: Vec<(String, String)> = tokio::task::spawn_blocking(move || {
             let conn = conn.lock();
@@ -425,9 +442,65 @@ impl SqliteMemory {
                 count += 1;
             }
         }
-
         Ok(count)
     }
+
+    /// Lock the vector index, reopening it first when memories were written
+    /// without going through it.
+    fn current_index<'a>(
+        conn: &mut Connection,
+        index: &'a Mutex<HnswIndex>,
+    ) -> anyhow::Result<MutexGuard<'a, HnswIndex>> {
+        let mut index = index.lock();
+        if !index.is_current(conn)? {
+            *index = HnswIndex::open(conn)?;
+        }
+        Ok(index)
+    }
+
+    /// Run a memory write and the index update it implies in one
+    /// transaction. On failure the in-memory index is reloaded from the
+    /// database so it never runs ahead of what was committed.
+    fn write_indexed<T>(
+        conn: &mut Connection,
+        index: &Mutex<HnswIndex>,
+        write: impl FnOnce(&rusqlite::Transaction<'_>, &mut HnswIndex) -> anyhow::Result<(T, Vec<u32>)>,
+    ) -> anyhow::Result<T> {
+        let mut index = Self::current_index(conn, index)?;
+        let result = (|| {
+            let tx = conn.transaction()?;
+            let (value, changed) = write(&tx, &mut index)?;
+            index.persist(&tx, &changed)?;
+            index.mark_current(&tx)?;
+            tx.commit()?;
+            Ok(value)
+        })();
+        if result.is_err() {
+            match HnswIndex::open(conn) {
+                Ok(reloaded) => *index = reloaded,
+                Err(e) => tracing::warn!("Failed to reload memory vector index: {e:#}"),
+            }
+        }
+        result
+    }
+}
+
+/// The vector index for `db_path`, shared by every [`SqliteMemory`] open on
+/// it so that building a memory per request reuses the loaded graph. The
+/// index lives as long as some instance holds it; the next open after that
+/// loads it again from the database.
+fn shared_index(db_path: &Path, conn: &mut Connection) -> anyhow::Result<Arc<Mutex<HnswIndex>>> {
+    static INDEXES: OnceLock<Mutex<HashMap<PathBuf, Weak<Mutex<HnswIndex>>>>> = OnceLock::new();
+
+    let key = std::fs::canonicalize(db_path).unwrap_or_else(|_| db_path.to_path_buf());
+    let mut indexes = INDEXES.get_or_init(|| Mutex::new(HashMap::new())).lock();
+    if let Some(index) = indexes.get(&key).and_then(Weak::upgrade) {
+        return Ok(index);
+    }
+    let index = Arc::new(Mutex::new(HnswIndex::open(conn)?));
+    indexes.retain(|_, index| index.strong_count() > 0);
+    indexes.insert(key, Arc::downgrade(&index));
+    Ok(index)
 }
 
 #[async_trait]
@@ -444,34 +517,45 @@ impl Memory for SqliteMemory {
         session_id: Option<&str>,
     ) -> anyhow::Result<()> {
         // Compute embedding (async, before blocking work)
-        let embedding_bytes = self
-            .get_or_compute_embedding(content)
-            .await?
-            .map(|emb| vector::vec_to_bytes(&emb));
+        let embedding = self.get_or_compute_embedding(content).await?;
+        let embedding_bytes = embedding.as_deref().map(vector::vec_to_bytes);
 
         let conn = self.conn.clone();
+        let index = self.index.clone();
         let key = key.to_string();
         let content = content.to_string();
         let sid = session_id.map(String::from);
 
         tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
-            let conn = conn.lock();
+            let mut conn = conn.lock();
             let now = Local::now().to_rfc3339();
             let cat = Self::category_to_str(&category);
             let id = Uuid::new_v4().to_string();
 
-            conn.execute(
-                "INSERT INTO memories (id, key, content, category, embedding, created_at, updated_at, session_id)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
-                 ON CONFLICT(key) DO UPDATE SET
-                    content = excluded.content,
-                    category = excluded.category,
-                    embedding = excluded.embedding,
-                    updated_at = excluded.updated_at,
-                    session_id = excluded.session_id",
-                params![id, key, content, cat, embedding_bytes, now, now, sid],
-            )?;
-            Ok(())
+            Self::write_indexed(&mut conn, &index, |tx, index| {
+                tx.execute(
+                    "INSERT INTO memories (id, key, content, category, embedding, created_at, updated_at, session_id)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                     ON CONFLICT(key) DO UPDATE SET
+                        content = excluded.content,
+                        category = excluded.category,
+                        embedding = excluded.embedding,
+                        updated_at = excluded.updated_at,
+                        session_id = excluded.session_id",
+                    params![id, key, content, cat, embedding_bytes, now, now, sid],
+                )?;
+                // An upsert keeps the existing row's id.
+                let memory_id: String = tx.query_row(
+                    "SELECT id FROM memories WHERE key = ?1",
+                    params![key],
+                    |row| row.get(0),
+                )?;
+                let changed = match &embedding {
+                    Some(embedding) => index.insert(&memory_id, embedding),
+                    None => index.remove(&memory_id),
+                };
+                Ok(((), changed))
+            })
         })
         .await?
     }
@@ -490,13 +574,14 @@ impl Memory for SqliteMemory {
         let query_embedding = self.get_or_compute_embedding(query).await?;
 
         let conn = self.conn.clone();
+        let index = self.index.clone();
         let query = query.to_string();
         let sid = session_id.map(String::from);
         let vector_weight = self.vector_weight;
         let keyword_weight = self.keyword_weight;
 
         tokio::task::spawn_blocking(move || -> anyhow::Result<Vec<MemoryEntry>> {
-            let conn = conn.lock();
+            let mut conn = conn.lock();
             let session_ref = sid.as_deref();
 
             // FTS5 BM25 keyword search
@@ -504,7 +589,12 @@ impl Memory for SqliteMemory {
 
             // Vector similarity search (if embeddings available)
             let vector_results = if let Some(ref qe) = query_embedding {
-                Self::vector_search(&conn, qe, limit * 2, None, session_ref).unwrap_or_default()
+                let index = Self::current_index(&mut conn, &index)?;
+                if session_ref.is_none() && index.accepts(qe) {
+                    index.search(qe, limit * 2)
+                } else {
+                    Self::vector_search(&conn, qe, limit * 2, None, session_ref).unwrap_or_default()
+                }
             } else {
                 Vec::new()
             };
@@ -750,12 +840,25 @@ impl Memory for SqliteMemory {
 
     async fn forget(&self, key: &str) -> anyhow::Result<bool> {
         let conn = self.conn.clone();
+        let index = self.index.clone();
         let key = key.to_string();
 
         tokio::task::spawn_blocking(move || -> anyhow::Result<bool> {
-            let conn = conn.lock();
-            let affected = conn.execute("DELETE FROM memories WHERE key = ?1", params![key])?;
-            Ok(affected > 0)
+            let mut conn = conn.lock();
+            Self::write_indexed(&mut conn, &index, |tx, index| {
+                let memory_id: Option<String> = tx
+                    .query_row(
+                        "SELECT id FROM memories WHERE key = ?1",
+                        params![key],
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+                let Some(memory_id) = memory_id else {
+                    return Ok((false, Vec::new()));
+                };
+                tx.execute("DELETE FROM memories WHERE id = ?1", params![memory_id])?;
+                Ok((true, index.remove(&memory_id)))
+            })
         })
         .await?
     }
@@ -779,6 +882,40 @@ impl Memory for SqliteMemory {
             .await
             .unwrap_or(false)
     }
+
+    /// Safe reindex: rebuild FTS5 + embeddings with rollback on failure
+    async fn reindex(&self) -> anyhow::Result<usize> {
+        // Step 1: Rebuild FTS5
+        {
+            let conn = self.conn.clone();
+            tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+                let conn = conn.lock();
+                conn.execute_batch("INSERT INTO memories_fts(memories_fts) VALUES('rebuild');")?;
+                Ok(())
+            })
+            .await??;
+        }
+
+        // Step 2: Re-embed all memories that lack embeddings
+        let count = if self.embedder.dimensions() == 0 {
+            0
+        } else {
+            self.embed_missing().await?
+        };
+
+        // Step 3: Rebuild the vector index from the current embeddings
+        let conn = self.conn.clone();
+        let index = self.index.clone();
+        tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+            let mut conn = conn.lock();
+            let rebuilt = HnswIndex::rebuild(&mut conn)?;
+            *index.lock() = rebuilt;
+            Ok(())
+        })
+        .await??;
+
+        Ok(count)
+    }
 }
 
 #[cfg(test)]
@@ -1287,6 +1424,133 @@ mod tests {
         assert_eq!(results.len(), 2);
     }
 
+    // ── Vector index tests ───────────────────────────────────────
+
+    struct KeywordEmbedding;
+
+    #[async_trait]
+    impl EmbeddingProvider for KeywordEmbedding {
+        fn name(&self) -> &str {
+            "keyword"
+        }
+
+        fn dimensions(&self) -> usize {
+            3
+        }
+
+        async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    ["rust", "garden", "coffee"]
+                        .iter()
+                        .map(|word| if text.contains(word) { 1.0 } else { 0.01 })
+                        .collect()
+                })
+                .collect())
+        }
+    }
+
+    fn keyword_sqlite(tmp: &TempDir) -> SqliteMemory {
+        SqliteMemory::with_embedder(tmp.path(), Arc::new(KeywordEmbedding), 1.0, 0.0, 100, None)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn vector_index_persists_and_tracks_writes() {
+        let tmp = TempDir::new().unwrap();
+        let mem = keyword_sqlite(&tmp);
+        for (key, content) in [
+            ("lang", "writes rust daily"),
+            ("hobby", "tends the garden"),
+            ("drink", "black coffee"),
+        ] {
+            mem.store(key, content, MemoryCategory::Core, None)
+                .await
+                .unwrap();
+        }
+        assert_eq!(mem.index.lock().len(), 3);
+
+        let results = mem.recall("garden", 1, None).await.unwrap();
+        assert_eq!(results[0].key, "hobby");
+        drop(mem);
+
+        let mem = keyword_sqlite(&tmp);
+        assert_eq!(mem.index.lock().len(), 3);
+        let results = mem.recall("coffee", 1, None).await.unwrap();
+        assert_eq!(results[0].key, "drink");
+
+        // Updating content moves the node; forgetting removes it.
+        mem.store(
+            "drink",
+            "green tea in the garden",
+            MemoryCategory::Core,
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(mem.forget("hobby").await.unwrap());
+        assert_eq!(mem.index.lock().len(), 2);
+        let results = mem.recall("garden", 1, None).await.unwrap();
+        assert_eq!(results[0].key, "drink");
+
+        assert_eq!(mem.reindex().await.unwrap(), 0);
+        assert_eq!(mem.index.lock().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn vector_index_is_shared_by_memories_on_one_database() {
+        let tmp = TempDir::new().unwrap();
+        let first = keyword_sqlite(&tmp);
+        let second = keyword_sqlite(&tmp);
+        assert!(Arc::ptr_eq(&first.index, &second.index));
+
+        first
+            .store("hobby", "tends the garden", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        second
+            .store("drink", "black coffee", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        assert_eq!(first.index.lock().len(), 2);
+        let results = first.recall("coffee", 1, None).await.unwrap();
+        assert_eq!(results[0].key, "drink");
+    }
+
+    #[tokio::test]
+    async fn vector_index_reloads_after_writes_behind_its_back() {
+        let tmp = TempDir::new().unwrap();
+        let mem = keyword_sqlite(&tmp);
+        mem.store("hobby", "tends the garden", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        mem.store("lang", "writes rust daily", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        assert_eq!(mem.index.lock().len(), 2);
+
+        // Another process drops one memory and adds an embedded one.
+        let other = Connection::open(tmp.path().join("memory").join("brain.db")).unwrap();
+        other
+            .execute("DELETE FROM memories WHERE key = 'lang'", [])
+            .unwrap();
+        other
+            .execute(
+                "INSERT INTO memories (id, key, content, category, embedding, created_at, updated_at)
+                 VALUES ('m3', 'drink', 'black coffee', 'core', ?1, 'now', 'now')",
+                params![vector::vec_to_bytes(&[0.01, 0.01, 1.0])],
+            )
+            .unwrap();
+
+        let results = mem.recall("coffee", 1, None).await.unwrap();
+        assert_eq!(results[0].key, "drink");
+        let conn = mem.conn.lock();
+        let index = mem.index.lock();
+        assert_eq!(index.len(), 2);
+        assert!(index.is_current(&conn).unwrap());
+    }
+
     // ── Recall limit test ────────────────────────────────────────
 
     #[tokio::test]
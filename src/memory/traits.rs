@@ -92,6 +92,12 @@ pub trait Memory: Send + Sync {
 
     /// Health check
     async fn health_check(&self) -> bool;
+
+    /// Rebuild search indexes and embed memories stored without an
+    /// embedding. Returns how many memories were embedded.
+    async fn reindex(&self) -> anyhow::Result<usize> {
+        anyhow::bail!("the {} memory backend has no index to rebuild", self.name())
+    }
 }
 
 #[cfg(test)]
@@ -1,5 +1,5 @@
 use crate::config::{build_runtime_proxy_client_with_timeouts, MultimodalConfig};
-use crate::providers::ChatMessage;
+use crate::providers::{ChatMessage, ContentPart, MediaSource};
 use base64::{engine::general_purpose::STANDARD, Engine as _};
 use reqwest::Client;
 use std::path::Path;
@@ -85,11 +85,19 @@ pub fn parse_image_markers(content: &str) -> (String, Vec<String>) {
     (cleaned.trim().to_string(), refs)
 }
 
+/// Count image inputs in user messages: `[IMAGE:]` markers in plain messages
+/// and image parts in messages that already carry typed parts.
 pub fn count_image_markers(messages: &[ChatMessage]) -> usize {
     messages
         .iter()
         .filter(|m| m.role == "user")
-        .map(|m| parse_image_markers(&m.content).1.len())
+        .map(|m| {
+            if m.parts.is_empty() {
+                parse_image_markers(&m.content).1.len()
+            } else {
+                m.vision_part_count()
+            }
+        })
         .sum()
 }
 
@@ -144,6 +152,27 @@ pub async fn prepare_messages_for_provider(
             continue;
         }
 
+        if !message.parts.is_empty() {
+            let mut parts = Vec::with_capacity(message.parts.len());
+            for part in &message.parts {
+                parts.push(match part {
+                    ContentPart::Image { source } => {
+                        let data_uri = normalize_image_reference(
+                            &source.to_url(),
+                            config,
+                            max_bytes,
+                            &remote_client,
+                        )
+                        .await?;
+                        ContentPart::image(MediaSource::from_reference(&data_uri))
+                    }
+                    other => other.clone(),
+                });
+            }
+            normalized_messages.push(ChatMessage::with_parts(message.role.clone(), parts));
+            continue;
+        }
+
         let (cleaned_text, refs) = parse_image_markers(&message.content);
         if refs.is_empty() {
             normalized_messages.push(message.clone());
@@ -157,11 +186,10 @@ pub async fn prepare_messages_for_provider(
             normalized_refs.push(data_uri);
         }
 
-        let content = compose_multimodal_message(&cleaned_text, &normalized_refs);
-        normalized_messages.push(ChatMessage {
-            role: message.role.clone(),
-            content,
-        });
+        normalized_messages.push(ChatMessage::with_parts(
+            message.role.clone(),
+            compose_multimodal_parts(&cleaned_text, &normalized_refs),
+        ));
     }
 
     Ok(PreparedMessages {
@@ -170,25 +198,21 @@ pub async fn prepare_messages_for_provider(
     })
 }
 
-fn compose_multimodal_message(text: &str, data_uris: &[String]) -> String {
-    let mut content = String::new();
+fn compose_multimodal_parts(text: &str, data_uris: &[String]) -> Vec<ContentPart> {
+    let mut parts = Vec::with_capacity(data_uris.len() + 1);
     let trimmed = text.trim();
 
     if !trimmed.is_empty() {
-        content.push_str(trimmed);
-        content.push_str("\n\n");
+        parts.push(ContentPart::text(trimmed));
     }
 
-    for (index, data_uri) in data_uris.iter().enumerate() {
-        if index > 0 {
-            content.push('\n');
-        }
-        content.push_str(IMAGE_MARKER_PREFIX);
-        content.push_str(data_uri);
-        content.push(']');
-    }
+    parts.extend(
+        data_uris
+            .iter()
+            .map(|data_uri| ContentPart::image(MediaSource::from_reference(data_uri))),
+    );
 
-    content
+    parts
 }
 
 async fn normalize_image_reference(
@@ -495,6 +519,66 @@ mod tests {
         assert_eq!(cleaned, "Please inspect this screenshot");
         assert_eq!(refs.len(), 1);
         assert!(refs[0].starts_with("data:image/png;base64,"));
+
+        let parts = &prepared.messages[0].parts;
+        assert_eq!(parts.len(), 2);
+        assert_eq!(
+            parts[0],
+            ContentPart::text("Please inspect this screenshot")
+        );
+        assert!(matches!(
+            &parts[1],
+            ContentPart::Image {
+                source: MediaSource::Base64 { media_type, .. }
+            } if media_type == "image/png"
+        ));
+    }
+
+    #[tokio::test]
+    async fn prepare_messages_normalizes_typed_image_parts() {
+        let temp = tempfile::tempdir().unwrap();
+        let image_path = temp.path().join("sample.png");
+        std::fs::write(
+            &image_path,
+            [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'],
+        )
+        .unwrap();
+
+        let messages = vec![ChatMessage::with_parts(
+            "user",
+            vec![
+                ContentPart::text("Look"),
+                ContentPart::image(MediaSource::Url {
+                    url: image_path.display().to_string(),
+                }),
+            ],
+        )];
+
+        let prepared = prepare_messages_for_provider(&messages, &MultimodalConfig::default())
+            .await
+            .unwrap();
+
+        let parts = &prepared.messages[0].parts;
+        assert_eq!(parts[0], ContentPart::text("Look"));
+        assert!(matches!(
+            &parts[1],
+            ContentPart::Image {
+                source: MediaSource::Base64 { media_type, .. }
+            } if media_type == "image/png"
+        ));
+
+        let remote = vec![ChatMessage::with_parts(
+            "user",
+            vec![ContentPart::image(MediaSource::Url {
+                url: "https://example.com/a.png".into(),
+            })],
+        )];
+        assert!(
+            prepare_messages_for_provider(&remote, &MultimodalConfig::default())
+                .await
+                .is_err(),
+            "remote typed images follow allow_remote_fetch"
+        );
     }
 
     #[tokio::test]
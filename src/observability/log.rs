@@ -101,6 +101,20 @@ impl Observer for LogObserver {
             ObserverMetric::QueueDepth(d) => {
                 info!(depth = d, "metric.queue_depth");
             }
+            ObserverMetric::ProviderCircuit {
+                provider,
+                model,
+                state,
+                error_rate,
+            } => {
+                info!(
+                    provider = provider.as_str(),
+                    model = model.as_deref().unwrap_or("*"),
+                    state,
+                    error_rate,
+                    "metric.provider_circuit"
+                );
+            }
         }
     }
 
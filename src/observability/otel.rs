@@ -27,6 +27,8 @@ pub struct OtelObserver {
     tokens_used: Counter<u64>,
     active_sessions: Gauge<u64>,
     queue_depth: Gauge<u64>,
+    provider_circuit_state: Gauge<u64>,
+    provider_error_rate: Gauge<f64>,
 }
 
 impl OtelObserver {
@@ -152,6 +154,18 @@ impl OtelObserver {
             .with_description("Current message queue depth")
             .build();
 
+        let provider_circuit_state = meter
+            .u64_gauge("zeroclaw.provider.circuit_state")
+            .with_description(
+                "Provider circuit breaker state (0 = closed, 1 = half-open, 2 = open)",
+            )
+            .build();
+
+        let provider_error_rate = meter
+            .f64_gauge("zeroclaw.provider.error_rate")
+            .with_description("Provider failure ratio over the circuit breaker window")
+            .build();
+
         Ok(Self {
             tracer_provider,
             meter_provider: meter_provider_clone,
@@ -168,6 +182,8 @@ impl OtelObserver {
             tokens_used,
             active_sessions,
             queue_depth,
+            provider_circuit_state,
+            provider_error_rate,
         })
     }
 }
@@ -352,6 +368,24 @@ impl Observer for OtelObserver {
             ObserverMetric::QueueDepth(d) => {
                 self.queue_depth.record(*d as u64, &[]);
             }
+            ObserverMetric::ProviderCircuit {
+                provider,
+                model,
+                state,
+                error_rate,
+            } => {
+                let attrs = [
+                    KeyValue::new("provider", provider.clone()),
+                    KeyValue::new("model", model.clone().unwrap_or_else(|| "*".into())),
+                ];
+                let state_value = match *state {
+                    "open" => 2,
+                    "half_open" => 1,
+                    _ => 0,
+                };
+                self.provider_circuit_state.record(state_value, &attrs);
+                self.provider_error_rate.record(*error_rate, &attrs);
+            }
         }
     }
 
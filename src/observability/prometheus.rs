@@ -26,6 +26,8 @@ pub struct PrometheusObserver {
     tokens_used: prometheus::IntGauge,
     active_sessions: GaugeVec,
     queue_depth: GaugeVec,
+    provider_circuit_state: GaugeVec,
+    provider_error_rate: GaugeVec,
 }
 
 impl PrometheusObserver {
@@ -128,6 +130,24 @@ impl PrometheusObserver {
         )
         .expect("valid metric");
 
+        let provider_circuit_state = GaugeVec::new(
+            prometheus::Opts::new(
+                "zeroclaw_provider_circuit_state",
+                "Provider circuit breaker state (0 = closed, 1 = half-open, 2 = open)",
+            ),
+            &["provider", "model"],
+        )
+        .expect("valid metric");
+
+        let provider_error_rate = GaugeVec::new(
+            prometheus::Opts::new(
+                "zeroclaw_provider_error_rate",
+                "Provider failure ratio over the circuit breaker window",
+            ),
+            &["provider", "model"],
+        )
+        .expect("valid metric");
+
         // Register all metrics
         registry.register(Box::new(agent_starts.clone())).ok();
         registry.register(Box::new(llm_requests.clone())).ok();
@@ -145,6 +165,12 @@ impl PrometheusObserver {
         registry.register(Box::new(tokens_used.clone())).ok();
         registry.register(Box::new(active_sessions.clone())).ok();
         registry.register(Box::new(queue_depth.clone())).ok();
+        registry
+            .register(Box::new(provider_circuit_state.clone()))
+            .ok();
+        registry
+            .register(Box::new(provider_error_rate.clone()))
+            .ok();
 
         Self {
             registry,
@@ -162,6 +188,8 @@ impl PrometheusObserver {
             tokens_used,
             active_sessions,
             queue_depth,
+            provider_circuit_state,
+            provider_error_rate,
         }
     }
 
@@ -272,6 +300,25 @@ impl Observer for PrometheusObserver {
                     .with_label_values(&[] as &[&str])
                     .set(*d as f64);
             }
+            ObserverMetric::ProviderCircuit {
+                provider,
+                model,
+                state,
+                error_rate,
+            } => {
+                let model = model.as_deref().unwrap_or("*");
+                let state_value = match *state {
+                    "open" => 2.0,
+                    "half_open" => 1.0,
+                    _ => 0.0,
+                };
+                self.provider_circuit_state
+                    .with_label_values(&[provider.as_str(), model])
+                    .set(state_value);
+                self.provider_error_rate
+                    .with_label_values(&[provider.as_str(), model])
+                    .set(*error_rate);
+            }
         }
     }
 
@@ -368,6 +415,23 @@ mod tests {
         assert!(output.contains("zeroclaw_request_latency_seconds"));
     }
 
+    #[test]
+    fn provider_circuit_metric_sets_state_gauge() {
+        let obs = PrometheusObserver::new();
+        obs.record_metric(&ObserverMetric::ProviderCircuit {
+            provider: "openai".into(),
+            model: Some("gpt-4o".into()),
+            state: "open",
+            error_rate: 0.75,
+        });
+
+        let output = obs.encode();
+        assert!(output
+            .contains(r#"zeroclaw_provider_circuit_state{model="gpt-4o",provider="openai"} 2"#));
+        assert!(output
+            .contains(r#"zeroclaw_provider_error_rate{model="gpt-4o",provider="openai"} 0.75"#));
+    }
+
     #[test]
     fn counters_increment_correctly() {
         let obs = PrometheusObserver::new();
@@ -70,7 +70,8 @@ pub enum ObserverEvent {
 /// Numeric metrics emitted by the agent runtime.
 ///
 /// Observers can aggregate these into dashboards, alerts, or structured logs.
-/// Each variant carries a single scalar value with implicit units.
+/// Each variant carries a single scalar value with implicit units, plus labels
+/// where the value is per provider.
 #[derive(Debug, Clone)]
 pub enum ObserverMetric {
     /// Time elapsed for a single LLM or tool request.
@@ -81,6 +82,16 @@ pub enum ObserverMetric {
     ActiveSessions(u64),
     /// Current depth of the inbound message queue.
     QueueDepth(u64),
+    /// Circuit breaker state for a provider (`model: None`) or a
+    /// provider/model pair.
+    ProviderCircuit {
+        provider: String,
+        model: Option<String>,
+        /// `"closed"`, `"half_open"` or `"open"`.
+        state: &'static str,
+        /// Failure ratio over the rolling window (0.0–1.0).
+        error_rate: f64,
+    },
 }
 
 /// Core observability trait for recording agent runtime telemetry.
@@ -157,14 +157,18 @@ pub async fn run_wizard(force: bool) -> Result<Config> {
         http_request: crate::config::HttpRequestConfig::default(),
         multimodal: crate::config::MultimodalConfig::default(),
         web_search: crate::config::WebSearchConfig::default(),
+        github: crate::config::GitHubConfig::default(),
+        home_assistant: crate::config::HomeAssistantConfig::default(),
         proxy: crate::config::ProxyConfig::default(),
         identity: crate::config::IdentityConfig::default(),
+        security: crate::config::SecurityConfig::default(),
         cost: crate::config::CostConfig::default(),
         peripherals: crate::config::PeripheralsConfig::default(),
         agents: std::collections::HashMap::new(),
         hooks: crate::config::HooksConfig::default(),
         hardware: hardware_config,
         query_classification: crate::config::QueryClassificationConfig::default(),
+        auto_routing: crate::config::AutoRoutingConfig::default(),
         transcription: crate::config::TranscriptionConfig::default(),
         providers: std::collections::HashMap::new(),
     };
@@ -387,6 +391,7 @@ fn memory_config_defaults_for_backend(backend: &str) -> MemoryConfig {
         snapshot_on_hygiene: false,
         auto_hydrate: true,
         sqlite_open_timeout_secs: None,
+        obsidian: crate::config::ObsidianMemoryConfig::default(),
     }
 }
 
@@ -506,14 +511,18 @@ async fn run_quick_setup_with_home(
         http_request: crate::config::HttpRequestConfig::default(),
         multimodal: crate::config::MultimodalConfig::default(),
         web_search: crate::config::WebSearchConfig::default(),
+        github: crate::config::GitHubConfig::default(),
+        home_assistant: crate::config::HomeAssistantConfig::default(),
         proxy: crate::config::ProxyConfig::default(),
         identity: crate::config::IdentityConfig::default(),
+        security: crate::config::SecurityConfig::default(),
         cost: crate::config::CostConfig::default(),
         peripherals: crate::config::PeripheralsConfig::default(),
         agents: std::collections::HashMap::new(),
         hooks: crate::config::HooksConfig::default(),
         hardware: crate::config::HardwareConfig::default(),
         query_classification: crate::config::QueryClassificationConfig::default(),
+        auto_routing: crate::config::AutoRoutingConfig::default(),
         transcription: crate::config::TranscriptionConfig::default(),
         providers: std::collections::HashMap::new(),
     };
@@ -0,0 +1,677 @@
+//! WASM tool plugins — manifest format, installation, and discovery.
+//!
+//! A plugin is a WASM module plus a TOML manifest describing the tool it
+//! provides and the capabilities it needs. Installed plugins live side by
+//! side in `[runtime.wasm].tools_dir` as `<name>.wasm` and `<name>.toml`, and
+//! are registered as tools by `tools::all_tools_with_runtime`.
+//!
+//! Installing records the granted capabilities and the module's SHA-256 in
+//! `trusted_plugins.json` next to `config.toml`, outside the workspace the
+//! agent can write to. Plugins whose manifest or module no longer match that
+//! record are not loaded.
+//!
+//! ```toml
+//! name = "word_count"
+//! description = "Count the words in a piece of text"
+//! version = "0.1.0"
+//!
+//! [parameters]
+//! type = "object"
+//! required = ["text"]
+//!
+//! [parameters.properties.text]
+//! type = "string"
+//! description = "Text to count"
+//!
+//! [capabilities]
+//! read_workspace = false
+//! write_workspace = false
+//! fuel = 5000000
+//! memory_mb = 16
+//! ```
+//!
+//! See [`crate::runtime::wasm`] for the module ABI.
+
+use crate::config::{Config, WasmRuntimeConfig};
+use crate::runtime::{WasmCapabilities, WasmRuntime};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Manifest file name looked up when installing from a directory.
+pub const MANIFEST_FILE: &str = "plugin.toml";
+
+/// File, next to `config.toml`, recording what each installed plugin was
+/// granted.
+const TRUST_FILE: &str = "trusted_plugins.json";
+
+/// Parsed plugin manifest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginManifest {
+    /// Tool name exposed to the model (`[a-z][a-z0-9_]*`, at most 64 chars).
+    pub name: String,
+    /// Tool description exposed to the model.
+    pub description: String,
+    #[serde(default = "default_version")]
+    pub version: String,
+    #[serde(default)]
+    pub author: Option<String>,
+    /// JSON Schema for the tool arguments (must be an object schema).
+    #[serde(default = "default_parameters")]
+    pub parameters: serde_json::Value,
+    #[serde(default)]
+    pub capabilities: PluginCapabilities,
+}
+
+/// Capabilities a plugin requests; granted when the user confirms the install.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PluginCapabilities {
+    /// Read files inside the workspace via `zeroclaw.read_file`.
+    #[serde(default)]
+    pub read_workspace: bool,
+    /// Create or overwrite files inside the workspace via `zeroclaw.write_file`.
+    #[serde(default)]
+    pub write_workspace: bool,
+    /// Fuel budget per call (0 = `[runtime.wasm].fuel_limit`).
+    #[serde(default)]
+    pub fuel: u64,
+    /// Memory ceiling per call in MB (0 = `[runtime.wasm].memory_limit_mb`).
+    #[serde(default)]
+    pub memory_mb: u64,
+}
+
+fn default_version() -> String {
+    "0.1.0".into()
+}
+
+fn default_parameters() -> serde_json::Value {
+    serde_json::json!({ "type": "object", "properties": {} })
+}
+
+impl PluginManifest {
+    /// Parse and validate a manifest.
+    pub fn parse(text: &str) -> Result<Self> {
+        let manifest: Self = toml::from_str(text).context("Invalid plugin manifest")?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !is_valid_plugin_name(&self.name) {
+            bail!(
+                "Invalid plugin name '{}': use lowercase letters, digits and '_' (max 64 chars, starting with a letter)",
+                self.name
+            );
+        }
+        if self.description.trim().is_empty() {
+            bail!("Plugin '{}' must have a description", self.name);
+        }
+        if self
+            .parameters
+            .get("type")
+            .and_then(serde_json::Value::as_str)
+            != Some("object")
+        {
+            bail!(
+                "Plugin '{}' parameters must be a JSON Schema with type = \"object\"",
+                self.name
+            );
+        }
+        if self.capabilities.memory_mb > 4096 {
+            bail!(
+                "Plugin '{}' requests {} MB of memory; the limit is 4096",
+                self.name,
+                self.capabilities.memory_mb
+            );
+        }
+        Ok(())
+    }
+}
+
+impl PluginCapabilities {
+    /// Runtime capabilities for one invocation of the plugin.
+    pub fn to_wasm(&self) -> WasmCapabilities {
+        WasmCapabilities {
+            read_workspace: self.read_workspace,
+            write_workspace: self.write_workspace,
+            allowed_hosts: Vec::new(),
+            fuel_override: self.fuel,
+            memory_override_mb: self.memory_mb,
+        }
+    }
+
+    /// Human-readable lines for install prompts and listings.
+    pub fn describe(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if self.read_workspace {
+            lines.push("read files in the workspace".to_string());
+        }
+        if self.write_workspace {
+            lines.push("create and overwrite files in the workspace".to_string());
+        }
+        if self.fuel > 0 {
+            lines.push(format!("fuel budget of {} per call", self.fuel));
+        }
+        if self.memory_mb > 0 {
+            lines.push(format!("up to {} MB of memory per call", self.memory_mb));
+        }
+        lines
+    }
+}
+
+fn is_valid_plugin_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    name.len() <= 64
+        && chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// What the user approved when installing a plugin.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct PluginGrant {
+    /// Hex SHA-256 of the installed module.
+    module_sha256: String,
+    capabilities: PluginCapabilities,
+}
+
+/// Location of the plugin trust store for `config`.
+pub fn trust_store_path(config: &Config) -> PathBuf {
+    config
+        .config_path
+        .parent()
+        .map_or_else(|| PathBuf::from("."), PathBuf::from)
+        .join(TRUST_FILE)
+}
+
+fn read_grants(trust_path: &Path) -> Result<BTreeMap<String, PluginGrant>> {
+    match std::fs::read_to_string(trust_path) {
+        Ok(text) => serde_json::from_str(&text)
+            .with_context(|| format!("Invalid plugin trust store {}", trust_path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn write_grants(trust_path: &Path, grants: &BTreeMap<String, PluginGrant>) -> Result<()> {
+    if let Some(parent) = trust_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(trust_path, serde_json::to_string_pretty(grants)?)?;
+    Ok(())
+}
+
+fn module_sha256(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// A plugin installed in the tools directory.
+#[derive(Debug, Clone)]
+pub struct InstalledPlugin {
+    pub manifest: PluginManifest,
+    pub module_path: PathBuf,
+    pub manifest_path: PathBuf,
+}
+
+/// Load every installed plugin, skipping invalid ones with a warning.
+///
+/// Modules without a manifest are not plugins and are ignored. Plugins not
+/// recorded in the trust store at `trust_path`, or changed since they were
+/// installed, are skipped.
+pub fn load_plugins(
+    workspace_dir: &Path,
+    config: &WasmRuntimeConfig,
+    trust_path: &Path,
+) -> Vec<InstalledPlugin> {
+    let tools_dir = WasmRuntime::new(config.clone()).tools_dir(workspace_dir);
+    let Ok(entries) = std::fs::read_dir(&tools_dir) else {
+        return Vec::new();
+    };
+    let grants = match read_grants(trust_path) {
+        Ok(grants) => grants,
+        Err(e) => {
+            tracing::warn!("Skipping WASM plugins: {e:#}");
+            return Vec::new();
+        }
+    };
+
+    let mut plugins = Vec::new();
+    for entry in entries.flatten() {
+        let manifest_path = entry.path();
+        if manifest_path.extension().is_none_or(|ext| ext != "toml") {
+            continue;
+        }
+        match load_installed(&manifest_path, &grants) {
+            Ok(plugin) => plugins.push(plugin),
+            Err(e) => tracing::warn!(
+                "Skipping WASM plugin manifest {}: {e:#}",
+                manifest_path.display()
+            ),
+        }
+    }
+    plugins.sort_by(|a, b| a.manifest.name.cmp(&b.manifest.name));
+    plugins
+}
+
+fn load_installed(
+    manifest_path: &Path,
+    grants: &BTreeMap<String, PluginGrant>,
+) -> Result<InstalledPlugin> {
+    let text = std::fs::read_to_string(manifest_path)?;
+    let manifest = PluginManifest::parse(&text)?;
+    let stem = manifest_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    if stem != manifest.name {
+        bail!(
+            "manifest name '{}' does not match file name '{stem}'",
+            manifest.name
+        );
+    }
+    let module_path = manifest_path.with_extension("wasm");
+    if !module_path.is_file() {
+        bail!("module {} is missing", module_path.display());
+    }
+
+    let Some(grant) = grants.get(&manifest.name) else {
+        bail!(
+            "plugin '{}' was not installed with `zeroclaw plugins install`",
+            manifest.name
+        );
+    };
+    if grant.capabilities != manifest.capabilities {
+        bail!(
+            "plugin '{}' requests capabilities that were not granted at install time",
+            manifest.name
+        );
+    }
+    if grant.module_sha256 != module_sha256(&std::fs::read(&module_path)?) {
+        bail!("plugin '{}' module changed since install", manifest.name);
+    }
+
+    Ok(InstalledPlugin {
+        manifest,
+        module_path,
+        manifest_path: manifest_path.to_path_buf(),
+    })
+}
+
+/// Install a plugin from a directory containing `plugin.toml`, or from a
+/// manifest path. The module is the `<name>.wasm` file next to the manifest.
+///
+/// `confirm` is asked to approve the requested capabilities; returning
+/// `false` aborts without touching the tools directory. Approved
+/// capabilities and the module hash are recorded in the trust store at
+/// `trust_path`.
+pub fn install_plugin(
+    source: &Path,
+    tools_dir: &Path,
+    trust_path: &Path,
+    confirm: impl FnOnce(&PluginManifest) -> Result<bool>,
+) -> Result<Option<InstalledPlugin>> {
+    let manifest_src = if source.is_dir() {
+        source.join(MANIFEST_FILE)
+    } else {
+        source.to_path_buf()
+    };
+    let text = std::fs::read_to_string(&manifest_src)
+        .with_context(|| format!("Failed to read plugin manifest {}", manifest_src.display()))?;
+    let manifest = PluginManifest::parse(&text)?;
+
+    let module_src = manifest_src
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(format!("{}.wasm", manifest.name));
+    let module_bytes = std::fs::read(&module_src)
+        .with_context(|| format!("Failed to read plugin module {}", module_src.display()))?;
+    WasmRuntime::validate_plugin_module(&module_bytes)
+        .with_context(|| format!("{} is not a valid plugin module", module_src.display()))?;
+
+    let module_path = tools_dir.join(format!("{}.wasm", manifest.name));
+    let manifest_path = tools_dir.join(format!("{}.toml", manifest.name));
+    if module_path.exists() || manifest_path.exists() {
+        bail!(
+            "Plugin '{}' is already installed; remove it first with `zeroclaw plugins remove {}`",
+            manifest.name,
+            manifest.name
+        );
+    }
+
+    if !confirm(&manifest)? {
+        return Ok(None);
+    }
+
+    let mut grants = read_grants(trust_path)?;
+    grants.insert(
+        manifest.name.clone(),
+        PluginGrant {
+            module_sha256: module_sha256(&module_bytes),
+            capabilities: manifest.capabilities.clone(),
+        },
+    );
+    write_grants(trust_path, &grants)?;
+
+    std::fs::create_dir_all(tools_dir)?;
+    std::fs::write(&module_path, &module_bytes)?;
+    std::fs::write(&manifest_path, &text)?;
+    Ok(Some(InstalledPlugin {
+        manifest,
+        module_path,
+        manifest_path,
+    }))
+}
+
+/// Remove an installed plugin's module, manifest and trust record.
+pub fn remove_plugin(name: &str, tools_dir: &Path, trust_path: &Path) -> Result<()> {
+    if !is_valid_plugin_name(name) {
+        bail!("Invalid plugin name: {name}");
+    }
+    let module_path = tools_dir.join(format!("{name}.wasm"));
+    let manifest_path = tools_dir.join(format!("{name}.toml"));
+    if !manifest_path.exists() {
+        bail!("Plugin not found: {name}");
+    }
+    std::fs::remove_file(&manifest_path)?;
+    if module_path.exists() {
+        std::fs::remove_file(&module_path)?;
+    }
+    let mut grants = read_grants(trust_path)?;
+    if grants.remove(name).is_some() {
+        write_grants(trust_path, &grants)?;
+    }
+    Ok(())
+}
+
+/// Handle `zeroclaw plugins` subcommands.
+pub fn handle_command(command: crate::PluginCommands, config: &Config) -> Result<()> {
+    let runtime = WasmRuntime::new(config.runtime.wasm.clone());
+    runtime.validate_config()?;
+    let tools_dir = runtime.tools_dir(&config.workspace_dir);
+    let trust_path = trust_store_path(config);
+
+    match command {
+        crate::PluginCommands::List => {
+            let plugins = load_plugins(&config.workspace_dir, &config.runtime.wasm, &trust_path);
+            if plugins.is_empty() {
+                println!("No WASM plugins installed.");
+                println!();
+                println!("  Install one: zeroclaw plugins install <dir-with-plugin.toml>");
+            } else {
+                println!("Installed WASM plugins ({}):", plugins.len());
+                println!();
+                for plugin in &plugins {
+                    let manifest = &plugin.manifest;
+                    println!(
+                        "  {} {} — {}",
+                        console::style(&manifest.name).white().bold(),
+                        console::style(format!("v{}", manifest.version)).dim(),
+                        manifest.description
+                    );
+                    let caps = manifest.capabilities.describe();
+                    if !caps.is_empty() {
+                        println!("    Capabilities: {}", caps.join("; "));
+                    }
+                }
+            }
+            if !WasmRuntime::is_available() {
+                println!();
+                println!(
+                    "  {} This build cannot run plugins. Rebuild with `--features runtime-wasm`.",
+                    console::style("!").yellow().bold()
+                );
+            }
+            println!();
+            Ok(())
+        }
+        crate::PluginCommands::Install { source, yes } => {
+            println!("Installing WASM plugin from: {source}");
+            let installed =
+                install_plugin(Path::new(&source), &tools_dir, &trust_path, |manifest| {
+                    let caps = manifest.capabilities.describe();
+                    if caps.is_empty() {
+                        println!("  Requested capabilities: none (pure computation)");
+                        return Ok(true);
+                    }
+                    println!("  Plugin '{}' requests permission to:", manifest.name);
+                    for cap in &caps {
+                        println!("    - {cap}");
+                    }
+                    if yes {
+                        return Ok(true);
+                    }
+                    Ok(dialoguer::Confirm::new()
+                        .with_prompt("  Grant these capabilities and install?")
+                        .default(false)
+                        .interact()?)
+                })?;
+
+            match installed {
+                Some(plugin) => {
+                    println!(
+                        "  {} Plugin '{}' installed: {}",
+                        console::style("✓").green().bold(),
+                        plugin.manifest.name,
+                        plugin.module_path.display()
+                    );
+                    if WasmRuntime::is_available() {
+                        println!(
+                            "  Restart `zeroclaw daemon` or `zeroclaw channel start` to activate."
+                        );
+                    } else {
+                        println!(
+                            "  {} This build cannot run plugins. Rebuild with `--features runtime-wasm`.",
+                            console::style("!").yellow().bold()
+                        );
+                    }
+                }
+                None => println!("Aborted."),
+            }
+            Ok(())
+        }
+        crate::PluginCommands::Remove { name } => {
+            remove_plugin(&name, &tools_dir, &trust_path)?;
+            println!(
+                "  {} Plugin '{}' removed.",
+                console::style("✓").green().bold(),
+                name
+            );
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const MANIFEST: &str = r#"
+name = "word_count"
+description = "Count words"
+version = "1.2.0"
+
+[parameters]
+type = "object"
+required = ["text"]
+
+[parameters.properties.text]
+type = "string"
+
+[capabilities]
+read_workspace = true
+fuel = 5000
+"#;
+
+    // Smallest module exporting the plugin ABI.
+    const MODULE: &str = r#"(module
+        (memory (export "memory") 1)
+        (func (export "alloc") (param i32) (result i32) i32.const 0)
+        (func (export "call") (param i32 i32) (result i64) i64.const 0))"#;
+
+    /// Trust store kept beside, not inside, the test workspace's tools dir.
+    fn trust_path(dir: &TempDir) -> PathBuf {
+        dir.path().join("config").join(TRUST_FILE)
+    }
+
+    fn write_source(dir: &Path) {
+        std::fs::write(dir.join(MANIFEST_FILE), MANIFEST).unwrap();
+        std::fs::write(dir.join("word_count.wasm"), wat::parse_str(MODULE).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn manifest_parses_schema_and_capabilities() {
+        let manifest = PluginManifest::parse(MANIFEST).unwrap();
+        assert_eq!(manifest.name, "word_count");
+        assert_eq!(manifest.version, "1.2.0");
+        assert_eq!(manifest.parameters["required"][0], "text");
+        assert_eq!(manifest.parameters["properties"]["text"]["type"], "string");
+        assert!(manifest.capabilities.read_workspace);
+        assert!(!manifest.capabilities.write_workspace);
+
+        let caps = manifest.capabilities.to_wasm();
+        assert!(caps.read_workspace);
+        assert_eq!(caps.fuel_override, 5000);
+        assert!(caps.allowed_hosts.is_empty());
+    }
+
+    #[test]
+    fn manifest_defaults_to_empty_object_schema() {
+        let manifest = PluginManifest::parse("name = \"noop\"\ndescription = \"x\"").unwrap();
+        assert_eq!(manifest.parameters["type"], "object");
+        assert_eq!(manifest.capabilities, PluginCapabilities::default());
+        assert!(manifest.capabilities.describe().is_empty());
+    }
+
+    #[test]
+    fn manifest_rejects_bad_names_schemas_and_capabilities() {
+        assert!(PluginManifest::parse("name = \"Bad-Name\"\ndescription = \"x\"").is_err());
+        assert!(PluginManifest::parse("name = \"../x\"\ndescription = \"x\"").is_err());
+        assert!(PluginManifest::parse(
+            "name = \"x\"\ndescription = \"x\"\n[parameters]\ntype = \"string\""
+        )
+        .is_err());
+        let err = PluginManifest::parse(
+            "name = \"x\"\ndescription = \"x\"\n[capabilities]\nallowed_hosts = [\"a.com\"]",
+        )
+        .unwrap_err();
+        assert!(format!("{err:#}").contains("allowed_hosts"));
+    }
+
+    #[test]
+    fn install_list_remove_roundtrip() {
+        let source = TempDir::new().unwrap();
+        write_source(source.path());
+        let workspace = TempDir::new().unwrap();
+        let config = WasmRuntimeConfig::default();
+        let tools_dir = WasmRuntime::new(config.clone()).tools_dir(workspace.path());
+        let trust = trust_path(&workspace);
+
+        let mut prompted = Vec::new();
+        let installed = install_plugin(source.path(), &tools_dir, &trust, |manifest| {
+            prompted = manifest.capabilities.describe();
+            Ok(true)
+        })
+        .unwrap()
+        .unwrap();
+        assert_eq!(installed.manifest.name, "word_count");
+        assert_eq!(prompted[0], "read files in the workspace");
+        assert!(tools_dir.join("word_count.wasm").is_file());
+
+        let plugins = load_plugins(workspace.path(), &config, &trust);
+        assert_eq!(plugins.len(), 1);
+        assert_eq!(plugins[0].manifest.description, "Count words");
+
+        let again = install_plugin(source.path(), &tools_dir, &trust, |_| Ok(true));
+        assert!(again.unwrap_err().to_string().contains("already installed"));
+
+        remove_plugin("word_count", &tools_dir, &trust).unwrap();
+        assert!(load_plugins(workspace.path(), &config, &trust).is_empty());
+        assert!(!tools_dir.join("word_count.wasm").exists());
+        assert!(read_grants(&trust).unwrap().is_empty());
+        assert!(remove_plugin("word_count", &tools_dir, &trust).is_err());
+    }
+
+    #[test]
+    fn declined_install_leaves_tools_dir_untouched() {
+        let source = TempDir::new().unwrap();
+        write_source(source.path());
+        let state = TempDir::new().unwrap();
+        let tools_dir = state.path().join("tools/wasm");
+        let trust = trust_path(&state);
+
+        let installed = install_plugin(source.path(), &tools_dir, &trust, |_| Ok(false)).unwrap();
+        assert!(installed.is_none());
+        assert!(!tools_dir.exists());
+        assert!(!trust.exists());
+    }
+
+    #[test]
+    fn install_rejects_non_wasm_module() {
+        let source = TempDir::new().unwrap();
+        std::fs::write(source.path().join(MANIFEST_FILE), MANIFEST).unwrap();
+        std::fs::write(source.path().join("word_count.wasm"), b"not wasm").unwrap();
+        let tools_dir = source.path().join("installed");
+        let trust = source.path().join(TRUST_FILE);
+
+        assert!(install_plugin(source.path(), &tools_dir, &trust, |_| Ok(true)).is_err());
+        assert!(!tools_dir.exists());
+    }
+
+    #[test]
+    fn load_skips_modules_without_manifest_and_mismatched_names() {
+        let workspace = TempDir::new().unwrap();
+        let config = WasmRuntimeConfig::default();
+        let tools_dir = WasmRuntime::new(config.clone()).tools_dir(workspace.path());
+        std::fs::create_dir_all(&tools_dir).unwrap();
+        std::fs::write(tools_dir.join("bare.wasm"), b"\0asm").unwrap();
+        std::fs::write(tools_dir.join("other.toml"), MANIFEST).unwrap();
+        std::fs::write(tools_dir.join("other.wasm"), b"\0asm").unwrap();
+
+        assert!(load_plugins(workspace.path(), &config, &trust_path(&workspace)).is_empty());
+    }
+
+    #[test]
+    fn load_skips_plugins_changed_or_dropped_in_after_install() {
+        let source = TempDir::new().unwrap();
+        write_source(source.path());
+        let workspace = TempDir::new().unwrap();
+        let config = WasmRuntimeConfig::default();
+        let tools_dir = WasmRuntime::new(config.clone()).tools_dir(workspace.path());
+        let trust = trust_path(&workspace);
+        install_plugin(source.path(), &tools_dir, &trust, |_| Ok(true))
+            .unwrap()
+            .unwrap();
+        assert_eq!(load_plugins(workspace.path(), &config, &trust).len(), 1);
+
+        // Capabilities escalated by editing the installed manifest.
+        let manifest_path = tools_dir.join("word_count.toml");
+        std::fs::write(
+            &manifest_path,
+            MANIFEST.replace("fuel = 5000", "fuel = 5000\nwrite_workspace = true"),
+        )
+        .unwrap();
+        assert!(load_plugins(workspace.path(), &config, &trust).is_empty());
+        std::fs::write(&manifest_path, MANIFEST).unwrap();
+
+        // Module swapped after install.
+        let module = wat::parse_str(MODULE.replace("i64.const 0))", "i64.const 1))")).unwrap();
+        std::fs::write(tools_dir.join("word_count.wasm"), module).unwrap();
+        assert!(load_plugins(workspace.path(), &config, &trust).is_empty());
+
+        // A plugin written straight into the tools directory is never trusted.
+        std::fs::write(
+            tools_dir.join("dropped.toml"),
+            MANIFEST.replace("word_count", "dropped"),
+        )
+        .unwrap();
+        std::fs::write(
+            tools_dir.join("dropped.wasm"),
+            wat::parse_str(MODULE).unwrap(),
+        )
+        .unwrap();
+        assert!(load_plugins(workspace.path(), &config, &trust).is_empty());
+    }
+}
@@ -1,11 +1,14 @@
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, TokenUsage, ToolCall as ProviderToolCall,
+    ContentPart, MediaSource, Provider, ProviderCapabilities, StreamError, StreamEvent,
+    StreamResult, TokenUsage, ToolCall as ProviderToolCall,
 };
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
+use futures_util::{stream, StreamExt};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 pub struct AnthropicProvider {
     credential: Option<String>,
@@ -51,6 +54,8 @@ struct NativeChatRequest<'a> {
     temperature: f64,
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<NativeToolSpec<'a>>>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -80,11 +85,48 @@ enum NativeContentOut {
     ToolResult {
         tool_use_id: String,
         content: String,
+        #[serde(skip_serializing_if = "std::ops::Not::not")]
+        is_error: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
+    #[serde(rename = "image")]
+    Image {
+        source: NativeMediaSource,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
+    #[serde(rename = "document")]
+    Document {
+        source: NativeMediaSource,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
         cache_control: Option<CacheControl>,
     },
 }
 
+#[derive(Debug, Serialize)]
+#[serde(tag = "type")]
+enum NativeMediaSource {
+    #[serde(rename = "base64")]
+    Base64 { media_type: String, data: String },
+    #[serde(rename = "url")]
+    Url { url: String },
+}
+
+impl From<&MediaSource> for NativeMediaSource {
+    fn from(source: &MediaSource) -> Self {
+        match source {
+            MediaSource::Base64 { media_type, data } => Self::Base64 {
+                media_type: media_type.clone(),
+                data: data.clone(),
+            },
+            MediaSource::Url { url } => Self::Url { url: url.clone() },
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 struct NativeToolSpec<'a> {
     name: &'a str,
@@ -138,6 +180,21 @@ struct AnthropicUsage {
     input_tokens: Option<u64>,
     #[serde(default)]
     output_tokens: Option<u64>,
+    #[serde(default)]
+    cache_read_input_tokens: Option<u64>,
+    #[serde(default)]
+    cache_creation_input_tokens: Option<u64>,
+}
+
+impl From<AnthropicUsage> for TokenUsage {
+    fn from(usage: AnthropicUsage) -> Self {
+        Self {
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            cache_read_tokens: usage.cache_read_input_tokens,
+            cache_write_tokens: usage.cache_creation_input_tokens,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -154,6 +211,128 @@ struct NativeContentIn {
     input: Option<serde_json::Value>,
 }
 
+#[derive(Debug, Deserialize)]
+struct StreamEventIn {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    index: Option<usize>,
+    #[serde(default)]
+    message: Option<StreamMessageStart>,
+    #[serde(default)]
+    content_block: Option<NativeContentIn>,
+    #[serde(default)]
+    delta: Option<StreamDeltaIn>,
+    #[serde(default)]
+    usage: Option<AnthropicUsage>,
+    #[serde(default)]
+    error: Option<StreamErrorIn>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamMessageStart {
+    #[serde(default)]
+    usage: Option<AnthropicUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamDeltaIn {
+    #[serde(rename = "type", default)]
+    kind: Option<String>,
+    #[serde(default)]
+    text: Option<String>,
+    #[serde(default)]
+    partial_json: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamErrorIn {
+    #[serde(default)]
+    message: String,
+}
+
+/// Turns Anthropic Messages API stream events into [`StreamEvent`]s.
+///
+/// Anthropic indexes content blocks across text and tool use, so tool
+/// blocks are renumbered to a dense tool-call index.
+#[derive(Debug, Default)]
+struct AnthropicStreamParser {
+    tool_index_by_block: HashMap<usize, usize>,
+}
+
+impl AnthropicStreamParser {
+    fn parse(&mut self, data: &str) -> StreamResult<Vec<StreamEvent>> {
+        let event: StreamEventIn = serde_json::from_str(data).map_err(StreamError::Json)?;
+        let block_index = event.index.unwrap_or_default();
+
+        let events = match event.kind.as_str() {
+            "message_start" => event
+                .message
+                .and_then(|m| m.usage)
+                .map(|u| vec![StreamEvent::Usage(u.into())])
+                .unwrap_or_default(),
+            "content_block_start" => match event.content_block {
+                Some(block) if block.kind == "tool_use" => {
+                    let index = self.tool_index_by_block.len();
+                    self.tool_index_by_block.insert(block_index, index);
+                    vec![StreamEvent::ToolCallStart {
+                        index,
+                        id: block.id.unwrap_or_default(),
+                        name: block.name.unwrap_or_default(),
+                    }]
+                }
+                Some(block) if block.kind == "text" => block
+                    .text
+                    .filter(|t| !t.is_empty())
+                    .map(|t| vec![StreamEvent::TextDelta(t)])
+                    .unwrap_or_default(),
+                _ => Vec::new(),
+            },
+            "content_block_delta" => {
+                let Some(delta) = event.delta else {
+                    return Ok(Vec::new());
+                };
+                match delta.kind.as_deref() {
+                    Some("text_delta") => delta
+                        .text
+                        .map(|t| vec![StreamEvent::TextDelta(t)])
+                        .unwrap_or_default(),
+                    Some("input_json_delta") => {
+                        match (
+                            self.tool_index_by_block.get(&block_index),
+                            delta.partial_json,
+                        ) {
+                            (Some(index), Some(arguments)) if !arguments.is_empty() => {
+                                vec![StreamEvent::ToolCallDelta {
+                                    index: *index,
+                                    arguments,
+                                }]
+                            }
+                            _ => Vec::new(),
+                        }
+                    }
+                    _ => Vec::new(),
+                }
+            }
+            "message_delta" => event
+                .usage
+                .map(|u| vec![StreamEvent::Usage(u.into())])
+                .unwrap_or_default(),
+            "message_stop" => vec![StreamEvent::Done],
+            "error" => {
+                let message = event
+                    .error
+                    .map(|e| e.message)
+                    .unwrap_or_else(|| "unknown stream error".to_string());
+                return Err(StreamError::Provider(format!("Anthropic: {message}")));
+            }
+            _ => Vec::new(),
+        };
+
+        Ok(events)
+    }
+}
+
 impl AnthropicProvider {
     pub fn new(credential: Option<&str>) -> Self {
         Self::with_base_url(credential, None)
@@ -207,7 +386,9 @@ impl AnthropicProvider {
             if let Some(last_content) = last_msg.content.last_mut() {
                 match last_content {
                     NativeContentOut::Text { cache_control, .. }
-                    | NativeContentOut::ToolResult { cache_control, .. } => {
+                    | NativeContentOut::ToolResult { cache_control, .. }
+                    | NativeContentOut::Image { cache_control, .. }
+                    | NativeContentOut::Document { cache_control, .. } => {
                         *cache_control = Some(CacheControl::ephemeral());
                     }
                     NativeContentOut::ToolUse { .. } => {}
@@ -258,8 +439,7 @@ impl AnthropicProvider {
             });
         }
         for call in tool_calls {
-            let input = serde_json::from_str::<serde_json::Value>(&call.arguments)
-                .unwrap_or_else(|_| serde_json::Value::Object(serde_json::Map::new()));
+            let input = Self::tool_input(&call.name, &call.arguments);
             blocks.push(NativeContentOut::ToolUse {
                 id: call.id,
                 name: call.name,
@@ -286,16 +466,90 @@ impl AnthropicProvider {
             content: vec![NativeContentOut::ToolResult {
                 tool_use_id,
                 content: result,
+                is_error: false,
                 cache_control: None,
             }],
         })
     }
 
+    /// Parse tool-call arguments into a `tool_use` input. Anthropic requires
+    /// an object, so malformed JSON is sent as `{}` and logged.
+    fn tool_input(name: &str, arguments: &str) -> serde_json::Value {
+        match serde_json::from_str::<serde_json::Value>(arguments) {
+            Ok(input) => input,
+            Err(e) => {
+                tracing::warn!(
+                    tool = name,
+                    "Malformed tool-call arguments replaced with {{}}: {e}"
+                );
+                serde_json::Value::Object(serde_json::Map::new())
+            }
+        }
+    }
+
+    fn convert_parts(parts: &[ContentPart]) -> Vec<NativeContentOut> {
+        parts
+            .iter()
+            .filter_map(|part| match part {
+                ContentPart::Text { text } => {
+                    let text = text.trim();
+                    (!text.is_empty()).then(|| NativeContentOut::Text {
+                        text: text.to_string(),
+                        cache_control: None,
+                    })
+                }
+                ContentPart::Image { source } => Some(NativeContentOut::Image {
+                    source: source.into(),
+                    cache_control: None,
+                }),
+                ContentPart::Document { source, name } => Some(NativeContentOut::Document {
+                    source: source.into(),
+                    title: name.clone(),
+                    cache_control: None,
+                }),
+                ContentPart::ToolUse {
+                    id,
+                    name,
+                    arguments,
+                } => Some(NativeContentOut::ToolUse {
+                    id: id.clone(),
+                    name: name.clone(),
+                    input: Self::tool_input(name, arguments),
+                    cache_control: None,
+                }),
+                ContentPart::ToolResult {
+                    tool_use_id,
+                    content,
+                    is_error,
+                } => Some(NativeContentOut::ToolResult {
+                    tool_use_id: tool_use_id.clone(),
+                    content: content.clone(),
+                    is_error: *is_error,
+                    cache_control: None,
+                }),
+            })
+            .collect()
+    }
+
     fn convert_messages(messages: &[ChatMessage]) -> (Option<SystemPrompt>, Vec<NativeMessage>) {
         let mut system_text = None;
         let mut native_messages = Vec::new();
 
         for msg in messages {
+            if msg.role != "system" && !msg.parts.is_empty() {
+                // Anthropic carries tool results in user turns.
+                let role = if msg.role == "assistant" {
+                    "assistant"
+                } else {
+                    "user"
+                };
+                native_messages.push(NativeMessage {
+                    role: role.to_string(),
+                    content: Self::convert_parts(&msg.parts),
+                });
+                continue;
+            }
+
             match msg.role.as_str() {
                 "system" => {
                     if system_text.is_none() {
@@ -359,6 +613,30 @@ impl AnthropicProvider {
         (system_prompt, native_messages)
     }
 
+    fn build_native_request<'a>(
+        request: ProviderChatRequest<'a>,
+        model: &str,
+        temperature: f64,
+        stream: bool,
+    ) -> NativeChatRequest<'a> {
+        let (system_prompt, mut messages) = Self::convert_messages(request.messages);
+
+        // Auto-cache last message if conversation is long
+        if Self::should_cache_conversation(request.messages) {
+            Self::apply_cache_to_last_message(&mut messages);
+        }
+
+        NativeChatRequest {
+            model: model.to_string(),
+            max_tokens: 4096,
+            system: system_prompt,
+            messages,
+            temperature,
+            tools: Self::convert_tools(request.tools),
+            stream,
+        }
+    }
+
     fn parse_text_response(response: ChatResponse) -> anyhow::Result<String> {
         response
             .content
@@ -372,10 +650,7 @@ impl AnthropicProvider {
         let mut text_parts = Vec::new();
         let mut tool_calls = Vec::new();
 
-        let usage = response.usage.map(|u| TokenUsage {
-            input_tokens: u.input_tokens,
-            output_tokens: u.output_tokens,
-        });
+        let usage = response.usage.map(TokenUsage::from);
 
         for block in response.content {
             match block.kind.as_str() {
@@ -412,6 +687,7 @@ impl AnthropicProvider {
             },
             tool_calls,
             usage,
+            routed_to: None,
         }
     }
 
@@ -422,6 +698,13 @@ impl AnthropicProvider {
 
 #[async_trait]
 impl Provider for AnthropicProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            native_tool_calling: true,
+            vision: true,
+        }
+    }
+
     async fn chat_with_system(
         &self,
         system_prompt: Option<&str>,
@@ -477,27 +760,44 @@ impl Provider for AnthropicProvider {
             )
         })?;
 
-        let (system_prompt, mut messages) = Self::convert_messages(request.messages);
+        let native_request = Self::build_native_request(request, model, temperature, false);
 
-        // Auto-cache last message if conversation is long
-        if Self::should_cache_conversation(request.messages) {
-            Self::apply_cache_to_last_message(&mut messages);
+        let req = self
+            .http_client()
+            .post(format!("{}/v1/messages", self.base_url))
+            .header("anthropic-version", "2023-06-01")
+            .header("content-type", "application/json")
+            .json(&native_request);
+
+        let response = self.apply_auth(req, credential).send().await?;
+        if !response.status().is_success() {
+            return Err(super::api_error("Anthropic", response).await);
         }
 
-        let native_request = NativeChatRequest {
-            model: model.to_string(),
-            max_tokens: 4096,
-            system: system_prompt,
-            messages,
-            temperature,
-            tools: Self::convert_tools(request.tools),
-        };
+        let native_response: NativeChatResponse = response.json().await?;
+        Ok(Self::parse_native_response(native_response))
+    }
+
+    async fn stream_chat(
+        &self,
+        request: ProviderChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<stream::BoxStream<'static, StreamResult<StreamEvent>>> {
+        let credential = self.credential.as_ref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Anthropic credentials not set. Set ANTHROPIC_API_KEY or ANTHROPIC_OAUTH_TOKEN (setup-token)."
+            )
+        })?;
+
+        let native_request = Self::build_native_request(request, model, temperature, true);
 
         let req = self
             .http_client()
             .post(format!("{}/v1/messages", self.base_url))
             .header("anthropic-version", "2023-06-01")
             .header("content-type", "application/json")
+            .header("accept", "text/event-stream")
             .json(&native_request);
 
         let response = self.apply_auth(req, credential).send().await?;
@@ -505,8 +805,16 @@ impl Provider for AnthropicProvider {
             return Err(super::api_error("Anthropic", response).await);
         }
 
-        let native_response: NativeChatResponse = response.json().await?;
-        Ok(Self::parse_native_response(native_response))
+        let mut parser = AnthropicStreamParser::default();
+        Ok(super::streaming::sse_data_lines(response)
+            .flat_map(move |item| {
+                let events = match item.and_then(|data| parser.parse(&data)) {
+                    Ok(events) => events.into_iter().map(Ok).collect::<Vec<_>>(),
+                    Err(error) => vec![Err(error)],
+                };
+                stream::iter(events)
+            })
+            .boxed())
     }
 
     fn supports_native_tools(&self) -> bool {
@@ -880,6 +1188,7 @@ mod tests {
         let content = NativeContentOut::ToolResult {
             tool_use_id: "tool_123".to_string(),
             content: "Result data".to_string(),
+            is_error: false,
             cache_control: Some(CacheControl::ephemeral()),
         };
         let json = serde_json::to_string(&content).unwrap();
@@ -944,14 +1253,17 @@ mod tests {
             ChatMessage {
                 role: "system".to_string(),
                 content: "System prompt".to_string(),
+                parts: Vec::new(),
             },
             ChatMessage {
                 role: "user".to_string(),
                 content: "Hello".to_string(),
+                parts: Vec::new(),
             },
             ChatMessage {
                 role: "assistant".to_string(),
                 content: "Hi".to_string(),
+                parts: Vec::new(),
             },
         ];
         // Only 2 non-system messages
@@ -963,12 +1275,14 @@ mod tests {
         let mut messages = vec![ChatMessage {
             role: "system".to_string(),
             content: "System prompt".to_string(),
+            parts: Vec::new(),
         }];
         // Add 5 non-system messages
         for i in 0..5 {
             messages.push(ChatMessage {
                 role: if i % 2 == 0 { "user" } else { "assistant" }.to_string(),
                 content: format!("Message {i}"),
+                parts: Vec::new(),
             });
         }
         assert!(AnthropicProvider::should_cache_conversation(&messages));
@@ -982,6 +1296,7 @@ mod tests {
             messages.push(ChatMessage {
                 role: if i % 2 == 0 { "user" } else { "assistant" }.to_string(),
                 content: format!("Message {i}"),
+                parts: Vec::new(),
             });
         }
         assert!(!AnthropicProvider::should_cache_conversation(&messages));
@@ -990,6 +1305,7 @@ mod tests {
         messages.push(ChatMessage {
             role: "user".to_string(),
             content: "One more".to_string(),
+            parts: Vec::new(),
         });
         assert!(AnthropicProvider::should_cache_conversation(&messages));
     }
@@ -1021,6 +1337,7 @@ mod tests {
             content: vec![NativeContentOut::ToolResult {
                 tool_use_id: "tool_123".to_string(),
                 content: "Result".to_string(),
+                is_error: false,
                 cache_control: None,
             }],
         }];
@@ -1107,6 +1424,7 @@ mod tests {
         let messages = vec![ChatMessage {
             role: "system".to_string(),
             content: "Short system prompt".to_string(),
+            parts: Vec::new(),
         }];
 
         let (system_prompt, _) = AnthropicProvider::convert_messages(&messages);
@@ -1125,6 +1443,7 @@ mod tests {
         let messages = vec![ChatMessage {
             role: "system".to_string(),
             content: large_content.clone(),
+            parts: Vec::new(),
         }];
 
         let (system_prompt, _) = AnthropicProvider::convert_messages(&messages);
@@ -1139,6 +1458,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn convert_messages_maps_typed_parts_to_native_blocks() {
+        let messages = vec![
+            ChatMessage::with_parts(
+                "user",
+                vec![
+                    ContentPart::text("What is in this picture?"),
+                    ContentPart::image(MediaSource::from_reference(
+                        "data:image/png;base64,iVBORw0KGgo=",
+                    )),
+                ],
+            ),
+            ChatMessage::assistant_tool_calls(
+                Some("Checking"),
+                &[ProviderToolCall {
+                    id: "toolu_1".to_string(),
+                    name: "shell".to_string(),
+                    arguments: r#"{"command":"ls"}"#.to_string(),
+                }],
+            ),
+            ChatMessage::tool_result("toolu_1", "file.txt"),
+        ];
+
+        let (_, native) = AnthropicProvider::convert_messages(&messages);
+        let json = serde_json::to_value(&native).unwrap();
+
+        assert_eq!(json[0]["role"], "user");
+        assert_eq!(json[0]["content"][1]["type"], "image");
+        assert_eq!(json[0]["content"][1]["source"]["type"], "base64");
+        assert_eq!(json[0]["content"][1]["source"]["media_type"], "image/png");
+        assert_eq!(json[1]["content"][1]["type"], "tool_use");
+        assert_eq!(json[1]["content"][1]["input"]["command"], "ls");
+        assert_eq!(json[2]["role"], "user");
+        assert_eq!(json[2]["content"][0]["type"], "tool_result");
+        assert_eq!(json[2]["content"][0]["tool_use_id"], "toolu_1");
+    }
+
     #[test]
     fn backward_compatibility_native_chat_request() {
         // Test that requests without cache_control serialize identically to old format
@@ -1155,13 +1511,58 @@ mod tests {
             }],
             temperature: 0.7,
             tools: None,
+            stream: false,
         };
 
         let json = serde_json::to_string(&req).unwrap();
         assert!(!json.contains("cache_control"));
+        assert!(!json.contains("stream"));
         assert!(json.contains(r#""system":"System""#));
     }
 
+    #[test]
+    fn stream_parser_maps_text_tool_and_usage_events() {
+        let mut parser = AnthropicStreamParser::default();
+        let lines = [
+            r#"{"type":"message_start","message":{"usage":{"input_tokens":25,"output_tokens":1}}}"#,
+            r#"{"type":"content_block_start","index":0,"content_block":{"type":"text","text":""}}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Checking"}}"#,
+            r#"{"type":"content_block_start","index":1,"content_block":{"type":"tool_use","id":"toolu_1","name":"shell","input":{}}}"#,
+            r#"{"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":"{\"command\":"}}"#,
+            r#"{"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":"\"ls\"}"}}"#,
+            r#"{"type":"message_delta","delta":{"stop_reason":"tool_use"},"usage":{"output_tokens":42}}"#,
+            r#"{"type":"message_stop"}"#,
+        ];
+
+        let mut accumulator = crate::providers::ChatStreamAccumulator::new();
+        let mut saw_done = false;
+        for line in lines {
+            for event in parser.parse(line).unwrap() {
+                saw_done |= event == StreamEvent::Done;
+                accumulator.push(&event);
+            }
+        }
+
+        assert!(saw_done);
+        let response = accumulator.finish();
+        assert_eq!(response.text.as_deref(), Some("Checking"));
+        assert_eq!(response.tool_calls.len(), 1);
+        assert_eq!(response.tool_calls[0].id, "toolu_1");
+        assert_eq!(response.tool_calls[0].arguments, r#"{"command":"ls"}"#);
+        let usage = response.usage.unwrap();
+        assert_eq!(usage.input_tokens, Some(25));
+        assert_eq!(usage.output_tokens, Some(42));
+    }
+
+    #[test]
+    fn stream_parser_surfaces_error_events() {
+        let mut parser = AnthropicStreamParser::default();
+        let err = parser
+            .parse(r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#)
+            .unwrap_err();
+        assert!(err.to_string().contains("Overloaded"));
+    }
+
     #[tokio::test]
     async fn warmup_without_key_is_noop() {
         let provider = AnthropicProvider::new(None);
@@ -1175,18 +1576,22 @@ mod tests {
             ChatMessage {
                 role: "system".to_string(),
                 content: "You are helpful.".to_string(),
+                parts: Vec::new(),
             },
             ChatMessage {
                 role: "user".to_string(),
                 content: "gen a 2 sum in golang".to_string(),
+                parts: Vec::new(),
             },
             ChatMessage {
                 role: "assistant".to_string(),
                 content: "```go\nfunc twoSum(nums []int) {}\n```".to_string(),
+                parts: Vec::new(),
             },
             ChatMessage {
                 role: "user".to_string(),
                 content: "what's meaning of make here?".to_string(),
+                parts: Vec::new(),
             },
         ];
 
@@ -1345,6 +1750,21 @@ mod tests {
         assert_eq!(usage.output_tokens, Some(75));
     }
 
+    #[test]
+    fn native_response_parses_cache_usage() {
+        let json = r#"{
+            "content": [{"type": "text", "text": "Hello"}],
+            "usage": {"input_tokens": 12, "output_tokens": 75, "cache_read_input_tokens": 4096, "cache_creation_input_tokens": 512}
+        }"#;
+        let resp: NativeChatResponse = serde_json::from_str(json).unwrap();
+        let usage = AnthropicProvider::parse_native_response(resp)
+            .usage
+            .unwrap();
+        assert_eq!(usage.input_tokens, Some(12));
+        assert_eq!(usage.cache_read_tokens, Some(4096));
+        assert_eq!(usage.cache_write_tokens, Some(512));
+    }
+
     #[test]
     fn native_response_parses_without_usage() {
         let json = r#"{"content": [{"type": "text", "text": "Hello"}]}"#;
@@ -374,9 +374,17 @@ struct ToolConfig {
     tools: Vec<ToolDefinition>,
 }
 
+/// Tool config entries: either `{"toolSpec": {...}}` or `{"cachePoint": {...}}`.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum ToolDefinition {
+    Spec(ToolSpecWrapper),
+    CachePoint(CachePointWrapper),
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
-struct ToolDefinition {
+struct ToolSpecWrapper {
     tool_spec: ToolSpecDef,
 }
 
@@ -414,6 +422,10 @@ struct BedrockUsage {
     input_tokens: Option<u64>,
     #[serde(default)]
     output_tokens: Option<u64>,
+    #[serde(default)]
+    cache_read_input_tokens: Option<u64>,
+    #[serde(default)]
+    cache_write_input_tokens: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -725,18 +737,24 @@ impl BedrockProvider {
         if items.is_empty() {
             return None;
         }
-        let tool_defs: Vec<ToolDefinition> = items
+        let mut tool_defs: Vec<ToolDefinition> = items
             .iter()
-            .map(|tool| ToolDefinition {
-                tool_spec: ToolSpecDef {
-                    name: tool.name.clone(),
-                    description: tool.description.clone(),
-                    input_schema: InputSchema {
-                        json: tool.parameters.clone(),
+            .map(|tool| {
+                ToolDefinition::Spec(ToolSpecWrapper {
+                    tool_spec: ToolSpecDef {
+                        name: tool.name.clone(),
+                        description: tool.description.clone(),
+                        input_schema: InputSchema {
+                            json: tool.parameters.clone(),
+                        },
                     },
-                },
+                })
             })
             .collect();
+        // Tool specs are stable across turns; a trailing cachePoint caches all of them.
+        tool_defs.push(ToolDefinition::CachePoint(CachePointWrapper {
+            cache_point: CachePoint::default_cache(),
+        }));
         Some(ToolConfig { tools: tool_defs })
     }
 
@@ -749,6 +767,8 @@ impl BedrockProvider {
         let usage = response.usage.map(|u| TokenUsage {
             input_tokens: u.input_tokens,
             output_tokens: u.output_tokens,
+            cache_read_tokens: u.cache_read_input_tokens,
+            cache_write_tokens: u.cache_write_input_tokens,
         });
 
         if let Some(output) = response.output {
@@ -784,6 +804,7 @@ impl BedrockProvider {
             },
             tool_calls,
             usage,
+            routed_to: None,
         }
     }
 
@@ -1313,6 +1334,7 @@ mod tests {
             messages.push(ChatMessage {
                 role: if i % 2 == 0 { "user" } else { "assistant" }.to_string(),
                 content: format!("Message {i}"),
+                parts: Vec::new(),
             });
         }
         assert!(BedrockProvider::should_cache_conversation(&messages));
@@ -1330,8 +1352,13 @@ mod tests {
         let config = BedrockProvider::convert_tools_to_converse(Some(&tools));
         assert!(config.is_some());
         let config = config.unwrap();
-        assert_eq!(config.tools.len(), 1);
-        assert_eq!(config.tools[0].tool_spec.name, "shell");
+        assert_eq!(config.tools.len(), 2);
+        match &config.tools[0] {
+            ToolDefinition::Spec(spec) => assert_eq!(spec.tool_spec.name, "shell"),
+            ToolDefinition::CachePoint(_) => panic!("expected tool spec first"),
+        }
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!(json["tools"][1]["cachePoint"]["type"], "default");
     }
 
     #[test]
@@ -1487,6 +1514,21 @@ mod tests {
         assert_eq!(usage.output_tokens, Some(100));
     }
 
+    #[test]
+    fn converse_response_parses_cache_usage() {
+        let json = r#"{
+            "output": {"message": {"role": "assistant", "content": [{"text": "Hello"}]}},
+            "usage": {"inputTokens": 20, "outputTokens": 5, "cacheReadInputTokens": 4000, "cacheWriteInputTokens": 300}
+        }"#;
+        let resp: ConverseResponse = serde_json::from_str(json).unwrap();
+        let usage = BedrockProvider::parse_converse_response(resp)
+            .usage
+            .unwrap();
+        assert_eq!(usage.input_tokens, Some(20));
+        assert_eq!(usage.cache_read_tokens, Some(4000));
+        assert_eq!(usage.cache_write_tokens, Some(300));
+    }
+
     #[test]
     fn converse_response_parses_without_usage() {
         let json = r#"{"output": {"message": {"role": "assistant", "content": []}}}"#;
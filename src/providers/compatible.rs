@@ -5,8 +5,8 @@
 use crate::multimodal;
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, StreamChunk, StreamError, StreamOptions, StreamResult, TokenUsage,
-    ToolCall as ProviderToolCall,
+    ContentPart, Provider, StreamChunk, StreamError, StreamEvent, StreamOptions, StreamResult,
+    TokenUsage, ToolCall as ProviderToolCall,
 };
 use async_trait::async_trait;
 use futures_util::{stream, StreamExt};
@@ -215,6 +215,11 @@ impl OpenAiCompatibleProvider {
             .collect();
 
         if let Some(first_user) = result.iter_mut().find(|m| m.role == "user") {
+            if !first_user.parts.is_empty() {
+                first_user
+                    .parts
+                    .insert(0, ContentPart::text(system_content.clone()));
+            }
             first_user.content = format!("{system_content}\n\n{}", first_user.content);
         } else {
             // No user message found: insert a synthetic user message with system content
@@ -368,6 +373,7 @@ enum MessageContent {
 enum MessagePart {
     Text { text: String },
     ImageUrl { image_url: ImageUrlPart },
+    File { file: FilePart },
 }
 
 #[derive(Debug, Serialize)]
@@ -375,6 +381,13 @@ struct ImageUrlPart {
     url: String,
 }
 
+#[derive(Debug, Serialize)]
+struct FilePart {
+    file_data: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filename: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct ApiChatResponse {
     choices: Vec<Choice>,
@@ -388,6 +401,14 @@ struct UsageInfo {
     prompt_tokens: Option<u64>,
     #[serde(default)]
     completion_tokens: Option<u64>,
+    #[serde(default)]
+    prompt_tokens_details: Option<PromptTokensDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PromptTokensDetails {
+    #[serde(default)]
+    cached_tokens: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -938,10 +959,102 @@ impl OpenAiCompatibleProvider {
         MessageContent::Parts(parts)
     }
 
+    /// Map typed parts to OpenAI-style content parts. Tool parts are carried
+    /// by the surrounding message, not the content array.
+    fn parts_to_message_content(parts: &[ContentPart]) -> MessageContent {
+        let converted = parts
+            .iter()
+            .filter_map(|part| match part {
+                ContentPart::Text { text } => Some(MessagePart::Text { text: text.clone() }),
+                ContentPart::Image { source } => Some(MessagePart::ImageUrl {
+                    image_url: ImageUrlPart {
+                        url: source.to_url(),
+                    },
+                }),
+                ContentPart::Document { source, name } => Some(MessagePart::File {
+                    file: FilePart {
+                        file_data: source.to_url(),
+                        filename: name.clone(),
+                    },
+                }),
+                ContentPart::ToolUse { .. } | ContentPart::ToolResult { .. } => None,
+            })
+            .collect::<Vec<_>>();
+
+        match converted.as_slice() {
+            [MessagePart::Text { text }] => MessageContent::Text(text.clone()),
+            _ => MessageContent::Parts(converted),
+        }
+    }
+
+    fn message_content(message: &ChatMessage) -> MessageContent {
+        if message.parts.is_empty() {
+            Self::to_message_content(&message.role, &message.content)
+        } else {
+            Self::parts_to_message_content(&message.parts)
+        }
+    }
+
+    fn convert_structured_message_for_native(message: &ChatMessage) -> NativeMessage {
+        if let Some((tool_use_id, content)) = message.parts.iter().find_map(|part| match part {
+            ContentPart::ToolResult {
+                tool_use_id,
+                content,
+                ..
+            } => Some((tool_use_id, content)),
+            _ => None,
+        }) {
+            return NativeMessage {
+                role: "tool".to_string(),
+                content: Some(MessageContent::Text(content.clone())),
+                tool_call_id: Some(tool_use_id.clone()),
+                tool_calls: None,
+            };
+        }
+
+        let tool_calls = message
+            .parts
+            .iter()
+            .filter_map(|part| match part {
+                ContentPart::ToolUse {
+                    id,
+                    name,
+                    arguments,
+                } => Some(ToolCall {
+                    id: Some(id.clone()),
+                    kind: Some("function".to_string()),
+                    function: Some(Function {
+                        name: Some(name.clone()),
+                        arguments: Some(arguments.clone()),
+                    }),
+                    name: None,
+                    arguments: None,
+                    parameters: None,
+                }),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let has_content = message
+            .parts
+            .iter()
+            .any(|part| !matches!(part, ContentPart::ToolUse { .. }));
+        NativeMessage {
+            role: message.role.clone(),
+            content: has_content.then(|| Self::parts_to_message_content(&message.parts)),
+            tool_call_id: None,
+            tool_calls: (!tool_calls.is_empty()).then_some(tool_calls),
+        }
+    }
+
     fn convert_messages_for_native(messages: &[ChatMessage]) -> Vec<NativeMessage> {
         messages
             .iter()
             .map(|message| {
+                if !message.parts.is_empty() {
+                    return Self::convert_structured_message_for_native(message);
+                }
+
                 if message.role == "assistant" {
                     if let Ok(value) = serde_json::from_str::<serde_json::Value>(&message.content)
                     {
@@ -1005,7 +1118,7 @@ impl OpenAiCompatibleProvider {
 
                 NativeMessage {
                     role: message.role.clone(),
-                    content: Some(Self::to_message_content(&message.role, &message.content)),
+                    content: Some(Self::message_content(message)),
                     tool_call_id: None,
                     tool_calls: None,
                 }
@@ -1072,6 +1185,7 @@ impl OpenAiCompatibleProvider {
             text,
             tool_calls,
             usage: None,
+            routed_to: None,
         }
     }
 
@@ -1258,7 +1372,7 @@ impl Provider for OpenAiCompatibleProvider {
             .iter()
             .map(|m| Message {
                 role: m.role.clone(),
-                content: Self::to_message_content(&m.role, &m.content),
+                content: Self::message_content(m),
             })
             .collect();
 
@@ -1364,7 +1478,7 @@ impl Provider for OpenAiCompatibleProvider {
             .iter()
             .map(|m| Message {
                 role: m.role.clone(),
-                content: Self::to_message_content(&m.role, &m.content),
+                content: Self::message_content(m),
             })
             .collect();
 
@@ -1402,6 +1516,7 @@ impl Provider for OpenAiCompatibleProvider {
                     text: Some(text),
                     tool_calls: vec![],
                     usage: None,
+                    routed_to: None,
                 });
             }
         };
@@ -1412,9 +1527,12 @@ impl Provider for OpenAiCompatibleProvider {
 
         let body = response.text().await?;
         let chat_response = parse_chat_response_body(&self.name, &body)?;
-        let usage = chat_response.usage.map(|u| TokenUsage {
-            input_tokens: u.prompt_tokens,
-            output_tokens: u.completion_tokens,
+        let usage = chat_response.usage.map(|u| {
+            TokenUsage::from_openai_counts(
+                u.prompt_tokens,
+                u.completion_tokens,
+                u.prompt_tokens_details.and_then(|d| d.cached_tokens),
+            )
         });
         let choice = chat_response
             .choices
@@ -1444,6 +1562,7 @@ impl Provider for OpenAiCompatibleProvider {
             text,
             tool_calls,
             usage,
+            routed_to: None,
         })
     }
 
@@ -1495,6 +1614,7 @@ impl Provider for OpenAiCompatibleProvider {
                             text: Some(text),
                             tool_calls: vec![],
                             usage: None,
+                            routed_to: None,
                         })
                         .map_err(|responses_err| {
                             anyhow::anyhow!(
@@ -1523,6 +1643,7 @@ impl Provider for OpenAiCompatibleProvider {
                     text: Some(text),
                     tool_calls: vec![],
                     usage: None,
+                    routed_to: None,
                 });
             }
 
@@ -1534,6 +1655,7 @@ impl Provider for OpenAiCompatibleProvider {
                         text: Some(text),
                         tool_calls: vec![],
                         usage: None,
+                        routed_to: None,
                     })
                     .map_err(|responses_err| {
                         anyhow::anyhow!(
@@ -1547,9 +1669,12 @@ impl Provider for OpenAiCompatibleProvider {
         }
 
         let native_response: ApiChatResponse = response.json().await?;
-        let usage = native_response.usage.map(|u| TokenUsage {
-            input_tokens: u.prompt_tokens,
-            output_tokens: u.completion_tokens,
+        let usage = native_response.usage.map(|u| {
+            TokenUsage::from_openai_counts(
+                u.prompt_tokens,
+                u.completion_tokens,
+                u.prompt_tokens_details.and_then(|d| d.cached_tokens),
+            )
         });
         let message = native_response
             .choices
@@ -1571,6 +1696,66 @@ impl Provider for OpenAiCompatibleProvider {
         true
     }
 
+    async fn stream_chat(
+        &self,
+        request: ProviderChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<stream::BoxStream<'static, StreamResult<StreamEvent>>> {
+        let credential = self.credential.as_ref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "{} API key not set. Run `zeroclaw onboard` or set the appropriate env var.",
+                self.name
+            )
+        })?;
+
+        let tools = Self::convert_tool_specs(request.tools);
+        let effective_messages = if self.merge_system_into_user {
+            Self::flatten_system_messages(request.messages)
+        } else {
+            request.messages.to_vec()
+        };
+        let native_request = NativeChatRequest {
+            model: model.to_string(),
+            messages: Self::convert_messages_for_native(&effective_messages),
+            temperature,
+            stream: Some(true),
+            tool_choice: tools.as_ref().map(|_| "auto".to_string()),
+            tools,
+        };
+
+        let url = self.chat_completions_url();
+        let streamed = self
+            .apply_auth_header(
+                self.http_client()
+                    .post(&url)
+                    .header("Accept", "text/event-stream")
+                    .json(&native_request),
+                credential,
+            )
+            .send()
+            .await;
+
+        match streamed {
+            Ok(response) if response.status().is_success() => {
+                Ok(super::streaming::openai_event_stream(response))
+            }
+            other => {
+                // Responses fallback, prompt-guided tool fallback and friends
+                // all live in `chat`; replay its result rather than duplicating them.
+                if let Ok(response) = other {
+                    tracing::debug!(
+                        provider = %self.name,
+                        status = %response.status(),
+                        "streaming chat rejected, falling back to non-streaming chat"
+                    );
+                }
+                let response = self.chat(request, model, temperature).await?;
+                Ok(stream::iter(StreamEvent::from_response(response).into_iter().map(Ok)).boxed())
+            }
+        }
+    }
+
     fn stream_chat_with_system(
         &self,
         system_prompt: Option<&str>,
@@ -2322,6 +2507,53 @@ mod tests {
         assert_eq!(parts[1]["image_url"]["url"], "data:image/png;base64,abcd");
     }
 
+    #[test]
+    fn message_content_maps_typed_parts_to_openai_parts() {
+        let message = ChatMessage::with_parts(
+            "user",
+            vec![
+                ContentPart::text("Summarize"),
+                ContentPart::Document {
+                    source: crate::providers::MediaSource::from_reference(
+                        "data:application/pdf;base64,JVBERi0=",
+                    ),
+                    name: Some("report.pdf".to_string()),
+                },
+            ],
+        );
+        let value =
+            serde_json::to_value(OpenAiCompatibleProvider::message_content(&message)).unwrap();
+        assert_eq!(value[0]["type"], "text");
+        assert_eq!(value[1]["type"], "file");
+        assert_eq!(
+            value[1]["file"]["file_data"],
+            "data:application/pdf;base64,JVBERi0="
+        );
+        assert_eq!(value[1]["file"]["filename"], "report.pdf");
+    }
+
+    #[test]
+    fn convert_messages_for_native_maps_structured_tool_parts() {
+        let input = vec![
+            ChatMessage::assistant_tool_calls(
+                Some("Running"),
+                &[ProviderToolCall {
+                    id: "call_9".to_string(),
+                    name: "shell".to_string(),
+                    arguments: r#"{"command":"pwd"}"#.to_string(),
+                }],
+            ),
+            ChatMessage::tool_result("call_9", "/tmp"),
+        ];
+        let converted = OpenAiCompatibleProvider::convert_messages_for_native(&input);
+        let value = serde_json::to_value(&converted).unwrap();
+        assert_eq!(value[0]["content"], "Running");
+        assert_eq!(value[0]["tool_calls"][0]["id"], "call_9");
+        assert_eq!(value[1]["role"], "tool");
+        assert_eq!(value[1]["tool_call_id"], "call_9");
+        assert_eq!(value[1]["content"], "/tmp");
+    }
+
     #[test]
     fn to_message_content_keeps_plain_text_for_non_user_roles() {
         let value = serde_json::to_value(OpenAiCompatibleProvider::to_message_content(
@@ -2469,6 +2701,7 @@ mod tests {
         let messages = vec![ChatMessage {
             role: "user".to_string(),
             content: "hello".to_string(),
+            parts: Vec::new(),
         }];
         let tools = vec![serde_json::json!({
             "type": "function",
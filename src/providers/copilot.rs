@@ -144,6 +144,14 @@ struct UsageInfo {
     prompt_tokens: Option<u64>,
     #[serde(default)]
     completion_tokens: Option<u64>,
+    #[serde(default)]
+    prompt_tokens_details: Option<PromptTokensDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PromptTokensDetails {
+    #[serde(default)]
+    cached_tokens: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -350,9 +358,12 @@ impl CopilotProvider {
         }
 
         let api_response: ApiChatResponse = response.json().await?;
-        let usage = api_response.usage.map(|u| TokenUsage {
-            input_tokens: u.prompt_tokens,
-            output_tokens: u.completion_tokens,
+        let usage = api_response.usage.map(|u| {
+            TokenUsage::from_openai_counts(
+                u.prompt_tokens,
+                u.completion_tokens,
+                u.prompt_tokens_details.and_then(|d| d.cached_tokens),
+            )
         });
         let choice = api_response
             .choices
@@ -378,6 +389,7 @@ impl CopilotProvider {
             text: choice.message.content,
             tool_calls,
             usage,
+            routed_to: None,
         })
     }
 
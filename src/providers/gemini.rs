@@ -5,7 +5,9 @@
 //! - Google Cloud ADC (`GOOGLE_APPLICATION_CREDENTIALS`)
 
 use crate::auth::AuthService;
-use crate::providers::traits::{ChatMessage, ChatResponse, Provider, TokenUsage};
+use crate::providers::traits::{
+    ChatMessage, ChatResponse, ContentPart, MediaSource, Provider, ProviderCapabilities, TokenUsage,
+};
 use async_trait::async_trait;
 use directories::UserDirs;
 use reqwest::Client;
@@ -134,8 +136,34 @@ struct Content {
 }
 
 #[derive(Debug, Serialize, Clone)]
-struct Part {
-    text: String,
+#[serde(untagged)]
+enum Part {
+    Text {
+        text: String,
+    },
+    InlineData {
+        #[serde(rename = "inlineData")]
+        inline_data: Blob,
+    },
+    FileData {
+        #[serde(rename = "fileData")]
+        file_data: FileData,
+    },
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct Blob {
+    #[serde(rename = "mimeType")]
+    mime_type: String,
+    data: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct FileData {
+    #[serde(rename = "mimeType", skip_serializing_if = "Option::is_none")]
+    mime_type: Option<String>,
+    #[serde(rename = "fileUri")]
+    file_uri: String,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -161,6 +189,8 @@ struct GeminiUsageMetadata {
     prompt_token_count: Option<u64>,
     #[serde(default, rename = "candidatesTokenCount")]
     candidates_token_count: Option<u64>,
+    #[serde(default, rename = "cachedContentTokenCount")]
+    cached_content_token_count: Option<u64>,
 }
 
 /// Response envelope for the internal cloudcode-pa API.
@@ -727,6 +757,41 @@ impl GeminiProvider {
     /// not the public API. Sending them to the public endpoint results in
     /// "400 Bad Request: API key not valid" errors.
     /// See: https://github.com/google-gemini/gemini-cli/issues/19200
+    /// Map a chat message to Gemini parts: images and documents become
+    /// `inlineData` (base64) or `fileData` (URL) parts next to the text.
+    fn convert_message_parts(message: &ChatMessage) -> Vec<Part> {
+        if message.parts.is_empty() {
+            return vec![Part::Text {
+                text: message.content.clone(),
+            }];
+        }
+
+        message
+            .parts
+            .iter()
+            .filter_map(|part| match part {
+                ContentPart::Text { text } => Some(Part::Text { text: text.clone() }),
+                ContentPart::Image { source } | ContentPart::Document { source, .. } => {
+                    Some(match source {
+                        MediaSource::Base64 { media_type, data } => Part::InlineData {
+                            inline_data: Blob {
+                                mime_type: media_type.clone(),
+                                data: data.clone(),
+                            },
+                        },
+                        MediaSource::Url { url } => Part::FileData {
+                            file_data: FileData {
+                                mime_type: None,
+                                file_uri: url.clone(),
+                            },
+                        },
+                    })
+                }
+                ContentPart::ToolUse { .. } | ContentPart::ToolResult { .. } => None,
+            })
+            .collect()
+    }
+
     fn build_generate_content_url(model: &str, auth: &GeminiAuth) -> String {
         match auth {
             GeminiAuth::OAuthToken(_) | GeminiAuth::ManagedOAuth => {
@@ -1077,9 +1142,13 @@ impl GeminiProvider {
             anyhow::bail!("Gemini API error: {}", err.message);
         }
 
-        let usage = result.usage_metadata.map(|u| TokenUsage {
-            input_tokens: u.prompt_token_count,
-            output_tokens: u.candidates_token_count,
+        // Like OpenAI, Gemini's prompt count includes cached content tokens.
+        let usage = result.usage_metadata.map(|u| {
+            TokenUsage::from_openai_counts(
+                u.prompt_token_count,
+                u.candidates_token_count,
+                u.cached_content_token_count,
+            )
         });
 
         let text = result
@@ -1095,6 +1164,13 @@ impl GeminiProvider {
 
 #[async_trait]
 impl Provider for GeminiProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            native_tool_calling: false,
+            vision: true,
+        }
+    }
+
     async fn chat_with_system(
         &self,
         system_prompt: Option<&str>,
@@ -1104,14 +1180,14 @@ impl Provider for GeminiProvider {
     ) -> anyhow::Result<String> {
         let system_instruction = system_prompt.map(|sys| Content {
             role: None,
-            parts: vec![Part {
+            parts: vec![Part::Text {
                 text: sys.to_string(),
             }],
         });
 
         let contents = vec![Content {
             role: Some("user".to_string()),
-            parts: vec![Part {
+            parts: vec![Part::Text {
                 text: message.to_string(),
             }],
         }];
@@ -1139,18 +1215,14 @@ impl Provider for GeminiProvider {
                 "user" => {
                     contents.push(Content {
                         role: Some("user".to_string()),
-                        parts: vec![Part {
-                            text: msg.content.clone(),
-                        }],
+                        parts: Self::convert_message_parts(msg),
                     });
                 }
                 "assistant" => {
                     // Gemini API uses "model" role instead of "assistant"
                     contents.push(Content {
                         role: Some("model".to_string()),
-                        parts: vec![Part {
-                            text: msg.content.clone(),
-                        }],
+                        parts: Self::convert_message_parts(msg),
                     });
                 }
                 _ => {}
@@ -1162,7 +1234,7 @@ impl Provider for GeminiProvider {
         } else {
             Some(Content {
                 role: None,
-                parts: vec![Part {
+                parts: vec![Part::Text {
                     text: system_parts.join("\n\n"),
                 }],
             })
@@ -1188,15 +1260,11 @@ impl Provider for GeminiProvider {
                 "system" => system_parts.push(&msg.content),
                 "user" => contents.push(Content {
                     role: Some("user".to_string()),
-                    parts: vec![Part {
-                        text: msg.content.clone(),
-                    }],
+                    parts: Self::convert_message_parts(msg),
                 }),
                 "assistant" => contents.push(Content {
                     role: Some("model".to_string()),
-                    parts: vec![Part {
-                        text: msg.content.clone(),
-                    }],
+                    parts: Self::convert_message_parts(msg),
                 }),
                 _ => {}
             }
@@ -1207,7 +1275,7 @@ impl Provider for GeminiProvider {
         } else {
             Some(Content {
                 role: None,
-                parts: vec![Part {
+                parts: vec![Part::Text {
                     text: system_parts.join("\n\n"),
                 }],
             })
@@ -1221,6 +1289,7 @@ impl Provider for GeminiProvider {
             text: Some(text),
             tool_calls: Vec::new(),
             usage,
+            routed_to: None,
         })
     }
 
@@ -1391,7 +1460,7 @@ mod tests {
         let body = GenerateContentRequest {
             contents: vec![Content {
                 role: Some("user".into()),
-                parts: vec![Part {
+                parts: vec![Part::Text {
                     text: "hello".into(),
                 }],
             }],
@@ -1424,6 +1493,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn convert_message_parts_maps_images_to_inline_and_file_data() {
+        let message = ChatMessage::with_parts(
+            "user",
+            vec![
+                ContentPart::text("Compare these"),
+                ContentPart::image(MediaSource::from_reference(
+                    "data:image/jpeg;base64,/9j/4AAQ",
+                )),
+                ContentPart::image(MediaSource::Url {
+                    url: "gs://bucket/cat.png".into(),
+                }),
+            ],
+        );
+
+        let parts = GeminiProvider::convert_message_parts(&message);
+        let json = serde_json::to_value(&parts).unwrap();
+
+        assert_eq!(json[0]["text"], "Compare these");
+        assert_eq!(json[1]["inlineData"]["mimeType"], "image/jpeg");
+        assert_eq!(json[1]["inlineData"]["data"], "/9j/4AAQ");
+        assert_eq!(json[2]["fileData"]["fileUri"], "gs://bucket/cat.png");
+    }
+
     #[test]
     fn oauth_request_wraps_payload_in_request_envelope() {
         let provider = test_provider(Some(test_oauth_auth("ya29.mock-token")));
@@ -1432,7 +1525,7 @@ mod tests {
         let body = GenerateContentRequest {
             contents: vec![Content {
                 role: Some("user".into()),
-                parts: vec![Part {
+                parts: vec![Part::Text {
                     text: "hello".into(),
                 }],
             }],
@@ -1476,7 +1569,7 @@ mod tests {
         let body = GenerateContentRequest {
             contents: vec![Content {
                 role: Some("user".into()),
-                parts: vec![Part {
+                parts: vec![Part::Text {
                     text: "hello".into(),
                 }],
             }],
@@ -1508,13 +1601,13 @@ mod tests {
         let request = GenerateContentRequest {
             contents: vec![Content {
                 role: Some("user".to_string()),
-                parts: vec![Part {
+                parts: vec![Part::Text {
                     text: "Hello".to_string(),
                 }],
             }],
             system_instruction: Some(Content {
                 role: None,
-                parts: vec![Part {
+                parts: vec![Part::Text {
                     text: "You are helpful".to_string(),
                 }],
             }),
@@ -1542,7 +1635,7 @@ mod tests {
             request: InternalGenerateContentRequest {
                 contents: vec![Content {
                     role: Some("user".to_string()),
-                    parts: vec![Part {
+                    parts: vec![Part::Text {
                         text: "Hello".to_string(),
                     }],
                 }],
@@ -1574,7 +1667,7 @@ mod tests {
             request: InternalGenerateContentRequest {
                 contents: vec![Content {
                     role: Some("user".to_string()),
-                    parts: vec![Part {
+                    parts: vec![Part::Text {
                         text: "Hello".to_string(),
                     }],
                 }],
@@ -1597,7 +1690,7 @@ mod tests {
             request: InternalGenerateContentRequest {
                 contents: vec![Content {
                     role: Some("user".to_string()),
-                    parts: vec![Part {
+                    parts: vec![Part::Text {
                         text: "Hello".to_string(),
                     }],
                 }],
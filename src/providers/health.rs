@@ -0,0 +1,600 @@
+//! Circuit breakers and rolling health windows for provider calls.
+//!
+//! [`ReliableProvider`](super::reliable::ReliableProvider) records the outcome
+//! and latency of every attempt here, once for the provider as a whole and
+//! once for the provider/model pair. Each breaker is closed until the error
+//! rate over the rolling window crosses the configured threshold, then open
+//! for `open_secs`, then half-open: a single probe call decides whether it
+//! closes again or re-opens.
+//!
+//! The process-wide tracker ([`global`]) is shared by every provider built
+//! from config, so `/api/health`, `zeroclaw doctor` (via the daemon state
+//! file) and the observer metrics all see the same state.
+
+use crate::config::CircuitBreakerConfig;
+use crate::observability::traits::{Observer, ObserverMetric};
+use parking_lot::{Mutex, RwLock};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Upper bound on samples kept per breaker, regardless of the window length.
+const MAX_WINDOW_SAMPLES: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    Closed,
+    HalfOpen,
+    Open,
+}
+
+impl CircuitState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Closed => "closed",
+            Self::HalfOpen => "half_open",
+            Self::Open => "open",
+        }
+    }
+}
+
+/// Point-in-time view of one breaker.
+#[derive(Debug, Clone, Serialize)]
+pub struct CircuitSnapshot {
+    pub provider: String,
+    /// `None` for the provider-wide breaker.
+    pub model: Option<String>,
+    pub state: CircuitState,
+    /// Calls in the rolling window.
+    pub requests: usize,
+    /// Failure ratio over the rolling window (0.0–1.0).
+    pub error_rate: f64,
+    pub p95_latency_ms: Option<u64>,
+    /// Seconds until an open circuit lets a probe through.
+    pub retry_in_secs: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    at: Instant,
+    ok: bool,
+    latency_ms: u64,
+}
+
+#[derive(Debug)]
+struct Breaker {
+    state: CircuitState,
+    opened_at: Option<Instant>,
+    probe_in_flight: bool,
+    samples: VecDeque<Sample>,
+    last_error: Option<String>,
+}
+
+impl Default for Breaker {
+    fn default() -> Self {
+        Self {
+            state: CircuitState::Closed,
+            opened_at: None,
+            probe_in_flight: false,
+            samples: VecDeque::new(),
+            last_error: None,
+        }
+    }
+}
+
+impl Breaker {
+    /// Expire old samples and move open circuits to half-open once their
+    /// cool-down has passed.
+    fn refresh(&mut self, config: &CircuitBreakerConfig, now: Instant) {
+        let window = Duration::from_secs(config.window_secs);
+        while self
+            .samples
+            .front()
+            .is_some_and(|sample| now.duration_since(sample.at) > window)
+        {
+            self.samples.pop_front();
+        }
+
+        if self.state == CircuitState::Open
+            && self.opened_at.is_some_and(|opened| {
+                now.duration_since(opened) >= Duration::from_secs(config.open_secs)
+            })
+        {
+            self.state = CircuitState::HalfOpen;
+            self.probe_in_flight = false;
+        }
+    }
+
+    fn admits(&self) -> bool {
+        match self.state {
+            CircuitState::Closed => true,
+            CircuitState::HalfOpen => !self.probe_in_flight,
+            CircuitState::Open => false,
+        }
+    }
+
+    fn error_rate(&self) -> f64 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        let failures = self.samples.iter().filter(|sample| !sample.ok).count();
+        failures as f64 / self.samples.len() as f64
+    }
+
+    fn p95_latency_ms(&self) -> Option<u64> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let mut latencies: Vec<u64> = self.samples.iter().map(|s| s.latency_ms).collect();
+        latencies.sort_unstable();
+        let idx = (latencies.len() * 95).div_ceil(100).saturating_sub(1);
+        latencies.get(idx).copied()
+    }
+
+    /// Record one call and return the state transition it caused, if any.
+    fn record(
+        &mut self,
+        sample: Sample,
+        error: Option<&str>,
+        config: &CircuitBreakerConfig,
+    ) -> Option<(CircuitState, CircuitState)> {
+        self.refresh(config, sample.at);
+        self.samples.push_back(sample);
+        while self.samples.len() > MAX_WINDOW_SAMPLES {
+            self.samples.pop_front();
+        }
+        if let Some(error) = error {
+            self.last_error = Some(error.to_string());
+        }
+
+        let before = self.state;
+        match self.state {
+            CircuitState::HalfOpen if sample.ok => {
+                self.state = CircuitState::Closed;
+                self.opened_at = None;
+                self.samples.clear();
+                self.samples.push_back(sample);
+            }
+            CircuitState::HalfOpen => {
+                self.state = CircuitState::Open;
+                self.opened_at = Some(sample.at);
+            }
+            CircuitState::Closed => {
+                let min_requests = usize::try_from(config.min_requests.max(1)).unwrap_or(1);
+                if !sample.ok
+                    && self.samples.len() >= min_requests
+                    && self.error_rate() >= config.failure_rate_threshold
+                {
+                    self.state = CircuitState::Open;
+                    self.opened_at = Some(sample.at);
+                }
+            }
+            // A call admitted before the circuit opened; keep the cool-down.
+            CircuitState::Open => {}
+        }
+        self.probe_in_flight = false;
+
+        (before != self.state).then_some((before, self.state))
+    }
+}
+
+type BreakerKey = (String, Option<String>);
+
+/// Routing rank for a candidate; lower sorts first.
+///
+/// Ordered by circuit state, then by error rate in 10% steps (only once the
+/// window holds `min_requests` calls), then by whether p95 latency exceeds
+/// `slow_call_ms`. Equal ranks keep the configured order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HealthRank {
+    state: CircuitState,
+    error_bucket: u8,
+    slow: bool,
+}
+
+/// Half-open probe slots claimed by one admitted call.
+///
+/// A call whose future is dropped mid-flight never records an outcome, so
+/// dropping the guard frees the slots it claimed. Callers that do record an
+/// outcome [`disarm`](ProbeGuard::disarm) it first.
+#[must_use = "dropping the guard frees the probe slot"]
+pub struct ProbeGuard<'a> {
+    health: Option<&'a ProviderHealth>,
+    claimed: Vec<BreakerKey>,
+}
+
+impl ProbeGuard<'_> {
+    /// A guard holding no slots, for calls admitted with breakers bypassed.
+    pub fn unclaimed() -> Self {
+        Self {
+            health: None,
+            claimed: Vec::new(),
+        }
+    }
+
+    /// Leave the claimed slots to the `record_*` call that follows.
+    pub fn disarm(mut self) {
+        self.claimed.clear();
+    }
+}
+
+impl Drop for ProbeGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(health) = self.health.filter(|_| !self.claimed.is_empty()) {
+            health.release_keys(&self.claimed);
+        }
+    }
+}
+
+/// Breaker registry keyed by provider and by provider/model pair.
+pub struct ProviderHealth {
+    config: RwLock<CircuitBreakerConfig>,
+    breakers: Mutex<HashMap<BreakerKey, Breaker>>,
+}
+
+impl ProviderHealth {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            breakers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Replace the thresholds; recorded samples are kept.
+    pub fn configure(&self, config: &CircuitBreakerConfig) {
+        *self.config.write() = config.clone();
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.read().enabled
+    }
+
+    fn keys(provider: &str, model: &str) -> [BreakerKey; 2] {
+        [
+            (provider.to_string(), None),
+            (provider.to_string(), Some(model.to_string())),
+        ]
+    }
+
+    /// Admit a call to `provider`/`model` if its circuits allow one now,
+    /// claiming the probe slot of half-open circuits. The next `record_*` or
+    /// [`release`] frees the slot, and so does dropping the returned guard
+    /// before the call finished.
+    ///
+    /// [`release`]: ProviderHealth::release
+    pub fn try_acquire(&self, provider: &str, model: &str) -> Option<ProbeGuard<'_>> {
+        let config = self.config.read().clone();
+        let now = Instant::now();
+        let keys = Self::keys(provider, model);
+        let mut breakers = self.breakers.lock();
+
+        for key in &keys {
+            breakers
+                .entry(key.clone())
+                .or_default()
+                .refresh(&config, now);
+        }
+        if !keys
+            .iter()
+            .all(|key| breakers.get(key).is_none_or(Breaker::admits))
+        {
+            return None;
+        }
+        let mut claimed = Vec::new();
+        for key in keys {
+            if let Some(breaker) = breakers.get_mut(&key) {
+                if breaker.state == CircuitState::HalfOpen {
+                    breaker.probe_in_flight = true;
+                    claimed.push(key);
+                }
+            }
+        }
+        Some(ProbeGuard {
+            health: Some(self),
+            claimed,
+        })
+    }
+
+    /// Whether every breaker for `provider`/`model` is open right now,
+    /// without claiming anything.
+    pub fn is_open(&self, provider: &str, model: &str) -> bool {
+        let config = self.config.read().clone();
+        let now = Instant::now();
+        let mut breakers = self.breakers.lock();
+        Self::keys(provider, model).iter().any(|key| {
+            breakers.get_mut(key).is_some_and(|breaker| {
+                breaker.refresh(&config, now);
+                breaker.state == CircuitState::Open
+            })
+        })
+    }
+
+    pub fn record_success(&self, provider: &str, model: &str, latency: Duration) {
+        self.record(provider, model, latency, None);
+    }
+
+    pub fn record_failure(&self, provider: &str, model: &str, latency: Duration, error: &str) {
+        self.record(provider, model, latency, Some(error));
+    }
+
+    /// Free a claimed probe slot for a call whose outcome says nothing about
+    /// provider health (e.g. the request exceeded the context window).
+    pub fn release(&self, provider: &str, model: &str) {
+        let mut breakers = self.breakers.lock();
+        for key in Self::keys(provider, model) {
+            if let Some(breaker) = breakers.get_mut(&key) {
+                breaker.probe_in_flight = false;
+            }
+        }
+    }
+
+    fn release_keys(&self, keys: &[BreakerKey]) {
+        let mut breakers = self.breakers.lock();
+        for key in keys {
+            if let Some(breaker) = breakers.get_mut(key) {
+                breaker.probe_in_flight = false;
+            }
+        }
+    }
+
+    fn record(&self, provider: &str, model: &str, latency: Duration, error: Option<&str>) {
+        let config = self.config.read().clone();
+        let sample = Sample {
+            at: Instant::now(),
+            ok: error.is_none(),
+            latency_ms: u64::try_from(latency.as_millis()).unwrap_or(u64::MAX),
+        };
+        let mut breakers = self.breakers.lock();
+        for (provider_key, model_key) in Self::keys(provider, model) {
+            let breaker = breakers
+                .entry((provider_key, model_key.clone()))
+                .or_default();
+            let Some((from, to)) = breaker.record(sample, error, &config) else {
+                continue;
+            };
+            let model_label = model_key.as_deref().unwrap_or("*");
+            if to == CircuitState::Open {
+                tracing::warn!(
+                    provider,
+                    model = model_label,
+                    from = from.as_str(),
+                    error_rate = breaker.error_rate(),
+                    "Circuit breaker opened"
+                );
+            } else {
+                tracing::info!(
+                    provider,
+                    model = model_label,
+                    from = from.as_str(),
+                    to = to.as_str(),
+                    "Circuit breaker state changed"
+                );
+            }
+        }
+    }
+
+    pub fn rank(&self, provider: &str, model: &str) -> HealthRank {
+        let config = self.config.read().clone();
+        let now = Instant::now();
+        let min_requests = usize::try_from(config.min_requests.max(1)).unwrap_or(1);
+        let mut breakers = self.breakers.lock();
+        let mut rank = HealthRank {
+            state: CircuitState::Closed,
+            error_bucket: 0,
+            slow: false,
+        };
+
+        for key in Self::keys(provider, model) {
+            let Some(breaker) = breakers.get_mut(&key) else {
+                continue;
+            };
+            breaker.refresh(&config, now);
+            rank.state = rank.state.max(breaker.state);
+            if breaker.samples.len() >= min_requests {
+                // Truncation to a 0..=10 bucket is intended.
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                let bucket = (breaker.error_rate() * 10.0).floor() as u8;
+                rank.error_bucket = rank.error_bucket.max(bucket);
+            }
+            if key.1.is_some() {
+                rank.slow = breaker
+                    .p95_latency_ms()
+                    .is_some_and(|p95| p95 > config.slow_call_ms);
+            }
+        }
+        rank
+    }
+
+    /// All breakers, provider-wide entries first within each provider.
+    pub fn snapshot(&self) -> Vec<CircuitSnapshot> {
+        let config = self.config.read().clone();
+        let now = Instant::now();
+        let mut breakers = self.breakers.lock();
+        let mut snapshot: Vec<CircuitSnapshot> = breakers
+            .iter_mut()
+            .map(|((provider, model), breaker)| {
+                breaker.refresh(&config, now);
+                let retry_in_secs = (breaker.state == CircuitState::Open)
+                    .then(|| {
+                        breaker.opened_at.map(|opened| {
+                            config
+                                .open_secs
+                                .saturating_sub(now.duration_since(opened).as_secs())
+                        })
+                    })
+                    .flatten();
+                CircuitSnapshot {
+                    provider: provider.clone(),
+                    model: model.clone(),
+                    state: breaker.state,
+                    requests: breaker.samples.len(),
+                    error_rate: breaker.error_rate(),
+                    p95_latency_ms: breaker.p95_latency_ms(),
+                    retry_in_secs,
+                    last_error: breaker.last_error.clone(),
+                }
+            })
+            .collect();
+        snapshot.sort_by(|a, b| (&a.provider, &a.model).cmp(&(&b.provider, &b.model)));
+        snapshot
+    }
+}
+
+static GLOBAL: OnceLock<Arc<ProviderHealth>> = OnceLock::new();
+
+/// Process-wide tracker shared by every provider created from config.
+pub fn global() -> Arc<ProviderHealth> {
+    GLOBAL
+        .get_or_init(|| Arc::new(ProviderHealth::new(CircuitBreakerConfig::default())))
+        .clone()
+}
+
+/// Snapshot of the process-wide tracker.
+pub fn snapshot() -> Vec<CircuitSnapshot> {
+    GLOBAL
+        .get()
+        .map(|health| health.snapshot())
+        .unwrap_or_default()
+}
+
+/// Emit one [`ObserverMetric::ProviderCircuit`] per tracked breaker.
+pub fn report_to(observer: &dyn Observer) {
+    for circuit in snapshot() {
+        observer.record_metric(&ObserverMetric::ProviderCircuit {
+            provider: circuit.provider,
+            model: circuit.model,
+            state: circuit.state.as_str(),
+            error_rate: circuit.error_rate,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> CircuitBreakerConfig {
+        CircuitBreakerConfig {
+            min_requests: 3,
+            failure_rate_threshold: 0.5,
+            open_secs: 3600,
+            ..CircuitBreakerConfig::default()
+        }
+    }
+
+    fn fail(health: &ProviderHealth, provider: &str, model: &str) {
+        health.record_failure(provider, model, Duration::from_millis(10), "boom");
+    }
+
+    #[test]
+    fn opens_after_error_rate_crosses_threshold() {
+        let health = ProviderHealth::new(config());
+        fail(&health, "a", "m");
+        fail(&health, "a", "m");
+        assert!(health.try_acquire("a", "m").is_some());
+        fail(&health, "a", "m");
+
+        assert!(health.try_acquire("a", "m").is_none());
+        assert!(health.is_open("a", "m"));
+        // The provider-wide breaker opened too, so other models are skipped.
+        assert!(health.try_acquire("a", "other").is_none());
+        assert!(health.try_acquire("b", "m").is_some());
+    }
+
+    #[test]
+    fn half_open_admits_one_probe_and_closes_on_success() {
+        let health = ProviderHealth::new(CircuitBreakerConfig {
+            open_secs: 0,
+            ..config()
+        });
+        for _ in 0..3 {
+            fail(&health, "a", "m");
+        }
+
+        let probe = health.try_acquire("a", "m").expect("probe admitted");
+        assert!(
+            health.try_acquire("a", "m").is_none(),
+            "only one probe at a time"
+        );
+        probe.disarm();
+        health.record_success("a", "m", Duration::from_millis(5));
+
+        let states: Vec<_> = health.snapshot().into_iter().map(|c| c.state).collect();
+        assert_eq!(states, vec![CircuitState::Closed, CircuitState::Closed]);
+        assert!(health.try_acquire("a", "m").is_some());
+    }
+
+    #[test]
+    fn failed_probe_reopens_circuit() {
+        let health = ProviderHealth::new(CircuitBreakerConfig {
+            open_secs: 0,
+            ..config()
+        });
+        for _ in 0..3 {
+            fail(&health, "a", "m");
+        }
+        health
+            .try_acquire("a", "m")
+            .expect("probe admitted")
+            .disarm();
+        fail(&health, "a", "m");
+
+        let snapshot = health.snapshot();
+        assert_eq!(snapshot[0].last_error.as_deref(), Some("boom"));
+        // open_secs = 0 moves it straight back to half-open on the next look.
+        assert_eq!(snapshot[0].state, CircuitState::HalfOpen);
+    }
+
+    #[test]
+    fn rank_prefers_healthy_and_fast_candidates() {
+        let health = ProviderHealth::new(CircuitBreakerConfig {
+            slow_call_ms: 100,
+            ..config()
+        });
+        for _ in 0..3 {
+            health.record_success("fast", "m", Duration::from_millis(10));
+            health.record_success("slow", "m", Duration::from_millis(500));
+        }
+        health.record_success("flaky", "m", Duration::from_millis(10));
+        fail(&health, "flaky", "m");
+        health.record_success("flaky", "m", Duration::from_millis(10));
+
+        assert!(health.rank("fast", "m") < health.rank("slow", "m"));
+        assert!(health.rank("slow", "m") < health.rank("flaky", "m"));
+        assert_eq!(health.rank("unknown", "m"), health.rank("fast", "m"));
+    }
+
+    #[test]
+    fn release_frees_probe_slot() {
+        let health = ProviderHealth::new(CircuitBreakerConfig {
+            open_secs: 0,
+            ..config()
+        });
+        for _ in 0..3 {
+            fail(&health, "a", "m");
+        }
+        let probe = health.try_acquire("a", "m").expect("probe admitted");
+        probe.disarm();
+        assert!(health.try_acquire("a", "m").is_none());
+        health.release("a", "m");
+        assert!(health.try_acquire("a", "m").is_some());
+    }
+
+    #[test]
+    fn dropped_probe_guard_frees_probe_slot() {
+        let health = ProviderHealth::new(CircuitBreakerConfig {
+            open_secs: 0,
+            ..config()
+        });
+        for _ in 0..3 {
+            fail(&health, "a", "m");
+        }
+        let probe = health.try_acquire("a", "m").expect("probe admitted");
+        assert!(health.try_acquire("a", "m").is_none());
+        drop(probe);
+        assert!(health.try_acquire("a", "m").is_some());
+    }
+}
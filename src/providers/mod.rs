@@ -9,7 +9,8 @@
 //! The subsystem supports resilient multi-provider configurations through the
 //! [`ReliableProvider`](reliable::ReliableProvider) wrapper, which handles fallback
 //! chains and automatic retry. Model routing across providers is available via
-//! [`create_routed_provider`].
+//! [`create_routed_provider`], and cost-aware automatic routing via
+//! [`create_routed_provider_from_config`].
 //!
 //! # Extension
 //!
@@ -21,19 +22,22 @@ pub mod bedrock;
 pub mod compatible;
 pub mod copilot;
 pub mod gemini;
+pub mod health;
 pub mod ollama;
 pub mod openai;
 pub mod openai_codex;
 pub mod openrouter;
 pub mod reliable;
 pub mod router;
+pub(crate) mod streaming;
 pub mod telnyx;
 pub mod traits;
 
 #[allow(unused_imports)]
 pub use traits::{
-    ChatMessage, ChatRequest, ChatResponse, ConversationMessage, Provider, ProviderCapabilityError,
-    ToolCall, ToolResultMessage,
+    ChatMessage, ChatRequest, ChatResponse, ChatStreamAccumulator, ContentPart,
+    ConversationMessage, MediaSource, Provider, ProviderCapabilityError, StreamEvent, ToolCall,
+    ToolResultMessage,
 };
 
 use crate::auth::AuthService;
@@ -1408,6 +1412,12 @@ pub fn create_resilient_provider_with_options(
     .with_api_keys(reliability.api_keys.clone())
     .with_model_fallbacks(reliability.model_fallbacks.clone());
 
+    // Breaker state is process-wide so every provider chain, `/api/health`
+    // and `zeroclaw doctor` see the same circuits.
+    let health = health::global();
+    health.configure(&reliability.circuit_breaker);
+    let reliable = reliable.with_health(health);
+
     Ok(Box::new(reliable))
 }
 
@@ -1453,6 +1463,70 @@ pub fn create_routed_provider_with_options(
         );
     }
 
+    Ok(Box::new(build_router(
+        primary_name,
+        api_key,
+        api_url,
+        reliability,
+        model_routes,
+        default_model,
+        options,
+    )?))
+}
+
+/// Create a routed provider from `config`, with `[auto_routing]` applied to
+/// default-model requests when it is enabled and routes are configured.
+pub fn create_routed_provider_from_config(
+    config: &crate::config::Config,
+    primary_name: &str,
+    default_model: &str,
+    options: &ProviderRuntimeOptions,
+) -> anyhow::Result<Box<dyn Provider>> {
+    if !config.auto_routing.enabled || config.model_routes.is_empty() {
+        return create_routed_provider_with_options(
+            primary_name,
+            config.api_key.as_deref(),
+            config.api_url.as_deref(),
+            &config.reliability,
+            &config.model_routes,
+            default_model,
+            options,
+        );
+    }
+
+    let router = build_router(
+        primary_name,
+        config.api_key.as_deref(),
+        config.api_url.as_deref(),
+        &config.reliability,
+        &config.model_routes,
+        default_model,
+        options,
+    )?;
+    let tracker = match crate::cost::shared_tracker(&config.cost, &config.workspace_dir) {
+        Ok(tracker) => Some(tracker),
+        Err(error) => {
+            tracing::warn!("Auto-routing without prices or call statistics: {error}");
+            None
+        }
+    };
+
+    Ok(Box::new(router.with_auto_routing(router::AutoRouting {
+        config: config.auto_routing.clone(),
+        classification: config.query_classification.clone(),
+        tracker,
+    })))
+}
+
+fn build_router(
+    primary_name: &str,
+    api_key: Option<&str>,
+    api_url: Option<&str>,
+    reliability: &crate::config::ReliabilityConfig,
+    model_routes: &[crate::config::ModelRouteConfig],
+    default_model: &str,
+    options: &ProviderRuntimeOptions,
+) -> anyhow::Result<router::RouterProvider> {
     // Collect unique provider names needed
     let mut needed: Vec<String> = vec![primary_name.to_string()];
     for route in model_routes {
@@ -1504,11 +1578,11 @@ pub fn create_routed_provider_with_options(
         })
         .collect();
 
-    Ok(Box::new(router::RouterProvider::new(
+    Ok(router::RouterProvider::new(
         providers,
         routes,
         default_model.to_string(),
-    )))
+    ))
 }
 
 /// Information about a supported provider for display purposes.
@@ -2500,6 +2574,7 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            circuit_breaker: crate::config::CircuitBreakerConfig::default(),
         };
 
         let provider = create_resilient_provider(
@@ -2539,6 +2614,7 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            circuit_breaker: crate::config::CircuitBreakerConfig::default(),
         };
 
         // Primary uses a ZAI key; fallbacks (lmstudio, ollama) should NOT
@@ -2561,6 +2637,7 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            circuit_breaker: crate::config::CircuitBreakerConfig::default(),
         };
 
         let provider =
@@ -2587,6 +2664,7 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            circuit_breaker: crate::config::CircuitBreakerConfig::default(),
         };
 
         let provider = create_resilient_provider("zai", Some("zai-test-key"), None, &reliability);
@@ -2619,6 +2697,7 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            circuit_breaker: crate::config::CircuitBreakerConfig::default(),
         };
 
         let provider = create_resilient_provider("zai", Some("zai-test-key"), None, &reliability);
@@ -2911,6 +2990,7 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            circuit_breaker: crate::config::CircuitBreakerConfig::default(),
         };
 
         // openai-codex resolves its own OAuth credential; it should not
@@ -2940,6 +3020,7 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            circuit_breaker: crate::config::CircuitBreakerConfig::default(),
         };
 
         let provider = create_resilient_provider("ollama", None, None, &reliability);
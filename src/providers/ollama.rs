@@ -1,6 +1,7 @@
 use crate::multimodal;
 use crate::providers::traits::{
-    ChatMessage, ChatResponse, Provider, ProviderCapabilities, TokenUsage, ToolCall,
+    ChatMessage, ChatResponse, ContentPart, MediaSource, Provider, ProviderCapabilities,
+    TokenUsage, ToolCall,
 };
 use async_trait::async_trait;
 use reqwest::Client;
@@ -210,6 +211,69 @@ impl OllamaProvider {
         (content, Some(images))
     }
 
+    /// Convert a message carrying typed parts. Ollama takes images as bare
+    /// base64 payloads next to the text; documents are sent as their text marker.
+    fn convert_structured_message(
+        message: &ChatMessage,
+        tool_name_by_id: &mut HashMap<String, String>,
+    ) -> Message {
+        let mut text = Vec::new();
+        let mut images = Vec::new();
+        let mut outgoing_calls = Vec::new();
+
+        for part in &message.parts {
+            match part {
+                ContentPart::Text { text: value } => {
+                    if !value.trim().is_empty() {
+                        text.push(value.trim().to_string());
+                    }
+                }
+                ContentPart::Image { source } => match source {
+                    MediaSource::Base64 { data, .. } => images.push(data.clone()),
+                    MediaSource::Url { url } => images.push(url.clone()),
+                },
+                ContentPart::Document { source, .. } => {
+                    text.push(format!("[DOCUMENT:{}]", source.to_url()));
+                }
+                ContentPart::ToolUse {
+                    id,
+                    name,
+                    arguments,
+                } => {
+                    tool_name_by_id.insert(id.clone(), name.clone());
+                    outgoing_calls.push(OutgoingToolCall {
+                        kind: "function".to_string(),
+                        function: OutgoingFunction {
+                            name: name.clone(),
+                            arguments: Self::parse_tool_arguments(arguments),
+                        },
+                    });
+                }
+                ContentPart::ToolResult {
+                    tool_use_id,
+                    content,
+                    ..
+                } => {
+                    return Message {
+                        role: "tool".to_string(),
+                        content: Some(content.clone()),
+                        images: None,
+                        tool_calls: None,
+                        tool_name: tool_name_by_id.get(tool_use_id).cloned(),
+                    };
+                }
+            }
+        }
+
+        Message {
+            role: message.role.clone(),
+            content: (!text.is_empty()).then(|| text.join("\n\n")),
+            images: (!images.is_empty()).then_some(images),
+            tool_calls: (!outgoing_calls.is_empty()).then_some(outgoing_calls),
+            tool_name: None,
+        }
+    }
+
     /// Convert internal chat history format to Ollama's native tool-call message schema.
     ///
     /// `run_tool_call_loop` stores native assistant/tool entries as JSON strings in
@@ -221,6 +285,10 @@ impl OllamaProvider {
         messages
             .iter()
             .map(|message| {
+                if !message.parts.is_empty() {
+                    return Self::convert_structured_message(message, &mut tool_name_by_id);
+                }
+
                 if message.role == "assistant" {
                     if let Ok(value) = serde_json::from_str::<serde_json::Value>(&message.content) {
                         if let Some(tool_calls_value) = value.get("tool_calls") {
@@ -608,6 +676,7 @@ impl Provider for OllamaProvider {
             Some(TokenUsage {
                 input_tokens: response.prompt_eval_count,
                 output_tokens: response.eval_count,
+                ..TokenUsage::default()
             })
         } else {
             None
@@ -641,6 +710,7 @@ impl Provider for OllamaProvider {
                 text,
                 tool_calls,
                 usage,
+                routed_to: None,
             });
         }
 
@@ -659,6 +729,7 @@ impl Provider for OllamaProvider {
                     )),
                     tool_calls: vec![],
                     usage,
+                    routed_to: None,
                 });
             }
             tracing::warn!("Ollama returned empty content with no tool calls");
@@ -667,6 +738,7 @@ impl Provider for OllamaProvider {
             text: Some(content),
             tool_calls: vec![],
             usage,
+            routed_to: None,
         })
     }
 
@@ -713,6 +785,7 @@ impl Provider for OllamaProvider {
             text: Some(text),
             tool_calls: vec![],
             usage: None,
+            routed_to: None,
         })
     }
 }
@@ -961,6 +1034,7 @@ mod tests {
         let messages = vec![ChatMessage {
             role: "assistant".into(),
             content: r#"{"content":null,"tool_calls":[{"id":"call_1","name":"shell","arguments":"{\"command\":\"ls\"}"}]}"#.into(),
+            parts: Vec::new(),
         }];
 
         let converted = provider.convert_messages(&messages);
@@ -985,10 +1059,12 @@ mod tests {
             ChatMessage {
                 role: "assistant".into(),
                 content: r#"{"content":null,"tool_calls":[{"id":"call_7","name":"file_read","arguments":"{\"path\":\"README.md\"}"}]}"#.into(),
+                parts: Vec::new(),
             },
             ChatMessage {
                 role: "tool".into(),
                 content: r#"{"tool_call_id":"call_7","content":"ok"}"#.into(),
+                parts: Vec::new(),
             },
         ];
 
@@ -1007,6 +1083,7 @@ mod tests {
         let messages = vec![ChatMessage {
             role: "user".into(),
             content: "Inspect this screenshot [IMAGE:data:image/png;base64,abcd==]".into(),
+            parts: Vec::new(),
         }];
 
         let converted = provider.convert_messages(&messages);
@@ -1023,6 +1100,38 @@ mod tests {
         assert_eq!(images, &vec!["abcd==".to_string()]);
     }
 
+    #[test]
+    fn convert_messages_maps_typed_image_and_tool_parts() {
+        let provider = OllamaProvider::new(None, None);
+        let messages = vec![
+            ChatMessage::with_parts(
+                "user",
+                vec![
+                    ContentPart::text("What is this?"),
+                    ContentPart::image(MediaSource::from_reference("data:image/png;base64,abcd==")),
+                ],
+            ),
+            ChatMessage::assistant_tool_calls(
+                None,
+                &[ToolCall {
+                    id: "call_3".into(),
+                    name: "file_read".into(),
+                    arguments: r#"{"path":"a.txt"}"#.into(),
+                }],
+            ),
+            ChatMessage::tool_result("call_3", "contents"),
+        ];
+
+        let converted = provider.convert_messages(&messages);
+        assert_eq!(converted[0].content.as_deref(), Some("What is this?"));
+        assert_eq!(converted[0].images, Some(vec!["abcd==".to_string()]));
+        let calls = converted[1].tool_calls.as_ref().expect("tool calls");
+        assert_eq!(calls[0].function.name, "file_read");
+        assert_eq!(converted[2].role, "tool");
+        assert_eq!(converted[2].tool_name.as_deref(), Some("file_read"));
+        assert_eq!(converted[2].content.as_deref(), Some("contents"));
+    }
+
     #[test]
     fn capabilities_include_native_tools_and_vision() {
         let provider = OllamaProvider::new(None, None);
@@ -1,9 +1,11 @@
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, TokenUsage, ToolCall as ProviderToolCall,
+    ContentPart, Provider, ProviderCapabilities, StreamEvent, StreamResult, TokenUsage,
+    ToolCall as ProviderToolCall,
 };
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
+use futures_util::stream;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
@@ -62,19 +64,55 @@ struct NativeChatRequest {
     tools: Option<Vec<NativeToolSpec>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tool_choice: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<NativeStreamOptions>,
+}
+
+#[derive(Debug, Serialize)]
+struct NativeStreamOptions {
+    include_usage: bool,
 }
 
 #[derive(Debug, Serialize)]
 struct NativeMessage {
     role: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    content: Option<String>,
+    content: Option<MessageContent>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tool_call_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tool_calls: Option<Vec<NativeToolCall>>,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum MessageContent {
+    Text(String),
+    Parts(Vec<MessagePart>),
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum MessagePart {
+    Text { text: String },
+    ImageUrl { image_url: ImageUrlPart },
+    File { file: FilePart },
+}
+
+#[derive(Debug, Serialize)]
+struct ImageUrlPart {
+    url: String,
+}
+
+#[derive(Debug, Serialize)]
+struct FilePart {
+    file_data: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filename: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct NativeToolSpec {
     #[serde(rename = "type")]
@@ -131,6 +169,14 @@ struct UsageInfo {
     prompt_tokens: Option<u64>,
     #[serde(default)]
     completion_tokens: Option<u64>,
+    #[serde(default)]
+    prompt_tokens_details: Option<PromptTokensDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PromptTokensDetails {
+    #[serde(default)]
+    cached_tokens: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -190,10 +236,89 @@ impl OpenAiProvider {
         })
     }
 
+    fn convert_parts(parts: &[ContentPart]) -> MessageContent {
+        let converted = parts
+            .iter()
+            .filter_map(|part| match part {
+                ContentPart::Text { text } => Some(MessagePart::Text { text: text.clone() }),
+                ContentPart::Image { source } => Some(MessagePart::ImageUrl {
+                    image_url: ImageUrlPart {
+                        url: source.to_url(),
+                    },
+                }),
+                ContentPart::Document { source, name } => Some(MessagePart::File {
+                    file: FilePart {
+                        file_data: source.to_url(),
+                        filename: name.clone(),
+                    },
+                }),
+                ContentPart::ToolUse { .. } | ContentPart::ToolResult { .. } => None,
+            })
+            .collect::<Vec<_>>();
+
+        match converted.as_slice() {
+            [MessagePart::Text { text }] => MessageContent::Text(text.clone()),
+            _ => MessageContent::Parts(converted),
+        }
+    }
+
+    fn convert_structured_message(message: &ChatMessage) -> NativeMessage {
+        let tool_calls = message
+            .parts
+            .iter()
+            .filter_map(|part| match part {
+                ContentPart::ToolUse {
+                    id,
+                    name,
+                    arguments,
+                } => Some(NativeToolCall {
+                    id: Some(id.clone()),
+                    kind: Some("function".to_string()),
+                    function: NativeFunctionCall {
+                        name: name.clone(),
+                        arguments: arguments.clone(),
+                    },
+                }),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        if let Some((tool_use_id, content)) = message.parts.iter().find_map(|part| match part {
+            ContentPart::ToolResult {
+                tool_use_id,
+                content,
+                ..
+            } => Some((tool_use_id, content)),
+            _ => None,
+        }) {
+            return NativeMessage {
+                role: "tool".to_string(),
+                content: Some(MessageContent::Text(content.clone())),
+                tool_call_id: Some(tool_use_id.clone()),
+                tool_calls: None,
+            };
+        }
+
+        let has_content = message
+            .parts
+            .iter()
+            .any(|part| !matches!(part, ContentPart::ToolUse { .. }));
+        NativeMessage {
+            role: message.role.clone(),
+            content: has_content.then(|| Self::convert_parts(&message.parts)),
+            tool_call_id: None,
+            tool_calls: (!tool_calls.is_empty()).then_some(tool_calls),
+        }
+    }
+
     fn convert_messages(messages: &[ChatMessage]) -> Vec<NativeMessage> {
         messages
             .iter()
             .map(|m| {
+                if !m.parts.is_empty() {
+                    return Self::convert_structured_message(m);
+                }
+
                 if m.role == "assistant" {
                     if let Ok(value) = serde_json::from_str::<serde_json::Value>(&m.content) {
                         if let Some(tool_calls_value) = value.get("tool_calls") {
@@ -216,7 +341,7 @@ impl OpenAiProvider {
                                 let content = value
                                     .get("content")
                                     .and_then(serde_json::Value::as_str)
-                                    .map(ToString::to_string);
+                                    .map(|text| MessageContent::Text(text.to_string()));
                                 return NativeMessage {
                                     role: "assistant".to_string(),
                                     content,
@@ -237,7 +362,7 @@ impl OpenAiProvider {
                         let content = value
                             .get("content")
                             .and_then(serde_json::Value::as_str)
-                            .map(ToString::to_string);
+                            .map(|text| MessageContent::Text(text.to_string()));
                         return NativeMessage {
                             role: "tool".to_string(),
                             content,
@@ -249,7 +374,7 @@ impl OpenAiProvider {
 
                 NativeMessage {
                     role: m.role.clone(),
-                    content: Some(m.content.clone()),
+                    content: Some(MessageContent::Text(m.content.clone())),
                     tool_call_id: None,
                     tool_calls: None,
                 }
@@ -274,6 +399,7 @@ impl OpenAiProvider {
             text,
             tool_calls,
             usage: None,
+            routed_to: None,
         }
     }
 
@@ -284,6 +410,13 @@ impl OpenAiProvider {
 
 #[async_trait]
 impl Provider for OpenAiProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            native_tool_calling: true,
+            vision: true,
+        }
+    }
+
     async fn chat_with_system(
         &self,
         system_prompt: Option<&str>,
@@ -354,6 +487,8 @@ impl Provider for OpenAiProvider {
             temperature,
             tool_choice: tools.as_ref().map(|_| "auto".to_string()),
             tools,
+            stream: None,
+            stream_options: None,
         };
 
         let response = self
@@ -369,9 +504,12 @@ impl Provider for OpenAiProvider {
         }
 
         let native_response: NativeChatResponse = response.json().await?;
-        let usage = native_response.usage.map(|u| TokenUsage {
-            input_tokens: u.prompt_tokens,
-            output_tokens: u.completion_tokens,
+        let usage = native_response.usage.map(|u| {
+            TokenUsage::from_openai_counts(
+                u.prompt_tokens,
+                u.completion_tokens,
+                u.prompt_tokens_details.and_then(|d| d.cached_tokens),
+            )
         });
         let message = native_response
             .choices
@@ -384,6 +522,45 @@ impl Provider for OpenAiProvider {
         Ok(result)
     }
 
+    async fn stream_chat(
+        &self,
+        request: ProviderChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<stream::BoxStream<'static, StreamResult<StreamEvent>>> {
+        let credential = self.credential.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("OpenAI API key not set. Set OPENAI_API_KEY or edit config.toml.")
+        })?;
+
+        let tools = Self::convert_tools(request.tools);
+        let native_request = NativeChatRequest {
+            model: model.to_string(),
+            messages: Self::convert_messages(request.messages),
+            temperature,
+            tool_choice: tools.as_ref().map(|_| "auto".to_string()),
+            tools,
+            stream: Some(true),
+            stream_options: Some(NativeStreamOptions {
+                include_usage: true,
+            }),
+        };
+
+        let response = self
+            .http_client()
+            .post(format!("{}/chat/completions", self.base_url))
+            .header("Authorization", format!("Bearer {credential}"))
+            .header("Accept", "text/event-stream")
+            .json(&native_request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(super::api_error("OpenAI", response).await);
+        }
+
+        Ok(super::streaming::openai_event_stream(response))
+    }
+
     fn supports_native_tools(&self) -> bool {
         true
     }
@@ -417,6 +594,8 @@ impl Provider for OpenAiProvider {
             temperature,
             tool_choice: native_tools.as_ref().map(|_| "auto".to_string()),
             tools: native_tools,
+            stream: None,
+            stream_options: None,
         };
 
         let response = self
@@ -432,9 +611,12 @@ impl Provider for OpenAiProvider {
         }
 
         let native_response: NativeChatResponse = response.json().await?;
-        let usage = native_response.usage.map(|u| TokenUsage {
-            input_tokens: u.prompt_tokens,
-            output_tokens: u.completion_tokens,
+        let usage = native_response.usage.map(|u| {
+            TokenUsage::from_openai_counts(
+                u.prompt_tokens,
+                u.completion_tokens,
+                u.prompt_tokens_details.and_then(|d| d.cached_tokens),
+            )
         });
         let message = native_response
             .choices
@@ -719,4 +901,43 @@ mod tests {
         let resp: NativeChatResponse = serde_json::from_str(json).unwrap();
         assert!(resp.usage.is_none());
     }
+
+    #[test]
+    fn convert_messages_maps_typed_parts() {
+        let messages = vec![
+            ChatMessage::with_parts(
+                "user",
+                vec![
+                    ContentPart::text("Describe"),
+                    ContentPart::image(crate::providers::MediaSource::Url {
+                        url: "https://example.com/cat.png".to_string(),
+                    }),
+                ],
+            ),
+            ChatMessage::assistant_tool_calls(
+                None,
+                &[ProviderToolCall {
+                    id: "call_1".to_string(),
+                    name: "shell".to_string(),
+                    arguments: "{}".to_string(),
+                }],
+            ),
+            ChatMessage::tool_result("call_1", "done"),
+        ];
+
+        let native = OpenAiProvider::convert_messages(&messages);
+        let json = serde_json::to_value(&native).unwrap();
+
+        assert_eq!(json[0]["content"][0]["type"], "text");
+        assert_eq!(json[0]["content"][1]["type"], "image_url");
+        assert_eq!(
+            json[0]["content"][1]["image_url"]["url"],
+            "https://example.com/cat.png"
+        );
+        assert!(json[1].get("content").is_none());
+        assert_eq!(json[1]["tool_calls"][0]["id"], "call_1");
+        assert_eq!(json[2]["role"], "tool");
+        assert_eq!(json[2]["tool_call_id"], "call_1");
+        assert_eq!(json[2]["content"], "done");
+    }
 }
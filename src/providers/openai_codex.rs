@@ -607,18 +607,22 @@ data: [DONE]
             ChatMessage {
                 role: "system".into(),
                 content: "You are helpful.".into(),
+                parts: Vec::new(),
             },
             ChatMessage {
                 role: "user".into(),
                 content: "Hi".into(),
+                parts: Vec::new(),
             },
             ChatMessage {
                 role: "assistant".into(),
                 content: "Hello!".into(),
+                parts: Vec::new(),
             },
             ChatMessage {
                 role: "user".into(),
                 content: "Thanks".into(),
+                parts: Vec::new(),
             },
         ];
         let (instructions, input) = build_responses_input(&messages);
@@ -642,6 +646,7 @@ data: [DONE]
         let messages = vec![ChatMessage {
             role: "user".into(),
             content: "Hello".into(),
+            parts: Vec::new(),
         }];
         let (instructions, input) = build_responses_input(&messages);
         assert_eq!(instructions, DEFAULT_CODEX_INSTRUCTIONS);
@@ -654,10 +659,12 @@ data: [DONE]
             ChatMessage {
                 role: "tool".into(),
                 content: "result".into(),
+                parts: Vec::new(),
             },
             ChatMessage {
                 role: "user".into(),
                 content: "Go".into(),
+                parts: Vec::new(),
             },
         ];
         let (instructions, input) = build_responses_input(&messages);
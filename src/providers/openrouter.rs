@@ -1,10 +1,12 @@
 use crate::multimodal;
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, ProviderCapabilities, TokenUsage, ToolCall as ProviderToolCall,
+    Provider, ProviderCapabilities, StreamEvent, StreamResult, TokenUsage,
+    ToolCall as ProviderToolCall,
 };
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
+use futures_util::stream;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
@@ -68,6 +70,15 @@ struct NativeChatRequest {
     tools: Option<Vec<NativeToolSpec>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tool_choice: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<NativeStreamOptions>,
+}
+
+#[derive(Debug, Serialize)]
+struct NativeStreamOptions {
+    include_usage: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -123,6 +134,14 @@ struct UsageInfo {
     prompt_tokens: Option<u64>,
     #[serde(default)]
     completion_tokens: Option<u64>,
+    #[serde(default)]
+    prompt_tokens_details: Option<PromptTokensDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PromptTokensDetails {
+    #[serde(default)]
+    cached_tokens: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -276,6 +295,7 @@ impl OpenRouterProvider {
             text: message.content,
             tool_calls,
             usage: None,
+            routed_to: None,
         }
     }
 
@@ -433,6 +453,8 @@ impl Provider for OpenRouterProvider {
             temperature,
             tool_choice: tools.as_ref().map(|_| "auto".to_string()),
             tools,
+            stream: None,
+            stream_options: None,
         };
 
         let response = self
@@ -453,9 +475,12 @@ impl Provider for OpenRouterProvider {
         }
 
         let native_response: NativeChatResponse = response.json().await?;
-        let usage = native_response.usage.map(|u| TokenUsage {
-            input_tokens: u.prompt_tokens,
-            output_tokens: u.completion_tokens,
+        let usage = native_response.usage.map(|u| {
+            TokenUsage::from_openai_counts(
+                u.prompt_tokens,
+                u.completion_tokens,
+                u.prompt_tokens_details.and_then(|d| d.cached_tokens),
+            )
         });
         let message = native_response
             .choices
@@ -468,6 +493,52 @@ impl Provider for OpenRouterProvider {
         Ok(result)
     }
 
+    async fn stream_chat(
+        &self,
+        request: ProviderChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<stream::BoxStream<'static, StreamResult<StreamEvent>>> {
+        let credential = self.credential.as_ref().ok_or_else(|| {
+            anyhow::anyhow!(
+            "OpenRouter API key not set. Run `zeroclaw onboard` or set OPENROUTER_API_KEY env var."
+        )
+        })?;
+
+        let tools = Self::convert_tools(request.tools);
+        let native_request = NativeChatRequest {
+            model: model.to_string(),
+            messages: Self::convert_messages(request.messages),
+            temperature,
+            tool_choice: tools.as_ref().map(|_| "auto".to_string()),
+            tools,
+            stream: Some(true),
+            stream_options: Some(NativeStreamOptions {
+                include_usage: true,
+            }),
+        };
+
+        let response = self
+            .http_client()
+            .post("https://openrouter.ai/api/v1/chat/completions")
+            .header("Authorization", format!("Bearer {credential}"))
+            .header(
+                "HTTP-Referer",
+                "https://github.com/theonlyhennygod/zeroclaw",
+            )
+            .header("X-Title", "ZeroClaw")
+            .header("Accept", "text/event-stream")
+            .json(&native_request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(super::api_error("OpenRouter", response).await);
+        }
+
+        Ok(super::streaming::openai_event_stream(response))
+    }
+
     fn supports_native_tools(&self) -> bool {
         true
     }
@@ -527,6 +598,8 @@ impl Provider for OpenRouterProvider {
             temperature,
             tool_choice: native_tools.as_ref().map(|_| "auto".to_string()),
             tools: native_tools,
+            stream: None,
+            stream_options: None,
         };
 
         let response = self
@@ -547,9 +620,12 @@ impl Provider for OpenRouterProvider {
         }
 
         let native_response: NativeChatResponse = response.json().await?;
-        let usage = native_response.usage.map(|u| TokenUsage {
-            input_tokens: u.prompt_tokens,
-            output_tokens: u.completion_tokens,
+        let usage = native_response.usage.map(|u| {
+            TokenUsage::from_openai_counts(
+                u.prompt_tokens,
+                u.completion_tokens,
+                u.prompt_tokens_details.and_then(|d| d.cached_tokens),
+            )
         });
         let message = native_response
             .choices
@@ -616,10 +692,12 @@ mod tests {
             ChatMessage {
                 role: "system".into(),
                 content: "be concise".into(),
+                parts: Vec::new(),
             },
             ChatMessage {
                 role: "user".into(),
                 content: "hello".into(),
+                parts: Vec::new(),
             },
         ];
 
@@ -662,10 +740,12 @@ mod tests {
             ChatMessage {
                 role: "assistant".into(),
                 content: "Previous answer".into(),
+                parts: Vec::new(),
             },
             ChatMessage {
                 role: "user".into(),
                 content: "Follow-up".into(),
+                parts: Vec::new(),
             },
         ];
 
@@ -712,6 +792,7 @@ mod tests {
         let messages = vec![ChatMessage {
             role: "user".into(),
             content: "What is the date?".into(),
+            parts: Vec::new(),
         }];
         let tools = vec![serde_json::json!({
             "type": "function",
@@ -806,6 +887,7 @@ mod tests {
             role: "assistant".into(),
             content: r#"{"content":"Using tool","tool_calls":[{"id":"call_abc","name":"shell","arguments":"{\"command\":\"pwd\"}"}]}"#
                 .into(),
+            parts: Vec::new(),
         }];
 
         let converted = OpenRouterProvider::convert_messages(&messages);
@@ -834,6 +916,7 @@ mod tests {
         let messages = vec![ChatMessage {
             role: "tool".into(),
             content: r#"{"tool_call_id":"call_xyz","content":"done"}"#.into(),
+            parts: Vec::new(),
         }];
 
         let converted = OpenRouterProvider::convert_messages(&messages);
@@ -1,12 +1,14 @@
+use super::health::{ProbeGuard, ProviderHealth};
 use super::traits::{
-    ChatMessage, ChatRequest, ChatResponse, StreamChunk, StreamOptions, StreamResult,
+    ChatMessage, ChatRequest, ChatResponse, StreamChunk, StreamEvent, StreamOptions, StreamResult,
 };
 use super::Provider;
 use async_trait::async_trait;
 use futures_util::{stream, StreamExt};
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 // ── Error Classification ─────────────────────────────────────────────────
 // Errors are split into retryable (transient server/network failures) and
@@ -220,6 +222,21 @@ fn push_failure(
 //                backoff, rotating API keys on rate-limit errors.
 // Loop invariant: `failures` accumulates every failed attempt so the final
 // error message gives operators a complete diagnostic trail.
+// With a health tracker attached, providers within each model are tried
+// healthiest first and attempts against open circuits are skipped.
+
+/// A named provider in the fallback chain.
+type NamedProvider = (String, Box<dyn Provider>);
+
+/// Candidates for one request. The model chain order is kept; providers
+/// within each model are ordered healthiest first.
+struct RoutePlan<'a> {
+    steps: Vec<(&'a str, Vec<&'a NamedProvider>)>,
+    /// Breakers are disabled, or every candidate's circuit is open. In the
+    /// latter case the chain is tried anyway rather than failing without a
+    /// single call.
+    bypass_breakers: bool,
+}
 
 /// Provider wrapper with retry, fallback, auth rotation, and model failover.
 pub struct ReliableProvider {
@@ -231,6 +248,8 @@ pub struct ReliableProvider {
     key_index: AtomicUsize,
     /// Per-model fallback chains: model_name → [fallback_model_1, fallback_model_2, ...]
     model_fallbacks: HashMap<String, Vec<String>>,
+    /// Circuit breakers and rolling health windows (None = plain failover).
+    health: Option<Arc<ProviderHealth>>,
 }
 
 impl ReliableProvider {
@@ -246,6 +265,7 @@ impl ReliableProvider {
             api_keys: Vec::new(),
             key_index: AtomicUsize::new(0),
             model_fallbacks: HashMap::new(),
+            health: None,
         }
     }
 
@@ -261,6 +281,103 @@ impl ReliableProvider {
         self
     }
 
+    /// Record call outcomes in `health` and route by its circuit breakers.
+    pub fn with_health(mut self, health: Arc<ProviderHealth>) -> Self {
+        self.health = Some(health);
+        self
+    }
+
+    fn active_health(&self) -> Option<&ProviderHealth> {
+        self.health.as_deref().filter(|health| health.is_enabled())
+    }
+
+    fn route_plan<'a>(&'a self, model: &'a str) -> RoutePlan<'a> {
+        let Some(health) = self.active_health() else {
+            return RoutePlan {
+                steps: self
+                    .model_chain(model)
+                    .into_iter()
+                    .map(|current_model| (current_model, self.providers.iter().collect()))
+                    .collect(),
+                bypass_breakers: true,
+            };
+        };
+
+        let mut all_open = true;
+        let steps = self
+            .model_chain(model)
+            .into_iter()
+            .map(|current_model| {
+                let mut providers: Vec<_> = self.providers.iter().collect();
+                // Stable: equally healthy providers keep their configured order.
+                providers.sort_by_cached_key(|(name, _)| health.rank(name, current_model));
+                all_open &= providers
+                    .iter()
+                    .all(|(name, _)| health.is_open(name, current_model));
+                (current_model, providers)
+            })
+            .collect();
+        if all_open {
+            tracing::warn!(
+                model,
+                "All provider circuits are open; trying the chain anyway"
+            );
+        }
+        RoutePlan {
+            steps,
+            bypass_breakers: all_open,
+        }
+    }
+
+    /// Admit an attempt, claiming the half-open probe slot, or `None` while
+    /// the circuit is open. The guard frees the slot if the attempt is
+    /// dropped before its outcome is recorded.
+    fn admit(
+        &self,
+        plan: &RoutePlan<'_>,
+        provider_name: &str,
+        model: &str,
+    ) -> Option<ProbeGuard<'_>> {
+        match self.active_health() {
+            Some(health) if !plan.bypass_breakers => health.try_acquire(provider_name, model),
+            _ => Some(ProbeGuard::unclaimed()),
+        }
+    }
+
+    fn record_success(
+        &self,
+        probe: ProbeGuard<'_>,
+        provider_name: &str,
+        model: &str,
+        started: Instant,
+    ) {
+        probe.disarm();
+        if let Some(health) = &self.health {
+            health.record_success(provider_name, model, started.elapsed());
+        }
+    }
+
+    fn record_failure(
+        &self,
+        probe: ProbeGuard<'_>,
+        provider_name: &str,
+        model: &str,
+        started: Instant,
+        err: &anyhow::Error,
+        error_detail: &str,
+    ) {
+        probe.disarm();
+        let Some(health) = &self.health else {
+            return;
+        };
+        // An oversized request says nothing about the provider's health.
+        if is_context_window_exceeded(err) {
+            health.release(provider_name, model);
+        } else {
+            health.record_failure(provider_name, model, started.elapsed(), error_detail);
+        }
+    }
+
     /// Build the list of models to try: [original, fallback1, fallback2, ...]
     fn model_chain<'a>(&'a self, model: &'a str) -> Vec<&'a str> {
         let mut chain = vec![model];
@@ -288,44 +405,43 @@ impl ReliableProvider {
             base
         }
     }
-}
-
-#[async_trait]
-impl Provider for ReliableProvider {
-    async fn warmup(&self) -> anyhow::Result<()> {
-        for (name, provider) in &self.providers {
-            tracing::info!(provider = name, "Warming up provider connection pool");
-            if provider.warmup().await.is_err() {
-                tracing::warn!(provider = name, "Warmup failed (non-fatal)");
-            }
-        }
-        Ok(())
-    }
 
-    async fn chat_with_system(
-        &self,
-        system_prompt: Option<&str>,
-        message: &str,
-        model: &str,
-        temperature: f64,
-    ) -> anyhow::Result<String> {
-        let models = self.model_chain(model);
+    /// Run `call` across the model fallback chain and providers with
+    /// retries, backoff and circuit breaking, returning the first success.
+    async fn with_failover<'s, T, F, Fut>(
+        &'s self,
+        model: &'s str,
+        mut call: F,
+    ) -> anyhow::Result<T>
+    where
+        F: FnMut(&'s dyn Provider, &'s str) -> Fut + Send,
+        Fut: std::future::Future<Output = anyhow::Result<T>> + Send,
+        T: Send,
+    {
+        let plan = self.route_plan(model);
         let mut failures = Vec::new();
 
-        // Outer: model fallback chain. Middle: provider priority. Inner: retries.
-        // Each iteration: attempt one (provider, model) call. On success, return
-        // immediately. On non-retryable error, break to next provider. On
-        // retryable error, sleep with exponential backoff and retry.
-        for current_model in &models {
-            for (provider_name, provider) in &self.providers {
+        for (current_model, providers) in &plan.steps {
+            for (provider_name, provider) in providers {
                 let mut backoff_ms = self.base_backoff_ms;
 
                 for attempt in 0..=self.max_retries {
-                    match provider
-                        .chat_with_system(system_prompt, message, current_model, temperature)
-                        .await
-                    {
+                    let Some(probe) = self.admit(&plan, provider_name, current_model) else {
+                        push_failure(
+                            &mut failures,
+                            provider_name,
+                            current_model,
+                            attempt + 1,
+                            self.max_retries + 1,
+                            "circuit_open",
+                            "skipped while the circuit breaker is open",
+                        );
+                        break;
+                    };
+                    let started = Instant::now();
+                    match call(provider.as_ref(), current_model).await {
                         Ok(resp) => {
+                            self.record_success(probe, provider_name, current_model, started);
                             if attempt > 0 || *current_model != model {
                                 tracing::info!(
                                     provider = provider_name,
@@ -343,6 +459,14 @@ impl Provider for ReliableProvider {
                             let rate_limited = is_rate_limited(&e);
                             let failure_reason = failure_reason(rate_limited, non_retryable);
                             let error_detail = compact_error_detail(&e);
+                            self.record_failure(
+                                probe,
+                                provider_name,
+                                current_model,
+                                started,
+                                &e,
+                                &error_detail,
+                            );
 
                             push_failure(
                                 &mut failures,
@@ -354,8 +478,6 @@ impl Provider for ReliableProvider {
                                 &error_detail,
                             );
 
-                            // Rate-limit with rotatable keys: cycle to the next API key
-                            // so the retry hits a different quota bucket.
                             if rate_limited && !non_retryable_rate_limit {
                                 if let Some(new_key) = self.rotate_key() {
                                     tracing::warn!(
@@ -426,26 +548,58 @@ impl Provider for ReliableProvider {
             failures.join("\n")
         )
     }
+}
 
-    async fn chat_with_history(
+#[async_trait]
+impl Provider for ReliableProvider {
+    async fn warmup(&self) -> anyhow::Result<()> {
+        for (name, provider) in &self.providers {
+            tracing::info!(provider = name, "Warming up provider connection pool");
+            if provider.warmup().await.is_err() {
+                tracing::warn!(provider = name, "Warmup failed (non-fatal)");
+            }
+        }
+        Ok(())
+    }
+
+    async fn chat_with_system(
         &self,
-        messages: &[ChatMessage],
+        system_prompt: Option<&str>,
+        message: &str,
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
-        let models = self.model_chain(model);
+        let plan = self.route_plan(model);
         let mut failures = Vec::new();
 
-        for current_model in &models {
-            for (provider_name, provider) in &self.providers {
+        // Outer: model fallback chain. Middle: provider priority. Inner: retries.
+        // Each iteration: attempt one (provider, model) call. On success, return
+        // immediately. On non-retryable error, break to next provider. On
+        // retryable error, sleep with exponential backoff and retry.
+        for (current_model, providers) in &plan.steps {
+            for (provider_name, provider) in providers {
                 let mut backoff_ms = self.base_backoff_ms;
 
                 for attempt in 0..=self.max_retries {
+                    let Some(probe) = self.admit(&plan, provider_name, current_model) else {
+                        push_failure(
+                            &mut failures,
+                            provider_name,
+                            current_model,
+                            attempt + 1,
+                            self.max_retries + 1,
+                            "circuit_open",
+                            "skipped while the circuit breaker is open",
+                        );
+                        break;
+                    };
+                    let started = Instant::now();
                     match provider
-                        .chat_with_history(messages, current_model, temperature)
+                        .chat_with_system(system_prompt, message, current_model, temperature)
                         .await
                     {
                         Ok(resp) => {
+                            self.record_success(probe, provider_name, current_model, started);
                             if attempt > 0 || *current_model != model {
                                 tracing::info!(
                                     provider = provider_name,
@@ -463,6 +617,14 @@ impl Provider for ReliableProvider {
                             let rate_limited = is_rate_limited(&e);
                             let failure_reason = failure_reason(rate_limited, non_retryable);
                             let error_detail = compact_error_detail(&e);
+                            self.record_failure(
+                                probe,
+                                provider_name,
+                                current_model,
+                                started,
+                                &e,
+                                &error_detail,
+                            );
 
                             push_failure(
                                 &mut failures,
@@ -474,6 +636,8 @@ impl Provider for ReliableProvider {
                                 &error_detail,
                             );
 
+                            // Rate-limit with rotatable keys: cycle to the next API key
+                            // so the retry hits a different quota bucket.
                             if rate_limited && !non_retryable_rate_limit {
                                 if let Some(new_key) = self.rotate_key() {
                                     tracing::warn!(
@@ -529,6 +693,14 @@ impl Provider for ReliableProvider {
                     "Exhausted retries, trying next provider/model"
                 );
             }
+
+            if *current_model != model {
+                tracing::warn!(
+                    original_model = model,
+                    fallback_model = *current_model,
+                    "Model fallback exhausted all providers, trying next fallback model"
+                );
+            }
         }
 
         anyhow::bail!(
@@ -537,39 +709,39 @@ impl Provider for ReliableProvider {
         )
     }
 
-    fn supports_native_tools(&self) -> bool {
-        self.providers
-            .first()
-            .map(|(_, p)| p.supports_native_tools())
-            .unwrap_or(false)
-    }
-
-    fn supports_vision(&self) -> bool {
-        self.providers
-            .iter()
-            .any(|(_, provider)| provider.supports_vision())
-    }
-
-    async fn chat_with_tools(
+    async fn chat_with_history(
         &self,
         messages: &[ChatMessage],
-        tools: &[serde_json::Value],
         model: &str,
         temperature: f64,
-    ) -> anyhow::Result<ChatResponse> {
-        let models = self.model_chain(model);
+    ) -> anyhow::Result<String> {
+        let plan = self.route_plan(model);
         let mut failures = Vec::new();
 
-        for current_model in &models {
-            for (provider_name, provider) in &self.providers {
+        for (current_model, providers) in &plan.steps {
+            for (provider_name, provider) in providers {
                 let mut backoff_ms = self.base_backoff_ms;
 
                 for attempt in 0..=self.max_retries {
+                    let Some(probe) = self.admit(&plan, provider_name, current_model) else {
+                        push_failure(
+                            &mut failures,
+                            provider_name,
+                            current_model,
+                            attempt + 1,
+                            self.max_retries + 1,
+                            "circuit_open",
+                            "skipped while the circuit breaker is open",
+                        );
+                        break;
+                    };
+                    let started = Instant::now();
                     match provider
-                        .chat_with_tools(messages, tools, current_model, temperature)
+                        .chat_with_history(messages, current_model, temperature)
                         .await
                     {
                         Ok(resp) => {
+                            self.record_success(probe, provider_name, current_model, started);
                             if attempt > 0 || *current_model != model {
                                 tracing::info!(
                                     provider = provider_name,
@@ -587,6 +759,14 @@ impl Provider for ReliableProvider {
                             let rate_limited = is_rate_limited(&e);
                             let failure_reason = failure_reason(rate_limited, non_retryable);
                             let error_detail = compact_error_detail(&e);
+                            self.record_failure(
+                                probe,
+                                provider_name,
+                                current_model,
+                                started,
+                                &e,
+                                &error_detail,
+                            );
 
                             push_failure(
                                 &mut failures,
@@ -661,26 +841,53 @@ impl Provider for ReliableProvider {
         )
     }
 
-    async fn chat(
+    fn supports_native_tools(&self) -> bool {
+        self.providers
+            .first()
+            .map(|(_, p)| p.supports_native_tools())
+            .unwrap_or(false)
+    }
+
+    fn supports_vision(&self) -> bool {
+        self.providers
+            .iter()
+            .any(|(_, provider)| provider.supports_vision())
+    }
+
+    async fn chat_with_tools(
         &self,
-        request: ChatRequest<'_>,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ChatResponse> {
-        let models = self.model_chain(model);
+        let plan = self.route_plan(model);
         let mut failures = Vec::new();
 
-        for current_model in &models {
-            for (provider_name, provider) in &self.providers {
+        for (current_model, providers) in &plan.steps {
+            for (provider_name, provider) in providers {
                 let mut backoff_ms = self.base_backoff_ms;
 
                 for attempt in 0..=self.max_retries {
-                    let req = ChatRequest {
-                        messages: request.messages,
-                        tools: request.tools,
+                    let Some(probe) = self.admit(&plan, provider_name, current_model) else {
+                        push_failure(
+                            &mut failures,
+                            provider_name,
+                            current_model,
+                            attempt + 1,
+                            self.max_retries + 1,
+                            "circuit_open",
+                            "skipped while the circuit breaker is open",
+                        );
+                        break;
                     };
-                    match provider.chat(req, current_model, temperature).await {
+                    let started = Instant::now();
+                    match provider
+                        .chat_with_tools(messages, tools, current_model, temperature)
+                        .await
+                    {
                         Ok(resp) => {
+                            self.record_success(probe, provider_name, current_model, started);
                             if attempt > 0 || *current_model != model {
                                 tracing::info!(
                                     provider = provider_name,
@@ -698,6 +905,14 @@ impl Provider for ReliableProvider {
                             let rate_limited = is_rate_limited(&e);
                             let failure_reason = failure_reason(rate_limited, non_retryable);
                             let error_detail = compact_error_detail(&e);
+                            self.record_failure(
+                                probe,
+                                provider_name,
+                                current_model,
+                                started,
+                                &e,
+                                &error_detail,
+                            );
 
                             push_failure(
                                 &mut failures,
@@ -764,14 +979,6 @@ impl Provider for ReliableProvider {
                     "Exhausted retries, trying next provider/model"
                 );
             }
-
-            if *current_model != model {
-                tracing::warn!(
-                    original_model = model,
-                    fallback_model = *current_model,
-                    "Model fallback exhausted all providers, trying next fallback model"
-                );
-            }
         }
 
         anyhow::bail!(
@@ -780,6 +987,41 @@ impl Provider for ReliableProvider {
         )
     }
 
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        self.with_failover(model, |provider, current_model| {
+            let req = ChatRequest {
+                messages: request.messages,
+                tools: request.tools,
+            };
+            provider.chat(req, current_model, temperature)
+        })
+        .await
+    }
+
+    async fn stream_chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<stream::BoxStream<'static, StreamResult<StreamEvent>>> {
+        // Retries and failover apply until a stream is established. Errors
+        // after the first event are surfaced to the caller, since partial
+        // output may already have been shown.
+        self.with_failover(model, |provider, current_model| {
+            let req = ChatRequest {
+                messages: request.messages,
+                tools: request.tools,
+            };
+            provider.stream_chat(req, current_model, temperature)
+        })
+        .await
+    }
+
     fn supports_streaming(&self) -> bool {
         self.providers.iter().any(|(_, p)| p.supports_streaming())
     }
@@ -1670,6 +1912,7 @@ mod tests {
                 text: Some(self.response_text.to_string()),
                 tool_calls: self.tool_calls.clone(),
                 usage: None,
+                routed_to: None,
             })
         }
     }
@@ -1862,6 +2105,7 @@ mod tests {
                 text: Some(self.response_text.to_string()),
                 tool_calls: vec![],
                 usage: None,
+                routed_to: None,
             })
         }
     }
@@ -1978,4 +2222,181 @@ mod tests {
         assert_eq!(primary_calls.load(Ordering::SeqCst), 1);
         assert_eq!(fallback_calls.load(Ordering::SeqCst), 1);
     }
+
+    // ── Circuit breaker tests ──
+
+    fn breaker_health(min_requests: u32, failure_rate_threshold: f64) -> Arc<ProviderHealth> {
+        Arc::new(ProviderHealth::new(crate::config::CircuitBreakerConfig {
+            enabled: true,
+            min_requests,
+            failure_rate_threshold,
+            open_secs: 3600,
+            ..crate::config::CircuitBreakerConfig::default()
+        }))
+    }
+
+    #[tokio::test]
+    async fn open_circuit_stops_retries_and_skips_provider() {
+        let primary_calls = Arc::new(AtomicUsize::new(0));
+        let fallback_calls = Arc::new(AtomicUsize::new(0));
+        let health = breaker_health(2, 0.5);
+        let provider = ReliableProvider::new(
+            vec![
+                (
+                    "primary".into(),
+                    Box::new(MockProvider {
+                        calls: Arc::clone(&primary_calls),
+                        fail_until_attempt: usize::MAX,
+                        response: "never",
+                        error: "500 primary down",
+                    }),
+                ),
+                (
+                    "fallback".into(),
+                    Box::new(MockProvider {
+                        calls: Arc::clone(&fallback_calls),
+                        fail_until_attempt: 0,
+                        response: "from fallback",
+                        error: "fallback down",
+                    }),
+                ),
+            ],
+            3,
+            1,
+        )
+        .with_health(Arc::clone(&health));
+
+        let result = provider.simple_chat("hello", "test", 0.0).await.unwrap();
+        assert_eq!(result, "from fallback");
+        // The circuit opened after two failures, cutting the retries short.
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 2);
+        assert!(health.is_open("primary", "test"));
+
+        let result = provider.simple_chat("again", "test", 0.0).await.unwrap();
+        assert_eq!(result, "from fallback");
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 2);
+        assert_eq!(fallback_calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn healthier_provider_is_tried_first() {
+        let primary_calls = Arc::new(AtomicUsize::new(0));
+        let fallback_calls = Arc::new(AtomicUsize::new(0));
+        let health = breaker_health(1, 0.9);
+        health.record_success("primary", "test", Duration::from_millis(5));
+        health.record_failure("primary", "test", Duration::from_millis(5), "500");
+
+        let provider = ReliableProvider::new(
+            vec![
+                (
+                    "primary".into(),
+                    Box::new(MockProvider {
+                        calls: Arc::clone(&primary_calls),
+                        fail_until_attempt: 0,
+                        response: "from primary",
+                        error: "unused",
+                    }),
+                ),
+                (
+                    "fallback".into(),
+                    Box::new(MockProvider {
+                        calls: Arc::clone(&fallback_calls),
+                        fail_until_attempt: 0,
+                        response: "from fallback",
+                        error: "unused",
+                    }),
+                ),
+            ],
+            1,
+            1,
+        )
+        .with_health(health);
+
+        let result = provider.simple_chat("hello", "test", 0.0).await.unwrap();
+        assert_eq!(result, "from fallback");
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 0);
+        assert_eq!(fallback_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn all_circuits_open_still_tries_the_chain() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let health = breaker_health(1, 0.5);
+        health.record_failure("primary", "test", Duration::from_millis(5), "500");
+        assert!(health.is_open("primary", "test"));
+
+        let provider = ReliableProvider::new(
+            vec![(
+                "primary".into(),
+                Box::new(MockProvider {
+                    calls: Arc::clone(&calls),
+                    fail_until_attempt: 0,
+                    response: "ok",
+                    error: "unused",
+                }),
+            )],
+            1,
+            1,
+        )
+        .with_health(health);
+
+        let result = provider.simple_chat("hello", "test", 0.0).await.unwrap();
+        assert_eq!(result, "ok");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    /// Mock whose calls never finish.
+    struct HangingProvider {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Provider for HangingProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            std::future::pending().await
+        }
+    }
+
+    #[tokio::test]
+    async fn dropped_call_frees_half_open_probe() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let health = Arc::new(ProviderHealth::new(crate::config::CircuitBreakerConfig {
+            enabled: true,
+            min_requests: 1,
+            failure_rate_threshold: 0.5,
+            open_secs: 0,
+            ..crate::config::CircuitBreakerConfig::default()
+        }));
+        // Tripped, and half-open again on the next look.
+        health.record_failure("primary", "test", Duration::from_millis(5), "500");
+
+        let provider = ReliableProvider::new(
+            vec![(
+                "primary".into(),
+                Box::new(HangingProvider {
+                    calls: Arc::clone(&calls),
+                }) as Box<dyn Provider>,
+            )],
+            0,
+            1,
+        )
+        .with_health(Arc::clone(&health));
+
+        for _ in 0..2 {
+            let call = provider.simple_chat("hello", "test", 0.0);
+            assert!(tokio::time::timeout(Duration::from_millis(20), call)
+                .await
+                .is_err());
+        }
+        // Each cancelled probe gave its slot back, so the second call went out.
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(health.try_acquire("primary", "test").is_some());
+    }
 }
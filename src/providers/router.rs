@@ -1,10 +1,16 @@
-use super::traits::{ChatMessage, ChatRequest, ChatResponse};
+use super::traits::{ChatMessage, ChatRequest, ChatResponse, StreamEvent, StreamResult};
 use super::Provider;
+use crate::config::schema::{AutoRoutingConfig, ModelPricing, QueryClassificationConfig};
+use crate::cost::CostTracker;
 use async_trait::async_trait;
+use futures_util::{stream, StreamExt};
 use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Instant;
 
 /// A single route: maps a task hint to a provider + model combo.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Route {
     pub provider_name: String,
     pub model: String,
@@ -23,6 +29,242 @@ pub struct RouterProvider {
     providers: Vec<(String, Box<dyn Provider>)>,
     default_index: usize,
     default_model: String,
+    auto: Option<AutoRouter>,
+}
+
+/// Inputs for automatic routing of default-model requests (`[auto_routing]`).
+pub struct AutoRouting {
+    pub config: AutoRoutingConfig,
+    /// Classifier rules; a matching hint sets the weakest route considered.
+    pub classification: QueryClassificationConfig,
+    /// Source of `[cost.prices]` and observed per-model latency and success.
+    pub tracker: Option<Arc<CostTracker>>,
+}
+
+/// Output tokens assumed when estimating what a request will cost.
+const ESTIMATED_OUTPUT_TOKENS: f64 = 512.0;
+
+/// A route automatic routing may pick.
+struct AutoCandidate {
+    /// Route hint, or `default` for the default model.
+    label: String,
+    provider_index: usize,
+    model: String,
+    /// Unknown prices sort after every priced route.
+    pricing: Option<ModelPricing>,
+}
+
+impl AutoCandidate {
+    /// Estimated USD cost of sending `prompt_chars` characters to this route.
+    fn estimated_cost(&self, prompt_chars: usize) -> f64 {
+        #[allow(clippy::cast_precision_loss)]
+        let input_tokens = (prompt_chars / 4) as f64;
+        self.pricing.as_ref().map_or(f64::INFINITY, |pricing| {
+            (input_tokens * pricing.input + ESTIMATED_OUTPUT_TOKENS * pricing.output) / 1_000_000.0
+        })
+    }
+}
+
+/// The parts of a request that drive its complexity estimate.
+struct RequestProfile<'a> {
+    messages: usize,
+    tools: usize,
+    prompt_chars: usize,
+    last_user_message: Option<&'a str>,
+}
+
+impl<'a> RequestProfile<'a> {
+    fn from_messages(messages: &'a [ChatMessage], tools: usize) -> Self {
+        Self {
+            messages: messages.len(),
+            tools,
+            prompt_chars: messages.iter().map(|m| m.content.len()).sum(),
+            last_user_message: messages
+                .iter()
+                .rev()
+                .find(|m| m.role == "user")
+                .map(|m| m.content.as_str()),
+        }
+    }
+
+    fn from_prompt(system_prompt: Option<&str>, message: &'a str) -> Self {
+        Self {
+            messages: 1 + usize::from(system_prompt.is_some()),
+            tools: 0,
+            prompt_chars: system_prompt.map_or(0, str::len) + message.len(),
+            last_user_message: Some(message),
+        }
+    }
+
+    /// Complexity in 0.0–1.0 from tool count, history length and prompt size.
+    #[allow(clippy::cast_precision_loss)]
+    fn complexity(&self) -> f64 {
+        let saturate = |value: usize, full: usize| (value as f64 / full as f64).min(1.0);
+        0.4 * saturate(self.tools, 16)
+            + 0.3 * saturate(self.messages, 24)
+            + 0.3 * saturate(self.prompt_chars, 12_000)
+    }
+}
+
+struct AutoRouter {
+    config: AutoRoutingConfig,
+    classification: QueryClassificationConfig,
+    tracker: Option<Arc<CostTracker>>,
+    candidates: Vec<AutoCandidate>,
+}
+
+impl AutoRouter {
+    /// Whether observed latency and success rate allow picking `candidate`.
+    fn looks_healthy(&self, candidate: &AutoCandidate) -> bool {
+        let Some(stats) = self
+            .tracker
+            .as_ref()
+            .and_then(|tracker| tracker.model_performance(&candidate.model))
+        else {
+            return true;
+        };
+        if stats.requests < u64::from(self.config.min_samples) {
+            return true;
+        }
+        let fast_enough = self.config.max_latency_ms == 0
+            || stats
+                .avg_latency_ms()
+                .is_none_or(|latency| latency <= self.config.max_latency_ms);
+        stats.success_rate() >= self.config.min_success_rate && fast_enough
+    }
+
+    /// Candidates to try for one request, cheapest expected-to-succeed first
+    /// and then escalating to pricier routes.
+    fn plan(&self, profile: &RequestProfile<'_>) -> Vec<&AutoCandidate> {
+        let mut ordered: Vec<&AutoCandidate> = self.candidates.iter().collect();
+        ordered.sort_by(|a, b| {
+            a.estimated_cost(profile.prompt_chars)
+                .total_cmp(&b.estimated_cost(profile.prompt_chars))
+        });
+
+        // Complex requests skip the cheapest routes outright.
+        let last = ordered.len().saturating_sub(1);
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            clippy::cast_precision_loss
+        )]
+        let mut floor = (profile.complexity() * last as f64).round() as usize;
+        if let Some(hint) = profile
+            .last_user_message
+            .and_then(|message| crate::agent::classifier::classify(&self.classification, message))
+        {
+            if let Some(position) = ordered.iter().position(|c| c.label == hint) {
+                floor = floor.max(position);
+            }
+        }
+        let floor = floor.min(last);
+
+        let (healthy, unhealthy): (Vec<_>, Vec<_>) = ordered
+            .into_iter()
+            .skip(floor)
+            .partition(|candidate| self.looks_healthy(candidate));
+        let max_attempts = usize::try_from(self.config.max_escalations)
+            .unwrap_or(usize::MAX)
+            .saturating_add(1);
+        healthy
+            .into_iter()
+            .chain(unhealthy)
+            .take(max_attempts)
+            .collect()
+    }
+
+    fn record(&self, candidate: &AutoCandidate, started: Instant, success: bool) {
+        if let Some(tracker) = &self.tracker {
+            tracker.record_call_outcome(&candidate.model, started.elapsed(), success);
+        }
+    }
+}
+
+/// Whether `text` carries a `<tool_call>` block whose body is not valid JSON.
+fn has_unparseable_tool_call(text: &str) -> bool {
+    let mut rest = text;
+    while let Some(start) = rest.find("<tool_call>") {
+        let body = &rest[start + "<tool_call>".len()..];
+        let Some(end) = body.find("</tool_call>") else {
+            return true;
+        };
+        let json = body[..end]
+            .trim()
+            .trim_start_matches("```json")
+            .trim_start_matches("```")
+            .trim_end_matches("```")
+            .trim();
+        if serde_json::from_str::<serde_json::Value>(json).is_err() {
+            return true;
+        }
+        rest = &body[end..];
+    }
+    false
+}
+
+/// Whether a native response names an unknown tool or carries arguments
+/// that are not valid JSON. `tool_names` is empty when tools are unknown.
+fn has_bad_native_tool_call(response: &ChatResponse, tool_names: &[&str]) -> bool {
+    response.tool_calls.iter().any(|call| {
+        let args_ok = call.arguments.trim().is_empty()
+            || serde_json::from_str::<serde_json::Value>(&call.arguments).is_ok();
+        let name_ok = tool_names.is_empty() || tool_names.contains(&call.name.as_str());
+        !(args_ok && name_ok)
+    }) || response
+        .text
+        .as_deref()
+        .is_some_and(has_unparseable_tool_call)
+}
+
+/// Tag a response with the route that served it, unless an inner router
+/// already did.
+fn with_route(mut response: ChatResponse, route: Route) -> ChatResponse {
+    response.routed_to.get_or_insert(route);
+    response
+}
+
+/// Report a streamed auto-routed call to the cost tracker once the stream
+/// fails or ends, so streamed turns feed the same health data as
+/// [`RouterProvider::dispatch_auto`]. A stream dropped early reports nothing.
+fn record_stream_outcome(
+    events: stream::BoxStream<'static, StreamResult<StreamEvent>>,
+    tracker: Option<Arc<CostTracker>>,
+    model: String,
+    started: Instant,
+) -> stream::BoxStream<'static, StreamResult<StreamEvent>> {
+    let Some(tracker) = tracker else {
+        return events;
+    };
+    stream::unfold(
+        (events, Some((tracker, model))),
+        move |(mut events, mut pending)| async move {
+            let next = events.next().await;
+            let success = match &next {
+                Some(Err(_)) => Some(false),
+                Some(Ok(StreamEvent::Done)) | None => Some(true),
+                Some(Ok(_)) => None,
+            };
+            if let Some(success) = success {
+                if let Some((tracker, model)) = pending.take() {
+                    tracker.record_call_outcome(&model, started.elapsed(), success);
+                }
+            }
+            next.map(|event| (event, (events, pending)))
+        },
+    )
+    .boxed()
+}
+
+/// Lead a stream with the route that serves it, so usage is attributed to
+/// the routed model.
+fn announce_route(
+    events: stream::BoxStream<'static, StreamResult<StreamEvent>>,
+    route: Route,
+) -> stream::BoxStream<'static, StreamResult<StreamEvent>> {
+    stream::once(async move { Ok(StreamEvent::Routed(route)) })
+        .chain(events)
+        .boxed()
 }
 
 impl RouterProvider {
@@ -66,6 +308,128 @@ impl RouterProvider {
             providers,
             default_index: 0,
             default_model,
+            auto: None,
+        }
+    }
+
+    /// Route default-model requests automatically across the default model
+    /// and the configured routes (restricted to `config.hints` when set).
+    pub fn with_auto_routing(mut self, auto: AutoRouting) -> Self {
+        let price_of = |provider_index: usize, model: &str| {
+            auto.tracker.as_ref().and_then(|tracker| {
+                tracker
+                    .pricing(&self.providers[provider_index].0, model)
+                    .cloned()
+            })
+        };
+
+        let mut candidates = vec![AutoCandidate {
+            label: "default".to_string(),
+            provider_index: self.default_index,
+            model: self.default_model.clone(),
+            pricing: price_of(self.default_index, &self.default_model),
+        }];
+        let mut hints: Vec<&String> = self
+            .routes
+            .keys()
+            .filter(|hint| auto.config.hints.is_empty() || auto.config.hints.contains(hint))
+            .collect();
+        // HashMap order is random; keep ties between unpriced routes stable.
+        hints.sort();
+        for hint in hints {
+            let (provider_index, model) = &self.routes[hint];
+            if candidates
+                .iter()
+                .any(|c| c.provider_index == *provider_index && c.model == *model)
+            {
+                continue;
+            }
+            candidates.push(AutoCandidate {
+                label: hint.clone(),
+                provider_index: *provider_index,
+                model: model.clone(),
+                pricing: price_of(*provider_index, model),
+            });
+        }
+
+        self.auto = Some(AutoRouter {
+            config: auto.config,
+            classification: auto.classification,
+            tracker: auto.tracker,
+            candidates,
+        });
+        self
+    }
+
+    /// Automatic routing applies to requests for the default model only;
+    /// explicit models and `hint:` requests keep their fixed routes.
+    fn auto_for(&self, model: &str) -> Option<&AutoRouter> {
+        self.auto
+            .as_ref()
+            .filter(|auto| auto.config.enabled && model == self.default_model)
+    }
+
+    /// Try the planned candidates in order, escalating on errors and on
+    /// responses `is_usable` rejects. The last candidate's result is
+    /// returned as is, along with the route that produced it.
+    async fn dispatch_auto<T, F, Fut>(
+        &self,
+        auto: &AutoRouter,
+        profile: &RequestProfile<'_>,
+        call: F,
+        is_usable: impl Fn(&T) -> bool,
+    ) -> anyhow::Result<(T, Route)>
+    where
+        F: Fn(usize, String) -> Fut,
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let plan = auto.plan(profile);
+        let attempts = plan.len();
+        let mut last_error = None;
+
+        for (attempt, candidate) in plan.into_iter().enumerate() {
+            let is_last = attempt + 1 == attempts;
+            tracing::info!(
+                route = candidate.label.as_str(),
+                provider = self.providers[candidate.provider_index].0.as_str(),
+                model = candidate.model.as_str(),
+                attempt,
+                "Auto-routing request"
+            );
+            let started = Instant::now();
+            match call(candidate.provider_index, candidate.model.clone()).await {
+                Ok(response) => {
+                    let usable = is_usable(&response);
+                    auto.record(candidate, started, usable);
+                    if usable || is_last {
+                        return Ok((
+                            response,
+                            self.route_to(candidate.provider_index, &candidate.model),
+                        ));
+                    }
+                    tracing::warn!(
+                        route = candidate.label.as_str(),
+                        "Unparseable tool call, escalating to a stronger route"
+                    );
+                }
+                Err(error) => {
+                    auto.record(candidate, started, false);
+                    tracing::warn!(
+                        route = candidate.label.as_str(),
+                        "Routed call failed, escalating to a stronger route: {error}"
+                    );
+                    last_error = Some(error);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No auto-routing candidates available")))
+    }
+
+    fn route_to(&self, provider_index: usize, model: &str) -> Route {
+        Route {
+            provider_name: self.providers[provider_index].0.clone(),
+            model: model.to_string(),
         }
     }
 
@@ -99,6 +463,24 @@ impl Provider for RouterProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
+        if let Some(auto) = self.auto_for(model) {
+            let profile = RequestProfile::from_prompt(system_prompt, message);
+            return self
+                .dispatch_auto(
+                    auto,
+                    &profile,
+                    move |idx, model| async move {
+                        self.providers[idx]
+                            .1
+                            .chat_with_system(system_prompt, message, &model, temperature)
+                            .await
+                    },
+                    |text: &String| !has_unparseable_tool_call(text),
+                )
+                .await
+                .map(|(text, _)| text);
+        }
+
         let (provider_idx, resolved_model) = self.resolve(model);
 
         let (provider_name, provider) = &self.providers[provider_idx];
@@ -119,6 +501,24 @@ impl Provider for RouterProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
+        if let Some(auto) = self.auto_for(model) {
+            let profile = RequestProfile::from_messages(messages, 0);
+            return self
+                .dispatch_auto(
+                    auto,
+                    &profile,
+                    move |idx, model| async move {
+                        self.providers[idx]
+                            .1
+                            .chat_with_history(messages, &model, temperature)
+                            .await
+                    },
+                    |text: &String| !has_unparseable_tool_call(text),
+                )
+                .await
+                .map(|(text, _)| text);
+        }
+
         let (provider_idx, resolved_model) = self.resolve(model);
         let (_, provider) = &self.providers[provider_idx];
         provider
@@ -132,9 +532,79 @@ impl Provider for RouterProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ChatResponse> {
+        if let Some(auto) = self.auto_for(model) {
+            let tools = request.tools.unwrap_or_default();
+            let tool_names: Vec<&str> = tools.iter().map(|tool| tool.name.as_str()).collect();
+            let profile = RequestProfile::from_messages(request.messages, tools.len());
+            return self
+                .dispatch_auto(
+                    auto,
+                    &profile,
+                    move |idx, model| async move {
+                        self.providers[idx]
+                            .1
+                            .chat(request, &model, temperature)
+                            .await
+                    },
+                    |response: &ChatResponse| !has_bad_native_tool_call(response, &tool_names),
+                )
+                .await
+                .map(|(response, route)| with_route(response, route));
+        }
+
         let (provider_idx, resolved_model) = self.resolve(model);
+        let route = self.route_to(provider_idx, &resolved_model);
         let (_, provider) = &self.providers[provider_idx];
-        provider.chat(request, &resolved_model, temperature).await
+        provider
+            .chat(request, &resolved_model, temperature)
+            .await
+            .map(|response| with_route(response, route))
+    }
+
+    async fn stream_chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<stream::BoxStream<'static, StreamResult<StreamEvent>>> {
+        // A stream cannot be replayed on another route, so auto-routing only
+        // picks the first candidate here.
+        if let Some(auto) = self.auto_for(model) {
+            let profile = RequestProfile::from_messages(
+                request.messages,
+                request.tools.map_or(0, <[_]>::len),
+            );
+            if let Some(candidate) = auto.plan(&profile).first() {
+                let route = self.route_to(candidate.provider_index, &candidate.model);
+                let started = Instant::now();
+                let events = match self.providers[candidate.provider_index]
+                    .1
+                    .stream_chat(request, &candidate.model, temperature)
+                    .await
+                {
+                    Ok(events) => events,
+                    Err(error) => {
+                        auto.record(candidate, started, false);
+                        return Err(error);
+                    }
+                };
+                let events = record_stream_outcome(
+                    events,
+                    auto.tracker.clone(),
+                    candidate.model.clone(),
+                    started,
+                );
+                return Ok(announce_route(events, route));
+            }
+        }
+
+        let (provider_idx, resolved_model) = self.resolve(model);
+        let route = self.route_to(provider_idx, &resolved_model);
+        let (_, provider) = &self.providers[provider_idx];
+        provider
+            .stream_chat(request, &resolved_model, temperature)
+            .await
+            .map(|events| announce_route(events, route))
     }
 
     async fn chat_with_tools(
@@ -144,11 +614,35 @@ impl Provider for RouterProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ChatResponse> {
+        if let Some(auto) = self.auto_for(model) {
+            let tool_names: Vec<&str> = tools
+                .iter()
+                .filter_map(|tool| tool.pointer("/function/name")?.as_str())
+                .collect();
+            let profile = RequestProfile::from_messages(messages, tools.len());
+            return self
+                .dispatch_auto(
+                    auto,
+                    &profile,
+                    move |idx, model| async move {
+                        self.providers[idx]
+                            .1
+                            .chat_with_tools(messages, tools, &model, temperature)
+                            .await
+                    },
+                    |response: &ChatResponse| !has_bad_native_tool_call(response, &tool_names),
+                )
+                .await
+                .map(|(response, route)| with_route(response, route));
+        }
+
         let (provider_idx, resolved_model) = self.resolve(model);
+        let route = self.route_to(provider_idx, &resolved_model);
         let (_, provider) = &self.providers[provider_idx];
         provider
             .chat_with_tools(messages, tools, &resolved_model, temperature)
             .await
+            .map(|response| with_route(response, route))
     }
 
     fn supports_native_tools(&self) -> bool {
@@ -418,6 +912,7 @@ mod tests {
         let messages = vec![ChatMessage {
             role: "user".to_string(),
             content: "use tools".to_string(),
+            parts: Vec::new(),
         }];
         let tools = vec![serde_json::json!({
             "type": "function",
@@ -449,6 +944,7 @@ mod tests {
         let messages = vec![ChatMessage {
             role: "user".to_string(),
             content: "reason about this".to_string(),
+            parts: Vec::new(),
         }];
         let tools = vec![serde_json::json!({"type": "function", "function": {"name": "test"}})];
 
@@ -461,4 +957,302 @@ mod tests {
         assert_eq!(mocks[1].last_model(), "claude-opus");
         assert_eq!(mocks[0].call_count(), 0);
     }
+
+    // ── Automatic routing ──
+
+    fn priced(input: f64, output: f64) -> ModelPricing {
+        ModelPricing {
+            input,
+            output,
+            cache_read: None,
+            cache_write: None,
+        }
+    }
+
+    fn auto_tracker(tmp: &tempfile::TempDir) -> Arc<CostTracker> {
+        let prices = HashMap::from([
+            ("cheap-model".to_string(), priced(0.1, 0.4)),
+            ("strong-model".to_string(), priced(15.0, 75.0)),
+        ]);
+        let config = crate::config::CostConfig {
+            prices,
+            ..Default::default()
+        };
+        Arc::new(CostTracker::new(config, tmp.path()).unwrap())
+    }
+
+    fn make_auto_router(
+        cheap_response: &'static str,
+        tracker: Arc<CostTracker>,
+        classification: QueryClassificationConfig,
+    ) -> (RouterProvider, Vec<Arc<MockProvider>>) {
+        let (router, mocks) = make_router(
+            vec![
+                ("default", "default-response"),
+                ("cheap", cheap_response),
+                ("strong", "strong-response"),
+            ],
+            vec![
+                ("fast", "cheap", "cheap-model"),
+                ("reasoning", "strong", "strong-model"),
+            ],
+        );
+        let router = router.with_auto_routing(AutoRouting {
+            config: AutoRoutingConfig {
+                enabled: true,
+                ..AutoRoutingConfig::default()
+            },
+            classification,
+            tracker: Some(tracker),
+        });
+        (router, mocks)
+    }
+
+    #[tokio::test]
+    async fn auto_routing_picks_cheapest_route_for_simple_requests() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let tracker = auto_tracker(&tmp);
+        let (router, mocks) = make_auto_router(
+            "cheap-response",
+            Arc::clone(&tracker),
+            QueryClassificationConfig::default(),
+        );
+
+        let result = router
+            .simple_chat("hello", "default-model", 0.5)
+            .await
+            .unwrap();
+        assert_eq!(result, "cheap-response");
+        assert_eq!(mocks[1].last_model(), "cheap-model");
+        assert_eq!(mocks[0].call_count() + mocks[2].call_count(), 0);
+        assert_eq!(
+            tracker.model_performance("cheap-model").unwrap().successes,
+            1
+        );
+
+        // Explicit hints bypass automatic routing.
+        let result = router
+            .simple_chat("hello", "hint:reasoning", 0.5)
+            .await
+            .unwrap();
+        assert_eq!(result, "strong-response");
+    }
+
+    #[tokio::test]
+    async fn auto_routing_escalates_on_unparseable_tool_call() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let tracker = auto_tracker(&tmp);
+        let (router, mocks) = make_auto_router(
+            "<tool_call>{\"name\": \"shell\", </tool_call>",
+            Arc::clone(&tracker),
+            QueryClassificationConfig::default(),
+        );
+
+        let result = router
+            .simple_chat("list files", "default-model", 0.5)
+            .await
+            .unwrap();
+        assert_eq!(result, "strong-response");
+        assert_eq!(mocks[1].call_count(), 1);
+        assert_eq!(mocks[2].call_count(), 1);
+        let cheap = tracker.model_performance("cheap-model").unwrap();
+        assert_eq!((cheap.requests, cheap.successes), (1, 0));
+    }
+
+    #[tokio::test]
+    async fn auto_routing_sends_long_conversations_to_stronger_route() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let (router, mocks) = make_auto_router(
+            "cheap-response",
+            auto_tracker(&tmp),
+            QueryClassificationConfig::default(),
+        );
+
+        let history: Vec<ChatMessage> = (0..30)
+            .map(|i| ChatMessage::user(format!("message {i}")))
+            .collect();
+        let result = router
+            .chat_with_history(&history, "default-model", 0.5)
+            .await
+            .unwrap();
+        assert_eq!(result, "strong-response");
+        assert_eq!(mocks[1].call_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn auto_routing_uses_classifier_hint_as_floor() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let classification = QueryClassificationConfig {
+            enabled: true,
+            rules: vec![crate::config::ClassificationRule {
+                hint: "reasoning".into(),
+                keywords: vec!["prove".into()],
+                ..Default::default()
+            }],
+        };
+        let (router, mocks) =
+            make_auto_router("cheap-response", auto_tracker(&tmp), classification);
+
+        let result = router
+            .simple_chat("prove this lemma", "default-model", 0.5)
+            .await
+            .unwrap();
+        assert_eq!(result, "strong-response");
+        assert_eq!(mocks[1].call_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn auto_routing_skips_routes_with_poor_observed_success() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let tracker = auto_tracker(&tmp);
+        for _ in 0..5 {
+            tracker.record_call_outcome("cheap-model", std::time::Duration::from_millis(10), false);
+        }
+        let (router, mocks) = make_auto_router(
+            "cheap-response",
+            tracker,
+            QueryClassificationConfig::default(),
+        );
+
+        let result = router
+            .simple_chat("hello", "default-model", 0.5)
+            .await
+            .unwrap();
+        assert_eq!(result, "strong-response");
+        assert_eq!(mocks[1].call_count(), 0);
+    }
+
+    #[test]
+    fn unparseable_tool_call_detection() {
+        assert!(!has_unparseable_tool_call("plain answer"));
+        assert!(!has_unparseable_tool_call(
+            "<tool_call>{\"name\": \"shell\", \"arguments\": {}}</tool_call>"
+        ));
+        assert!(has_unparseable_tool_call(
+            "<tool_call>{\"name\": </tool_call>"
+        ));
+        assert!(has_unparseable_tool_call(
+            "<tool_call>{\"name\": \"shell\"}"
+        ));
+    }
+
+    #[tokio::test]
+    async fn responses_carry_the_route_that_served_them() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (router, _mocks) = make_auto_router(
+            "cheap-response",
+            auto_tracker(&tmp),
+            QueryClassificationConfig::default(),
+        );
+        let messages = vec![ChatMessage::user("hi")];
+        let request = ChatRequest {
+            messages: &messages,
+            tools: None,
+        };
+
+        let response = router.chat(request, "default-model", 0.0).await.unwrap();
+        assert_eq!(
+            response.routed_to,
+            Some(Route {
+                provider_name: "cheap".into(),
+                model: "cheap-model".into(),
+            })
+        );
+
+        let response = router.chat(request, "hint:reasoning", 0.0).await.unwrap();
+        assert_eq!(
+            response.routed_to.map(|route| route.model),
+            Some("strong-model".to_string())
+        );
+    }
+
+    /// Provider whose stream fails after its first text delta.
+    struct BrokenStream;
+
+    #[async_trait]
+    impl Provider for BrokenStream {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            Ok("unused".into())
+        }
+
+        async fn stream_chat(
+            &self,
+            _request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<stream::BoxStream<'static, StreamResult<StreamEvent>>> {
+            Ok(stream::iter([
+                Ok(StreamEvent::TextDelta("partial".into())),
+                Err(super::super::traits::StreamError::Provider("reset".into())),
+            ])
+            .boxed())
+        }
+    }
+
+    #[tokio::test]
+    async fn auto_routed_streams_record_their_outcome() {
+        let tmp = tempfile::tempdir().unwrap();
+        let tracker = auto_tracker(&tmp);
+        let (router, _mocks) = make_auto_router(
+            "cheap-response",
+            Arc::clone(&tracker),
+            QueryClassificationConfig::default(),
+        );
+        let messages = vec![ChatMessage::user("hi")];
+        let request = ChatRequest {
+            messages: &messages,
+            tools: None,
+        };
+
+        let events = router
+            .stream_chat(request, "default-model", 0.0)
+            .await
+            .unwrap();
+        assert!(tracker.model_performance("cheap-model").is_none());
+        let events: Vec<_> = events.collect().await;
+        assert!(events.iter().all(Result::is_ok));
+        let stats = tracker.model_performance("cheap-model").unwrap();
+        assert_eq!((stats.requests, stats.successes), (1, 1));
+
+        let broken = RouterProvider::new(
+            vec![
+                (
+                    "default".to_string(),
+                    Box::new(MockProvider::new("default")) as Box<dyn Provider>,
+                ),
+                ("cheap".to_string(), Box::new(BrokenStream)),
+            ],
+            vec![(
+                "fast".to_string(),
+                Route {
+                    provider_name: "cheap".into(),
+                    model: "cheap-model".into(),
+                },
+            )],
+            "default-model".to_string(),
+        )
+        .with_auto_routing(AutoRouting {
+            config: AutoRoutingConfig {
+                enabled: true,
+                ..AutoRoutingConfig::default()
+            },
+            classification: QueryClassificationConfig::default(),
+            tracker: Some(Arc::clone(&tracker)),
+        });
+        let events: Vec<_> = broken
+            .stream_chat(request, "default-model", 0.0)
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert!(events.last().unwrap().is_err());
+        let stats = tracker.model_performance("cheap-model").unwrap();
+        assert_eq!((stats.requests, stats.successes), (2, 1));
+    }
 }
@@ -0,0 +1,335 @@
+//! Shared plumbing for providers that stream typed chat events.
+//!
+//! Providers speak Server-Sent Events on the wire. [`sse_data_lines`] turns a
+//! response body into `data:` payloads; [`openai_event_stream`] decodes the
+//! OpenAI chat-completions chunk format used by OpenAI, OpenRouter and the
+//! OpenAI-compatible providers into [`StreamEvent`]s.
+
+use crate::providers::traits::{StreamError, StreamEvent, StreamResult, TokenUsage};
+use futures_util::{stream, StreamExt};
+use serde::Deserialize;
+use std::collections::VecDeque;
+
+/// Split an SSE response body into the payloads of its `data:` lines.
+///
+/// Comments, `event:` lines and the OpenAI `[DONE]` sentinel are dropped.
+/// Bytes are buffered until a full line arrives, so multi-byte characters
+/// split across network chunks decode correctly.
+pub(crate) fn sse_data_lines(
+    response: reqwest::Response,
+) -> stream::BoxStream<'static, StreamResult<String>> {
+    struct State {
+        bytes: stream::BoxStream<'static, reqwest::Result<Vec<u8>>>,
+        buffer: Vec<u8>,
+        pending: VecDeque<String>,
+        finished: bool,
+    }
+
+    fn drain_lines(buffer: &mut Vec<u8>, pending: &mut VecDeque<String>, flush: bool) {
+        while let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=pos).collect();
+            push_data_line(&String::from_utf8_lossy(&line), pending);
+        }
+        if flush && !buffer.is_empty() {
+            let line = std::mem::take(buffer);
+            push_data_line(&String::from_utf8_lossy(&line), pending);
+        }
+    }
+
+    let state = State {
+        bytes: response
+            .bytes_stream()
+            .map(|chunk| chunk.map(|bytes| bytes.to_vec()))
+            .boxed(),
+        buffer: Vec::new(),
+        pending: VecDeque::new(),
+        finished: false,
+    };
+
+    stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(data) = state.pending.pop_front() {
+                return Some((Ok(data), state));
+            }
+            if state.finished {
+                return None;
+            }
+            match state.bytes.next().await {
+                Some(Ok(chunk)) => {
+                    state.buffer.extend_from_slice(&chunk);
+                    drain_lines(&mut state.buffer, &mut state.pending, false);
+                }
+                Some(Err(error)) => {
+                    state.finished = true;
+                    return Some((Err(StreamError::Http(error)), state));
+                }
+                None => {
+                    state.finished = true;
+                    drain_lines(&mut state.buffer, &mut state.pending, true);
+                }
+            }
+        }
+    })
+    .boxed()
+}
+
+fn push_data_line(line: &str, pending: &mut VecDeque<String>) {
+    let Some(data) = line.trim().strip_prefix("data:") else {
+        return;
+    };
+    let data = data.trim();
+    if !data.is_empty() && data != "[DONE]" {
+        pending.push_back(data.to_string());
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiStreamChunk {
+    #[serde(default)]
+    choices: Vec<OpenAiStreamChoice>,
+    #[serde(default)]
+    usage: Option<OpenAiStreamUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiStreamChoice {
+    #[serde(default)]
+    delta: OpenAiStreamDelta,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct OpenAiStreamDelta {
+    #[serde(default)]
+    content: Option<String>,
+    /// Reasoning/thinking models may stream output via `reasoning_content`.
+    #[serde(default)]
+    reasoning_content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<OpenAiToolCallDelta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiToolCallDelta {
+    #[serde(default)]
+    index: usize,
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    function: Option<OpenAiFunctionDelta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiFunctionDelta {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    arguments: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiStreamUsage {
+    #[serde(default)]
+    prompt_tokens: Option<u64>,
+    #[serde(default)]
+    completion_tokens: Option<u64>,
+    #[serde(default)]
+    prompt_tokens_details: Option<OpenAiPromptTokensDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiPromptTokensDetails {
+    #[serde(default)]
+    cached_tokens: Option<u64>,
+}
+
+/// Decode one OpenAI chat-completions stream chunk into events.
+pub(crate) fn parse_openai_stream_chunk(data: &str) -> StreamResult<Vec<StreamEvent>> {
+    let chunk: OpenAiStreamChunk = serde_json::from_str(data).map_err(StreamError::Json)?;
+    let mut events = Vec::new();
+
+    if let Some(choice) = chunk.choices.into_iter().next() {
+        let delta = choice.delta;
+        match (delta.content, delta.reasoning_content) {
+            (Some(content), _) if !content.is_empty() => {
+                events.push(StreamEvent::TextDelta(content));
+            }
+            (_, Some(reasoning)) if !reasoning.is_empty() => {
+                events.push(StreamEvent::ReasoningDelta(reasoning));
+            }
+            _ => {}
+        }
+
+        for call in delta.tool_calls {
+            let (name, arguments) = call
+                .function
+                .map(|f| (f.name, f.arguments))
+                .unwrap_or((None, None));
+            if call.id.is_some() || name.is_some() {
+                events.push(StreamEvent::ToolCallStart {
+                    index: call.index,
+                    id: call.id.unwrap_or_default(),
+                    name: name.unwrap_or_default(),
+                });
+            }
+            if let Some(arguments) = arguments.filter(|a| !a.is_empty()) {
+                events.push(StreamEvent::ToolCallDelta {
+                    index: call.index,
+                    arguments,
+                });
+            }
+        }
+    }
+
+    if let Some(usage) = chunk.usage {
+        events.push(StreamEvent::Usage(TokenUsage::from_openai_counts(
+            usage.prompt_tokens,
+            usage.completion_tokens,
+            usage
+                .prompt_tokens_details
+                .and_then(|details| details.cached_tokens),
+        )));
+    }
+
+    Ok(events)
+}
+
+/// Typed event stream for an OpenAI-format streaming response.
+pub(crate) fn openai_event_stream(
+    response: reqwest::Response,
+) -> stream::BoxStream<'static, StreamResult<StreamEvent>> {
+    sse_data_lines(response)
+        .flat_map(|item| {
+            let events = match item.and_then(|data| parse_openai_stream_chunk(&data)) {
+                Ok(events) => events.into_iter().map(Ok).collect::<Vec<_>>(),
+                Err(error) => vec![Err(error)],
+            };
+            stream::iter(events)
+        })
+        .chain(stream::once(async { Ok(StreamEvent::Done) }))
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::traits::ChatStreamAccumulator;
+
+    #[test]
+    fn parse_openai_chunk_emits_text_delta() {
+        let events =
+            parse_openai_stream_chunk(r#"{"choices":[{"delta":{"content":"Hel"}}]}"#).unwrap();
+        assert_eq!(events, vec![StreamEvent::TextDelta("Hel".into())]);
+    }
+
+    #[test]
+    fn parse_openai_chunk_keeps_reasoning_out_of_the_answer() {
+        let events = parse_openai_stream_chunk(
+            r#"{"choices":[{"delta":{"content":"","reasoning_content":"hmm"}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(events, vec![StreamEvent::ReasoningDelta("hmm".into())]);
+
+        let mut accumulator = ChatStreamAccumulator::new();
+        accumulator.push(&StreamEvent::ReasoningDelta("thinking".into()));
+        accumulator.push(&StreamEvent::TextDelta("answer".into()));
+        assert_eq!(accumulator.finish().text.as_deref(), Some("answer"));
+
+        // A reply made only of reasoning falls back to it, as without streaming.
+        let mut accumulator = ChatStreamAccumulator::new();
+        accumulator.push(&StreamEvent::ReasoningDelta("only".into()));
+        assert_eq!(accumulator.finish().text.as_deref(), Some("only"));
+    }
+
+    #[test]
+    fn parse_openai_chunk_emits_tool_call_start_and_deltas() {
+        let start = parse_openai_stream_chunk(
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"id":"call_1","type":"function","function":{"name":"shell","arguments":""}}]}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            start,
+            vec![StreamEvent::ToolCallStart {
+                index: 0,
+                id: "call_1".into(),
+                name: "shell".into(),
+            }]
+        );
+
+        let delta = parse_openai_stream_chunk(
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"function":{"arguments":"{\"command\":"}}]}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            delta,
+            vec![StreamEvent::ToolCallDelta {
+                index: 0,
+                arguments: r#"{"command":"#.into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_openai_chunk_reads_trailing_usage() {
+        let events = parse_openai_stream_chunk(
+            r#"{"choices":[],"usage":{"prompt_tokens":12,"completion_tokens":3}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            events,
+            vec![StreamEvent::Usage(TokenUsage {
+                input_tokens: Some(12),
+                output_tokens: Some(3),
+                ..TokenUsage::default()
+            })]
+        );
+    }
+
+    #[test]
+    fn parse_openai_chunk_splits_cached_prompt_tokens() {
+        let events = parse_openai_stream_chunk(
+            r#"{"choices":[],"usage":{"prompt_tokens":2000,"completion_tokens":10,"prompt_tokens_details":{"cached_tokens":1536}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            events,
+            vec![StreamEvent::Usage(TokenUsage {
+                input_tokens: Some(464),
+                output_tokens: Some(10),
+                cache_read_tokens: Some(1536),
+                cache_write_tokens: None,
+            })]
+        );
+    }
+
+    #[test]
+    fn accumulator_rebuilds_response_from_openai_chunks() {
+        let chunks = [
+            r#"{"choices":[{"delta":{"content":"Let me check. "}}]}"#,
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"id":"call_1","function":{"name":"shell","arguments":"{\"comm"}}]}}]}"#,
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"function":{"arguments":"and\":\"ls\"}"}}]}}]}"#,
+            r#"{"choices":[],"usage":{"prompt_tokens":5,"completion_tokens":7}}"#,
+        ];
+        let mut acc = ChatStreamAccumulator::new();
+        for chunk in chunks {
+            for event in parse_openai_stream_chunk(chunk).unwrap() {
+                acc.push(&event);
+            }
+        }
+        let response = acc.finish();
+        assert_eq!(response.text.as_deref(), Some("Let me check. "));
+        assert_eq!(response.tool_calls.len(), 1);
+        assert_eq!(response.tool_calls[0].id, "call_1");
+        assert_eq!(response.tool_calls[0].arguments, r#"{"command":"ls"}"#);
+        assert_eq!(response.usage.unwrap().output_tokens, Some(7));
+    }
+
+    #[test]
+    fn push_data_line_skips_comments_events_and_done() {
+        let mut pending = VecDeque::new();
+        push_data_line(": keep-alive", &mut pending);
+        push_data_line("event: message_start", &mut pending);
+        push_data_line("data: [DONE]", &mut pending);
+        push_data_line("data: {\"a\":1}\r\n", &mut pending);
+        assert_eq!(pending, VecDeque::from(vec!["{\"a\":1}".to_string()]));
+    }
+}
@@ -1,14 +1,187 @@
+use super::router::Route;
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
 use futures_util::{stream, StreamExt};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::fmt::Write;
 
+/// Where the bytes of an image or document part come from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MediaSource {
+    /// Inline base64 payload with its MIME type.
+    Base64 { media_type: String, data: String },
+    /// Remote URL the provider fetches on its own.
+    Url { url: String },
+}
+
+impl MediaSource {
+    /// Parse a `data:<mime>;base64,<payload>` URI. Plain URLs become [`MediaSource::Url`].
+    pub fn from_reference(reference: &str) -> Self {
+        if let Some(rest) = reference.strip_prefix("data:") {
+            if let Some((header, payload)) = rest.split_once(',') {
+                if let Some(mime) = header.strip_suffix(";base64") {
+                    return Self::Base64 {
+                        media_type: mime.trim().to_ascii_lowercase(),
+                        data: payload.trim().to_string(),
+                    };
+                }
+            }
+        }
+        Self::Url {
+            url: reference.trim().to_string(),
+        }
+    }
+
+    /// Render as a URL: a `data:` URI for inline payloads, the URL itself otherwise.
+    pub fn to_url(&self) -> String {
+        match self {
+            Self::Base64 { media_type, data } => format!("data:{media_type};base64,{data}"),
+            Self::Url { url } => url.clone(),
+        }
+    }
+}
+
+/// A typed piece of message content.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentPart {
+    Text {
+        text: String,
+    },
+    Image {
+        source: MediaSource,
+    },
+    Document {
+        source: MediaSource,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+    },
+    /// A tool invocation issued by the assistant.
+    ToolUse {
+        id: String,
+        name: String,
+        /// Raw JSON arguments, exactly as the model produced them.
+        arguments: String,
+    },
+    /// The outcome of a tool invocation, fed back to the model.
+    ToolResult {
+        tool_use_id: String,
+        content: String,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        is_error: bool,
+    },
+}
+
+impl ContentPart {
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::Text { text: text.into() }
+    }
+
+    pub fn image(source: MediaSource) -> Self {
+        Self::Image { source }
+    }
+
+    /// True for parts that need a vision-capable provider.
+    pub fn requires_vision(&self) -> bool {
+        matches!(self, Self::Image { .. })
+    }
+}
+
+/// Render typed parts into the flat text form older consumers understand.
+///
+/// Images and documents become `[IMAGE:...]` / `[DOCUMENT:...]` markers and
+/// tool parts become the JSON envelopes the providers already parse, so code
+/// that only looks at `ChatMessage::content` keeps working.
+pub fn render_parts_text(parts: &[ContentPart]) -> String {
+    let tool_uses: Vec<&ContentPart> = parts
+        .iter()
+        .filter(|part| matches!(part, ContentPart::ToolUse { .. }))
+        .collect();
+    if !tool_uses.is_empty() {
+        let text = parts
+            .iter()
+            .filter_map(|part| match part {
+                ContentPart::Text { text } => Some(text.trim()),
+                _ => None,
+            })
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let calls: Vec<serde_json::Value> = tool_uses
+            .into_iter()
+            .filter_map(|part| match part {
+                ContentPart::ToolUse {
+                    id,
+                    name,
+                    arguments,
+                } => Some(serde_json::json!({
+                    "id": id,
+                    "name": name,
+                    "arguments": arguments,
+                })),
+                _ => None,
+            })
+            .collect();
+        let content = if text.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::Value::String(text)
+        };
+        return serde_json::json!({
+            "content": content,
+            "tool_calls": calls,
+        })
+        .to_string();
+    }
+
+    if let [ContentPart::ToolResult {
+        tool_use_id,
+        content,
+        ..
+    }] = parts
+    {
+        return serde_json::json!({
+            "tool_call_id": tool_use_id,
+            "content": content,
+        })
+        .to_string();
+    }
+
+    let mut rendered = Vec::with_capacity(parts.len());
+    for part in parts {
+        match part {
+            ContentPart::Text { text } => {
+                let trimmed = text.trim();
+                if !trimmed.is_empty() {
+                    rendered.push(trimmed.to_string());
+                }
+            }
+            ContentPart::Image { source } => {
+                rendered.push(format!("[IMAGE:{}]", source.to_url()));
+            }
+            ContentPart::Document { source, .. } => {
+                rendered.push(format!("[DOCUMENT:{}]", source.to_url()));
+            }
+            ContentPart::ToolResult { content, .. } => rendered.push(content.clone()),
+            ContentPart::ToolUse { .. } => {}
+        }
+    }
+    rendered.join("\n\n")
+}
+
 /// A single message in a conversation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
     pub role: String,
+    /// Flat text rendering of the message. Always populated, so consumers
+    /// that only deal in strings (history trimming, memory, logging) keep working.
     pub content: String,
+    /// Typed content parts. Empty means the message is a single text part
+    /// equal to `content`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parts: Vec<ContentPart>,
 }
 
 impl ChatMessage {
@@ -16,6 +189,7 @@ impl ChatMessage {
         Self {
             role: "system".into(),
             content: content.into(),
+            parts: Vec::new(),
         }
     }
 
@@ -23,6 +197,7 @@ impl ChatMessage {
         Self {
             role: "user".into(),
             content: content.into(),
+            parts: Vec::new(),
         }
     }
 
@@ -30,6 +205,7 @@ impl ChatMessage {
         Self {
             role: "assistant".into(),
             content: content.into(),
+            parts: Vec::new(),
         }
     }
 
@@ -37,8 +213,61 @@ impl ChatMessage {
         Self {
             role: "tool".into(),
             content: content.into(),
+            parts: Vec::new(),
+        }
+    }
+
+    /// Build a message from typed parts; `content` is derived from them.
+    pub fn with_parts(role: impl Into<String>, parts: Vec<ContentPart>) -> Self {
+        Self {
+            role: role.into(),
+            content: render_parts_text(&parts),
+            parts,
+        }
+    }
+
+    /// Assistant turn that requested one or more tool calls.
+    pub fn assistant_tool_calls(text: Option<&str>, tool_calls: &[ToolCall]) -> Self {
+        let mut parts = Vec::with_capacity(tool_calls.len() + 1);
+        if let Some(text) = text.map(str::trim).filter(|t| !t.is_empty()) {
+            parts.push(ContentPart::text(text));
+        }
+        parts.extend(tool_calls.iter().map(|call| ContentPart::ToolUse {
+            id: call.id.clone(),
+            name: call.name.clone(),
+            arguments: call.arguments.clone(),
+        }));
+        Self::with_parts("assistant", parts)
+    }
+
+    /// Tool result fed back for the call identified by `tool_call_id`.
+    pub fn tool_result(tool_call_id: impl Into<String>, content: impl Into<String>) -> Self {
+        Self::with_parts(
+            "tool",
+            vec![ContentPart::ToolResult {
+                tool_use_id: tool_call_id.into(),
+                content: content.into(),
+                is_error: false,
+            }],
+        )
+    }
+
+    /// Typed parts of this message, falling back to a single text part.
+    pub fn content_parts(&self) -> Cow<'_, [ContentPart]> {
+        if self.parts.is_empty() {
+            Cow::Owned(vec![ContentPart::text(self.content.clone())])
+        } else {
+            Cow::Borrowed(&self.parts)
         }
     }
+
+    /// Number of parts in this message that need vision support.
+    pub fn vision_part_count(&self) -> usize {
+        self.parts
+            .iter()
+            .filter(|part| part.requires_vision())
+            .count()
+    }
 }
 
 /// A tool call requested by the LLM.
@@ -50,10 +279,37 @@ pub struct ToolCall {
 }
 
 /// Raw token counts from a single LLM API response.
-#[derive(Debug, Clone, Default)]
+///
+/// `input_tokens` counts only uncached prompt tokens. Tokens served from or
+/// written to a provider-side prompt cache are reported separately so they
+/// can be priced at their own rates.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct TokenUsage {
     pub input_tokens: Option<u64>,
     pub output_tokens: Option<u64>,
+    /// Prompt tokens read from the provider's prompt cache.
+    pub cache_read_tokens: Option<u64>,
+    /// Prompt tokens written to the provider's prompt cache.
+    pub cache_write_tokens: Option<u64>,
+}
+
+impl TokenUsage {
+    /// Build usage from OpenAI-style counts, where `prompt_tokens` already
+    /// includes the `cached_tokens` served from the prompt cache.
+    pub fn from_openai_counts(
+        prompt_tokens: Option<u64>,
+        completion_tokens: Option<u64>,
+        cached_tokens: Option<u64>,
+    ) -> Self {
+        let cached_tokens = cached_tokens.filter(|cached| *cached > 0);
+        Self {
+            input_tokens: prompt_tokens
+                .map(|prompt| prompt.saturating_sub(cached_tokens.unwrap_or(0))),
+            output_tokens: completion_tokens,
+            cache_read_tokens: cached_tokens,
+            cache_write_tokens: None,
+        }
+    }
 }
 
 /// An LLM response that may contain text, tool calls, or both.
@@ -65,6 +321,9 @@ pub struct ChatResponse {
     pub tool_calls: Vec<ToolCall>,
     /// Token usage reported by the provider, if available.
     pub usage: Option<TokenUsage>,
+    /// Provider and model that served the request when a router chose them;
+    /// `None` means the ones the request named.
+    pub routed_to: Option<Route>,
 }
 
 impl ChatResponse {
@@ -154,6 +413,151 @@ impl StreamChunk {
     }
 }
 
+/// A typed event from a streaming [`Provider::stream_chat`] call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamEvent {
+    /// Text delta for the assistant answer.
+    TextDelta(String),
+    /// Thinking text from a reasoning model. Not part of the answer; it is
+    /// only used as the reply when a response carries no answer text.
+    ReasoningDelta(String),
+    /// The model started a tool call. `index` identifies the call in
+    /// subsequent [`StreamEvent::ToolCallDelta`] events.
+    ToolCallStart {
+        index: usize,
+        id: String,
+        name: String,
+    },
+    /// A fragment of a tool call's JSON arguments.
+    ToolCallDelta { index: usize, arguments: String },
+    /// Token usage reported by the provider.
+    Usage(TokenUsage),
+    /// A router picked this provider and model to serve the stream.
+    Routed(Route),
+    /// The response is complete.
+    Done,
+}
+
+impl StreamEvent {
+    /// Replay a complete response as events, for providers without native streaming.
+    pub fn from_response(response: ChatResponse) -> Vec<Self> {
+        let mut events = Vec::with_capacity(response.tool_calls.len() * 2 + 4);
+        if let Some(route) = response.routed_to {
+            events.push(Self::Routed(route));
+        }
+        if let Some(text) = response.text.filter(|t| !t.is_empty()) {
+            events.push(Self::TextDelta(text));
+        }
+        for (index, call) in response.tool_calls.into_iter().enumerate() {
+            events.push(Self::ToolCallStart {
+                index,
+                id: call.id,
+                name: call.name,
+            });
+            events.push(Self::ToolCallDelta {
+                index,
+                arguments: call.arguments,
+            });
+        }
+        if let Some(usage) = response.usage {
+            events.push(Self::Usage(usage));
+        }
+        events.push(Self::Done);
+        events
+    }
+}
+
+/// Folds [`StreamEvent`]s back into a [`ChatResponse`].
+#[derive(Debug, Default)]
+pub struct ChatStreamAccumulator {
+    text: String,
+    reasoning: String,
+    tool_calls: Vec<ToolCall>,
+    usage: Option<TokenUsage>,
+    routed_to: Option<Route>,
+}
+
+impl ChatStreamAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, event: &StreamEvent) {
+        match event {
+            StreamEvent::TextDelta(delta) => self.text.push_str(delta),
+            StreamEvent::ReasoningDelta(delta) => self.reasoning.push_str(delta),
+            StreamEvent::ToolCallStart { index, id, name } => {
+                let call = self.call_at(*index);
+                if !id.is_empty() {
+                    call.id.clone_from(id);
+                }
+                if !name.is_empty() {
+                    call.name.clone_from(name);
+                }
+            }
+            StreamEvent::ToolCallDelta { index, arguments } => {
+                self.call_at(*index).arguments.push_str(arguments);
+            }
+            StreamEvent::Usage(usage) => {
+                let merged = self.usage.get_or_insert_with(TokenUsage::default);
+                if usage.input_tokens.is_some() {
+                    merged.input_tokens = usage.input_tokens;
+                }
+                if usage.output_tokens.is_some() {
+                    merged.output_tokens = usage.output_tokens;
+                }
+                if usage.cache_read_tokens.is_some() {
+                    merged.cache_read_tokens = usage.cache_read_tokens;
+                }
+                if usage.cache_write_tokens.is_some() {
+                    merged.cache_write_tokens = usage.cache_write_tokens;
+                }
+            }
+            StreamEvent::Routed(route) => self.routed_to = Some(route.clone()),
+            StreamEvent::Done => {}
+        }
+    }
+
+    fn call_at(&mut self, index: usize) -> &mut ToolCall {
+        while self.tool_calls.len() <= index {
+            self.tool_calls.push(ToolCall {
+                id: String::new(),
+                name: String::new(),
+                arguments: String::new(),
+            });
+        }
+        &mut self.tool_calls[index]
+    }
+
+    pub fn finish(mut self) -> ChatResponse {
+        let tool_calls: Vec<ToolCall> = self
+            .tool_calls
+            .into_iter()
+            .filter(|call| !call.name.is_empty())
+            .map(|mut call| {
+                if call.id.is_empty() {
+                    call.id = uuid::Uuid::new_v4().to_string();
+                }
+                if call.arguments.trim().is_empty() {
+                    call.arguments = "{}".to_string();
+                }
+                call
+            })
+            .collect();
+        // Like the non-streaming path, thinking models that put their whole
+        // reply in reasoning still produce an answer.
+        if self.text.is_empty() && tool_calls.is_empty() {
+            self.text = std::mem::take(&mut self.reasoning);
+        }
+        ChatResponse {
+            text: (!self.text.is_empty()).then_some(self.text),
+            tool_calls,
+            usage: self.usage,
+            routed_to: self.routed_to,
+        }
+    }
+}
+
 /// Options for streaming chat requests.
 #[derive(Debug, Clone, Copy, Default)]
 pub struct StreamOptions {
@@ -354,6 +758,7 @@ pub trait Provider: Send + Sync {
                     text: Some(text),
                     tool_calls: Vec::new(),
                     usage: None,
+                    routed_to: None,
                 });
             }
         }
@@ -365,6 +770,7 @@ pub trait Provider: Send + Sync {
             text: Some(text),
             tool_calls: Vec::new(),
             usage: None,
+            routed_to: None,
         })
     }
 
@@ -399,6 +805,7 @@ pub trait Provider: Send + Sync {
             text: Some(text),
             tool_calls: Vec::new(),
             usage: None,
+            routed_to: None,
         })
     }
 
@@ -408,6 +815,22 @@ pub trait Provider: Send + Sync {
         false
     }
 
+    /// Streaming variant of [`Provider::chat`] that emits text deltas,
+    /// tool-call argument deltas and usage as typed events.
+    ///
+    /// Default implementation runs `chat` and replays the full response, so
+    /// callers can always consume a stream. Providers with native streaming
+    /// override this.
+    async fn stream_chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<stream::BoxStream<'static, StreamResult<StreamEvent>>> {
+        let response = self.chat(request, model, temperature).await?;
+        Ok(stream::iter(StreamEvent::from_response(response).into_iter().map(Ok)).boxed())
+    }
+
     /// Streaming chat with optional system prompt.
     /// Returns an async stream of text chunks.
     /// Default implementation falls back to non-streaming chat.
@@ -517,12 +940,85 @@ mod tests {
         assert_eq!(tool.role, "tool");
     }
 
+    #[test]
+    fn chat_message_with_parts_renders_legacy_content() {
+        let msg = ChatMessage::with_parts(
+            "user",
+            vec![
+                ContentPart::text("Look"),
+                ContentPart::image(MediaSource::from_reference("data:image/png;base64,AAAA")),
+            ],
+        );
+        assert_eq!(msg.content, "Look\n\n[IMAGE:data:image/png;base64,AAAA]");
+        assert_eq!(msg.vision_part_count(), 1);
+        assert_eq!(msg.content_parts().len(), 2);
+
+        let plain = ChatMessage::user("hi");
+        assert_eq!(plain.content_parts().as_ref(), &[ContentPart::text("hi")]);
+        assert_eq!(plain.vision_part_count(), 0);
+    }
+
+    #[test]
+    fn chat_message_tool_constructors_render_json_envelopes() {
+        let call = ChatMessage::assistant_tool_calls(
+            Some("running"),
+            &[ToolCall {
+                id: "c1".into(),
+                name: "shell".into(),
+                arguments: "{}".into(),
+            }],
+        );
+        let value: serde_json::Value = serde_json::from_str(&call.content).unwrap();
+        assert_eq!(value["content"], "running");
+        assert_eq!(value["tool_calls"][0]["id"], "c1");
+        assert_eq!(value["tool_calls"][0]["arguments"], "{}");
+
+        let result = ChatMessage::tool_result("c1", "ok");
+        assert_eq!(result.role, "tool");
+        let value: serde_json::Value = serde_json::from_str(&result.content).unwrap();
+        assert_eq!(value["tool_call_id"], "c1");
+        assert_eq!(value["content"], "ok");
+    }
+
+    #[test]
+    fn media_source_parses_data_uris_and_urls() {
+        assert_eq!(
+            MediaSource::from_reference("data:Image/PNG;base64,abcd"),
+            MediaSource::Base64 {
+                media_type: "image/png".into(),
+                data: "abcd".into(),
+            }
+        );
+        assert_eq!(
+            MediaSource::from_reference("https://example.com/a.jpg"),
+            MediaSource::Url {
+                url: "https://example.com/a.jpg".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn chat_message_parts_roundtrip_and_stay_optional() {
+        let json = serde_json::to_string(&ChatMessage::user("plain")).unwrap();
+        assert!(!json.contains("parts"));
+
+        let legacy: ChatMessage =
+            serde_json::from_str(r#"{"role":"user","content":"old"}"#).unwrap();
+        assert!(legacy.parts.is_empty());
+
+        let msg = ChatMessage::tool_result("c2", "done");
+        let parsed: ChatMessage =
+            serde_json::from_str(&serde_json::to_string(&msg).unwrap()).unwrap();
+        assert_eq!(parsed.parts, msg.parts);
+    }
+
     #[test]
     fn chat_response_helpers() {
         let empty = ChatResponse {
             text: None,
             tool_calls: vec![],
             usage: None,
+            routed_to: None,
         };
         assert!(!empty.has_tool_calls());
         assert_eq!(empty.text_or_empty(), "");
@@ -535,6 +1031,7 @@ mod tests {
                 arguments: "{}".into(),
             }],
             usage: None,
+            routed_to: None,
         };
         assert!(with_tools.has_tool_calls());
         assert_eq!(with_tools.text_or_empty(), "Let me check");
@@ -555,7 +1052,9 @@ mod tests {
             usage: Some(TokenUsage {
                 input_tokens: Some(100),
                 output_tokens: Some(50),
+                ..TokenUsage::default()
             }),
+            routed_to: None,
         };
         assert_eq!(resp.usage.as_ref().unwrap().input_tokens, Some(100));
         assert_eq!(resp.usage.as_ref().unwrap().output_tokens, Some(50));
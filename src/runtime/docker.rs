@@ -1,3 +1,4 @@
+use super::limits::{LimitedCommand, ResourceLimits};
 use super::traits::RuntimeAdapter;
 use crate::config::DockerRuntimeConfig;
 use anyhow::{Context, Result};
@@ -7,11 +8,22 @@ use std::path::{Path, PathBuf};
 #[derive(Debug, Clone)]
 pub struct DockerRuntime {
     config: DockerRuntimeConfig,
+    limits: ResourceLimits,
 }
 
 impl DockerRuntime {
     pub fn new(config: DockerRuntimeConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            limits: ResourceLimits::unlimited(),
+        }
+    }
+
+    /// Enforce `limits` inside every container this runtime starts.
+    /// `runtime.docker.memory_limit_mb` takes precedence for memory.
+    pub fn with_limits(mut self, limits: ResourceLimits) -> Self {
+        self.limits = limits;
+        self
     }
 
     fn workspace_mount_path(&self, workspace_dir: &Path) -> Result<PathBuf> {
@@ -87,7 +99,7 @@ impl RuntimeAdapter for DockerRuntime {
         &self,
         command: &str,
         workspace_dir: &Path,
-    ) -> anyhow::Result<tokio::process::Command> {
+    ) -> anyhow::Result<LimitedCommand> {
         let mut process = tokio::process::Command::new("docker");
         process
             .arg("run")
@@ -102,6 +114,23 @@ impl RuntimeAdapter for DockerRuntime {
 
         if let Some(memory_limit_mb) = self.config.memory_limit_mb.filter(|mb| *mb > 0) {
             process.arg("--memory").arg(format!("{memory_limit_mb}m"));
+        } else if self.limits.max_memory_bytes > 0 {
+            process
+                .arg("--memory")
+                .arg(format!("{}m", self.limits.max_memory_mb()));
+        }
+
+        if self.limits.max_subprocesses > 0 {
+            process
+                .arg("--pids-limit")
+                .arg(self.limits.max_subprocesses.to_string());
+        }
+
+        if self.limits.max_cpu_time_secs > 0 {
+            let secs = self.limits.max_cpu_time_secs;
+            process
+                .arg("--ulimit")
+                .arg(format!("cpu={secs}:{}", secs.saturating_add(1)));
         }
 
         if let Some(cpu_limit) = self.config.cpu_limit.filter(|cpus| *cpus > 0.0) {
@@ -133,7 +162,8 @@ impl RuntimeAdapter for DockerRuntime {
             .arg("-c")
             .arg(command);
 
-        Ok(process)
+        // The container enforces the limits; the host only classifies exits.
+        Ok(LimitedCommand::delegated(process, self.limits))
     }
 }
 
@@ -272,4 +302,27 @@ mod tests {
             "should not include --memory when not configured"
         );
     }
+
+    #[test]
+    fn docker_maps_resource_limits_to_flags() {
+        let limits = ResourceLimits::from_config(&crate::config::ResourceLimitsConfig::default());
+        let runtime = DockerRuntime::new(DockerRuntimeConfig {
+            memory_limit_mb: None,
+            ..DockerRuntimeConfig::default()
+        })
+        .with_limits(limits);
+        let workspace = std::env::temp_dir();
+        let cmd = runtime
+            .build_shell_command("echo hello", &workspace)
+            .unwrap();
+        let args: Vec<String> = cmd
+            .as_std()
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+
+        assert!(args.windows(2).any(|w| w == ["--memory", "2048m"]));
+        assert!(args.windows(2).any(|w| w == ["--pids-limit", "256"]));
+        assert!(args.windows(2).any(|w| w == ["--ulimit", "cpu=600:601"]));
+    }
 }
@@ -0,0 +1,631 @@
+//! Per-command resource limits from `[security.resources]`.
+//!
+//! Every command built through [`RuntimeAdapter::build_shell_command`] comes
+//! back as a [`LimitedCommand`]. On Unix the child gets `RLIMIT_CPU` before
+//! exec. On Linux hosts where the agent's cgroup v2 subtree is delegated
+//! (the `memory` and `pids` controllers are enabled for children) each
+//! invocation also runs in its own cgroup with `memory.max` and `pids.max`,
+//! so memory and process caps cover the whole process tree and breaches can be
+//! read back from the cgroup's event counters. Without a cgroup the memory cap
+//! falls back to a per-process rlimit (`RLIMIT_DATA` on Linux, `RLIMIT_AS`
+//! elsewhere), whose breaches surface as the program's own allocation errors.
+//!
+//! [`RuntimeAdapter::build_shell_command`]: super::RuntimeAdapter::build_shell_command
+
+use crate::config::ResourceLimitsConfig;
+use std::fmt;
+use std::process::{ExitStatus, Output};
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Limits applied to a single spawned command. A zero field means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceLimits {
+    pub max_memory_bytes: u64,
+    pub max_cpu_time_secs: u64,
+    pub max_subprocesses: u64,
+}
+
+impl ResourceLimits {
+    /// Build limits from config. Unlimited when `enabled = false`;
+    /// `memory_monitoring = false` disables the memory cap but keeps the CPU
+    /// and process caps.
+    pub fn from_config(config: &ResourceLimitsConfig) -> Self {
+        if !config.enabled {
+            return Self::unlimited();
+        }
+        Self {
+            max_memory_bytes: if config.memory_monitoring {
+                u64::from(config.max_memory_mb).saturating_mul(BYTES_PER_MB)
+            } else {
+                0
+            },
+            max_cpu_time_secs: config.max_cpu_time_seconds,
+            max_subprocesses: u64::from(config.max_subprocesses),
+        }
+    }
+
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn max_memory_mb(&self) -> u64 {
+        self.max_memory_bytes / BYTES_PER_MB
+    }
+
+    /// Enforce the limits on the host: a per-invocation cgroup when one is
+    /// available, rlimits in the child otherwise.
+    pub fn apply(&self, command: tokio::process::Command) -> LimitedCommand {
+        #[cfg_attr(not(unix), allow(unused_mut))]
+        let mut limited = LimitedCommand::delegated(command, *self);
+        if self.is_unlimited() {
+            return limited;
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            limited.cgroup = cgroup::CgroupSlice::create(self);
+        }
+        #[cfg(unix)]
+        {
+            #[cfg(target_os = "linux")]
+            let procs_path = limited.cgroup.as_ref().map(|slice| slice.procs_path());
+            #[cfg(not(target_os = "linux"))]
+            let procs_path = None;
+            rlimits::install(&mut limited.command, self, procs_path);
+        }
+
+        limited
+    }
+
+    /// Classify a finished command's exit against these limits.
+    fn classify(&self, status: ExitStatus, events: &CgroupEvents) -> Option<LimitBreach> {
+        if self.max_memory_bytes > 0 && events.oom_kills > 0 {
+            return Some(LimitBreach::Memory {
+                limit_mb: self.max_memory_mb(),
+            });
+        }
+
+        if self.max_cpu_time_secs > 0 && killed_by_cpu_limit(status) {
+            return Some(LimitBreach::CpuTime {
+                limit_secs: self.max_cpu_time_secs,
+            });
+        }
+
+        if self.max_subprocesses > 0 && events.pids_max_hits > 0 && !status.success() {
+            return Some(LimitBreach::Subprocesses {
+                limit: self.max_subprocesses,
+            });
+        }
+
+        None
+    }
+}
+
+/// A resource limit the command ran into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitBreach {
+    Memory { limit_mb: u64 },
+    CpuTime { limit_secs: u64 },
+    Subprocesses { limit: u64 },
+}
+
+impl LimitBreach {
+    /// Stable identifier for logs and audit events.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Memory { .. } => "memory",
+            Self::CpuTime { .. } => "cpu_time",
+            Self::Subprocesses { .. } => "subprocesses",
+        }
+    }
+}
+
+impl fmt::Display for LimitBreach {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Memory { limit_mb } => write!(f, "memory limit of {limit_mb} MB exceeded"),
+            Self::CpuTime { limit_secs } => {
+                write!(f, "CPU time limit of {limit_secs}s exceeded")
+            }
+            Self::Subprocesses { limit } => write!(f, "subprocess limit of {limit} exceeded"),
+        }
+    }
+}
+
+/// Counters read back from a per-invocation cgroup.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct CgroupEvents {
+    oom_kills: u64,
+    pids_max_hits: u64,
+}
+
+#[cfg(unix)]
+fn killed_by_cpu_limit(status: ExitStatus) -> bool {
+    use std::os::unix::process::ExitStatusExt;
+
+    // `sh -c` either execs the command (signal reported directly) or reports
+    // a signalled child as 128 + signal.
+    status.signal() == Some(libc::SIGXCPU) || status.code() == Some(128 + libc::SIGXCPU)
+}
+
+#[cfg(not(unix))]
+fn killed_by_cpu_limit(_status: ExitStatus) -> bool {
+    false
+}
+
+/// A shell command together with the limits (and cgroup, if any) it runs under.
+#[derive(Debug)]
+pub struct LimitedCommand {
+    command: tokio::process::Command,
+    limits: ResourceLimits,
+    #[cfg(target_os = "linux")]
+    cgroup: Option<cgroup::CgroupSlice>,
+}
+
+impl LimitedCommand {
+    /// Wrap a command whose limits are enforced elsewhere (e.g. by a container
+    /// runtime). Exit statuses are still classified against `limits`.
+    pub fn delegated(command: tokio::process::Command, limits: ResourceLimits) -> Self {
+        Self {
+            command,
+            limits,
+            #[cfg(target_os = "linux")]
+            cgroup: None,
+        }
+    }
+
+    pub fn unlimited(command: tokio::process::Command) -> Self {
+        Self::delegated(command, ResourceLimits::unlimited())
+    }
+
+    pub fn command_mut(&mut self) -> &mut tokio::process::Command {
+        &mut self.command
+    }
+
+    pub fn as_std(&self) -> &std::process::Command {
+        self.command.as_std()
+    }
+
+    pub fn limits(&self) -> ResourceLimits {
+        self.limits
+    }
+
+    /// How the limits are enforced, for audit records.
+    pub fn enforcement(&self) -> &'static str {
+        #[cfg(target_os = "linux")]
+        if self.cgroup.is_some() {
+            return "cgroup-v2";
+        }
+        if self.limits.is_unlimited() {
+            "none"
+        } else if cfg!(unix) {
+            "rlimit"
+        } else {
+            "unenforced"
+        }
+    }
+
+    /// Run to completion, capturing output, and report any limit breach.
+    /// The per-invocation cgroup is torn down when this returns or is dropped.
+    pub async fn output(mut self) -> std::io::Result<LimitedOutput> {
+        let output = self.command.output().await?;
+        let breach = self.limits.classify(output.status, &self.cgroup_events());
+        Ok(LimitedOutput { output, breach })
+    }
+
+    fn cgroup_events(&self) -> CgroupEvents {
+        #[cfg(target_os = "linux")]
+        if let Some(slice) = &self.cgroup {
+            return slice.events();
+        }
+        CgroupEvents::default()
+    }
+}
+
+/// Captured output of a [`LimitedCommand`].
+#[derive(Debug)]
+pub struct LimitedOutput {
+    pub output: Output,
+    pub breach: Option<LimitBreach>,
+}
+
+#[cfg(unix)]
+mod rlimits {
+    use super::ResourceLimits;
+    use std::ffi::CString;
+
+    /// Install a pre-exec hook that joins the cgroup (when given) and sets
+    /// rlimits. Only async-signal-safe syscalls run between fork and exec.
+    pub(super) fn install(
+        command: &mut tokio::process::Command,
+        limits: &ResourceLimits,
+        cgroup_procs: Option<CString>,
+    ) {
+        let limits = *limits;
+        let hook = move || -> std::io::Result<()> {
+            let joined = cgroup_procs.as_deref().is_some_and(join_cgroup);
+            if limits.max_cpu_time_secs > 0 {
+                // The soft limit raises SIGXCPU; the hard limit one second
+                // later kills a child that ignores it.
+                set_rlimit(
+                    libc::RLIMIT_CPU,
+                    limits.max_cpu_time_secs,
+                    limits.max_cpu_time_secs.saturating_add(1),
+                )?;
+            }
+            // The cgroup caps the whole tree precisely; the memory rlimit is
+            // only the per-process fallback. RLIMIT_NPROC is deliberately not
+            // used: it counts every process of the user, not this tree.
+            if !joined && limits.max_memory_bytes > 0 {
+                set_rlimit(
+                    MEMORY_RLIMIT,
+                    limits.max_memory_bytes,
+                    limits.max_memory_bytes,
+                )?;
+            }
+            Ok(())
+        };
+
+        // SAFETY: the hook only calls getrlimit/setrlimit/open/write/close,
+        // all async-signal-safe, and allocates nothing.
+        unsafe {
+            command.pre_exec(hook);
+        }
+    }
+
+    fn join_cgroup(procs_path: &std::ffi::CStr) -> bool {
+        // Writing "0" to cgroup.procs moves the writing process.
+        unsafe {
+            let fd = libc::open(procs_path.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+            if fd < 0 {
+                return false;
+            }
+            let written = libc::write(fd, b"0".as_ptr().cast(), 1);
+            libc::close(fd);
+            written == 1
+        }
+    }
+
+    /// Linux counts heap and private writable mappings in `RLIMIT_DATA`,
+    /// which (unlike `RLIMIT_AS`) tolerates runtimes that reserve large
+    /// address ranges up front (V8, Go). Other Unixes only enforce `RLIMIT_AS`.
+    #[cfg(target_os = "linux")]
+    const MEMORY_RLIMIT: Resource = libc::RLIMIT_DATA;
+    #[cfg(not(target_os = "linux"))]
+    const MEMORY_RLIMIT: Resource = libc::RLIMIT_AS;
+
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    type Resource = libc::__rlimit_resource_t;
+    #[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+    type Resource = libc::c_int;
+
+    fn set_rlimit(resource: Resource, soft: u64, hard: u64) -> std::io::Result<()> {
+        let mut current = libc::rlimit {
+            rlim_cur: 0,
+            rlim_max: 0,
+        };
+        // SAFETY: `current` is a valid, writable rlimit struct.
+        if unsafe { libc::getrlimit(resource, &raw mut current) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        // Unprivileged processes may only lower the hard limit.
+        let hard = (hard as libc::rlim_t).min(current.rlim_max);
+        let soft = (soft as libc::rlim_t).min(hard);
+        let wanted = libc::rlimit {
+            rlim_cur: soft,
+            rlim_max: hard,
+        };
+        // SAFETY: `wanted` is a valid rlimit struct.
+        if unsafe { libc::setrlimit(resource, &raw const wanted) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod cgroup {
+    use super::{CgroupEvents, ResourceLimits};
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::{Path, PathBuf};
+    use std::sync::OnceLock;
+    use std::time::Duration;
+
+    const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+    const REQUIRED_CONTROLLERS: [&str; 2] = ["memory", "pids"];
+
+    /// Cgroup under which per-invocation cgroups are created, detected once.
+    fn parent() -> Option<&'static Path> {
+        static PARENT: OnceLock<Option<PathBuf>> = OnceLock::new();
+        PARENT
+            .get_or_init(|| {
+                let parent = detect_parent();
+                match &parent {
+                    Some(dir) => tracing::debug!(
+                        cgroup = %dir.display(),
+                        "Per-command cgroups enabled for resource limits"
+                    ),
+                    None => tracing::debug!(
+                        "No delegated cgroup v2 subtree; resource limits use rlimits only"
+                    ),
+                }
+                parent
+            })
+            .as_deref()
+    }
+
+    fn detect_parent() -> Option<PathBuf> {
+        let cgroup = std::fs::read_to_string("/proc/self/cgroup").ok()?;
+        let own = parse_unified_path(&cgroup)?;
+        let own_dir = Path::new(CGROUP_ROOT).join(own.trim_start_matches('/'));
+        // Our own cgroup first; then its parent, which covers service managers
+        // that place the main process in a leaf of the delegated subtree.
+        let parent = [Some(own_dir.as_path()), own_dir.parent()]
+            .into_iter()
+            .flatten()
+            .filter(|dir| dir.starts_with(CGROUP_ROOT))
+            .find(|dir| delegates_controllers(dir))
+            .map(Path::to_path_buf);
+        parent
+    }
+
+    /// The `0::<path>` entry of `/proc/self/cgroup` (cgroup v2 only).
+    pub(super) fn parse_unified_path(contents: &str) -> Option<&str> {
+        contents
+            .lines()
+            .find_map(|line| line.strip_prefix("0::"))
+            .map(str::trim)
+    }
+
+    fn delegates_controllers(dir: &Path) -> bool {
+        let subtree = dir.join("cgroup.subtree_control");
+        let enabled = |contents: &str| {
+            REQUIRED_CONTROLLERS
+                .iter()
+                .all(|wanted| contents.split_whitespace().any(|c| c == *wanted))
+        };
+        if std::fs::read_to_string(&subtree).is_ok_and(|c| enabled(&c)) {
+            return true;
+        }
+        // Try to enable them; fails harmlessly when not delegated to us.
+        let _ = std::fs::write(&subtree, "+memory +pids");
+        std::fs::read_to_string(&subtree).is_ok_and(|c| enabled(&c))
+    }
+
+    /// Read a `key value` counter from a cgroup events file.
+    pub(super) fn parse_counter(contents: &str, key: &str) -> u64 {
+        contents
+            .lines()
+            .find_map(|line| {
+                let (name, value) = line.split_once(' ')?;
+                (name == key).then(|| value.trim().parse().ok())?
+            })
+            .unwrap_or(0)
+    }
+
+    /// A cgroup created for one command and removed (killing stragglers)
+    /// when dropped.
+    #[derive(Debug)]
+    pub(super) struct CgroupSlice {
+        path: PathBuf,
+    }
+
+    impl CgroupSlice {
+        pub(super) fn create(limits: &ResourceLimits) -> Option<Self> {
+            let path = parent()?.join(format!("zeroclaw-cmd-{}", uuid::Uuid::new_v4().simple()));
+            if let Err(e) = std::fs::create_dir(&path) {
+                tracing::debug!(cgroup = %path.display(), "Failed to create command cgroup: {e}");
+                return None;
+            }
+            let slice = Self { path };
+
+            let mut settings = Vec::new();
+            if limits.max_memory_bytes > 0 {
+                settings.push(("memory.max", limits.max_memory_bytes.to_string()));
+                // Without this the kernel swaps instead of OOM-killing.
+                settings.push(("memory.swap.max", "0".to_string()));
+            }
+            if limits.max_subprocesses > 0 {
+                settings.push(("pids.max", limits.max_subprocesses.to_string()));
+            }
+            for (file, value) in settings {
+                if let Err(e) = std::fs::write(slice.path.join(file), value) {
+                    if file == "memory.swap.max" {
+                        // Absent when swap accounting is disabled.
+                        continue;
+                    }
+                    tracing::debug!(
+                        cgroup = %slice.path.display(),
+                        "Failed to write {file}: {e}"
+                    );
+                    return None;
+                }
+            }
+
+            Some(slice)
+        }
+
+        pub(super) fn procs_path(&self) -> CString {
+            CString::new(self.path.join("cgroup.procs").as_os_str().as_bytes())
+                .expect("cgroup paths never contain NUL bytes")
+        }
+
+        pub(super) fn events(&self) -> CgroupEvents {
+            let read =
+                |file: &str| std::fs::read_to_string(self.path.join(file)).unwrap_or_default();
+            CgroupEvents {
+                oom_kills: parse_counter(&read("memory.events"), "oom_kill"),
+                pids_max_hits: parse_counter(&read("pids.events"), "max"),
+            }
+        }
+    }
+
+    impl Drop for CgroupSlice {
+        fn drop(&mut self) {
+            if std::fs::remove_dir(&self.path).is_ok() {
+                return;
+            }
+            // Background processes left behind (or a timed-out command) keep
+            // the cgroup busy: kill them and retry off the async runtime.
+            let _ = std::fs::write(self.path.join("cgroup.kill"), "1");
+            let path = self.path.clone();
+            std::thread::spawn(move || {
+                for _ in 0..40 {
+                    if std::fs::remove_dir(&path).is_ok() {
+                        return;
+                    }
+                    std::thread::sleep(Duration::from_millis(25));
+                }
+                tracing::warn!(cgroup = %path.display(), "Failed to remove command cgroup");
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> ResourceLimits {
+        ResourceLimits::from_config(&ResourceLimitsConfig::default())
+    }
+
+    #[test]
+    fn default_config_is_enforced() {
+        assert!(!limits().is_unlimited());
+    }
+
+    #[test]
+    fn disabled_config_is_unlimited() {
+        let config = ResourceLimitsConfig {
+            enabled: false,
+            ..ResourceLimitsConfig::default()
+        };
+        assert!(ResourceLimits::from_config(&config).is_unlimited());
+    }
+
+    #[test]
+    fn from_config_converts_units() {
+        let limits = limits();
+        assert_eq!(limits.max_memory_bytes, 2048 * 1024 * 1024);
+        assert_eq!(limits.max_memory_mb(), 2048);
+        assert_eq!(limits.max_cpu_time_secs, 600);
+        assert_eq!(limits.max_subprocesses, 256);
+    }
+
+    #[test]
+    fn memory_monitoring_off_drops_memory_cap() {
+        let config = ResourceLimitsConfig {
+            memory_monitoring: false,
+            ..ResourceLimitsConfig::default()
+        };
+        let limits = ResourceLimits::from_config(&config);
+        assert_eq!(limits.max_memory_bytes, 0);
+        assert_eq!(limits.max_cpu_time_secs, 600);
+    }
+
+    #[test]
+    fn breach_messages_are_descriptive() {
+        let breach = LimitBreach::Memory { limit_mb: 512 };
+        assert_eq!(breach.kind(), "memory");
+        assert_eq!(breach.to_string(), "memory limit of 512 MB exceeded");
+        assert_eq!(
+            LimitBreach::CpuTime { limit_secs: 60 }.to_string(),
+            "CPU time limit of 60s exceeded"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn classify_detects_breaches() {
+        use std::os::unix::process::ExitStatusExt;
+
+        let limits = limits();
+        let clean = CgroupEvents::default();
+
+        let xcpu = ExitStatus::from_raw(libc::SIGXCPU);
+        assert_eq!(
+            limits.classify(xcpu, &clean),
+            Some(LimitBreach::CpuTime { limit_secs: 600 })
+        );
+        let shell_xcpu = ExitStatus::from_raw((128 + libc::SIGXCPU) << 8);
+        assert!(limits.classify(shell_xcpu, &clean).is_some());
+
+        let killed = ExitStatus::from_raw(libc::SIGKILL);
+        let oom = CgroupEvents {
+            oom_kills: 1,
+            ..CgroupEvents::default()
+        };
+        assert_eq!(
+            limits.classify(killed, &oom),
+            Some(LimitBreach::Memory { limit_mb: 2048 })
+        );
+
+        let failed = ExitStatus::from_raw(1 << 8);
+        let forks_denied = CgroupEvents {
+            pids_max_hits: 3,
+            ..CgroupEvents::default()
+        };
+        assert_eq!(
+            limits.classify(failed, &forks_denied),
+            Some(LimitBreach::Subprocesses { limit: 256 })
+        );
+
+        assert_eq!(limits.classify(failed, &clean), None);
+        assert_eq!(ResourceLimits::unlimited().classify(xcpu, &oom), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn parses_cgroup_files() {
+        assert_eq!(
+            cgroup::parse_unified_path("0::/user.slice/zeroclaw.service\n"),
+            Some("/user.slice/zeroclaw.service")
+        );
+        assert_eq!(cgroup::parse_unified_path("12:pids:/foo\n"), None);
+
+        let events = "low 0\nhigh 0\nmax 4\noom 1\noom_kill 2\n";
+        assert_eq!(cgroup::parse_counter(events, "oom_kill"), 2);
+        assert_eq!(cgroup::parse_counter(events, "max"), 4);
+        assert_eq!(cgroup::parse_counter(events, "missing"), 0);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn cpu_limit_kills_busy_command() {
+        let limits = ResourceLimits {
+            max_cpu_time_secs: 1,
+            ..ResourceLimits::unlimited()
+        };
+        let mut command = tokio::process::Command::new("sh");
+        command.arg("-c").arg("while :; do :; done");
+        let result = tokio::time::timeout(
+            std::time::Duration::from_secs(20),
+            limits.apply(command).output(),
+        )
+        .await
+        .expect("CPU rlimit should stop the loop")
+        .unwrap();
+
+        assert!(!result.output.status.success());
+        assert_eq!(result.breach, Some(LimitBreach::CpuTime { limit_secs: 1 }));
+    }
+
+    #[tokio::test]
+    async fn unlimited_command_runs_normally() {
+        let mut command = tokio::process::Command::new("sh");
+        command.arg("-c").arg("echo ok");
+        let limited = ResourceLimits::unlimited().apply(command);
+        assert_eq!(limited.enforcement(), "none");
+
+        let result = limited.output().await.unwrap();
+        assert!(result.output.status.success());
+        assert!(result.breach.is_none());
+    }
+}
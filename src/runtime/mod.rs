@@ -1,18 +1,29 @@
 pub mod docker;
+pub mod limits;
 pub mod native;
 pub mod traits;
+pub mod wasm;
 
 pub use docker::DockerRuntime;
+pub use limits::{LimitBreach, LimitedOutput, ResourceLimits};
 pub use native::NativeRuntime;
 pub use traits::RuntimeAdapter;
+pub use wasm::{WasmCapabilities, WasmRuntime};
 
-use crate::config::RuntimeConfig;
+use crate::config::{ResourceLimitsConfig, RuntimeConfig};
 
-/// Factory: create the right runtime from config
-pub fn create_runtime(config: &RuntimeConfig) -> anyhow::Result<Box<dyn RuntimeAdapter>> {
+/// Factory: create the right runtime from config, enforcing `limits` on
+/// every shell command it builds.
+pub fn create_runtime(
+    config: &RuntimeConfig,
+    limits: &ResourceLimitsConfig,
+) -> anyhow::Result<Box<dyn RuntimeAdapter>> {
+    let limits = ResourceLimits::from_config(limits);
     match config.kind.as_str() {
-        "native" => Ok(Box::new(NativeRuntime::new())),
-        "docker" => Ok(Box::new(DockerRuntime::new(config.docker.clone()))),
+        "native" => Ok(Box::new(NativeRuntime::new().with_limits(limits))),
+        "docker" => Ok(Box::new(
+            DockerRuntime::new(config.docker.clone()).with_limits(limits),
+        )),
         "cloudflare" => anyhow::bail!(
             "runtime.kind='cloudflare' is not implemented yet. Use runtime.kind='native' for now."
         ),
@@ -33,7 +44,7 @@ mod tests {
             kind: "native".into(),
             ..RuntimeConfig::default()
         };
-        let rt = create_runtime(&cfg).unwrap();
+        let rt = create_runtime(&cfg, &ResourceLimitsConfig::default()).unwrap();
         assert_eq!(rt.name(), "native");
         assert!(rt.has_shell_access());
     }
@@ -44,7 +55,7 @@ mod tests {
             kind: "docker".into(),
             ..RuntimeConfig::default()
         };
-        let rt = create_runtime(&cfg).unwrap();
+        let rt = create_runtime(&cfg, &ResourceLimitsConfig::default()).unwrap();
         assert_eq!(rt.name(), "docker");
         assert!(rt.has_shell_access());
     }
@@ -55,7 +66,7 @@ mod tests {
             kind: "cloudflare".into(),
             ..RuntimeConfig::default()
         };
-        match create_runtime(&cfg) {
+        match create_runtime(&cfg, &ResourceLimitsConfig::default()) {
             Err(err) => assert!(err.to_string().contains("not implemented")),
             Ok(_) => panic!("cloudflare runtime should error"),
         }
@@ -67,7 +78,7 @@ mod tests {
             kind: "wasm-edge-unknown".into(),
             ..RuntimeConfig::default()
         };
-        match create_runtime(&cfg) {
+        match create_runtime(&cfg, &ResourceLimitsConfig::default()) {
             Err(err) => assert!(err.to_string().contains("Unknown runtime kind")),
             Ok(_) => panic!("unknown runtime should error"),
         }
@@ -79,7 +90,7 @@ mod tests {
             kind: String::new(),
             ..RuntimeConfig::default()
         };
-        match create_runtime(&cfg) {
+        match create_runtime(&cfg, &ResourceLimitsConfig::default()) {
             Err(err) => assert!(err.to_string().contains("cannot be empty")),
             Ok(_) => panic!("empty runtime should error"),
         }
@@ -1,12 +1,23 @@
+use super::limits::{LimitedCommand, ResourceLimits};
 use super::traits::RuntimeAdapter;
 use std::path::{Path, PathBuf};
 
 /// Native runtime — full access, runs on Mac/Linux/Docker/Raspberry Pi
-pub struct NativeRuntime;
+pub struct NativeRuntime {
+    limits: ResourceLimits,
+}
 
 impl NativeRuntime {
     pub fn new() -> Self {
-        Self
+        Self {
+            limits: ResourceLimits::unlimited(),
+        }
+    }
+
+    /// Enforce `limits` on every shell command this runtime builds.
+    pub fn with_limits(mut self, limits: ResourceLimits) -> Self {
+        self.limits = limits;
+        self
     }
 }
 
@@ -38,10 +49,10 @@ impl RuntimeAdapter for NativeRuntime {
         &self,
         command: &str,
         workspace_dir: &Path,
-    ) -> anyhow::Result<tokio::process::Command> {
+    ) -> anyhow::Result<LimitedCommand> {
         let mut process = tokio::process::Command::new("sh");
         process.arg("-c").arg(command).current_dir(workspace_dir);
-        Ok(process)
+        Ok(self.limits.apply(process))
     }
 }
 
@@ -89,4 +100,15 @@ mod tests {
         let debug = format!("{command:?}");
         assert!(debug.contains("echo hello"));
     }
+
+    #[test]
+    fn native_attaches_configured_limits() {
+        let limits = ResourceLimits::from_config(&crate::config::ResourceLimitsConfig::default());
+        let command = NativeRuntime::new()
+            .with_limits(limits)
+            .build_shell_command("echo hello", &std::env::temp_dir())
+            .unwrap();
+        assert_eq!(command.limits(), limits);
+        assert_ne!(command.enforcement(), "none");
+    }
 }
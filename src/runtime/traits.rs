@@ -1,3 +1,4 @@
+use super::limits::LimitedCommand;
 use std::path::{Path, PathBuf};
 
 /// Runtime adapter that abstracts platform differences for the agent.
@@ -57,7 +58,9 @@ pub trait RuntimeAdapter: Send + Sync {
     /// Constructs a [`tokio::process::Command`] that will execute `command`
     /// with `workspace_dir` as the working directory. Implementations may
     /// prepend sandbox wrappers, set environment variables, or redirect
-    /// I/O as appropriate for the platform.
+    /// I/O as appropriate for the platform. The command is returned as a
+    /// [`LimitedCommand`] carrying the runtime's resource limits so callers
+    /// can report limit breaches after it exits.
     ///
     /// # Errors
     ///
@@ -67,7 +70,7 @@ pub trait RuntimeAdapter: Send + Sync {
         &self,
         command: &str,
         workspace_dir: &Path,
-    ) -> anyhow::Result<tokio::process::Command>;
+    ) -> anyhow::Result<LimitedCommand>;
 }
 
 #[cfg(test)]
@@ -101,11 +104,11 @@ mod tests {
             &self,
             command: &str,
             workspace_dir: &Path,
-        ) -> anyhow::Result<tokio::process::Command> {
+        ) -> anyhow::Result<LimitedCommand> {
             let mut cmd = tokio::process::Command::new("echo");
             cmd.arg(command);
             cmd.current_dir(workspace_dir);
-            Ok(cmd)
+            Ok(LimitedCommand::unlimited(cmd))
         }
     }
 
@@ -129,11 +132,11 @@ mod tests {
     #[tokio::test]
     async fn build_shell_command_executes() {
         let runtime = DummyRuntime;
-        let mut cmd = runtime
+        let cmd = runtime
             .build_shell_command("hello-runtime", Path::new("."))
             .unwrap();
 
-        let output = cmd.output().await.unwrap();
+        let output = cmd.output().await.unwrap().output;
         let stdout = String::from_utf8_lossy(&output.stdout);
 
         assert!(output.status.success());
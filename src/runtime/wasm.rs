@@ -7,15 +7,46 @@
 //! - **No filesystem access**: by default, tools are pure computation
 //! - **No network access**: unless explicitly allowlisted hosts are configured
 //!
+//! # Plugin ABI
+//! Tool plugins (see [`crate::plugins`]) are invoked through [`WasmRuntime::call_module`].
+//! The module must export:
+//! - `memory` — its linear memory
+//! - `alloc(len: i32) -> i32` — returns a buffer of `len` bytes owned by the module
+//! - `call(ptr: i32, len: i32) -> i64` — receives the tool arguments as UTF-8
+//!   JSON at `ptr..ptr+len` and returns `(out_ptr << 32) | out_len` pointing
+//!   at the UTF-8 result
+//!
+//! Host functions are provided under the `zeroclaw` import module:
+//! - `log(ptr: i32, len: i32)` — append a line to the invocation's stderr
+//! - `read_file(path_ptr: i32, path_len: i32) -> i64` — requires `read_workspace`;
+//!   returns a packed pointer/length to a module-allocated buffer, or `-1`
+//! - `write_file(path_ptr: i32, path_len: i32, data_ptr: i32, data_len: i32) -> i32`
+//!   — requires `write_workspace`; returns `0` on success or `-1`
+//!
+//! Paths are relative to the workspace and may not escape it.
+//!
 //! # Feature gate
-//! This module is only compiled when `--features runtime-wasm` is enabled.
-//! The default ZeroClaw binary excludes it to maintain the 4.6 MB size target.
+//! Module execution is only available when `--features runtime-wasm` is enabled.
+//! The default ZeroClaw binary excludes it to maintain the 4.6 MB size target;
+//! without it the execution entry points return an error.
 
+use super::limits::LimitedCommand;
 use super::traits::RuntimeAdapter;
 use crate::config::WasmRuntimeConfig;
 use anyhow::{bail, Context, Result};
 use std::path::{Path, PathBuf};
 
+/// Largest module the runtime will load.
+const MAX_MODULE_BYTES: usize = 50 * 1024 * 1024;
+
+/// Largest file a module may read through `zeroclaw.read_file`.
+#[cfg(feature = "runtime-wasm")]
+const MAX_HOST_READ_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Cap on log output collected from `zeroclaw.log` per invocation.
+#[cfg(feature = "runtime-wasm")]
+const MAX_LOG_BYTES: usize = 64 * 1024;
+
 /// WASM sandbox runtime — executes tool modules in an isolated interpreter.
 #[derive(Debug, Clone)]
 pub struct WasmRuntime {
@@ -26,9 +57,9 @@ pub struct WasmRuntime {
 /// Result of executing a WASM module.
 #[derive(Debug, Clone)]
 pub struct WasmExecutionResult {
-    /// Standard output captured from the module (if WASI is used)
+    /// Standard output captured from the module (the `call` result for plugins)
     pub stdout: String,
-    /// Standard error captured from the module
+    /// Standard error captured from the module (`zeroclaw.log` lines for plugins)
     pub stderr: String,
     /// Exit code (0 = success)
     pub exit_code: i32,
@@ -129,21 +160,8 @@ impl WasmRuntime {
         mb.saturating_mul(1024 * 1024)
     }
 
-    /// Execute a WASM module from the tools directory.
-    ///
-    /// This is the primary entry point for running sandboxed tool code.
-    /// The module must export a `_start` function (WASI convention) or
-    /// a custom `run` function that takes no arguments and returns i32.
-    #[cfg(feature = "runtime-wasm")]
-    pub fn execute_module(
-        &self,
-        module_name: &str,
-        workspace_dir: &Path,
-        caps: &WasmCapabilities,
-    ) -> Result<WasmExecutionResult> {
-        use wasmi::{Engine, Linker, Module, Store};
-
-        // Resolve module path
+    /// Read a module's bytes from the tools directory, enforcing the size limit.
+    fn read_module_bytes(&self, module_name: &str, workspace_dir: &Path) -> Result<Vec<u8>> {
         let tools_path = self.tools_dir(workspace_dir);
         let module_path = tools_path.join(format!("{module_name}.wasm"));
 
@@ -155,12 +173,11 @@ impl WasmRuntime {
             );
         }
 
-        // Read module bytes
         let wasm_bytes = std::fs::read(&module_path)
             .with_context(|| format!("Failed to read WASM module: {}", module_path.display()))?;
 
         // Validate module size (sanity check)
-        if wasm_bytes.len() > 50 * 1024 * 1024 {
+        if wasm_bytes.len() > MAX_MODULE_BYTES {
             bail!(
                 "WASM module {} is {} MB — exceeds 50 MB safety limit",
                 module_name,
@@ -168,6 +185,25 @@ impl WasmRuntime {
             );
         }
 
+        Ok(wasm_bytes)
+    }
+
+    /// Execute a WASM module from the tools directory.
+    ///
+    /// This is the primary entry point for running sandboxed tool code.
+    /// The module must export a `_start` function (WASI convention) or
+    /// a custom `run` function that takes no arguments and returns i32.
+    #[cfg(feature = "runtime-wasm")]
+    pub fn execute_module(
+        &self,
+        module_name: &str,
+        workspace_dir: &Path,
+        caps: &WasmCapabilities,
+    ) -> Result<WasmExecutionResult> {
+        use wasmi::{Engine, Linker, Module, Store};
+
+        let wasm_bytes = self.read_module_bytes(module_name, workspace_dir)?;
+
         // Configure engine with fuel metering
         let mut engine_config = wasmi::Config::default();
         engine_config.consume_fuel(true);
@@ -181,8 +217,8 @@ impl WasmRuntime {
         let mut store = Store::new(&engine, ());
         let fuel = self.effective_fuel(caps);
         if fuel > 0 {
-            store.set_fuel(fuel).with_context(|| {
-                format!("Failed to set fuel budget ({fuel}) for module: {module_name}")
+            store.set_fuel(fuel).map_err(|e| {
+                anyhow::anyhow!("Failed to set fuel budget ({fuel}) for module {module_name}: {e}")
             })?;
         }
 
@@ -211,16 +247,8 @@ impl WasmRuntime {
             Ok(code) => code,
             Err(e) => {
                 // Check if we ran out of fuel (infinite loop protection)
-                let fuel_after = store.get_fuel().unwrap_or(0);
-                if fuel_after == 0 && fuel > 0 {
-                    return Ok(WasmExecutionResult {
-                        stdout: String::new(),
-                        stderr: format!(
-                            "WASM module '{module_name}' exceeded fuel limit ({fuel} ticks) — likely an infinite loop"
-                        ),
-                        exit_code: -1,
-                        fuel_consumed: fuel,
-                    });
+                if is_out_of_fuel(&e) {
+                    return Ok(fuel_exhausted(module_name, fuel, String::new()));
                 }
                 bail!("WASM execution error in '{module_name}': {e}");
             }
@@ -229,7 +257,7 @@ impl WasmRuntime {
         let fuel_consumed = fuel_before.saturating_sub(fuel_after);
 
         Ok(WasmExecutionResult {
-            stdout: String::new(),  // No WASI stdout yet — pure computation
+            stdout: String::new(), // No WASI stdout yet — pure computation
             stderr: String::new(),
             exit_code,
             fuel_consumed,
@@ -251,6 +279,170 @@ impl WasmRuntime {
         )
     }
 
+    /// Invoke a plugin module's `call` export with `input` in linear memory.
+    ///
+    /// See the module docs for the ABI. The returned `stdout` holds the
+    /// module's result and `stderr` the lines it logged. Running out of fuel
+    /// yields `exit_code = -1` rather than an error.
+    #[cfg(feature = "runtime-wasm")]
+    pub fn call_module(
+        &self,
+        module_name: &str,
+        workspace_dir: &Path,
+        caps: &WasmCapabilities,
+        input: &[u8],
+    ) -> Result<WasmExecutionResult> {
+        use wasmi::{Engine, Linker, Module, Store, StoreLimitsBuilder};
+
+        let wasm_bytes = self.read_module_bytes(module_name, workspace_dir)?;
+
+        let mut engine_config = wasmi::Config::default();
+        engine_config.consume_fuel(true);
+        let engine = Engine::new(&engine_config);
+
+        let module = Module::new(&engine, &wasm_bytes[..])
+            .with_context(|| format!("Failed to parse WASM module: {module_name}"))?;
+
+        let memory_bytes = usize::try_from(self.effective_memory_bytes(caps)).unwrap_or(usize::MAX);
+        let mut store = Store::new(
+            &engine,
+            HostState {
+                workspace_dir: workspace_dir.to_path_buf(),
+                caps: caps.clone(),
+                log: String::new(),
+                limits: StoreLimitsBuilder::new().memory_size(memory_bytes).build(),
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+        let fuel = self.effective_fuel(caps);
+        if fuel > 0 {
+            store.set_fuel(fuel).map_err(|e| {
+                anyhow::anyhow!("Failed to set fuel budget ({fuel}) for module {module_name}: {e}")
+            })?;
+        }
+
+        let mut linker = Linker::new(&engine);
+        host::link(&mut linker)?;
+
+        let instance = linker
+            .instantiate(&mut store, &module)
+            .and_then(|pre| pre.start(&mut store))
+            .with_context(|| format!("Failed to instantiate WASM module: {module_name}"))?;
+
+        let memory = instance
+            .get_memory(&store, "memory")
+            .with_context(|| format!("WASM plugin '{module_name}' must export 'memory'"))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&store, "alloc")
+            .with_context(|| {
+                format!("WASM plugin '{module_name}' must export 'alloc(i32) -> i32'")
+            })?;
+        let call = instance
+            .get_typed_func::<(i32, i32), i64>(&store, "call")
+            .with_context(|| {
+                format!("WASM plugin '{module_name}' must export 'call(i32, i32) -> i64'")
+            })?;
+
+        let input_len = i32::try_from(input.len()).context("Plugin input too large")?;
+        let fuel_before = store.get_fuel().unwrap_or(0);
+        let outcome = alloc.call(&mut store, input_len).and_then(|ptr| {
+            memory
+                .write(&mut store, ptr.cast_unsigned() as usize, input)
+                .map_err(|e| wasmi::Error::new(format!("input buffer out of bounds: {e}")))?;
+            call.call(&mut store, (ptr, input_len))
+        });
+
+        let packed = match outcome {
+            Ok(packed) => packed,
+            Err(e) => {
+                let log = std::mem::take(&mut store.data_mut().log);
+                if is_out_of_fuel(&e) {
+                    return Ok(fuel_exhausted(module_name, fuel, log));
+                }
+                bail!("WASM execution error in '{module_name}': {e}");
+            }
+        };
+        let fuel_after = store.get_fuel().unwrap_or(0);
+
+        let (out_ptr, out_len) = unpack(packed);
+        let mut output = vec![0u8; out_len];
+        memory.read(&store, out_ptr, &mut output).map_err(|e| {
+            anyhow::anyhow!("WASM plugin '{module_name}' returned an invalid buffer: {e}")
+        })?;
+
+        Ok(WasmExecutionResult {
+            stdout: String::from_utf8_lossy(&output).into_owned(),
+            stderr: std::mem::take(&mut store.data_mut().log),
+            exit_code: 0,
+            fuel_consumed: fuel_before.saturating_sub(fuel_after),
+        })
+    }
+
+    /// Stub for when the `runtime-wasm` feature is not enabled.
+    #[cfg(not(feature = "runtime-wasm"))]
+    pub fn call_module(
+        &self,
+        module_name: &str,
+        _workspace_dir: &Path,
+        _caps: &WasmCapabilities,
+        _input: &[u8],
+    ) -> Result<WasmExecutionResult> {
+        bail!(
+            "WASM runtime is not available in this build. \
+             Rebuild with `cargo build --features runtime-wasm` to enable WASM sandbox support. \
+             Module requested: {module_name}"
+        )
+    }
+
+    /// Check that `wasm_bytes` is a loadable plugin module exporting the plugin ABI.
+    #[cfg(feature = "runtime-wasm")]
+    pub fn validate_plugin_module(wasm_bytes: &[u8]) -> Result<()> {
+        use wasmi::{Engine, ExternType, Module};
+
+        if wasm_bytes.len() > MAX_MODULE_BYTES {
+            bail!("WASM module exceeds 50 MB safety limit");
+        }
+        let module = Module::new(&Engine::default(), wasm_bytes).context("Invalid WASM module")?;
+
+        for required in ["memory", "alloc", "call"] {
+            let export = module
+                .exports()
+                .find(|export| export.name() == required)
+                .with_context(|| format!("WASM plugin must export '{required}'"))?;
+            let kind_ok = match export.ty() {
+                ExternType::Memory(_) => required == "memory",
+                ExternType::Func(_) => required != "memory",
+                _ => false,
+            };
+            if !kind_ok {
+                bail!("WASM plugin export '{required}' has the wrong kind");
+            }
+        }
+        for import in module.imports() {
+            if import.module() != host::IMPORT_MODULE {
+                bail!(
+                    "WASM plugin imports '{}.{}'; only '{}' host functions are available",
+                    import.module(),
+                    import.name(),
+                    host::IMPORT_MODULE
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Without the `runtime-wasm` feature only the module header can be checked.
+    #[cfg(not(feature = "runtime-wasm"))]
+    pub fn validate_plugin_module(wasm_bytes: &[u8]) -> Result<()> {
+        if wasm_bytes.len() > MAX_MODULE_BYTES {
+            bail!("WASM module exceeds 50 MB safety limit");
+        }
+        if !wasm_bytes.starts_with(b"\0asm") {
+            bail!("Invalid WASM module: missing '\\0asm' header");
+        }
+        Ok(())
+    }
+
     /// List available WASM tool modules in the tools directory.
     pub fn list_modules(&self, workspace_dir: &Path) -> Result<Vec<String>> {
         let tools_path = self.tools_dir(workspace_dir);
@@ -275,6 +467,241 @@ impl WasmRuntime {
     }
 }
 
+#[cfg(feature = "runtime-wasm")]
+fn fuel_exhausted(module_name: &str, fuel: u64, log: String) -> WasmExecutionResult {
+    use std::fmt::Write;
+
+    // `log` is empty or newline-terminated (see `HostState::log_line`).
+    let mut stderr = log;
+    let _ = write!(
+        stderr,
+        "WASM module '{module_name}' exceeded fuel limit ({fuel} ticks) — likely an infinite loop"
+    );
+    WasmExecutionResult {
+        stdout: String::new(),
+        stderr,
+        exit_code: -1,
+        fuel_consumed: fuel,
+    }
+}
+
+#[cfg(feature = "runtime-wasm")]
+fn is_out_of_fuel(error: &wasmi::Error) -> bool {
+    error.as_trap_code() == Some(wasmi::core::TrapCode::OutOfFuel)
+}
+
+/// Split a packed `(ptr << 32) | len` ABI value.
+#[cfg(feature = "runtime-wasm")]
+fn unpack(packed: i64) -> (usize, usize) {
+    let packed = packed.cast_unsigned();
+    ((packed >> 32) as usize, (packed & 0xFFFF_FFFF) as usize)
+}
+
+#[cfg(feature = "runtime-wasm")]
+fn pack(ptr: i32, len: i32) -> i64 {
+    ((u64::from(ptr.cast_unsigned()) << 32) | u64::from(len.cast_unsigned())).cast_signed()
+}
+
+/// Per-invocation state visible to host functions.
+#[cfg(feature = "runtime-wasm")]
+struct HostState {
+    workspace_dir: PathBuf,
+    caps: WasmCapabilities,
+    log: String,
+    limits: wasmi::StoreLimits,
+}
+
+#[cfg(feature = "runtime-wasm")]
+impl HostState {
+    fn log_line(&mut self, line: &str) {
+        if self.log.len() >= MAX_LOG_BYTES {
+            return;
+        }
+        let room = MAX_LOG_BYTES - self.log.len();
+        let mut end = line.len().min(room);
+        while !line.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.log.push_str(&line[..end]);
+        self.log.push('\n');
+    }
+}
+
+/// `zeroclaw.*` host functions linked into plugin modules.
+#[cfg(feature = "runtime-wasm")]
+mod host {
+    use super::{pack, resolve_workspace_path, HostState, MAX_HOST_READ_BYTES};
+    use wasmi::{Caller, Extern, Linker};
+
+    pub(super) const IMPORT_MODULE: &str = "zeroclaw";
+
+    pub(super) fn link(linker: &mut Linker<HostState>) -> anyhow::Result<()> {
+        linker.func_wrap(IMPORT_MODULE, "log", log)?;
+        linker.func_wrap(IMPORT_MODULE, "read_file", read_file)?;
+        linker.func_wrap(IMPORT_MODULE, "write_file", write_file)?;
+        Ok(())
+    }
+
+    fn log(mut caller: Caller<'_, HostState>, ptr: i32, len: i32) {
+        if let Ok(bytes) = read_guest(&caller, ptr, len) {
+            let line = String::from_utf8_lossy(&bytes).into_owned();
+            caller.data_mut().log_line(&line);
+        }
+    }
+
+    fn read_file(mut caller: Caller<'_, HostState>, path_ptr: i32, path_len: i32) -> i64 {
+        match try_read_file(&mut caller, path_ptr, path_len) {
+            Ok(packed) => packed,
+            Err(e) => {
+                caller.data_mut().log_line(&format!("read_file: {e}"));
+                -1
+            }
+        }
+    }
+
+    fn try_read_file(
+        caller: &mut Caller<'_, HostState>,
+        path_ptr: i32,
+        path_len: i32,
+    ) -> anyhow::Result<i64> {
+        if !caller.data().caps.read_workspace {
+            anyhow::bail!("capability 'read_workspace' not granted");
+        }
+        let path = read_guest_str(caller, path_ptr, path_len)?;
+        let resolved = resolve_workspace_path(&caller.data().workspace_dir, &path, false)?;
+        let size = std::fs::metadata(&resolved)?.len();
+        if size > MAX_HOST_READ_BYTES {
+            anyhow::bail!("{path} is {size} bytes; limit is {MAX_HOST_READ_BYTES}");
+        }
+        let contents = std::fs::read(&resolved)?;
+        let len = i32::try_from(contents.len())?;
+
+        let alloc = caller
+            .get_export("alloc")
+            .and_then(Extern::into_func)
+            .ok_or_else(|| anyhow::anyhow!("module does not export 'alloc'"))?
+            .typed::<i32, i32>(&*caller)?;
+        let ptr = alloc.call(&mut *caller, len)?;
+        let memory = guest_memory(caller)?;
+        memory
+            .write(&mut *caller, ptr.cast_unsigned() as usize, &contents)
+            .map_err(|e| anyhow::anyhow!("buffer out of bounds: {e}"))?;
+        Ok(pack(ptr, len))
+    }
+
+    fn write_file(
+        mut caller: Caller<'_, HostState>,
+        path_ptr: i32,
+        path_len: i32,
+        data_ptr: i32,
+        data_len: i32,
+    ) -> i32 {
+        match try_write_file(&mut caller, path_ptr, path_len, data_ptr, data_len) {
+            Ok(()) => 0,
+            Err(e) => {
+                caller.data_mut().log_line(&format!("write_file: {e}"));
+                -1
+            }
+        }
+    }
+
+    fn try_write_file(
+        caller: &mut Caller<'_, HostState>,
+        path_ptr: i32,
+        path_len: i32,
+        data_ptr: i32,
+        data_len: i32,
+    ) -> anyhow::Result<()> {
+        if !caller.data().caps.write_workspace {
+            anyhow::bail!("capability 'write_workspace' not granted");
+        }
+        let path = read_guest_str(caller, path_ptr, path_len)?;
+        let data = read_guest(caller, data_ptr, data_len)?;
+        let resolved = resolve_workspace_path(&caller.data().workspace_dir, &path, true)?;
+        std::fs::write(resolved, data)?;
+        Ok(())
+    }
+
+    fn guest_memory(caller: &Caller<'_, HostState>) -> anyhow::Result<wasmi::Memory> {
+        caller
+            .get_export("memory")
+            .and_then(Extern::into_memory)
+            .ok_or_else(|| anyhow::anyhow!("module does not export 'memory'"))
+    }
+
+    fn read_guest(caller: &Caller<'_, HostState>, ptr: i32, len: i32) -> anyhow::Result<Vec<u8>> {
+        let memory = guest_memory(caller)?;
+        let mut buf = vec![0u8; usize::try_from(len)?];
+        memory
+            .read(caller, ptr.cast_unsigned() as usize, &mut buf)
+            .map_err(|e| anyhow::anyhow!("buffer out of bounds: {e}"))?;
+        Ok(buf)
+    }
+
+    fn read_guest_str(
+        caller: &Caller<'_, HostState>,
+        ptr: i32,
+        len: i32,
+    ) -> anyhow::Result<String> {
+        Ok(String::from_utf8(read_guest(caller, ptr, len)?)?)
+    }
+}
+
+/// Resolve a module-supplied relative path inside the workspace.
+///
+/// Absolute paths and `..` components are rejected, and the resolved path
+/// (or its parent, for writes) must stay inside the workspace after
+/// symlinks are followed. Writes never follow a symlinked target.
+#[cfg(feature = "runtime-wasm")]
+fn resolve_workspace_path(
+    workspace_dir: &Path,
+    requested: &str,
+    for_write: bool,
+) -> Result<PathBuf> {
+    use std::path::Component;
+
+    let relative = Path::new(requested);
+    if requested.is_empty()
+        || !relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        bail!("path must be relative to the workspace without '..': {requested}");
+    }
+
+    let workspace = workspace_dir
+        .canonicalize()
+        .with_context(|| format!("workspace not found: {}", workspace_dir.display()))?;
+    let target = workspace.join(relative);
+
+    if !for_write {
+        let resolved = target
+            .canonicalize()
+            .with_context(|| format!("not found: {requested}"))?;
+        if !resolved.starts_with(&workspace) {
+            bail!("path escapes the workspace: {requested}");
+        }
+        return Ok(resolved);
+    }
+
+    let parent = target
+        .parent()
+        .with_context(|| format!("invalid path: {requested}"))?;
+    std::fs::create_dir_all(parent)?;
+    let resolved_parent = parent.canonicalize()?;
+    if !resolved_parent.starts_with(&workspace) {
+        bail!("path escapes the workspace: {requested}");
+    }
+    let file_name = target
+        .file_name()
+        .with_context(|| format!("invalid path: {requested}"))?;
+    let resolved = resolved_parent.join(file_name);
+    if std::fs::symlink_metadata(&resolved).is_ok_and(|meta| meta.file_type().is_symlink()) {
+        bail!("refusing to write through symlink: {requested}");
+    }
+    Ok(resolved)
+}
+
 impl RuntimeAdapter for WasmRuntime {
     fn name(&self) -> &str {
         "wasm"
@@ -308,7 +735,7 @@ impl RuntimeAdapter for WasmRuntime {
         &self,
         _command: &str,
         _workspace_dir: &Path,
-    ) -> anyhow::Result<tokio::process::Command> {
+    ) -> anyhow::Result<LimitedCommand> {
         bail!(
             "WASM runtime does not support shell commands. \
              Use `execute_module()` to run WASM tools, or switch to runtime.kind = \"native\" for shell access."
@@ -379,7 +806,10 @@ mod tests {
         let rt = WasmRuntime::new(default_config());
         let result = rt.build_shell_command("echo hello", Path::new("/tmp"));
         assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("does not support shell"));
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("does not support shell"));
     }
 
     #[test]
@@ -391,7 +821,10 @@ mod tests {
     #[test]
     fn wasm_storage_path_with_workspace() {
         let rt = WasmRuntime::with_workspace(default_config(), PathBuf::from("/home/user/project"));
-        assert_eq!(rt.storage_path(), PathBuf::from("/home/user/project/.zeroclaw"));
+        assert_eq!(
+            rt.storage_path(),
+            PathBuf::from("/home/user/project/.zeroclaw")
+        );
     }
 
     // ── Config validation ──────────────────────────────────────
@@ -636,10 +1069,7 @@ mod tests {
         let rt = WasmRuntime::new(default_config());
         let caps = WasmCapabilities::default();
         let mem_bytes = rt.effective_memory_bytes(&caps);
-        assert!(
-            mem_bytes > 0,
-            "default memory limit must be > 0"
-        );
+        assert!(mem_bytes > 0, "default memory limit must be > 0");
         assert!(
             mem_bytes <= 4096 * 1024 * 1024,
             "default memory must not exceed 4 GB safety limit"
@@ -669,6 +1099,28 @@ mod tests {
         assert!(err.to_string().contains("4 GB safety limit"));
     }
 
+    #[cfg(feature = "runtime-wasm")]
+    #[test]
+    fn resolve_workspace_path_stays_inside_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "x").unwrap();
+
+        assert!(resolve_workspace_path(dir.path(), "a.txt", false).is_ok());
+        assert!(resolve_workspace_path(dir.path(), "../a.txt", false).is_err());
+        assert!(resolve_workspace_path(dir.path(), "/etc/passwd", false).is_err());
+        assert!(resolve_workspace_path(dir.path(), "", true).is_err());
+
+        let written = resolve_workspace_path(dir.path(), "out/b.txt", true).unwrap();
+        assert!(written.starts_with(dir.path().canonicalize().unwrap()));
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink("/etc", dir.path().join("link")).unwrap();
+            assert!(resolve_workspace_path(dir.path(), "link/passwd", false).is_err());
+            assert!(resolve_workspace_path(dir.path(), "link/new.txt", true).is_err());
+        }
+    }
+
     #[test]
     fn execute_module_stub_returns_error_without_feature() {
         if !WasmRuntime::is_available() {
@@ -1,13 +1,25 @@
 //! Audit logging for security events
+//!
+//! With `sign_events` enabled every record carries a sequence number, the
+//! hash of the previous record and an HMAC-SHA256 signature over its own
+//! hash, so edits, deletions and insertions are detectable by
+//! `zeroclaw audit verify` — including across rotated files. The oldest
+//! retained record is anchored in a signed `.audit_anchor` file next to the
+//! signing key, so records cut from the start of the chain are caught too.
 
-use crate::config::AuditConfig;
-use anyhow::Result;
+use super::SecretStore;
+use crate::config::{AuditConfig, Config};
+use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
-use std::fs::OpenOptions;
-use std::io::Write;
-use std::path::PathBuf;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// Audit event types
@@ -21,6 +33,8 @@ pub enum AuditEventType {
     AuthFailure,
     PolicyViolation,
     SecurityEvent,
+    ResourceLimitExceeded,
+    ApprovalDecision,
 }
 
 /// Actor information (who performed the action)
@@ -67,6 +81,18 @@ pub struct AuditEvent {
     pub action: Option<Action>,
     pub result: Option<ExecutionResult>,
     pub security: SecurityContext,
+    /// Position in the hash chain (signed logs only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<u64>,
+    /// Hash of the preceding record (signed logs only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev_hash: Option<String>,
+    /// SHA-256 of this record without `hash` and `signature`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    /// HMAC-SHA256 of `hash` with the audit signing key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
 }
 
 impl AuditEvent {
@@ -84,6 +110,10 @@ impl AuditEvent {
                 rate_limit_remaining: None,
                 sandbox_backend: None,
             },
+            sequence: None,
+            prev_hash: None,
+            hash: None,
+            signature: None,
         }
     }
 
@@ -148,6 +178,10 @@ pub struct AuditLogger {
     log_path: PathBuf,
     config: AuditConfig,
     buffer: Mutex<Vec<AuditEvent>>,
+    /// HMAC key when `sign_events` is enabled.
+    signing_key: Option<Vec<u8>>,
+    /// Where the signed [`ChainAnchor`] is kept.
+    anchor_path: PathBuf,
 }
 
 /// Structured command execution details for audit logging.
@@ -162,14 +196,73 @@ pub struct CommandExecutionLog<'a> {
     pub duration_ms: u64,
 }
 
+/// A command stopped by `[security.resources]` limits.
+#[derive(Debug, Clone)]
+pub struct ResourceLimitLog<'a> {
+    pub channel: &'a str,
+    pub command: &'a str,
+    /// Human-readable breach, e.g. `memory limit of 512 MB exceeded`.
+    pub detail: &'a str,
+    /// How the limit was enforced (`cgroup-v2`, `rlimit`, ...).
+    pub enforcement: &'a str,
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+}
+
+/// An approval decision for a gated tool call.
+#[derive(Debug, Clone)]
+pub struct ApprovalAuditLog<'a> {
+    pub channel: &'a str,
+    pub tool_name: &'a str,
+    pub arguments_summary: &'a str,
+    /// `yes`, `no` or `always`.
+    pub decision: &'a str,
+}
+
 impl AuditLogger {
-    /// Create a new audit logger
+    /// Create a new audit logger. The signing key (when `sign_events` is set)
+    /// is kept encrypted in the [`SecretStore`] under `zeroclaw_dir`.
     pub fn new(config: AuditConfig, zeroclaw_dir: PathBuf) -> Result<Self> {
+        let secrets = SecretStore::new(&zeroclaw_dir, true);
+        Self::with_secret_store(config, zeroclaw_dir, &secrets)
+    }
+
+    /// Create the logger described by `config.security.audit`.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let zeroclaw_dir = config_dir(config)?;
+        let secrets = SecretStore::new(&zeroclaw_dir, config.secrets.encrypt);
+        Self::with_secret_store(config.security.audit.clone(), zeroclaw_dir, &secrets)
+    }
+
+    /// [`from_config`](Self::from_config) for callers that keep working
+    /// without an audit log: failures are logged and yield `None`.
+    pub fn try_from_config(config: &Config) -> Option<Arc<Self>> {
+        match Self::from_config(config) {
+            Ok(logger) => Some(Arc::new(logger)),
+            Err(e) => {
+                tracing::warn!("Security audit log unavailable: {e:#}");
+                None
+            }
+        }
+    }
+
+    fn with_secret_store(
+        config: AuditConfig,
+        zeroclaw_dir: PathBuf,
+        secrets: &SecretStore,
+    ) -> Result<Self> {
         let log_path = zeroclaw_dir.join(&config.log_path);
+        let signing_key = if config.enabled && config.sign_events {
+            Some(secrets.load_or_create_signing_key(SIGNING_KEY_NAME)?)
+        } else {
+            None
+        };
         Ok(Self {
             log_path,
             config,
             buffer: Mutex::new(Vec::new()),
+            signing_key,
+            anchor_path: zeroclaw_dir.join(ANCHOR_FILE),
         })
     }
 
@@ -179,11 +272,36 @@ impl AuditLogger {
             return Ok(());
         }
 
+        // Serialize writers (across processes too) so each record links to
+        // the one actually written before it.
+        let _lock = ChainLock::acquire(&self.log_path)?;
+
         // Check log size and rotate if needed
         self.rotate_if_needed()?;
 
         // Serialize and write
-        let line = serde_json::to_string(event)?;
+        let mut genesis = None;
+        let line = match &self.signing_key {
+            Some(key) => {
+                let mut event = event.clone();
+                let (sequence, prev_hash) = match chain_head(&self.log_path)? {
+                    Some(head) => (head.sequence + 1, head.hash),
+                    None => (0, GENESIS_HASH.to_string()),
+                };
+                event.sequence = Some(sequence);
+                event.prev_hash = Some(prev_hash);
+                event.hash = None;
+                event.signature = None;
+                let hash = record_hash(&serde_json::to_value(&event)?)?;
+                event.signature = Some(sign(key, &hash));
+                if sequence == 0 {
+                    genesis = Some(hash.clone());
+                }
+                event.hash = Some(hash);
+                serde_json::to_string(&event)?
+            }
+            None => serde_json::to_string(event)?,
+        };
         let mut file = OpenOptions::new()
             .create(true)
             .append(true)
@@ -192,6 +310,14 @@ impl AuditLogger {
         writeln!(file, "{}", line)?;
         file.sync_all()?;
 
+        // Anchor a brand-new chain. An existing anchor is kept, so a chain
+        // restarted over a wiped log still fails verification.
+        if let (Some(key), Some(hash)) = (&self.signing_key, genesis) {
+            if !self.anchor_path.exists() {
+                ChainAnchor::new(key, &self.log_path, 0, hash).save(&self.anchor_path)?;
+            }
+        }
+
         Ok(())
     }
 
@@ -210,6 +336,28 @@ impl AuditLogger {
         self.log(&event)
     }
 
+    /// Log a resource limit breach.
+    pub fn log_resource_limit(&self, entry: ResourceLimitLog<'_>) -> Result<()> {
+        let mut event = AuditEvent::new(AuditEventType::ResourceLimitExceeded)
+            .with_actor(entry.channel.to_string(), None, None)
+            .with_result(
+                false,
+                entry.exit_code,
+                entry.duration_ms,
+                Some(entry.detail.to_string()),
+            )
+            .with_security(Some(entry.enforcement.to_string()));
+        event.action = Some(Action {
+            command: Some(entry.command.to_string()),
+            risk_level: None,
+            approved: false,
+            allowed: true,
+        });
+        event.security.policy_violation = true;
+
+        self.log(&event)
+    }
+
     /// Backward-compatible helper to log a command execution event.
     #[allow(clippy::too_many_arguments)]
     pub fn log_command(
@@ -233,6 +381,21 @@ impl AuditLogger {
         })
     }
 
+    /// Log an approval decision.
+    pub fn log_approval(&self, entry: ApprovalAuditLog<'_>) -> Result<()> {
+        let approved = entry.decision != "no";
+        let event = AuditEvent::new(AuditEventType::ApprovalDecision)
+            .with_actor(entry.channel.to_string(), None, None)
+            .with_action(
+                format!("{}: {}", entry.tool_name, entry.arguments_summary),
+                entry.decision.to_string(),
+                approved,
+                approved,
+            );
+
+        self.log(&event)
+    }
+
     /// Rotate log if it exceeds max size
     fn rotate_if_needed(&self) -> Result<()> {
         if let Ok(metadata) = std::fs::metadata(&self.log_path) {
@@ -244,20 +407,517 @@ impl AuditLogger {
         Ok(())
     }
 
-    /// Rotate the log file
+    /// Rotate the log file. The chain continues into the new file: its first
+    /// record links to the last record of `<log>.1.log`.
+    ///
+    /// When the oldest file is about to be dropped, the anchor moves to the
+    /// first record that remains. It is written before any file is renamed,
+    /// so an interrupted rotation leaves the anchor inside the retained chain.
     fn rotate(&self) -> Result<()> {
-        for i in (1..10).rev() {
-            let old_name = format!("{}.{}.log", self.log_path.display(), i);
-            let new_name = format!("{}.{}.log", self.log_path.display(), i + 1);
-            let _ = std::fs::rename(&old_name, &new_name);
+        let oldest = rotated_path(&self.log_path, MAX_ROTATED_FILES);
+        if let (Some(key), true) = (&self.signing_key, oldest.exists()) {
+            let retained = (1..MAX_ROTATED_FILES)
+                .rev()
+                .map(|i| rotated_path(&self.log_path, i))
+                .chain(std::iter::once(self.log_path.clone()));
+            if let Some((sequence, hash)) = first_signed_record(retained)? {
+                ChainAnchor::new(key, &self.log_path, sequence, hash).save(&self.anchor_path)?;
+            }
+        }
+
+        for i in (1..MAX_ROTATED_FILES).rev() {
+            let _ = std::fs::rename(
+                rotated_path(&self.log_path, i),
+                rotated_path(&self.log_path, i + 1),
+            );
         }
 
-        let rotated = format!("{}.1.log", self.log_path.display());
-        std::fs::rename(&self.log_path, &rotated)?;
+        std::fs::rename(&self.log_path, rotated_path(&self.log_path, 1))?;
         Ok(())
     }
 }
 
+/// Name of the audit HMAC key in the secret store.
+const SIGNING_KEY_NAME: &str = "audit_signing";
+/// Signed record of where the retained chain starts, next to the signing key.
+const ANCHOR_FILE: &str = ".audit_anchor";
+/// `prev_hash` of the first record in a chain.
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+/// Rotated files kept (`<log>.1.log` newest … `<log>.10.log` oldest).
+const MAX_ROTATED_FILES: usize = 10;
+
+fn config_dir(config: &Config) -> Result<PathBuf> {
+    config
+        .config_path
+        .parent()
+        .map(PathBuf::from)
+        .context("config path has no parent directory")
+}
+
+fn rotated_path(log_path: &Path, index: usize) -> PathBuf {
+    PathBuf::from(format!("{}.{index}.log", log_path.display()))
+}
+
+/// SHA-256 over the record with `hash` and `signature` removed. Keys are
+/// serialized in sorted order, so the digest does not depend on field order.
+fn record_hash(record: &serde_json::Value) -> Result<String> {
+    let mut record = record.clone();
+    if let Some(fields) = record.as_object_mut() {
+        fields.remove("hash");
+        fields.remove("signature");
+    }
+    let canonical = serde_json::to_string(&record)?;
+    Ok(hex::encode(Sha256::digest(canonical.as_bytes())))
+}
+
+fn sign(key: &[u8], hash: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(hash.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+fn signature_valid(key: &[u8], hash: &str, signature: &str) -> bool {
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(hash.as_bytes());
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// Sequence and hash of the newest chained record.
+struct ChainHead {
+    sequence: u64,
+    hash: String,
+}
+
+/// Find the record to link to: the last line of the live log, or of the
+/// newest rotated file when the live log was just rotated away.
+fn chain_head(log_path: &Path) -> Result<Option<ChainHead>> {
+    for path in [log_path.to_path_buf(), rotated_path(log_path, 1)] {
+        let Some(line) = last_line(&path)? else {
+            continue;
+        };
+        let event: AuditEvent = match serde_json::from_str(&line) {
+            Ok(event) => event,
+            Err(e) => {
+                // Keep logging; `zeroclaw audit verify` reports the bad line.
+                tracing::warn!(
+                    "Last audit record in {} is unreadable ({e}); starting a new chain",
+                    path.display()
+                );
+                return Ok(None);
+            }
+        };
+        // An unsigned last record means signing was just enabled: start a
+        // fresh chain.
+        return Ok(match (event.sequence, event.hash) {
+            (Some(sequence), Some(hash)) => Some(ChainHead { sequence, hash }),
+            _ => None,
+        });
+    }
+    Ok(None)
+}
+
+/// Last non-empty line of a file, reading backwards from the end.
+fn last_line(path: &Path) -> Result<Option<String>> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let len = file.metadata()?.len();
+    let mut window: u64 = 8 * 1024;
+    loop {
+        let start = len.saturating_sub(window);
+        file.seek(SeekFrom::Start(start))?;
+        let mut tail = Vec::new();
+        file.read_to_end(&mut tail)?;
+        let text = String::from_utf8_lossy(&tail);
+        let trimmed = text.trim_end();
+        match trimmed.rfind('\n') {
+            Some(pos) => return Ok(Some(trimmed[pos + 1..].to_string())),
+            None if start == 0 => {
+                return Ok((!trimmed.is_empty()).then(|| trimmed.to_string()));
+            }
+            None => window = window.saturating_mul(4),
+        }
+    }
+}
+
+/// Sequence and hash of the oldest record the chain must still contain,
+/// signed with the audit key so it cannot be moved forward to hide records
+/// cut from the start of the log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ChainAnchor {
+    log: PathBuf,
+    sequence: u64,
+    hash: String,
+    signature: String,
+}
+
+impl ChainAnchor {
+    fn new(key: &[u8], log: &Path, sequence: u64, hash: String) -> Self {
+        let signature = sign(key, &Self::message(sequence, &hash));
+        Self {
+            log: log.to_path_buf(),
+            sequence,
+            hash,
+            signature,
+        }
+    }
+
+    /// Signed payload, prefixed so a record signature cannot pass as an anchor's.
+    fn message(sequence: u64, hash: &str) -> String {
+        format!("anchor:{sequence}:{hash}")
+    }
+
+    fn is_signed(&self, key: &[u8]) -> bool {
+        signature_valid(
+            key,
+            &Self::message(self.sequence, &self.hash),
+            &self.signature,
+        )
+    }
+
+    /// Whether this anchor was written for the log at `log_path`.
+    fn covers(&self, log_path: &Path) -> bool {
+        let canonical =
+            |path: &Path| std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        canonical(&self.log) == canonical(log_path)
+    }
+
+    fn load(path: &Path) -> Result<Option<Self>> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map(Some)
+                .with_context(|| format!("audit anchor {} is corrupt", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_string(self)?)?;
+        std::fs::rename(&tmp, path)
+            .with_context(|| format!("failed to write audit anchor {}", path.display()))
+    }
+}
+
+/// Sequence and hash of the first signed record across `files`, oldest first.
+fn first_signed_record(files: impl Iterator<Item = PathBuf>) -> Result<Option<(u64, String)>> {
+    use std::io::BufRead;
+
+    for path in files {
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        for line in std::io::BufReader::new(file).lines() {
+            let Ok(event) = serde_json::from_str::<AuditEvent>(&line?) else {
+                continue;
+            };
+            if let (Some(sequence), Some(hash)) = (event.sequence, event.hash) {
+                return Ok(Some((sequence, hash)));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Exclusive lock on `<log>.lock`, released on drop.
+struct ChainLock {
+    _file: File,
+}
+
+impl ChainLock {
+    fn acquire(log_path: &Path) -> Result<Self> {
+        if let Some(parent) = log_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(format!("{}.lock", log_path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::io::AsRawFd;
+            // SAFETY: the descriptor is valid for the lifetime of `file`;
+            // the lock is released when it is closed.
+            if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } != 0 {
+                return Err(std::io::Error::last_os_error()).context("failed to lock audit log");
+            }
+        }
+        Ok(Self { _file: file })
+    }
+}
+
+/// Outcome of [`verify_chain`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainReport {
+    /// Files checked, oldest first.
+    pub files: Vec<PathBuf>,
+    /// Signed records that verified.
+    pub verified: u64,
+    /// Unsigned records written before signing was enabled.
+    pub unsigned_prefix: u64,
+    /// Sequence and hash of the newest record, for anchoring elsewhere.
+    pub head: Option<(u64, String)>,
+    /// First sequence of a chain that does not start at genesis when no
+    /// anchor accounts for the records before it.
+    pub unanchored_start: Option<u64>,
+    pub problem: Option<ChainBreak>,
+}
+
+/// The first record that failed verification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainBreak {
+    pub file: PathBuf,
+    /// 1-based line number within `file`.
+    pub line: usize,
+    pub sequence: Option<u64>,
+    pub reason: String,
+}
+
+impl fmt::Display for ChainBreak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file.display(), self.line)?;
+        if let Some(sequence) = self.sequence {
+            write!(f, " (sequence {sequence})")?;
+        }
+        write!(f, ": {}", self.reason)
+    }
+}
+
+/// Walk the live log and its rotated files oldest-first, checking each
+/// record's hash, signature and link to its predecessor. Stops at the first
+/// broken, missing or unsigned record.
+///
+/// The anchor at `anchor_path`, when it was written for this log, must be
+/// signed with `key` and its record must still be in the chain; a chain
+/// that starts after it lost records from the front.
+///
+/// Records removed from the end of the newest file cannot be detected from
+/// the log alone; compare the reported head with a copy kept elsewhere.
+pub fn verify_chain(log_path: &Path, anchor_path: &Path, key: &[u8]) -> Result<ChainReport> {
+    let mut files: Vec<PathBuf> = (1..=MAX_ROTATED_FILES)
+        .rev()
+        .map(|i| rotated_path(log_path, i))
+        .filter(|path| path.exists())
+        .collect();
+    if log_path.exists() {
+        files.push(log_path.to_path_buf());
+    }
+
+    let mut report = ChainReport {
+        files: files.clone(),
+        verified: 0,
+        unsigned_prefix: 0,
+        head: None,
+        unanchored_start: None,
+        problem: None,
+    };
+    let anchor_break = |sequence: Option<u64>, reason: String| ChainBreak {
+        file: anchor_path.to_path_buf(),
+        line: 1,
+        sequence,
+        reason,
+    };
+    let anchor = match ChainAnchor::load(anchor_path)? {
+        Some(anchor) if anchor.covers(log_path) => {
+            if !anchor.is_signed(key) {
+                report.problem = Some(anchor_break(
+                    Some(anchor.sequence),
+                    "anchor signature is invalid".into(),
+                ));
+                return Ok(report);
+            }
+            Some(anchor)
+        }
+        _ => None,
+    };
+    let mut anchor_seen = false;
+    let mut previous: Option<(u64, String)> = None;
+
+    for file in &files {
+        let contents = std::fs::read_to_string(file)
+            .with_context(|| format!("failed to read {}", file.display()))?;
+        for (index, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let fail = |sequence: Option<u64>, reason: String| ChainBreak {
+                file: file.clone(),
+                line: index + 1,
+                sequence,
+                reason,
+            };
+
+            let record: serde_json::Value = match serde_json::from_str(line) {
+                Ok(record) => record,
+                Err(e) => {
+                    report.problem = Some(fail(None, format!("malformed record: {e}")));
+                    return Ok(report);
+                }
+            };
+            let sequence = record.get("sequence").and_then(serde_json::Value::as_u64);
+            let (Some(sequence), Some(prev_hash), Some(hash), Some(signature)) = (
+                sequence,
+                str_field(&record, "prev_hash"),
+                str_field(&record, "hash"),
+                str_field(&record, "signature"),
+            ) else {
+                if previous.is_none() {
+                    report.unsigned_prefix += 1;
+                    continue;
+                }
+                report.problem = Some(fail(
+                    sequence,
+                    "unsigned record inside the signed chain".into(),
+                ));
+                return Ok(report);
+            };
+
+            if record_hash(&record)? != hash {
+                report.problem = Some(fail(
+                    Some(sequence),
+                    "record was modified (hash mismatch)".into(),
+                ));
+                return Ok(report);
+            }
+            if !signature_valid(key, hash, signature) {
+                report.problem = Some(fail(Some(sequence), "signature is invalid".into()));
+                return Ok(report);
+            }
+            match &previous {
+                Some((prev_sequence, prev)) => {
+                    if sequence != prev_sequence + 1 {
+                        report.problem = Some(fail(
+                            Some(sequence),
+                            format!(
+                                "missing record(s): expected sequence {}, found {sequence}",
+                                prev_sequence + 1
+                            ),
+                        ));
+                        return Ok(report);
+                    }
+                    if prev_hash != prev.as_str() {
+                        report.problem = Some(fail(
+                            Some(sequence),
+                            "does not link to the previous record".into(),
+                        ));
+                        return Ok(report);
+                    }
+                }
+                None if sequence == 0 && prev_hash != GENESIS_HASH => {
+                    report.problem = Some(fail(
+                        Some(sequence),
+                        "first record does not start a chain".into(),
+                    ));
+                    return Ok(report);
+                }
+                // The oldest retained record may follow files rotated away,
+                // but never the anchored one.
+                None => match &anchor {
+                    Some(anchor) if sequence > anchor.sequence => {
+                        report.problem = Some(fail(
+                            Some(sequence),
+                            format!(
+                                "chain starts at sequence {sequence} but is anchored at sequence {}; leading records were removed",
+                                anchor.sequence
+                            ),
+                        ));
+                        return Ok(report);
+                    }
+                    None if sequence > 0 => report.unanchored_start = Some(sequence),
+                    _ => {}
+                },
+            }
+            if let Some(anchor) = anchor.as_ref().filter(|a| a.sequence == sequence) {
+                if anchor.hash != hash {
+                    report.problem = Some(fail(
+                        Some(sequence),
+                        "record does not match the anchored record".into(),
+                    ));
+                    return Ok(report);
+                }
+                anchor_seen = true;
+            }
+
+            report.verified += 1;
+            previous = Some((sequence, hash.to_string()));
+        }
+    }
+
+    if let Some(anchor) = anchor.filter(|_| !anchor_seen) {
+        report.problem = Some(anchor_break(
+            Some(anchor.sequence),
+            format!(
+                "anchored record (sequence {}) is missing from the log",
+                anchor.sequence
+            ),
+        ));
+        return Ok(report);
+    }
+
+    report.head = previous;
+    Ok(report)
+}
+
+fn str_field<'a>(record: &'a serde_json::Value, name: &str) -> Option<&'a str> {
+    record.get(name).and_then(serde_json::Value::as_str)
+}
+
+/// Handle `zeroclaw audit` subcommands.
+pub fn handle_command(command: crate::AuditCommands, config: &Config) -> Result<()> {
+    match command {
+        crate::AuditCommands::Verify { path } => {
+            let zeroclaw_dir = config_dir(config)?;
+            let log_path =
+                path.unwrap_or_else(|| zeroclaw_dir.join(&config.security.audit.log_path));
+            let secrets = SecretStore::new(&zeroclaw_dir, config.secrets.encrypt);
+            let key = secrets
+                .load_signing_key(SIGNING_KEY_NAME)?
+                .context("no audit signing key found; enable [security.audit] sign_events first")?;
+
+            let report = verify_chain(&log_path, &zeroclaw_dir.join(ANCHOR_FILE), &key)?;
+            if report.files.is_empty() {
+                anyhow::bail!("no audit log found at {}", log_path.display());
+            }
+            if let Some(problem) = report.problem {
+                anyhow::bail!(
+                    "audit log verification failed after {} good record(s) at {problem}",
+                    report.verified
+                );
+            }
+
+            println!(
+                "✅ {} signed record(s) verified across {} file(s)",
+                report.verified,
+                report.files.len()
+            );
+            if report.unsigned_prefix > 0 {
+                println!(
+                    "   {} unsigned record(s) precede the chain (written before signing was enabled)",
+                    report.unsigned_prefix
+                );
+            }
+            if let Some(sequence) = report.unanchored_start {
+                println!(
+                    "⚠️  chain starts at sequence {sequence} and no anchor accounts for the records before it"
+                );
+            }
+            if let Some((sequence, hash)) = report.head {
+                println!("   head: sequence {sequence}, hash {hash}");
+            }
+            Ok(())
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -333,6 +993,238 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn audit_log_resource_limit_marks_policy_violation() -> Result<()> {
+        let tmp = TempDir::new()?;
+        let logger = AuditLogger::new(AuditConfig::default(), tmp.path().to_path_buf())?;
+
+        logger.log_resource_limit(ResourceLimitLog {
+            channel: "shell",
+            command: "yes > /dev/null",
+            detail: "CPU time limit of 60s exceeded",
+            enforcement: "rlimit",
+            exit_code: None,
+            duration_ms: 60_000,
+        })?;
+
+        let content = std::fs::read_to_string(tmp.path().join("audit.log"))?;
+        let parsed: AuditEvent = serde_json::from_str(content.trim())?;
+        assert!(matches!(
+            parsed.event_type,
+            AuditEventType::ResourceLimitExceeded
+        ));
+        assert!(parsed.security.policy_violation);
+        assert_eq!(parsed.security.sandbox_backend.as_deref(), Some("rlimit"));
+        let result = parsed.result.unwrap();
+        assert!(!result.success);
+        assert_eq!(
+            result.error.as_deref(),
+            Some("CPU time limit of 60s exceeded")
+        );
+        Ok(())
+    }
+
+    fn signed_logger(dir: &Path, max_size_mb: u32) -> AuditLogger {
+        let config = AuditConfig {
+            sign_events: true,
+            max_size_mb,
+            ..AuditConfig::default()
+        };
+        AuditLogger::new(config, dir.to_path_buf()).unwrap()
+    }
+
+    fn signing_key(dir: &Path) -> Vec<u8> {
+        SecretStore::new(dir, true)
+            .load_signing_key(SIGNING_KEY_NAME)
+            .unwrap()
+            .unwrap()
+    }
+
+    fn verify(dir: &Path, key: &[u8]) -> Result<ChainReport> {
+        verify_chain(&dir.join("audit.log"), &dir.join(ANCHOR_FILE), key)
+    }
+
+    fn log_commands(logger: &AuditLogger, count: usize) {
+        for i in 0..count {
+            logger
+                .log_command("cli", &format!("echo {i}"), "low", false, true, true, 1)
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn signed_events_form_a_verifiable_chain() {
+        let tmp = TempDir::new().unwrap();
+        let logger = signed_logger(tmp.path(), 10);
+        log_commands(&logger, 3);
+
+        let content = std::fs::read_to_string(tmp.path().join("audit.log")).unwrap();
+        let events: Vec<AuditEvent> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(events[0].sequence, Some(0));
+        assert_eq!(events[0].prev_hash.as_deref(), Some(GENESIS_HASH));
+        assert_eq!(events[2].sequence, Some(2));
+        assert_eq!(events[2].prev_hash, events[1].hash);
+        assert!(events.iter().all(|event| event.signature.is_some()));
+
+        let report = verify(tmp.path(), &signing_key(tmp.path())).unwrap();
+        assert_eq!(report.problem, None);
+        assert_eq!(report.verified, 3);
+        assert_eq!(report.head.map(|(sequence, _)| sequence), Some(2));
+    }
+
+    #[test]
+    fn verify_pinpoints_edited_record() {
+        let tmp = TempDir::new().unwrap();
+        let logger = signed_logger(tmp.path(), 10);
+        log_commands(&logger, 3);
+
+        let log_path = tmp.path().join("audit.log");
+        let content = std::fs::read_to_string(&log_path).unwrap();
+        std::fs::write(&log_path, content.replacen("echo 1", "echo X", 1)).unwrap();
+
+        let report = verify(tmp.path(), &signing_key(tmp.path())).unwrap();
+        let problem = report.problem.unwrap();
+        assert_eq!(problem.line, 2);
+        assert_eq!(problem.sequence, Some(1));
+        assert!(problem.reason.contains("modified"));
+        assert_eq!(report.verified, 1);
+    }
+
+    #[test]
+    fn verify_pinpoints_deleted_record() {
+        let tmp = TempDir::new().unwrap();
+        let logger = signed_logger(tmp.path(), 10);
+        log_commands(&logger, 4);
+
+        let log_path = tmp.path().join("audit.log");
+        let content = std::fs::read_to_string(&log_path).unwrap();
+        let kept: Vec<&str> = content
+            .lines()
+            .enumerate()
+            .filter_map(|(i, line)| (i != 1).then_some(line))
+            .collect();
+        std::fs::write(&log_path, kept.join("\n") + "\n").unwrap();
+
+        let problem = verify(tmp.path(), &signing_key(tmp.path()))
+            .unwrap()
+            .problem
+            .unwrap();
+        assert_eq!(problem.line, 2);
+        assert!(problem.reason.contains("expected sequence 1, found 2"));
+    }
+
+    #[test]
+    fn verify_rejects_forged_signature() {
+        let tmp = TempDir::new().unwrap();
+        let logger = signed_logger(tmp.path(), 10);
+        log_commands(&logger, 2);
+
+        let problem = verify(tmp.path(), &[7u8; 32]).unwrap().problem.unwrap();
+        assert_eq!(problem.line, 1);
+        assert!(problem.reason.contains("signature"));
+    }
+
+    #[test]
+    fn chain_continues_across_rotated_files() {
+        let tmp = TempDir::new().unwrap();
+        // max_size_mb = 0 rotates before every write.
+        let logger = signed_logger(tmp.path(), 0);
+        log_commands(&logger, 4);
+
+        let log_path = tmp.path().join("audit.log");
+        let report = verify(tmp.path(), &signing_key(tmp.path())).unwrap();
+        assert_eq!(report.problem, None);
+        assert_eq!(report.files.len(), 4);
+        assert_eq!(report.verified, 4);
+        assert_eq!(report.head.map(|(sequence, _)| sequence), Some(3));
+
+        std::fs::remove_file(rotated_path(&log_path, 2)).unwrap();
+        let problem = verify(tmp.path(), &signing_key(tmp.path()))
+            .unwrap()
+            .problem
+            .unwrap();
+        assert_eq!(problem.file, rotated_path(&log_path, 1));
+        assert!(problem.reason.contains("expected sequence 1, found 2"));
+    }
+
+    #[test]
+    fn verify_flags_leading_records_removed_from_the_log() {
+        let tmp = TempDir::new().unwrap();
+        let logger = signed_logger(tmp.path(), 10);
+        log_commands(&logger, 4);
+
+        let log_path = tmp.path().join("audit.log");
+        let content = std::fs::read_to_string(&log_path).unwrap();
+        let kept: Vec<&str> = content.lines().skip(2).collect();
+        std::fs::write(&log_path, kept.join("\n") + "\n").unwrap();
+
+        let key = signing_key(tmp.path());
+        let problem = verify(tmp.path(), &key).unwrap().problem.unwrap();
+        assert_eq!(problem.line, 1);
+        assert_eq!(problem.sequence, Some(2));
+        assert!(problem.reason.contains("anchored at sequence 0"));
+
+        // Without the anchor the gap still shows up, as a warning.
+        std::fs::remove_file(tmp.path().join(ANCHOR_FILE)).unwrap();
+        let report = verify(tmp.path(), &key).unwrap();
+        assert_eq!(report.problem, None);
+        assert_eq!(report.unanchored_start, Some(2));
+    }
+
+    #[test]
+    fn anchor_follows_rotation_and_catches_dropped_files() {
+        let tmp = TempDir::new().unwrap();
+        let logger = signed_logger(tmp.path(), 0);
+        log_commands(&logger, MAX_ROTATED_FILES + 3);
+
+        let log_path = tmp.path().join("audit.log");
+        let key = signing_key(tmp.path());
+        let report = verify(tmp.path(), &key).unwrap();
+        assert_eq!(report.problem, None);
+        assert_eq!(report.unanchored_start, None);
+        assert_eq!(report.verified, MAX_ROTATED_FILES as u64 + 1);
+        let anchor = ChainAnchor::load(&tmp.path().join(ANCHOR_FILE))
+            .unwrap()
+            .unwrap();
+        assert_eq!(anchor.sequence, 2);
+
+        std::fs::remove_file(rotated_path(&log_path, MAX_ROTATED_FILES)).unwrap();
+        let problem = verify(tmp.path(), &key).unwrap().problem.unwrap();
+        assert!(problem.reason.contains("leading records were removed"));
+
+        // Moving the anchor forward without the key is caught as well.
+        let forged = ChainAnchor {
+            sequence: 3,
+            ..anchor
+        };
+        forged.save(&tmp.path().join(ANCHOR_FILE)).unwrap();
+        let problem = verify(tmp.path(), &key).unwrap().problem.unwrap();
+        assert!(problem.reason.contains("anchor signature"));
+    }
+
+    #[test]
+    fn unsigned_records_before_signing_are_tolerated_but_not_after() {
+        let tmp = TempDir::new().unwrap();
+        let plain = AuditLogger::new(AuditConfig::default(), tmp.path().to_path_buf()).unwrap();
+        log_commands(&plain, 2);
+        let signed = signed_logger(tmp.path(), 10);
+        log_commands(&signed, 2);
+
+        let key = signing_key(tmp.path());
+        let report = verify(tmp.path(), &key).unwrap();
+        assert_eq!(report.problem, None);
+        assert_eq!(report.unsigned_prefix, 2);
+        assert_eq!(report.verified, 2);
+
+        log_commands(&plain, 1);
+        let problem = verify(tmp.path(), &key).unwrap().problem.unwrap();
+        assert_eq!(problem.line, 5);
+        assert!(problem.reason.contains("unsigned"));
+    }
+
     // ── §8.1 Log rotation tests ─────────────────────────────
 
     #[tokio::test]
@@ -1,7 +1,7 @@
 //! Auto-detection of available security features
 
 use crate::config::{SandboxBackend, SecurityConfig};
-use crate::security::traits::Sandbox;
+use crate::security::traits::{Sandbox, StackedSandbox};
 use std::sync::Arc;
 
 /// Create a sandbox based on auto-detection or explicit config
@@ -21,14 +21,14 @@ pub fn create_sandbox(config: &SecurityConfig) -> Arc<dyn Sandbox> {
                 #[cfg(target_os = "linux")]
                 {
                     if let Ok(sandbox) = super::landlock::LandlockSandbox::new() {
-                        return Arc::new(sandbox);
+                        return with_seccomp(config, Arc::new(sandbox));
                     }
                 }
             }
             tracing::warn!(
                 "Landlock requested but not available, falling back to application-layer"
             );
-            Arc::new(super::traits::NoopSandbox)
+            with_seccomp(config, Arc::new(super::traits::NoopSandbox))
         }
         SandboxBackend::Firejail => {
             #[cfg(target_os = "linux")]
@@ -64,15 +64,56 @@ pub fn create_sandbox(config: &SecurityConfig) -> Arc<dyn Sandbox> {
             tracing::warn!("Docker requested but not available, falling back to application-layer");
             Arc::new(super::traits::NoopSandbox)
         }
+        SandboxBackend::Seccomp => with_seccomp(config, Arc::new(super::traits::NoopSandbox)),
         SandboxBackend::Auto | SandboxBackend::None => {
             // Auto-detect best available
-            detect_best_sandbox()
+            detect_best_sandbox(config)
+        }
+    }
+}
+
+/// Create the sandbox for commands the agent spawns itself (the shell tool).
+///
+/// Returns `None` unless seccomp is enabled. Wrapper backends (Firejail,
+/// Bubblewrap, Docker) replace the command with another binary, so the
+/// seccomp filter cannot be stacked with them.
+pub fn create_command_sandbox(config: &SecurityConfig) -> Option<Arc<dyn Sandbox>> {
+    if !config.sandbox.seccomp_enabled() {
+        return None;
+    }
+    match config.sandbox.backend {
+        SandboxBackend::Firejail | SandboxBackend::Bubblewrap | SandboxBackend::Docker => {
+            tracing::warn!(
+                "Seccomp cannot be stacked with the {:?} sandbox backend, skipping it",
+                config.sandbox.backend
+            );
+            None
+        }
+        _ => Some(create_sandbox(config)),
+    }
+}
+
+/// Layer the seccomp filter over `base` when `[security.sandbox.seccomp]` asks
+/// for it. Landlock runs first so its own syscalls are not filtered.
+fn with_seccomp(config: &SecurityConfig, base: Arc<dyn Sandbox>) -> Arc<dyn Sandbox> {
+    if !config.sandbox.seccomp_enabled() {
+        return base;
+    }
+    match super::seccomp::SeccompSandbox::probe() {
+        Ok(seccomp) if base.name() == "none" => Arc::new(seccomp),
+        Ok(seccomp) => Arc::new(StackedSandbox::new(vec![base, Arc::new(seccomp)])),
+        Err(e) => {
+            tracing::warn!("Seccomp requested but not available: {e}");
+            base
         }
     }
 }
 
 /// Auto-detect the best available sandbox
-fn detect_best_sandbox() -> Arc<dyn Sandbox> {
+fn detect_best_sandbox(config: &SecurityConfig) -> Arc<dyn Sandbox> {
+    #[cfg(not(target_os = "linux"))]
+    let _ = config;
+
     #[cfg(target_os = "linux")]
     {
         // Try Landlock first (native, no dependencies)
@@ -80,7 +121,17 @@ fn detect_best_sandbox() -> Arc<dyn Sandbox> {
         {
             if let Ok(sandbox) = super::landlock::LandlockSandbox::probe() {
                 tracing::info!("Landlock sandbox enabled (Linux kernel 5.13+)");
-                return Arc::new(sandbox);
+                return with_seccomp(config, Arc::new(sandbox));
+            }
+        }
+
+        // Wrapper backends cannot carry the seccomp filter, so an explicit
+        // seccomp opt-in wins over them.
+        if config.sandbox.seccomp_enabled() {
+            let sandbox = with_seccomp(config, Arc::new(super::traits::NoopSandbox));
+            if sandbox.name() != "none" {
+                tracing::info!("Seccomp sandbox enabled");
+                return sandbox;
             }
         }
 
@@ -121,7 +172,7 @@ mod tests {
 
     #[test]
     fn detect_best_sandbox_returns_something() {
-        let sandbox = detect_best_sandbox();
+        let sandbox = detect_best_sandbox(&SecurityConfig::default());
         // Should always return at least NoopSandbox
         assert!(sandbox.is_available());
     }
@@ -133,6 +184,7 @@ mod tests {
                 enabled: Some(false),
                 backend: SandboxBackend::None,
                 firejail_args: Vec::new(),
+                ..Default::default()
             },
             ..Default::default()
         };
@@ -140,6 +192,30 @@ mod tests {
         assert_eq!(sandbox.name(), "none");
     }
 
+    #[test]
+    fn command_sandbox_requires_seccomp() {
+        let config = SecurityConfig::default();
+        assert!(create_command_sandbox(&config).is_none());
+
+        let mut config = SecurityConfig::default();
+        config.sandbox.seccomp.enabled = true;
+        config.sandbox.backend = SandboxBackend::Docker;
+        assert!(create_command_sandbox(&config).is_none());
+    }
+
+    #[test]
+    fn seccomp_backend_uses_seccomp_when_available() {
+        let mut config = SecurityConfig::default();
+        config.sandbox.backend = SandboxBackend::Seccomp;
+        let sandbox = create_command_sandbox(&config).expect("seccomp backend enables the layer");
+        let expected = if crate::security::seccomp::SeccompSandbox::probe().is_ok() {
+            "seccomp"
+        } else {
+            "none"
+        };
+        assert_eq!(sandbox.name(), expected);
+    }
+
     #[test]
     fn auto_mode_detects_something() {
         let config = SecurityConfig {
@@ -147,6 +223,7 @@ mod tests {
                 enabled: None, // Auto-detect
                 backend: SandboxBackend::Auto,
                 firejail_args: Vec::new(),
+                ..Default::default()
             },
             ..Default::default()
         };
@@ -4,7 +4,9 @@
 //! This module uses the pure-Rust `landlock` crate for filesystem access control.
 
 #[cfg(all(feature = "sandbox-landlock", target_os = "linux"))]
-use landlock::{AccessFs, PathBeneath, PathFd, Ruleset, RulesetAttr, RulesetCreatedAttr};
+use landlock::{
+    AccessFs, PathBeneath, PathFd, Ruleset, RulesetAttr, RulesetCreated, RulesetCreatedAttr,
+};
 
 use crate::security::traits::Sandbox;
 use std::path::Path;
@@ -47,8 +49,8 @@ impl LandlockSandbox {
         Self::new()
     }
 
-    /// Apply Landlock restrictions to the current process
-    fn apply_restrictions(&self) -> std::io::Result<()> {
+    /// Build the ruleset in the parent so the child only has to enforce it
+    fn build_ruleset(&self) -> std::io::Result<RulesetCreated> {
         let mut ruleset = Ruleset::default()
             .handle_access(
                 AccessFs::ReadFile
@@ -109,27 +111,33 @@ impl LandlockSandbox {
             ))
             .map_err(|e| std::io::Error::other(e.to_string()))?;
 
-        // Apply the ruleset
-        match ruleset.restrict_self() {
-            Ok(_) => {
-                tracing::debug!("Landlock restrictions applied successfully");
-                Ok(())
-            }
-            Err(e) => {
-                tracing::warn!("Failed to apply Landlock restrictions: {}", e);
-                Err(std::io::Error::other(e.to_string()))
-            }
-        }
+        Ok(ruleset)
     }
 }
 
 #[cfg(all(feature = "sandbox-landlock", target_os = "linux"))]
 impl Sandbox for LandlockSandbox {
-    fn wrap_command(&self, _cmd: &mut std::process::Command) -> std::io::Result<()> {
-        // Apply Landlock restrictions before executing the command
-        // Note: This affects the current process, not the child process
-        // Child processes inherit the Landlock restrictions
-        self.apply_restrictions()
+    fn wrap_command(&self, cmd: &mut std::process::Command) -> std::io::Result<()> {
+        use std::os::unix::process::CommandExt;
+
+        // Restrict the child between fork and exec so the agent process itself
+        // stays unrestricted. Layers stacked after this one (e.g. seccomp)
+        // register their hooks later, so the Landlock syscalls are not yet
+        // filtered when this hook runs.
+        let mut ruleset = Some(self.build_ruleset()?);
+        // SAFETY: the hook only makes the landlock_restrict_self and prctl
+        // syscalls on a ruleset built before fork; it does not allocate.
+        unsafe {
+            cmd.pre_exec(move || {
+                // Each forked child works on its own copy of the closure, so
+                // taking the ruleset leaves the parent's copy for later spawns.
+                let denied = || std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+                let ruleset = ruleset.take().ok_or_else(denied)?;
+                ruleset.restrict_self().map(|_| ()).map_err(|_| denied())
+            });
+        }
+        tracing::debug!("Landlock restrictions attached to command");
+        Ok(())
     }
 
     fn is_available(&self) -> bool {
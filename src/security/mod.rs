@@ -9,7 +9,8 @@
 //! OS-level isolation is provided through the [`Sandbox`] trait defined in
 //! [`traits`], with pluggable backends including Docker, Firejail, Bubblewrap,
 //! and Landlock. The [`create_sandbox`] function selects the best available
-//! backend at runtime. An [`AuditLogger`] records security-relevant events for
+//! backend at runtime and can stack a per-command seccomp syscall filter on
+//! top of it. An [`AuditLogger`] records security-relevant events for
 //! forensic review.
 //!
 //! # Extension
@@ -29,20 +30,23 @@ pub mod firejail;
 pub mod landlock;
 pub mod pairing;
 pub mod policy;
+pub mod seccomp;
 pub mod secrets;
 pub mod traits;
 
 #[allow(unused_imports)]
-pub use audit::{AuditEvent, AuditEventType, AuditLogger};
+pub use audit::{AuditEvent, AuditEventType, AuditLogger, ResourceLimitLog};
 #[allow(unused_imports)]
 pub use detect::create_sandbox;
 #[allow(unused_imports)]
 pub use pairing::PairingGuard;
 pub use policy::{AutonomyLevel, SecurityPolicy};
 #[allow(unused_imports)]
+pub use seccomp::SeccompSandbox;
+#[allow(unused_imports)]
 pub use secrets::SecretStore;
 #[allow(unused_imports)]
-pub use traits::{NoopSandbox, Sandbox};
+pub use traits::{NoopSandbox, Sandbox, StackedSandbox};
 
 /// Redact sensitive values for safe logging. Shows first 4 chars + "***" suffix.
 /// This function intentionally breaks the data-flow taint chain for static analysis.
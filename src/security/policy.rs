@@ -605,7 +605,18 @@ impl SecurityPolicy {
         command: &str,
         approved: bool,
     ) -> Result<CommandRiskLevel, String> {
-        if !self.is_command_allowed(command) {
+        self.validate_command_execution_with(command, approved, &self.allowed_commands)
+    }
+
+    /// [`Self::validate_command_execution`] against an explicit allowlist,
+    /// e.g. a skill's narrowed command set.
+    pub fn validate_command_execution_with(
+        &self,
+        command: &str,
+        approved: bool,
+        allowed_commands: &[String],
+    ) -> Result<CommandRiskLevel, String> {
+        if !self.is_command_allowed_with(command, allowed_commands) {
             return Err(format!("Command not allowed by security policy: {command}"));
         }
 
@@ -651,6 +662,11 @@ impl SecurityPolicy {
     /// - Blocks output redirections (`>`, `>>`) that could write outside workspace
     /// - Blocks dangerous arguments (e.g. `find -exec`, `git config`)
     pub fn is_command_allowed(&self, command: &str) -> bool {
+        self.is_command_allowed_with(command, &self.allowed_commands)
+    }
+
+    /// [`Self::is_command_allowed`] against an explicit allowlist.
+    pub fn is_command_allowed_with(&self, command: &str, allowed_commands: &[String]) -> bool {
         if self.autonomy == AutonomyLevel::ReadOnly {
             return false;
         }
@@ -703,11 +719,7 @@ impl SecurityPolicy {
                 continue;
             }
 
-            if !self
-                .allowed_commands
-                .iter()
-                .any(|allowed| allowed == base_cmd)
-            {
+            if !allowed_commands.iter().any(|allowed| allowed == base_cmd) {
                 return false;
             }
 
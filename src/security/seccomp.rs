@@ -0,0 +1,589 @@
+//! Seccomp-BPF sandbox (Linux 3.5+)
+//!
+//! Installs a classic BPF syscall filter in the child between fork and exec,
+//! so the agent process itself is never filtered. Filters are built from a
+//! per-profile denylist: privileged kernel interfaces are denied for every
+//! profile, `network_denied` additionally refuses non-Unix sockets, and
+//! `read_only` also refuses filesystem mutation. Denied syscalls fail with
+//! `EPERM` rather than killing the process, so tools still see a readable
+//! error from the command.
+//!
+//! Seccomp only filters syscalls; it stacks with Landlock (see
+//! [`StackedSandbox`](super::traits::StackedSandbox)), which restricts paths.
+
+use crate::config::{SeccompConfig, SeccompProfile};
+use crate::security::policy::CommandRiskLevel;
+use crate::security::traits::Sandbox;
+
+/// Pick the seccomp profile for a command run by `tool`.
+///
+/// A per-tool override wins; otherwise the profile configured for the
+/// command's risk level is used.
+pub fn select_profile(
+    config: &SeccompConfig,
+    tool: &str,
+    risk: CommandRiskLevel,
+) -> SeccompProfile {
+    if let Some(profile) = config.tool_profiles.get(tool) {
+        return *profile;
+    }
+    match risk {
+        CommandRiskLevel::Low => config.low_risk_profile,
+        CommandRiskLevel::Medium => config.medium_risk_profile,
+        CommandRiskLevel::High => config.high_risk_profile,
+    }
+}
+
+/// Seccomp sandbox backend for Linux
+#[derive(Debug, Clone)]
+pub struct SeccompSandbox {
+    default_profile: SeccompProfile,
+}
+
+impl SeccompSandbox {
+    /// Create a seccomp sandbox that applies `default_profile` when no
+    /// per-command profile is given
+    pub fn new(default_profile: SeccompProfile) -> std::io::Result<Self> {
+        if filter::supported() {
+            Ok(Self { default_profile })
+        } else {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "seccomp filtering is only supported on Linux x86_64 and aarch64",
+            ))
+        }
+    }
+
+    /// Probe if seccomp is available (for auto-detection)
+    pub fn probe() -> std::io::Result<Self> {
+        Self::new(SeccompProfile::default())
+    }
+
+    pub fn default_profile(&self) -> SeccompProfile {
+        self.default_profile
+    }
+}
+
+impl Sandbox for SeccompSandbox {
+    fn wrap_command(&self, cmd: &mut std::process::Command) -> std::io::Result<()> {
+        self.wrap_command_with_profile(cmd, self.default_profile)
+    }
+
+    #[cfg(all(
+        target_os = "linux",
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    ))]
+    fn wrap_command_with_profile(
+        &self,
+        cmd: &mut std::process::Command,
+        profile: SeccompProfile,
+    ) -> std::io::Result<()> {
+        use std::os::unix::process::CommandExt;
+
+        // The program is assembled here; the child only hands it to the kernel.
+        let program = filter::build(profile);
+        // SAFETY: the hook makes two prctl calls on memory owned by the
+        // closure and does not allocate.
+        unsafe {
+            cmd.pre_exec(move || filter::install(&program));
+        }
+        tracing::debug!(
+            profile = profile.as_str(),
+            "Seccomp filter attached to command"
+        );
+        Ok(())
+    }
+
+    #[cfg(not(all(
+        target_os = "linux",
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    )))]
+    fn wrap_command_with_profile(
+        &self,
+        _cmd: &mut std::process::Command,
+        _profile: SeccompProfile,
+    ) -> std::io::Result<()> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "seccomp filtering is only supported on Linux x86_64 and aarch64",
+        ))
+    }
+
+    fn is_available(&self) -> bool {
+        filter::supported()
+    }
+
+    fn name(&self) -> &str {
+        "seccomp"
+    }
+
+    fn description(&self) -> &str {
+        "Linux seccomp-BPF syscall filtering (per-command profiles)"
+    }
+}
+
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+// Syscall numbers, flags and BPF fields are fixed-width kernel ABI values.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+mod filter {
+    use crate::config::SeccompProfile;
+    use libc::{c_long, sock_filter};
+
+    #[cfg(target_arch = "x86_64")]
+    const AUDIT_ARCH: u32 = 0xC000_003E; // AUDIT_ARCH_X86_64
+    #[cfg(target_arch = "aarch64")]
+    const AUDIT_ARCH: u32 = 0xC000_00B7; // AUDIT_ARCH_AARCH64
+
+    /// x32 syscalls report the x86_64 audit arch but set this bit in `nr`.
+    #[cfg(target_arch = "x86_64")]
+    const X32_SYSCALL_BIT: u32 = 0x4000_0000;
+
+    // Offsets into `struct seccomp_data`.
+    const NR_OFFSET: u32 = 0;
+    const ARCH_OFFSET: u32 = 4;
+    const ARGS_OFFSET: u32 = 16;
+
+    const CLONE_NAMESPACE_FLAGS: u32 = (libc::CLONE_NEWNS
+        | libc::CLONE_NEWUTS
+        | libc::CLONE_NEWIPC
+        | libc::CLONE_NEWUSER
+        | libc::CLONE_NEWPID
+        | libc::CLONE_NEWNET
+        | libc::CLONE_NEWCGROUP) as u32;
+
+    const OPEN_WRITE_FLAGS: u32 =
+        (libc::O_WRONLY | libc::O_RDWR | libc::O_CREAT | libc::O_TRUNC) as u32;
+
+    #[derive(Debug, Clone, Copy)]
+    pub(super) enum Rule {
+        /// Fail the syscall with `errno`.
+        Deny { nr: c_long, errno: i32 },
+        /// Fail with `EPERM` when `args[arg] & mask` is non-zero.
+        DenyIfFlags { nr: c_long, arg: u32, mask: u32 },
+        /// Fail with `EPERM` unless `args[arg] == value`.
+        DenyUnlessEq { nr: c_long, arg: u32, value: u32 },
+    }
+
+    impl Rule {
+        pub(super) fn nr(self) -> c_long {
+            match self {
+                Self::Deny { nr, .. }
+                | Self::DenyIfFlags { nr, .. }
+                | Self::DenyUnlessEq { nr, .. } => nr,
+            }
+        }
+    }
+
+    fn deny(nr: c_long) -> Rule {
+        Rule::Deny {
+            nr,
+            errno: libc::EPERM,
+        }
+    }
+
+    /// Privileged kernel interfaces no tool command should need.
+    fn base_rules() -> Vec<Rule> {
+        let mut rules: Vec<Rule> = [
+            libc::SYS_ptrace,
+            libc::SYS_process_vm_readv,
+            libc::SYS_process_vm_writev,
+            libc::SYS_pidfd_getfd,
+            libc::SYS_mount,
+            libc::SYS_umount2,
+            libc::SYS_pivot_root,
+            libc::SYS_chroot,
+            libc::SYS_fsopen,
+            libc::SYS_fsconfig,
+            libc::SYS_fsmount,
+            libc::SYS_fspick,
+            libc::SYS_move_mount,
+            libc::SYS_open_tree,
+            libc::SYS_mount_setattr,
+            libc::SYS_unshare,
+            libc::SYS_setns,
+            libc::SYS_swapon,
+            libc::SYS_swapoff,
+            libc::SYS_reboot,
+            libc::SYS_kexec_load,
+            libc::SYS_kexec_file_load,
+            libc::SYS_init_module,
+            libc::SYS_finit_module,
+            libc::SYS_delete_module,
+            libc::SYS_bpf,
+            libc::SYS_perf_event_open,
+            libc::SYS_keyctl,
+            libc::SYS_add_key,
+            libc::SYS_request_key,
+            libc::SYS_acct,
+            libc::SYS_quotactl,
+            libc::SYS_settimeofday,
+            libc::SYS_clock_settime,
+            libc::SYS_adjtimex,
+            libc::SYS_clock_adjtime,
+            libc::SYS_syslog,
+            libc::SYS_userfaultfd,
+            libc::SYS_open_by_handle_at,
+            libc::SYS_name_to_handle_at,
+            libc::SYS_fanotify_init,
+            libc::SYS_io_uring_setup,
+            libc::SYS_io_uring_enter,
+            libc::SYS_io_uring_register,
+            #[cfg(target_arch = "x86_64")]
+            libc::SYS_iopl,
+            #[cfg(target_arch = "x86_64")]
+            libc::SYS_ioperm,
+        ]
+        .into_iter()
+        .map(deny)
+        .collect();
+
+        // clone3 passes flags through a pointer the filter cannot inspect;
+        // ENOSYS makes libc fall back to clone, whose flags are checked.
+        rules.push(Rule::Deny {
+            nr: libc::SYS_clone3,
+            errno: libc::ENOSYS,
+        });
+        rules.push(Rule::DenyIfFlags {
+            nr: libc::SYS_clone,
+            arg: 0,
+            mask: CLONE_NAMESPACE_FLAGS,
+        });
+        rules
+    }
+
+    fn network_rules() -> Vec<Rule> {
+        vec![Rule::DenyUnlessEq {
+            nr: libc::SYS_socket,
+            arg: 0,
+            value: libc::AF_UNIX as u32,
+        }]
+    }
+
+    fn read_only_rules() -> Vec<Rule> {
+        let mut rules: Vec<Rule> = [
+            libc::SYS_unlinkat,
+            libc::SYS_renameat2,
+            libc::SYS_mkdirat,
+            libc::SYS_linkat,
+            libc::SYS_symlinkat,
+            libc::SYS_mknodat,
+            libc::SYS_fchmod,
+            libc::SYS_fchmodat,
+            libc::SYS_fchown,
+            libc::SYS_fchownat,
+            libc::SYS_truncate,
+            libc::SYS_ftruncate,
+            libc::SYS_fallocate,
+            libc::SYS_utimensat,
+            libc::SYS_setxattr,
+            libc::SYS_lsetxattr,
+            libc::SYS_fsetxattr,
+            libc::SYS_removexattr,
+            libc::SYS_lremovexattr,
+            libc::SYS_fremovexattr,
+            #[cfg(target_arch = "x86_64")]
+            libc::SYS_creat,
+            #[cfg(target_arch = "x86_64")]
+            libc::SYS_unlink,
+            #[cfg(target_arch = "x86_64")]
+            libc::SYS_rename,
+            #[cfg(target_arch = "x86_64")]
+            libc::SYS_renameat,
+            #[cfg(target_arch = "x86_64")]
+            libc::SYS_mkdir,
+            #[cfg(target_arch = "x86_64")]
+            libc::SYS_rmdir,
+            #[cfg(target_arch = "x86_64")]
+            libc::SYS_link,
+            #[cfg(target_arch = "x86_64")]
+            libc::SYS_symlink,
+            #[cfg(target_arch = "x86_64")]
+            libc::SYS_mknod,
+            #[cfg(target_arch = "x86_64")]
+            libc::SYS_chmod,
+            #[cfg(target_arch = "x86_64")]
+            libc::SYS_chown,
+            #[cfg(target_arch = "x86_64")]
+            libc::SYS_lchown,
+            #[cfg(target_arch = "x86_64")]
+            libc::SYS_utime,
+            #[cfg(target_arch = "x86_64")]
+            libc::SYS_utimes,
+            #[cfg(target_arch = "x86_64")]
+            libc::SYS_futimesat,
+        ]
+        .into_iter()
+        .map(deny)
+        .collect();
+
+        rules.push(Rule::DenyIfFlags {
+            nr: libc::SYS_openat,
+            arg: 2,
+            mask: OPEN_WRITE_FLAGS,
+        });
+        #[cfg(target_arch = "x86_64")]
+        rules.push(Rule::DenyIfFlags {
+            nr: libc::SYS_open,
+            arg: 1,
+            mask: OPEN_WRITE_FLAGS,
+        });
+        // openat2 hides its flags behind a pointer.
+        rules.push(Rule::Deny {
+            nr: libc::SYS_openat2,
+            errno: libc::ENOSYS,
+        });
+        rules
+    }
+
+    pub(super) fn rules(profile: SeccompProfile) -> Vec<Rule> {
+        let mut rules = base_rules();
+        match profile {
+            SeccompProfile::Build => {}
+            SeccompProfile::NetworkDenied => rules.extend(network_rules()),
+            SeccompProfile::ReadOnly => {
+                rules.extend(network_rules());
+                rules.extend(read_only_rules());
+            }
+        }
+        rules
+    }
+
+    fn stmt(code: u32, k: u32) -> sock_filter {
+        sock_filter {
+            code: code as u16,
+            jt: 0,
+            jf: 0,
+            k,
+        }
+    }
+
+    fn jump(code: u32, k: u32, jt: u8, jf: u8) -> sock_filter {
+        sock_filter {
+            code: code as u16,
+            jt,
+            jf,
+            k,
+        }
+    }
+
+    fn load(offset: u32) -> sock_filter {
+        stmt(libc::BPF_LD | libc::BPF_W | libc::BPF_ABS, offset)
+    }
+
+    /// Low 32 bits of a syscall argument (both supported arches are little-endian).
+    fn load_arg(arg: u32) -> sock_filter {
+        load(ARGS_OFFSET + arg * 8)
+    }
+
+    fn ret(action: u32) -> sock_filter {
+        stmt(libc::BPF_RET | libc::BPF_K, action)
+    }
+
+    fn ret_errno(errno: i32) -> sock_filter {
+        ret(libc::SECCOMP_RET_ERRNO | (errno as u32 & libc::SECCOMP_RET_DATA))
+    }
+
+    /// Assemble the BPF program for `profile`.
+    ///
+    /// Each rule is a self-contained block entered with the syscall number in
+    /// the accumulator; a syscall matches at most one rule, so blocks that
+    /// load an argument end in their own `ALLOW`.
+    pub(super) fn build(profile: SeccompProfile) -> Vec<sock_filter> {
+        let jeq = libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K;
+        let mut program = vec![
+            load(ARCH_OFFSET),
+            jump(jeq, AUDIT_ARCH, 1, 0),
+            ret(libc::SECCOMP_RET_KILL_PROCESS),
+            load(NR_OFFSET),
+        ];
+        #[cfg(target_arch = "x86_64")]
+        program.extend([
+            jump(
+                libc::BPF_JMP | libc::BPF_JGE | libc::BPF_K,
+                X32_SYSCALL_BIT,
+                0,
+                1,
+            ),
+            ret(libc::SECCOMP_RET_KILL_PROCESS),
+        ]);
+
+        for rule in rules(profile) {
+            let nr = rule.nr() as u32;
+            match rule {
+                Rule::Deny { errno, .. } => {
+                    program.extend([jump(jeq, nr, 0, 1), ret_errno(errno)]);
+                }
+                Rule::DenyIfFlags { arg, mask, .. } => {
+                    program.extend([
+                        jump(jeq, nr, 0, 4),
+                        load_arg(arg),
+                        jump(libc::BPF_JMP | libc::BPF_JSET | libc::BPF_K, mask, 0, 1),
+                        ret_errno(libc::EPERM),
+                        ret(libc::SECCOMP_RET_ALLOW),
+                    ]);
+                }
+                Rule::DenyUnlessEq { arg, value, .. } => {
+                    program.extend([
+                        jump(jeq, nr, 0, 4),
+                        load_arg(arg),
+                        jump(jeq, value, 1, 0),
+                        ret_errno(libc::EPERM),
+                        ret(libc::SECCOMP_RET_ALLOW),
+                    ]);
+                }
+            }
+        }
+
+        program.push(ret(libc::SECCOMP_RET_ALLOW));
+        program
+    }
+
+    /// Install `program` on the calling process. Runs in the forked child.
+    pub(super) fn install(program: &[sock_filter]) -> std::io::Result<()> {
+        let prog = libc::sock_fprog {
+            len: program.len() as libc::c_ushort,
+            filter: program.as_ptr().cast_mut(),
+        };
+        // SAFETY: `prog` points at `program`, which outlives both calls; the
+        // kernel copies the filter during PR_SET_SECCOMP.
+        unsafe {
+            if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            if libc::prctl(
+                libc::PR_SET_SECCOMP,
+                libc::SECCOMP_MODE_FILTER,
+                std::ptr::addr_of!(prog),
+            ) != 0
+            {
+                return Err(std::io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) fn supported() -> bool {
+        // SAFETY: PR_GET_SECCOMP only reads the calling thread's seccomp mode.
+        unsafe { libc::prctl(libc::PR_GET_SECCOMP, 0, 0, 0, 0) >= 0 }
+    }
+}
+
+#[cfg(not(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+)))]
+mod filter {
+    pub(super) fn supported() -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tool_override_beats_risk_level() {
+        let mut config = SeccompConfig::default();
+        config
+            .tool_profiles
+            .insert("shell".into(), SeccompProfile::ReadOnly);
+
+        assert_eq!(
+            select_profile(&config, "shell", CommandRiskLevel::Medium),
+            SeccompProfile::ReadOnly
+        );
+        assert_eq!(
+            select_profile(&config, "other", CommandRiskLevel::Medium),
+            SeccompProfile::Build
+        );
+        assert_eq!(
+            select_profile(&config, "other", CommandRiskLevel::High),
+            SeccompProfile::NetworkDenied
+        );
+    }
+
+    #[cfg(all(
+        target_os = "linux",
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    ))]
+    #[test]
+    fn each_syscall_has_at_most_one_rule() {
+        for profile in [
+            SeccompProfile::Build,
+            SeccompProfile::NetworkDenied,
+            SeccompProfile::ReadOnly,
+        ] {
+            let rules = filter::rules(profile);
+            let mut seen = std::collections::HashSet::new();
+            for rule in &rules {
+                assert!(seen.insert(rule.nr()), "duplicate rule in {profile:?}");
+            }
+            let program = filter::build(profile);
+            assert!(program.len() < usize::from(u16::MAX));
+            assert_eq!(program.last().unwrap().k, libc::SECCOMP_RET_ALLOW);
+        }
+    }
+
+    #[cfg(all(
+        target_os = "linux",
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    ))]
+    #[test]
+    fn read_only_profile_blocks_writes_but_build_allows_them() {
+        let Ok(sandbox) = SeccompSandbox::probe() else {
+            return;
+        };
+        let tmp = tempfile::tempdir().unwrap();
+
+        let run = |profile: SeccompProfile, script: &str| {
+            let mut cmd = std::process::Command::new("sh");
+            cmd.arg("-c").arg(script).current_dir(tmp.path());
+            sandbox
+                .wrap_command_with_profile(&mut cmd, profile)
+                .unwrap();
+            cmd.output().unwrap()
+        };
+
+        let denied = run(SeccompProfile::ReadOnly, "touch blocked");
+        assert!(!denied.status.success());
+        assert!(!tmp.path().join("blocked").exists());
+
+        let listed = run(SeccompProfile::ReadOnly, "ls .");
+        assert!(listed.status.success());
+
+        let allowed = run(SeccompProfile::Build, "touch allowed");
+        assert!(allowed.status.success());
+        assert!(tmp.path().join("allowed").exists());
+    }
+
+    #[cfg(all(
+        target_os = "linux",
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    ))]
+    #[test]
+    fn filter_applies_to_child_only() {
+        let Ok(sandbox) = SeccompSandbox::new(SeccompProfile::ReadOnly) else {
+            return;
+        };
+        let mut cmd = std::process::Command::new("true");
+        sandbox.wrap_command(&mut cmd).unwrap();
+        assert!(cmd.status().unwrap().success());
+
+        // The test process itself can still write after spawning the child.
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("parent.txt"), "ok").unwrap();
+    }
+
+    #[cfg(not(all(
+        target_os = "linux",
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    )))]
+    #[test]
+    fn seccomp_unsupported_elsewhere() {
+        assert!(SeccompSandbox::probe().is_err());
+    }
+}
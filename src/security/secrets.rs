@@ -167,6 +167,51 @@ impl SecretStore {
         value.starts_with("enc2:")
     }
 
+    /// Load the named 256-bit signing key (e.g. for audit log HMACs),
+    /// generating it on first use. Stored as `.<name>_key` next to the secret
+    /// key and encrypted with it when encryption is enabled.
+    pub fn load_or_create_signing_key(&self, name: &str) -> Result<Vec<u8>> {
+        if let Some(key) = self.load_signing_key(name)? {
+            return Ok(key);
+        }
+
+        let key = generate_random_key();
+        let path = self.signing_key_path(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, self.encrypt(&hex_encode(&key))?)
+            .with_context(|| format!("Failed to write signing key {}", path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o600))
+                .context("Failed to set signing key permissions")?;
+        }
+        Ok(key)
+    }
+
+    /// Load the named signing key without creating it.
+    pub fn load_signing_key(&self, name: &str) -> Result<Option<Vec<u8>>> {
+        let path = self.signing_key_path(name);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let stored = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read signing key {}", path.display()))?;
+        let hex_key = self.decrypt(stored.trim())?;
+        hex_decode(hex_key.trim())
+            .map(Some)
+            .with_context(|| format!("Signing key {} is corrupt", path.display()))
+    }
+
+    fn signing_key_path(&self, name: &str) -> PathBuf {
+        self.key_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(format!(".{name}_key"))
+    }
+
     /// Load the encryption key from disk, or create one if it doesn't exist.
     fn load_or_create_key(&self) -> Result<Vec<u8>> {
         if self.key_path.exists() {
@@ -749,6 +794,24 @@ mod tests {
         assert_eq!(decoded, data);
     }
 
+    #[test]
+    fn signing_key_is_created_once_and_stored_encrypted() {
+        let tmp = TempDir::new().unwrap();
+        let store = SecretStore::new(tmp.path(), true);
+        assert!(store.load_signing_key("audit_signing").unwrap().is_none());
+
+        let key = store.load_or_create_signing_key("audit_signing").unwrap();
+        assert_eq!(key.len(), KEY_LEN);
+        assert_eq!(
+            store.load_or_create_signing_key("audit_signing").unwrap(),
+            key
+        );
+
+        let stored = fs::read_to_string(tmp.path().join(".audit_signing_key")).unwrap();
+        assert!(SecretStore::is_secure_encrypted(&stored));
+        assert!(!stored.contains(&hex_encode(&key)));
+    }
+
     #[test]
     fn hex_decode_odd_length_fails() {
         assert!(hex_decode("abc").is_err());
@@ -6,8 +6,10 @@
 //! of tool execution. The agent runtime selects and applies a sandbox backend
 //! before executing any shell command.
 
+use crate::config::SeccompProfile;
 use async_trait::async_trait;
 use std::process::Command;
+use std::sync::Arc;
 
 /// Sandbox backend for OS-level process isolation.
 ///
@@ -31,6 +33,24 @@ pub trait Sandbox: Send + Sync {
     /// (e.g., missing wrapper binary, invalid policy file).
     fn wrap_command(&self, cmd: &mut Command) -> std::io::Result<()>;
 
+    /// Wrap a command using a specific seccomp profile.
+    ///
+    /// Callers that classify commands (e.g. by risk level) use this to pick a
+    /// syscall profile per invocation. Backends without syscall profiles ignore
+    /// `profile` and fall back to [`wrap_command`](Sandbox::wrap_command).
+    ///
+    /// # Errors
+    ///
+    /// Same as [`wrap_command`](Sandbox::wrap_command).
+    fn wrap_command_with_profile(
+        &self,
+        cmd: &mut Command,
+        profile: SeccompProfile,
+    ) -> std::io::Result<()> {
+        let _ = profile;
+        self.wrap_command(cmd)
+    }
+
     /// Check if this sandbox backend is available on the current platform.
     ///
     /// Returns `true` when all required kernel features, binaries, and
@@ -79,6 +99,69 @@ impl Sandbox for NoopSandbox {
     }
 }
 
+/// Several sandboxes applied to the same command, in order.
+///
+/// Used to layer a seccomp syscall filter on top of Landlock filesystem rules.
+/// Only backends that keep the command intact (pre-exec hooks rather than a
+/// wrapper binary) should be stacked.
+pub struct StackedSandbox {
+    layers: Vec<Arc<dyn Sandbox>>,
+    name: String,
+    description: String,
+}
+
+impl StackedSandbox {
+    pub fn new(layers: Vec<Arc<dyn Sandbox>>) -> Self {
+        let name = layers
+            .iter()
+            .map(|layer| layer.name())
+            .collect::<Vec<_>>()
+            .join("+");
+        let description = layers
+            .iter()
+            .map(|layer| layer.description())
+            .collect::<Vec<_>>()
+            .join("; ");
+        Self {
+            layers,
+            name,
+            description,
+        }
+    }
+}
+
+impl Sandbox for StackedSandbox {
+    fn wrap_command(&self, cmd: &mut Command) -> std::io::Result<()> {
+        for layer in &self.layers {
+            layer.wrap_command(cmd)?;
+        }
+        Ok(())
+    }
+
+    fn wrap_command_with_profile(
+        &self,
+        cmd: &mut Command,
+        profile: SeccompProfile,
+    ) -> std::io::Result<()> {
+        for layer in &self.layers {
+            layer.wrap_command_with_profile(cmd, profile)?;
+        }
+        Ok(())
+    }
+
+    fn is_available(&self) -> bool {
+        self.layers.iter().all(|layer| layer.is_available())
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -93,6 +176,19 @@ mod tests {
         assert!(NoopSandbox.is_available());
     }
 
+    #[test]
+    fn stacked_sandbox_joins_layer_names() {
+        let stacked = StackedSandbox::new(vec![Arc::new(NoopSandbox), Arc::new(NoopSandbox)]);
+        assert_eq!(stacked.name(), "none+none");
+        assert!(stacked.is_available());
+
+        let mut cmd = Command::new("echo");
+        assert!(stacked
+            .wrap_command_with_profile(&mut cmd, SeccompProfile::ReadOnly)
+            .is_ok());
+        assert_eq!(cmd.get_program().to_string_lossy(), "echo");
+    }
+
     #[test]
     fn noop_sandbox_wrap_command_is_noop() {
         let mut cmd = Command::new("echo");
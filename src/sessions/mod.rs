@@ -0,0 +1,464 @@
+//! Persistent, resumable conversation sessions.
+//!
+//! A session is a named transcript of [`ConversationMessage`]s stored in
+//! `<workspace>/sessions/sessions.db`. The CLI agent persists to a session
+//! when started with `--session <id>`, and channels persist each sender's
+//! conversation under its history key so restarts do not lose context.
+
+use crate::agent::dispatcher::{NativeToolDispatcher, ToolDispatcher, XmlToolDispatcher};
+use crate::config::Config;
+use crate::providers::{ChatMessage, ConversationMessage, ToolCall, ToolResultMessage};
+use anyhow::{bail, Context, Result};
+use console::style;
+use std::fmt::Write as _;
+
+mod store;
+
+pub use store::{SessionInfo, SessionStore};
+
+/// Tool output longer than this is truncated by `sessions show`.
+const SHOW_TOOL_OUTPUT_CHARS: usize = 400;
+
+/// Convert agent-loop history into a typed transcript.
+///
+/// System messages are dropped (the system prompt is rebuilt on resume).
+/// Native tool calls and tool results, whether carried as typed parts or as
+/// the JSON envelopes used in loop history, become
+/// [`ConversationMessage::AssistantToolCalls`] and
+/// [`ConversationMessage::ToolResults`]; consecutive tool results are grouped.
+pub fn transcript_from_history(history: &[ChatMessage]) -> Vec<ConversationMessage> {
+    let mut transcript: Vec<ConversationMessage> = Vec::new();
+
+    for message in history {
+        match message.role.as_str() {
+            "system" => {}
+            "assistant" => transcript.push(
+                decode_assistant_tool_calls(message)
+                    .unwrap_or_else(|| ConversationMessage::Chat(message.clone())),
+            ),
+            "tool" => match decode_tool_result(message) {
+                Some(result) => {
+                    if let Some(ConversationMessage::ToolResults(results)) = transcript.last_mut() {
+                        results.push(result);
+                    } else {
+                        transcript.push(ConversationMessage::ToolResults(vec![result]));
+                    }
+                }
+                None => transcript.push(ConversationMessage::Chat(message.clone())),
+            },
+            _ => transcript.push(ConversationMessage::Chat(message.clone())),
+        }
+    }
+
+    transcript
+}
+
+/// Rebuild provider-facing history from a stored transcript.
+///
+/// Tool interactions are rendered natively or as XML-style text depending
+/// on whether the active provider supports native tool calling.
+pub fn history_from_transcript(
+    transcript: &[ConversationMessage],
+    native_tools: bool,
+) -> Vec<ChatMessage> {
+    if native_tools {
+        NativeToolDispatcher.to_provider_messages(transcript)
+    } else {
+        XmlToolDispatcher.to_provider_messages(transcript)
+    }
+}
+
+fn decode_assistant_tool_calls(message: &ChatMessage) -> Option<ConversationMessage> {
+    let mut text = Vec::new();
+    let mut tool_calls = Vec::new();
+    for part in &message.parts {
+        match part {
+            crate::providers::ContentPart::Text { text: t } => text.push(t.clone()),
+            crate::providers::ContentPart::ToolUse {
+                id,
+                name,
+                arguments,
+            } => tool_calls.push(ToolCall {
+                id: id.clone(),
+                name: name.clone(),
+                arguments: arguments.clone(),
+            }),
+            _ => {}
+        }
+    }
+    if !tool_calls.is_empty() {
+        return Some(ConversationMessage::AssistantToolCalls {
+            text: Some(text.join("\n")).filter(|t| !t.is_empty()),
+            tool_calls,
+        });
+    }
+
+    let value: serde_json::Value = serde_json::from_str(&message.content).ok()?;
+    let calls = value.get("tool_calls")?.as_array()?;
+    let tool_calls: Vec<ToolCall> = calls
+        .iter()
+        .filter_map(|call| serde_json::from_value(call.clone()).ok())
+        .collect();
+    if tool_calls.is_empty() {
+        return None;
+    }
+    Some(ConversationMessage::AssistantToolCalls {
+        text: value
+            .get("content")
+            .and_then(serde_json::Value::as_str)
+            .map(ToString::to_string),
+        tool_calls,
+    })
+}
+
+fn decode_tool_result(message: &ChatMessage) -> Option<ToolResultMessage> {
+    for part in &message.parts {
+        if let crate::providers::ContentPart::ToolResult {
+            tool_use_id,
+            content,
+            ..
+        } = part
+        {
+            return Some(ToolResultMessage {
+                tool_call_id: tool_use_id.clone(),
+                content: content.clone(),
+            });
+        }
+    }
+
+    let value: serde_json::Value = serde_json::from_str(&message.content).ok()?;
+    Some(ToolResultMessage {
+        tool_call_id: value.get("tool_call_id")?.as_str()?.to_string(),
+        content: value
+            .get("content")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+    })
+}
+
+/// Render a transcript as Markdown.
+pub fn render_markdown(info: &SessionInfo, transcript: &[ConversationMessage]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "# Session {}\n\n- Source: {}\n- Created: {}\n- Updated: {}\n",
+        info.id,
+        info.source,
+        info.created_at.to_rfc3339(),
+        info.updated_at.to_rfc3339(),
+    );
+
+    for message in transcript {
+        match message {
+            ConversationMessage::Chat(chat) => {
+                let _ = writeln!(out, "## {}\n\n{}\n", chat.role, chat.content);
+            }
+            ConversationMessage::AssistantToolCalls { text, tool_calls } => {
+                out.push_str("## assistant\n\n");
+                if let Some(text) = text.as_deref().filter(|t| !t.trim().is_empty()) {
+                    let _ = writeln!(out, "{text}\n");
+                }
+                for call in tool_calls {
+                    let _ = writeln!(
+                        out,
+                        "Tool call `{}` (`{}`):\n\n```json\n{}\n```\n",
+                        call.name, call.id, call.arguments
+                    );
+                }
+            }
+            ConversationMessage::ToolResults(results) => {
+                for result in results {
+                    let _ = writeln!(
+                        out,
+                        "## tool result (`{}`)\n\n```\n{}\n```\n",
+                        result.tool_call_id, result.content
+                    );
+                }
+            }
+        }
+    }
+
+    out
+}
+
+/// Resolve an exact session id or a unique id prefix.
+fn resolve_session(store: &SessionStore, id: &str) -> Result<SessionInfo> {
+    if let Some(info) = store.get(id)? {
+        return Ok(info);
+    }
+
+    let matches: Vec<SessionInfo> = store
+        .list(usize::MAX)?
+        .into_iter()
+        .filter(|info| info.id.starts_with(id))
+        .collect();
+    match matches.len() {
+        0 => bail!("Session '{id}' not found"),
+        1 => Ok(matches.into_iter().next().expect("one match")),
+        n => {
+            let ids: Vec<&str> = matches.iter().map(|info| info.id.as_str()).collect();
+            bail!(
+                "Prefix '{id}' matched {n} sessions: {}. Specify a longer prefix.",
+                ids.join(", ")
+            )
+        }
+    }
+}
+
+/// Handle `zeroclaw sessions <subcommand>` CLI commands.
+pub async fn handle_command(command: crate::SessionCommands, config: Config) -> Result<()> {
+    let store = SessionStore::open(&config.workspace_dir)?;
+
+    match command {
+        crate::SessionCommands::List { limit } => {
+            let sessions = store.list(limit)?;
+            if sessions.is_empty() {
+                println!("No sessions yet.");
+                println!("\nUsage:");
+                println!("  zeroclaw agent --session <name>");
+                return Ok(());
+            }
+
+            println!("🗂️  Sessions ({}):", sessions.len());
+            for info in sessions {
+                println!(
+                    "- {} | {} | {} message(s) | updated {}",
+                    style(&info.id).white().bold(),
+                    info.source,
+                    info.message_count,
+                    info.updated_at.to_rfc3339(),
+                );
+                if let Some(title) = &info.title {
+                    println!("    {title}");
+                }
+            }
+            Ok(())
+        }
+        crate::SessionCommands::Show { id, last } => {
+            let info = resolve_session(&store, &id)?;
+            let transcript = match last {
+                Some(n) => store.load_recent(&info.id, n)?,
+                None => store.load(&info.id)?,
+            };
+
+            println!(
+                "Session {} ({}, {} message(s))\n",
+                style(&info.id).white().bold(),
+                info.source,
+                info.message_count
+            );
+            for message in &transcript {
+                print_message(message);
+            }
+            Ok(())
+        }
+        crate::SessionCommands::Resume {
+            id,
+            provider,
+            model,
+        } => {
+            let info = resolve_session(&store, &id)?;
+            drop(store);
+            let temperature = config.default_temperature;
+            crate::agent::run(
+                config,
+                None,
+                provider,
+                model,
+                temperature,
+                vec![],
+                true,
+                Some(info.id),
+                None,
+            )
+            .await
+            .map(|_| ())
+        }
+        crate::SessionCommands::Export { id, format, output } => {
+            let info = resolve_session(&store, &id)?;
+            let transcript = store.load(&info.id)?;
+            let rendered = match format.trim().to_ascii_lowercase().as_str() {
+                "json" => serde_json::to_string_pretty(&serde_json::json!({
+                    "session": info,
+                    "messages": transcript,
+                }))?,
+                "markdown" | "md" => render_markdown(&info, &transcript),
+                other => bail!("Unsupported export format '{other}' (expected json or markdown)"),
+            };
+
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, rendered)
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                    println!(
+                        "{} Exported session {} to {}",
+                        style("✓").green().bold(),
+                        info.id,
+                        path.display()
+                    );
+                }
+                None => println!("{rendered}"),
+            }
+            Ok(())
+        }
+        crate::SessionCommands::Delete { id, yes } => {
+            let info = resolve_session(&store, &id)?;
+            if !yes {
+                let confirmed = dialoguer::Confirm::new()
+                    .with_prompt(format!(
+                        "  Delete session '{}' ({} message(s))?",
+                        info.id, info.message_count
+                    ))
+                    .default(false)
+                    .interact()?;
+                if !confirmed {
+                    println!("Aborted.");
+                    return Ok(());
+                }
+            }
+
+            if store.delete(&info.id)? {
+                println!("{} Deleted session: {}", style("✓").green().bold(), info.id);
+            }
+            Ok(())
+        }
+    }
+}
+
+fn print_message(message: &ConversationMessage) {
+    match message {
+        ConversationMessage::Chat(chat) => {
+            println!(
+                "{} {}\n",
+                style(format!("[{}]", chat.role)).cyan(),
+                chat.content
+            );
+        }
+        ConversationMessage::AssistantToolCalls { text, tool_calls } => {
+            if let Some(text) = text.as_deref().filter(|t| !t.trim().is_empty()) {
+                println!("{} {text}", style("[assistant]").cyan());
+            }
+            for call in tool_calls {
+                println!(
+                    "{} {}({})",
+                    style("[tool call]").yellow(),
+                    call.name,
+                    call.arguments
+                );
+            }
+            println!();
+        }
+        ConversationMessage::ToolResults(results) => {
+            for result in results {
+                println!(
+                    "{} {}",
+                    style(format!("[tool result {}]", result.tool_call_id)).yellow(),
+                    crate::util::truncate_with_ellipsis(&result.content, SHOW_TOOL_OUTPUT_CHARS)
+                );
+            }
+            println!();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn legacy_history() -> Vec<ChatMessage> {
+        vec![
+            ChatMessage::system("You are ZeroClaw"),
+            ChatMessage::user("what's here?"),
+            ChatMessage::assistant(
+                r#"{"content":"Let me look","tool_calls":[{"id":"call_1","name":"shell","arguments":"{\"command\":\"ls\"}"},{"id":"call_2","name":"file_read","arguments":"{\"path\":\"a\"}"}]}"#,
+            ),
+            ChatMessage::tool(r#"{"tool_call_id":"call_1","content":"a b"}"#),
+            ChatMessage::tool(r#"{"tool_call_id":"call_2","content":"hello"}"#),
+            ChatMessage::assistant("Two files: a and b"),
+        ]
+    }
+
+    #[test]
+    fn transcript_decodes_legacy_tool_envelopes() {
+        let transcript = transcript_from_history(&legacy_history());
+
+        assert_eq!(transcript.len(), 4);
+        match &transcript[1] {
+            ConversationMessage::AssistantToolCalls { text, tool_calls } => {
+                assert_eq!(text.as_deref(), Some("Let me look"));
+                assert_eq!(tool_calls.len(), 2);
+                assert_eq!(tool_calls[1].name, "file_read");
+            }
+            other => panic!("expected tool calls, got {other:?}"),
+        }
+        match &transcript[2] {
+            ConversationMessage::ToolResults(results) => {
+                assert_eq!(results.len(), 2);
+                assert_eq!(results[1].content, "hello");
+            }
+            other => panic!("expected tool results, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn transcript_decodes_structured_tool_parts() {
+        let call = ToolCall {
+            id: "toolu_1".into(),
+            name: "shell".into(),
+            arguments: "{}".into(),
+        };
+        let history = vec![
+            ChatMessage::assistant_tool_calls(None, std::slice::from_ref(&call)),
+            ChatMessage::tool_result("toolu_1", "ok"),
+        ];
+        let transcript = transcript_from_history(&history);
+
+        assert!(matches!(
+            &transcript[0],
+            ConversationMessage::AssistantToolCalls { text: None, tool_calls } if tool_calls[0].id == "toolu_1"
+        ));
+        assert!(matches!(
+            &transcript[1],
+            ConversationMessage::ToolResults(results) if results[0].content == "ok"
+        ));
+    }
+
+    #[test]
+    fn history_roundtrip_restores_native_tool_messages() {
+        let transcript = transcript_from_history(&legacy_history());
+        let history = history_from_transcript(&transcript, true);
+
+        let roles: Vec<&str> = history.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, ["user", "assistant", "tool", "tool", "assistant"]);
+        assert_eq!(history[2].parts.len(), 1);
+    }
+
+    #[test]
+    fn history_from_transcript_uses_text_results_without_native_tools() {
+        let transcript = transcript_from_history(&legacy_history());
+        let history = history_from_transcript(&transcript, false);
+
+        assert!(history.iter().all(|m| m.role != "tool"));
+        assert!(history[2].content.contains("<tool_result id=\"call_1\">"));
+    }
+
+    #[test]
+    fn markdown_export_includes_tool_calls_and_results() {
+        let transcript = transcript_from_history(&legacy_history());
+        let now = chrono::Utc::now();
+        let info = SessionInfo {
+            id: "s1".into(),
+            source: "cli".into(),
+            title: None,
+            created_at: now,
+            updated_at: now,
+            message_count: transcript.len(),
+        };
+        let md = render_markdown(&info, &transcript);
+
+        assert!(md.starts_with("# Session s1"));
+        assert!(md.contains("Tool call `shell` (`call_1`)"));
+        assert!(md.contains("## tool result (`call_2`)"));
+    }
+}
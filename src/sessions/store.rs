@@ -0,0 +1,450 @@
+use crate::providers::ConversationMessage;
+use crate::util::truncate_with_ellipsis;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Maximum length of a session id.
+const MAX_SESSION_ID_LEN: usize = 256;
+/// Characters kept from the first user message as the session title.
+const SESSION_TITLE_CHARS: usize = 60;
+
+/// Metadata for a stored session.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SessionInfo {
+    pub id: String,
+    /// Where the session originated (`cli`, `telegram`, `discord`, ...).
+    pub source: String,
+    /// Preview of the first user message, if any.
+    pub title: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub message_count: usize,
+}
+
+/// SQLite-backed store of conversation transcripts, keyed by session id.
+///
+/// Lives at `<workspace>/sessions/sessions.db`. Each session keeps its full
+/// ordered list of [`ConversationMessage`]s, including tool calls and tool
+/// results, so a conversation survives process restarts.
+pub struct SessionStore {
+    conn: Mutex<Connection>,
+    db_path: PathBuf,
+}
+
+impl SessionStore {
+    /// Open (or create) the session store for a workspace.
+    pub fn open(workspace_dir: &Path) -> Result<Self> {
+        let db_path = workspace_dir.join("sessions").join("sessions.db");
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create sessions directory: {}", parent.display())
+            })?;
+        }
+
+        let conn = Connection::open(&db_path)
+            .with_context(|| format!("Failed to open session DB: {}", db_path.display()))?;
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             PRAGMA synchronous  = NORMAL;
+             PRAGMA foreign_keys = ON;",
+        )?;
+        Self::init_schema(&conn)?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+            db_path,
+        })
+    }
+
+    fn init_schema(conn: &Connection) -> Result<()> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS sessions (
+                id          TEXT PRIMARY KEY,
+                source      TEXT NOT NULL,
+                title       TEXT,
+                created_at  TEXT NOT NULL,
+                updated_at  TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_sessions_updated_at ON sessions(updated_at);
+
+            CREATE TABLE IF NOT EXISTS session_messages (
+                id          INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_id  TEXT NOT NULL,
+                payload     TEXT NOT NULL,
+                created_at  TEXT NOT NULL,
+                FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_session_messages_session
+                ON session_messages(session_id, id);",
+        )
+        .context("Failed to initialize session schema")?;
+        Ok(())
+    }
+
+    /// Path of the backing database file.
+    pub fn db_path(&self) -> &Path {
+        &self.db_path
+    }
+
+    /// Create a session with a fresh id.
+    pub fn create(&self, source: &str) -> Result<SessionInfo> {
+        let id = uuid::Uuid::new_v4().to_string();
+        self.ensure(&id, source)
+    }
+
+    /// Return the session with `id`, creating it if it does not exist yet.
+    pub fn ensure(&self, id: &str, source: &str) -> Result<SessionInfo> {
+        validate_session_id(id)?;
+        let now = Utc::now().to_rfc3339();
+        {
+            let conn = self.conn.lock();
+            conn.execute(
+                "INSERT OR IGNORE INTO sessions (id, source, title, created_at, updated_at)
+                 VALUES (?1, ?2, NULL, ?3, ?3)",
+                params![id, source, now],
+            )
+            .context("Failed to create session")?;
+        }
+        self.get(id)?
+            .with_context(|| format!("Session '{id}' disappeared after creation"))
+    }
+
+    /// Look up a session by id.
+    pub fn get(&self, id: &str) -> Result<Option<SessionInfo>> {
+        let conn = self.conn.lock();
+        conn.query_row(
+            "SELECT s.id, s.source, s.title, s.created_at, s.updated_at,
+                    (SELECT COUNT(*) FROM session_messages m WHERE m.session_id = s.id)
+             FROM sessions s WHERE s.id = ?1",
+            params![id],
+            map_session_row,
+        )
+        .optional()
+        .context("Failed to load session")
+    }
+
+    /// Most recently updated sessions first.
+    pub fn list(&self, limit: usize) -> Result<Vec<SessionInfo>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT s.id, s.source, s.title, s.created_at, s.updated_at,
+                    (SELECT COUNT(*) FROM session_messages m WHERE m.session_id = s.id)
+             FROM sessions s ORDER BY s.updated_at DESC LIMIT ?1",
+        )?;
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let rows = stmt.query_map(params![limit], map_session_row)?;
+
+        let mut sessions = Vec::new();
+        for row in rows {
+            sessions.push(row?);
+        }
+        Ok(sessions)
+    }
+
+    /// Append messages to the end of a session's transcript.
+    ///
+    /// The session must already exist (see [`SessionStore::ensure`]).
+    pub fn append(&self, id: &str, messages: &[ConversationMessage]) -> Result<()> {
+        if messages.is_empty() {
+            return Ok(());
+        }
+        self.write_messages(id, messages, false)
+    }
+
+    /// Replace a session's transcript, e.g. with a compacted version of it.
+    ///
+    /// The session must already exist (see [`SessionStore::ensure`]).
+    pub fn replace(&self, id: &str, messages: &[ConversationMessage]) -> Result<()> {
+        self.write_messages(id, messages, true)
+    }
+
+    fn write_messages(
+        &self,
+        id: &str,
+        messages: &[ConversationMessage],
+        replace: bool,
+    ) -> Result<()> {
+        let now = Utc::now().to_rfc3339();
+        let title = messages.iter().find_map(user_text).map(|text| {
+            truncate_with_ellipsis(
+                &text.split_whitespace().collect::<Vec<_>>().join(" "),
+                SESSION_TITLE_CHARS,
+            )
+        });
+
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        let updated = tx.execute(
+            "UPDATE sessions SET updated_at = ?2, title = COALESCE(title, ?3) WHERE id = ?1",
+            params![id, now, title],
+        )?;
+        if updated == 0 {
+            anyhow::bail!("Session '{id}' not found");
+        }
+        if replace {
+            tx.execute(
+                "DELETE FROM session_messages WHERE session_id = ?1",
+                params![id],
+            )?;
+        }
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO session_messages (session_id, payload, created_at)
+                 VALUES (?1, ?2, ?3)",
+            )?;
+            for message in messages {
+                stmt.execute(params![id, serde_json::to_string(message)?, now])?;
+            }
+        }
+        tx.commit().context("Failed to write session messages")?;
+        Ok(())
+    }
+
+    /// Full transcript of a session, oldest first.
+    pub fn load(&self, id: &str) -> Result<Vec<ConversationMessage>> {
+        self.load_recent(id, usize::MAX)
+    }
+
+    /// The last `limit` messages of a session, oldest first.
+    pub fn load_recent(&self, id: &str, limit: usize) -> Result<Vec<ConversationMessage>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT payload FROM (
+                SELECT id, payload FROM session_messages
+                WHERE session_id = ?1 ORDER BY id DESC LIMIT ?2
+             ) ORDER BY id ASC",
+        )?;
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let rows = stmt.query_map(params![id, limit], |row| row.get::<_, String>(0))?;
+
+        let mut messages = Vec::new();
+        for payload in rows {
+            let payload = payload?;
+            match serde_json::from_str(&payload) {
+                Ok(message) => messages.push(message),
+                Err(e) => tracing::warn!(session = id, "Skipping unreadable session message: {e}"),
+            }
+        }
+        Ok(messages)
+    }
+
+    /// Remove and return the most recent message of a session.
+    pub fn pop_last(&self, id: &str) -> Result<Option<ConversationMessage>> {
+        let conn = self.conn.lock();
+        let last: Option<(i64, String)> = conn
+            .query_row(
+                "SELECT id, payload FROM session_messages
+                 WHERE session_id = ?1 ORDER BY id DESC LIMIT 1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        let Some((row_id, payload)) = last else {
+            return Ok(None);
+        };
+        conn.execute(
+            "DELETE FROM session_messages WHERE id = ?1",
+            params![row_id],
+        )?;
+        Ok(serde_json::from_str(&payload).ok())
+    }
+
+    /// Drop every message of a session but keep the session itself.
+    pub fn clear(&self, id: &str) -> Result<()> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "DELETE FROM session_messages WHERE session_id = ?1",
+            params![id],
+        )?;
+        conn.execute(
+            "UPDATE sessions SET title = NULL, updated_at = ?2 WHERE id = ?1",
+            params![id, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Delete a session and its transcript. Returns `false` if it did not exist.
+    pub fn delete(&self, id: &str) -> Result<bool> {
+        let conn = self.conn.lock();
+        let deleted = conn.execute("DELETE FROM sessions WHERE id = ?1", params![id])?;
+        Ok(deleted > 0)
+    }
+}
+
+fn validate_session_id(id: &str) -> Result<()> {
+    if id.trim().is_empty() {
+        anyhow::bail!("Session id must not be empty");
+    }
+    if id.len() > MAX_SESSION_ID_LEN {
+        anyhow::bail!("Session id must be at most {MAX_SESSION_ID_LEN} bytes");
+    }
+    if id.chars().any(char::is_control) {
+        anyhow::bail!("Session id must not contain control characters");
+    }
+    Ok(())
+}
+
+fn user_text(message: &ConversationMessage) -> Option<&str> {
+    match message {
+        ConversationMessage::Chat(chat) if chat.role == "user" => {
+            Some(chat.content.trim()).filter(|text| !text.is_empty())
+        }
+        _ => None,
+    }
+}
+
+fn map_session_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<SessionInfo> {
+    let created_at: String = row.get(3)?;
+    let updated_at: String = row.get(4)?;
+    let message_count: i64 = row.get(5)?;
+    Ok(SessionInfo {
+        id: row.get(0)?,
+        source: row.get(1)?,
+        title: row.get(2)?,
+        created_at: parse_rfc3339(&created_at),
+        updated_at: parse_rfc3339(&updated_at),
+        message_count: usize::try_from(message_count).unwrap_or(0),
+    })
+}
+
+fn parse_rfc3339(raw: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(raw)
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::{ChatMessage, ToolCall, ToolResultMessage};
+    use tempfile::TempDir;
+
+    fn transcript() -> Vec<ConversationMessage> {
+        vec![
+            ConversationMessage::Chat(ChatMessage::user("list the   files\nplease")),
+            ConversationMessage::AssistantToolCalls {
+                text: Some("Checking".into()),
+                tool_calls: vec![ToolCall {
+                    id: "call_1".into(),
+                    name: "shell".into(),
+                    arguments: r#"{"command":"ls"}"#.into(),
+                }],
+            },
+            ConversationMessage::ToolResults(vec![ToolResultMessage {
+                tool_call_id: "call_1".into(),
+                content: "Cargo.toml".into(),
+            }]),
+            ConversationMessage::Chat(ChatMessage::assistant("Just Cargo.toml")),
+        ]
+    }
+
+    #[test]
+    fn append_and_load_roundtrip_preserves_tool_messages() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::open(tmp.path()).unwrap();
+        let session = store.create("cli").unwrap();
+
+        store.append(&session.id, &transcript()).unwrap();
+        let loaded = store.load(&session.id).unwrap();
+
+        assert_eq!(loaded.len(), 4);
+        assert!(matches!(
+            &loaded[1],
+            ConversationMessage::AssistantToolCalls { tool_calls, .. } if tool_calls[0].id == "call_1"
+        ));
+        assert!(matches!(
+            &loaded[2],
+            ConversationMessage::ToolResults(results) if results[0].content == "Cargo.toml"
+        ));
+
+        let info = store.get(&session.id).unwrap().unwrap();
+        assert_eq!(info.message_count, 4);
+        assert_eq!(info.title.as_deref(), Some("list the files please"));
+    }
+
+    #[test]
+    fn store_survives_reopen() {
+        let tmp = TempDir::new().unwrap();
+        {
+            let store = SessionStore::open(tmp.path()).unwrap();
+            store.ensure("telegram_alice", "telegram").unwrap();
+            store.append("telegram_alice", &transcript()).unwrap();
+        }
+        let store = SessionStore::open(tmp.path()).unwrap();
+        assert_eq!(store.load("telegram_alice").unwrap().len(), 4);
+        assert_eq!(store.list(10).unwrap()[0].source, "telegram");
+    }
+
+    #[test]
+    fn load_recent_returns_tail_in_order() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::open(tmp.path()).unwrap();
+        let session = store.create("cli").unwrap();
+        store.append(&session.id, &transcript()).unwrap();
+
+        let recent = store.load_recent(&session.id, 2).unwrap();
+        assert_eq!(recent.len(), 2);
+        assert!(matches!(&recent[0], ConversationMessage::ToolResults(_)));
+        assert!(matches!(&recent[1], ConversationMessage::Chat(chat) if chat.role == "assistant"));
+    }
+
+    #[test]
+    fn pop_clear_and_delete() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::open(tmp.path()).unwrap();
+        let session = store.create("cli").unwrap();
+        store.append(&session.id, &transcript()).unwrap();
+
+        let popped = store.pop_last(&session.id).unwrap();
+        assert!(matches!(popped, Some(ConversationMessage::Chat(_))));
+        assert_eq!(store.load(&session.id).unwrap().len(), 3);
+
+        store.clear(&session.id).unwrap();
+        assert!(store.load(&session.id).unwrap().is_empty());
+        assert!(store.get(&session.id).unwrap().is_some());
+
+        assert!(store.delete(&session.id).unwrap());
+        assert!(!store.delete(&session.id).unwrap());
+        assert!(store.get(&session.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn replace_swaps_transcript_and_keeps_title() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::open(tmp.path()).unwrap();
+        let session = store.create("cli").unwrap();
+        store.append(&session.id, &transcript()).unwrap();
+        let title = store.get(&session.id).unwrap().unwrap().title;
+
+        store
+            .replace(
+                &session.id,
+                &[ConversationMessage::Chat(ChatMessage::assistant("summary"))],
+            )
+            .unwrap();
+        let loaded = store.load(&session.id).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert!(matches!(&loaded[0], ConversationMessage::Chat(chat) if chat.content == "summary"));
+        assert_eq!(store.get(&session.id).unwrap().unwrap().title, title);
+    }
+
+    #[test]
+    fn append_to_unknown_session_fails() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::open(tmp.path()).unwrap();
+        assert!(store.append("missing", &transcript()).is_err());
+    }
+
+    #[test]
+    fn ensure_rejects_invalid_ids() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::open(tmp.path()).unwrap();
+        assert!(store.ensure("  ", "cli").is_err());
+        assert!(store.ensure("bad\nid", "cli").is_err());
+        assert!(store.ensure(&"x".repeat(300), "cli").is_err());
+    }
+}
@@ -26,6 +26,8 @@ pub struct Skill {
     pub tools: Vec<SkillTool>,
     #[serde(default)]
     pub prompts: Vec<String>,
+    #[serde(default)]
+    pub permissions: SkillPermissions,
     #[serde(skip)]
     pub location: Option<PathBuf>,
 }
@@ -37,10 +39,60 @@ pub struct SkillTool {
     pub description: String,
     /// "shell", "http", "script"
     pub kind: String,
-    /// The command/URL/script to execute
+    /// The command/URL/script to execute. `{param}` placeholders are filled
+    /// from the tool's parameters.
     pub command: String,
     #[serde(default)]
     pub args: HashMap<String, String>,
+    /// Typed parameters exposed to the model as the tool's JSON schema
+    #[serde(default)]
+    pub parameters: Vec<SkillToolParameter>,
+    /// HTTP method for `kind = "http"` (default GET)
+    #[serde(default)]
+    pub method: Option<String>,
+}
+
+/// A typed parameter of a skill tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillToolParameter {
+    pub name: String,
+    /// "string", "integer", "number", "boolean"
+    #[serde(rename = "type", default = "default_parameter_type")]
+    pub kind: String,
+    #[serde(default)]
+    pub description: String,
+    /// Defaults to true unless a `default` is given
+    #[serde(default)]
+    pub required: Option<bool>,
+    #[serde(default)]
+    pub default: Option<serde_json::Value>,
+    /// Allowed values
+    #[serde(default, rename = "enum")]
+    pub choices: Vec<String>,
+}
+
+impl SkillToolParameter {
+    pub fn is_required(&self) -> bool {
+        self.required.unwrap_or(self.default.is_none())
+    }
+}
+
+fn default_parameter_type() -> String {
+    "string".to_string()
+}
+
+/// What a skill's tools may reach, from the `[permissions]` table.
+///
+/// Shell and script tools may only run `allowed_commands` that the global
+/// `[autonomy].allowed_commands` also permits; when empty, only the
+/// executable named in each tool's `command`. HTTP tools may only call
+/// `allowed_domains`; when empty, only the host in each tool's URL.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SkillPermissions {
+    #[serde(default)]
+    pub allowed_commands: Vec<String>,
+    #[serde(default)]
+    pub allowed_domains: Vec<String>,
 }
 
 /// Skill manifest parsed from SKILL.toml
@@ -51,6 +103,8 @@ struct SkillManifest {
     tools: Vec<SkillTool>,
     #[serde(default)]
     prompts: Vec<String>,
+    #[serde(default)]
+    permissions: SkillPermissions,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -373,6 +427,7 @@ fn load_skill_toml(path: &Path) -> Result<Skill> {
         tags: manifest.skill.tags,
         tools: manifest.tools,
         prompts: manifest.prompts,
+        permissions: manifest.permissions,
         location: Some(path.to_path_buf()),
     })
 }
@@ -394,6 +449,7 @@ fn load_skill_md(path: &Path, dir: &Path) -> Result<Skill> {
         tags: Vec::new(),
         tools: Vec::new(),
         prompts: vec![content],
+        permissions: SkillPermissions::default(),
         location: Some(path.to_path_buf()),
     })
 }
@@ -414,6 +470,7 @@ fn load_open_skill_md(path: &Path) -> Result<Skill> {
         tags: vec!["open-skills".to_string()],
         tools: Vec::new(),
         prompts: vec![content],
+        permissions: SkillPermissions::default(),
         location: Some(path.to_path_buf()),
     })
 }
@@ -508,6 +565,15 @@ pub fn skills_to_prompt_with_mode(
         ),
     };
 
+    if matches!(mode, crate::config::SkillsPromptInjectionMode::Full)
+        && skills.iter().any(|skill| !skill.tools.is_empty())
+    {
+        prompt.insert_str(
+            prompt.len() - "<available_skills>\n".len(),
+            "Skill tools are registered as callable tools: call them by name, not through `shell`.\n\n",
+        );
+    }
+
     for skill in skills {
         let _ = writeln!(prompt, "  <skill>");
         write_xml_text_element(&mut prompt, 4, "name", &skill.name);
@@ -929,6 +995,7 @@ command = "echo hello"
             tags: vec![],
             tools: vec![],
             prompts: vec!["Do the thing.".to_string()],
+            permissions: SkillPermissions::default(),
             location: None,
         }];
         let prompt = skills_to_prompt(&skills, Path::new("/tmp"));
@@ -951,8 +1018,11 @@ command = "echo hello"
                 kind: "shell".to_string(),
                 command: "echo hi".to_string(),
                 args: HashMap::new(),
+                parameters: Vec::new(),
+                method: None,
             }],
             prompts: vec!["Do the thing.".to_string()],
+            permissions: SkillPermissions::default(),
             location: Some(PathBuf::from("/tmp/workspace/skills/test/SKILL.md")),
         }];
         let prompt = skills_to_prompt_with_mode(
@@ -1085,6 +1155,54 @@ command = "https://api.example.com/deploy"
         assert_eq!(s.tools[2].kind, "http");
     }
 
+    #[test]
+    fn toml_skill_tool_parameters_and_permissions() {
+        let dir = tempfile::tempdir().unwrap();
+        let skill_dir = dir.path().join("skills").join("weather");
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(
+            skill_dir.join("SKILL.toml"),
+            r#"
+[skill]
+name = "weather"
+description = "Forecasts"
+
+[permissions]
+allowed_domains = ["wttr.in"]
+
+[[tools]]
+name = "forecast"
+description = "Fetch a forecast"
+kind = "http"
+method = "get"
+command = "https://wttr.in/{city}?format={format}"
+
+[[tools.parameters]]
+name = "city"
+description = "City name"
+
+[[tools.parameters]]
+name = "format"
+type = "integer"
+default = 3
+enum = ["1", "3"]
+"#,
+        )
+        .unwrap();
+
+        let skills = load_skills(dir.path());
+        let skill = &skills[0];
+        assert_eq!(skill.permissions.allowed_domains, vec!["wttr.in"]);
+        let tool = &skill.tools[0];
+        assert_eq!(tool.method.as_deref(), Some("get"));
+        assert_eq!(tool.parameters.len(), 2);
+        assert_eq!(tool.parameters[0].kind, "string");
+        assert!(tool.parameters[0].is_required());
+        assert_eq!(tool.parameters[1].kind, "integer");
+        assert!(!tool.parameters[1].is_required());
+        assert_eq!(tool.parameters[1].choices, vec!["1", "3"]);
+    }
+
     #[test]
     fn toml_skill_minimal() {
         let dir = tempfile::tempdir().unwrap();
@@ -1151,8 +1269,11 @@ description = "Bare minimum"
                 kind: "shell".to_string(),
                 command: "curl wttr.in".to_string(),
                 args: HashMap::new(),
+                parameters: Vec::new(),
+                method: None,
             }],
             prompts: vec![],
+            permissions: SkillPermissions::default(),
             location: None,
         }];
         let prompt = skills_to_prompt(&skills, Path::new("/tmp"));
@@ -1160,6 +1281,7 @@ description = "Bare minimum"
         assert!(prompt.contains("<name>get_weather</name>"));
         assert!(prompt.contains("<description>Fetch forecast</description>"));
         assert!(prompt.contains("<kind>shell</kind>"));
+        assert!(prompt.contains("call them by name, not through `shell`"));
     }
 
     #[test]
@@ -1172,6 +1294,7 @@ description = "Bare minimum"
             tags: vec![],
             tools: vec![],
             prompts: vec!["Use <tool> & check \"quotes\".".to_string()],
+            permissions: SkillPermissions::default(),
             location: None,
         }];
 
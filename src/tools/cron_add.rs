@@ -1,6 +1,8 @@
 use super::traits::{Tool, ToolResult};
 use crate::config::Config;
-use crate::cron::{self, DeliveryConfig, JobType, Schedule, SessionTarget};
+use crate::cron::{
+    self, CronJobPatch, DeliveryConfig, JobType, RetryPolicy, Schedule, SessionTarget,
+};
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use serde_json::json;
@@ -16,6 +18,38 @@ impl CronAddTool {
         Self { config, security }
     }
 
+    /// Retry, timeout, dependency and alert options applied after creation.
+    fn parse_job_options(&self, args: &serde_json::Value) -> Result<CronJobPatch, String> {
+        let mut options = CronJobPatch::default();
+        if let Some(v) = args.get("retry") {
+            options.retry = Some(
+                serde_json::from_value::<RetryPolicy>(v.clone())
+                    .map_err(|e| format!("Invalid retry policy: {e}"))?,
+            );
+        }
+        if let Some(v) = args.get("timeout_secs") {
+            match v.as_u64() {
+                Some(secs) if secs > 0 => options.timeout_secs = Some(secs),
+                _ => return Err("timeout_secs must be a positive integer".to_string()),
+            }
+        }
+        if let Some(v) = args.get("depends_on") {
+            let deps = serde_json::from_value::<Vec<String>>(v.clone())
+                .map_err(|e| format!("Invalid depends_on: {e}"))?;
+            for dep in &deps {
+                cron::get_job(&self.config, dep).map_err(|e| e.to_string())?;
+            }
+            options.depends_on = Some(deps);
+        }
+        if let Some(v) = args.get("on_failure") {
+            options.on_failure = Some(
+                serde_json::from_value::<DeliveryConfig>(v.clone())
+                    .map_err(|e| format!("Invalid on_failure config: {e}"))?,
+            );
+        }
+        Ok(options)
+    }
+
     fn enforce_mutation_allowed(&self, action: &str) -> Option<ToolResult> {
         if !self.security.can_act() {
             return Some(ToolResult {
@@ -73,6 +107,20 @@ impl Tool for CronAddTool {
                 "model": { "type": "string" },
                 "delivery": { "type": "object" },
                 "delete_after_run": { "type": "boolean" },
+                "retry": {
+                    "type": "object",
+                    "description": "Retry policy: {max_attempts, backoff_ms?, max_backoff_ms?}"
+                },
+                "timeout_secs": { "type": "integer", "minimum": 1 },
+                "depends_on": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Job ids that must succeed before this job runs"
+                },
+                "on_failure": {
+                    "type": "object",
+                    "description": "Delivery target {channel, to} alerted when the job exhausts its retries"
+                },
                 "approved": {
                     "type": "boolean",
                     "description": "Set true to explicitly approve medium/high-risk shell commands in supervised mode",
@@ -146,6 +194,17 @@ impl Tool for CronAddTool {
             .and_then(serde_json::Value::as_bool)
             .unwrap_or(false);
 
+        let options = match self.parse_job_options(&args) {
+            Ok(options) => options,
+            Err(reason) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(reason),
+                });
+            }
+        };
+
         let result = match job_type {
             JobType::Shell => {
                 let command = match args.get("command").and_then(serde_json::Value::as_str) {
@@ -235,6 +294,14 @@ impl Tool for CronAddTool {
             }
         };
 
+        let result = result.and_then(|job| {
+            if options.is_empty() {
+                Ok(job)
+            } else {
+                cron::update_job(&self.config, &job.id, options)
+            }
+        });
+
         match result {
             Ok(job) => Ok(ToolResult {
                 success: true,
@@ -244,7 +311,8 @@ impl Tool for CronAddTool {
                     "job_type": job.job_type,
                     "schedule": job.schedule,
                     "next_run": job.next_run,
-                    "enabled": job.enabled
+                    "enabled": job.enabled,
+                    "depends_on": job.depends_on
                 }))?,
                 error: None,
             }),
@@ -438,4 +506,51 @@ mod tests {
             .unwrap_or_default()
             .contains("Missing 'prompt'"));
     }
+
+    #[tokio::test]
+    async fn adds_job_with_dependency_and_retry_policy() {
+        let tmp = TempDir::new().unwrap();
+        let cfg = test_config(&tmp).await;
+        let upstream = cron::add_job(&cfg, "*/5 * * * *", "echo upstream").unwrap();
+        let tool = CronAddTool::new(cfg.clone(), test_security(&cfg));
+
+        let result = tool
+            .execute(json!({
+                "schedule": { "kind": "cron", "expr": "*/5 * * * *" },
+                "command": "echo downstream",
+                "retry": { "max_attempts": 2 },
+                "timeout_secs": 30,
+                "depends_on": [upstream.id],
+                "on_failure": { "channel": "telegram", "to": "ops" }
+            }))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+
+        let created: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        let job = cron::get_job(&cfg, created["id"].as_str().unwrap()).unwrap();
+        assert_eq!(job.depends_on, vec![upstream.id]);
+        assert_eq!(job.retry.unwrap().max_attempts, 2);
+        assert_eq!(job.timeout_secs, Some(30));
+        assert_eq!(job.on_failure.unwrap().to.as_deref(), Some("ops"));
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_dependency() {
+        let tmp = TempDir::new().unwrap();
+        let cfg = test_config(&tmp).await;
+        let tool = CronAddTool::new(cfg.clone(), test_security(&cfg));
+
+        let result = tool
+            .execute(json!({
+                "schedule": { "kind": "cron", "expr": "*/5 * * * *" },
+                "command": "echo downstream",
+                "depends_on": ["missing-job"]
+            }))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap_or_default().contains("not found"));
+        assert!(cron::list_jobs(&cfg).unwrap().is_empty());
+    }
 }
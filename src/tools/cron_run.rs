@@ -115,20 +115,22 @@ impl Tool for CronRunTool {
             });
         }
 
-        let started_at = Utc::now();
-        let (success, output) = cron::scheduler::execute_job_now(&self.config, &job).await;
+        let outcome = cron::scheduler::execute_job_now(&self.config, &job).await;
         let finished_at = Utc::now();
-        let duration_ms = (finished_at - started_at).num_milliseconds();
+        let duration_ms = (finished_at - outcome.started_at).num_milliseconds();
+        let success = outcome.success;
+        let output = outcome.output;
         let status = if success { "ok" } else { "error" };
 
         let _ = cron::record_run(
             &self.config,
             &job.id,
-            started_at,
+            outcome.started_at,
             finished_at,
             status,
             Some(&output),
             duration_ms,
+            outcome.attempt,
         );
         let _ = cron::record_last_run(&self.config, &job.id, finished_at, success, &output);
 
@@ -137,6 +139,7 @@ impl Tool for CronRunTool {
             output: serde_json::to_string_pretty(&json!({
                 "job_id": job.id,
                 "status": status,
+                "attempt": outcome.attempt,
                 "duration_ms": duration_ms,
                 "output": output
             }))?,
@@ -27,6 +27,7 @@ struct RunView {
     status: String,
     output: Option<String>,
     duration_ms: Option<i64>,
+    attempt: u32,
 }
 
 #[async_trait]
@@ -36,7 +37,7 @@ impl Tool for CronRunsTool {
     }
 
     fn description(&self) -> &str {
-        "List recent run history for a cron job"
+        "List recent run history for a cron job, including retry attempts"
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -87,6 +88,7 @@ impl Tool for CronRunsTool {
                         status: run.status,
                         output: run.output.map(|out| truncate(&out, MAX_RUN_OUTPUT_CHARS)),
                         duration_ms: run.duration_ms,
+                        attempt: run.attempt,
                     })
                     .collect();
 
@@ -149,6 +151,7 @@ mod tests {
             "ok",
             Some(&long_output),
             1,
+            2,
         )
         .unwrap();
 
@@ -160,6 +163,7 @@ mod tests {
 
         assert!(result.success);
         assert!(result.output.contains("..."));
+        assert!(result.output.contains("\"attempt\": 2"));
     }
 
     #[tokio::test]
@@ -54,7 +54,7 @@ impl Tool for CronUpdateTool {
     }
 
     fn description(&self) -> &str {
-        "Patch an existing cron job (schedule, command, prompt, enabled, delivery, model, etc.)"
+        "Patch an existing cron job (schedule, command, prompt, enabled, delivery, model, retry, timeout_secs, depends_on, on_failure, etc.)"
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -1,6 +1,8 @@
 use super::traits::{Tool, ToolResult};
+use crate::agent::context::ContextBudget;
 use crate::agent::loop_::run_tool_call_loop;
 use crate::config::DelegateAgentConfig;
+use crate::cost::CostContext;
 use crate::observability::traits::{Observer, ObserverEvent, ObserverMetric};
 use crate::providers::{self, ChatMessage, Provider};
 use crate::security::policy::ToolOperation;
@@ -33,6 +35,8 @@ pub struct DelegateTool {
     parent_tools: Arc<Vec<Arc<dyn Tool>>>,
     /// Inherited multimodal handling config for sub-agent loops.
     multimodal_config: crate::config::MultimodalConfig,
+    /// Spend tracking; sub-agent usage is charged to the agent's name.
+    cost: Option<CostContext>,
 }
 
 impl DelegateTool {
@@ -63,6 +67,7 @@ impl DelegateTool {
             depth: 0,
             parent_tools: Arc::new(Vec::new()),
             multimodal_config: crate::config::MultimodalConfig::default(),
+            cost: None,
         }
     }
 
@@ -99,6 +104,7 @@ impl DelegateTool {
             depth,
             parent_tools: Arc::new(Vec::new()),
             multimodal_config: crate::config::MultimodalConfig::default(),
+            cost: None,
         }
     }
 
@@ -113,6 +119,12 @@ impl DelegateTool {
         self.multimodal_config = config;
         self
     }
+
+    /// Attach cost tracking so sub-agent spend counts against agent budgets.
+    pub fn with_cost_context(mut self, cost: Option<CostContext>) -> Self {
+        self.cost = cost;
+        self
+    }
 }
 
 #[async_trait]
@@ -240,6 +252,15 @@ impl Tool for DelegateTool {
             });
         }
 
+        let cost = self.cost.as_ref().map(|cost| cost.for_agent(agent_name));
+        if let Some(Err(e)) = cost.as_ref().map(CostContext::ensure_within_budget) {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Agent '{agent_name}' cannot run: {e}")),
+            });
+        }
+
         // Create provider for this agent
         let provider_credential_owned = agent_config
             .api_key
@@ -284,6 +305,7 @@ impl Tool for DelegateTool {
                     &*provider,
                     &full_prompt,
                     temperature,
+                    cost.as_ref(),
                 )
                 .await;
         }
@@ -347,6 +369,7 @@ impl DelegateTool {
         provider: &dyn Provider,
         full_prompt: &str,
         temperature: f64,
+        cost: Option<&CostContext>,
     ) -> anyhow::Result<ToolResult> {
         if agent_config.allowed_tools.is_empty() {
             return Ok(ToolResult {
@@ -411,6 +434,8 @@ impl DelegateTool {
                 None,
                 None,
                 &[],
+                cost,
+                Some(&ContextBudget::for_model(&agent_config.model, None)),
             ),
         )
         .await;
@@ -476,6 +501,10 @@ impl Tool for ToolArcRef {
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         self.inner.execute(args).await
     }
+
+    fn requires_approval(&self) -> bool {
+        self.inner.requires_approval()
+    }
 }
 
 struct NoopObserver;
@@ -601,6 +630,7 @@ mod tests {
                     text: Some("done".to_string()),
                     tool_calls: Vec::new(),
                     usage: None,
+                    routed_to: None,
                 })
             } else {
                 Ok(ChatResponse {
@@ -611,6 +641,7 @@ mod tests {
                         arguments: "{\"value\":\"ping\"}".to_string(),
                     }],
                     usage: None,
+                    routed_to: None,
                 })
             }
         }
@@ -644,6 +675,7 @@ mod tests {
                     arguments: "{\"value\":\"x\"}".to_string(),
                 }],
                 usage: None,
+                routed_to: None,
             })
         }
     }
@@ -1023,7 +1055,7 @@ mod tests {
 
         let provider = OneToolThenFinalProvider;
         let result = tool
-            .execute_agentic("agentic", &config, &provider, "run", 0.2)
+            .execute_agentic("agentic", &config, &provider, "run", 0.2, None)
             .await
             .unwrap();
 
@@ -1045,7 +1077,7 @@ mod tests {
 
         let provider = OneToolThenFinalProvider;
         let result = tool
-            .execute_agentic("agentic", &config, &provider, "run", 0.2)
+            .execute_agentic("agentic", &config, &provider, "run", 0.2, None)
             .await
             .unwrap();
 
@@ -1065,7 +1097,7 @@ mod tests {
 
         let provider = InfiniteToolCallProvider;
         let result = tool
-            .execute_agentic("agentic", &config, &provider, "run", 0.2)
+            .execute_agentic("agentic", &config, &provider, "run", 0.2, None)
             .await
             .unwrap();
 
@@ -1085,7 +1117,7 @@ mod tests {
 
         let provider = FailingProvider;
         let result = tool
-            .execute_agentic("agentic", &config, &provider, "run", 0.2)
+            .execute_agentic("agentic", &config, &provider, "run", 0.2, None)
             .await
             .unwrap();
 
@@ -744,6 +744,7 @@ mod tests {
                         text: Some("done".into()),
                         tool_calls: vec![],
                         usage: None,
+                        routed_to: None,
                     });
                 }
                 Ok(guard.remove(0))
@@ -803,12 +804,14 @@ mod tests {
                     arguments: r#"{"path": "report.pdf"}"#.into(),
                 }],
                 usage: None,
+                routed_to: None,
             },
             // Turn 1 continued: provider sees tool result and answers
             ChatResponse {
                 text: Some("The PDF contains a greeting: Hello PDF".into()),
                 tool_calls: vec![],
                 usage: None,
+                routed_to: None,
             },
         ]);
 
@@ -894,11 +897,13 @@ mod tests {
                     arguments: r#"{"path": "data.bin"}"#.into(),
                 }],
                 usage: None,
+                routed_to: None,
             },
             ChatResponse {
                 text: Some("The file appears to be binary data.".into()),
                 tool_calls: vec![],
                 usage: None,
+                routed_to: None,
             },
         ]);
 
@@ -0,0 +1,941 @@
+use super::traits::{Tool, ToolResult};
+use crate::config::GitHubConfig;
+use crate::security::policy::ToolOperation;
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use reqwest::Method;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Diffs longer than this are truncated before being returned to the model.
+const MAX_DIFF_CHARS: usize = 60_000;
+
+/// Comment and review bodies longer than this are truncated in listings.
+const MAX_BODY_CHARS: usize = 2_000;
+
+/// Upper bound for `limit` on listing actions.
+const MAX_LIMIT: u64 = 50;
+
+/// GitHub REST API client shared by the `github` and `github_write` tools.
+pub struct GitHubClient {
+    api_url: String,
+    token: String,
+    default_repo: Option<String>,
+    timeout_secs: u64,
+    workspace_dir: PathBuf,
+}
+
+impl GitHubClient {
+    /// Client for the configured API, or `None` without a token.
+    pub fn from_config(config: &GitHubConfig, workspace_dir: PathBuf) -> Option<Self> {
+        let token = config
+            .token
+            .as_deref()
+            .map(str::trim)
+            .filter(|token| !token.is_empty())?;
+        Some(Self {
+            api_url: config.api_url.trim().trim_end_matches('/').to_string(),
+            token: token.to_string(),
+            default_repo: config
+                .repo
+                .as_deref()
+                .map(str::trim)
+                .filter(|repo| !repo.is_empty())
+                .map(ToString::to_string),
+            timeout_secs: config.timeout_secs.max(1),
+            workspace_dir,
+        })
+    }
+
+    fn request(&self, method: Method, path: &str) -> reqwest::RequestBuilder {
+        crate::config::build_runtime_proxy_client_with_timeouts(
+            "tool.github",
+            self.timeout_secs,
+            10,
+        )
+        .request(method, format!("{}{path}", self.api_url))
+        .bearer_auth(&self.token)
+        .header("Accept", "application/vnd.github+json")
+        .header("X-GitHub-Api-Version", "2022-11-28")
+        .header("User-Agent", "zeroclaw")
+    }
+
+    async fn send(request: reqwest::RequestBuilder) -> anyhow::Result<reqwest::Response> {
+        let response = request.send().await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let body = response.text().await.unwrap_or_default();
+        let message = serde_json::from_str::<Value>(&body)
+            .ok()
+            .and_then(|error| error["message"].as_str().map(ToString::to_string))
+            .unwrap_or_else(|| crate::util::truncate_with_ellipsis(&body, 300));
+        anyhow::bail!("GitHub API error {status}: {message}")
+    }
+
+    async fn get(&self, path: &str) -> anyhow::Result<Value> {
+        Ok(Self::send(self.request(Method::GET, path))
+            .await?
+            .json()
+            .await?)
+    }
+
+    async fn post(&self, path: &str, body: &Value) -> anyhow::Result<Value> {
+        Ok(Self::send(self.request(Method::POST, path).json(body))
+            .await?
+            .json()
+            .await?)
+    }
+
+    async fn git(&self, args: &[&str]) -> anyhow::Result<String> {
+        let output = tokio::process::Command::new("git")
+            .args(args)
+            .current_dir(&self.workspace_dir)
+            .output()
+            .await?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "git {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// Repository to act on: the `repo` argument, the configured default, or
+    /// the workspace's `origin` remote.
+    async fn resolve_repo(&self, args: &Value) -> anyhow::Result<String> {
+        if let Some(repo) = args
+            .get("repo")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|repo| !repo.is_empty())
+        {
+            return parse_repo_name(repo)
+                .ok_or_else(|| anyhow::anyhow!("'repo' must be in owner/name form"));
+        }
+        if let Some(repo) = &self.default_repo {
+            return Ok(repo.clone());
+        }
+        let remote = self
+            .git(&["remote", "get-url", "origin"])
+            .await
+            .map_err(|_| anyhow::anyhow!("No 'repo' given and no git origin remote found"))?;
+        parse_remote_repo(&remote)
+            .ok_or_else(|| anyhow::anyhow!("Cannot determine repository from origin '{remote}'"))
+    }
+
+    async fn current_branch(&self) -> anyhow::Result<String> {
+        self.git(&["symbolic-ref", "--short", "HEAD"])
+            .await
+            .map_err(|_| anyhow::anyhow!("Cannot determine current branch; pass 'head' explicitly"))
+    }
+}
+
+/// Validate an `owner/name` repository reference.
+fn parse_repo_name(repo: &str) -> Option<String> {
+    let (owner, name) = repo.trim().split_once('/')?;
+    let valid = |part: &str| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+    (valid(owner) && valid(name)).then(|| format!("{owner}/{name}"))
+}
+
+/// Extract `owner/name` from an HTTPS or SSH git remote URL.
+fn parse_remote_repo(remote: &str) -> Option<String> {
+    let remote = remote.trim().trim_end_matches('/');
+    let remote = remote.strip_suffix(".git").unwrap_or(remote);
+    let path = match remote.split_once("://") {
+        Some((_, rest)) => rest.split_once('/')?.1,
+        // scp-like syntax: git@github.com:owner/name
+        None => remote.split_once(':')?.1,
+    };
+    let mut segments = path.rsplit('/');
+    let name = segments.next()?;
+    let owner = segments.next()?;
+    parse_repo_name(&format!("{owner}/{name}"))
+}
+
+fn required_str<'a>(args: &'a Value, key: &str, action: &str) -> anyhow::Result<&'a str> {
+    args.get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("Missing '{key}' for {action}"))
+}
+
+fn required_number(args: &Value, action: &str) -> anyhow::Result<u64> {
+    args.get("number")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| anyhow::anyhow!("Missing 'number' for {action}"))
+}
+
+fn limit(args: &Value) -> u64 {
+    args.get("limit")
+        .and_then(Value::as_u64)
+        .unwrap_or(20)
+        .clamp(1, MAX_LIMIT)
+}
+
+fn body_excerpt(body: &Value) -> Value {
+    body.as_str().map_or(Value::Null, |body| {
+        json!(crate::util::truncate_with_ellipsis(body, MAX_BODY_CHARS))
+    })
+}
+
+/// Compact view of an issue or pull request from a search or list response.
+fn issue_summary(issue: &Value) -> Value {
+    json!({
+        "number": issue["number"],
+        "title": issue["title"],
+        "state": issue["state"],
+        "author": issue.pointer("/user/login"),
+        "is_pull_request": issue.get("pull_request").is_some(),
+        "comments": issue["comments"],
+        "updated_at": issue["updated_at"],
+        "url": issue["html_url"],
+    })
+}
+
+fn comment_summary(comment: &Value) -> Value {
+    json!({
+        "author": comment.pointer("/user/login"),
+        "created_at": comment["created_at"],
+        "body": body_excerpt(&comment["body"]),
+    })
+}
+
+fn tool_output(value: &Value) -> anyhow::Result<ToolResult> {
+    Ok(ToolResult {
+        success: true,
+        output: serde_json::to_string_pretty(value)?,
+        error: None,
+    })
+}
+
+fn tool_error(error: String) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error),
+    }
+}
+
+/// Read-only GitHub tool: search and read issues and PRs, fetch diffs and
+/// CI status, and list notifications.
+pub struct GitHubTool {
+    client: Arc<GitHubClient>,
+}
+
+impl GitHubTool {
+    pub fn new(client: Arc<GitHubClient>) -> Self {
+        Self { client }
+    }
+
+    async fn search(&self, args: &Value) -> anyhow::Result<Value> {
+        let query = required_str(args, "query", "search")?;
+        let mut q = query.to_string();
+        if !q.contains("repo:") && !q.contains("org:") && !q.contains("user:") {
+            if let Ok(repo) = self.client.resolve_repo(args).await {
+                q = format!("{q} repo:{repo}");
+            }
+        }
+        let path = format!(
+            "/search/issues?q={}&per_page={}",
+            urlencoding::encode(&q),
+            limit(args)
+        );
+        let results = self.client.get(&path).await?;
+        let items: Vec<Value> = results["items"]
+            .as_array()
+            .map(|items| items.iter().map(issue_summary).collect())
+            .unwrap_or_default();
+        Ok(json!({
+            "query": q,
+            "total_count": results["total_count"],
+            "items": items,
+        }))
+    }
+
+    async fn get_issue(&self, args: &Value) -> anyhow::Result<Value> {
+        let repo = self.client.resolve_repo(args).await?;
+        let number = required_number(args, "get_issue")?;
+        let issue = self
+            .client
+            .get(&format!("/repos/{repo}/issues/{number}"))
+            .await?;
+        let comments = self
+            .client
+            .get(&format!(
+                "/repos/{repo}/issues/{number}/comments?per_page={}",
+                limit(args)
+            ))
+            .await?;
+        Ok(json!({
+            "number": number,
+            "title": issue["title"],
+            "state": issue["state"],
+            "author": issue.pointer("/user/login"),
+            "labels": issue["labels"]
+                .as_array()
+                .map(|labels| labels.iter().map(|label| label["name"].clone()).collect::<Vec<_>>()),
+            "assignees": issue["assignees"]
+                .as_array()
+                .map(|users| users.iter().map(|user| user["login"].clone()).collect::<Vec<_>>()),
+            "is_pull_request": issue.get("pull_request").is_some(),
+            "body": body_excerpt(&issue["body"]),
+            "comments": comments.as_array().map(|c| c.iter().map(comment_summary).collect::<Vec<_>>()),
+            "url": issue["html_url"],
+        }))
+    }
+
+    async fn get_pull_request(&self, args: &Value) -> anyhow::Result<Value> {
+        let repo = self.client.resolve_repo(args).await?;
+        let number = required_number(args, "get_pull_request")?;
+        let pr = self
+            .client
+            .get(&format!("/repos/{repo}/pulls/{number}"))
+            .await?;
+        let reviews = self
+            .client
+            .get(&format!("/repos/{repo}/pulls/{number}/reviews"))
+            .await?;
+        let reviews: Vec<Value> = reviews
+            .as_array()
+            .map(|reviews| {
+                reviews
+                    .iter()
+                    .map(|review| {
+                        json!({
+                            "author": review.pointer("/user/login"),
+                            "state": review["state"],
+                            "body": body_excerpt(&review["body"]),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(json!({
+            "number": number,
+            "title": pr["title"],
+            "state": pr["state"],
+            "draft": pr["draft"],
+            "merged": pr["merged"],
+            "mergeable_state": pr["mergeable_state"],
+            "author": pr.pointer("/user/login"),
+            "head": pr.pointer("/head/ref"),
+            "head_sha": pr.pointer("/head/sha"),
+            "base": pr.pointer("/base/ref"),
+            "additions": pr["additions"],
+            "deletions": pr["deletions"],
+            "changed_files": pr["changed_files"],
+            "body": body_excerpt(&pr["body"]),
+            "reviews": reviews,
+            "url": pr["html_url"],
+        }))
+    }
+
+    async fn pr_diff(&self, args: &Value) -> anyhow::Result<Value> {
+        let repo = self.client.resolve_repo(args).await?;
+        let number = required_number(args, "pr_diff")?;
+        let request = self
+            .client
+            .request(Method::GET, &format!("/repos/{repo}/pulls/{number}"))
+            .header("Accept", "application/vnd.github.diff");
+        let diff = GitHubClient::send(request).await?.text().await?;
+        let truncated = diff.chars().count() > MAX_DIFF_CHARS;
+        Ok(json!({
+            "number": number,
+            "truncated": truncated,
+            "diff": crate::util::truncate_with_ellipsis(&diff, MAX_DIFF_CHARS),
+        }))
+    }
+
+    async fn check_status(&self, args: &Value) -> anyhow::Result<Value> {
+        let repo = self.client.resolve_repo(args).await?;
+        let git_ref = match args.get("ref").and_then(Value::as_str) {
+            Some(git_ref) => git_ref.trim().to_string(),
+            None => {
+                let number = required_number(args, "check_status")?;
+                let pr = self
+                    .client
+                    .get(&format!("/repos/{repo}/pulls/{number}"))
+                    .await?;
+                pr.pointer("/head/sha")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| anyhow::anyhow!("Pull request #{number} has no head commit"))?
+                    .to_string()
+            }
+        };
+        let encoded = urlencoding::encode(&git_ref);
+        let runs = self
+            .client
+            .get(&format!(
+                "/repos/{repo}/commits/{encoded}/check-runs?per_page=100"
+            ))
+            .await?;
+        let status = self
+            .client
+            .get(&format!("/repos/{repo}/commits/{encoded}/status"))
+            .await?;
+
+        let check_runs: Vec<Value> = runs["check_runs"]
+            .as_array()
+            .map(|runs| {
+                runs.iter()
+                    .map(|run| {
+                        json!({
+                            "name": run["name"],
+                            "status": run["status"],
+                            "conclusion": run["conclusion"],
+                            "url": run["html_url"],
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let statuses: Vec<Value> = status["statuses"]
+            .as_array()
+            .map(|statuses| {
+                statuses
+                    .iter()
+                    .map(|status| {
+                        json!({
+                            "context": status["context"],
+                            "state": status["state"],
+                            "description": status["description"],
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(json!({
+            "ref": git_ref,
+            "combined_state": status["state"],
+            "check_runs": check_runs,
+            "statuses": statuses,
+        }))
+    }
+
+    async fn notifications(&self, args: &Value) -> anyhow::Result<Value> {
+        let all = args.get("all").and_then(Value::as_bool).unwrap_or(false);
+        let notifications = self
+            .client
+            .get(&format!(
+                "/notifications?all={all}&per_page={}",
+                limit(args)
+            ))
+            .await?;
+        let items: Vec<Value> = notifications
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .map(|item| {
+                        json!({
+                            "id": item["id"],
+                            "repo": item.pointer("/repository/full_name"),
+                            "type": item.pointer("/subject/type"),
+                            "title": item.pointer("/subject/title"),
+                            "reason": item["reason"],
+                            "unread": item["unread"],
+                            "updated_at": item["updated_at"],
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(json!({ "notifications": items }))
+    }
+}
+
+#[async_trait]
+impl Tool for GitHubTool {
+    fn name(&self) -> &str {
+        "github"
+    }
+
+    fn description(&self) -> &str {
+        "Read GitHub issues and pull requests. Actions: search (issue/PR search syntax), get_issue, get_pull_request (with reviews), pr_diff, check_status (CI checks for a PR or ref), notifications. Use github_write to comment, review or open PRs."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["search", "get_issue", "get_pull_request", "pr_diff", "check_status", "notifications"],
+                    "description": "Operation to perform"
+                },
+                "repo": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to the configured repo or the workspace origin remote)"
+                },
+                "number": {
+                    "type": "integer",
+                    "description": "Issue or pull request number"
+                },
+                "query": {
+                    "type": "string",
+                    "description": "Search query, e.g. 'is:pr is:open label:bug' (search)"
+                },
+                "ref": {
+                    "type": "string",
+                    "description": "Branch, tag or commit SHA (check_status, instead of number)"
+                },
+                "all": {
+                    "type": "boolean",
+                    "description": "Include read notifications (notifications)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum items to return (default 20, max 50)"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        let action = required_str(&args, "action", "github")?;
+        let result = match action {
+            "search" => self.search(&args).await,
+            "get_issue" => self.get_issue(&args).await,
+            "get_pull_request" => self.get_pull_request(&args).await,
+            "pr_diff" => self.pr_diff(&args).await,
+            "check_status" => self.check_status(&args).await,
+            "notifications" => self.notifications(&args).await,
+            other => return Ok(tool_error(format!("Unknown action '{other}'"))),
+        };
+        match result {
+            Ok(value) => tool_output(&value),
+            Err(e) => Ok(tool_error(format!("GitHub {action} failed: {e}"))),
+        }
+    }
+}
+
+/// Mutating GitHub tool: comment, review and open pull requests.
+///
+/// Kept separate from [`GitHubTool`] so the approval workflow can gate every
+/// write by tool name. Writes are refused where no approval prompt exists.
+pub struct GitHubWriteTool {
+    client: Arc<GitHubClient>,
+    security: Arc<SecurityPolicy>,
+}
+
+impl GitHubWriteTool {
+    pub fn new(client: Arc<GitHubClient>, security: Arc<SecurityPolicy>) -> Self {
+        Self { client, security }
+    }
+
+    async fn comment(&self, args: &Value) -> anyhow::Result<Value> {
+        let repo = self.client.resolve_repo(args).await?;
+        let number = required_number(args, "comment")?;
+        let body = required_str(args, "body", "comment")?;
+        let comment = self
+            .client
+            .post(
+                &format!("/repos/{repo}/issues/{number}/comments"),
+                &json!({ "body": body }),
+            )
+            .await?;
+        Ok(json!({ "id": comment["id"], "url": comment["html_url"] }))
+    }
+
+    async fn review(&self, args: &Value) -> anyhow::Result<Value> {
+        let repo = self.client.resolve_repo(args).await?;
+        let number = required_number(args, "review")?;
+        let event = args
+            .get("event")
+            .and_then(Value::as_str)
+            .unwrap_or("COMMENT")
+            .to_ascii_uppercase();
+        if !matches!(event.as_str(), "COMMENT" | "APPROVE" | "REQUEST_CHANGES") {
+            anyhow::bail!("'event' must be COMMENT, APPROVE or REQUEST_CHANGES");
+        }
+        let body = args.get("body").and_then(Value::as_str).unwrap_or_default();
+        if body.trim().is_empty() && event != "APPROVE" {
+            anyhow::bail!("Missing 'body' for a {event} review");
+        }
+        let review = self
+            .client
+            .post(
+                &format!("/repos/{repo}/pulls/{number}/reviews"),
+                &json!({ "event": event, "body": body }),
+            )
+            .await?;
+        Ok(json!({
+            "id": review["id"],
+            "state": review["state"],
+            "url": review["html_url"],
+        }))
+    }
+
+    async fn create_pull_request(&self, args: &Value) -> anyhow::Result<Value> {
+        let repo = self.client.resolve_repo(args).await?;
+        let title = required_str(args, "title", "create_pull_request")?;
+        let head = match args.get("head").and_then(Value::as_str) {
+            Some(head) => head.trim().to_string(),
+            None => self.client.current_branch().await?,
+        };
+        let base = match args.get("base").and_then(Value::as_str) {
+            Some(base) => base.trim().to_string(),
+            None => self
+                .client
+                .get(&format!("/repos/{repo}"))
+                .await?
+                .get("default_branch")
+                .and_then(Value::as_str)
+                .ok_or_else(|| anyhow::anyhow!("Cannot determine default branch of {repo}"))?
+                .to_string(),
+        };
+        if head == base {
+            anyhow::bail!("Head branch '{head}' is the same as base; push a feature branch first");
+        }
+        let pr = self
+            .client
+            .post(
+                &format!("/repos/{repo}/pulls"),
+                &json!({
+                    "title": title,
+                    "head": head,
+                    "base": base,
+                    "body": args.get("body").and_then(Value::as_str).unwrap_or_default(),
+                    "draft": args.get("draft").and_then(Value::as_bool).unwrap_or(false),
+                }),
+            )
+            .await?;
+        Ok(json!({
+            "number": pr["number"],
+            "head": head,
+            "base": base,
+            "url": pr["html_url"],
+        }))
+    }
+}
+
+#[async_trait]
+impl Tool for GitHubWriteTool {
+    fn name(&self) -> &str {
+        "github_write"
+    }
+
+    fn description(&self) -> &str {
+        "Make changes on GitHub (requires approval). Actions: comment (on an issue or PR), review (COMMENT, APPROVE or REQUEST_CHANGES on a PR), create_pull_request (defaults to the current branch into the repository's default branch)."
+    }
+
+    fn requires_approval(&self) -> bool {
+        true
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["comment", "review", "create_pull_request"],
+                    "description": "Operation to perform"
+                },
+                "repo": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to the configured repo or the workspace origin remote)"
+                },
+                "number": {
+                    "type": "integer",
+                    "description": "Issue or pull request number (comment, review)"
+                },
+                "body": {
+                    "type": "string",
+                    "description": "Markdown body of the comment, review or pull request"
+                },
+                "event": {
+                    "type": "string",
+                    "enum": ["COMMENT", "APPROVE", "REQUEST_CHANGES"],
+                    "description": "Review verdict (review, default COMMENT)"
+                },
+                "title": {
+                    "type": "string",
+                    "description": "Pull request title (create_pull_request)"
+                },
+                "head": {
+                    "type": "string",
+                    "description": "Branch to merge from (create_pull_request, default: current branch)"
+                },
+                "base": {
+                    "type": "string",
+                    "description": "Branch to merge into (create_pull_request, default: repository default branch)"
+                },
+                "draft": {
+                    "type": "boolean",
+                    "description": "Open the pull request as a draft (create_pull_request)"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        let action = required_str(&args, "action", "github_write")?;
+        if !matches!(action, "comment" | "review" | "create_pull_request") {
+            return Ok(tool_error(format!("Unknown action '{action}'")));
+        }
+
+        if let Err(error) = self
+            .security
+            .enforce_tool_operation(ToolOperation::Act, &format!("github.{action}"))
+        {
+            return Ok(tool_error(error));
+        }
+
+        let result = match action {
+            "comment" => self.comment(&args).await,
+            "review" => self.review(&args).await,
+            _ => self.create_pull_request(&args).await,
+        };
+        match result {
+            Ok(value) => tool_output(&value),
+            Err(e) => Ok(tool_error(format!("GitHub {action} failed: {e}"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+    use wiremock::matchers::{body_json, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn client(api_url: &str, workspace_dir: PathBuf) -> Arc<GitHubClient> {
+        Arc::new(
+            GitHubClient::from_config(
+                &GitHubConfig {
+                    enabled: true,
+                    api_url: api_url.to_string(),
+                    token: Some("gh-token".into()),
+                    repo: Some("acme/widgets".into()),
+                    timeout_secs: 5,
+                },
+                workspace_dir,
+            )
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn client_requires_token() {
+        let config = GitHubConfig::default();
+        assert!(GitHubClient::from_config(&config, PathBuf::from(".")).is_none());
+    }
+
+    #[test]
+    fn remote_urls_resolve_to_repo() {
+        for remote in [
+            "https://github.com/acme/widgets.git",
+            "https://github.com/acme/widgets",
+            "git@github.com:acme/widgets.git",
+            "ssh://git@github.example.com:2222/acme/widgets.git",
+            "https://ghe.example.com/acme/widgets/",
+        ] {
+            assert_eq!(
+                parse_remote_repo(remote).as_deref(),
+                Some("acme/widgets"),
+                "{remote}"
+            );
+        }
+        assert!(parse_remote_repo("/srv/git/widgets").is_none());
+        assert!(parse_repo_name("acme").is_none());
+        assert!(parse_repo_name("acme/wid gets").is_none());
+    }
+
+    #[tokio::test]
+    async fn search_scopes_query_to_repo() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/search/issues"))
+            .and(query_param("q", "is:open label:bug repo:acme/widgets"))
+            .and(header("authorization", "Bearer gh-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "total_count": 1,
+                "items": [{
+                    "number": 7,
+                    "title": "Crash on start",
+                    "state": "open",
+                    "user": {"login": "octocat"},
+                    "comments": 2,
+                    "html_url": "https://github.com/acme/widgets/issues/7"
+                }]
+            })))
+            .mount(&server)
+            .await;
+        let tool = GitHubTool::new(client(&server.uri(), PathBuf::from(".")));
+
+        let result = tool
+            .execute(json!({"action": "search", "query": "is:open label:bug"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let output: Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(output["items"][0]["number"], 7);
+        assert_eq!(output["items"][0]["author"], "octocat");
+        assert_eq!(output["items"][0]["is_pull_request"], false);
+    }
+
+    #[tokio::test]
+    async fn check_status_uses_pull_request_head() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/acme/widgets/pulls/3"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"head": {"sha": "abc123"}})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/acme/widgets/commits/abc123/check-runs"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "check_runs": [{"name": "test", "status": "completed", "conclusion": "failure"}]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/acme/widgets/commits/abc123/status"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({"state": "failure", "statuses": []})),
+            )
+            .mount(&server)
+            .await;
+        let tool = GitHubTool::new(client(&server.uri(), PathBuf::from(".")));
+
+        let result = tool
+            .execute(json!({"action": "check_status", "number": 3}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let output: Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(output["ref"], "abc123");
+        assert_eq!(output["combined_state"], "failure");
+        assert_eq!(output["check_runs"][0]["conclusion"], "failure");
+    }
+
+    #[tokio::test]
+    async fn api_errors_are_reported() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/acme/widgets/issues/404"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(json!({"message": "Not Found"})))
+            .mount(&server)
+            .await;
+        let tool = GitHubTool::new(client(&server.uri(), PathBuf::from(".")));
+
+        let result = tool
+            .execute(json!({"action": "get_issue", "number": 404}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("Not Found"));
+    }
+
+    #[tokio::test]
+    async fn create_pull_request_defaults_to_current_branch() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        for args in [
+            vec!["init", "-q"],
+            vec!["checkout", "-q", "-b", "feature/login"],
+        ] {
+            std::process::Command::new("git")
+                .args(&args)
+                .current_dir(tmp.path())
+                .output()
+                .unwrap();
+        }
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/acme/widgets"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"default_branch": "main"})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/repos/acme/widgets/pulls"))
+            .and(body_json(json!({
+                "title": "Add login",
+                "head": "feature/login",
+                "base": "main",
+                "body": "",
+                "draft": true
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                "number": 12,
+                "html_url": "https://github.com/acme/widgets/pull/12"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let tool = GitHubWriteTool::new(
+            client(&server.uri(), tmp.path().to_path_buf()),
+            Arc::new(SecurityPolicy::default()),
+        );
+
+        let result = tool
+            .execute(json!({"action": "create_pull_request", "title": "Add login", "draft": true}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let output: Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(output["number"], 12);
+        assert_eq!(output["head"], "feature/login");
+    }
+
+    #[tokio::test]
+    async fn write_actions_blocked_in_readonly_mode() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({})))
+            .expect(0)
+            .mount(&server)
+            .await;
+        let readonly = SecurityPolicy {
+            autonomy: AutonomyLevel::ReadOnly,
+            ..SecurityPolicy::default()
+        };
+        let tool = GitHubWriteTool::new(
+            client(&server.uri(), PathBuf::from(".")),
+            Arc::new(readonly),
+        );
+
+        let result = tool
+            .execute(json!({"action": "comment", "number": 1, "body": "LGTM"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("read-only"));
+    }
+
+    #[tokio::test]
+    async fn review_rejects_unknown_event() {
+        let server = MockServer::start().await;
+        let tool = GitHubWriteTool::new(
+            client(&server.uri(), PathBuf::from(".")),
+            Arc::new(SecurityPolicy::default()),
+        );
+
+        let result = tool
+            .execute(json!({"action": "review", "number": 1, "event": "MERGE", "body": "x"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("REQUEST_CHANGES"));
+    }
+}
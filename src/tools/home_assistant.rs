@@ -0,0 +1,1009 @@
+use super::traits::{Tool, ToolResult};
+use crate::config::{HomeAssistantConfig, HomeAssistantTrigger};
+use crate::security::policy::ToolOperation;
+use crate::security::SecurityPolicy;
+use anyhow::Context;
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+
+/// Entities listed per `list_entities` call before the output is truncated.
+const MAX_LISTED_ENTITIES: usize = 200;
+
+/// Service-call keys that select targets rather than service fields.
+const TARGET_KEYS: &[&str] = &["entity_id", "device_id", "area_id", "floor_id", "label_id"];
+
+/// Renders every area with its name and entity count as JSON.
+const AREAS_TEMPLATE: &str = "{% set ns = namespace(items=[]) %}\
+{% for a in areas() %}\
+{% set ns.items = ns.items + [{'id': a, 'name': area_name(a), 'entities': area_entities(a) | length}] %}\
+{% endfor %}{{ ns.items | tojson }}";
+
+/// A `state_changed` event from the Home Assistant WebSocket API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateChange {
+    pub entity_id: String,
+    pub old_state: Option<String>,
+    pub new_state: Option<String>,
+}
+
+impl StateChange {
+    fn from_event(event: &Value) -> Option<Self> {
+        let data = event.get("data")?;
+        let state_of = |key: &str| {
+            data.get(key)
+                .and_then(|state| state.get("state"))
+                .and_then(Value::as_str)
+                .map(ToString::to_string)
+        };
+        Some(Self {
+            entity_id: data.get("entity_id")?.as_str()?.to_string(),
+            old_state: state_of("old_state"),
+            new_state: state_of("new_state"),
+        })
+    }
+}
+
+/// Whether `change` should fire `trigger`. Attribute-only updates (same
+/// state before and after) never fire.
+pub fn trigger_matches(trigger: &HomeAssistantTrigger, change: &StateChange) -> bool {
+    if change.old_state == change.new_state {
+        return false;
+    }
+    let state_matches = |expected: &Option<String>, actual: &Option<String>| {
+        expected
+            .as_deref()
+            .is_none_or(|expected| actual.as_deref() == Some(expected))
+    };
+    entity_pattern_matches(&trigger.entity_id, &change.entity_id)
+        && state_matches(&trigger.from, &change.old_state)
+        && state_matches(&trigger.to, &change.new_state)
+}
+
+/// Match an entity ID against a pattern where `*` matches any run of characters.
+fn entity_pattern_matches(pattern: &str, entity_id: &str) -> bool {
+    let pattern = pattern.trim();
+    let mut pieces = pattern.split('*');
+    let first = pieces.next().unwrap_or_default();
+    let Some(mut rest) = entity_id.strip_prefix(first) else {
+        return false;
+    };
+    let pieces: Vec<&str> = pieces.collect();
+    let Some((last, middle)) = pieces.split_last() else {
+        return rest.is_empty();
+    };
+    for piece in middle {
+        match rest.find(piece) {
+            Some(idx) => rest = &rest[idx + piece.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// REST and WebSocket client for one Home Assistant instance.
+pub struct HomeAssistantClient {
+    base_url: String,
+    token: String,
+    timeout_secs: u64,
+}
+
+impl HomeAssistantClient {
+    /// Client for the configured instance, or `None` without an access token.
+    pub fn from_config(config: &HomeAssistantConfig) -> Option<Self> {
+        let token = config
+            .token
+            .as_deref()
+            .map(str::trim)
+            .filter(|token| !token.is_empty())?;
+        Some(Self {
+            base_url: config.url.trim().trim_end_matches('/').to_string(),
+            token: token.to_string(),
+            timeout_secs: config.timeout_secs.max(1),
+        })
+    }
+
+    fn http_client(&self) -> reqwest::Client {
+        crate::config::build_runtime_proxy_client_with_timeouts(
+            "tool.home_assistant",
+            self.timeout_secs,
+            10,
+        )
+    }
+
+    async fn get(&self, path: &str) -> anyhow::Result<Value> {
+        let response = self
+            .http_client()
+            .get(format!("{}{path}", self.base_url))
+            .bearer_auth(&self.token)
+            .send()
+            .await?;
+        Self::json_body(response).await
+    }
+
+    async fn post(&self, path: &str, body: &Value) -> anyhow::Result<reqwest::Response> {
+        Ok(self
+            .http_client()
+            .post(format!("{}{path}", self.base_url))
+            .bearer_auth(&self.token)
+            .json(body)
+            .send()
+            .await?)
+    }
+
+    async fn json_body(response: reqwest::Response) -> anyhow::Result<Value> {
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!(
+                "Home Assistant API error {status}: {}",
+                crate::util::truncate_with_ellipsis(&body, 300)
+            );
+        }
+        Ok(response.json().await?)
+    }
+
+    /// Render a Jinja template server-side (`POST /api/template`).
+    async fn render_template(&self, template: &str) -> anyhow::Result<String> {
+        let response = self
+            .post("/api/template", &json!({ "template": template }))
+            .await?;
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            anyhow::bail!(
+                "Home Assistant template error {status}: {}",
+                crate::util::truncate_with_ellipsis(&body, 300)
+            );
+        }
+        Ok(body)
+    }
+
+    pub async fn states(&self) -> anyhow::Result<Vec<Value>> {
+        Ok(serde_json::from_value(self.get("/api/states").await?)?)
+    }
+
+    pub async fn state(&self, entity_id: &str) -> anyhow::Result<Value> {
+        self.get(&format!("/api/states/{}", urlencoding::encode(entity_id)))
+            .await
+    }
+
+    /// The service registry: `[{domain, services: {name: {fields, target, ...}}}]`.
+    pub async fn services(&self) -> anyhow::Result<Vec<Value>> {
+        Ok(serde_json::from_value(self.get("/api/services").await?)?)
+    }
+
+    pub async fn areas(&self) -> anyhow::Result<Value> {
+        let rendered = self.render_template(AREAS_TEMPLATE).await?;
+        serde_json::from_str(&rendered).context("unexpected area template output")
+    }
+
+    /// Entity IDs in an area, looked up by area ID or name.
+    pub async fn area_entities(&self, area: &str) -> anyhow::Result<Vec<String>> {
+        let template = format!("{{{{ area_entities({}) | tojson }}}}", json!(area));
+        let rendered = self.render_template(&template).await?;
+        serde_json::from_str(&rendered).context("unexpected area template output")
+    }
+
+    async fn area_of(&self, entity_id: &str) -> anyhow::Result<Option<String>> {
+        let template = format!("{{{{ area_name({}) | tojson }}}}", json!(entity_id));
+        let rendered = self.render_template(&template).await?;
+        Ok(serde_json::from_str(&rendered).unwrap_or(None))
+    }
+
+    pub async fn call_service(
+        &self,
+        domain: &str,
+        service: &str,
+        data: &Value,
+    ) -> anyhow::Result<Value> {
+        let path = format!(
+            "/api/services/{}/{}",
+            urlencoding::encode(domain),
+            urlencoding::encode(service)
+        );
+        Self::json_body(self.post(&path, data).await?).await
+    }
+
+    fn websocket_url(&self) -> String {
+        let base = if let Some(rest) = self.base_url.strip_prefix("https://") {
+            format!("wss://{rest}")
+        } else if let Some(rest) = self.base_url.strip_prefix("http://") {
+            format!("ws://{rest}")
+        } else {
+            self.base_url.clone()
+        };
+        format!("{base}/api/websocket")
+    }
+
+    /// Subscribe to `state_changed` events and forward them to `tx`.
+    ///
+    /// Runs until the connection drops or `tx` is closed.
+    pub async fn subscribe_state_changes(
+        &self,
+        tx: mpsc::Sender<StateChange>,
+    ) -> anyhow::Result<()> {
+        let (ws, _) = tokio_tungstenite::connect_async(self.websocket_url()).await?;
+        let (mut sink, mut stream) = ws.split();
+
+        loop {
+            let Some(frame) = stream.next().await else {
+                anyhow::bail!("Home Assistant WebSocket closed");
+            };
+            let Message::Text(text) = frame? else {
+                continue;
+            };
+            let msg: Value = serde_json::from_str(&text)?;
+            match msg["type"].as_str() {
+                Some("auth_required") => {
+                    let auth = json!({"type": "auth", "access_token": self.token});
+                    sink.send(Message::Text(auth.to_string().into())).await?;
+                }
+                Some("auth_ok") => {
+                    let subscribe = json!({
+                        "id": 1,
+                        "type": "subscribe_events",
+                        "event_type": "state_changed",
+                    });
+                    sink.send(Message::Text(subscribe.to_string().into()))
+                        .await?;
+                    tracing::info!("Home Assistant: subscribed to state changes");
+                }
+                Some("auth_invalid") => {
+                    anyhow::bail!(
+                        "Home Assistant rejected the access token: {}",
+                        msg["message"].as_str().unwrap_or("auth_invalid")
+                    );
+                }
+                Some("result") if msg["success"] == false => {
+                    anyhow::bail!("Home Assistant subscription failed: {}", msg["error"]);
+                }
+                Some("event") => {
+                    if let Some(change) = StateChange::from_event(&msg["event"]) {
+                        if tx.send(change).await.is_err() {
+                            return Ok(());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Fields a service accepts, flattening collapsible field sections.
+fn service_fields(service: &Value) -> serde_json::Map<String, Value> {
+    let mut fields = serde_json::Map::new();
+    if let Some(declared) = service.get("fields").and_then(Value::as_object) {
+        for (name, field) in declared {
+            match field.get("fields").and_then(Value::as_object) {
+                Some(section) => fields.extend(section.clone()),
+                None => {
+                    fields.insert(name.clone(), field.clone());
+                }
+            }
+        }
+    }
+    fields
+}
+
+/// Validate a service call against the Home Assistant service registry.
+fn validate_service_call(
+    registry: &[Value],
+    domain: &str,
+    service: &str,
+    data: &serde_json::Map<String, Value>,
+) -> Result<(), String> {
+    let Some(services) = registry
+        .iter()
+        .find(|entry| entry["domain"] == domain)
+        .and_then(|entry| entry["services"].as_object())
+    else {
+        return Err(format!("Unknown service domain '{domain}'"));
+    };
+    let Some(definition) = services.get(service) else {
+        let mut available: Vec<&str> = services.keys().map(String::as_str).collect();
+        available.sort_unstable();
+        return Err(format!(
+            "Unknown service '{domain}.{service}'. Available: {}",
+            available.join(", ")
+        ));
+    };
+
+    let fields = service_fields(definition);
+    let accepts_target = definition.get("target").is_some();
+    for (key, value) in data {
+        if accepts_target && TARGET_KEYS.contains(&key.as_str()) {
+            continue;
+        }
+        let Some(field) = fields.get(key) else {
+            let mut valid: Vec<&str> = fields.keys().map(String::as_str).collect();
+            if accepts_target {
+                valid.extend(TARGET_KEYS);
+            }
+            valid.sort_unstable();
+            return Err(format!(
+                "'{key}' is not a field of {domain}.{service}. Valid fields: {}",
+                valid.join(", ")
+            ));
+        };
+
+        let options: Vec<&str> = field
+            .pointer("/selector/select/options")
+            .and_then(Value::as_array)
+            .map(|options| {
+                options
+                    .iter()
+                    .filter_map(|option| option.as_str().or_else(|| option["value"].as_str()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if let Some(value) = value.as_str() {
+            if !options.is_empty() && !options.contains(&value) {
+                return Err(format!(
+                    "'{value}' is not a valid {key} for {domain}.{service}. Options: {}",
+                    options.join(", ")
+                ));
+            }
+        }
+    }
+
+    let missing: Vec<&str> = fields
+        .iter()
+        .filter(|(name, field)| field["required"] == true && !data.contains_key(name.as_str()))
+        .map(|(name, _)| name.as_str())
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!(
+            "{domain}.{service} requires: {}",
+            missing.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Compact `{entity_id, state, name}` summary of a state object.
+fn entity_summary(state: &Value) -> Value {
+    json!({
+        "entity_id": state["entity_id"],
+        "state": state["state"],
+        "name": state.pointer("/attributes/friendly_name"),
+    })
+}
+
+/// Home Assistant tool: discover areas and entities, read states and call
+/// services validated against the instance's service registry.
+pub struct HomeAssistantTool {
+    client: HomeAssistantClient,
+    security: Arc<SecurityPolicy>,
+}
+
+impl HomeAssistantTool {
+    pub fn new(client: HomeAssistantClient, security: Arc<SecurityPolicy>) -> Self {
+        Self { client, security }
+    }
+
+    fn required_str<'a>(args: &'a Value, key: &str, action: &str) -> anyhow::Result<&'a str> {
+        args.get(key)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Missing '{key}' for {action}"))
+    }
+
+    async fn list_entities(&self, args: &Value) -> anyhow::Result<Value> {
+        let domain = args.get("domain").and_then(Value::as_str);
+        let area_entities = match args.get("area").and_then(Value::as_str) {
+            Some(area) => Some(self.client.area_entities(area).await?),
+            None => None,
+        };
+
+        let matching: Vec<Value> = self
+            .client
+            .states()
+            .await?
+            .iter()
+            .filter(|state| {
+                let entity_id = state["entity_id"].as_str().unwrap_or_default();
+                domain.is_none_or(|domain| {
+                    entity_id
+                        .strip_prefix(domain)
+                        .is_some_and(|rest| rest.starts_with('.'))
+                }) && area_entities
+                    .as_ref()
+                    .is_none_or(|ids| ids.iter().any(|id| id == entity_id))
+            })
+            .map(entity_summary)
+            .collect();
+
+        let total = matching.len();
+        let entities: Vec<Value> = matching.into_iter().take(MAX_LISTED_ENTITIES).collect();
+        Ok(json!({
+            "total": total,
+            "truncated": total > MAX_LISTED_ENTITIES,
+            "entities": entities,
+        }))
+    }
+
+    async fn describe_entity(&self, entity_id: &str) -> anyhow::Result<Value> {
+        let state = self.client.state(entity_id).await?;
+        let area = self.client.area_of(entity_id).await.unwrap_or(None);
+        let domain = entity_id.split('.').next().unwrap_or_default();
+        let mut services: Vec<String> = self
+            .client
+            .services()
+            .await?
+            .iter()
+            .find(|entry| entry["domain"] == domain)
+            .and_then(|entry| entry["services"].as_object())
+            .map(|services| services.keys().cloned().collect())
+            .unwrap_or_default();
+        services.sort_unstable();
+        Ok(json!({
+            "entity_id": entity_id,
+            "state": state["state"],
+            "attributes": state["attributes"],
+            "last_changed": state["last_changed"],
+            "area": area,
+            "services": services,
+        }))
+    }
+
+    async fn list_services(&self, domain: Option<&str>) -> anyhow::Result<Value> {
+        let registry = self.client.services().await?;
+        let Some(domain) = domain else {
+            let mut domains: Vec<&str> = registry
+                .iter()
+                .filter_map(|entry| entry["domain"].as_str())
+                .collect();
+            domains.sort_unstable();
+            return Ok(json!({ "domains": domains }));
+        };
+
+        let services = registry
+            .iter()
+            .find(|entry| entry["domain"] == domain)
+            .and_then(|entry| entry["services"].as_object())
+            .ok_or_else(|| anyhow::anyhow!("Unknown service domain '{domain}'"))?;
+        let described: serde_json::Map<String, Value> = services
+            .iter()
+            .map(|(name, service)| {
+                let fields: serde_json::Map<String, Value> = service_fields(service)
+                    .into_iter()
+                    .map(|(field, spec)| {
+                        (
+                            field,
+                            json!({
+                                "required": spec["required"] == true,
+                                "description": spec["description"],
+                                "example": spec["example"],
+                                "selector": spec["selector"],
+                            }),
+                        )
+                    })
+                    .collect();
+                (
+                    name.clone(),
+                    json!({
+                        "description": service["description"],
+                        "target": service.get("target").is_some(),
+                        "fields": fields,
+                    }),
+                )
+            })
+            .collect();
+        Ok(json!({ "domain": domain, "services": described }))
+    }
+
+    async fn call_service(&self, args: &Value) -> anyhow::Result<ToolResult> {
+        let domain = Self::required_str(args, "domain", "call_service")?;
+        let service = Self::required_str(args, "service", "call_service")?;
+        let mut data = match args.get("data") {
+            Some(Value::Object(data)) => data.clone(),
+            Some(Value::Null) | None => serde_json::Map::new(),
+            Some(_) => anyhow::bail!("'data' must be an object"),
+        };
+        if let Some(entity_id) = args.get("entity_id").filter(|v| !v.is_null()) {
+            data.insert("entity_id".into(), entity_id.clone());
+        }
+
+        let registry = self.client.services().await?;
+        if let Err(error) = validate_service_call(&registry, domain, service, &data) {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(error),
+            });
+        }
+
+        if let Err(error) = self
+            .security
+            .enforce_tool_operation(ToolOperation::Act, "home_assistant.call_service")
+        {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(error),
+            });
+        }
+
+        let changed = self
+            .client
+            .call_service(domain, service, &Value::Object(data))
+            .await?;
+        let changed: Vec<Value> = changed
+            .as_array()
+            .map(|states| states.iter().map(entity_summary).collect())
+            .unwrap_or_default();
+        Ok(ToolResult {
+            success: true,
+            output: serde_json::to_string_pretty(&json!({
+                "called": format!("{domain}.{service}"),
+                "changed_states": changed,
+            }))?,
+            error: None,
+        })
+    }
+}
+
+#[async_trait]
+impl Tool for HomeAssistantTool {
+    fn name(&self) -> &str {
+        "home_assistant"
+    }
+
+    fn description(&self) -> &str {
+        "Control and inspect Home Assistant. Actions: list_areas, list_entities (filter by domain/area), describe_entity, get_state, list_services (service fields per domain), call_service (validated against the service registry; changes devices)."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["list_areas", "list_entities", "describe_entity", "get_state", "list_services", "call_service"],
+                    "description": "Operation to perform"
+                },
+                "domain": {
+                    "type": "string",
+                    "description": "Entity/service domain, e.g. 'light' (list_entities, list_services, call_service)"
+                },
+                "area": {
+                    "type": "string",
+                    "description": "Area ID or name to filter list_entities"
+                },
+                "entity_id": {
+                    "type": "string",
+                    "description": "Entity ID, e.g. 'light.kitchen' (describe_entity, get_state, optional target for call_service)"
+                },
+                "service": {
+                    "type": "string",
+                    "description": "Service name within the domain, e.g. 'turn_on' (call_service)"
+                },
+                "data": {
+                    "type": "object",
+                    "description": "Service data fields, e.g. {\"brightness_pct\": 50} (call_service)"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        let action = Self::required_str(&args, "action", "home_assistant")?;
+
+        let result = match action {
+            "list_areas" => self.client.areas().await,
+            "list_entities" => self.list_entities(&args).await,
+            "describe_entity" => {
+                let entity_id = Self::required_str(&args, "entity_id", action)?;
+                self.describe_entity(entity_id).await
+            }
+            "get_state" => {
+                let entity_id = Self::required_str(&args, "entity_id", action)?;
+                self.client.state(entity_id).await.map(|state| {
+                    json!({
+                        "entity_id": entity_id,
+                        "state": state["state"],
+                        "last_changed": state["last_changed"],
+                        "unit": state.pointer("/attributes/unit_of_measurement"),
+                    })
+                })
+            }
+            "list_services" => {
+                self.list_services(args.get("domain").and_then(Value::as_str))
+                    .await
+            }
+            "call_service" => return self.call_service(&args).await,
+            other => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Unknown action '{other}'")),
+                });
+            }
+        };
+
+        match result {
+            Ok(value) => Ok(ToolResult {
+                success: true,
+                output: serde_json::to_string_pretty(&value)?,
+                error: None,
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Home Assistant {action} failed: {e}")),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+    use wiremock::matchers::{body_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn client(url: &str) -> HomeAssistantClient {
+        HomeAssistantClient::from_config(&HomeAssistantConfig {
+            enabled: true,
+            url: url.to_string(),
+            token: Some("ha-token".into()),
+            ..HomeAssistantConfig::default()
+        })
+        .unwrap()
+    }
+
+    fn tool(url: &str, security: SecurityPolicy) -> HomeAssistantTool {
+        HomeAssistantTool::new(client(url), Arc::new(security))
+    }
+
+    fn service_registry() -> Value {
+        json!([
+            {
+                "domain": "light",
+                "services": {
+                    "turn_on": {
+                        "description": "Turn on lights",
+                        "target": {"entity": [{"domain": ["light"]}]},
+                        "fields": {
+                            "brightness_pct": {"selector": {"number": {"min": 0, "max": 100}}},
+                            "advanced_fields": {
+                                "collapsed": true,
+                                "fields": {
+                                    "effect": {"selector": {"select": {"options": ["colorloop", "random"]}}}
+                                }
+                            }
+                        }
+                    },
+                    "turn_off": {"target": {"entity": [{"domain": ["light"]}]}, "fields": {}}
+                }
+            },
+            {
+                "domain": "notify",
+                "services": {
+                    "persistent_notification": {
+                        "fields": {
+                            "message": {"required": true, "selector": {"text": {}}},
+                            "title": {"selector": {"text": {}}}
+                        }
+                    }
+                }
+            }
+        ])
+    }
+
+    fn trigger(entity_id: &str, from: Option<&str>, to: Option<&str>) -> HomeAssistantTrigger {
+        HomeAssistantTrigger {
+            entity_id: entity_id.into(),
+            from: from.map(Into::into),
+            to: to.map(Into::into),
+            prompt: Some("react".into()),
+            cron_job: None,
+            cooldown_secs: 60,
+        }
+    }
+
+    fn change(entity_id: &str, old: &str, new: &str) -> StateChange {
+        StateChange {
+            entity_id: entity_id.into(),
+            old_state: Some(old.into()),
+            new_state: Some(new.into()),
+        }
+    }
+
+    #[test]
+    fn client_requires_token() {
+        assert!(HomeAssistantClient::from_config(&HomeAssistantConfig::default()).is_none());
+        let client = client("https://ha.example.com/");
+        assert_eq!(client.websocket_url(), "wss://ha.example.com/api/websocket");
+    }
+
+    #[test]
+    fn triggers_match_entity_patterns_and_states() {
+        let door = change("binary_sensor.front_door", "off", "on");
+        assert!(trigger_matches(
+            &trigger("binary_sensor.front_door", None, None),
+            &door
+        ));
+        assert!(trigger_matches(
+            &trigger("binary_sensor.*_door", Some("off"), Some("on")),
+            &door
+        ));
+        assert!(trigger_matches(&trigger("*", None, None), &door));
+        assert!(!trigger_matches(
+            &trigger("binary_sensor.*_window", None, None),
+            &door
+        ));
+        assert!(!trigger_matches(
+            &trigger("binary_sensor.front_door", None, Some("off")),
+            &door
+        ));
+        assert!(!trigger_matches(
+            &trigger("binary_sensor.front", None, None),
+            &door
+        ));
+
+        let attribute_update = change("binary_sensor.front_door", "on", "on");
+        assert!(!trigger_matches(
+            &trigger("binary_sensor.front_door", None, None),
+            &attribute_update
+        ));
+    }
+
+    #[test]
+    fn service_calls_are_validated_against_registry() {
+        let registry: Vec<Value> = serde_json::from_value(service_registry()).unwrap();
+        let data = |value: Value| value.as_object().unwrap().clone();
+
+        assert!(validate_service_call(
+            &registry,
+            "light",
+            "turn_on",
+            &data(json!({"entity_id": "light.kitchen", "brightness_pct": 40, "effect": "random"}))
+        )
+        .is_ok());
+
+        let unknown_domain = validate_service_call(&registry, "vacuum", "start", &data(json!({})));
+        assert!(unknown_domain
+            .unwrap_err()
+            .contains("Unknown service domain"));
+
+        let unknown_service =
+            validate_service_call(&registry, "light", "explode", &data(json!({})));
+        assert!(unknown_service.unwrap_err().contains("turn_off, turn_on"));
+
+        let bad_field = validate_service_call(
+            &registry,
+            "light",
+            "turn_on",
+            &data(json!({"brightness": 40})),
+        );
+        assert!(bad_field
+            .unwrap_err()
+            .contains("'brightness' is not a field"));
+
+        let bad_option = validate_service_call(
+            &registry,
+            "light",
+            "turn_on",
+            &data(json!({"effect": "strobe"})),
+        );
+        assert!(bad_option.unwrap_err().contains("colorloop, random"));
+
+        let missing = validate_service_call(
+            &registry,
+            "notify",
+            "persistent_notification",
+            &data(json!({"title": "hi"})),
+        );
+        assert!(missing.unwrap_err().contains("requires: message"));
+    }
+
+    #[tokio::test]
+    async fn list_entities_filters_by_domain_and_area() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/states"))
+            .and(header("authorization", "Bearer ha-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"entity_id": "light.kitchen", "state": "on", "attributes": {"friendly_name": "Kitchen"}},
+                {"entity_id": "light.hall", "state": "off", "attributes": {}},
+                {"entity_id": "lightning.sensor", "state": "0", "attributes": {}},
+                {"entity_id": "switch.kettle", "state": "off", "attributes": {}}
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/template"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string(r#"["light.kitchen","switch.kettle"]"#),
+            )
+            .mount(&server)
+            .await;
+        let tool = tool(&server.uri(), SecurityPolicy::default());
+
+        let result = tool
+            .execute(json!({"action": "list_entities", "domain": "light"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let output: Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(output["total"], 2);
+        assert_eq!(output["entities"][0]["name"], "Kitchen");
+
+        let result = tool
+            .execute(json!({"action": "list_entities", "domain": "light", "area": "kitchen"}))
+            .await
+            .unwrap();
+        let output: Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(output["total"], 1);
+        assert_eq!(output["entities"][0]["entity_id"], "light.kitchen");
+    }
+
+    #[tokio::test]
+    async fn call_service_posts_validated_data() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/services"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(service_registry()))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/services/light/turn_on"))
+            .and(body_json(
+                json!({"entity_id": "light.kitchen", "brightness_pct": 30}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"entity_id": "light.kitchen", "state": "on", "attributes": {}}
+            ])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let tool = tool(&server.uri(), SecurityPolicy::default());
+
+        let result = tool
+            .execute(json!({
+                "action": "call_service",
+                "domain": "light",
+                "service": "turn_on",
+                "entity_id": "light.kitchen",
+                "data": {"brightness_pct": 30}
+            }))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.contains("light.turn_on"));
+
+        let invalid = tool
+            .execute(json!({
+                "action": "call_service",
+                "domain": "light",
+                "service": "turn_on",
+                "data": {"colour": "red"}
+            }))
+            .await
+            .unwrap();
+        assert!(!invalid.success);
+    }
+
+    #[tokio::test]
+    async fn call_service_blocked_in_readonly_mode() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/services"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(service_registry()))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/services/light/turn_off"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .expect(0)
+            .mount(&server)
+            .await;
+        let readonly = SecurityPolicy {
+            autonomy: AutonomyLevel::ReadOnly,
+            ..SecurityPolicy::default()
+        };
+        let tool = tool(&server.uri(), readonly);
+
+        let result = tool
+            .execute(json!({
+                "action": "call_service",
+                "domain": "light",
+                "service": "turn_off",
+                "entity_id": "light.kitchen"
+            }))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("read-only"));
+    }
+
+    #[tokio::test]
+    async fn get_state_reads_entity() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/states/sensor.outside_temperature"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "entity_id": "sensor.outside_temperature",
+                "state": "12.5",
+                "last_changed": "2026-01-01T00:00:00+00:00",
+                "attributes": {"unit_of_measurement": "°C"}
+            })))
+            .mount(&server)
+            .await;
+        let readonly = SecurityPolicy {
+            autonomy: AutonomyLevel::ReadOnly,
+            ..SecurityPolicy::default()
+        };
+        let tool = tool(&server.uri(), readonly);
+
+        let result = tool
+            .execute(json!({"action": "get_state", "entity_id": "sensor.outside_temperature"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let output: Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(output["state"], "12.5");
+        assert_eq!(output["unit"], "°C");
+    }
+
+    #[tokio::test]
+    async fn subscription_authenticates_and_forwards_state_changes() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            ws.send(Message::Text(
+                json!({"type": "auth_required"}).to_string().into(),
+            ))
+            .await
+            .unwrap();
+            let auth: Value =
+                serde_json::from_str(ws.next().await.unwrap().unwrap().to_text().unwrap()).unwrap();
+            assert_eq!(auth["access_token"], "ha-token");
+            ws.send(Message::Text(json!({"type": "auth_ok"}).to_string().into()))
+                .await
+                .unwrap();
+            let subscribe: Value =
+                serde_json::from_str(ws.next().await.unwrap().unwrap().to_text().unwrap()).unwrap();
+            assert_eq!(subscribe["event_type"], "state_changed");
+            let event = json!({
+                "id": subscribe["id"],
+                "type": "event",
+                "event": {
+                    "event_type": "state_changed",
+                    "data": {
+                        "entity_id": "binary_sensor.front_door",
+                        "old_state": {"state": "off"},
+                        "new_state": {"state": "on"}
+                    }
+                }
+            });
+            ws.send(Message::Text(event.to_string().into()))
+                .await
+                .unwrap();
+            // Keep the socket open until the client is done.
+            let _ = ws.next().await;
+        });
+
+        let client = client(&format!("http://{addr}"));
+        let (tx, mut rx) = mpsc::channel(4);
+        let subscription = tokio::spawn(async move { client.subscribe_state_changes(tx).await });
+
+        let received = rx.recv().await.unwrap();
+        assert_eq!(received, change("binary_sensor.front_door", "off", "on"));
+
+        drop(rx);
+        subscription.abort();
+        server.abort();
+    }
+}
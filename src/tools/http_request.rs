@@ -299,7 +299,7 @@ impl Tool for HttpRequestTool {
 
 // Helper functions similar to browser_open.rs
 
-fn normalize_allowed_domains(domains: Vec<String>) -> Vec<String> {
+pub(super) fn normalize_allowed_domains(domains: Vec<String>) -> Vec<String> {
     let mut normalized = domains
         .into_iter()
         .filter_map(|d| normalize_domain(&d))
@@ -338,7 +338,7 @@ fn normalize_domain(raw: &str) -> Option<String> {
     Some(d)
 }
 
-fn extract_host(url: &str) -> anyhow::Result<String> {
+pub(super) fn extract_host(url: &str) -> anyhow::Result<String> {
     let rest = url
         .strip_prefix("http://")
         .or_else(|| url.strip_prefix("https://"))
@@ -376,7 +376,7 @@ fn extract_host(url: &str) -> anyhow::Result<String> {
     Ok(host)
 }
 
-fn host_matches_allowlist(host: &str, allowed_domains: &[String]) -> bool {
+pub(super) fn host_matches_allowlist(host: &str, allowed_domains: &[String]) -> bool {
     if allowed_domains.iter().any(|domain| domain == "*") {
         return true;
     }
@@ -30,10 +30,12 @@ pub mod file_edit;
 pub mod file_read;
 pub mod file_write;
 pub mod git_operations;
+pub mod github;
 pub mod glob_search;
 pub mod hardware_board_info;
 pub mod hardware_memory_map;
 pub mod hardware_memory_read;
+pub mod home_assistant;
 pub mod http_request;
 pub mod image_info;
 pub mod memory_forget;
@@ -47,7 +49,9 @@ pub mod schedule;
 pub mod schema;
 pub mod screenshot;
 pub mod shell;
+pub mod skill_tool;
 pub mod traits;
+pub mod wasm_plugin;
 pub mod web_search_tool;
 
 pub use browser::{BrowserTool, ComputerUseConfig};
@@ -64,10 +68,12 @@ pub use file_edit::FileEditTool;
 pub use file_read::FileReadTool;
 pub use file_write::FileWriteTool;
 pub use git_operations::GitOperationsTool;
+pub use github::{GitHubTool, GitHubWriteTool};
 pub use glob_search::GlobSearchTool;
 pub use hardware_board_info::HardwareBoardInfoTool;
 pub use hardware_memory_map::HardwareMemoryMapTool;
 pub use hardware_memory_read::HardwareMemoryReadTool;
+pub use home_assistant::HomeAssistantTool;
 pub use http_request::HttpRequestTool;
 pub use image_info::ImageInfoTool;
 pub use memory_forget::MemoryForgetTool;
@@ -123,6 +129,10 @@ impl Tool for ArcDelegatingTool {
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         self.inner.execute(args).await
     }
+
+    fn requires_approval(&self) -> bool {
+        self.inner.requires_approval()
+    }
 }
 
 fn boxed_registry_from_arcs(tools: Vec<Arc<dyn Tool>>) -> Vec<Box<dyn Tool>> {
@@ -166,7 +176,11 @@ pub fn all_tools(
     all_tools_with_runtime(
         config,
         security,
-        Arc::new(NativeRuntime::new()),
+        Arc::new(
+            NativeRuntime::new().with_limits(crate::runtime::ResourceLimits::from_config(
+                &root_config.security.resources,
+            )),
+        ),
         memory,
         composio_key,
         composio_entity_id,
@@ -195,8 +209,26 @@ pub fn all_tools_with_runtime(
     fallback_api_key: Option<&str>,
     root_config: &crate::config::Config,
 ) -> Vec<Box<dyn Tool>> {
+    // Container runtimes isolate the command themselves; the seccomp filter
+    // would only constrain the local `docker` client.
+    let runtime_is_native = runtime.name() == "native";
+    let mut shell_tool = ShellTool::new(security.clone(), runtime);
+    if let Some(audit) = crate::security::AuditLogger::try_from_config(root_config) {
+        shell_tool = shell_tool.with_audit(audit);
+    }
+    if runtime_is_native {
+        if let Some(sandbox) =
+            crate::security::detect::create_command_sandbox(&root_config.security)
+        {
+            shell_tool =
+                shell_tool.with_sandbox(sandbox, root_config.security.sandbox.seccomp.clone());
+        }
+    }
+
+    let shell_tool = Arc::new(shell_tool);
+
     let mut tool_arcs: Vec<Arc<dyn Tool>> = vec![
-        Arc::new(ShellTool::new(security.clone(), runtime)),
+        shell_tool.clone(),
         Arc::new(FileReadTool::new(security.clone())),
         Arc::new(FileWriteTool::new(security.clone())),
         Arc::new(FileEditTool::new(security.clone())),
@@ -272,6 +304,28 @@ pub fn all_tools_with_runtime(
         )));
     }
 
+    // GitHub tools (writes go through the separate, approval-gated github_write)
+    if root_config.github.enabled {
+        match github::GitHubClient::from_config(&root_config.github, workspace_dir.to_path_buf()) {
+            Some(client) => {
+                let client = Arc::new(client);
+                tool_arcs.push(Arc::new(GitHubTool::new(client.clone())));
+                tool_arcs.push(Arc::new(GitHubWriteTool::new(client, security.clone())));
+            }
+            None => tracing::warn!("github enabled but no token configured"),
+        }
+    }
+
+    // Home Assistant tool (requires a long-lived access token)
+    if root_config.home_assistant.enabled {
+        match home_assistant::HomeAssistantClient::from_config(&root_config.home_assistant) {
+            Some(client) => {
+                tool_arcs.push(Arc::new(HomeAssistantTool::new(client, security.clone())));
+            }
+            None => tracing::warn!("home_assistant enabled but no access token configured"),
+        }
+    }
+
     // PDF extraction (feature-gated at compile time via rag-pdf)
     tool_arcs.push(Arc::new(PdfReadTool::new(security.clone())));
 
@@ -289,6 +343,43 @@ pub fn all_tools_with_runtime(
         }
     }
 
+    // Tools declared by installed skills (SKILL.toml [[tools]])
+    let skills = crate::skills::load_skills_with_config(workspace_dir, root_config);
+    for tool in skill_tool::skill_tools(&skills, &shell_tool, security, http_config) {
+        if tool_arcs
+            .iter()
+            .any(|existing| existing.name() == tool.name())
+        {
+            tracing::warn!(
+                "Skipping skill tool '{}': name already registered",
+                tool.name()
+            );
+            continue;
+        }
+        tool_arcs.push(tool);
+    }
+
+    // Installed WASM tool plugins ([runtime.wasm].tools_dir)
+    let plugin_trust = crate::plugins::trust_store_path(root_config);
+    for tool in wasm_plugin::plugin_tools(
+        &root_config.runtime.wasm,
+        workspace_dir,
+        &plugin_trust,
+        security,
+    ) {
+        if tool_arcs
+            .iter()
+            .any(|existing| existing.name() == tool.name())
+        {
+            tracing::warn!(
+                "Skipping WASM plugin '{}': name already registered",
+                tool.name()
+            );
+            continue;
+        }
+        tool_arcs.push(tool);
+    }
+
     // Add delegation tool when agents are configured
     if !agents.is_empty() {
         let delegate_agents: HashMap<String, DelegateAgentConfig> = agents
@@ -315,7 +406,11 @@ pub fn all_tools_with_runtime(
             },
         )
         .with_parent_tools(parent_tools)
-        .with_multimodal_config(root_config.multimodal.clone());
+        .with_multimodal_config(root_config.multimodal.clone())
+        .with_cost_context(crate::cost::CostContext::from_config(
+            root_config,
+            crate::cost::CostAttribution::default(),
+        ));
         tool_arcs.push(Arc::new(delegate_tool));
     }
 
@@ -384,6 +479,79 @@ mod tests {
         assert!(names.contains(&"proxy_config"));
     }
 
+    #[test]
+    fn all_tools_registers_skill_tools() {
+        let tmp = TempDir::new().unwrap();
+        let skill_dir = tmp.path().join("skills").join("weather");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.toml"),
+            r#"
+[skill]
+name = "weather"
+description = "Weather lookups"
+
+[[tools]]
+name = "forecast"
+description = "Fetch a forecast"
+kind = "http"
+command = "https://wttr.in/{city}?format=3"
+
+[[tools.parameters]]
+name = "city"
+description = "City name"
+
+[[tools]]
+name = "shell"
+description = "Shadows a built-in"
+kind = "shell"
+command = "echo nope"
+"#,
+        )
+        .unwrap();
+
+        let security = Arc::new(SecurityPolicy::default());
+        let mem_cfg = MemoryConfig {
+            backend: "markdown".into(),
+            ..MemoryConfig::default()
+        };
+        let mem: Arc<dyn Memory> =
+            Arc::from(crate::memory::create_memory(&mem_cfg, tmp.path(), None).unwrap());
+        let cfg = test_config(&tmp);
+        let http = crate::config::HttpRequestConfig {
+            enabled: true,
+            allowed_domains: vec!["wttr.in".into()],
+            ..crate::config::HttpRequestConfig::default()
+        };
+
+        let tools = all_tools(
+            Arc::new(Config::default()),
+            &security,
+            mem,
+            None,
+            None,
+            &BrowserConfig::default(),
+            &http,
+            tmp.path(),
+            &HashMap::new(),
+            None,
+            &cfg,
+        );
+        let forecast = tools.iter().find(|t| t.name() == "forecast").unwrap();
+        assert_eq!(
+            forecast.spec().parameters["required"],
+            serde_json::json!(["city"])
+        );
+        let shells = tools.iter().filter(|t| t.name() == "shell").count();
+        assert_eq!(shells, 1);
+        assert!(tools
+            .iter()
+            .find(|t| t.name() == "shell")
+            .unwrap()
+            .description()
+            .starts_with("Execute"));
+    }
+
     #[test]
     fn all_tools_includes_browser_when_enabled() {
         let tmp = TempDir::new().unwrap();
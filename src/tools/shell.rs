@@ -1,11 +1,15 @@
 use super::traits::{Tool, ToolResult};
-use crate::runtime::RuntimeAdapter;
-use crate::security::SecurityPolicy;
+use crate::config::SeccompConfig;
+use crate::runtime::{LimitBreach, RuntimeAdapter};
+use crate::security::audit::CommandExecutionLog;
+use crate::security::policy::CommandRiskLevel;
+use crate::security::seccomp::select_profile;
+use crate::security::{AuditLogger, ResourceLimitLog, Sandbox, SecurityPolicy};
 use async_trait::async_trait;
 use serde_json::json;
 use std::collections::HashSet;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// Maximum shell command execution time before kill.
 const SHELL_TIMEOUT_SECS: u64 = 60;
@@ -21,6 +25,9 @@ const SAFE_ENV_VARS: &[&str] = &[
 pub struct ShellTool {
     security: Arc<SecurityPolicy>,
     runtime: Arc<dyn RuntimeAdapter>,
+    audit: Option<Arc<AuditLogger>>,
+    sandbox: Option<Arc<dyn Sandbox>>,
+    seccomp: SeccompConfig,
 }
 
 fn is_env_assignment(word: &str) -> bool {
@@ -83,76 +90,88 @@ fn forbidden_path_argument(security: &SecurityPolicy, command: &str) -> Option<S
 
 impl ShellTool {
     pub fn new(security: Arc<SecurityPolicy>, runtime: Arc<dyn RuntimeAdapter>) -> Self {
-        Self { security, runtime }
-    }
-}
-
-fn is_valid_env_var_name(name: &str) -> bool {
-    let mut chars = name.chars();
-    match chars.next() {
-        Some(first) if first.is_ascii_alphabetic() || first == '_' => {}
-        _ => return false,
-    }
-    chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
-}
-
-fn collect_allowed_shell_env_vars(security: &SecurityPolicy) -> Vec<String> {
-    let mut out = Vec::new();
-    let mut seen = HashSet::new();
-    for key in SAFE_ENV_VARS
-        .iter()
-        .copied()
-        .chain(security.shell_env_passthrough.iter().map(|s| s.as_str()))
-    {
-        let candidate = key.trim();
-        if candidate.is_empty() || !is_valid_env_var_name(candidate) {
-            continue;
-        }
-        if seen.insert(candidate.to_string()) {
-            out.push(candidate.to_string());
+        Self {
+            security,
+            runtime,
+            audit: None,
+            sandbox: None,
+            seccomp: SeccompConfig::default(),
         }
     }
-    out
-}
 
-#[async_trait]
-impl Tool for ShellTool {
-    fn name(&self) -> &str {
-        "shell"
+    /// Record resource limit breaches in the audit log.
+    pub fn with_audit(mut self, audit: Arc<AuditLogger>) -> Self {
+        self.audit = Some(audit);
+        self
     }
 
-    fn description(&self) -> &str {
-        "Execute a shell command in the workspace directory"
+    /// Apply `sandbox` to every spawned command, choosing the seccomp profile
+    /// per command from `seccomp`.
+    pub fn with_sandbox(mut self, sandbox: Arc<dyn Sandbox>, seccomp: SeccompConfig) -> Self {
+        self.sandbox = Some(sandbox);
+        self.seccomp = seccomp;
+        self
     }
 
-    fn parameters_schema(&self) -> serde_json::Value {
-        json!({
-            "type": "object",
-            "properties": {
-                "command": {
-                    "type": "string",
-                    "description": "The shell command to execute"
-                },
-                "approved": {
-                    "type": "boolean",
-                    "description": "Set true to explicitly approve medium/high-risk commands in supervised mode",
-                    "default": false
-                }
-            },
-            "required": ["command"]
-        })
+    fn audit_execution(&self, command: &str, approved: bool, success: bool, elapsed: Duration) {
+        let Some(audit) = &self.audit else {
+            return;
+        };
+        let risk_level = match self.security.command_risk_level(command) {
+            CommandRiskLevel::Low => "low",
+            CommandRiskLevel::Medium => "medium",
+            CommandRiskLevel::High => "high",
+        };
+        if let Err(e) = audit.log_command_event(CommandExecutionLog {
+            channel: "shell",
+            command,
+            risk_level,
+            approved,
+            allowed: true,
+            success,
+            duration_ms: u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+        }) {
+            tracing::warn!("Failed to write command audit event: {e}");
+        }
     }
 
-    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
-        let command = args
-            .get("command")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| anyhow::anyhow!("Missing 'command' parameter"))?;
-        let approved = args
-            .get("approved")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false);
+    fn audit_limit_breach(
+        &self,
+        command: &str,
+        breach: &LimitBreach,
+        enforcement: &str,
+        exit_code: Option<i32>,
+        elapsed: Duration,
+    ) {
+        tracing::warn!(
+            limit = breach.kind(),
+            enforcement,
+            "Shell command stopped by resource limit: {breach}"
+        );
+        let Some(audit) = &self.audit else {
+            return;
+        };
+        let detail = breach.to_string();
+        if let Err(e) = audit.log_resource_limit(ResourceLimitLog {
+            channel: "shell",
+            command,
+            detail: &detail,
+            enforcement,
+            exit_code,
+            duration_ms: u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+        }) {
+            tracing::warn!("Failed to write resource limit audit event: {e}");
+        }
+    }
 
+    /// Run `command` through the policy gates, runtime adapter and sandbox,
+    /// checking executables against `allowed_commands`.
+    pub(crate) async fn run_command(
+        &self,
+        command: &str,
+        approved: bool,
+        allowed_commands: &[String],
+    ) -> anyhow::Result<ToolResult> {
         if self.security.is_rate_limited() {
             return Ok(ToolResult {
                 success: false,
@@ -161,7 +180,10 @@ impl Tool for ShellTool {
             });
         }
 
-        match self.security.validate_command_execution(command, approved) {
+        match self
+            .security
+            .validate_command_execution_with(command, approved, allowed_commands)
+        {
             Ok(_) => {}
             Err(reason) => {
                 return Ok(ToolResult {
@@ -204,19 +226,36 @@ impl Tool for ShellTool {
                 });
             }
         };
-        cmd.env_clear();
+        cmd.command_mut().env_clear();
 
         for var in collect_allowed_shell_env_vars(&self.security) {
             if let Ok(val) = std::env::var(&var) {
-                cmd.env(&var, val);
+                cmd.command_mut().env(&var, val);
             }
         }
 
+        if let Some(sandbox) = &self.sandbox {
+            let risk = self.security.command_risk_level(command);
+            let profile = select_profile(&self.seccomp, "shell", risk);
+            if let Err(e) =
+                sandbox.wrap_command_with_profile(cmd.command_mut().as_std_mut(), profile)
+            {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Failed to apply {} sandbox: {e}", sandbox.name())),
+                });
+            }
+        }
+
+        let enforcement = cmd.enforcement();
+        let started = Instant::now();
         let result =
             tokio::time::timeout(Duration::from_secs(SHELL_TIMEOUT_SECS), cmd.output()).await;
 
-        match result {
-            Ok(Ok(output)) => {
+        let tool_result = match result {
+            Ok(Ok(limited)) => {
+                let output = limited.output;
                 let mut stdout = String::from_utf8_lossy(&output.stdout).to_string();
                 let mut stderr = String::from_utf8_lossy(&output.stderr).to_string();
 
@@ -230,30 +269,124 @@ impl Tool for ShellTool {
                     stderr.push_str("\n... [stderr truncated at 1MB]");
                 }
 
-                Ok(ToolResult {
-                    success: output.status.success(),
-                    output: stdout,
-                    error: if stderr.is_empty() {
-                        None
-                    } else {
-                        Some(stderr)
-                    },
-                })
+                if let Some(breach) = limited.breach {
+                    self.audit_limit_breach(
+                        command,
+                        &breach,
+                        enforcement,
+                        output.status.code(),
+                        started.elapsed(),
+                    );
+                    let mut error = format!("Resource limit exceeded: {breach}");
+                    if !stderr.is_empty() {
+                        error.push('\n');
+                        error.push_str(&stderr);
+                    }
+                    ToolResult {
+                        success: false,
+                        output: stdout,
+                        error: Some(error),
+                    }
+                } else {
+                    ToolResult {
+                        success: output.status.success(),
+                        output: stdout,
+                        error: if stderr.is_empty() {
+                            None
+                        } else {
+                            Some(stderr)
+                        },
+                    }
+                }
             }
-            Ok(Err(e)) => Ok(ToolResult {
+            Ok(Err(e)) => ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Failed to execute command: {e}")),
-            }),
-            Err(_) => Ok(ToolResult {
+            },
+            Err(_) => ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!(
                     "Command timed out after {SHELL_TIMEOUT_SECS}s and was killed"
                 )),
-            }),
+            },
+        };
+
+        self.audit_execution(command, approved, tool_result.success, started.elapsed());
+        Ok(tool_result)
+    }
+}
+
+fn is_valid_env_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() || first == '_' => {}
+        _ => return false,
+    }
+    chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+}
+
+fn collect_allowed_shell_env_vars(security: &SecurityPolicy) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut seen = HashSet::new();
+    for key in SAFE_ENV_VARS
+        .iter()
+        .copied()
+        .chain(security.shell_env_passthrough.iter().map(|s| s.as_str()))
+    {
+        let candidate = key.trim();
+        if candidate.is_empty() || !is_valid_env_var_name(candidate) {
+            continue;
+        }
+        if seen.insert(candidate.to_string()) {
+            out.push(candidate.to_string());
         }
     }
+    out
+}
+
+#[async_trait]
+impl Tool for ShellTool {
+    fn name(&self) -> &str {
+        "shell"
+    }
+
+    fn description(&self) -> &str {
+        "Execute a shell command in the workspace directory"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "command": {
+                    "type": "string",
+                    "description": "The shell command to execute"
+                },
+                "approved": {
+                    "type": "boolean",
+                    "description": "Set true to explicitly approve medium/high-risk commands in supervised mode",
+                    "default": false
+                }
+            },
+            "required": ["command"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let command = args
+            .get("command")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'command' parameter"))?;
+        let approved = args
+            .get("approved")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        self.run_command(command, approved, &self.security.allowed_commands)
+            .await
+    }
 }
 
 #[cfg(test)]
@@ -657,4 +790,77 @@ mod tests {
                 || r2.error.as_deref().unwrap_or("").contains("budget")
         );
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn shell_reports_resource_limit_breach_and_audits_it() {
+        use crate::config::AuditConfig;
+        use crate::runtime::ResourceLimits;
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("burn.sh"), "while :; do :; done\n").unwrap();
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Full,
+            allowed_commands: vec!["sh".into()],
+            workspace_dir: tmp.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+        let runtime = Arc::new(NativeRuntime::new().with_limits(ResourceLimits {
+            max_cpu_time_secs: 1,
+            ..ResourceLimits::unlimited()
+        }));
+        let audit =
+            Arc::new(AuditLogger::new(AuditConfig::default(), tmp.path().to_path_buf()).unwrap());
+
+        let tool = ShellTool::new(security, runtime).with_audit(audit);
+        let result = tool
+            .execute(json!({"command": "sh burn.sh"}))
+            .await
+            .expect("limited command should return a result");
+
+        assert!(!result.success);
+        assert!(result
+            .error
+            .as_deref()
+            .unwrap_or("")
+            .contains("Resource limit exceeded: CPU time limit of 1s exceeded"));
+        let log = std::fs::read_to_string(tmp.path().join("audit.log")).unwrap();
+        assert!(log.contains("resource_limit_exceeded"));
+        assert!(log.contains("sh burn.sh"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn shell_applies_seccomp_profile_by_risk_level() {
+        use crate::config::SeccompProfile;
+        use crate::security::SeccompSandbox;
+
+        let Ok(sandbox) = SeccompSandbox::probe() else {
+            return;
+        };
+        let tmp = tempfile::TempDir::new().unwrap();
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Full,
+            allowed_commands: vec!["touch".into(), "ls".into()],
+            workspace_dir: tmp.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+        let seccomp = SeccompConfig {
+            enabled: true,
+            medium_risk_profile: SeccompProfile::ReadOnly,
+            ..SeccompConfig::default()
+        };
+        let tool =
+            ShellTool::new(security, test_runtime()).with_sandbox(Arc::new(sandbox), seccomp);
+
+        let blocked = tool
+            .execute(json!({"command": "touch created.txt"}))
+            .await
+            .unwrap();
+        assert!(!blocked.success);
+        assert!(!tmp.path().join("created.txt").exists());
+
+        let listed = tool.execute(json!({"command": "ls"})).await.unwrap();
+        assert!(listed.success);
+    }
 }
@@ -0,0 +1,865 @@
+use super::http_request::{extract_host, host_matches_allowlist, normalize_allowed_domains};
+use super::shell::ShellTool;
+use super::traits::{Tool, ToolResult};
+use super::HttpRequestTool;
+use crate::config::HttpRequestConfig;
+use crate::security::SecurityPolicy;
+use crate::skills::{Skill, SkillTool, SkillToolParameter};
+use anyhow::Context;
+use async_trait::async_trait;
+use serde_json::json;
+use std::path::Path;
+use std::sync::Arc;
+
+const PARAMETER_TYPES: &[&str] = &["string", "integer", "number", "boolean"];
+
+/// How a skill tool runs once its arguments are rendered.
+enum Backend {
+    /// `shell` and `script` tools run through the shared shell tool, so the
+    /// security policy, runtime adapter, sandbox and audit log all apply.
+    Command {
+        shell: Arc<ShellTool>,
+        template: String,
+        allowed_commands: Vec<String>,
+    },
+    Http {
+        http: HttpRequestTool,
+        template: String,
+        method: String,
+    },
+}
+
+/// A `[[tools]]` entry from a skill's SKILL.toml, exposed as a callable tool.
+///
+/// `{param}` placeholders in `command` are filled from the typed arguments:
+/// shell-quoted for `shell` / `script` tools, percent-encoded for `http`
+/// tools. `{{` and `}}` produce literal braces.
+pub struct SkillToolAdapter {
+    name: String,
+    description: String,
+    parameters: Vec<SkillToolParameter>,
+    backend: Backend,
+}
+
+impl SkillToolAdapter {
+    pub fn new(
+        skill: &Skill,
+        tool: &SkillTool,
+        shell: &Arc<ShellTool>,
+        security: &Arc<SecurityPolicy>,
+        http_config: &HttpRequestConfig,
+    ) -> anyhow::Result<Self> {
+        if tool.name.is_empty()
+            || tool.name.len() > 64
+            || !tool
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            anyhow::bail!("tool name must be 1-64 characters of [A-Za-z0-9_-]");
+        }
+        validate_parameters(&tool.parameters)?;
+        // Reject placeholders that name no parameter up front.
+        render_template(&tool.command, |name| {
+            if tool.parameters.iter().any(|p| p.name == name) {
+                Ok(String::new())
+            } else {
+                anyhow::bail!("command references unknown parameter '{name}'")
+            }
+        })?;
+
+        let backend = match tool.kind.as_str() {
+            "shell" => {
+                let declared = if skill.permissions.allowed_commands.is_empty() {
+                    vec![command_executable(&tool.command)?]
+                } else {
+                    skill.permissions.allowed_commands.clone()
+                };
+                Backend::Command {
+                    shell: Arc::clone(shell),
+                    template: tool.command.clone(),
+                    allowed_commands: narrow_commands(&declared, security),
+                }
+            }
+            "script" => {
+                let (template, script_name) = resolve_script(skill, &tool.command)?;
+                let mut allowed_commands =
+                    narrow_commands(&skill.permissions.allowed_commands, security);
+                allowed_commands.push(script_name);
+                Backend::Command {
+                    shell: Arc::clone(shell),
+                    template,
+                    allowed_commands,
+                }
+            }
+            "http" => {
+                if !http_config.enabled {
+                    anyhow::bail!("http tools need [http_request].enabled = true");
+                }
+                let declared = if skill.permissions.allowed_domains.is_empty() {
+                    let host = extract_host(&tool.command)?;
+                    if host.contains('{') {
+                        anyhow::bail!(
+                            "URL host is a parameter; declare permissions.allowed_domains"
+                        );
+                    }
+                    vec![host]
+                } else {
+                    skill.permissions.allowed_domains.clone()
+                };
+                let domains = narrow_domains(&declared, &http_config.allowed_domains);
+                if domains.is_empty() {
+                    anyhow::bail!(
+                        "none of the skill's domains is in [http_request].allowed_domains"
+                    );
+                }
+                Backend::Http {
+                    http: HttpRequestTool::new(
+                        security.clone(),
+                        domains,
+                        http_config.max_response_size,
+                        http_config.timeout_secs,
+                    ),
+                    template: tool.command.clone(),
+                    method: tool.method.as_deref().unwrap_or("GET").to_ascii_uppercase(),
+                }
+            }
+            other => anyhow::bail!("unsupported tool kind '{other}'"),
+        };
+
+        Ok(Self {
+            name: tool.name.clone(),
+            description: format!("{} (skill: {})", tool.description, skill.name),
+            parameters: tool.parameters.clone(),
+            backend,
+        })
+    }
+
+    /// Argument for `parameter`, or its default, as template text.
+    fn argument(
+        parameter: &SkillToolParameter,
+        args: &serde_json::Value,
+    ) -> anyhow::Result<Option<String>> {
+        let value = match args.get(&parameter.name).filter(|v| !v.is_null()) {
+            Some(value) => value,
+            None => match &parameter.default {
+                Some(default) => default,
+                None if parameter.is_required() => {
+                    anyhow::bail!("Missing '{}' parameter", parameter.name)
+                }
+                None => return Ok(None),
+            },
+        };
+
+        let text = match parameter.kind.as_str() {
+            "integer" => value
+                .as_i64()
+                .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+                .map(|v| v.to_string()),
+            "number" => value
+                .as_f64()
+                .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+                .map(|v| v.to_string()),
+            "boolean" => value
+                .as_bool()
+                .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+                .map(|v| v.to_string()),
+            _ => value
+                .as_str()
+                .map(str::to_string)
+                .or_else(|| (value.is_number() || value.is_boolean()).then(|| value.to_string())),
+        }
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Parameter '{}' must be a {}",
+                parameter.name,
+                parameter.kind
+            )
+        })?;
+
+        if !parameter.choices.is_empty() && !parameter.choices.contains(&text) {
+            anyhow::bail!(
+                "Parameter '{}' must be one of: {}",
+                parameter.name,
+                parameter.choices.join(", ")
+            );
+        }
+        Ok(Some(text))
+    }
+
+    fn render(
+        &self,
+        template: &str,
+        args: &serde_json::Value,
+        escape: fn(&str) -> String,
+    ) -> anyhow::Result<String> {
+        render_template(template, |name| {
+            let parameter = self
+                .parameters
+                .iter()
+                .find(|p| p.name == name)
+                .ok_or_else(|| anyhow::anyhow!("Unknown parameter '{name}'"))?;
+            Ok(Self::argument(parameter, args)?
+                .map(|value| escape(&value))
+                .unwrap_or_default())
+        })
+    }
+}
+
+#[async_trait]
+impl Tool for SkillToolAdapter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        let mut properties = serde_json::Map::new();
+        for parameter in &self.parameters {
+            let mut property = json!({
+                "type": parameter.kind,
+                "description": parameter.description,
+            });
+            if !parameter.choices.is_empty() {
+                property["enum"] = json!(parameter.choices);
+            }
+            if let Some(default) = &parameter.default {
+                property["default"] = default.clone();
+            }
+            properties.insert(parameter.name.clone(), property);
+        }
+        if matches!(self.backend, Backend::Command { .. }) {
+            properties.insert(
+                "approved".into(),
+                json!({
+                    "type": "boolean",
+                    "description": "Set true to explicitly approve medium/high-risk commands in supervised mode",
+                    "default": false
+                }),
+            );
+        }
+        let required: Vec<&str> = self
+            .parameters
+            .iter()
+            .filter(|p| p.is_required())
+            .map(|p| p.name.as_str())
+            .collect();
+
+        json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        match &self.backend {
+            Backend::Command {
+                shell,
+                template,
+                allowed_commands,
+            } => {
+                let command = self.render(template, &args, shell_quote)?;
+                let approved = args
+                    .get("approved")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                shell
+                    .run_command(&command, approved, allowed_commands)
+                    .await
+            }
+            Backend::Http {
+                http,
+                template,
+                method,
+            } => {
+                let url = self.render(template, &args, |value| {
+                    urlencoding::encode(value).into_owned()
+                })?;
+                http.execute(json!({ "url": url, "method": method })).await
+            }
+        }
+    }
+}
+
+/// Build the tools declared by `skills`. Invalid declarations are skipped
+/// with a warning so one broken skill cannot take the others down.
+pub fn skill_tools(
+    skills: &[Skill],
+    shell: &Arc<ShellTool>,
+    security: &Arc<SecurityPolicy>,
+    http_config: &HttpRequestConfig,
+) -> Vec<Arc<dyn Tool>> {
+    let mut tools: Vec<Arc<dyn Tool>> = Vec::new();
+    for skill in skills {
+        for tool in &skill.tools {
+            match SkillToolAdapter::new(skill, tool, shell, security, http_config) {
+                Ok(adapter) => tools.push(Arc::new(adapter)),
+                Err(e) => tracing::warn!(
+                    "Skipping tool '{}' of skill '{}': {e:#}",
+                    tool.name,
+                    skill.name
+                ),
+            }
+        }
+    }
+    tools
+}
+
+fn validate_parameters(parameters: &[SkillToolParameter]) -> anyhow::Result<()> {
+    for (index, parameter) in parameters.iter().enumerate() {
+        let valid_name = parameter
+            .name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && parameter
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_name || parameter.name == "approved" {
+            anyhow::bail!("invalid parameter name '{}'", parameter.name);
+        }
+        if !PARAMETER_TYPES.contains(&parameter.kind.as_str()) {
+            anyhow::bail!(
+                "parameter '{}' has unsupported type '{}' (expected one of: {})",
+                parameter.name,
+                parameter.kind,
+                PARAMETER_TYPES.join(", ")
+            );
+        }
+        if parameters[..index].iter().any(|p| p.name == parameter.name) {
+            anyhow::bail!("duplicate parameter '{}'", parameter.name);
+        }
+    }
+    Ok(())
+}
+
+/// Fill `{name}` placeholders; `{{` / `}}` are literal braces.
+fn render_template(
+    template: &str,
+    mut value_for: impl FnMut(&str) -> anyhow::Result<String>,
+) -> anyhow::Result<String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(pos) = rest.find(['{', '}']) {
+        out.push_str(&rest[..pos]);
+        let brace = &rest[pos..=pos];
+        rest = &rest[pos + 1..];
+        if let Some(after) = rest.strip_prefix(brace) {
+            out.push_str(brace);
+            rest = after;
+            continue;
+        }
+        if brace == "}" {
+            anyhow::bail!("unmatched '}}' in template");
+        }
+        let end = rest.find('}').context("unclosed '{' in template")?;
+        out.push_str(&value_for(rest[..end].trim())?);
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Executable named by a shell command template (first word after any
+/// `VAR=value` assignments).
+fn command_executable(template: &str) -> anyhow::Result<String> {
+    let word = template
+        .split_whitespace()
+        .find(|word| !word.contains('=') || word.starts_with(['=', '{']))
+        .context("command is empty")?;
+    if word.contains('{') {
+        anyhow::bail!("the executable cannot be a parameter");
+    }
+    Ok(word.rsplit('/').next().unwrap_or(word).to_string())
+}
+
+/// Skill-declared commands that the global allowlist also permits.
+fn narrow_commands(declared: &[String], security: &SecurityPolicy) -> Vec<String> {
+    declared
+        .iter()
+        .filter(|command| security.allowed_commands.contains(command))
+        .cloned()
+        .collect()
+}
+
+/// Skill-declared domains that the global allowlist also permits. A `*` on
+/// either side defers to the other.
+fn narrow_domains(declared: &[String], global: &[String]) -> Vec<String> {
+    let global = normalize_allowed_domains(global.to_vec());
+    let mut narrowed: Vec<String> = normalize_allowed_domains(declared.to_vec())
+        .into_iter()
+        .flat_map(|domain| {
+            global
+                .iter()
+                .filter_map(|allowed| {
+                    if allowed == "*"
+                        || host_matches_allowlist(&domain, std::slice::from_ref(allowed))
+                    {
+                        Some(domain.clone())
+                    } else if domain == "*"
+                        || host_matches_allowlist(allowed, std::slice::from_ref(&domain))
+                    {
+                        Some(allowed.clone())
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    narrowed.sort_unstable();
+    narrowed.dedup();
+    narrowed
+}
+
+/// Resolve a `script` tool's first word to a script inside the skill
+/// directory. Returns the template with the absolute script path and the
+/// script's file name.
+fn resolve_script(skill: &Skill, template: &str) -> anyhow::Result<(String, String)> {
+    let skill_dir = skill
+        .location
+        .as_deref()
+        .and_then(Path::parent)
+        .context("skill has no directory")?
+        .canonicalize()?;
+    let template = template.trim_start();
+    let (script, rest) = template
+        .split_once(char::is_whitespace)
+        .map_or((template, ""), |(script, rest)| (script, rest));
+    if script.is_empty() || script.contains('{') {
+        anyhow::bail!("the script path cannot be empty or a parameter");
+    }
+
+    let path = skill_dir
+        .join(script)
+        .canonicalize()
+        .with_context(|| format!("script '{script}' not found"))?;
+    if !path.starts_with(&skill_dir) || !path.is_file() {
+        anyhow::bail!("script '{script}' must be a file inside the skill directory");
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if std::fs::metadata(&path)?.permissions().mode() & 0o111 == 0 {
+            anyhow::bail!("script '{script}' is not executable");
+        }
+    }
+
+    let path_text = path.to_string_lossy().into_owned();
+    if !path_text
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '\\' | '.' | '_' | '-' | '+' | ':'))
+    {
+        anyhow::bail!("script path must not contain spaces or shell metacharacters");
+    }
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .context("script has no file name")?;
+    let rendered = if rest.is_empty() {
+        path_text
+    } else {
+        format!("{path_text} {rest}")
+    };
+    Ok((rendered, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::NativeRuntime;
+    use crate::security::AutonomyLevel;
+    use crate::skills::SkillPermissions;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn security(workspace: &Path) -> Arc<SecurityPolicy> {
+        Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Full,
+            workspace_dir: workspace.to_path_buf(),
+            ..SecurityPolicy::default()
+        })
+    }
+
+    fn shell(security: &Arc<SecurityPolicy>) -> Arc<ShellTool> {
+        Arc::new(ShellTool::new(
+            security.clone(),
+            Arc::new(NativeRuntime::new()),
+        ))
+    }
+
+    fn parameter(name: &str, kind: &str) -> SkillToolParameter {
+        SkillToolParameter {
+            name: name.into(),
+            kind: kind.into(),
+            description: format!("The {name}"),
+            required: None,
+            default: None,
+            choices: Vec::new(),
+        }
+    }
+
+    fn skill(dir: &Path, tool: SkillTool, permissions: SkillPermissions) -> Skill {
+        Skill {
+            name: "demo".into(),
+            description: "Demo skill".into(),
+            version: "0.1.0".into(),
+            author: None,
+            tags: Vec::new(),
+            tools: vec![tool],
+            prompts: Vec::new(),
+            permissions,
+            location: Some(dir.join("SKILL.toml")),
+        }
+    }
+
+    fn tool(kind: &str, command: &str, parameters: Vec<SkillToolParameter>) -> SkillTool {
+        SkillTool {
+            name: "demo_tool".into(),
+            description: "Demo tool".into(),
+            kind: kind.into(),
+            command: command.into(),
+            args: HashMap::new(),
+            parameters,
+            method: None,
+        }
+    }
+
+    fn http_config(allowed_domains: &[&str]) -> HttpRequestConfig {
+        HttpRequestConfig {
+            enabled: true,
+            allowed_domains: allowed_domains.iter().map(ToString::to_string).collect(),
+            ..HttpRequestConfig::default()
+        }
+    }
+
+    fn adapter(skill: &Skill, workspace: &Path) -> anyhow::Result<SkillToolAdapter> {
+        adapter_with_http(skill, workspace, &http_config(&["example.com"]))
+    }
+
+    fn adapter_with_http(
+        skill: &Skill,
+        workspace: &Path,
+        http_config: &HttpRequestConfig,
+    ) -> anyhow::Result<SkillToolAdapter> {
+        let security = security(workspace);
+        SkillToolAdapter::new(
+            skill,
+            &skill.tools[0],
+            &shell(&security),
+            &security,
+            http_config,
+        )
+    }
+
+    #[test]
+    fn schema_lists_typed_parameters() {
+        let tmp = TempDir::new().unwrap();
+        let mut count = parameter("count", "integer");
+        count.default = Some(json!(3));
+        let mut unit = parameter("unit", "string");
+        unit.choices = vec!["c".into(), "f".into()];
+        let skill = skill(
+            tmp.path(),
+            tool(
+                "shell",
+                "echo {city} {count} {unit}",
+                vec![parameter("city", "string"), count, unit],
+            ),
+            SkillPermissions::default(),
+        );
+
+        let schema = adapter(&skill, tmp.path()).unwrap().parameters_schema();
+        assert_eq!(schema["properties"]["count"]["type"], "integer");
+        assert_eq!(schema["properties"]["count"]["default"], 3);
+        assert_eq!(schema["properties"]["unit"]["enum"], json!(["c", "f"]));
+        assert!(schema["properties"]["approved"].is_object());
+        assert_eq!(schema["required"], json!(["city", "unit"]));
+    }
+
+    #[test]
+    fn template_placeholders_and_escapes() {
+        let rendered = render_template("a {x} {{literal}} b", |name| {
+            assert_eq!(name, "x");
+            Ok("1".into())
+        })
+        .unwrap();
+        assert_eq!(rendered, "a 1 {literal} b");
+        assert!(render_template("a {x", |_| Ok(String::new())).is_err());
+        assert!(render_template("a }", |_| Ok(String::new())).is_err());
+    }
+
+    #[test]
+    fn invalid_declarations_are_skipped() {
+        let tmp = TempDir::new().unwrap();
+        let security = security(tmp.path());
+        let skills = vec![
+            skill(
+                tmp.path(),
+                tool("shell", "echo {missing}", Vec::new()),
+                SkillPermissions::default(),
+            ),
+            skill(
+                tmp.path(),
+                tool("shell", "{cmd} now", vec![parameter("cmd", "string")]),
+                SkillPermissions::default(),
+            ),
+            skill(
+                tmp.path(),
+                tool("shell", "echo {x}", vec![parameter("x", "list")]),
+                SkillPermissions::default(),
+            ),
+            skill(
+                tmp.path(),
+                tool("ftp", "get file", Vec::new()),
+                SkillPermissions::default(),
+            ),
+            skill(
+                tmp.path(),
+                tool("shell", "echo ok", Vec::new()),
+                SkillPermissions::default(),
+            ),
+        ];
+        let tools = skill_tools(
+            &skills,
+            &shell(&security),
+            &security,
+            &HttpRequestConfig::default(),
+        );
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name(), "demo_tool");
+    }
+
+    #[tokio::test]
+    async fn shell_arguments_are_quoted() {
+        let tmp = TempDir::new().unwrap();
+        let skill = skill(
+            tmp.path(),
+            tool(
+                "shell",
+                "echo {text} {n}",
+                vec![parameter("text", "string"), parameter("n", "integer")],
+            ),
+            SkillPermissions::default(),
+        );
+        let adapter = adapter(&skill, tmp.path()).unwrap();
+
+        let result = adapter
+            .execute(json!({"text": "it's; ls -la && $HOME", "n": "7"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output.trim(), "it's; ls -la && $HOME 7");
+
+        let err = adapter
+            .execute(json!({"text": "x", "n": "seven"}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("must be a integer"));
+        let err = adapter.execute(json!({"n": 1})).await.unwrap_err();
+        assert!(err.to_string().contains("Missing 'text'"));
+    }
+
+    #[tokio::test]
+    async fn skill_allowlist_narrows_the_global_one() {
+        let tmp = TempDir::new().unwrap();
+        // `ls` is globally allowed, but the skill only declares `echo`.
+        let skill = skill(
+            tmp.path(),
+            tool("shell", "echo hi && ls", Vec::new()),
+            SkillPermissions {
+                allowed_commands: vec!["echo".into()],
+                ..SkillPermissions::default()
+            },
+        );
+        let result = adapter(&skill, tmp.path())
+            .unwrap()
+            .execute(json!({}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("not allowed"));
+
+        // The skill cannot grant itself commands the global policy forbids.
+        let skill = super::tests::skill(
+            tmp.path(),
+            tool("shell", "python3 -c 'print(1)'", Vec::new()),
+            SkillPermissions {
+                allowed_commands: vec!["python3".into()],
+                ..SkillPermissions::default()
+            },
+        );
+        let result = adapter(&skill, tmp.path())
+            .unwrap()
+            .execute(json!({}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("not allowed"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn script_tools_run_scripts_from_the_skill_directory() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = TempDir::new().unwrap();
+        let skill_dir = tmp.path().join("skills").join("demo");
+        std::fs::create_dir_all(skill_dir.join("bin")).unwrap();
+        let script = skill_dir.join("bin").join("greet.sh");
+        std::fs::write(&script, "#!/bin/sh\necho \"hello $1\"\n").unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let skill = skill(
+            &skill_dir,
+            tool(
+                "script",
+                "bin/greet.sh {name}",
+                vec![parameter("name", "string")],
+            ),
+            SkillPermissions::default(),
+        );
+        let result = adapter(&skill, tmp.path())
+            .unwrap()
+            .execute(json!({"name": "world"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output.trim(), "hello world");
+
+        std::fs::write(tmp.path().join("outside.sh"), "#!/bin/sh\n").unwrap();
+        let escaping = super::tests::skill(
+            &skill_dir,
+            tool("script", "../../outside.sh", Vec::new()),
+            SkillPermissions::default(),
+        );
+        let err = adapter(&escaping, tmp.path()).err().unwrap();
+        assert!(err.to_string().contains("inside the skill directory"));
+    }
+
+    #[tokio::test]
+    async fn http_tools_encode_arguments_and_keep_to_declared_hosts() {
+        let tmp = TempDir::new().unwrap();
+        let skill = skill(
+            tmp.path(),
+            tool(
+                "http",
+                "https://api.example.com/search?q={query}",
+                vec![parameter("query", "string")],
+            ),
+            SkillPermissions::default(),
+        );
+        let adapter = adapter(&skill, tmp.path()).unwrap();
+        let Backend::Http { template, .. } = &adapter.backend else {
+            panic!("expected an HTTP backend");
+        };
+        let url = adapter
+            .render(template, &json!({"query": "a b&c"}), |value| {
+                urlencoding::encode(value).into_owned()
+            })
+            .unwrap();
+        assert_eq!(url, "https://api.example.com/search?q=a%20b%26c");
+        assert!(!adapter.parameters_schema()["properties"]
+            .as_object()
+            .unwrap()
+            .contains_key("approved"));
+
+        let skill = super::tests::skill(
+            tmp.path(),
+            tool(
+                "http",
+                "https://{host}/status",
+                vec![parameter("host", "string")],
+            ),
+            SkillPermissions {
+                allowed_domains: vec!["example.com".into()],
+                ..SkillPermissions::default()
+            },
+        );
+        let result = super::tests::adapter(&skill, tmp.path())
+            .unwrap()
+            .execute(json!({"host": "evil.test"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("not in"));
+
+        let unscoped = super::tests::skill(
+            tmp.path(),
+            tool("http", "https://{host}/", vec![parameter("host", "string")]),
+            SkillPermissions::default(),
+        );
+        assert!(super::tests::adapter(&unscoped, tmp.path()).is_err());
+    }
+
+    #[test]
+    fn http_tools_keep_to_the_global_http_request_policy() {
+        let tmp = TempDir::new().unwrap();
+        let skill_with = |domains: &[&str]| {
+            skill(
+                tmp.path(),
+                tool("http", "https://{host}/", vec![parameter("host", "string")]),
+                SkillPermissions {
+                    allowed_domains: domains.iter().map(ToString::to_string).collect(),
+                    ..SkillPermissions::default()
+                },
+            )
+        };
+
+        let disabled = HttpRequestConfig {
+            enabled: false,
+            ..http_config(&["*"])
+        };
+        let err = adapter_with_http(&skill_with(&["example.com"]), tmp.path(), &disabled)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("[http_request].enabled"));
+
+        let err = adapter_with_http(
+            &skill_with(&["evil.test"]),
+            tmp.path(),
+            &http_config(&["example.com"]),
+        )
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("allowed_domains"));
+        assert!(adapter_with_http(
+            &skill_with(&["*"]),
+            tmp.path(),
+            &http_config(&["example.com"])
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn skill_domains_narrow_to_the_global_allowlist() {
+        let domains = |list: &[&str]| list.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        // A skill-wide wildcard is narrowed to the global allowlist.
+        assert_eq!(
+            narrow_domains(&domains(&["*"]), &domains(&["example.com", "docs.rs"])),
+            ["docs.rs", "example.com"]
+        );
+        assert_eq!(
+            narrow_domains(
+                &domains(&["api.example.com", "evil.test", "rust-lang.org"]),
+                &domains(&["example.com", "blog.rust-lang.org"]),
+            ),
+            ["api.example.com", "blog.rust-lang.org"]
+        );
+        assert_eq!(
+            narrow_domains(&domains(&["https://Example.com/"]), &domains(&["*"])),
+            ["example.com"]
+        );
+        assert!(narrow_domains(&domains(&["*"]), &[]).is_empty());
+    }
+}
@@ -32,6 +32,12 @@ pub trait Tool: Send + Sync {
     /// Execute the tool with given arguments
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult>;
 
+    /// Every call must be confirmed through an approval prompt, whatever the
+    /// autonomy level. Calls are refused where no prompt can be shown.
+    fn requires_approval(&self) -> bool {
+        false
+    }
+
     /// Get the full spec for LLM registration
     fn spec(&self) -> ToolSpec {
         ToolSpec {
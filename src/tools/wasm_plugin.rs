@@ -0,0 +1,338 @@
+//! Tool adapter for installed WASM plugins (see [`crate::plugins`]).
+
+use super::traits::{Tool, ToolResult};
+use crate::config::WasmRuntimeConfig;
+use crate::plugins::{load_plugins, PluginManifest};
+use crate::runtime::{WasmCapabilities, WasmRuntime};
+use crate::security::policy::ToolOperation;
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A WASM plugin module exposed as a tool.
+pub struct WasmPluginTool {
+    manifest: PluginManifest,
+    caps: WasmCapabilities,
+    runtime: WasmRuntime,
+    workspace_dir: PathBuf,
+    security: Arc<SecurityPolicy>,
+}
+
+/// Structured result a plugin may return instead of plain text.
+#[derive(Deserialize)]
+struct PluginOutput {
+    success: bool,
+    #[serde(default)]
+    output: String,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+impl WasmPluginTool {
+    pub fn new(
+        manifest: PluginManifest,
+        runtime: WasmRuntime,
+        workspace_dir: PathBuf,
+        security: Arc<SecurityPolicy>,
+    ) -> Self {
+        let caps = manifest.capabilities.to_wasm();
+        Self {
+            manifest,
+            caps,
+            runtime,
+            workspace_dir,
+            security,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for WasmPluginTool {
+    fn name(&self) -> &str {
+        &self.manifest.name
+    }
+
+    fn description(&self) -> &str {
+        &self.manifest.description
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        self.manifest.parameters.clone()
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        // Pure-computation plugins have no side effects; writers count as actions.
+        let operation = if self.caps.write_workspace {
+            ToolOperation::Act
+        } else {
+            ToolOperation::Read
+        };
+        if let Err(error) = self
+            .security
+            .enforce_tool_operation(operation, &self.manifest.name)
+        {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(error),
+            });
+        }
+
+        let input = serde_json::to_vec(&args)?;
+        let runtime = self.runtime.clone();
+        let name = self.manifest.name.clone();
+        let workspace_dir = self.workspace_dir.clone();
+        let caps = self.caps.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            runtime.call_module(&name, &workspace_dir, &caps, &input)
+        })
+        .await?;
+
+        let result = match result {
+            Ok(result) => result,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("{e:#}")),
+                })
+            }
+        };
+
+        if !result.stderr.is_empty() {
+            tracing::debug!(plugin = %self.manifest.name, "WASM plugin log:\n{}", result.stderr);
+        }
+        if result.exit_code != 0 {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(result.stderr),
+            });
+        }
+
+        Ok(match serde_json::from_str::<PluginOutput>(&result.stdout) {
+            Ok(parsed) => ToolResult {
+                success: parsed.success,
+                output: parsed.output,
+                error: parsed.error,
+            },
+            Err(_) => ToolResult {
+                success: true,
+                output: result.stdout,
+                error: None,
+            },
+        })
+    }
+}
+
+/// Build tools for every installed plugin that matches its record in the
+/// trust store at `trust_path`.
+///
+/// Returns nothing (with a warning) when plugins are installed but this
+/// build lacks the `runtime-wasm` feature or `[runtime.wasm]` is invalid.
+pub fn plugin_tools(
+    config: &WasmRuntimeConfig,
+    workspace_dir: &Path,
+    trust_path: &Path,
+    security: &Arc<SecurityPolicy>,
+) -> Vec<Arc<dyn Tool>> {
+    let plugins = load_plugins(workspace_dir, config, trust_path);
+    if plugins.is_empty() {
+        return Vec::new();
+    }
+    if !WasmRuntime::is_available() {
+        tracing::warn!(
+            "{} WASM plugin(s) installed but this build lacks the `runtime-wasm` feature; skipping",
+            plugins.len()
+        );
+        return Vec::new();
+    }
+    let runtime = WasmRuntime::with_workspace(config.clone(), workspace_dir.to_path_buf());
+    if let Err(e) = runtime.validate_config() {
+        tracing::warn!("Skipping WASM plugins: {e}");
+        return Vec::new();
+    }
+
+    plugins
+        .into_iter()
+        .map(|plugin| {
+            Arc::new(WasmPluginTool::new(
+                plugin.manifest,
+                runtime.clone(),
+                workspace_dir.to_path_buf(),
+                security.clone(),
+            )) as Arc<dyn Tool>
+        })
+        .collect()
+}
+
+#[cfg(all(test, feature = "runtime-wasm"))]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    // Echoes its input back, logs a line, and reads `note.txt` when asked.
+    const ECHO: &str = r#"(module
+        (import "zeroclaw" "log" (func $log (param i32 i32)))
+        (import "zeroclaw" "read_file" (func $read (param i32 i32) (result i64)))
+        (import "zeroclaw" "write_file" (func $write (param i32 i32 i32 i32) (result i32)))
+        (memory (export "memory") 1)
+        (global $next (mut i32) (i32.const 1024))
+        (data (i32.const 0) "called")
+        (data (i32.const 16) "note.txt")
+        (data (i32.const 32) "{\"success\":false,\"error\":\"denied\"}")
+        (func $alloc (export "alloc") (param $len i32) (result i32)
+            (local $ptr i32)
+            (local.set $ptr (global.get $next))
+            (global.set $next (i32.add (global.get $next) (local.get $len)))
+            (local.get $ptr))
+        (func (export "call") (param $ptr i32) (param $len i32) (result i64)
+            (local $r i64)
+            (call $log (i32.const 0) (i32.const 6))
+            ;; input starting with `{"r` asks for note.txt
+            (if (i32.eq (i32.load8_u offset=2 (local.get $ptr)) (i32.const 114))
+                (then
+                    (local.set $r (call $read (i32.const 16) (i32.const 8)))
+                    (if (i64.eq (local.get $r) (i64.const -1))
+                        (then (return (i64.const 137438953506))))
+                    (return (local.get $r))))
+            ;; input starting with `{"w` writes the input to note.txt
+            (if (i32.eq (i32.load8_u offset=2 (local.get $ptr)) (i32.const 119))
+                (then
+                    (drop (call $write (i32.const 16) (i32.const 8) (local.get $ptr) (local.get $len)))))
+            (i64.or
+                (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+                (i64.extend_i32_u (local.get $len)))))"#;
+
+    // Spins forever.
+    const SPIN: &str = r#"(module
+        (memory (export "memory") 1)
+        (func (export "alloc") (param i32) (result i32) i32.const 0)
+        (func (export "call") (param i32 i32) (result i64) (loop $l (br $l)) i64.const 0))"#;
+
+    fn install(workspace: &Path, name: &str, wat_src: &str, capabilities: &str) {
+        let source = TempDir::new().unwrap();
+        std::fs::write(
+            source.path().join(format!("{name}.wasm")),
+            wat::parse_str(wat_src).unwrap(),
+        )
+        .unwrap();
+        std::fs::write(
+            source.path().join(crate::plugins::MANIFEST_FILE),
+            format!(
+                "name = \"{name}\"\ndescription = \"test plugin\"\n[capabilities]\n{capabilities}"
+            ),
+        )
+        .unwrap();
+        crate::plugins::install_plugin(
+            source.path(),
+            &workspace.join("tools/wasm"),
+            &trust_path(workspace),
+            |_| Ok(true),
+        )
+        .unwrap()
+        .unwrap();
+    }
+
+    fn trust_path(workspace: &Path) -> PathBuf {
+        workspace.join("trusted_plugins.json")
+    }
+
+    fn tool(workspace: &Path, autonomy: AutonomyLevel) -> Arc<dyn Tool> {
+        let security = Arc::new(SecurityPolicy {
+            autonomy,
+            workspace_dir: workspace.to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+        let mut tools = plugin_tools(
+            &WasmRuntimeConfig::default(),
+            workspace,
+            &trust_path(workspace),
+            &security,
+        );
+        assert_eq!(tools.len(), 1);
+        tools.remove(0)
+    }
+
+    #[tokio::test]
+    async fn plugin_receives_json_arguments_and_returns_output() {
+        let workspace = TempDir::new().unwrap();
+        install(workspace.path(), "echo", ECHO, "");
+        let tool = tool(workspace.path(), AutonomyLevel::Supervised);
+
+        assert_eq!(tool.name(), "echo");
+        assert_eq!(tool.parameters_schema()["type"], "object");
+        let result = tool.execute(json!({"msg": "hi"})).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output, r#"{"msg":"hi"}"#);
+    }
+
+    #[tokio::test]
+    async fn read_capability_is_enforced() {
+        let workspace = TempDir::new().unwrap();
+        std::fs::write(workspace.path().join("note.txt"), "from disk").unwrap();
+
+        install(workspace.path(), "echo", ECHO, "");
+        let denied = tool(workspace.path(), AutonomyLevel::Supervised)
+            .execute(json!({"read": true}))
+            .await
+            .unwrap();
+        assert!(!denied.success);
+        assert_eq!(denied.error.as_deref(), Some("denied"));
+
+        crate::plugins::remove_plugin(
+            "echo",
+            &workspace.path().join("tools/wasm"),
+            &trust_path(workspace.path()),
+        )
+        .unwrap();
+        install(workspace.path(), "echo", ECHO, "read_workspace = true");
+        let allowed = tool(workspace.path(), AutonomyLevel::Supervised)
+            .execute(json!({"read": true}))
+            .await
+            .unwrap();
+        assert!(allowed.success);
+        assert_eq!(allowed.output, "from disk");
+    }
+
+    #[tokio::test]
+    async fn write_capability_requires_act_permission() {
+        let workspace = TempDir::new().unwrap();
+        install(workspace.path(), "echo", ECHO, "write_workspace = true");
+
+        let blocked = tool(workspace.path(), AutonomyLevel::ReadOnly)
+            .execute(json!({"write": 1}))
+            .await
+            .unwrap();
+        assert!(!blocked.success);
+        assert!(blocked.error.unwrap().contains("read-only"));
+        assert!(!workspace.path().join("note.txt").exists());
+
+        let result = tool(workspace.path(), AutonomyLevel::Full)
+            .execute(json!({"write": 1}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(
+            std::fs::read_to_string(workspace.path().join("note.txt")).unwrap(),
+            r#"{"write":1}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn runaway_plugin_is_stopped_by_fuel_limit() {
+        let workspace = TempDir::new().unwrap();
+        install(workspace.path(), "spin", SPIN, "fuel = 10000");
+
+        let result = tool(workspace.path(), AutonomyLevel::Supervised)
+            .execute(json!({}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("fuel limit"));
+    }
+}
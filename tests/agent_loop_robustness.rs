@@ -60,6 +60,7 @@ impl Provider for MockProvider {
                 text: Some("done".into()),
                 tool_calls: vec![],
                 usage: None,
+                routed_to: None,
             });
         }
         Ok(guard.remove(0))
@@ -181,6 +182,7 @@ fn text_response(text: &str) -> ChatResponse {
         text: Some(text.into()),
         tool_calls: vec![],
         usage: None,
+        routed_to: None,
     }
 }
 
@@ -189,6 +191,7 @@ fn tool_response(calls: Vec<ToolCall>) -> ChatResponse {
         text: Some(String::new()),
         tool_calls: calls,
         usage: None,
+        routed_to: None,
     }
 }
 
@@ -357,6 +360,7 @@ async fn agent_handles_empty_provider_response() {
         text: Some(String::new()),
         tool_calls: vec![],
         usage: None,
+        routed_to: None,
     }]));
 
     let mut agent = build_agent(provider, vec![Box::new(EchoTool)]);
@@ -371,6 +375,7 @@ async fn agent_handles_none_text_response() {
         text: None,
         tool_calls: vec![],
         usage: None,
+        routed_to: None,
     }]));
 
     let mut agent = build_agent(provider, vec![Box::new(EchoTool)]);
@@ -64,30 +64,39 @@ export default function AgentChat() {
           break;
         }
 
-        case 'tool_call':
+        case 'clear':
+          pendingContentRef.current = '';
+          break;
+
+        case 'tool_start':
           setMessages((prev) => [
             ...prev,
             {
               id: crypto.randomUUID(),
               role: 'agent',
-              content: `[Tool Call] ${msg.name ?? 'unknown'}(${JSON.stringify(msg.args ?? {})})`,
+              content: `[Tool Call] ${msg.name ?? 'unknown'}`,
               timestamp: new Date(),
             },
           ]);
           break;
 
-        case 'tool_result':
+        case 'tool_end':
           setMessages((prev) => [
             ...prev,
             {
               id: crypto.randomUUID(),
               role: 'agent',
-              content: `[Tool Result] ${msg.output ?? ''}`,
+              content: `[Tool Result] ${msg.name ?? 'unknown'} ${msg.success ? 'ok' : 'failed'}`,
               timestamp: new Date(),
             },
           ]);
           break;
 
+        case 'cancelled':
+          pendingContentRef.current = '';
+          setTyping(false);
+          break;
+
         case 'error':
           setMessages((prev) => [
             ...prev,
@@ -95,11 +95,22 @@ export interface SSEEvent {
 }
 
 export interface WsMessage {
-  type: 'message' | 'chunk' | 'tool_call' | 'tool_result' | 'done' | 'error';
+  type:
+    | 'message'
+    | 'session'
+    | 'chunk'
+    | 'clear'
+    | 'progress'
+    | 'tool_start'
+    | 'tool_end'
+    | 'cost'
+    | 'done'
+    | 'cancelled'
+    | 'error';
   content?: string;
   full_response?: string;
   name?: string;
-  args?: any;
-  output?: string;
+  success?: boolean;
+  duration_ms?: number;
   message?: string;
 }